@@ -1,6 +1,7 @@
 use criterion::{black_box, criterion_group, criterion_main, Criterion, BenchmarkId};
 use rs_graphdb::{GraphDatabase};
 use rs_graphdb::storage::sled_store::SledStore;
+use rs_graphdb::storage::NodeId;
 use rs_graphdb::values::{Properties, Value};
 use std::path::PathBuf;
 
@@ -11,7 +12,7 @@ fn make_node_props(i: usize) -> (Vec<String>, Properties) {
     (vec!["User".to_string()], props)
 }
 
-fn make_rel_props(start: u64, end: u64) -> (u64, u64, String, Properties) {
+fn make_rel_props(start: NodeId, end: NodeId) -> (NodeId, NodeId, String, Properties) {
     (start, end, "FRIEND".to_string(), Properties::new())
 }
 
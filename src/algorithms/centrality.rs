@@ -1,24 +1,81 @@
+use crate::algorithms::deadline::{Deadline, Timeout};
 use crate::graph::db::GraphDatabase;
+use crate::graph::model::Relationship;
 use crate::storage::{NodeId, StorageEngine};
+use crate::values::Value;
+use std::collections::hash_map::Entry;
 use std::collections::{HashMap, HashSet, VecDeque};
+use std::time::Duration;
+
+/// 关系类型过滤：`None` 接受所有关系，否则只接受类型在列表中的关系
+pub(crate) fn accepts_rel_type(rel_types: Option<&[&str]>, rel: &Relationship) -> bool {
+    match rel_types {
+        Some(types) => types.contains(&rel.typ.as_str()),
+        None => true,
+    }
+}
 
 /// 度中心性（Degree Centrality）
 pub fn degree_centrality<E: StorageEngine>(
     db: &GraphDatabase<E>,
 ) -> HashMap<NodeId, f64> {
-    let mut centrality = HashMap::new();
-    let mut node_count = 0;
+    degree_centrality_filtered(db, None)
+}
 
-    for node in db.all_stored_nodes() {
-        node_count += 1;
-        let out_degree = db.neighbors_out(node.id).count();
-        let in_degree = db.neighbors_in(node.id).count();
-        let total_degree = (out_degree + in_degree) as f64;
+/// 只统计 `rel_types` 中类型关系的度中心性
+pub fn degree_centrality_on<E: StorageEngine>(
+    db: &GraphDatabase<E>,
+    rel_types: &[&str],
+) -> HashMap<NodeId, f64> {
+    degree_centrality_filtered(db, Some(rel_types))
+}
 
-        centrality.insert(node.id, total_degree);
-    }
+fn degree_centrality_filtered<E: StorageEngine>(
+    db: &GraphDatabase<E>,
+    rel_types: Option<&[&str]>,
+) -> HashMap<NodeId, f64> {
+    // 与 `GraphDatabase::degree` 一致：无向关系只计一次
+    normalized_degrees(db, |id| {
+        db.neighbors_out(id)
+            .filter(|r| accepts_rel_type(rel_types, r))
+            .count()
+            + db
+                .neighbors_in(id)
+                .filter(|r| r.directed && accepts_rel_type(rel_types, r))
+                .count()
+    })
+}
+
+/// 入度中心性：入边数 / (n - 1)
+///
+/// 无向关系可以从两端进入，同时计入两端的入度和出度。
+pub fn in_degree_centrality<E: StorageEngine>(
+    db: &GraphDatabase<E>,
+) -> HashMap<NodeId, f64> {
+    normalized_degrees(db, |id| db.neighbors_in(id).count())
+}
+
+/// 出度中心性：出边数 / (n - 1)
+///
+/// 无向关系同时计入两端的入度和出度。
+pub fn out_degree_centrality<E: StorageEngine>(
+    db: &GraphDatabase<E>,
+) -> HashMap<NodeId, f64> {
+    normalized_degrees(db, |id| db.neighbors_out(id).count())
+}
+
+/// 对每个节点计算 `degree`，并除以 (n - 1) 归一化（只有一个节点时不归一化）
+fn normalized_degrees<E: StorageEngine>(
+    db: &GraphDatabase<E>,
+    degree: impl Fn(NodeId) -> usize,
+) -> HashMap<NodeId, f64> {
+    let mut centrality: HashMap<NodeId, f64> = db
+        .all_stored_nodes()
+        .map(|node| (node.id, degree(node.id) as f64))
+        .collect();
 
     // 归一化
+    let node_count = centrality.len();
     if node_count > 1 {
         let max_possible = (node_count - 1) as f64;
         for val in centrality.values_mut() {
@@ -29,10 +86,110 @@ pub fn degree_centrality<E: StorageEngine>(
     centrality
 }
 
+/// 节点强度（加权度），区分出方向和入方向
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct NodeStrength {
+    /// 有向出边权重之和
+    pub out_strength: f64,
+    /// 有向入边权重之和
+    pub in_strength: f64,
+    /// 无向关系权重之和
+    pub undirected_strength: f64,
+}
+
+impl NodeStrength {
+    /// 全部关联边的权重总和
+    pub fn total(&self) -> f64 {
+        self.out_strength + self.in_strength + self.undirected_strength
+    }
+}
+
+/// 读取边权重：Int/Float 转为 f64，缺失或非数值时默认为 1.0
+fn edge_weight(rel: &Relationship, weight_prop: &str) -> f64 {
+    match rel.props.get(weight_prop) {
+        Some(Value::Int(i)) => *i as f64,
+        Some(Value::Float(f)) => *f,
+        _ => 1.0,
+    }
+}
+
+/// 加权度（节点强度，Strength Centrality），分别统计有向出边、有向入边和无向关系的权重
+pub fn weighted_degree_directed<E: StorageEngine>(
+    db: &GraphDatabase<E>,
+    weight_prop: &str,
+) -> HashMap<NodeId, NodeStrength> {
+    let mut strength = HashMap::new();
+
+    for node in db.all_stored_nodes() {
+        let mut s = NodeStrength::default();
+        for rel in db.neighbors_out(node.id) {
+            if rel.directed {
+                s.out_strength += edge_weight(&rel, weight_prop);
+            } else {
+                s.undirected_strength += edge_weight(&rel, weight_prop);
+            }
+        }
+        s.in_strength = db
+            .neighbors_in(node.id)
+            .filter(|rel| rel.directed)
+            .map(|rel| edge_weight(&rel, weight_prop))
+            .sum();
+
+        strength.insert(node.id, s);
+    }
+
+    strength
+}
+
+/// 加权度（节点强度）：每个节点所有关联边的权重之和（出边 + 入边），不做归一化
+pub fn weighted_degree<E: StorageEngine>(
+    db: &GraphDatabase<E>,
+    weight_prop: &str,
+) -> HashMap<NodeId, f64> {
+    weighted_degree_directed(db, weight_prop)
+        .into_iter()
+        .map(|(id, s)| (id, s.total()))
+        .collect()
+}
+
 /// 介数中心性（Betweenness Centrality）- 简化版
 pub fn betweenness_centrality<E: StorageEngine>(
     db: &GraphDatabase<E>,
 ) -> HashMap<NodeId, f64> {
+    betweenness_centrality_filtered(db, None)
+}
+
+/// 只沿 `rel_types` 中类型关系计算的介数中心性
+pub fn betweenness_centrality_on<E: StorageEngine>(
+    db: &GraphDatabase<E>,
+    rel_types: &[&str],
+) -> HashMap<NodeId, f64> {
+    betweenness_centrality_filtered(db, Some(rel_types))
+}
+
+/// 限时的介数中心性：每处理完一个源点检查一次时间
+///
+/// 超时时 `Timeout::partial` 为按已处理源点比例放大的估计值。
+pub fn betweenness_centrality_within<E: StorageEngine>(
+    db: &GraphDatabase<E>,
+    deadline: Duration,
+) -> Result<HashMap<NodeId, f64>, Timeout<HashMap<NodeId, f64>>> {
+    betweenness_centrality_filtered_until(db, None, Deadline::new(Some(deadline)))
+}
+
+fn betweenness_centrality_filtered<E: StorageEngine>(
+    db: &GraphDatabase<E>,
+    rel_types: Option<&[&str]>,
+) -> HashMap<NodeId, f64> {
+    betweenness_centrality_filtered_until(db, rel_types, Deadline::unbounded())
+        .unwrap_or_else(|t| t.partial)
+}
+
+pub(crate) fn betweenness_centrality_filtered_until<E: StorageEngine>(
+    db: &GraphDatabase<E>,
+    rel_types: Option<&[&str]>,
+    deadline: Deadline,
+) -> Result<HashMap<NodeId, f64>, Timeout<HashMap<NodeId, f64>>> {
     let mut centrality: HashMap<NodeId, f64> = HashMap::new();
     let nodes: Vec<NodeId> = db.all_stored_nodes().map(|n| n.id).collect();
 
@@ -41,8 +198,16 @@ pub fn betweenness_centrality<E: StorageEngine>(
     }
 
     // 对每对节点计算最短路径，统计每个节点被经过的次数
-    for &source in &nodes {
-        let paths = compute_shortest_paths(db, source, &nodes);
+    for (processed, &source) in nodes.iter().enumerate() {
+        deadline.check(|| {
+            let scale = if processed == 0 { 0.0 } else { nodes.len() as f64 / processed as f64 };
+            normalize_betweenness(
+                centrality.iter().map(|(&id, &v)| (id, v * scale)).collect(),
+                nodes.len(),
+            )
+        })?;
+
+        let paths = compute_shortest_paths(db, source, &nodes, rel_types);
 
         for (target, path_nodes) in paths {
             if source != target {
@@ -55,22 +220,213 @@ pub fn betweenness_centrality<E: StorageEngine>(
         }
     }
 
-    // 归一化
-    let n = nodes.len();
+    Ok(normalize_betweenness(centrality, nodes.len()))
+}
+
+fn normalize_betweenness(mut centrality: HashMap<NodeId, f64>, n: usize) -> HashMap<NodeId, f64> {
     if n > 2 {
         let normalizer = ((n - 1) * (n - 2)) as f64;
         for val in centrality.values_mut() {
             *val /= normalizer;
         }
     }
-
     centrality
 }
 
+/// 接近中心性（Closeness Centrality）
+///
+/// 沿出边做 BFS（无向关系双向可达），使用 Wasserman-Faust 公式处理非连通图：
+/// `C(u) = (r - 1) / (n - 1) · (r - 1) / Σd(u, v)`，其中 r 为从 u 可达的节点数（含自身）。
+/// 无法到达任何其他节点时为 0。
+pub fn closeness_centrality<E: StorageEngine>(
+    db: &GraphDatabase<E>,
+) -> HashMap<NodeId, f64> {
+    closeness_centrality_filtered(db, None)
+}
+
+/// 只沿 `rel_types` 中类型关系计算的接近中心性
+pub fn closeness_centrality_on<E: StorageEngine>(
+    db: &GraphDatabase<E>,
+    rel_types: &[&str],
+) -> HashMap<NodeId, f64> {
+    closeness_centrality_filtered(db, Some(rel_types))
+}
+
+/// 限时的接近中心性：每处理完一个源点检查一次时间
+///
+/// 超时时 `Timeout::partial` 只包含已经算完的节点。
+pub fn closeness_centrality_within<E: StorageEngine>(
+    db: &GraphDatabase<E>,
+    deadline: Duration,
+) -> Result<HashMap<NodeId, f64>, Timeout<HashMap<NodeId, f64>>> {
+    closeness_centrality_filtered_until(db, None, Deadline::new(Some(deadline)))
+}
+
+fn closeness_centrality_filtered<E: StorageEngine>(
+    db: &GraphDatabase<E>,
+    rel_types: Option<&[&str]>,
+) -> HashMap<NodeId, f64> {
+    closeness_centrality_filtered_until(db, rel_types, Deadline::unbounded())
+        .unwrap_or_else(|t| t.partial)
+}
+
+pub(crate) fn closeness_centrality_filtered_until<E: StorageEngine>(
+    db: &GraphDatabase<E>,
+    rel_types: Option<&[&str]>,
+    deadline: Deadline,
+) -> Result<HashMap<NodeId, f64>, Timeout<HashMap<NodeId, f64>>> {
+    let nodes: Vec<NodeId> = db.all_stored_nodes().map(|n| n.id).collect();
+    let n = nodes.len();
+    let mut scores = HashMap::with_capacity(n);
+
+    for &source in &nodes {
+        deadline.check(|| scores.clone())?;
+        let mut dist: HashMap<NodeId, usize> = HashMap::new();
+        let mut queue = VecDeque::new();
+        dist.insert(source, 0);
+        queue.push_back(source);
+
+        while let Some(current) = queue.pop_front() {
+            let d = dist[&current];
+            for rel in db.neighbors_out(current).filter(|r| accepts_rel_type(rel_types, r)) {
+                if let Entry::Vacant(slot) = dist.entry(rel.end) {
+                    slot.insert(d + 1);
+                    queue.push_back(rel.end);
+                }
+            }
+        }
+
+        let reachable = dist.len() - 1;
+        let total: usize = dist.values().sum();
+        let score = if reachable == 0 || total == 0 {
+            0.0
+        } else {
+            let r = reachable as f64;
+            (r / (n - 1) as f64) * (r / total as f64)
+        };
+        scores.insert(source, score);
+    }
+
+    Ok(scores)
+}
+
+/// 特征向量中心性（Eigenvector Centrality）
+///
+/// 关系视为无向，对 `A + I` 做幂迭代（加单位阵避免二部图上的振荡），
+/// 每轮按 L2 范数归一化；L1 变化量小于 `tolerance` 或达到 `max_iterations` 时停止。
+pub fn eigenvector_centrality<E: StorageEngine>(
+    db: &GraphDatabase<E>,
+    max_iterations: usize,
+    tolerance: f64,
+) -> HashMap<NodeId, f64> {
+    eigenvector_centrality_filtered(db, None, max_iterations, tolerance)
+}
+
+/// 只考虑 `rel_types` 中类型关系的特征向量中心性
+pub fn eigenvector_centrality_on<E: StorageEngine>(
+    db: &GraphDatabase<E>,
+    rel_types: &[&str],
+    max_iterations: usize,
+    tolerance: f64,
+) -> HashMap<NodeId, f64> {
+    eigenvector_centrality_filtered(db, Some(rel_types), max_iterations, tolerance)
+}
+
+/// 限时的特征向量中心性：每轮迭代前检查一次时间
+///
+/// 超时时 `Timeout::partial` 为最近一轮迭代的（已归一化的）得分。
+pub fn eigenvector_centrality_within<E: StorageEngine>(
+    db: &GraphDatabase<E>,
+    max_iterations: usize,
+    tolerance: f64,
+    deadline: Duration,
+) -> Result<HashMap<NodeId, f64>, Timeout<HashMap<NodeId, f64>>> {
+    eigenvector_centrality_filtered_until(
+        db,
+        None,
+        max_iterations,
+        tolerance,
+        Deadline::new(Some(deadline)),
+    )
+}
+
+fn eigenvector_centrality_filtered<E: StorageEngine>(
+    db: &GraphDatabase<E>,
+    rel_types: Option<&[&str]>,
+    max_iterations: usize,
+    tolerance: f64,
+) -> HashMap<NodeId, f64> {
+    eigenvector_centrality_filtered_until(db, rel_types, max_iterations, tolerance, Deadline::unbounded())
+        .unwrap_or_else(|t| t.partial)
+}
+
+pub(crate) fn eigenvector_centrality_filtered_until<E: StorageEngine>(
+    db: &GraphDatabase<E>,
+    rel_types: Option<&[&str]>,
+    max_iterations: usize,
+    tolerance: f64,
+    deadline: Deadline,
+) -> Result<HashMap<NodeId, f64>, Timeout<HashMap<NodeId, f64>>> {
+    let nodes: Vec<NodeId> = db.all_stored_nodes().map(|n| n.id).collect();
+    if nodes.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    // 无向邻接：出边 + 有向入边（无向关系已在出边中出现）
+    let neighbors: HashMap<NodeId, Vec<NodeId>> = nodes
+        .iter()
+        .map(|&node| {
+            let adj = db
+                .neighbors_out(node)
+                .filter(|r| accepts_rel_type(rel_types, r))
+                .map(|r| r.end)
+                .chain(
+                    db.neighbors_in(node)
+                        .filter(|r| r.directed && accepts_rel_type(rel_types, r))
+                        .map(|r| r.start),
+                )
+                .collect();
+            (node, adj)
+        })
+        .collect();
+
+    let initial = 1.0 / (nodes.len() as f64).sqrt();
+    let mut scores: HashMap<NodeId, f64> = nodes.iter().map(|&n| (n, initial)).collect();
+
+    for _ in 0..max_iterations {
+        deadline.check(|| scores.clone())?;
+        let mut next: HashMap<NodeId, f64> = scores.clone();
+        for (&node, adj) in &neighbors {
+            for neighbor in adj {
+                if let Some(score) = next.get_mut(neighbor) {
+                    *score += scores[&node];
+                }
+            }
+        }
+
+        let norm = next.values().map(|v| v * v).sum::<f64>().sqrt();
+        if norm == 0.0 {
+            return Ok(next);
+        }
+        for v in next.values_mut() {
+            *v /= norm;
+        }
+
+        let delta: f64 = nodes.iter().map(|n| (next[n] - scores[n]).abs()).sum();
+        scores = next;
+        if delta < tolerance {
+            break;
+        }
+    }
+
+    Ok(scores)
+}
+
 fn compute_shortest_paths<E: StorageEngine>(
     db: &GraphDatabase<E>,
     source: NodeId,
     all_nodes: &[NodeId],
+    rel_types: Option<&[&str]>,
 ) -> HashMap<NodeId, Vec<NodeId>> {
     let mut paths = HashMap::new();
     let mut queue = VecDeque::new();
@@ -81,7 +437,7 @@ fn compute_shortest_paths<E: StorageEngine>(
     visited.insert(source);
 
     while let Some(current) = queue.pop_front() {
-        for rel in db.neighbors_out(current) {
+        for rel in db.neighbors_out(current).filter(|r| accepts_rel_type(rel_types, r)) {
             let neighbor = rel.end;
             if !visited.contains(&neighbor) {
                 visited.insert(neighbor);
@@ -0,0 +1,70 @@
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use crate::cancellation::CancellationToken;
+
+/// 算法在截止时间前没有完成
+///
+/// `partial` 为超时前已经得到的结果：迭代算法是最近一轮的近似值，
+/// 逐源点计算的算法只包含已处理的源点或基于已处理源点的估计。
+#[derive(Debug, Clone, PartialEq)]
+pub struct Timeout<T> {
+    pub partial: T,
+    /// 超时时已经运行的时间
+    pub elapsed: Duration,
+}
+
+impl<T> fmt::Display for Timeout<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "algorithm timed out after {:?}", self.elapsed)
+    }
+}
+
+impl<T: fmt::Debug> std::error::Error for Timeout<T> {}
+
+/// 算法内部使用的计时器；`limit` 为 None 时永不超时
+///
+/// 关联的取消令牌被取消时视同超时，算法在下一个检查点返回 `Timeout`。
+#[derive(Debug, Clone)]
+pub(crate) struct Deadline {
+    start: Instant,
+    limit: Option<Duration>,
+    cancel: Option<CancellationToken>,
+}
+
+impl Deadline {
+    pub(crate) fn new(limit: Option<Duration>) -> Self {
+        Self {
+            start: Instant::now(),
+            limit,
+            cancel: None,
+        }
+    }
+
+    pub(crate) fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancel = Some(token);
+        self
+    }
+
+    /// 不限时
+    pub(crate) fn unbounded() -> Self {
+        Self::new(None)
+    }
+
+    pub(crate) fn expired(&self) -> bool {
+        self.cancel.as_ref().is_some_and(|token| token.is_cancelled())
+            || self.limit.is_some_and(|limit| self.start.elapsed() >= limit)
+    }
+
+    /// 已超时时把 `partial()` 包装为 `Timeout` 错误
+    pub(crate) fn check<T>(&self, partial: impl FnOnce() -> T) -> Result<(), Timeout<T>> {
+        if self.expired() {
+            Err(Timeout {
+                partial: partial(),
+                elapsed: self.start.elapsed(),
+            })
+        } else {
+            Ok(())
+        }
+    }
+}
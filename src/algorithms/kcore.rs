@@ -206,17 +206,6 @@ fn get_node_degree<E: StorageEngine>(
     db: &GraphDatabase<E>,
     node_id: NodeId,
 ) -> usize {
-    let mut degree = 0;
-
-    // 统计出边
-    for _rel in db.engine.outgoing_rels(node_id) {
-        degree += 1;
-    }
-
-    // 统计入边
-    for _rel in db.engine.incoming_rels(node_id) {
-        degree += 1;
-    }
-
-    degree
+    // 出边 + 入边，无向关系只计一次
+    db.degree(node_id)
 }
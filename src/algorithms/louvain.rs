@@ -1,86 +1,256 @@
+use crate::algorithms::deadline::{Deadline, Timeout};
 use crate::graph::db::GraphDatabase;
 use crate::storage::{NodeId, StorageEngine};
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::time::Duration;
 
-/// Louvain 社区检测算法（简化版）
+/// 模块度增益比较时的容差，避免浮点误差导致节点来回移动
+const GAIN_EPSILON: f64 = 1e-12;
+
+/// Louvain 社区检测算法（分辨率为 1.0）
+///
+/// 关系视为无向、权重为 1；返回节点到社区编号（从 0 开始连续编号）的映射。
 pub fn louvain<E: StorageEngine>(
     db: &GraphDatabase<E>,
     max_iterations: usize,
 ) -> HashMap<NodeId, usize> {
-    let nodes: Vec<NodeId> = db.all_stored_nodes().map(|n| n.id).collect();
+    louvain_resolution(db, 1.0, max_iterations)
+}
 
-    // 初始化：每个节点是自己的社区
-    let mut communities: HashMap<NodeId, usize> = nodes
-        .iter()
-        .enumerate()
-        .map(|(i, &node)| (node, i))
-        .collect();
+/// 带分辨率参数的 Louvain 社区检测
+///
+/// `resolution` 缩放模块度增益中的零模型项 `Σtot · k_i / 2m`：
+/// 大于 1 时倾向于更多、更小的社区，小于 1 时倾向于更少、更大的社区。
+/// `passes` 为最多执行的层数，每层先做局部移动直到稳定，再把社区聚合为超节点；
+/// 某一层没有任何节点移动时提前结束。
+pub fn louvain_resolution<E: StorageEngine>(
+    db: &GraphDatabase<E>,
+    resolution: f64,
+    passes: usize,
+) -> HashMap<NodeId, usize> {
+    louvain_until(db, resolution, passes, Deadline::unbounded()).unwrap_or_else(|t| t.partial)
+}
 
-    let m = count_edges(db);
-    if m == 0 {
-        return communities;
+/// 限时的 [`louvain_resolution`]：每一层开始前检查一次时间
+///
+/// 超时时 `Timeout::partial` 为已完成各层得到的划分（仍是合法的社区划分，只是不够粗）。
+pub fn louvain_within<E: StorageEngine>(
+    db: &GraphDatabase<E>,
+    resolution: f64,
+    passes: usize,
+    deadline: Duration,
+) -> Result<HashMap<NodeId, usize>, Timeout<HashMap<NodeId, usize>>> {
+    louvain_until(db, resolution, passes, Deadline::new(Some(deadline)))
+}
+
+fn louvain_until<E: StorageEngine>(
+    db: &GraphDatabase<E>,
+    resolution: f64,
+    passes: usize,
+    deadline: Deadline,
+) -> Result<HashMap<NodeId, usize>, Timeout<HashMap<NodeId, usize>>> {
+    let mut graph = WeightedGraph::from_db(db);
+    let node_ids = graph.node_ids.clone();
+    // 原始节点（按下标）当前所属的超节点
+    let mut membership: Vec<usize> = (0..node_ids.len()).collect();
+
+    if graph.total_weight > 0.0 {
+        for _ in 0..passes {
+            deadline.check(|| {
+                renumber_communities(node_ids.iter().copied().zip(membership.iter().copied()).collect())
+            })?;
+            let (communities, moved) = graph.local_moving(resolution);
+            if !moved {
+                break;
+            }
+            for slot in membership.iter_mut() {
+                *slot = communities[*slot];
+            }
+            graph = graph.aggregate(&communities);
+        }
+    }
+
+    Ok(renumber_communities(node_ids.into_iter().zip(membership).collect()))
+}
+
+/// 计算一个划分的模块度（分辨率 1.0）
+///
+/// `Q = Σ_c [ in_c / 2m − (tot_c / 2m)² ]`，关系视为无向、权重为 1。
+/// 未出现在 `communities` 中的节点各自视为单独的社区；没有关系的图模块度为 0。
+pub fn modularity<E: StorageEngine>(
+    db: &GraphDatabase<E>,
+    communities: &HashMap<NodeId, usize>,
+) -> f64 {
+    let graph = WeightedGraph::from_db(db);
+    if graph.total_weight == 0.0 {
+        return 0.0;
     }
 
-    let mut improved = true;
-    let mut iteration = 0;
+    // 未分配的节点使用独立的键，避免与已有社区编号冲突
+    let community_of = |idx: usize| -> (bool, usize) {
+        match communities.get(&graph.node_ids[idx]) {
+            Some(&c) => (true, c),
+            None => (false, idx),
+        }
+    };
+
+    let mut internal: HashMap<(bool, usize), f64> = HashMap::new();
+    let mut totals: HashMap<(bool, usize), f64> = HashMap::new();
+    for (i, neighbors) in graph.adjacency.iter().enumerate() {
+        let ci = community_of(i);
+        *totals.entry(ci).or_insert(0.0) += graph.degree[i];
+        for (&j, &w) in neighbors {
+            if community_of(j) == ci {
+                *internal.entry(ci).or_insert(0.0) += w;
+            }
+        }
+    }
 
-    while improved && iteration < max_iterations {
-        improved = false;
-        iteration += 1;
+    let two_m = graph.total_weight;
+    totals
+        .iter()
+        .map(|(c, &tot)| internal.get(c).copied().unwrap_or(0.0) / two_m - (tot / two_m).powi(2))
+        .sum()
+}
 
-        for &node in &nodes {
-            let current_comm = communities[&node];
+/// 无向加权图（下标形式），用于 Louvain 的逐层聚合
+struct WeightedGraph {
+    /// 第 0 层的节点 ID（聚合后的层不使用）
+    node_ids: Vec<NodeId>,
+    /// 对称邻接表；自环 u-u 记为 adjacency[u][u] += 2w
+    adjacency: Vec<BTreeMap<usize, f64>>,
+    /// 加权度 k_i
+    degree: Vec<f64>,
+    /// 所有度之和（即 2m）
+    total_weight: f64,
+}
 
-            // 计算移动到每个邻居社区的模块度增益
-            let mut comm_gains: HashMap<usize, f64> = HashMap::new();
+impl WeightedGraph {
+    fn from_db<E: StorageEngine>(db: &GraphDatabase<E>) -> Self {
+        let mut node_ids: Vec<NodeId> = db.all_stored_nodes().map(|n| n.id).collect();
+        node_ids.sort_unstable();
+        let index: HashMap<NodeId, usize> =
+            node_ids.iter().enumerate().map(|(i, &id)| (id, i)).collect();
 
+        let mut adjacency = vec![BTreeMap::new(); node_ids.len()];
+        // 无向关系会从两端各返回一次，按关系 ID 去重
+        let mut seen = HashSet::new();
+        for &node in &node_ids {
             for rel in db.neighbors_out(node) {
-                let neighbor = rel.end;
-                let neighbor_comm = communities[&neighbor];
-                *comm_gains.entry(neighbor_comm).or_insert(0.0) += 1.0;
+                if !seen.insert(rel.id) {
+                    continue;
+                }
+                let (Some(&u), Some(&v)) = (index.get(&rel.start), index.get(&rel.end)) else {
+                    continue;
+                };
+                if u == v {
+                    *adjacency[u].entry(u).or_insert(0.0) += 2.0;
+                } else {
+                    *adjacency[u].entry(v).or_insert(0.0) += 1.0;
+                    *adjacency[v].entry(u).or_insert(0.0) += 1.0;
+                }
             }
+        }
 
-            for rel in db.neighbors_in(node) {
-                let neighbor = rel.start;
-                let neighbor_comm = communities[&neighbor];
-                *comm_gains.entry(neighbor_comm).or_insert(0.0) += 1.0;
-            }
+        Self::with_adjacency(node_ids, adjacency)
+    }
+
+    fn with_adjacency(node_ids: Vec<NodeId>, adjacency: Vec<BTreeMap<usize, f64>>) -> Self {
+        let degree: Vec<f64> = adjacency.iter().map(|n| n.values().sum()).collect();
+        let total_weight = degree.iter().sum();
+        Self {
+            node_ids,
+            adjacency,
+            degree,
+            total_weight,
+        }
+    }
+
+    /// 局部移动阶段：反复把每个节点移到模块度增益最大的相邻社区，直到没有节点移动
+    ///
+    /// 返回每个节点所属的社区（连续编号）以及是否发生过移动。
+    fn local_moving(&self, resolution: f64) -> (Vec<usize>, bool) {
+        let n = self.adjacency.len();
+        let mut community: Vec<usize> = (0..n).collect();
+        let mut totals: Vec<f64> = self.degree.clone();
+        let mut moved_any = false;
 
-            // 找到最佳社区
-            let best_comm = comm_gains
-                .iter()
-                .max_by(|a, b| a.1.partial_cmp(b.1).unwrap())
-                .map(|(comm, _)| *comm);
+        loop {
+            let mut moved = false;
+            for node in 0..n {
+                let current = community[node];
+                let k_i = self.degree[node];
+                totals[current] -= k_i;
 
-            if let Some(best) = best_comm {
-                if best != current_comm && comm_gains.get(&best).unwrap_or(&0.0) > &0.0 {
-                    communities.insert(node, best);
-                    improved = true;
+                // 节点与各相邻社区之间的连接权重
+                let mut links: BTreeMap<usize, f64> = BTreeMap::new();
+                for (&neighbor, &w) in &self.adjacency[node] {
+                    if neighbor != node {
+                        *links.entry(community[neighbor]).or_insert(0.0) += w;
+                    }
+                }
+
+                let gain = |c: usize, k_in: f64| {
+                    k_in - resolution * totals[c] * k_i / self.total_weight
+                };
+                let mut best = current;
+                let mut best_gain = gain(current, links.get(&current).copied().unwrap_or(0.0));
+                for (&c, &k_in) in &links {
+                    let g = gain(c, k_in);
+                    if g > best_gain + GAIN_EPSILON {
+                        best = c;
+                        best_gain = g;
+                    }
+                }
+
+                totals[best] += k_i;
+                if best != current {
+                    community[node] = best;
+                    moved = true;
+                    moved_any = true;
                 }
             }
+            if !moved {
+                break;
+            }
         }
-    }
 
-    // 重新编号社区（确保连续）
-    renumber_communities(communities)
-}
+        // 社区编号压缩为 0..k
+        let mut remap: HashMap<usize, usize> = HashMap::new();
+        let community = community
+            .into_iter()
+            .map(|c| {
+                let next = remap.len();
+                *remap.entry(c).or_insert(next)
+            })
+            .collect();
+        (community, moved_any)
+    }
 
-fn count_edges<E: StorageEngine>(db: &GraphDatabase<E>) -> usize {
-    db.all_stored_nodes()
-        .map(|n| db.neighbors_out(n.id).count())
-        .sum()
+    /// 聚合阶段：每个社区成为一个超节点，社区之间的权重求和，社区内部权重变为自环
+    fn aggregate(&self, community: &[usize]) -> Self {
+        let count = community.iter().max().map_or(0, |&c| c + 1);
+        let mut adjacency = vec![BTreeMap::new(); count];
+        for (i, neighbors) in self.adjacency.iter().enumerate() {
+            for (&j, &w) in neighbors {
+                *adjacency[community[i]].entry(community[j]).or_insert(0.0) += w;
+            }
+        }
+        Self::with_adjacency(Vec::new(), adjacency)
+    }
 }
 
 fn renumber_communities(communities: HashMap<NodeId, usize>) -> HashMap<NodeId, usize> {
-    let unique_comms: HashSet<usize> = communities.values().copied().collect();
-    let comm_map: HashMap<usize, usize> = unique_comms
-        .into_iter()
-        .enumerate()
-        .map(|(i, old_id)| (old_id, i))
-        .collect();
+    // 按节点 ID 顺序分配编号，保证结果稳定
+    let mut nodes: Vec<(NodeId, usize)> = communities.into_iter().collect();
+    nodes.sort_unstable_by_key(|&(node, _)| node);
 
-    communities
+    let mut comm_map: HashMap<usize, usize> = HashMap::new();
+    nodes
         .into_iter()
-        .map(|(node, old_comm)| (node, comm_map[&old_comm]))
+        .map(|(node, old_comm)| {
+            let next = comm_map.len();
+            (node, *comm_map.entry(old_comm).or_insert(next))
+        })
         .collect()
 }
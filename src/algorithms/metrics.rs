@@ -0,0 +1,94 @@
+//! 图结构指标
+//!
+//! 用于刻画整张图的结构特征：密度和度同配性。
+
+use crate::graph::db::GraphDatabase;
+use crate::storage::{NodeId, StorageEngine};
+use std::collections::HashSet;
+
+/// 图密度：实际存在的有向边数 / 可能的有向边数 `n * (n - 1)`
+///
+/// - 自环和平行边不计入（只统计不同的有序节点对）
+/// - 无向关系相当于两个方向各一条边
+/// - 节点数小于 2 时没有可能的边，返回 0.0
+///
+/// # 示例
+///
+/// ```
+/// use rs_graphdb::algorithms::graph_density;
+/// use rs_graphdb::graph::db::GraphDatabase;
+/// use rs_graphdb::values::Properties;
+///
+/// let mut db = GraphDatabase::new_in_memory();
+/// let a = db.create_node(vec!["N"], Properties::new());
+/// let b = db.create_node(vec!["N"], Properties::new());
+/// db.create_rel(a, b, "E", Properties::new());
+///
+/// assert_eq!(graph_density(&db), 0.5);
+/// ```
+pub fn graph_density<E: StorageEngine>(db: &GraphDatabase<E>) -> f64 {
+    let n = db.node_count();
+    if n < 2 {
+        return 0.0;
+    }
+
+    let mut arcs: HashSet<(NodeId, NodeId)> = HashSet::new();
+    for node in db.all_stored_nodes() {
+        // 无向关系从两端都能作为出边看到，正好对应两个方向
+        for rel in db.neighbors_out(node.id) {
+            if rel.start != rel.end {
+                arcs.insert((rel.start, rel.end));
+            }
+        }
+    }
+
+    arcs.len() as f64 / (n as f64 * (n - 1) as f64)
+}
+
+/// 度同配系数（Degree Assortativity）
+///
+/// 计算每条边两端节点度数的皮尔逊相关系数，边按无向处理（每条边贡献
+/// (du, dv) 和 (dv, du) 两个样本）。取值范围 [-1, 1]：
+/// - 正值：高度数节点倾向于连接高度数节点（如合作网络）
+/// - 负值：高度数节点倾向于连接低度数节点（如星形图）
+///
+/// 没有边，或所有边两端度数完全相同（如正则图）导致方差为 0 时，返回 0.0
+pub fn degree_assortativity<E: StorageEngine>(db: &GraphDatabase<E>) -> f64 {
+    let mut pairs: Vec<(f64, f64)> = Vec::new();
+
+    for node in db.all_stored_nodes() {
+        for rel in db.neighbors_out(node.id) {
+            // 无向关系在两端各出现一次，只取一次
+            if !rel.directed && rel.start > rel.end {
+                continue;
+            }
+            let du = db.degree(rel.start) as f64;
+            let dv = db.degree(rel.end) as f64;
+            pairs.push((du, dv));
+            pairs.push((dv, du));
+        }
+    }
+
+    if pairs.is_empty() {
+        return 0.0;
+    }
+
+    let count = pairs.len() as f64;
+    let mean_x = pairs.iter().map(|(x, _)| x).sum::<f64>() / count;
+    let mean_y = pairs.iter().map(|(_, y)| y).sum::<f64>() / count;
+
+    let mut cov = 0.0;
+    let mut var_x = 0.0;
+    let mut var_y = 0.0;
+    for (x, y) in &pairs {
+        cov += (x - mean_x) * (y - mean_y);
+        var_x += (x - mean_x).powi(2);
+        var_y += (y - mean_y).powi(2);
+    }
+
+    let denom = (var_x * var_y).sqrt();
+    if denom < f64::EPSILON {
+        return 0.0;
+    }
+    cov / denom
+}
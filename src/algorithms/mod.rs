@@ -5,12 +5,18 @@ pub mod pagerank;
 pub mod louvain;
 pub mod traversal;
 pub mod triangle;
+pub mod motif;
 pub mod scc;
 pub mod kcore;
+pub mod coloring;
 pub mod astar;
+pub mod metrics;
+pub mod deadline;
 
 pub use shortest_path::{
     dijkstra,
+    dijkstra_with_rels,
+    dijkstra_multi,
     bfs_shortest_path,
     bfs_shortest_path_by_rel_type,
     all_shortest_paths,
@@ -18,10 +24,17 @@ pub use shortest_path::{
     count_all_shortest_paths,
     has_path,
 };
-pub use centrality::{degree_centrality, betweenness_centrality};
+pub use centrality::{
+    degree_centrality, betweenness_centrality, closeness_centrality, eigenvector_centrality,
+    degree_centrality_on, betweenness_centrality_on, closeness_centrality_on,
+    in_degree_centrality, out_degree_centrality,
+    eigenvector_centrality_on,
+    betweenness_centrality_within, closeness_centrality_within, eigenvector_centrality_within,
+    weighted_degree, weighted_degree_directed, NodeStrength,
+};
 pub use community::connected_components;
-pub use pagerank::pagerank;
-pub use louvain::louvain;
+pub use pagerank::{pagerank, pagerank_on, pagerank_until_converged, pagerank_until_converged_within};
+pub use louvain::{louvain, louvain_resolution, louvain_within, modularity};
 pub use triangle::{
     count_triangles,
     count_triangles_for_node,
@@ -29,6 +42,7 @@ pub use triangle::{
     local_clustering_coefficient,
     global_clustering_coefficient,
 };
+pub use motif::{count_motif, MotifPattern};
 pub use scc::{
     strongly_connected_components,
     count_scc,
@@ -41,11 +55,14 @@ pub use kcore::{
     get_k_core,
     max_core_number,
 };
+pub use coloring::{greedy_coloring, color_count};
 pub use astar::{
     astar,
     astar_euclidean,
     astar_manhattan,
 };
+pub use metrics::{graph_density, degree_assortativity};
+pub use deadline::Timeout;
 
 // 导出所有遍历算法
 pub use traversal::{
@@ -53,6 +70,9 @@ pub use traversal::{
     bfs,
     dfs,
     bfs_by_rel_type,
+    bfs_filtered,
+    dfs_filtered,
+    bfs_tree,
     variable_length_path,
     all_simple_paths,
     undirected_bfs,
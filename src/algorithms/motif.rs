@@ -0,0 +1,127 @@
+//! 小型子图模式（Motif）计数
+//!
+//! 把三角计数推广到其他小结构：长度为 2 的路径、开放三元组（wedge）和 4-环。
+//! 计数时忽略关系方向、自环和平行边，每个匹配的子图（按同构意义）只计一次。
+
+use crate::graph::db::GraphDatabase;
+use crate::storage::{NodeId, StorageEngine};
+use std::collections::{HashMap, HashSet};
+
+/// 要计数的子图模式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MotifPattern {
+    /// 长度为 2 的路径 a - b - c（不要求 a、c 不相连）
+    Path2,
+    /// 开放三元组：长度为 2 的路径且两端不相连
+    Wedge,
+    /// 三角形 a - b - c - a
+    Triangle,
+    /// 4-环 a - b - c - d - a（不要求对角线不存在）
+    Square,
+}
+
+/// 统计图中与 `pattern` 匹配的子图数量
+///
+/// # 算法说明
+///
+/// - `Path2`：以每个节点为中点，任取两个邻居，共 Σ C(d, 2) 条
+/// - `Wedge`：同上，但只计两个端点不相连的邻居对
+/// - `Triangle`：只在三角形中 ID 最小的节点处计数
+/// - `Square`：以环中 ID 最小的节点 a 为起点，统计 a 到对角节点 c 的长度为 2
+///   且中间节点大于 a 的路径数 p，每对路径组成一个 4-环，共 C(p, 2) 个
+///
+/// # 复杂度
+///
+/// - 时间复杂度: O(|V| * d^2)，其中 d 是平均度数
+/// - 空间复杂度: O(|V| + |E|)
+///
+/// # 示例
+///
+/// ```
+/// use rs_graphdb::algorithms::{count_motif, MotifPattern};
+/// use rs_graphdb::graph::db::GraphDatabase;
+/// use rs_graphdb::storage::mem_store::MemStore;
+/// use rs_graphdb::values::Properties;
+///
+/// let mut db = GraphDatabase::<MemStore>::new_in_memory();
+/// let n: Vec<_> = (0..4).map(|_| db.create_node(vec![], Properties::new())).collect();
+/// for i in 0..4 {
+///     db.create_rel(n[i], n[(i + 1) % 4], "EDGE", Properties::new());
+/// }
+///
+/// assert_eq!(count_motif(&db, MotifPattern::Square), 1);
+/// assert_eq!(count_motif(&db, MotifPattern::Wedge), 4);
+/// assert_eq!(count_motif(&db, MotifPattern::Triangle), 0);
+/// ```
+pub fn count_motif<E: StorageEngine>(db: &GraphDatabase<E>, pattern: MotifPattern) -> usize {
+    let adjacency = undirected_adjacency(db);
+
+    match pattern {
+        MotifPattern::Path2 => adjacency
+            .values()
+            .map(|neighbors| pairs(neighbors.len()))
+            .sum(),
+        MotifPattern::Wedge => {
+            let mut count = 0;
+            for neighbors in adjacency.values() {
+                let list: Vec<NodeId> = neighbors.iter().copied().collect();
+                for (i, a) in list.iter().enumerate() {
+                    count += list[i + 1..]
+                        .iter()
+                        .filter(|c| !adjacency[a].contains(c))
+                        .count();
+                }
+            }
+            count
+        }
+        MotifPattern::Triangle => {
+            let mut count = 0;
+            for (&u, neighbors) in &adjacency {
+                let larger: Vec<NodeId> = neighbors.iter().copied().filter(|&v| v > u).collect();
+                for (i, v) in larger.iter().enumerate() {
+                    count += larger[i + 1..]
+                        .iter()
+                        .filter(|w| adjacency[v].contains(w))
+                        .count();
+                }
+            }
+            count
+        }
+        MotifPattern::Square => {
+            let mut count = 0;
+            for (&a, neighbors) in &adjacency {
+                // 对角节点 c -> 经过大于 a 的中间节点到达 c 的路径数
+                let mut paths: HashMap<NodeId, usize> = HashMap::new();
+                for &b in neighbors.iter().filter(|&&b| b > a) {
+                    for &c in adjacency[&b].iter().filter(|&&c| c > a) {
+                        *paths.entry(c).or_insert(0) += 1;
+                    }
+                }
+                count += paths.values().map(|&p| pairs(p)).sum::<usize>();
+            }
+            count
+        }
+    }
+}
+
+/// C(n, 2)
+fn pairs(n: usize) -> usize {
+    n * n.saturating_sub(1) / 2
+}
+
+/// 忽略方向的邻接表（去掉自环和平行边）
+fn undirected_adjacency<E: StorageEngine>(
+    db: &GraphDatabase<E>,
+) -> HashMap<NodeId, HashSet<NodeId>> {
+    db.all_stored_nodes()
+        .map(|node| {
+            let neighbors = db
+                .neighbors_out(node.id)
+                .map(|rel| rel.end)
+                .chain(db.neighbors_in(node.id).map(|rel| rel.start))
+                .filter(|&other| other != node.id)
+                .collect();
+            (node.id, neighbors)
+        })
+        .collect()
+}
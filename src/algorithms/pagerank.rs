@@ -1,6 +1,9 @@
+use crate::algorithms::centrality::accepts_rel_type;
+use crate::algorithms::deadline::{Deadline, Timeout};
 use crate::graph::db::GraphDatabase;
 use crate::storage::{NodeId, StorageEngine};
 use std::collections::HashMap;
+use std::time::Duration;
 
 /// PageRank 算法
 /// 参数:
@@ -11,36 +14,159 @@ pub fn pagerank<E: StorageEngine>(
     damping: f64,
     iterations: usize,
 ) -> HashMap<NodeId, f64> {
-    let nodes: Vec<NodeId> = db.all_stored_nodes().map(|n| n.id).collect();
-    let n = nodes.len();
+    pagerank_filtered(db, None, damping, iterations)
+}
+
+/// 只沿 `rel_types` 中类型关系传播的 PageRank
+///
+/// 出度同样只统计这些类型的关系，例如在混合图中只对 CITES 关系计算排名。
+pub fn pagerank_on<E: StorageEngine>(
+    db: &GraphDatabase<E>,
+    rel_types: &[&str],
+    damping: f64,
+    iterations: usize,
+) -> HashMap<NodeId, f64> {
+    pagerank_filtered(db, Some(rel_types), damping, iterations)
+}
 
-    if n == 0 {
+fn pagerank_filtered<E: StorageEngine>(
+    db: &GraphDatabase<E>,
+    rel_types: Option<&[&str]>,
+    damping: f64,
+    iterations: usize,
+) -> HashMap<NodeId, f64> {
+    let Some(state) = PageRankState::new(db, rel_types) else {
         return HashMap::new();
+    };
+
+    let mut ranks = state.initial_ranks();
+    for _ in 0..iterations {
+        ranks = state.step(db, &ranks, damping);
     }
 
-    let initial_rank = 1.0 / n as f64;
-    let mut ranks: HashMap<NodeId, f64> = nodes.iter().map(|&id| (id, initial_rank)).collect();
+    normalize(ranks)
+}
 
-    // 计算每个节点的出度
-    let out_degree: HashMap<NodeId, usize> = nodes
-        .iter()
-        .map(|&id| {
-            let degree = db.neighbors_out(id).count();
-            (id, degree)
-        })
-        .collect();
+/// 带收敛判断的 PageRank
+///
+/// 每轮迭代后计算与上一轮的 L1 距离（各节点 rank 变化绝对值之和），
+/// 小于 `tolerance` 时提前停止，最多迭代 `max_iterations` 轮。
+///
+/// 返回 (rank 表, 实际迭代轮数)
+pub fn pagerank_until_converged<E: StorageEngine>(
+    db: &GraphDatabase<E>,
+    damping: f64,
+    tolerance: f64,
+    max_iterations: usize,
+) -> (HashMap<NodeId, f64>, usize) {
+    converge(db, damping, tolerance, max_iterations, Deadline::unbounded())
+        .unwrap_or_else(|t| (t.partial, max_iterations))
+}
 
-    for _ in 0..iterations {
+/// 限时的 [`pagerank_until_converged`]：每轮迭代前检查一次时间
+///
+/// 超时时 `Timeout::partial` 为最近一轮迭代的（已归一化的）rank 表。
+pub fn pagerank_until_converged_within<E: StorageEngine>(
+    db: &GraphDatabase<E>,
+    damping: f64,
+    tolerance: f64,
+    max_iterations: usize,
+    deadline: Duration,
+) -> Result<Converged, Timeout<HashMap<NodeId, f64>>> {
+    converge(db, damping, tolerance, max_iterations, Deadline::new(Some(deadline)))
+}
+
+pub(crate) fn converge<E: StorageEngine>(
+    db: &GraphDatabase<E>,
+    damping: f64,
+    tolerance: f64,
+    max_iterations: usize,
+    deadline: Deadline,
+) -> Result<Converged, Timeout<HashMap<NodeId, f64>>> {
+    let Some(state) = PageRankState::new(db, None) else {
+        return Ok((HashMap::new(), 0));
+    };
+
+    let mut ranks = state.initial_ranks();
+    let mut used = 0;
+    while used < max_iterations {
+        deadline.check(|| normalize(ranks.clone()))?;
+        let new_ranks = state.step(db, &ranks, damping);
+        used += 1;
+
+        let delta: f64 = new_ranks
+            .iter()
+            .map(|(id, rank)| (rank - ranks.get(id).copied().unwrap_or(0.0)).abs())
+            .sum();
+        ranks = new_ranks;
+
+        if delta < tolerance {
+            break;
+        }
+    }
+
+    Ok((normalize(ranks), used))
+}
+
+/// 收敛后的 (rank 表, 实际迭代轮数)
+type Converged = (HashMap<NodeId, f64>, usize);
+
+/// 迭代过程中不变的数据：节点列表、出度与关系类型过滤
+struct PageRankState<'a> {
+    nodes: Vec<NodeId>,
+    out_degree: HashMap<NodeId, usize>,
+    rel_types: Option<&'a [&'a str]>,
+}
+
+impl<'a> PageRankState<'a> {
+    /// 空图返回 None
+    fn new<E: StorageEngine>(
+        db: &GraphDatabase<E>,
+        rel_types: Option<&'a [&'a str]>,
+    ) -> Option<Self> {
+        let nodes: Vec<NodeId> = db.all_stored_nodes().map(|n| n.id).collect();
+        if nodes.is_empty() {
+            return None;
+        }
+
+        // 计算每个节点的出度
+        let out_degree: HashMap<NodeId, usize> = nodes
+            .iter()
+            .map(|&id| {
+                let degree = db
+                    .neighbors_out(id)
+                    .filter(|r| accepts_rel_type(rel_types, r))
+                    .count();
+                (id, degree)
+            })
+            .collect();
+
+        Some(Self { nodes, out_degree, rel_types })
+    }
+
+    fn initial_ranks(&self) -> HashMap<NodeId, f64> {
+        let initial_rank = 1.0 / self.nodes.len() as f64;
+        self.nodes.iter().map(|&id| (id, initial_rank)).collect()
+    }
+
+    /// 执行一轮迭代
+    fn step<E: StorageEngine>(
+        &self,
+        db: &GraphDatabase<E>,
+        ranks: &HashMap<NodeId, f64>,
+        damping: f64,
+    ) -> HashMap<NodeId, f64> {
+        let n = self.nodes.len();
         let mut new_ranks: HashMap<NodeId, f64> = HashMap::new();
 
-        for &node in &nodes {
+        for &node in &self.nodes {
             let mut rank = (1.0 - damping) / n as f64;
 
             // 遍历所有指向当前节点的节点
-            for rel in db.neighbors_in(node) {
+            for rel in db.neighbors_in(node).filter(|r| accepts_rel_type(self.rel_types, r)) {
                 let from_node = rel.start;
                 let from_rank = ranks.get(&from_node).copied().unwrap_or(0.0);
-                let from_out_degree = out_degree.get(&from_node).copied().unwrap_or(1);
+                let from_out_degree = self.out_degree.get(&from_node).copied().unwrap_or(1);
 
                 if from_out_degree > 0 {
                     rank += damping * (from_rank / from_out_degree as f64);
@@ -50,10 +176,12 @@ pub fn pagerank<E: StorageEngine>(
             new_ranks.insert(node, rank);
         }
 
-        ranks = new_ranks;
+        new_ranks
     }
+}
 
-    // 归一化
+/// 归一化，使所有 rank 之和为 1
+fn normalize(mut ranks: HashMap<NodeId, f64>) -> HashMap<NodeId, f64> {
     let sum: f64 = ranks.values().sum();
     if sum > 0.0 {
         for val in ranks.values_mut() {
@@ -1,5 +1,8 @@
+use crate::algorithms::traversal::Path;
 use crate::graph::db::GraphDatabase;
-use crate::storage::{NodeId, StorageEngine};
+use crate::graph::model::Relationship;
+use crate::storage::{NodeId, RelId, StorageEngine};
+use crate::values::Value;
 use std::collections::{HashMap, HashSet, VecDeque, BinaryHeap};
 use std::cmp::Ordering;
 
@@ -450,3 +453,121 @@ pub fn dijkstra<E: StorageEngine>(
 
     None
 }
+
+#[derive(Copy, Clone, PartialEq)]
+struct WeightedState {
+    cost: f64,
+    node: NodeId,
+}
+
+impl Eq for WeightedState {}
+
+impl Ord for WeightedState {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // 小顶堆：代价小的优先，相同代价按节点 ID 保证顺序稳定
+        other
+            .cost
+            .total_cmp(&self.cost)
+            .then_with(|| other.node.cmp(&self.node))
+    }
+}
+
+impl PartialOrd for WeightedState {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// 加权 Dijkstra 最短路径，同时返回经过的关系
+///
+/// 边权重取关系属性 `weight_prop`（Int/Float），缺失或非数值时为 1.0；
+/// Dijkstra 要求权重非负，负值按 0 处理。沿出边方向搜索（无向关系双向可达）。
+///
+/// 返回 `(路径, 总代价)`，其中 `path.rels[i]` 连接 `path.nodes[i]` 和 `path.nodes[i + 1]`；
+/// 不可达时返回 None。
+pub fn dijkstra_with_rels<E: StorageEngine>(
+    db: &GraphDatabase<E>,
+    start: NodeId,
+    end: NodeId,
+    weight_prop: &str,
+) -> Option<(Path, f64)> {
+    weighted_dijkstra(db, start, end, |rel| match rel.props.get(weight_prop) {
+        Some(Value::Int(i)) => *i as f64,
+        Some(Value::Float(f)) => *f,
+        _ => 1.0,
+    })
+}
+
+/// 多指标加权 Dijkstra 最短路径
+///
+/// 边代价为多个关系属性的加权和，例如 `[("time", 0.7), ("cost", 0.3)]`
+/// 表示 `0.7 * time + 0.3 * cost`。缺失或非数值的属性贡献 0；
+/// 加权和为负时按 0 处理。返回值与 [`dijkstra_with_rels`] 相同。
+pub fn dijkstra_multi<E: StorageEngine>(
+    db: &GraphDatabase<E>,
+    start: NodeId,
+    end: NodeId,
+    weights: &[(String, f64)],
+) -> Option<(Path, f64)> {
+    weighted_dijkstra(db, start, end, |rel| {
+        weights
+            .iter()
+            .map(|(prop, factor)| match rel.props.get(prop) {
+                Some(Value::Int(i)) => *i as f64 * factor,
+                Some(Value::Float(f)) => f * factor,
+                _ => 0.0,
+            })
+            .sum()
+    })
+}
+
+/// 加权 Dijkstra 的公共实现，边权重由 `weight` 计算，负值按 0 处理
+fn weighted_dijkstra<E: StorageEngine>(
+    db: &GraphDatabase<E>,
+    start: NodeId,
+    end: NodeId,
+    weight: impl Fn(&Relationship) -> f64,
+) -> Option<(Path, f64)> {
+    let mut heap = BinaryHeap::new();
+    let mut dist: HashMap<NodeId, f64> = HashMap::new();
+    let mut parent: HashMap<NodeId, (NodeId, RelId)> = HashMap::new();
+
+    dist.insert(start, 0.0);
+    heap.push(WeightedState { cost: 0.0, node: start });
+
+    while let Some(WeightedState { cost, node }) = heap.pop() {
+        if node == end {
+            let mut nodes = vec![end];
+            let mut rels = Vec::new();
+            let mut current = end;
+            while current != start {
+                let (p, rel) = parent[&current];
+                nodes.push(p);
+                rels.push(rel);
+                current = p;
+            }
+            nodes.reverse();
+            rels.reverse();
+            return Some((Path { nodes, rels }, cost));
+        }
+
+        if cost > dist.get(&node).copied().unwrap_or(f64::INFINITY) {
+            continue;
+        }
+
+        for rel in db.neighbors_out(node) {
+            let next_cost = cost + weight(&rel).max(0.0);
+
+            if next_cost < dist.get(&rel.end).copied().unwrap_or(f64::INFINITY) {
+                dist.insert(rel.end, next_cost);
+                parent.insert(rel.end, (node, rel.id));
+                heap.push(WeightedState {
+                    cost: next_cost,
+                    node: rel.end,
+                });
+            }
+        }
+    }
+
+    None
+}
@@ -8,6 +8,7 @@
 //! - 无向图遍历
 
 use crate::graph::db::GraphDatabase;
+use crate::query_engine::Direction;
 use crate::storage::{NodeId, RelId, StorageEngine};
 use std::collections::{HashMap, HashSet, VecDeque};
 
@@ -177,6 +178,132 @@ pub fn bfs_by_rel_type<E: StorageEngine>(
     result
 }
 
+/// 按关系类型和方向过滤的 BFS
+///
+/// 只沿 `rel_types` 中的关系类型扩展（为空时不限类型），`direction` 决定沿出边、
+/// 入边还是两个方向遍历。返回按访问顺序排列的节点，包含起始节点。
+pub fn bfs_filtered<E: StorageEngine>(
+    db: &GraphDatabase<E>,
+    start: NodeId,
+    max_depth: Option<usize>,
+    rel_types: &[&str],
+    direction: Direction,
+) -> Vec<NodeId> {
+    let mut visited = HashSet::new();
+    let mut queue = VecDeque::new();
+    let mut result = Vec::new();
+
+    queue.push_back((start, 0));
+    visited.insert(start);
+
+    while let Some((node, depth)) = queue.pop_front() {
+        result.push(node);
+
+        if let Some(max) = max_depth {
+            if depth >= max {
+                continue;
+            }
+        }
+
+        for neighbor in filtered_neighbors(db, node, rel_types, direction) {
+            if visited.insert(neighbor) {
+                queue.push_back((neighbor, depth + 1));
+            }
+        }
+    }
+
+    result
+}
+
+/// 按关系类型和方向过滤的 DFS
+///
+/// 过滤规则与 [`bfs_filtered`] 相同。
+pub fn dfs_filtered<E: StorageEngine>(
+    db: &GraphDatabase<E>,
+    start: NodeId,
+    max_depth: Option<usize>,
+    rel_types: &[&str],
+    direction: Direction,
+) -> Vec<NodeId> {
+    let mut visited = HashSet::new();
+    let mut result = Vec::new();
+    let next = |node| filtered_neighbors(db, node, rel_types, direction);
+    dfs_filtered_recursive(&next, start, max_depth, 0, &mut visited, &mut result);
+    result
+}
+
+fn dfs_filtered_recursive(
+    next: &impl Fn(NodeId) -> Vec<NodeId>,
+    node: NodeId,
+    max_depth: Option<usize>,
+    depth: usize,
+    visited: &mut HashSet<NodeId>,
+    result: &mut Vec<NodeId>,
+) {
+    visited.insert(node);
+    result.push(node);
+
+    if let Some(max) = max_depth {
+        if depth >= max {
+            return;
+        }
+    }
+
+    for neighbor in next(node) {
+        if !visited.contains(&neighbor) {
+            dfs_filtered_recursive(next, neighbor, max_depth, depth + 1, visited, result);
+        }
+    }
+}
+
+/// BFS 生成树
+///
+/// 从 `root` 按 [`bfs_filtered`] 的规则遍历，返回树上的 `(父节点, 子节点)` 边，
+/// 按发现顺序排列。每个可达节点（根除外）恰好出现一次作为子节点，
+/// 父节点是它最先被发现时所在的节点，因此深度即为到根的最少跳数。
+pub fn bfs_tree<E: StorageEngine>(
+    db: &GraphDatabase<E>,
+    root: NodeId,
+    rel_types: &[&str],
+    direction: Direction,
+) -> Vec<(NodeId, NodeId)> {
+    let mut visited = HashSet::new();
+    let mut queue = VecDeque::new();
+    let mut edges = Vec::new();
+
+    queue.push_back(root);
+    visited.insert(root);
+
+    while let Some(node) = queue.pop_front() {
+        for neighbor in filtered_neighbors(db, node, rel_types, direction) {
+            if visited.insert(neighbor) {
+                edges.push((node, neighbor));
+                queue.push_back(neighbor);
+            }
+        }
+    }
+
+    edges
+}
+
+/// 沿指定方向、满足类型过滤的相邻节点（`rel_types` 为空时不限类型）
+fn filtered_neighbors<E: StorageEngine>(
+    db: &GraphDatabase<E>,
+    node: NodeId,
+    rel_types: &[&str],
+    direction: Direction,
+) -> Vec<NodeId> {
+    let accepts = |typ: &str| rel_types.is_empty() || rel_types.contains(&typ);
+    let mut neighbors = Vec::new();
+    if matches!(direction, Direction::Outgoing | Direction::Both) {
+        neighbors.extend(db.neighbors_out(node).filter(|r| accepts(&r.typ)).map(|r| r.end));
+    }
+    if matches!(direction, Direction::Incoming | Direction::Both) {
+        neighbors.extend(db.neighbors_in(node).filter(|r| accepts(&r.typ)).map(|r| r.start));
+    }
+    neighbors
+}
+
 /// 可变长路径遍历
 ///
 /// 查找从 start 到 end 的所有路径，路径长度在 min_hops 到 max_hops 之间
@@ -643,6 +770,84 @@ mod tests {
         assert!(!result.contains(&n2));
     }
 
+    #[test]
+    fn test_filtered_traversal_respects_type_and_direction() {
+        let mut db = GraphDatabase::new_in_memory();
+
+        // 混合类型图:
+        // 0 -KNOWS-> 1 -KNOWS-> 2
+        // 0 -WORKS_AT-> 3 -LOCATED_IN-> 4
+        // 5 -KNOWS-> 0
+        let n: Vec<NodeId> = (0..6)
+            .map(|_| db.create_node(vec!["Node"], Properties::new()))
+            .collect();
+        db.create_rel(n[0], n[1], "KNOWS", Properties::new());
+        db.create_rel(n[1], n[2], "KNOWS", Properties::new());
+        db.create_rel(n[0], n[3], "WORKS_AT", Properties::new());
+        db.create_rel(n[3], n[4], "LOCATED_IN", Properties::new());
+        db.create_rel(n[5], n[0], "KNOWS", Properties::new());
+
+        let all = bfs_filtered(&db, n[0], None, &[], Direction::Outgoing);
+        assert_eq!(all.len(), 5);
+        assert_eq!(all, bfs(&db, n[0], None));
+
+        let knows = bfs_filtered(&db, n[0], None, &["KNOWS"], Direction::Outgoing);
+        assert!(knows.len() < all.len());
+        assert_eq!(knows, vec![n[0], n[1], n[2]]);
+
+        let knows_dfs = dfs_filtered(&db, n[0], None, &["KNOWS"], Direction::Outgoing);
+        assert_eq!(knows_dfs, vec![n[0], n[1], n[2]]);
+
+        // 反向只能沿入边走到 5
+        let incoming = bfs_filtered(&db, n[0], None, &["KNOWS"], Direction::Incoming);
+        assert_eq!(incoming, vec![n[0], n[5]]);
+
+        let both: HashSet<NodeId> = dfs_filtered(&db, n[0], Some(1), &["KNOWS"], Direction::Both)
+            .into_iter()
+            .collect();
+        assert_eq!(both, [n[0], n[1], n[5]].into_iter().collect());
+    }
+
+    #[test]
+    fn test_bfs_tree_assigns_single_parent() {
+        let mut db = GraphDatabase::new_in_memory();
+
+        // 0 -> 1 -> 3, 0 -> 2 -> 3, 3 -> 4；另有一条 0 -> 4 的 OTHER 关系
+        let n: Vec<NodeId> = (0..5)
+            .map(|_| db.create_node(vec!["Node"], Properties::new()))
+            .collect();
+        db.create_rel(n[0], n[1], "MANAGES", Properties::new());
+        db.create_rel(n[0], n[2], "MANAGES", Properties::new());
+        db.create_rel(n[1], n[3], "MANAGES", Properties::new());
+        db.create_rel(n[2], n[3], "MANAGES", Properties::new());
+        db.create_rel(n[3], n[4], "MANAGES", Properties::new());
+        db.create_rel(n[0], n[4], "OTHER", Properties::new());
+
+        let tree = bfs_tree(&db, n[0], &["MANAGES"], Direction::Outgoing);
+
+        // 每个可达节点恰好一个父节点
+        assert_eq!(tree.len(), 4);
+        let children: HashSet<NodeId> = tree.iter().map(|&(_, child)| child).collect();
+        assert_eq!(children, [n[1], n[2], n[3], n[4]].into_iter().collect());
+
+        let parents_of_3: Vec<NodeId> = tree
+            .iter()
+            .filter(|&&(_, child)| child == n[3])
+            .map(|&(parent, _)| parent)
+            .collect();
+        assert_eq!(parents_of_3.len(), 1);
+        assert!(parents_of_3[0] == n[1] || parents_of_3[0] == n[2]);
+
+        // 忽略 OTHER 关系，4 挂在 3 下面
+        assert!(tree.contains(&(n[3], n[4])));
+
+        // 沿入边从 3 向上得到的是反向的树
+        let up = bfs_tree(&db, n[3], &["MANAGES"], Direction::Incoming);
+        let up_children: HashSet<NodeId> = up.iter().map(|&(_, child)| child).collect();
+        assert_eq!(up_children, [n[0], n[1], n[2]].into_iter().collect());
+        assert_eq!(up.len(), 3);
+    }
+
     #[test]
     fn test_reachable_nodes() {
         let db = create_test_graph();
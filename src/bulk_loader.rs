@@ -0,0 +1,238 @@
+//! 并发批量导入
+//!
+//! 面向初始数据导入场景：接收节点流和关系流，使用多个线程分批写入
+//! `ConcurrentGraphDB`。节点通过用户提供的外部 ID 标识，关系通过外部 ID
+//! 引用端点，导入器负责维护 外部 ID -> NodeId 的映射。
+//!
+//! 导入分两个阶段进行：先导入全部节点，再导入全部关系，
+//! 保证关系写入时端点映射已经完整。
+
+use crate::concurrent::ConcurrentGraphDB;
+use crate::storage::{NodeId, RelId, StorageEngine};
+use crate::values::Properties;
+use std::collections::HashMap;
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// 待导入的节点
+#[derive(Debug, Clone)]
+pub struct BulkNode {
+    /// 外部 ID（在一次导入中唯一）
+    pub external_id: String,
+    pub labels: Vec<String>,
+    pub props: Properties,
+}
+
+/// 待导入的关系（端点使用外部 ID）
+#[derive(Debug, Clone)]
+pub struct BulkRel {
+    pub start: String,
+    pub end: String,
+    pub rel_type: String,
+    pub props: Properties,
+}
+
+/// 批量导入配置
+#[derive(Debug, Clone)]
+pub struct BulkLoaderConfig {
+    /// 工作线程数
+    pub threads: usize,
+
+    /// 每批写入的记录数（每批持有一次写锁）
+    pub batch_size: usize,
+}
+
+impl Default for BulkLoaderConfig {
+    fn default() -> Self {
+        Self {
+            threads: 4,
+            batch_size: 1_000,
+        }
+    }
+}
+
+/// 批量导入报告
+#[derive(Debug, Clone, Default)]
+pub struct BulkLoadReport {
+    /// 成功导入的节点数
+    pub nodes_loaded: usize,
+
+    /// 成功导入的关系数
+    pub rels_loaded: usize,
+
+    /// 重复出现的外部 ID 数（映射指向输入中最后出现的节点）
+    pub duplicate_ids: usize,
+
+    /// 端点无法解析而跳过的关系
+    pub skipped_rels: Vec<BulkRel>,
+
+    /// 外部 ID -> NodeId 映射
+    pub id_map: HashMap<String, NodeId>,
+
+    /// 节点阶段耗时
+    pub node_phase: Duration,
+
+    /// 关系阶段耗时
+    pub rel_phase: Duration,
+}
+
+impl BulkLoadReport {
+    /// 节点导入吞吐量（节点/秒）
+    pub fn nodes_per_sec(&self) -> f64 {
+        throughput(self.nodes_loaded, self.node_phase)
+    }
+
+    /// 关系导入吞吐量（关系/秒）
+    pub fn rels_per_sec(&self) -> f64 {
+        throughput(self.rels_loaded, self.rel_phase)
+    }
+
+    /// 总耗时
+    pub fn total_duration(&self) -> Duration {
+        self.node_phase + self.rel_phase
+    }
+}
+
+fn throughput(count: usize, elapsed: Duration) -> f64 {
+    let secs = elapsed.as_secs_f64();
+    if secs > 0.0 {
+        count as f64 / secs
+    } else {
+        count as f64
+    }
+}
+
+/// 并发批量导入器
+pub struct BulkLoader<E: StorageEngine> {
+    db: ConcurrentGraphDB<E>,
+    config: BulkLoaderConfig,
+}
+
+impl<E: StorageEngine> BulkLoader<E> {
+    pub fn new(db: ConcurrentGraphDB<E>) -> Self {
+        Self::with_config(db, BulkLoaderConfig::default())
+    }
+
+    pub fn with_config(db: ConcurrentGraphDB<E>, config: BulkLoaderConfig) -> Self {
+        Self { db, config }
+    }
+
+    /// 导入节点流和关系流
+    ///
+    /// 节点全部写入后才开始写入关系；端点外部 ID 无法解析的关系会被跳过并记录在报告中
+    pub fn load<N, R>(&self, nodes: N, rels: R) -> BulkLoadReport
+    where
+        N: IntoIterator<Item = BulkNode>,
+        R: IntoIterator<Item = BulkRel>,
+    {
+        let mut report = BulkLoadReport::default();
+
+        // 阶段一：节点
+        let started = Instant::now();
+        let node_results = self.run_parallel(nodes, |db, batch: Vec<BulkNode>| {
+            let data: Vec<(Vec<String>, Properties)> = batch
+                .iter()
+                .map(|n| (n.labels.clone(), n.props.clone()))
+                .collect();
+            let ids = db.batch_create_nodes(data);
+            batch
+                .into_iter()
+                .zip(ids)
+                .map(|(n, id)| (n.external_id, id))
+                .collect::<Vec<_>>()
+        });
+        for (external_id, id) in node_results.into_iter().flatten() {
+            report.nodes_loaded += 1;
+            if report.id_map.insert(external_id, id).is_some() {
+                report.duplicate_ids += 1;
+            }
+        }
+        report.node_phase = started.elapsed();
+
+        // 阶段二：关系
+        let started = Instant::now();
+        let id_map = &report.id_map;
+        let rel_results = self.run_parallel(rels, |db, batch: Vec<BulkRel>| {
+            let mut resolved = Vec::with_capacity(batch.len());
+            let mut skipped = Vec::new();
+            for rel in batch {
+                match (id_map.get(&rel.start), id_map.get(&rel.end)) {
+                    (Some(&start), Some(&end)) => {
+                        resolved.push((start, end, rel.rel_type, rel.props))
+                    }
+                    _ => skipped.push(rel),
+                }
+            }
+            let ids: Vec<RelId> = if resolved.is_empty() {
+                Vec::new()
+            } else {
+                db.batch_create_rels(resolved)
+            };
+            (ids.len(), skipped)
+        });
+        for (loaded, skipped) in rel_results {
+            report.rels_loaded += loaded;
+            report.skipped_rels.extend(skipped);
+        }
+        report.rel_phase = started.elapsed();
+
+        report
+    }
+
+    /// 将输入流切分为批次，分发给工作线程处理，按批次在输入中的顺序返回处理结果
+    fn run_parallel<T, O, I, F>(&self, input: I, work: F) -> Vec<O>
+    where
+        T: Send,
+        O: Send,
+        I: IntoIterator<Item = T>,
+        F: Fn(&ConcurrentGraphDB<E>, Vec<T>) -> O + Sync,
+    {
+        let threads = self.config.threads.max(1);
+        let batch_size = self.config.batch_size.max(1);
+
+        // 有界队列：生产者（调用线程）读取输入流，避免一次性把全部数据读入内存
+        let (tx, rx) = mpsc::sync_channel::<(usize, Vec<T>)>(threads * 2);
+        let rx = Arc::new(Mutex::new(rx));
+        let results = Mutex::new(Vec::new());
+
+        std::thread::scope(|scope| {
+            for _ in 0..threads {
+                let rx = Arc::clone(&rx);
+                let work = &work;
+                let results = &results;
+                let db = &self.db;
+                scope.spawn(move || loop {
+                    let (seq, batch) = match rx.lock().unwrap().recv() {
+                        Ok(batch) => batch,
+                        Err(_) => break,
+                    };
+                    let out = work(db, batch);
+                    results.lock().unwrap().push((seq, out));
+                });
+            }
+
+            let mut seq = 0;
+            let mut batch = Vec::with_capacity(batch_size);
+            for item in input {
+                batch.push(item);
+                if batch.len() >= batch_size {
+                    let full = std::mem::replace(&mut batch, Vec::with_capacity(batch_size));
+                    if tx.send((seq, full)).is_err() {
+                        break;
+                    }
+                    seq += 1;
+                }
+            }
+            if !batch.is_empty() {
+                let _ = tx.send((seq, batch));
+            }
+            drop(tx);
+        });
+
+        // 工作线程完成的先后不确定，按批次序号恢复输入顺序，重复外部 ID 的映射才是确定的
+        let mut results = results.into_inner().unwrap();
+        results.sort_unstable_by_key(|(seq, _)| *seq);
+        results.into_iter().map(|(_, out)| out).collect()
+    }
+}
@@ -185,6 +185,7 @@ mod tests {
             end,
             typ: typ.to_string(),
             props: Properties::new(),
+            directed: true,
         }
     }
 
@@ -0,0 +1,77 @@
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// 查询取消令牌
+///
+/// 克隆出的令牌共享同一个取消状态：任一副本调用 [`cancel`](Self::cancel) 后，
+/// 正在执行的 Cypher 查询或图算法会在下一个循环边界处停止。
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 请求取消；可以从其他线程调用，重复调用无副作用
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    /// 已取消时返回 [`Cancelled`]
+    pub fn check(&self) -> Result<(), Cancelled> {
+        if self.is_cancelled() {
+            Err(Cancelled)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// 返回一个在被 drop 时取消本令牌的守卫
+    ///
+    /// 用于把“调用方不再等待结果”（例如 HTTP 连接断开、请求 future 被丢弃）
+    /// 转换为取消；正常完成后调用 [`CancelOnDrop::disarm`]。
+    pub fn cancel_on_drop(&self) -> CancelOnDrop {
+        CancelOnDrop {
+            token: Some(self.clone()),
+        }
+    }
+}
+
+/// 被 drop 时取消令牌的守卫，见 [`CancellationToken::cancel_on_drop`]
+#[derive(Debug)]
+pub struct CancelOnDrop {
+    token: Option<CancellationToken>,
+}
+
+impl CancelOnDrop {
+    /// 解除守卫：之后 drop 不再取消令牌
+    pub fn disarm(mut self) {
+        self.token = None;
+    }
+}
+
+impl Drop for CancelOnDrop {
+    fn drop(&mut self) {
+        if let Some(token) = &self.token {
+            token.cancel();
+        }
+    }
+}
+
+/// 执行因令牌被取消而中止
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cancelled;
+
+impl fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "query cancelled")
+    }
+}
+
+impl std::error::Error for Cancelled {}
@@ -1,7 +1,8 @@
-use crate::graph::db::GraphDatabase;
+use crate::graph::db::{GraphDatabase, GraphError, OptimisticTx};
 use crate::graph::model::{Node, Relationship};
 use crate::storage::{NodeId, RelId, StorageEngine};
-use crate::values::Properties;
+use crate::transactions::{RetryConfig, TransactionResult};
+use crate::values::{Properties, Value};
 use std::sync::{Arc, RwLock};
 
 #[cfg(feature = "caching")]
@@ -97,6 +98,20 @@ impl<E: StorageEngine> ConcurrentGraphDB<E> {
         db.create_node(labels, props)
     }
 
+    /// 按标签和键属性获取或创建节点，查找与创建在同一把写锁内完成
+    ///
+    /// 多个线程对同一个键并发调用时只会创建一个节点；新节点违反约束时返回错误。
+    pub fn get_or_create_node(
+        &self,
+        label: &str,
+        key_prop: &str,
+        key_value: Value,
+        default_props: Properties,
+    ) -> Result<(NodeId, bool), GraphError> {
+        let mut db = self.db.write().unwrap();
+        db.get_or_create_node(label, key_prop, key_value, default_props)
+    }
+
     pub fn create_rel(
         &self,
         start: NodeId,
@@ -108,6 +123,18 @@ impl<E: StorageEngine> ConcurrentGraphDB<E> {
         db.create_rel(start, end, typ, props)
     }
 
+    /// 批量创建节点（一次写锁内完成）
+    pub fn batch_create_nodes(&self, nodes: Vec<(Vec<String>, Properties)>) -> Vec<NodeId> {
+        let mut db = self.db.write().unwrap();
+        db.batch_create_nodes(nodes)
+    }
+
+    /// 批量创建关系（一次写锁内完成）
+    pub fn batch_create_rels(&self, rels: Vec<(NodeId, NodeId, String, Properties)>) -> Vec<RelId> {
+        let mut db = self.db.write().unwrap();
+        db.batch_create_rels(rels)
+    }
+
     pub fn delete_node(&self, id: NodeId) -> bool {
         let mut db = self.db.write().unwrap();
         db.delete_node(id)
@@ -137,16 +164,81 @@ impl<E: StorageEngine> ConcurrentGraphDB<E> {
         db.neighbors_in(node).count()
     }
 
-    /// 获取节点的总度数
+    /// 获取节点的总度数（无向关系只计一次）
     pub fn degree(&self, node: NodeId) -> usize {
         let db = self.db.read().unwrap();
-        db.neighbors_out(node).count() + db.neighbors_in(node).count()
+        db.degree(node)
     }
 
     /// 获取图中节点总数
     pub fn node_count(&self) -> usize {
         let db = self.db.read().unwrap();
-        db.all_stored_nodes().count()
+        db.node_count()
+    }
+
+    /// 获取图中关系总数
+    pub fn rel_count(&self) -> usize {
+        let db = self.db.read().unwrap();
+        db.rel_count()
+    }
+}
+
+impl<E: StorageEngine> ConcurrentGraphDB<E> {
+    // ========== 乐观事务 ==========
+
+    /// 以乐观事务执行 `f`，遇到冲突时按 `config` 退避后重新执行
+    ///
+    /// 与 [`GraphDatabase::with_retry`] 相同，但 `f` 执行期间不持有写锁：每次读写只短暂加锁，
+    /// 其他线程可以在读取和提交之间改动同一节点。提交时在写锁内校验读过的节点版本，
+    /// 有变化则回滚并重试。`f` 可能被执行多次，不应有事务之外的副作用。
+    pub fn with_retry<T>(
+        &self,
+        config: &RetryConfig,
+        mut f: impl FnMut(&mut ConcurrentTx<'_, E>) -> TransactionResult<T>,
+    ) -> TransactionResult<T> {
+        config.run(|| {
+            let tx = self.db.write().unwrap().begin_optimistic()?;
+            let mut tx = ConcurrentTx { db: self, tx };
+            match f(&mut tx) {
+                Ok(value) => self.db.write().unwrap().commit_optimistic(tx.tx).map(|_| value),
+                Err(err) => {
+                    self.db.write().unwrap().rollback_optimistic(tx.tx)?;
+                    Err(err)
+                }
+            }
+        })
+    }
+}
+
+/// [`ConcurrentGraphDB::with_retry`] 中的乐观事务，每次操作只在调用期间持有锁
+pub struct ConcurrentTx<'a, E: StorageEngine> {
+    db: &'a ConcurrentGraphDB<E>,
+    tx: OptimisticTx,
+}
+
+impl<E: StorageEngine> ConcurrentTx<'_, E> {
+    /// 读取节点，结果包含本事务尚未提交的写入
+    pub fn get_node(&mut self, id: NodeId) -> TransactionResult<Option<Node>> {
+        let db = self.db.db.read().unwrap();
+        self.tx.get_node(&db, id)
+    }
+
+    /// 在事务中创建节点
+    pub fn create_node(&mut self, labels: Vec<&str>, props: Properties) -> TransactionResult<NodeId> {
+        let mut db = self.db.db.write().unwrap();
+        self.tx.create_node(&mut db, labels, props)
+    }
+
+    /// 在事务中合并更新节点属性
+    pub fn set_node_props(&mut self, id: NodeId, props: Properties) -> TransactionResult<()> {
+        let mut db = self.db.db.write().unwrap();
+        self.tx.set_node_props(&mut db, id, props)
+    }
+
+    /// 在事务中删除节点
+    pub fn delete_node(&mut self, id: NodeId) -> TransactionResult<bool> {
+        let mut db = self.db.db.write().unwrap();
+        self.tx.delete_node(&mut db, id)
     }
 }
 
@@ -251,4 +343,194 @@ mod tests {
         // 验证节点总数
         assert_eq!(concurrent_db.node_count(), 5);
     }
+
+    #[test]
+    fn test_concurrent_get_or_create_same_key() {
+        let concurrent_db = ConcurrentGraphDB::new(GraphDatabase::new_in_memory());
+
+        // User.name 被默认 schema 索引，Account.email 没有索引，两条查找路径都覆盖
+        for (label, key) in [("User", "name"), ("Account", "email")] {
+            let handles: Vec<_> = (0..16)
+                .map(|i| {
+                    let db_clone = concurrent_db.clone_handle();
+                    std::thread::spawn(move || {
+                        let mut defaults = Properties::new();
+                        defaults.insert("worker".to_string(), Value::Int(i));
+                        db_clone.get_or_create_node(
+                            label,
+                            key,
+                            Value::Text("shared".to_string()),
+                            defaults,
+                        )
+                        .unwrap()
+                    })
+                })
+                .collect();
+
+            let results: Vec<(NodeId, bool)> =
+                handles.into_iter().map(|h| h.join().unwrap()).collect();
+
+            assert_eq!(results.iter().filter(|(_, created)| *created).count(), 1);
+            let id = results[0].0;
+            assert!(results.iter().all(|(other, _)| *other == id));
+
+            let node = concurrent_db.get_node(id).unwrap();
+            assert_eq!(node.get(key), Some(&Value::Text("shared".to_string())));
+            assert!(node.get("worker").is_some());
+        }
+
+        assert_eq!(concurrent_db.node_count(), 2);
+    }
+
+    #[test]
+    fn test_get_or_create_returns_existing_node() {
+        let mut db = GraphDatabase::new_in_memory();
+        let mut props = Properties::new();
+        props.insert("email".to_string(), Value::Text("a@example.com".to_string()));
+        let existing = db.create_node(vec!["Account"], props);
+
+        // 标签不同的同值节点不算匹配
+        let (other, created) = db.get_or_create_node(
+            "Customer",
+            "email",
+            Value::Text("a@example.com".to_string()),
+            Properties::new(),
+        )
+        .unwrap();
+        assert!(created);
+        assert_ne!(other, existing);
+
+        let (id, created) = db.get_or_create_node(
+            "Account",
+            "email",
+            Value::Text("a@example.com".to_string()),
+            Properties::new(),
+        )
+        .unwrap();
+        assert_eq!((id, created), (existing, false));
+    }
+
+    #[test]
+    fn test_get_or_create_finds_key_set_by_update() {
+        let mut db = GraphDatabase::new_in_memory();
+        // User.name 被默认 schema 索引，键属性在创建之后才写入
+        let alice = db.create_node(vec!["User"], Properties::new());
+        let mut props = Properties::new();
+        props.insert("name".to_string(), Value::Text("alice".to_string()));
+        assert!(db.update_node_props(alice, props));
+
+        let (id, created) = db
+            .get_or_create_node("User", "name", Value::Text("alice".to_string()), Properties::new())
+            .unwrap();
+        assert_eq!((id, created), (alice, false));
+        assert_eq!(db.node_count(), 1);
+
+        // 旧键值不再命中
+        let mut props = Properties::new();
+        props.insert("name".to_string(), Value::Text("alicia".to_string()));
+        assert!(db.update_node_props(alice, props));
+        let (_, created) = db
+            .get_or_create_node("User", "name", Value::Text("alice".to_string()), Properties::new())
+            .unwrap();
+        assert!(created);
+    }
+
+    #[test]
+    fn test_get_or_create_validates_constraints() {
+        use crate::constraints::Constraint;
+
+        let mut db = GraphDatabase::new_in_memory();
+        db.constraints.add_constraint(Constraint::uniqueness("Account", "email")).unwrap();
+        let (first, created) = db
+            .get_or_create_node("Account", "id", Value::Int(1), {
+                let mut props = Properties::new();
+                props.insert("email".to_string(), Value::Text("a@example.com".to_string()));
+                props
+            })
+            .unwrap();
+        assert!(created);
+
+        // 新键、重复的 email：违反唯一性约束，不创建节点
+        let mut defaults = Properties::new();
+        defaults.insert("email".to_string(), Value::Text("a@example.com".to_string()));
+        let result = db.get_or_create_node("Account", "id", Value::Int(2), defaults);
+        assert!(matches!(result, Err(GraphError::ConstraintViolation(_))));
+        assert_eq!(db.node_count(), 1);
+        assert!(db.get_node(first).is_some());
+    }
+
+    #[test]
+    fn test_with_retry_retries_after_conflict() {
+        use crate::transactions::TransactionError;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::{Arc, Barrier};
+        use std::time::Duration;
+
+        let concurrent_db = ConcurrentGraphDB::new(GraphDatabase::new_in_memory());
+        let mut props = Properties::new();
+        props.insert("count".to_string(), Value::Int(0));
+        let counter = concurrent_db.create_node(vec!["Counter"], props);
+
+        let increment = move |tx: &mut ConcurrentTx<'_, crate::storage::mem_store::MemStore>| -> TransactionResult<i64> {
+            let current = match tx.get_node(counter)?.and_then(|n| n.get("count").cloned()) {
+                Some(Value::Int(n)) => n,
+                _ => return Err(TransactionError::Other("missing counter".to_string())),
+            };
+            let mut props = Properties::new();
+            props.insert("count".to_string(), Value::Int(current + 1));
+            tx.set_node_props(counter, props)?;
+            Ok(current + 1)
+        };
+
+        let config = RetryConfig::new()
+            .with_max_retries(3)
+            .with_backoff(Duration::from_millis(1), Duration::from_millis(10));
+        // 第一个事务读取后等待第二个事务提交，保证两者争用同一个节点
+        let read_done = Arc::new(Barrier::new(2));
+        let other_committed = Arc::new(Barrier::new(2));
+        let attempts = Arc::new(AtomicUsize::new(0));
+
+        let handle = {
+            let db = concurrent_db.clone_handle();
+            let (read_done, other_committed) = (read_done.clone(), other_committed.clone());
+            std::thread::spawn(move || {
+                read_done.wait();
+                let value = db.with_retry(&RetryConfig::new(), increment).unwrap();
+                other_committed.wait();
+                value
+            })
+        };
+
+        let value = concurrent_db
+            .with_retry(&config, |tx| {
+                let value = increment(tx)?;
+                if attempts.fetch_add(1, Ordering::SeqCst) == 0 {
+                    read_done.wait();
+                    other_committed.wait();
+                }
+                Ok(value)
+            })
+            .unwrap();
+
+        assert_eq!(handle.join().unwrap(), 1);
+        // 第一次提交因冲突失败，重试时读到另一个事务的结果
+        assert_eq!(attempts.load(Ordering::SeqCst), 2);
+        assert_eq!(value, 2);
+        let node = concurrent_db.get_node(counter).unwrap();
+        assert_eq!(node.get("count"), Some(&Value::Int(2)));
+
+        // 其他写入把值改回原样也算冲突；重试次数用尽时返回冲突错误，事务中的写入不生效
+        let err = concurrent_db
+            .with_retry(&RetryConfig::new().with_max_retries(0), |tx| {
+                let value = increment(tx)?;
+                let mut props = Properties::new();
+                props.insert("count".to_string(), Value::Int(2));
+                concurrent_db.db.write().unwrap().update_node_props(counter, props);
+                Ok(value)
+            })
+            .unwrap_err();
+        assert!(matches!(err, TransactionError::VersionConflict { .. }));
+        let node = concurrent_db.get_node(counter).unwrap();
+        assert_eq!(node.get("count"), Some(&Value::Int(2)));
+    }
 }
@@ -1,11 +1,12 @@
 //! 图约束模块
 //!
-//! 支持两种类型的约束：
+//! 支持三种类型的约束：
 //! - 唯一性约束 (Uniqueness Constraint): 确保节点的某个属性值在标签内唯一
 //! - 存在性约束 (Existence Constraint): 确保节点的某个属性必须存在
+//! - 关系基数约束 (Relationship Cardinality Constraint): 限制节点某类型出边或入边的数量
 
 use crate::storage::{NodeId, StorageEngine};
-use crate::values::Value;
+use crate::values::Properties;
 use std::collections::HashMap;
 use std::sync::{RwLock, Arc};
 
@@ -16,6 +17,23 @@ pub enum ConstraintType {
     Uniqueness,
     /// 存在性约束：确保属性必须存在
     Existence,
+    /// 关系基数约束：标签内每个节点指定方向、指定类型的关系数在 `[min, max]` 之间
+    ///
+    /// 上限在创建关系时检查；下限无法在逐条创建时保证，只由 [`ConstraintManager::validate_node`] 检查。
+    RelCardinality {
+        direction: RelDirection,
+        min: usize,
+        max: Option<usize>,
+    },
+}
+
+/// 关系基数约束计数的方向
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RelDirection {
+    /// 以该节点为起点的关系
+    Outgoing,
+    /// 以该节点为终点的关系
+    Incoming,
 }
 
 /// 约束定义
@@ -25,7 +43,7 @@ pub struct Constraint {
     pub constraint_type: ConstraintType,
     /// 标签
     pub label: String,
-    /// 属性名
+    /// 属性名（关系基数约束中为关系类型）
     pub property: String,
 }
 
@@ -48,20 +66,42 @@ impl Constraint {
         }
     }
 
+    /// 创建新的关系基数约束：`label` 节点的 `rel_type` 关系数（按 `direction` 计）在 `[min, max]` 之间
+    pub fn rel_cardinality(
+        label: &str,
+        rel_type: &str,
+        direction: RelDirection,
+        min: usize,
+        max: Option<usize>,
+    ) -> Self {
+        Constraint {
+            constraint_type: ConstraintType::RelCardinality { direction, min, max },
+            label: label.to_string(),
+            property: rel_type.to_string(),
+        }
+    }
+
     /// 获取约束的唯一标识
     pub fn key(&self) -> String {
-        format!(
-            "{}:{}:{}",
-            match self.constraint_type {
-                ConstraintType::Uniqueness => "unique",
-                ConstraintType::Existence => "exists",
-            },
-            self.label,
-            self.property
-        )
+        constraint_key(&self.constraint_type, &self.label, &self.property)
     }
 }
 
+/// 约束标识：同一标签、属性（关系类型）和种类只能有一个约束，基数约束的上下限不参与标识
+fn constraint_key(constraint_type: &ConstraintType, label: &str, property: &str) -> String {
+    format!(
+        "{}:{}:{}",
+        match constraint_type {
+            ConstraintType::Uniqueness => "unique",
+            ConstraintType::Existence => "exists",
+            ConstraintType::RelCardinality { direction: RelDirection::Outgoing, .. } => "cardinality_out",
+            ConstraintType::RelCardinality { direction: RelDirection::Incoming, .. } => "cardinality_in",
+        },
+        label,
+        property
+    )
+}
+
 /// 约束验证结果
 #[derive(Debug, Clone, PartialEq)]
 pub enum ConstraintValidation {
@@ -103,15 +143,7 @@ impl ConstraintManager {
 
     /// 移除约束
     pub fn drop_constraint(&self, label: &str, property: &str, constraint_type: &ConstraintType) -> Result<bool, String> {
-        let key = format!(
-            "{}:{}:{}",
-            match constraint_type {
-                ConstraintType::Uniqueness => "unique",
-                ConstraintType::Existence => "exists",
-            },
-            label,
-            property
-        );
+        let key = constraint_key(constraint_type, label, property);
 
         let mut constraints = self.constraints.write()
             .map_err(|e| format!("Failed to acquire write lock: {}", e))?;
@@ -148,58 +180,151 @@ impl ConstraintManager {
         let node = db.get_node(node_id)
             .ok_or("Node not found")?;
 
+        match self.validate_props(db, Some(node_id), &node.labels, &node.props)? {
+            ConstraintValidation::Valid => {}
+            violated => return Ok(violated),
+        }
+
+        let constraints = self.constraints.read()
+            .map_err(|e| format!("Failed to acquire read lock: {}", e))?;
+        for constraint in constraints.values().filter(|c| node.labels.contains(&c.label)) {
+            if let ConstraintType::RelCardinality { direction, min, max } = &constraint.constraint_type {
+                let count = count_rels(db, node_id, &constraint.property, *direction);
+                if count < *min || max.is_some_and(|max| count > max) {
+                    return Ok(ConstraintValidation::Violated {
+                        message: format!(
+                            "Cardinality constraint violated: node {:?} (label: {}) has {} {} '{}' relationship(s), expected {}",
+                            node_id, constraint.label, count, direction_name(*direction), constraint.property,
+                            describe_bounds(*min, *max)
+                        ),
+                    });
+                }
+            }
+        }
+
+        Ok(ConstraintValidation::Valid)
+    }
+
+    /// 验证新建关系是否会让起点的出边数或终点的入边数超过基数约束上限
+    pub fn validate_new_rel<E: StorageEngine>(
+        &self,
+        db: &crate::graph::db::GraphDatabase<E>,
+        start: NodeId,
+        end: NodeId,
+        rel_type: &str,
+    ) -> Result<ConstraintValidation, String> {
+        self.validate_new_rels(db, &[(start, end, rel_type)])
+    }
+
+    /// 按顺序验证一批尚未写入的关系，批内排在前面的关系计入后续关系的基数
+    pub fn validate_new_rels<E: StorageEngine>(
+        &self,
+        db: &crate::graph::db::GraphDatabase<E>,
+        rels: &[(NodeId, NodeId, &str)],
+    ) -> Result<ConstraintValidation, String> {
+        let constraints = self.constraints.read()
+            .map_err(|e| format!("Failed to acquire read lock: {}", e))?;
+
+        let mut pending: HashMap<(NodeId, RelDirection, &str), usize> = HashMap::new();
+        for &(start, end, rel_type) in rels {
+            for constraint in constraints.values().filter(|c| c.property == rel_type) {
+                let ConstraintType::RelCardinality { direction, min, max: Some(max) } = &constraint.constraint_type else {
+                    continue;
+                };
+                let node_id = match direction {
+                    RelDirection::Outgoing => start,
+                    RelDirection::Incoming => end,
+                };
+                let applies = db
+                    .get_node(node_id)
+                    .is_some_and(|node| node.labels.contains(&constraint.label));
+                if !applies {
+                    continue;
+                }
+
+                let count = count_rels(db, node_id, rel_type, *direction)
+                    + pending.get(&(node_id, *direction, rel_type)).copied().unwrap_or(0);
+                if count >= *max {
+                    return Ok(ConstraintValidation::Violated {
+                        message: format!(
+                            "Cardinality constraint violated: node {:?} (label: {}) already has {} {} '{}' relationship(s), expected {}",
+                            node_id, constraint.label, count, direction_name(*direction), rel_type,
+                            describe_bounds(*min, Some(*max))
+                        ),
+                    });
+                }
+            }
+            *pending.entry((start, RelDirection::Outgoing, rel_type)).or_default() += 1;
+            *pending.entry((end, RelDirection::Incoming, rel_type)).or_default() += 1;
+        }
+
+        Ok(ConstraintValidation::Valid)
+    }
+
+    /// 验证尚未写入的节点是否满足约束，用于在创建前拒绝违反约束的写入
+    pub fn validate_new_node<E: StorageEngine>(
+        &self,
+        db: &crate::graph::db::GraphDatabase<E>,
+        labels: &[String],
+        props: &Properties,
+    ) -> Result<ConstraintValidation, String> {
+        self.validate_props(db, None, labels, props)
+    }
+
+    /// 按标签和属性检查约束；`node_id` 为已存在节点自身的 ID，唯一性检查时跳过
+    fn validate_props<E: StorageEngine>(
+        &self,
+        db: &crate::graph::db::GraphDatabase<E>,
+        node_id: Option<NodeId>,
+        labels: &[String],
+        props: &Properties,
+    ) -> Result<ConstraintValidation, String> {
         let constraints = self.constraints.read()
             .map_err(|e| format!("Failed to acquire read lock: {}", e))?;
 
         // 只检查适用于该节点标签的约束
         let applicable_constraints: Vec<_> = constraints
             .values()
-            .filter(|c| node.has_label(&c.label))
+            .filter(|c| labels.contains(&c.label))
             .collect();
 
+        let describe = |node_id: Option<NodeId>| match node_id {
+            Some(id) => format!("node {:?}", id),
+            None => "new node".to_string(),
+        };
+
         for constraint in applicable_constraints {
             match &constraint.constraint_type {
                 ConstraintType::Existence => {
                     // 检查属性是否存在
-                    if !node.props.contains_key(&constraint.property) {
+                    if !props.contains_key(&constraint.property) {
                         return Ok(ConstraintValidation::Violated {
                             message: format!(
-                                "Existence constraint violated: node {:?} (label: {}) missing required property '{}'",
-                                node_id, constraint.label, constraint.property
+                                "Existence constraint violated: {} (label: {}) missing required property '{}'",
+                                describe(node_id), constraint.label, constraint.property
                             ),
                         });
                     }
                 }
+                // 关系基数与节点属性无关，由 validate_node / validate_new_rel 检查
+                ConstraintType::RelCardinality { .. } => {}
                 ConstraintType::Uniqueness => {
                     // 检查属性值是否唯一
-                    if let Some(value) = node.props.get(&constraint.property) {
+                    if let Some(value) = props.get(&constraint.property) {
                         // 查询具有相同标签和属性值的其他节点
-                        let mut duplicates = Vec::new();
-                        for stored_node in db.all_stored_nodes() {
-                            if stored_node.id == node_id {
-                                continue;
-                            }
-
-                            let other_node = crate::graph::model::Node {
-                                id: stored_node.id,
-                                labels: stored_node.labels.clone(),
-                                props: stored_node.props.clone(),
-                            };
-
-                            if other_node.has_label(&constraint.label) {
-                                if let Some(other_value) = other_node.get(&constraint.property) {
-                                    if other_value == value {
-                                        duplicates.push(stored_node.id);
-                                    }
-                                }
-                            }
-                        }
+                        let duplicates: Vec<NodeId> = db
+                            .all_stored_nodes()
+                            .filter(|other| Some(other.id) != node_id)
+                            .filter(|other| other.labels.contains(&constraint.label))
+                            .filter(|other| other.props.get(&constraint.property) == Some(value))
+                            .map(|other| other.id)
+                            .collect();
 
                         if !duplicates.is_empty() {
                             return Ok(ConstraintValidation::Violated {
                                 message: format!(
-                                    "Uniqueness constraint violated: node {:?} (label: {}) has duplicate value {:?} for property '{}'. Existing nodes: {:?}",
-                                    node_id, constraint.label, value, constraint.property, duplicates
+                                    "Uniqueness constraint violated: {} (label: {}) has duplicate value {:?} for property '{}'. Existing nodes: {:?}",
+                                    describe(node_id), constraint.label, value, constraint.property, duplicates
                                 ),
                             });
                         }
@@ -225,10 +350,38 @@ impl Default for ConstraintManager {
     }
 }
 
+/// 节点在指定方向上某类型关系的数量
+fn count_rels<E: StorageEngine>(
+    db: &crate::graph::db::GraphDatabase<E>,
+    node_id: NodeId,
+    rel_type: &str,
+    direction: RelDirection,
+) -> usize {
+    match direction {
+        RelDirection::Outgoing => db.neighbors_out(node_id).filter(|r| r.typ == rel_type).count(),
+        RelDirection::Incoming => db.neighbors_in(node_id).filter(|r| r.typ == rel_type).count(),
+    }
+}
+
+fn direction_name(direction: RelDirection) -> &'static str {
+    match direction {
+        RelDirection::Outgoing => "outgoing",
+        RelDirection::Incoming => "incoming",
+    }
+}
+
+fn describe_bounds(min: usize, max: Option<usize>) -> String {
+    match max {
+        Some(max) if max == min => format!("exactly {}", min),
+        Some(max) => format!("between {} and {}", min, max),
+        None => format!("at least {}", min),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::values::Properties;
+    use crate::values::Value;
     use crate::storage::mem_store::MemStore;
 
     fn create_test_db() -> crate::graph::db::GraphDatabase<MemStore> {
@@ -394,4 +547,102 @@ mod tests {
             _ => panic!("Expected constraint violation"),
         }
     }
+
+    #[test]
+    fn test_rel_cardinality_rejects_second_manager() {
+        let mut db = create_test_db();
+        db.constraints
+            .add_constraint(Constraint::rel_cardinality("Person", "MANAGER", RelDirection::Outgoing, 1, Some(1)))
+            .unwrap();
+
+        let alice = db.create_node(vec!["Person"], Properties::new());
+        let bob = db.create_node(vec!["Person"], Properties::new());
+        let carol = db.create_node(vec!["Person"], Properties::new());
+
+        // 还没有经理时不满足下限
+        assert!(matches!(
+            db.constraints.validate_node(&db, alice).unwrap(),
+            ConstraintValidation::Violated { .. }
+        ));
+
+        db.try_create_rel(alice, bob, "MANAGER", Properties::new()).unwrap();
+        assert_eq!(db.constraints.validate_node(&db, alice).unwrap(), ConstraintValidation::Valid);
+
+        let err = db
+            .try_create_rel(alice, carol, "MANAGER", Properties::new())
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains(&format!("node {:?}", alice)));
+        assert!(err.contains("'MANAGER'"));
+        assert_eq!(db.neighbors_out(alice).count(), 1);
+
+        // 其他类型的关系和入边不受限制
+        db.try_create_rel(alice, carol, "KNOWS", Properties::new()).unwrap();
+        db.try_create_rel(carol, bob, "MANAGER", Properties::new()).unwrap();
+    }
+
+    #[test]
+    fn test_rel_cardinality_allows_up_to_higher_limit() {
+        let mut db = create_test_db();
+        let limit = |max| Constraint::rel_cardinality("Person", "MANAGER", RelDirection::Outgoing, 0, Some(max));
+        db.constraints.add_constraint(limit(2)).unwrap();
+
+        let alice = db.create_node(vec!["Person"], Properties::new());
+        let managers: Vec<_> = (0..3).map(|_| db.create_node(vec!["Person"], Properties::new())).collect();
+
+        db.try_create_rel(alice, managers[0], "MANAGER", Properties::new()).unwrap();
+        db.try_create_rel(alice, managers[1], "MANAGER", Properties::new()).unwrap();
+        assert!(db.try_create_rel(alice, managers[2], "MANAGER", Properties::new()).is_err());
+
+        // 替换约束后放宽上限；上下限不参与约束标识
+        assert!(db.constraints.add_constraint(limit(3)).is_err());
+        assert!(db
+            .constraints
+            .drop_constraint("Person", "MANAGER", &limit(2).constraint_type)
+            .unwrap());
+        db.constraints.add_constraint(limit(3)).unwrap();
+        db.try_create_rel(alice, managers[2], "MANAGER", Properties::new()).unwrap();
+
+        // 入边方向的约束按终点计数
+        db.constraints
+            .add_constraint(Constraint::rel_cardinality("Person", "MANAGER", RelDirection::Incoming, 0, Some(1)))
+            .unwrap();
+        let dave = db.create_node(vec!["Person"], Properties::new());
+        assert!(db.try_create_rel(dave, managers[0], "MANAGER", Properties::new()).is_err());
+    }
+
+    #[test]
+    fn test_rel_cardinality_covers_batch_undirected_and_cypher_writes() {
+        let mut db = create_test_db();
+        db.constraints
+            .add_constraint(Constraint::rel_cardinality("Person", "MANAGER", RelDirection::Outgoing, 0, Some(1)))
+            .unwrap();
+        let alice = db.create_node(vec!["Person"], Properties::new());
+        let bob = db.create_node(vec!["Person"], Properties::new());
+        let carol = db.create_node(vec!["Person"], Properties::new());
+
+        // 批内的关系互相计数，超限时整批拒绝
+        let batch = vec![
+            (alice, bob, "MANAGER".to_string(), Properties::new()),
+            (alice, carol, "MANAGER".to_string(), Properties::new()),
+        ];
+        assert!(db.try_batch_create_rels(batch).is_err());
+        assert_eq!(db.rel_count(), 0);
+
+        // 无向关系同时计入两个端点的出边
+        db.try_create_undirected_rel(bob, carol, "MANAGER", Properties::new()).unwrap();
+        assert!(db.try_create_rel(carol, alice, "MANAGER", Properties::new()).is_err());
+
+        // Cypher CREATE 走同一检查
+        db.try_create_rel(alice, bob, "MANAGER", Properties::new()).unwrap();
+        let stmt = crate::cypher::parse_cypher("CREATE (a:Person)-[:MANAGER]->(b:Person)").unwrap();
+        assert!(crate::cypher::execute_statement(&mut db, &stmt).is_ok());
+        db.constraints
+            .add_constraint(Constraint::rel_cardinality("Person", "MANAGER", RelDirection::Incoming, 0, Some(0)))
+            .unwrap();
+        assert!(crate::cypher::execute_statement(&mut db, &stmt).is_err());
+        assert_eq!(db.rel_count(), 3);
+        // 被拒绝的 CREATE 不留下已创建的 Person 节点
+        assert_eq!(db.node_count(), 5);
+    }
 }
@@ -27,6 +27,8 @@ pub struct CypherQuery {
 pub struct MatchClause {
     pub pattern: Pattern,
     pub optional: bool,
+    /// `MATCH p = (..)-[..]->(..)` 中绑定的路径变量
+    pub path_var: Option<String>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -66,6 +68,7 @@ pub enum PropertyValue {
     String(String),
     Int(i64),
     Variable(String),
+    Parameter(String), // $name：执行前由 bind_parameters 替换为字面量
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -87,7 +90,8 @@ pub enum Condition {
     Exists(String, String),           // EXISTS(var.prop)
     IsNull(Expression),               // IS NULL
     IsNotNull(Expression),            // IS NOT NULL
-    In(Expression, Vec<Expression>),  // IN [v1, v2, ...]
+    In(Expression, Expression),       // IN [v1, v2, ...] 或 IN var.prop（列表属性）
+    PatternExists(Pattern),           // (n)-[:TYPE]->(:Label) 或 EXISTS { ... }：至少存在一条匹配路径
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -95,6 +99,7 @@ pub enum Expression {
     Property(String, String), // var.prop
     Literal(PropertyValue),
     List(Vec<Expression>),   // 列表字面量 [v1, v2, ...]
+    PathLength(String),      // length(p)：路径变量 p 的关系数
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -133,6 +138,7 @@ pub enum AggFunc {
     Min,
     Max,
     Count,
+    CountDistinct, // COUNT(DISTINCT ...)：去重后的非空值个数
     Collect,      // COLLECT 聚合
     StDev,        // 标准差
     PercentileCont,  // 连续百分位数
@@ -155,14 +161,18 @@ pub struct OrderByItem {
 #[derive(Debug, Clone, PartialEq)]
 pub struct CreateClause {
     pub pattern: Pattern,
+    /// `CREATE ... RETURN p`：返回新建的节点
+    pub return_clause: Option<ReturnClause>,
 }
 
-/// DELETE 语句：MATCH ... DELETE var
+/// DELETE 语句：MATCH ... [DETACH] DELETE var
 #[derive(Debug, Clone, PartialEq)]
 pub struct DeleteStatement {
     pub match_clause: MatchClause,
     pub where_clause: Option<WhereClause>,
     pub variables: Vec<String>, // 要删除的变量名
+    /// `DETACH DELETE`：同时删除节点的关联关系；否则节点仍有关系时报错
+    pub detach: bool,
 }
 
 /// SET 语句：MATCH ... SET var.prop = value
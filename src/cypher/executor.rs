@@ -1,11 +1,16 @@
-use crate::graph::db::GraphDatabase;
-use crate::graph::model::Node;
+use crate::cancellation::{CancellationToken, Cancelled};
+use crate::graph::db::{GraphDatabase, GraphError};
+use crate::graph::model::{Node, Relationship};
 use crate::query::Query;
-use crate::storage::{NodeId, RelId, StorageEngine};
+use crate::query_engine::ColumnType;
+use crate::storage::{NodeFilter, NodeId, RelId, StorageEngine};
 use crate::values::{Properties, Value};
 
 use super::ast::*;
 use regex::Regex;
+use crate::visualization::GraphView;
+use std::collections::{HashMap, HashSet};
+use std::time::Instant;
 
 /// 执行 Cypher 语句，支持：
 /// - 读查询：MATCH / WHERE / RETURN（带 ORDER BY / SKIP / LIMIT）
@@ -23,18 +28,115 @@ pub enum CypherResult {
 pub fn execute_statement<E: StorageEngine>(
     db: &mut GraphDatabase<E>,
     stmt: &CypherStatement,
+) -> Result<CypherResult, String> {
+    execute_statement_with_stats(db, stmt).map(|(result, _)| result)
+}
+
+/// 执行 Cypher 语句并返回执行统计（耗时、检查的行数、是否使用索引）
+pub fn execute_statement_with_stats<E: StorageEngine>(
+    db: &mut GraphDatabase<E>,
+    stmt: &CypherStatement,
+) -> Result<(CypherResult, ExecutionStats), String> {
+    let started = Instant::now();
+    let mut stats = ExecutionStats::default();
+    let result = dispatch_statement(db, stmt, &mut stats)?;
+    stats.execution_time_ms = started.elapsed().as_secs_f64() * 1000.0;
+    Ok((result, stats))
+}
+
+/// 可取消执行的错误
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExecutionError {
+    /// 令牌在执行完成前被取消
+    Cancelled,
+    /// 语句执行失败
+    Failed(String),
+}
+
+impl std::fmt::Display for ExecutionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Cancelled => write!(f, "{}", Cancelled),
+            Self::Failed(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+impl std::error::Error for ExecutionError {}
+
+impl From<Cancelled> for ExecutionError {
+    fn from(_: Cancelled) -> Self {
+        Self::Cancelled
+    }
+}
+
+/// 可取消地执行 Cypher 语句
+///
+/// 读查询在扫描、关系遍历和 WHERE 过滤的循环边界处检查 `cancel`，被取消后尽快返回
+/// [`ExecutionError::Cancelled`]，丢弃已得到的部分结果。写语句不会中途停止（避免只写入一半），
+/// 只在开始前检查一次；一旦开始执行，写入已经生效，即使期间令牌被取消也如实返回执行结果。
+pub fn execute_statement_cancellable<E: StorageEngine>(
+    db: &mut GraphDatabase<E>,
+    stmt: &CypherStatement,
+    cancel: &CancellationToken,
+) -> Result<(CypherResult, ExecutionStats), ExecutionError> {
+    cancel.check()?;
+    let started = Instant::now();
+    let mut stats = ExecutionStats::default();
+    let result = match stmt {
+        CypherStatement::Query(q) => {
+            let result = execute_query_with_stats(db, q, &mut stats, Some(cancel)).map(CypherResult::Nodes);
+            // 取消导致的提前退出优先于其他错误
+            cancel.check()?;
+            result
+        }
+        _ => dispatch_statement(db, stmt, &mut stats),
+    };
+    let result = result.map_err(ExecutionError::Failed)?;
+    stats.execution_time_ms = started.elapsed().as_secs_f64() * 1000.0;
+    Ok((result, stats))
+}
+
+fn dispatch_statement<E: StorageEngine>(
+    db: &mut GraphDatabase<E>,
+    stmt: &CypherStatement,
+    stats: &mut ExecutionStats,
 ) -> Result<CypherResult, String> {
     match stmt {
         CypherStatement::Query(q) => {
-            let nodes = execute_query(db, q)?;
+            let nodes = execute_query_with_stats(db, q, stats, None)?;
             Ok(CypherResult::Nodes(nodes))
         }
         CypherStatement::Create(c) => {
-            let (node_ids, rel_count) = execute_create(db, c)?;
-            Ok(CypherResult::Created {
-                nodes: node_ids,
-                rels: rel_count,
-            })
+            // RETURN 中引用的变量必须在 pattern 中绑定，先检查再写入
+            let bindings = create_bindings(c)?;
+            let (node_ids, rel_count) = write_atomically(db, |db| execute_create(db, c))?;
+            match bindings {
+                Some(CreateReturn::Nodes(positions)) => Ok(CypherResult::Nodes(
+                    positions
+                        .into_iter()
+                        .filter_map(|i| db.get_node(node_ids[i]))
+                        .collect(),
+                )),
+                Some(CreateReturn::Columns(columns)) => {
+                    let props = columns
+                        .into_iter()
+                        .map(|(i, prop, name)| {
+                            let value = db.get_node(node_ids[i]).and_then(|n| n.props.get(&prop).cloned());
+                            (name, value.unwrap_or(Value::Null))
+                        })
+                        .collect();
+                    Ok(CypherResult::Nodes(vec![Node {
+                        id: NodeId::MAX,
+                        labels: vec!["Row".to_string()],
+                        props,
+                    }]))
+                }
+                None => Ok(CypherResult::Created {
+                    nodes: node_ids,
+                    rels: rel_count,
+                }),
+            }
         }
         CypherStatement::Delete(d) => {
             let (nodes_deleted, rels_deleted) = execute_delete(db, d)?;
@@ -49,10 +151,7 @@ pub fn execute_statement<E: StorageEngine>(
                 nodes: nodes_updated,
             })
         }
-        CypherStatement::Merge(m) => {
-            let result = execute_merge(db, m)?;
-            Ok(result)
-        }
+        CypherStatement::Merge(m) => write_atomically(db, |db| execute_merge(db, m)),
         CypherStatement::Foreach(f) => {
             let nodes_updated = execute_foreach(db, f)?;
             Ok(CypherResult::Updated {
@@ -82,6 +181,21 @@ pub fn execute_statement<E: StorageEngine>(
     }
 }
 
+/// 查询执行统计
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ExecutionStats {
+    /// 是否走了 SKIP/LIMIT 下推的流式扫描路径
+    pub limit_pushdown: bool,
+    /// 流式扫描中实际检查过的节点数（仅下推路径统计）
+    pub nodes_scanned: usize,
+    /// 执行耗时（毫秒）
+    pub execution_time_ms: f64,
+    /// 选择起始节点及关系遍历过程中检查过的节点数
+    pub rows_examined: usize,
+    /// 起始节点是否通过属性索引查找
+    pub index_used: bool,
+}
+
 /// 向后兼容：只返回节点的查询入口
 pub fn execute_cypher<E: StorageEngine>(
     db: &GraphDatabase<E>,
@@ -90,20 +204,181 @@ pub fn execute_cypher<E: StorageEngine>(
     execute_query(db, query)
 }
 
+/// 执行查询并返回执行统计
+pub fn execute_cypher_with_stats<E: StorageEngine>(
+    db: &GraphDatabase<E>,
+    query: &CypherQuery,
+) -> Result<(Vec<Node>, ExecutionStats), String> {
+    let started = Instant::now();
+    let mut stats = ExecutionStats::default();
+    let nodes = execute_query_with_stats(db, query, &mut stats, None)?;
+    stats.execution_time_ms = started.elapsed().as_secs_f64() * 1000.0;
+    Ok((nodes, stats))
+}
+
+/// 以迭代器形式逐行返回查询结果
+///
+/// 简单的单节点扫描（`MATCH (n[:Label] {..}) [WHERE ..] RETURN n [SKIP s] [LIMIT l]`）
+/// 按存储顺序惰性求值，调用方可以在扫描结束前开始处理结果；
+/// 其他查询先完整执行，再逐行返回。
+pub fn execute_cypher_iter<'a, E: StorageEngine>(
+    db: &'a GraphDatabase<E>,
+    query: &CypherQuery,
+) -> Result<Box<dyn Iterator<Item = Node> + 'a>, String> {
+    let start = match &query.match_clause {
+        Some(m) if is_simple_scan(query) => m.pattern.start_node.clone(),
+        _ => return execute_query(db, query).map(|nodes| Box::new(nodes.into_iter()) as Box<_>),
+    };
+
+    let mut filter = NodeFilter::new();
+    if let Some(label) = &start.label {
+        filter = filter.with_label(label);
+    }
+    let where_filter = compile_where(query.where_clause.as_ref(), row_variable(query.match_clause.as_ref()))?;
+    let ret = &query.return_clause;
+
+    let rows = db
+        .scan_nodes(filter)
+        .map(|stored| Node {
+            id: stored.id,
+            labels: stored.labels,
+            props: stored.props,
+        })
+        .filter(move |node| start_node_matches(db, node, &start, where_filter.as_ref()))
+        .skip(ret.skip.unwrap_or(0))
+        .take(ret.limit.unwrap_or(usize::MAX));
+    Ok(Box::new(rows))
+}
+
+/// 分批拉取的查询结果；不借用数据库，调用方可以在两批之间释放数据库锁
+///
+/// 简单的单节点扫描（同 [`execute_cypher_iter`]）打开时只记下候选节点 ID，
+/// 每批再读取节点并求值属性和 WHERE 条件，批次之间被删除的节点会被跳过；
+/// 其他查询在打开时完整执行，之后分批返回。
+pub struct CypherBatches {
+    source: BatchSource,
+}
+
+enum BatchSource {
+    Scan {
+        candidates: std::vec::IntoIter<NodeId>,
+        start: NodePattern,
+        where_filter: Option<CompiledWhere>,
+        skip: usize,
+        remaining: usize,
+    },
+    Rows(std::vec::IntoIter<Node>),
+}
+
+impl CypherBatches {
+    pub fn open<E: StorageEngine>(db: &GraphDatabase<E>, query: &CypherQuery) -> Result<Self, String> {
+        let start = match &query.match_clause {
+            Some(m) if is_simple_scan(query) => m.pattern.start_node.clone(),
+            _ => return execute_query(db, query).map(Self::from),
+        };
+
+        let mut filter = NodeFilter::new();
+        if let Some(label) = &start.label {
+            filter = filter.with_label(label);
+        }
+        let where_filter = compile_where(query.where_clause.as_ref(), row_variable(query.match_clause.as_ref()))?;
+        let candidates: Vec<NodeId> = db.scan_nodes(filter).map(|n| n.id).collect();
+        let ret = &query.return_clause;
+        Ok(Self {
+            source: BatchSource::Scan {
+                candidates: candidates.into_iter(),
+                start,
+                where_filter,
+                skip: ret.skip.unwrap_or(0),
+                remaining: ret.limit.unwrap_or(usize::MAX),
+            },
+        })
+    }
+
+    /// 取下一批结果，每批至多检查 `max` 个候选节点（或返回 `max` 行）
+    ///
+    /// 候选节点都不满足条件时返回空批次；结果取完后返回 None
+    pub fn next_batch<E: StorageEngine>(&mut self, db: &GraphDatabase<E>, max: usize) -> Option<Vec<Node>> {
+        match &mut self.source {
+            BatchSource::Rows(rows) => {
+                let batch: Vec<Node> = rows.by_ref().take(max).collect();
+                (!batch.is_empty()).then_some(batch)
+            }
+            BatchSource::Scan { candidates, start, where_filter, skip, remaining } => {
+                let ids: Vec<NodeId> = candidates.by_ref().take(max).collect();
+                if ids.is_empty() || *remaining == 0 {
+                    return None;
+                }
+                let mut batch = Vec::new();
+                for node in db.get_nodes(&ids).into_iter().flatten() {
+                    if *remaining == 0 {
+                        break;
+                    }
+                    if !start_node_matches(db, &node, start, where_filter.as_ref()) {
+                        continue;
+                    }
+                    if *skip > 0 {
+                        *skip -= 1;
+                        continue;
+                    }
+                    *remaining -= 1;
+                    batch.push(node);
+                }
+                Some(batch)
+            }
+        }
+    }
+}
+
+impl From<Vec<Node>> for CypherBatches {
+    fn from(rows: Vec<Node>) -> Self {
+        Self { source: BatchSource::Rows(rows.into_iter()) }
+    }
+}
+
 fn execute_query<E: StorageEngine>(
     db: &GraphDatabase<E>,
     query: &CypherQuery,
 ) -> Result<Vec<Node>, String> {
+    execute_query_with_stats(db, query, &mut ExecutionStats::default(), None)
+}
+
+/// `cancel` 被取消时在下一个循环边界处返回错误（调用方据令牌状态区分取消和其他错误）
+fn execute_query_with_stats<E: StorageEngine>(
+    db: &GraphDatabase<E>,
+    query: &CypherQuery,
+    stats: &mut ExecutionStats,
+    cancel: Option<&CancellationToken>,
+) -> Result<Vec<Node>, String> {
+    let rewritten = apply_path_length(query)?;
+    let query: &CypherQuery = &rewritten;
+    let check_cancel = || cancel.map_or(Ok(()), |token| token.check()).map_err(|e| e.to_string());
+
+    // 正则等在查询开始时编译一次，无效时直接报错
+    let where_filter = compile_where(query.where_clause.as_ref(), row_variable(query.match_clause.as_ref()))?;
+    trace_property_filters(db, query);
+
+    // 引用了关系变量时需要保留每条路径上的关系，走逐路径执行
+    if references_rel_vars(query) {
+        return execute_with_rel_bindings(db, query, where_filter.as_ref(), stats, cancel);
+    }
+
+    // 0. 简单的单节点模式 + LIMIT（无排序/聚合）：流式扫描，够数即停
+    if let Some(nodes) = try_limit_pushdown(db, query, where_filter.as_ref(), stats) {
+        return Ok(nodes);
+    }
+
     // 1. 先用 MATCH 构建基础 Query
-    let mut q = build_match_query(db, &query.match_clause)?;
+    let mut q = build_match_query(db, &query.match_clause, stats, cancel)?;
 
     // 2. 应用 WITH 子句（投影和过滤）
     if let Some(with_clause) = &query.with_clause {
         // WITH 的 WHERE 过滤
-        if let Some(where_clause) = &with_clause.where_clause {
+        if let Some(where_filter) = compile_where(with_clause.where_clause.as_ref(), row_variable(query.match_clause.as_ref()))? {
             let mut filtered_ids = Vec::new();
             for node in q.collect_nodes() {
-                if eval_where_clause(&node, where_clause) {
+                check_cancel()?;
+                if where_filter.matches(db, &node) {
                     filtered_ids.push(node.id);
                 }
             }
@@ -116,10 +391,11 @@ fn execute_query<E: StorageEngine>(
     }
 
     // 3. 应用查询的 WHERE 子句（在内存中过滤）
-    if let Some(where_clause) = &query.where_clause {
+    if let Some(where_filter) = &where_filter {
         let mut filtered_ids = Vec::new();
         for node in q.collect_nodes() {
-            if eval_where_clause(&node, where_clause) {
+            check_cancel()?;
+            if where_filter.matches(db, &node) {
                 filtered_ids.push(node.id);
             }
         }
@@ -129,15 +405,7 @@ fn execute_query<E: StorageEngine>(
     }
 
     // 4. 检查是否有聚合或 GROUP BY
-    let has_aggregation = query.return_clause.items.iter().any(|item| {
-        matches!(item, ReturnItem::Aggregation(_, _, _)
-                 | ReturnItem::AggregationAs(_, _, _, _)
-                 | ReturnItem::AggregationWithParam(_, _, _, _)
-                 | ReturnItem::AggregationWithParamAs(_, _, _, _, _)
-                 | ReturnItem::Count)
-    });
-
-    if has_aggregation || query.return_clause.group_by.is_some() {
+    if is_aggregated(&query.return_clause) {
         // 使用聚合执行路径
         return execute_aggregation_query(db, &q, &query.return_clause);
     }
@@ -159,6 +427,655 @@ fn execute_query<E: StorageEngine>(
     Ok(q.collect_nodes())
 }
 
+/// 把 WHERE 中的 `length(p)` 比较改写为路径上可变长度关系的跳数范围
+///
+/// 遍历只沿满足范围的跳数展开，等价于在遍历过程中按路径长度过滤。`length(p)` 只能出现在
+/// 以 AND 连接的、与整数字面量的比较（`=`、`<`、`<=`、`>`、`>=`）中，且路径中最多有一段
+/// 可变长度关系；范围无法满足时查询结果为空。没有 `length()` 条件时原样返回。
+fn apply_path_length(query: &CypherQuery) -> Result<std::borrow::Cow<'_, CypherQuery>, String> {
+    let Some(where_clause) = query
+        .where_clause
+        .as_ref()
+        .filter(|w| w.conditions.iter().any(mentions_path_length))
+    else {
+        return Ok(std::borrow::Cow::Borrowed(query));
+    };
+    let path_var = query.match_clause.as_ref().and_then(|m| m.path_var.as_deref());
+
+    let mut conjuncts = Vec::new();
+    for cond in &where_clause.conditions {
+        flatten_and(cond, &mut conjuncts);
+    }
+    let (mut lo, mut hi) = (0i64, i64::MAX);
+    let mut remaining = Vec::new();
+    for cond in conjuncts {
+        if !mentions_path_length(cond) {
+            remaining.push(cond.clone());
+            continue;
+        }
+        let (var, min, max) = path_length_bound(cond).ok_or_else(|| {
+            "length() is only supported in AND-combined comparisons with an integer".to_string()
+        })?;
+        if path_var != Some(var) {
+            return Err(format!("Unknown path variable '{}'", var));
+        }
+        lo = lo.max(min);
+        hi = hi.min(max);
+    }
+
+    let mut rewritten = query.clone();
+    let rels = &mut rewritten
+        .match_clause
+        .as_mut()
+        .expect("path variable is bound in MATCH")
+        .pattern
+        .relationships;
+    let fixed = rels.iter().filter(|(rel, _)| rel.var_length.is_none()).count() as i64;
+    let mut var_rels = rels.iter_mut().filter(|(rel, _)| rel.var_length.is_some());
+    let feasible = match (var_rels.next(), var_rels.next()) {
+        (None, _) => lo <= fixed && fixed <= hi,
+        (Some((rel, _)), None) => {
+            let (min, max) = rel.var_length.unwrap_or_default();
+            let min = (min.unwrap_or(1) as i64).max(lo - fixed);
+            let max = match max {
+                Some(max) => Some((max as i64).min(hi.saturating_sub(fixed))),
+                None if hi == i64::MAX => None,
+                None => Some(hi - fixed),
+            };
+            if max.is_none_or(|max| min <= max) {
+                rel.var_length = Some((Some(min as usize), max.map(|max| max as usize)));
+                true
+            } else {
+                false
+            }
+        }
+        _ => {
+            return Err(
+                "length() requires at most one variable-length relationship in the path".to_string(),
+            )
+        }
+    };
+    if !feasible {
+        // 范围为空：保留一个恒假的条件，聚合等后续步骤照常执行
+        remaining.push(Condition::Eq(
+            Expression::Literal(PropertyValue::Int(0)),
+            Expression::Literal(PropertyValue::Int(1)),
+        ));
+    }
+    rewritten.where_clause = (!remaining.is_empty()).then_some(WhereClause { conditions: remaining });
+    Ok(std::borrow::Cow::Owned(rewritten))
+}
+
+fn mentions_path_length(cond: &Condition) -> bool {
+    fn in_expr(expr: &Expression) -> bool {
+        match expr {
+            Expression::PathLength(_) => true,
+            Expression::List(items) => items.iter().any(in_expr),
+            Expression::Property(_, _) | Expression::Literal(_) => false,
+        }
+    }
+    match cond {
+        Condition::Eq(a, b)
+        | Condition::Gt(a, b)
+        | Condition::Lt(a, b)
+        | Condition::Gte(a, b)
+        | Condition::Lte(a, b)
+        | Condition::Ne(a, b)
+        | Condition::In(a, b) => in_expr(a) || in_expr(b),
+        Condition::And(a, b) | Condition::Or(a, b) => mentions_path_length(a) || mentions_path_length(b),
+        Condition::RegexMatch(expr, _) | Condition::IsNull(expr) | Condition::IsNotNull(expr) => in_expr(expr),
+        Condition::Exists(_, _) | Condition::PatternExists(_) => false,
+    }
+}
+
+fn flatten_and<'a>(cond: &'a Condition, out: &mut Vec<&'a Condition>) {
+    match cond {
+        Condition::And(a, b) => {
+            flatten_and(a, out);
+            flatten_and(b, out);
+        }
+        _ => out.push(cond),
+    }
+}
+
+/// `length(p) <op> n`（或 `n <op> length(p)`）对应的路径变量和长度范围 `[min, max]`
+fn path_length_bound(cond: &Condition) -> Option<(&str, i64, i64)> {
+    let (Condition::Eq(lhs, rhs)
+    | Condition::Gt(lhs, rhs)
+    | Condition::Lt(lhs, rhs)
+    | Condition::Gte(lhs, rhs)
+    | Condition::Lte(lhs, rhs)) = cond
+    else {
+        return None;
+    };
+    let (var, n, flipped) = match (lhs, rhs) {
+        (Expression::PathLength(var), Expression::Literal(PropertyValue::Int(n))) => (var, *n, false),
+        (Expression::Literal(PropertyValue::Int(n)), Expression::PathLength(var)) => (var, *n, true),
+        _ => return None,
+    };
+    // 统一成 length(p) 在左侧的形式
+    let range = match (cond, flipped) {
+        (Condition::Eq(..), _) => (n, n),
+        (Condition::Lt(..), false) | (Condition::Gt(..), true) => (i64::MIN, n.saturating_sub(1)),
+        (Condition::Lte(..), false) | (Condition::Gte(..), true) => (i64::MIN, n),
+        (Condition::Gt(..), false) | (Condition::Lt(..), true) => (n.saturating_add(1), i64::MAX),
+        _ => (n, i64::MAX),
+    };
+    Some((var.as_str(), range.0, range.1))
+}
+
+/// 记录查询对起始节点标签属性的等值过滤，供 [`GraphDatabase::suggest_indexes`] 使用
+///
+/// 包括模式中的内联属性以及 WHERE 中以 AND 连接的 `n.prop = 字面量` 和 `n.prop IN [..]`。
+fn trace_property_filters<E: StorageEngine>(db: &GraphDatabase<E>, query: &CypherQuery) {
+    let Some(match_clause) = &query.match_clause else {
+        return;
+    };
+    let start = &match_clause.pattern.start_node;
+    let Some(label) = &start.label else {
+        return;
+    };
+
+    let mut props: Vec<&str> = start.props.iter().map(|(prop, _)| prop.as_str()).collect();
+    if let (Some(where_clause), Some(start_var)) = (&query.where_clause, &start.var) {
+        let mut conds = Vec::new();
+        for cond in &where_clause.conditions {
+            flatten_and(cond, &mut conds);
+        }
+        props.extend(conds.into_iter().filter_map(|cond| match cond {
+            Condition::Eq(Expression::Property(var, prop), Expression::Literal(_))
+            | Condition::Eq(Expression::Literal(_), Expression::Property(var, prop))
+            | Condition::In(Expression::Property(var, prop), Expression::List(_))
+                if var == start_var =>
+            {
+                Some(prop.as_str())
+            }
+            _ => None,
+        }));
+    }
+
+    let mut seen = HashSet::new();
+    for prop in props {
+        if seen.insert(prop) {
+            db.trace_filter(label, prop);
+        }
+    }
+}
+
+/// 查询的 WHERE、RETURN 或 ORDER BY 是否引用了 MATCH 中绑定的关系变量
+fn references_rel_vars(query: &CypherQuery) -> bool {
+    let Some(match_clause) = &query.match_clause else {
+        return false;
+    };
+    let rel_vars: HashSet<&str> = match_clause
+        .pattern
+        .relationships
+        .iter()
+        .filter_map(|(rel, _)| rel.var.as_deref())
+        .collect();
+    if rel_vars.is_empty() {
+        return false;
+    }
+
+    let mut referenced = Vec::new();
+    if let Some(where_clause) = &query.where_clause {
+        for cond in &where_clause.conditions {
+            condition_vars(cond, &mut referenced);
+        }
+    }
+    let ret = &query.return_clause;
+    referenced.extend(ret.items.iter().filter_map(|item| match item {
+        ReturnItem::Variable(var)
+        | ReturnItem::VariableAs(var, _)
+        | ReturnItem::Property(var, _)
+        | ReturnItem::PropertyAs(var, _, _)
+        | ReturnItem::Aggregation(_, var, _)
+        | ReturnItem::AggregationAs(_, var, _, _)
+        | ReturnItem::AggregationWithParam(_, var, _, _)
+        | ReturnItem::AggregationWithParamAs(_, var, _, _, _) => Some(var.as_str()),
+        ReturnItem::Count => None,
+    }));
+    referenced.extend(ret.order_by.iter().flat_map(|o| o.items.iter().map(|i| i.var.as_str())));
+
+    referenced.iter().any(|var| rel_vars.contains(var))
+}
+
+/// 条件中以 `var.prop` 形式读取的变量
+fn condition_vars<'a>(cond: &'a Condition, out: &mut Vec<&'a str>) {
+    let expr_vars = |expr: &'a Expression, out: &mut Vec<&'a str>| {
+        if let Expression::Property(var, _) = expr {
+            out.push(var);
+        }
+    };
+    match cond {
+        Condition::Eq(a, b)
+        | Condition::Gt(a, b)
+        | Condition::Lt(a, b)
+        | Condition::Gte(a, b)
+        | Condition::Lte(a, b)
+        | Condition::Ne(a, b)
+        | Condition::In(a, b) => {
+            expr_vars(a, out);
+            expr_vars(b, out);
+        }
+        Condition::And(a, b) | Condition::Or(a, b) => {
+            condition_vars(a, out);
+            condition_vars(b, out);
+        }
+        Condition::RegexMatch(expr, _) | Condition::IsNull(expr) | Condition::IsNotNull(expr) => {
+            expr_vars(expr, out)
+        }
+        Condition::Exists(var, _) => out.push(var),
+        Condition::PatternExists(_) => {}
+    }
+}
+
+/// 一条匹配路径：`nodes[i]` 与 `nodes[i + 1]` 之间是 `rels[i]`
+struct PathRow {
+    nodes: Vec<Node>,
+    rels: Vec<Relationship>,
+}
+
+impl PathRow {
+    /// 模式中命名的节点变量和关系变量到属性的绑定
+    fn bindings<'a>(&'a self, pattern: &'a Pattern) -> VarProps<'a> {
+        let mut vars = VarProps::new();
+        if let Some(var) = &pattern.start_node.var {
+            vars.insert(var, &self.nodes[0].props);
+        }
+        for (i, (rel, node)) in pattern.relationships.iter().enumerate() {
+            if let Some(var) = &rel.var {
+                vars.insert(var, &self.rels[i].props);
+            }
+            if let Some(var) = &node.var {
+                vars.insert(var, &self.nodes[i + 1].props);
+            }
+        }
+        vars
+    }
+
+    fn last(&self) -> &Node {
+        &self.nodes[self.nodes.len() - 1]
+    }
+
+    /// 模式中命名为 `var` 的节点
+    fn node(&self, pattern: &Pattern, var: &str) -> Option<&Node> {
+        std::iter::once(&pattern.start_node)
+            .chain(pattern.relationships.iter().map(|(_, node)| node))
+            .position(|node| node.var.as_deref() == Some(var))
+            .map(|i| &self.nodes[i])
+    }
+}
+
+/// 执行引用了关系变量的查询（`MATCH (a)-[r:FRIEND]->(b) WHERE r.since > 2018 RETURN r.since`）
+///
+/// 逐条枚举匹配路径，每条路径产生一行，同一条路径中的关系不重复使用；WHERE 和 ORDER BY
+/// 中的 `r.prop` 读取关系属性，命名的节点变量读取对应位置的节点。
+///
+/// RETURN 整个节点变量时每行是该变量绑定的节点（不做修改）；RETURN 属性列（`b.name`、
+/// `r.since AS s`）时每行是一个与聚合结果相同的虚拟行节点，属性只包含按列名存放的各列值，
+/// 两种形式不能混用。只支持单跳关系，不支持 WITH、聚合和返回整个关系变量。
+fn execute_with_rel_bindings<E: StorageEngine>(
+    db: &GraphDatabase<E>,
+    query: &CypherQuery,
+    where_filter: Option<&CompiledWhere>,
+    stats: &mut ExecutionStats,
+    cancel: Option<&CancellationToken>,
+) -> Result<Vec<Node>, String> {
+    let check_cancel = || cancel.map_or(Ok(()), |token| token.check()).map_err(|e| e.to_string());
+    let Some(match_clause) = &query.match_clause else {
+        return Ok(Vec::new());
+    };
+    let pattern = &match_clause.pattern;
+    let ret = &query.return_clause;
+    let rel_vars: HashSet<&str> = pattern
+        .relationships
+        .iter()
+        .filter_map(|(rel, _)| rel.var.as_deref())
+        .collect();
+
+    if pattern.relationships.iter().any(|(rel, _)| rel.var_length.is_some()) {
+        return Err("Relationship variables are only supported on single-hop relationships".to_string());
+    }
+    if query.with_clause.is_some() || is_aggregated(ret) {
+        return Err("WITH and aggregation are not supported with relationship variables".to_string());
+    }
+    if let Some(var) = ret.items.iter().find_map(|item| match item {
+        ReturnItem::Variable(var) | ReturnItem::VariableAs(var, _) if rel_vars.contains(var.as_str()) => Some(var),
+        _ => None,
+    }) {
+        return Err(format!("Cannot return relationship variable '{}'; return its properties instead", var));
+    }
+    let returned_vars: Vec<&str> = ret
+        .items
+        .iter()
+        .filter_map(|item| match item {
+            ReturnItem::Variable(var) | ReturnItem::VariableAs(var, _) => Some(var.as_str()),
+            _ => None,
+        })
+        .collect();
+    let columns: Vec<(&str, &str, String)> = ret
+        .items
+        .iter()
+        .filter_map(|item| match item {
+            ReturnItem::Property(var, prop) => Some((var.as_str(), prop.as_str(), format!("{}.{}", var, prop))),
+            ReturnItem::PropertyAs(var, prop, alias) => Some((var.as_str(), prop.as_str(), alias.clone())),
+            _ => None,
+        })
+        .collect();
+    if !returned_vars.is_empty() && !columns.is_empty() {
+        return Err("Cannot mix node variables and property columns in RETURN with relationship variables".to_string());
+    }
+    if returned_vars.len() > 1 {
+        return Err("Only one node variable can be returned with relationship variables".to_string());
+    }
+
+    // 起始节点：有标签时沿用索引/标签扫描，否则扫描全部节点
+    let start = &pattern.start_node;
+    let candidates: Vec<Node> = if start.label.is_some() {
+        let start_only = MatchClause {
+            pattern: Pattern {
+                start_node: start.clone(),
+                relationships: Vec::new(),
+            },
+            optional: false,
+            path_var: None,
+        };
+        build_match_query(db, &Some(start_only), stats, cancel)?.collect_nodes()
+    } else {
+        stats.rows_examined += db.node_count();
+        db.all_stored_nodes()
+            .map(|n| Node { id: n.id, labels: n.labels, props: n.props })
+            .collect()
+    };
+    let mut rows: Vec<PathRow> = candidates
+        .into_iter()
+        .filter(|node| node_pattern_matches(node, start))
+        .map(|node| PathRow { nodes: vec![node], rels: Vec::new() })
+        .collect();
+
+    for (rel_pattern, node_pattern) in &pattern.relationships {
+        let mut next = Vec::new();
+        for row in rows {
+            check_cancel()?;
+            let from = row.last().id;
+            for rel in step_rels(db, from, rel_pattern) {
+                if row.rels.iter().any(|r| r.id == rel.id) {
+                    continue;
+                }
+                let other = if rel.start == from { rel.end } else { rel.start };
+                let Some(node) = db.get_node(other).filter(|n| node_pattern_matches(n, node_pattern)) else {
+                    continue;
+                };
+                let mut nodes = row.nodes.clone();
+                nodes.push(node);
+                let mut rels = row.rels.clone();
+                rels.push(rel);
+                next.push(PathRow { nodes, rels });
+            }
+        }
+        rows = next;
+        stats.rows_examined += rows.len();
+    }
+
+    if let Some(where_filter) = where_filter {
+        let mut kept = Vec::with_capacity(rows.len());
+        for row in rows {
+            check_cancel()?;
+            if where_filter.matches_bound(db, row.last(), &row.bindings(pattern)) {
+                kept.push(row);
+            }
+        }
+        rows = kept;
+    }
+
+    if let Some(order) = &ret.order_by {
+        let mut keyed: Vec<(Vec<Option<Value>>, PathRow)> = rows
+            .into_iter()
+            .map(|row| {
+                let vars = row.bindings(pattern);
+                let keys = order
+                    .items
+                    .iter()
+                    .map(|item| {
+                        vars.get(item.var.as_str())
+                            .copied()
+                            .unwrap_or(&row.last().props)
+                            .get(&item.prop)
+                            .cloned()
+                    })
+                    .collect();
+                (keys, row)
+            })
+            .collect();
+        keyed.sort_by(|(a, _), (b, _)| {
+            order
+                .items
+                .iter()
+                .zip(a.iter().zip(b))
+                .map(|(item, (x, y))| compare_sort_values(x, y, item.ascending))
+                .find(|ord| ord.is_ne())
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        rows = keyed.into_iter().map(|(_, row)| row).collect();
+    }
+
+    let skip = ret.skip.unwrap_or(0);
+    let limit = ret.limit.unwrap_or(usize::MAX);
+    Ok(rows
+        .into_iter()
+        .skip(skip)
+        .take(limit)
+        .map(|row| {
+            if columns.is_empty() {
+                let node = returned_vars.first().and_then(|var| row.node(pattern, var));
+                return node.unwrap_or(row.last()).clone();
+            }
+            let vars = row.bindings(pattern);
+            let props = columns
+                .iter()
+                .map(|(var, prop, name)| {
+                    let value = vars.get(var).and_then(|props| props.get(*prop)).cloned();
+                    (name.clone(), value.unwrap_or(Value::Null))
+                })
+                .collect();
+            Node {
+                id: NodeId::MAX,
+                labels: vec!["Row".to_string()],
+                props,
+            }
+        })
+        .collect())
+}
+
+/// 排序键比较：与 `Query::order_by` 一致，只比较同类型的整数、字符串和日期时间，缺失值排在最后
+fn compare_sort_values(a: &Option<Value>, b: &Option<Value>, ascending: bool) -> std::cmp::Ordering {
+    use std::cmp::Ordering;
+    let ord = match (a, b) {
+        (Some(Value::Int(x)), Some(Value::Int(y))) => x.cmp(y),
+        (Some(Value::Text(x)), Some(Value::Text(y))) => x.cmp(y),
+        (Some(Value::DateTime(x)), Some(Value::DateTime(y))) => x.cmp(y),
+        (None, Some(_)) => return Ordering::Greater,
+        (Some(_), None) => return Ordering::Less,
+        _ => Ordering::Equal,
+    };
+    if ascending { ord } else { ord.reverse() }
+}
+
+/// 从 `id` 出发沿一段单跳关系模式能走的关系（无向关系和 `-` 方向下不重复）
+fn step_rels<E: StorageEngine>(db: &GraphDatabase<E>, id: NodeId, rel: &RelPattern) -> Vec<Relationship> {
+    let type_matches = |typ: &str| rel.rel_type.as_deref().is_none_or(|t| t == typ);
+    let mut rels: Vec<Relationship> = Vec::new();
+    if matches!(rel.direction, Direction::Outgoing | Direction::Both) {
+        rels.extend(db.neighbors_out(id).filter(|r| type_matches(&r.typ)));
+    }
+    if matches!(rel.direction, Direction::Incoming | Direction::Both) {
+        rels.extend(db.neighbors_in(id).filter(|r| type_matches(&r.typ)));
+    }
+    let mut seen = HashSet::new();
+    rels.retain(|r| seen.insert(r.id));
+    rels
+}
+
+/// RETURN 是否走聚合执行路径（含聚合函数或 GROUP BY）
+fn is_aggregated(ret: &ReturnClause) -> bool {
+    ret.group_by.is_some()
+        || ret.items.iter().any(|item| {
+            matches!(item, ReturnItem::Aggregation(_, _, _)
+                     | ReturnItem::AggregationAs(_, _, _, _)
+                     | ReturnItem::AggregationWithParam(_, _, _, _)
+                     | ReturnItem::AggregationWithParamAs(_, _, _, _, _)
+                     | ReturnItem::Count)
+        })
+}
+
+/// 查询结果的列名和列类型（与 RETURN 项一一对应）
+///
+/// 变量按 MATCH 中的绑定确定为节点或关系，`count` 等聚合的类型是固定的；
+/// 属性列以及 sum/min/max 等取决于数据的列，按结果中第一个非空值推断，
+/// 没有可用值时为 [`ColumnType::Unknown`]。
+pub fn return_columns(query: &CypherQuery, results: &[Node]) -> Vec<(String, ColumnType)> {
+    let aggregated = is_aggregated(&query.return_clause);
+    let rel_vars: Vec<&str> = query
+        .match_clause
+        .iter()
+        .flat_map(|m| m.pattern.relationships.iter())
+        .filter_map(|(rel, _)| rel.var.as_deref())
+        .collect();
+
+    // 结果中某一列的值：聚合路径按列名存放，普通路径就是匹配到的节点属性
+    let infer = |key: &str, prop: &str| {
+        results
+            .iter()
+            .filter_map(|node| {
+                let value = node.props.get(key);
+                if aggregated { value } else { value.or_else(|| node.props.get(prop)) }
+            })
+            .find(|v| !matches!(v, Value::Null))
+            .map(ColumnType::of_value)
+            .unwrap_or(ColumnType::Unknown)
+    };
+    let variable_type = |var: &str| {
+        if rel_vars.contains(&var) {
+            ColumnType::Relationship
+        } else {
+            ColumnType::Node
+        }
+    };
+    let aggregation_type = |func: &AggFunc, key: &str| match func {
+        AggFunc::Count | AggFunc::CountDistinct => ColumnType::Int,
+        AggFunc::Avg | AggFunc::StDev | AggFunc::PercentileCont => ColumnType::Float,
+        AggFunc::Collect => ColumnType::List,
+        AggFunc::Sum | AggFunc::Min | AggFunc::Max | AggFunc::PercentileDisc => infer(key, ""),
+    };
+
+    query
+        .return_clause
+        .items
+        .iter()
+        .map(|item| match item {
+            ReturnItem::Variable(var) => (var.clone(), variable_type(var)),
+            ReturnItem::VariableAs(var, alias) => (alias.clone(), variable_type(var)),
+            ReturnItem::Property(var, prop) => {
+                let name = format!("{}.{}", var, prop);
+                let typ = infer(&name, prop);
+                (name, typ)
+            }
+            ReturnItem::PropertyAs(_, prop, alias) => (alias.clone(), infer(alias, prop)),
+            ReturnItem::Aggregation(func, var, prop) => {
+                let name = aggregation_name(func, var, prop);
+                let typ = aggregation_type(func, &name);
+                (name, typ)
+            }
+            ReturnItem::AggregationAs(func, _, _, alias)
+            | ReturnItem::AggregationWithParamAs(func, _, _, _, alias) => {
+                (alias.clone(), aggregation_type(func, alias))
+            }
+            ReturnItem::AggregationWithParam(func, var, prop, param) => {
+                let name = format!("{}({}.{}, {})", func_str(func), var, prop, param);
+                let typ = aggregation_type(func, &name);
+                (name, typ)
+            }
+            ReturnItem::Count => ("count(*)".to_string(), ColumnType::Int),
+        })
+        .collect()
+}
+
+/// 是否为可以按存储顺序流式求值的单节点模式
+///
+/// 即 `MATCH (n[:Label] {..}) [WHERE ..] RETURN n [SKIP s] [LIMIT l]`：
+/// 没有关系遍历、WITH、聚合、ORDER BY 和 GROUP BY。
+fn is_simple_scan(query: &CypherQuery) -> bool {
+    let ret = &query.return_clause;
+    let Some(match_clause) = &query.match_clause else {
+        return false;
+    };
+
+    match_clause.pattern.relationships.is_empty()
+        && query.with_clause.is_none()
+        && ret.order_by.is_none()
+        && !is_aggregated(ret)
+}
+
+/// 节点是否满足起始节点模式中的属性条件和 WHERE 子句
+fn start_node_matches<E: StorageEngine>(
+    db: &GraphDatabase<E>,
+    node: &Node,
+    start: &NodePattern,
+    where_filter: Option<&CompiledWhere>,
+) -> bool {
+    let props_match = start.props.iter().all(|(key, expected)| match expected {
+        PropertyValue::String(s) => {
+            matches!(node.props.get(key), Some(Value::Text(v)) if v == s)
+        }
+        PropertyValue::Int(i) => matches!(node.props.get(key), Some(Value::Int(v)) if v == i),
+        // 变量在 WHERE 中处理
+        PropertyValue::Variable(_) | PropertyValue::Parameter(_) => true,
+    });
+    props_match && where_filter.is_none_or(|w| w.matches(db, node))
+}
+
+/// LIMIT/SKIP 下推
+///
+/// 仅适用于 `MATCH (n:Label {..}) [WHERE ..] RETURN n [SKIP s] LIMIT l` 形式：
+/// 没有关系遍历、WITH、聚合和 ORDER BY。此时按存储顺序逐个检查节点，
+/// 收集到 skip + limit 条结果后立即停止扫描。不满足条件时返回 None。
+fn try_limit_pushdown<E: StorageEngine>(
+    db: &GraphDatabase<E>,
+    query: &CypherQuery,
+    where_filter: Option<&CompiledWhere>,
+    stats: &mut ExecutionStats,
+) -> Option<Vec<Node>> {
+    let ret = &query.return_clause;
+    let limit = ret.limit?;
+    let pattern = &query.match_clause.as_ref()?.pattern;
+    let label = pattern.start_node.label.as_ref()?;
+    if !is_simple_scan(query) {
+        return None;
+    }
+
+    stats.limit_pushdown = true;
+    let skip = ret.skip.unwrap_or(0);
+    let mut scanned = 0;
+
+    let nodes = db
+        .scan_nodes(NodeFilter::new().with_label(label))
+        .inspect(|_| scanned += 1)
+        .map(|stored| Node {
+            id: stored.id,
+            labels: stored.labels,
+            props: stored.props,
+        })
+        .filter(|node| start_node_matches(db, node, &pattern.start_node, where_filter))
+        .skip(skip)
+        .take(limit)
+        .collect();
+
+    stats.nodes_scanned = scanned;
+    stats.rows_examined += scanned;
+    Some(nodes)
+}
+
 /// 执行包含聚合函数和 GROUP BY 的查询
 /// 返回包含聚合结果的虚拟节点
 fn execute_aggregation_query<E: StorageEngine>(
@@ -202,12 +1119,7 @@ fn execute_aggregation_query<E: StorageEngine>(
             match item {
                 ReturnItem::Aggregation(func, var, prop) => {
                     let value = compute_aggregation(func, &group_nodes, var, prop)?;
-                    let prop_name = if prop.is_empty() {
-                        format!("{}({})", func_str(func), var)
-                    } else {
-                        format!("{}({}.{})", func_str(func), var, prop)
-                    };
-                    props.insert(prop_name, value);
+                    props.insert(aggregation_name(func, var, prop), value);
                 }
                 ReturnItem::AggregationAs(func, var, prop, alias) => {
                     let value = compute_aggregation(func, &group_nodes, var, prop)?;
@@ -275,7 +1187,7 @@ fn execute_aggregation_query<E: StorageEngine>(
 
         // 创建虚拟节点来承载聚合结果（使用一个特殊的 ID）
         result_nodes.push(Node {
-            id: u64::MAX, // 使用最大值作为虚拟节点的 ID
+            id: NodeId::MAX, // 使用最大值作为虚拟节点的 ID
             labels: vec!["Aggregation".to_string()],
             props,
         });
@@ -304,6 +1216,9 @@ fn execute_aggregation_query<E: StorageEngine>(
                     (Some(Value::Text(a)), Some(Value::Text(b))) => {
                         if item.ascending { a.cmp(b) } else { b.cmp(a) }
                     }
+                    (Some(Value::DateTime(a)), Some(Value::DateTime(b))) => {
+                        if item.ascending { a.cmp(b) } else { b.cmp(a) }
+                    }
                     _ => std::cmp::Ordering::Equal,
                 }
             });
@@ -371,6 +1286,8 @@ fn format_value(val: &Value) -> String {
         Value::Bool(b) => b.to_string(),
         Value::Null => "NULL".to_string(),
         Value::List(_) => "LIST".to_string(),
+        Value::DateTime(ms) => crate::values::format_datetime(*ms),
+        Value::Bytes(bytes) => bytes.iter().map(|b| format!("{:02x}", b)).collect(),
     }
 }
 
@@ -385,19 +1302,21 @@ fn compute_aggregation(
         return Ok(Value::Null);
     }
 
-    // 收集所有属性值
-    let mut values: Vec<Value> = Vec::new();
-    for node in nodes {
-        let prop_to_use = if prop.is_empty() { var } else { prop };
-        if let Some(val) = node.get(prop_to_use) {
-            values.push(val.clone());
-        }
+    // count(n)：统计绑定了变量的行数
+    if prop.is_empty() && matches!(func, AggFunc::Count) {
+        return Ok(Value::Int(nodes.len() as i64));
     }
 
+    let values = collect_non_null(nodes, var, prop);
+
     match func {
         AggFunc::Count => {
             Ok(Value::Int(values.len() as i64))
         }
+        AggFunc::CountDistinct => {
+            let distinct: HashSet<DistinctKey<'_>> = values.iter().map(DistinctKey::from).collect();
+            Ok(Value::Int(distinct.len() as i64))
+        }
         AggFunc::Sum => {
             let mut sum: i64 = 0;
             for val in &values {
@@ -508,6 +1427,60 @@ fn compute_aggregation(
     }
 }
 
+/// `count(DISTINCT ..)` 的去重键
+///
+/// Value 含浮点数，没有实现 Hash；这里浮点数按位比较（统一 -0.0 与 0.0、所有 NaN），
+/// 不同类型的值互不相等（Int(1) 与 Float(1.0) 不同）
+#[derive(PartialEq, Eq, Hash)]
+enum DistinctKey<'a> {
+    Int(i64),
+    Bool(bool),
+    Text(&'a str),
+    Float(u64),
+    Null,
+    List(Vec<DistinctKey<'a>>),
+    DateTime(i64),
+    Bytes(&'a [u8]),
+}
+
+impl<'a> From<&'a Value> for DistinctKey<'a> {
+    fn from(value: &'a Value) -> Self {
+        match value {
+            Value::Int(i) => DistinctKey::Int(*i),
+            Value::Bool(b) => DistinctKey::Bool(*b),
+            Value::Text(s) => DistinctKey::Text(s),
+            Value::Float(f) if *f == 0.0 => DistinctKey::Float(0f64.to_bits()),
+            Value::Float(f) if f.is_nan() => DistinctKey::Float(f64::NAN.to_bits()),
+            Value::Float(f) => DistinctKey::Float(f.to_bits()),
+            Value::Null => DistinctKey::Null,
+            Value::List(items) => DistinctKey::List(items.iter().map(DistinctKey::from).collect()),
+            Value::DateTime(ms) => DistinctKey::DateTime(*ms),
+            Value::Bytes(b) => DistinctKey::Bytes(b),
+        }
+    }
+}
+
+/// 收集聚合的输入值：缺失的属性和 NULL 都不参与聚合
+fn collect_non_null(nodes: &[Node], var: &str, prop: &str) -> Vec<Value> {
+    let prop_to_use = if prop.is_empty() { var } else { prop };
+    nodes
+        .iter()
+        .filter_map(|node| node.get(prop_to_use))
+        .filter(|val| !matches!(val, Value::Null))
+        .cloned()
+        .collect()
+}
+
+/// 聚合结果的列名，例如 `sum(n.age)`、`count(DISTINCT n.city)`
+fn aggregation_name(func: &AggFunc, var: &str, prop: &str) -> String {
+    let distinct = if matches!(func, AggFunc::CountDistinct) { "DISTINCT " } else { "" };
+    if prop.is_empty() {
+        format!("{}({}{})", func_str(func), distinct, var)
+    } else {
+        format!("{}({}{}.{})", func_str(func), distinct, var, prop)
+    }
+}
+
 /// 获取聚合函数的字符串表示
 fn func_str(func: &AggFunc) -> &str {
     match func {
@@ -515,7 +1488,7 @@ fn func_str(func: &AggFunc) -> &str {
         AggFunc::Avg => "avg",
         AggFunc::Min => "min",
         AggFunc::Max => "max",
-        AggFunc::Count => "count",
+        AggFunc::Count | AggFunc::CountDistinct => "count",
         AggFunc::Collect => "collect",
         AggFunc::StDev => "stdev",
         AggFunc::PercentileCont => "percentileCont",
@@ -587,14 +1560,7 @@ fn compute_aggregation_with_param(
     prop: &str,
     param: f64,
 ) -> Result<Value, String> {
-    // 收集所有属性值
-    let mut values: Vec<Value> = Vec::new();
-    for node in nodes {
-        let prop_to_use = if prop.is_empty() { var } else { prop };
-        if let Some(val) = node.get(prop_to_use) {
-            values.push(val.clone());
-        }
-    }
+    let values = collect_non_null(nodes, var, prop);
 
     match func {
         AggFunc::PercentileCont => {
@@ -607,6 +1573,59 @@ fn compute_aggregation_with_param(
     }
 }
 
+/// `CREATE ... RETURN` 的返回内容，节点用其在 pattern 中的位置表示
+enum CreateReturn {
+    /// `RETURN p, q`：返回整个节点（按 RETURN 顺序去重）
+    Nodes(Vec<usize>),
+    /// `RETURN p.name AS name`：返回一行属性列（节点位置、属性名、列名）
+    Columns(Vec<(usize, String, String)>),
+}
+
+/// 解析 `CREATE ... RETURN` 引用的节点
+///
+/// 没有 RETURN 时返回 None；RETURN 引用未绑定的变量、使用聚合或混用节点变量与属性列时报错。
+fn create_bindings(create: &CreateClause) -> Result<Option<CreateReturn>, String> {
+    let Some(return_clause) = &create.return_clause else {
+        return Ok(None);
+    };
+
+    let pattern_vars: Vec<Option<&str>> = std::iter::once(&create.pattern.start_node)
+        .chain(create.pattern.relationships.iter().map(|(_, node)| node))
+        .map(|node| node.var.as_deref())
+        .collect();
+
+    let position = |var: &str| {
+        pattern_vars
+            .iter()
+            .position(|v| *v == Some(var))
+            .ok_or_else(|| format!("Unknown variable in RETURN: {}", var))
+    };
+    let mut positions = Vec::new();
+    let mut columns = Vec::new();
+    for item in &return_clause.items {
+        match item {
+            ReturnItem::Variable(var) | ReturnItem::VariableAs(var, _) => {
+                let position = position(var)?;
+                if !positions.contains(&position) {
+                    positions.push(position);
+                }
+            }
+            ReturnItem::Property(var, prop) => {
+                columns.push((position(var)?, prop.clone(), format!("{}.{}", var, prop)));
+            }
+            ReturnItem::PropertyAs(var, prop, alias) => {
+                columns.push((position(var)?, prop.clone(), alias.clone()));
+            }
+            _ => return Err("Aggregations are not supported in CREATE ... RETURN".to_string()),
+        }
+    }
+    match (positions.is_empty(), columns.is_empty()) {
+        (false, false) => Err("Cannot mix node variables and property columns in CREATE ... RETURN".to_string()),
+        (true, _) => Ok(Some(CreateReturn::Columns(columns))),
+        (false, true) => Ok(Some(CreateReturn::Nodes(positions))),
+    }
+}
+
 fn execute_create<E: StorageEngine>(
     db: &mut GraphDatabase<E>,
     create: &CreateClause,
@@ -614,7 +1633,7 @@ fn execute_create<E: StorageEngine>(
     let pattern = &create.pattern;
 
     // 创建起始节点
-    let start_node = create_node_from_pattern(db, &pattern.start_node);
+    let start_node = create_node_from_pattern(db, &pattern.start_node)?;
     let mut created_nodes = vec![start_node];
     let mut rel_count = 0;
 
@@ -622,20 +1641,23 @@ fn execute_create<E: StorageEngine>(
 
     // 依次处理关系链：-[:REL]->(node)
     for (rel_pat, node_pat) in &pattern.relationships {
-        let next_node = create_node_from_pattern(db, node_pat);
+        let next_node = create_node_from_pattern(db, node_pat)?;
         created_nodes.push(next_node);
 
         // 创建关系
         if let Some(rel_type) = &rel_pat.rel_type {
             match rel_pat.direction {
                 Direction::Outgoing => {
-                    db.create_rel(prev_node, next_node, rel_type, Properties::new());
+                    db.try_create_rel(prev_node, next_node, rel_type, Properties::new())
+                        .map_err(|e| e.to_string())?;
                 }
                 Direction::Incoming => {
-                    db.create_rel(next_node, prev_node, rel_type, Properties::new());
+                    db.try_create_rel(next_node, prev_node, rel_type, Properties::new())
+                        .map_err(|e| e.to_string())?;
                 }
                 Direction::Both => {
-                    return Err("CREATE with undirected relationships not supported".to_string());
+                    db.try_create_undirected_rel(prev_node, next_node, rel_type, Properties::new())
+                        .map_err(|e| format!("CREATE undirected relationship failed: {}", e))?;
                 }
             }
             rel_count += 1;
@@ -652,13 +1674,13 @@ fn execute_delete<E: StorageEngine>(
     delete: &DeleteStatement,
 ) -> Result<(usize, usize), String> {
     // 1. 先用 MATCH 找到要删除的节点
-    let mut q = build_match_query(db, &Some(delete.match_clause.clone()))?;
+    let mut q = build_match_query(db, &Some(delete.match_clause.clone()), &mut ExecutionStats::default(), None)?;
 
     // 2. 应用 WHERE 过滤
-    if let Some(where_clause) = &delete.where_clause {
+    if let Some(where_filter) = compile_where(delete.where_clause.as_ref(), row_variable(Some(&delete.match_clause)))? {
         let mut filtered_ids = Vec::new();
         for node in q.collect_nodes() {
-            if eval_where_clause(&node, where_clause) {
+            if where_filter.matches(db, &node) {
                 filtered_ids.push(node.id);
             }
         }
@@ -668,7 +1690,19 @@ fn execute_delete<E: StorageEngine>(
 
     let nodes_to_delete: Vec<NodeId> = q.collect_nodes().into_iter().map(|n| n.id).collect();
 
-    // 3. 删除节点（delete_node 会自动删除相关的关系）
+    // 3. 普通 DELETE 不允许留下悬空关系：任一节点仍有关系时整条语句不做修改
+    if !delete.detach {
+        for &node_id in &nodes_to_delete {
+            if db.neighbors_out(node_id).next().is_some() || db.neighbors_in(node_id).next().is_some() {
+                return Err(format!(
+                    "Cannot delete node {} because it still has relationships; use DETACH DELETE",
+                    node_id
+                ));
+            }
+        }
+    }
+
+    // 4. 删除节点（delete_node 会一并删除相关的关系）
     let mut nodes_deleted = 0;
     let mut rels_deleted = 0;
 
@@ -691,13 +1725,13 @@ fn execute_set<E: StorageEngine>(
     set: &SetStatement,
 ) -> Result<usize, String> {
     // 1. 先用 MATCH 找到要更新的节点
-    let mut q = build_match_query(db, &Some(set.match_clause.clone()))?;
+    let mut q = build_match_query(db, &Some(set.match_clause.clone()), &mut ExecutionStats::default(), None)?;
 
     // 2. 应用 WHERE 过滤
-    if let Some(where_clause) = &set.where_clause {
+    if let Some(where_filter) = compile_where(set.where_clause.as_ref(), row_variable(Some(&set.match_clause)))? {
         let mut filtered_ids = Vec::new();
         for node in q.collect_nodes() {
-            if eval_where_clause(&node, where_clause) {
+            if where_filter.matches(db, &node) {
                 filtered_ids.push(node.id);
             }
         }
@@ -721,7 +1755,7 @@ fn execute_set<E: StorageEngine>(
                 PropertyValue::Int(i) => {
                     new_props.insert(assignment.prop.clone(), Value::Int(*i));
                 }
-                PropertyValue::Variable(_) => {
+                PropertyValue::Variable(_) | PropertyValue::Parameter(_) => {
                     // 暂不支持变量引用
                     return Err("SET with variable references not yet supported".to_string());
                 }
@@ -729,7 +1763,7 @@ fn execute_set<E: StorageEngine>(
         }
 
         // 使用 update_node_props 更新节点
-        if db.update_node_props(node.id, new_props) {
+        if checked_update_node(db, node.id, new_props)? {
             nodes_updated += 1;
         }
     }
@@ -785,13 +1819,13 @@ fn execute_merge_node<E: StorageEngine>(
                         PropertyValue::Int(i) => {
                             new_props.insert(assignment.prop.clone(), Value::Int(*i));
                         }
-                        PropertyValue::Variable(_) => {
+                        PropertyValue::Variable(_) | PropertyValue::Parameter(_) => {
                             return Err("MERGE ON MATCH with variable references not yet supported".to_string());
                         }
                     }
                 }
 
-                if db.update_node_props(node.id, new_props) {
+                if checked_update_node(db, node.id, new_props)? {
                     nodes_updated += 1;
                 }
             }
@@ -813,7 +1847,7 @@ fn execute_merge_node<E: StorageEngine>(
                 PropertyValue::Int(i) => {
                     props.insert(key.clone(), Value::Int(*i));
                 }
-                PropertyValue::Variable(_) => {
+                PropertyValue::Variable(_) | PropertyValue::Parameter(_) => {
                     return Err("MERGE CREATE with variable references not yet supported".to_string());
                 }
             }
@@ -825,7 +1859,7 @@ fn execute_merge_node<E: StorageEngine>(
             Vec::new()
         };
 
-        let node_id = db.create_node(labels, props);
+        let node_id = db.try_create_node(labels, props).map_err(|e| e.to_string())?;
         let node = db.get_node(node_id).ok_or("Failed to retrieve created node")?;
 
         // 执行 ON CREATE SET
@@ -840,13 +1874,13 @@ fn execute_merge_node<E: StorageEngine>(
                     PropertyValue::Int(i) => {
                         new_props.insert(assignment.prop.clone(), Value::Int(*i));
                     }
-                    PropertyValue::Variable(_) => {
+                    PropertyValue::Variable(_) | PropertyValue::Parameter(_) => {
                         return Err("MERGE ON CREATE with variable references not yet supported".to_string());
                     }
                 }
             }
 
-            db.update_node_props(node_id, new_props);
+            checked_update_node(db, node_id, new_props)?;
         }
 
         // 返回创建的节点
@@ -875,45 +1909,13 @@ fn execute_merge_with_relationships<E: StorageEngine>(
         let mut matched_paths: Vec<(NodeId, NodeId, RelId)> = Vec::new();
 
         for start_node in &start_matches {
-            // 获取该节点的所有出边（或入边/无向，取决于方向）
-            let rels: Vec<RelId> = match rel_pattern.direction {
-                Direction::Outgoing => db.engine.outgoing_rels(start_node.id).map(|r| r.id).collect(),
-                Direction::Incoming => db.engine.incoming_rels(start_node.id).map(|r| r.id).collect(),
-                Direction::Both => {
-                    // 无向：同时获取出边和入边
-                    let mut rels: Vec<RelId> = db.engine.outgoing_rels(start_node.id).map(|r| r.id).collect();
-                    rels.extend(db.engine.incoming_rels(start_node.id).map(|r| r.id));
-                    rels
-                }
-            };
-
-            for rel_id in rels {
-                if let Some(rel) = db.get_rel(rel_id) {
-                    // 检查方向和类型
-                    let direction_match = match rel_pattern.direction {
-                        Direction::Outgoing => rel.start == start_node.id,
-                        Direction::Incoming => rel.end == start_node.id,
-                        Direction::Both => true, // 无向，都匹配
-                    };
-
-                    if !direction_match {
-                        continue;
-                    }
-
-                    // 检查关系类型
-                    if let Some(ref rel_type) = rel_pattern.rel_type {
-                        if rel.typ != *rel_type {
-                            continue;
-                        }
-                    }
-
-                    // 获取目标节点
-                    let target_id = if rel.start == start_node.id { rel.end } else { rel.start };
-                    if let Some(target_node) = db.get_node(target_id) {
-                        // 检查目标节点是否匹配模式
-                        if node_pattern_matches(&target_node, end_node_pattern) {
-                            matched_paths.push((start_node.id, target_node.id, rel_id));
-                        }
+            // 沿模式方向可走的关系：无向关系两端都能走，且只出现一次
+            for rel in step_rels(db, start_node.id, rel_pattern) {
+                let target_id = if rel.start == start_node.id { rel.end } else { rel.start };
+                if let Some(target_node) = db.get_node(target_id) {
+                    // 检查目标节点是否匹配模式
+                    if node_pattern_matches(&target_node, end_node_pattern) {
+                        matched_paths.push((start_node.id, target_node.id, rel.id));
                     }
                 }
             }
@@ -943,13 +1945,13 @@ fn execute_merge_with_relationships<E: StorageEngine>(
                                 PropertyValue::Int(i) => {
                                     new_props.insert(assignment.prop.clone(), Value::Int(*i));
                                 }
-                                PropertyValue::Variable(_) => {
+                                PropertyValue::Variable(_) | PropertyValue::Parameter(_) => {
                                     return Err("MERGE ON MATCH with variable references not yet supported".to_string());
                                 }
                             }
                         }
 
-                        if db.update_rel_props(*rel_id, new_props) {
+                        if checked_update_rel(db, *rel_id, new_props)? {
                             updated += 1;
                         }
                     }
@@ -964,23 +1966,24 @@ fn execute_merge_with_relationships<E: StorageEngine>(
             // 确保起始节点存在
             let start_id = if start_matches.is_empty() {
                 // 创建起始节点
-                create_node_from_pattern(db, &pattern.start_node)
+                create_node_from_pattern(db, &pattern.start_node)?
             } else {
                 start_matches[0].id
             };
 
             // 创建结束节点
-            let end_id = create_node_from_pattern(db, end_node_pattern);
+            let end_id = create_node_from_pattern(db, end_node_pattern)?;
 
             // 创建关系
             let direction = rel_pattern.direction.clone();
             let rel_type = rel_pattern.rel_type.clone().unwrap_or("RELATED".to_string());
 
             let rel_id = match direction {
-                Direction::Outgoing => db.create_rel(start_id, end_id, &rel_type, Properties::new()),
-                Direction::Incoming => db.create_rel(end_id, start_id, &rel_type, Properties::new()),
-                Direction::Both => db.create_rel(start_id, end_id, &rel_type, Properties::new()),
-            };
+                Direction::Outgoing => db.try_create_rel(start_id, end_id, &rel_type, Properties::new()),
+                Direction::Incoming => db.try_create_rel(end_id, start_id, &rel_type, Properties::new()),
+                Direction::Both => db.try_create_undirected_rel(start_id, end_id, &rel_type, Properties::new()),
+            }
+            .map_err(|e| e.to_string())?;
 
             // 执行 ON CREATE SET
             if let Some(assignments) = &merge_stmt.on_create {
@@ -1001,13 +2004,13 @@ fn execute_merge_with_relationships<E: StorageEngine>(
                             PropertyValue::Int(i) => {
                                 new_props.insert(assignment.prop.clone(), Value::Int(*i));
                             }
-                            PropertyValue::Variable(_) => {
+                            PropertyValue::Variable(_) | PropertyValue::Parameter(_) => {
                                 return Err("MERGE ON CREATE with variable references not yet supported".to_string());
                             }
                         }
                     }
 
-                    db.update_rel_props(rel_id, new_props);
+                    checked_update_rel(db, rel_id, new_props)?;
                 }
             }
 
@@ -1041,48 +2044,17 @@ fn execute_merge_multiple_relationships<E: StorageEngine>(
 
         // 遍历每个关系，尝试找到完整路径
         for (rel_pattern, end_node_pattern) in relationships {
-            // 查找从 current_id 出发的匹配关系
-            let rels: Vec<RelId> = match rel_pattern.direction {
-                Direction::Outgoing => db.engine.outgoing_rels(current_id).map(|r| r.id).collect(),
-                Direction::Incoming => db.engine.incoming_rels(current_id).map(|r| r.id).collect(),
-                Direction::Both => {
-                    let mut rels: Vec<RelId> = db.engine.outgoing_rels(current_id).map(|r| r.id).collect();
-                    rels.extend(db.engine.incoming_rels(current_id).map(|r| r.id));
-                    rels
-                }
-            };
-
+            // 查找从 current_id 出发的匹配关系（无向关系与存储方向无关）
             let mut found_match = false;
-            for rel_id in rels {
-                if let Some(rel) = db.get_rel(rel_id) {
-                    // 检查方向
-                    let direction_match = match rel_pattern.direction {
-                        Direction::Outgoing => rel.start == current_id,
-                        Direction::Incoming => rel.end == current_id,
-                        Direction::Both => true,
-                    };
-
-                    if !direction_match {
-                        continue;
-                    }
-
-                    // 检查关系类型
-                    if let Some(ref rel_type) = rel_pattern.rel_type {
-                        if rel.typ != *rel_type {
-                            continue;
-                        }
-                    }
-
-                    // 获取目标节点
-                    let target_id = if rel.start == current_id { rel.end } else { rel.start };
-                    if let Some(target_node) = db.get_node(target_id) {
-                        // 检查目标节点是否匹配
-                        if node_pattern_matches(&target_node, end_node_pattern) {
-                            current_path.push(target_id);
-                            current_id = target_id;
-                            found_match = true;
-                            break;
-                        }
+            for rel in step_rels(db, current_id, rel_pattern) {
+                let target_id = if rel.start == current_id { rel.end } else { rel.start };
+                if let Some(target_node) = db.get_node(target_id) {
+                    // 检查目标节点是否匹配
+                    if node_pattern_matches(&target_node, end_node_pattern) {
+                        current_path.push(target_id);
+                        current_id = target_id;
+                        found_match = true;
+                        break;
                     }
                 }
             }
@@ -1115,12 +2087,12 @@ fn execute_merge_multiple_relationships<E: StorageEngine>(
                                 PropertyValue::Int(i) => {
                                     new_props.insert(assignment.prop.clone(), Value::Int(*i));
                                 }
-                                PropertyValue::Variable(_) => {
+                                PropertyValue::Variable(_) | PropertyValue::Parameter(_) => {
                                     return Err("MERGE ON MATCH with variable references not yet supported".to_string());
                                 }
                             }
                         }
-                        if db.update_node_props(*node_id, new_props) {
+                        if checked_update_node(db, *node_id, new_props)? {
                             nodes_updated += 1;
                         }
                     }
@@ -1144,7 +2116,7 @@ fn execute_merge_multiple_relationships<E: StorageEngine>(
     let start_id = if !start_matches.is_empty() {
         start_matches[0].id
     } else {
-        create_node_from_pattern(db, &pattern.start_node)
+        create_node_from_pattern(db, &pattern.start_node)?
     };
     created_nodes.push(start_id);
 
@@ -1157,7 +2129,7 @@ fn execute_merge_multiple_relationships<E: StorageEngine>(
         let end_id = if !end_matches.is_empty() {
             end_matches[0].id
         } else {
-            create_node_from_pattern(db, end_node_pattern)
+            create_node_from_pattern(db, end_node_pattern)?
         };
         created_nodes.push(end_id);
 
@@ -1166,16 +2138,11 @@ fn execute_merge_multiple_relationships<E: StorageEngine>(
         let rel_type = rel_pattern.rel_type.clone().unwrap_or("RELATED".to_string());
 
         match direction {
-            Direction::Outgoing => {
-                db.create_rel(current_id, end_id, &rel_type, Properties::new());
-            }
-            Direction::Incoming => {
-                db.create_rel(end_id, current_id, &rel_type, Properties::new());
-            }
-            Direction::Both => {
-                db.create_rel(current_id, end_id, &rel_type, Properties::new());
-            }
+            Direction::Outgoing => db.try_create_rel(current_id, end_id, &rel_type, Properties::new()),
+            Direction::Incoming => db.try_create_rel(end_id, current_id, &rel_type, Properties::new()),
+            Direction::Both => db.try_create_undirected_rel(current_id, end_id, &rel_type, Properties::new()),
         }
+        .map_err(|e| e.to_string())?;
         created_rels += 1;
 
         current_id = end_id;
@@ -1194,12 +2161,12 @@ fn execute_merge_multiple_relationships<E: StorageEngine>(
                         PropertyValue::Int(i) => {
                             new_props.insert(assignment.prop.clone(), Value::Int(*i));
                         }
-                        PropertyValue::Variable(_) => {
+                        PropertyValue::Variable(_) | PropertyValue::Parameter(_) => {
                             return Err("MERGE ON CREATE with variable references not yet supported".to_string());
                         }
                     }
                 }
-                db.update_node_props(*node_id, new_props);
+                checked_update_node(db, *node_id, new_props)?;
             }
         }
     }
@@ -1254,7 +2221,7 @@ fn find_matching_nodes_optimized<E: StorageEngine>(
                 first_indexed_prop = Some((key, Value::Int(*i)));
                 break;
             }
-            PropertyValue::Variable(_) => {
+            PropertyValue::Variable(_) | PropertyValue::Parameter(_) => {
                 // 变量引用无法使用索引
             }
         }
@@ -1356,7 +2323,7 @@ fn property_value_equals_value(prop_value: &PropertyValue, value: &Value) -> boo
 fn create_node_from_pattern<E: StorageEngine>(
     db: &mut GraphDatabase<E>,
     node_pat: &NodePattern,
-) -> NodeId {
+) -> Result<NodeId, String> {
     let labels: Vec<&str> = if let Some(ref label) = node_pat.label {
         vec![label.as_str()]
     } else {
@@ -1372,20 +2339,65 @@ fn create_node_from_pattern<E: StorageEngine>(
             PropertyValue::Int(i) => {
                 props.insert(key.clone(), Value::Int(*i));
             }
-            PropertyValue::Variable(_) => {
+            PropertyValue::Variable(_) | PropertyValue::Parameter(_) => {
                 // 变量暂不支持，跳过
             }
         }
     }
 
-    db.create_node(labels, props)
+    db.try_create_node(labels, props).map_err(|e| e.to_string())
+}
+
+/// 原子地执行一条写语句：中途出错时撤销已写入的节点和关系，不留下部分写入
+fn write_atomically<E: StorageEngine, T>(
+    db: &mut GraphDatabase<E>,
+    write: impl FnOnce(&mut GraphDatabase<E>) -> Result<T, String>,
+) -> Result<T, String> {
+    struct Failed(String);
+    impl From<GraphError> for Failed {
+        fn from(err: GraphError) -> Self {
+            Failed(err.to_string())
+        }
+    }
+    db.atomically(|db| write(db).map_err(Failed)).map_err(|Failed(msg)| msg)
+}
+
+/// 更新节点属性，超过属性限制时报错；节点不存在时返回 false
+fn checked_update_node<E: StorageEngine>(
+    db: &mut GraphDatabase<E>,
+    id: NodeId,
+    props: Properties,
+) -> Result<bool, String> {
+    match db.try_update_node_props(id, props) {
+        Ok(()) => Ok(true),
+        Err(GraphError::NotFound) => Ok(false),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// 更新关系属性，超过属性限制时报错；关系不存在时返回 false
+fn checked_update_rel<E: StorageEngine>(
+    db: &mut GraphDatabase<E>,
+    id: RelId,
+    props: Properties,
+) -> Result<bool, String> {
+    match db.try_update_rel_props(id, props) {
+        Ok(()) => Ok(true),
+        Err(GraphError::NotFound) => Ok(false),
+        Err(e) => Err(e.to_string()),
+    }
 }
 
 fn build_match_query<'a, E: StorageEngine>(
     db: &'a GraphDatabase<E>,
     match_clause: &Option<MatchClause>,
+    stats: &mut ExecutionStats,
+    cancel: Option<&CancellationToken>,
 ) -> Result<Query<'a, E>, String> {
     let mut q = Query::new(db);
+    if let Some(token) = cancel {
+        q = q.with_cancellation(token.clone());
+    }
 
     if let Some(match_clause) = match_clause {
         let pattern = &match_clause.pattern;
@@ -1393,31 +2405,41 @@ fn build_match_query<'a, E: StorageEngine>(
         // 处理起始节点
         let start = &pattern.start_node;
         if let Some(label) = &start.label {
-            // 首先按 label 选择节点
-            q = q.from_label(label);
+            // 有索引的属性条件：直接用索引结果作为候选集，避免全量扫描
+            let indexed = start.props.iter().find_map(|(prop_name, prop_val)| {
+                let value = match prop_val {
+                    PropertyValue::String(s) => Value::Text(s.clone()),
+                    PropertyValue::Int(i) => Value::Int(*i),
+                    PropertyValue::Variable(_) | PropertyValue::Parameter(_) => return None,
+                };
+                db.schema
+                    .should_index(label, prop_name)
+                    .then(|| db.index.find(label, prop_name, &value))
+            });
 
-            // 然后应用属性过滤（使用 where_prop_eq）
+            match indexed {
+                Some(ids) => {
+                    stats.index_used = true;
+                    stats.rows_examined += ids.len();
+                    q.current = ids;
+                }
+                None => {
+                    // 按 label 选择节点（需要遍历全部节点）
+                    stats.rows_examined += db.node_count();
+                    q = q.from_label(label);
+                }
+            }
+
+            // 然后应用属性过滤（索引命中的条件重复过滤也不影响结果）
             for (prop_name, prop_val) in &start.props {
                 match prop_val {
                     PropertyValue::String(s) => {
                         q = q.where_prop_eq(prop_name, s);
                     }
                     PropertyValue::Int(i) => {
-                        // 对于整型，需要特殊处理
-                        // 暂时使用 from_label_and_prop_int_eq（如果有索引）
-                        // 否则跳过
-                        use crate::values::Value;
-                        let ids = db.index.find(label, prop_name, &Value::Int(*i));
-                        if !ids.is_empty() {
-                            // 如果索引中有结果，使用索引结果
-                            q = Query::new(db);
-                            q.current = ids;
-                        } else {
-                            // 没有索引，手动过滤
-                            q = q.where_prop_int_eq(prop_name, *i);
-                        }
+                        q = q.where_prop_int_eq(prop_name, *i);
                     }
-                    PropertyValue::Variable(_) => {
+                    PropertyValue::Variable(_) | PropertyValue::Parameter(_) => {
                         // 变量在 WHERE 中处理
                     }
                 }
@@ -1426,111 +2448,359 @@ fn build_match_query<'a, E: StorageEngine>(
 
         // 处理关系遍历
         for (rel, _node) in &pattern.relationships {
-            // 检查是否是可变长度路径
-            if let Some((min_hops, max_hops)) = &rel.var_length {
-                let rel_type = rel.rel_type.as_deref().unwrap_or("");
-
-                match rel.direction {
-                    Direction::Outgoing => {
-                        // 默认最小值为 1
-                        let min = min_hops.unwrap_or(1);
-                        q = q.out_variable_length(rel_type, min, *max_hops);
-                    }
-                    Direction::Incoming => {
-                        let min = min_hops.unwrap_or(1);
-                        q = q.in_variable_length(rel_type, min, *max_hops);
-                    }
-                    Direction::Both => {
-                        let min = min_hops.unwrap_or(1);
-                        q = q.undirected_variable_length(rel_type, min, *max_hops);
-                    }
-                }
-            } else {
-                // 固定长度路径（1 跳）
-                if let Some(rel_type) = &rel.rel_type {
-                    match rel.direction {
-                        Direction::Outgoing => {
-                            q = q.out(rel_type);
-                        }
-                        Direction::Incoming => {
-                            q = q.in_(rel_type);
-                        }
-                        Direction::Both => {
-                            // 使用可变长度路径，范围 1-1
-                            q = q.undirected_variable_length(rel_type, 1, Some(1));
-                        }
-                    }
-                }
+            q = apply_rel_step(q, rel);
+            stats.rows_examined += q.current.len();
+            if q.is_cancelled() {
+                return Err(Cancelled.to_string());
             }
         }
     }
 
+    if q.is_cancelled() {
+        return Err(Cancelled.to_string());
+    }
     Ok(q)
 }
 
-fn eval_where_clause(node: &Node, where_clause: &WhereClause) -> bool {
-    where_clause
-        .conditions
-        .iter()
-        .all(|cond| eval_condition(node, cond))
+/// 沿模式中的一段关系走一步（固定 1 跳或可变长度）
+fn apply_rel_step<'a, E: StorageEngine>(q: Query<'a, E>, rel: &RelPattern) -> Query<'a, E> {
+    // 检查是否是可变长度路径
+    if let Some((min_hops, max_hops)) = &rel.var_length {
+        let rel_type = rel.rel_type.as_deref().unwrap_or("");
+        // 默认最小值为 1
+        let min = min_hops.unwrap_or(1);
+
+        match rel.direction {
+            Direction::Outgoing => q.out_variable_length(rel_type, min, *max_hops),
+            Direction::Incoming => q.in_variable_length(rel_type, min, *max_hops),
+            Direction::Both => q.undirected_variable_length(rel_type, min, *max_hops),
+        }
+    } else if let Some(rel_type) = &rel.rel_type {
+        // 固定长度路径（1 跳）
+        match rel.direction {
+            Direction::Outgoing => q.out(rel_type),
+            Direction::Incoming => q.in_(rel_type),
+            // 使用可变长度路径，范围 1-1
+            Direction::Both => q.undirected_variable_length(rel_type, 1, Some(1)),
+        }
+    } else {
+        q
+    }
+}
+
+/// 执行 MATCH 查询并收集匹配到的子图
+///
+/// 先按正常语义执行查询得到结果节点，再沿模式逐段回溯，只保留能走到结果节点的
+/// 起始节点和中间节点；视图中的边为这些节点之间的全部关系。
+/// 可变长关系只保留两端节点，途经的节点不会出现在视图中。聚合查询没有对应的子图，返回错误。
+pub fn match_subgraph<E: StorageEngine>(
+    db: &GraphDatabase<E>,
+    query: &CypherQuery,
+) -> Result<GraphView, String> {
+    if is_aggregated(&query.return_clause) {
+        return Err("Cannot export the subgraph of an aggregation query".to_string());
+    }
+    let results: HashSet<NodeId> = execute_query(db, query)?.into_iter().map(|n| n.id).collect();
+
+    let Some(match_clause) = &query.match_clause else {
+        let mut node_ids: Vec<NodeId> = results.into_iter().collect();
+        node_ids.sort_unstable();
+        return Ok(db.to_subgraph_view(&node_ids));
+    };
+    let pattern = &match_clause.pattern;
+
+    // 每一段关系之前的节点集合（frontiers[i] 是第 i 段关系的出发点）
+    let start_only = MatchClause {
+        pattern: Pattern {
+            start_node: pattern.start_node.clone(),
+            relationships: Vec::new(),
+        },
+        optional: match_clause.optional,
+        path_var: None,
+    };
+    let mut frontiers = vec![build_match_query(db, &Some(start_only), &mut ExecutionStats::default(), None)?.current];
+    for (rel, _) in pattern.relationships.iter().take(pattern.relationships.len().saturating_sub(1)) {
+        let mut q = Query::new(db);
+        q.current = frontiers.last().cloned().unwrap_or_default();
+        frontiers.push(apply_rel_step(q, rel).current);
+    }
+
+    // 从结果节点往回剪枝：只保留沿下一段关系能到达已保留节点的节点
+    let mut matched = results.clone();
+    let mut kept = results;
+    for (frontier, (rel, _)) in frontiers.iter().zip(&pattern.relationships).rev() {
+        let mut layer = HashSet::new();
+        for &node in frontier {
+            let mut q = Query::new(db);
+            q.current = vec![node];
+            if apply_rel_step(q, rel).current.iter().any(|n| kept.contains(n)) {
+                layer.insert(node);
+            }
+        }
+        matched.extend(layer.iter().copied());
+        kept = layer;
+    }
+
+    let mut node_ids: Vec<NodeId> = matched.into_iter().collect();
+    node_ids.sort_unstable();
+    Ok(db.to_subgraph_view(&node_ids))
+}
+
+/// 预编译的 WHERE 子句
+///
+/// `=~` 的正则在查询开始时编译一次，逐节点求值时直接复用；
+/// 正则无效时编译阶段即返回错误，而不是把每个节点都当作不匹配。
+#[derive(Debug, Clone)]
+struct CompiledWhere {
+    clause: WhereClause,
+    regexes: HashMap<String, Regex>,
+    /// 求值时当前行节点对应的变量（MATCH 模式最后一个节点），模式谓词从该变量出发匹配
+    row_var: Option<String>,
+}
+
+impl CompiledWhere {
+    fn new(clause: &WhereClause, row_var: Option<&str>) -> Result<Self, String> {
+        let mut regexes = HashMap::new();
+        for cond in &clause.conditions {
+            collect_regexes(cond, &mut regexes)?;
+            check_pattern_anchors(cond, row_var)?;
+        }
+        Ok(Self {
+            clause: clause.clone(),
+            regexes,
+            row_var: row_var.map(str::to_string),
+        })
+    }
+
+    fn matches<E: StorageEngine>(&self, db: &GraphDatabase<E>, node: &Node) -> bool {
+        self.matches_bound(db, node, &VarProps::new())
+    }
+
+    /// `vars` 中绑定的变量读取对应的属性，其余变量读取 `node`
+    fn matches_bound<E: StorageEngine>(&self, db: &GraphDatabase<E>, node: &Node, vars: &VarProps) -> bool {
+        self.clause
+            .conditions
+            .iter()
+            .all(|cond| eval_condition(db, node, vars, cond, &self.regexes, self.row_var.as_deref()))
+    }
 }
 
-fn eval_condition(node: &Node, cond: &Condition) -> bool {
+/// WHERE 求值时变量名到属性的绑定（节点变量或关系变量）
+type VarProps<'a> = HashMap<&'a str, &'a Properties>;
+
+/// `row_var` 为求值时当前行节点的变量，通常由 [`row_variable`] 从 MATCH 模式得到
+fn compile_where(clause: Option<&WhereClause>, row_var: Option<&str>) -> Result<Option<CompiledWhere>, String> {
+    clause.map(|clause| CompiledWhere::new(clause, row_var)).transpose()
+}
+
+/// MATCH 结果中每一行对应的节点变量：模式中最后一个节点的变量
+fn row_variable(match_clause: Option<&MatchClause>) -> Option<&str> {
+    let pattern = &match_clause?.pattern;
+    let last = pattern.relationships.last().map_or(&pattern.start_node, |(_, node)| node);
+    last.var.as_deref()
+}
+
+/// 模式谓词只能从当前行节点出发求值，模式中必须有一个节点使用该变量
+fn check_pattern_anchors(cond: &Condition, row_var: Option<&str>) -> Result<(), String> {
+    match cond {
+        Condition::PatternExists(pattern) => match row_var {
+            Some(var) if pattern_anchor(pattern, var).is_some() => Ok(()),
+            Some(var) => Err(format!("Pattern predicate must reference the matched variable '{}'", var)),
+            None => Err("Pattern predicates require a named node variable in MATCH".to_string()),
+        },
+        Condition::And(a, b) | Condition::Or(a, b) => {
+            check_pattern_anchors(a, row_var)?;
+            check_pattern_anchors(b, row_var)
+        }
+        _ => Ok(()),
+    }
+}
+
+fn collect_regexes(cond: &Condition, regexes: &mut HashMap<String, Regex>) -> Result<(), String> {
+    match cond {
+        Condition::RegexMatch(_, pattern) if !regexes.contains_key(pattern) => {
+            let re = Regex::new(pattern)
+                .map_err(|e| format!("Invalid regular expression '{}': {}", pattern, e))?;
+            regexes.insert(pattern.clone(), re);
+        }
+        Condition::And(a, b) | Condition::Or(a, b) => {
+            collect_regexes(a, regexes)?;
+            collect_regexes(b, regexes)?;
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+fn eval_condition<E: StorageEngine>(
+    db: &GraphDatabase<E>,
+    node: &Node,
+    vars: &VarProps,
+    cond: &Condition,
+    regexes: &HashMap<String, Regex>,
+    row_var: Option<&str>,
+) -> bool {
     match cond {
-        Condition::Eq(lhs, rhs) => eval_expr(node, lhs) == eval_expr(node, rhs),
-        Condition::Gt(lhs, rhs) => match (eval_expr(node, lhs), eval_expr(node, rhs)) {
+        Condition::Eq(lhs, rhs) => eval_expr(node, vars, lhs) == eval_expr(node, vars, rhs),
+        Condition::Gt(lhs, rhs) => match (eval_expr(node, vars, lhs), eval_expr(node, vars, rhs)) {
             (Some(Value::Int(a)), Some(Value::Int(b))) => a > b,
+            (Some(Value::DateTime(a)), Some(Value::DateTime(b))) => a > b,
             _ => false,
         },
-        Condition::Lt(lhs, rhs) => match (eval_expr(node, lhs), eval_expr(node, rhs)) {
+        Condition::Lt(lhs, rhs) => match (eval_expr(node, vars, lhs), eval_expr(node, vars, rhs)) {
             (Some(Value::Int(a)), Some(Value::Int(b))) => a < b,
+            (Some(Value::DateTime(a)), Some(Value::DateTime(b))) => a < b,
             _ => false,
         },
-        Condition::Gte(lhs, rhs) => match (eval_expr(node, lhs), eval_expr(node, rhs)) {
+        Condition::Gte(lhs, rhs) => match (eval_expr(node, vars, lhs), eval_expr(node, vars, rhs)) {
             (Some(Value::Int(a)), Some(Value::Int(b))) => a >= b,
+            (Some(Value::DateTime(a)), Some(Value::DateTime(b))) => a >= b,
             _ => false,
         },
-        Condition::Lte(lhs, rhs) => match (eval_expr(node, lhs), eval_expr(node, rhs)) {
+        Condition::Lte(lhs, rhs) => match (eval_expr(node, vars, lhs), eval_expr(node, vars, rhs)) {
             (Some(Value::Int(a)), Some(Value::Int(b))) => a <= b,
+            (Some(Value::DateTime(a)), Some(Value::DateTime(b))) => a <= b,
             _ => false,
         },
-        Condition::Ne(lhs, rhs) => eval_expr(node, lhs) != eval_expr(node, rhs),
-        Condition::And(a, b) => eval_condition(node, a) && eval_condition(node, b),
-        Condition::Or(a, b) => eval_condition(node, a) || eval_condition(node, b),
-        Condition::RegexMatch(expr, pattern) => {
-            if let Some(Value::Text(s)) = eval_expr(node, expr) {
-                match Regex::new(pattern) {
-                    Ok(re) => re.is_match(&s),
-                    Err(_) => false,
-                }
-            } else {
-                false
-            }
+        Condition::Ne(lhs, rhs) => eval_expr(node, vars, lhs) != eval_expr(node, vars, rhs),
+        Condition::And(a, b) => {
+            eval_condition(db, node, vars, a, regexes, row_var)
+                && eval_condition(db, node, vars, b, regexes, row_var)
         }
-        Condition::Exists(_var, prop) => {
+        Condition::Or(a, b) => {
+            eval_condition(db, node, vars, a, regexes, row_var)
+                || eval_condition(db, node, vars, b, regexes, row_var)
+        }
+        Condition::RegexMatch(expr, pattern) => match (eval_expr(node, vars, expr), regexes.get(pattern)) {
+            (Some(Value::Text(s)), Some(re)) => re.is_match(&s),
+            _ => false,
+        },
+        Condition::Exists(var, prop) => {
             // 检查属性是否存在
-            node.props.contains_key(prop)
+            bound_props(node, vars, var).contains_key(prop)
         }
         Condition::IsNull(expr) => {
-            eval_expr(node, expr).is_none()
+            eval_expr(node, vars, expr).is_none()
         }
         Condition::IsNotNull(expr) => {
-            eval_expr(node, expr).is_some()
+            eval_expr(node, vars, expr).is_some()
         }
         Condition::In(expr, list) => {
-            let val = eval_expr(node, expr);
-            if val.is_none() {
+            let Some(val) = eval_expr(node, vars, expr) else {
                 return false;
+            };
+            match list {
+                Expression::List(items) => items
+                    .iter()
+                    .any(|item| eval_expr_for_value(item).as_ref() == Some(&val)),
+                // 右侧为属性时，属性值必须是列表；标量属性不匹配
+                _ => match eval_expr(node, vars, list) {
+                    Some(Value::List(items)) => items.contains(&val),
+                    _ => false,
+                },
             }
-            let val = val.unwrap();
-            for item in list {
-                if eval_expr_for_value(item) == Some(val.clone()) {
-                    return true;
+        }
+        Condition::PatternExists(pattern) => {
+            // 编译时已检查模式中有使用 row_var 的节点
+            row_var.and_then(|var| pattern_anchor(pattern, var)).is_some_and(|anchor| {
+                pattern_exists(db, node, pattern, anchor)
+            })
+        }
+    }
+}
+
+/// 模式中第一个变量为 `var` 的节点的位置（0 为起始节点，i 为第 i 段关系之后的节点）
+fn pattern_anchor(pattern: &Pattern, var: &str) -> Option<usize> {
+    std::iter::once(&pattern.start_node)
+        .chain(pattern.relationships.iter().map(|(_, node)| node))
+        .position(|node| node.var.as_deref() == Some(var))
+}
+
+/// 模式谓词：`node` 作为模式中第 `anchor` 个节点时，是否存在一条匹配 `pattern` 的路径
+///
+/// 从锚点分别向后、向前（方向取反）逐段检查，找到第一条匹配路径即返回 true。
+fn pattern_exists<E: StorageEngine>(
+    db: &GraphDatabase<E>,
+    node: &Node,
+    pattern: &Pattern,
+    anchor: usize,
+) -> bool {
+    let nodes: Vec<&NodePattern> = std::iter::once(&pattern.start_node)
+        .chain(pattern.relationships.iter().map(|(_, node)| node))
+        .collect();
+    let (before, after) = pattern.relationships.split_at(anchor);
+    // 锚点之前的部分倒过来走：第 i 段关系反向后通向第 i 个节点
+    let backward: Vec<(RelPattern, NodePattern)> = before
+        .iter()
+        .enumerate()
+        .rev()
+        .map(|(i, (rel, _))| {
+            let direction = match rel.direction {
+                Direction::Outgoing => Direction::Incoming,
+                Direction::Incoming => Direction::Outgoing,
+                Direction::Both => Direction::Both,
+            };
+            (RelPattern { direction, ..rel.clone() }, nodes[i].clone())
+        })
+        .collect();
+
+    node_pattern_matches(node, nodes[anchor])
+        && pattern_steps_exist(db, node.id, after)
+        && pattern_steps_exist(db, node.id, &backward)
+}
+
+fn pattern_steps_exist<E: StorageEngine>(
+    db: &GraphDatabase<E>,
+    from: NodeId,
+    steps: &[(RelPattern, NodePattern)],
+) -> bool {
+    let Some(((rel, target), rest)) = steps.split_first() else {
+        return true;
+    };
+    let (min, max) = match rel.var_length {
+        Some((min, max)) => (min.unwrap_or(1), max.unwrap_or(usize::MAX)),
+        None => (1, 1),
+    };
+
+    // 逐层扩展；达到最小跳数后已访问过的节点不再重复检查，保证无上限时也能结束
+    let mut visited = HashSet::new();
+    let mut frontier = HashSet::from([from]);
+    let mut depth = 0;
+    while !frontier.is_empty() && depth < max {
+        depth += 1;
+        let mut next = HashSet::new();
+        for id in frontier {
+            for other in step_targets(db, id, rel) {
+                if depth >= min {
+                    if !visited.insert(other) {
+                        continue;
+                    }
+                    let matched = db
+                        .get_node(other)
+                        .is_some_and(|n| node_pattern_matches(&n, target));
+                    if matched && pattern_steps_exist(db, other, rest) {
+                        return true;
+                    }
                 }
+                next.insert(other);
             }
-            false
         }
+        frontier = next;
     }
+    false
+}
+
+/// 沿一段关系模式走一步能到达的节点
+fn step_targets<E: StorageEngine>(db: &GraphDatabase<E>, id: NodeId, rel: &RelPattern) -> Vec<NodeId> {
+    let type_matches = |typ: &str| rel.rel_type.as_deref().is_none_or(|t| t == typ);
+    let mut targets = Vec::new();
+    if matches!(rel.direction, Direction::Outgoing | Direction::Both) {
+        targets.extend(db.neighbors_out(id).filter(|r| type_matches(&r.typ)).map(|r| r.end));
+    }
+    if matches!(rel.direction, Direction::Incoming | Direction::Both) {
+        targets.extend(db.neighbors_in(id).filter(|r| type_matches(&r.typ)).map(|r| r.start));
+    }
+    targets
 }
 
 fn eval_expr_for_value(expr: &Expression) -> Option<Value> {
@@ -1538,23 +2808,30 @@ fn eval_expr_for_value(expr: &Expression) -> Option<Value> {
         Expression::Literal(pv) => match pv {
             PropertyValue::String(s) => Some(Value::Text(s.clone())),
             PropertyValue::Int(i) => Some(Value::Int(*i)),
-            PropertyValue::Variable(_) => None,
+            PropertyValue::Variable(_) | PropertyValue::Parameter(_) => None,
         },
         _ => None,
     }
 }
 
-fn eval_expr(node: &Node, expr: &Expression) -> Option<Value> {
+/// 变量绑定的属性；未绑定的变量读取当前节点
+fn bound_props<'a>(node: &'a Node, vars: &VarProps<'a>, var: &str) -> &'a Properties {
+    vars.get(var).copied().unwrap_or(&node.props)
+}
+
+fn eval_expr(node: &Node, vars: &VarProps, expr: &Expression) -> Option<Value> {
     match expr {
-        Expression::Property(_var, prop) => {
-            node.props.get(prop).cloned()
+        Expression::Property(var, prop) => {
+            bound_props(node, vars, var).get(prop).cloned()
         }
         Expression::Literal(pv) => match pv {
             PropertyValue::String(s) => Some(Value::Text(s.clone())),
             PropertyValue::Int(i) => Some(Value::Int(*i)),
-            PropertyValue::Variable(_) => None,
+            PropertyValue::Variable(_) | PropertyValue::Parameter(_) => None,
         },
         Expression::List(_) => None, // 列表字面量不直接求值为单一值
+        // 执行前已由 apply_path_length 改写为跳数范围，不会在这里求值
+        Expression::PathLength(_) => None,
     }
 }
 
@@ -1607,13 +2884,13 @@ fn execute_foreach<E: StorageEngine>(
                 PropertyValue::Int(i) => {
                     new_props.insert(assignment.prop.clone(), Value::Int(*i));
                 }
-                PropertyValue::Variable(_) => {
+                PropertyValue::Variable(_) | PropertyValue::Parameter(_) => {
                     return Err("FOREACH with variable values not yet supported".to_string());
                 }
             }
 
             // 更新节点属性
-            if db.update_node_props(node_id, new_props) {
+            if checked_update_node(db, node_id, new_props)? {
                 total_updated += 1;
             }
         }
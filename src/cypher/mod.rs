@@ -1,11 +1,17 @@
 pub mod parser;
 pub mod ast;
 pub mod executor;
+pub mod params;
 pub mod streaming;
 
 pub use parser::parse_cypher;
-pub use executor::{execute_cypher, execute_statement, CypherResult};
+pub use executor::{
+    execute_cypher, execute_cypher_iter, execute_cypher_with_stats, execute_statement,
+    execute_statement_cancellable, execute_statement_with_stats, match_subgraph, return_columns,
+    CypherBatches, CypherResult, ExecutionError, ExecutionStats,
+};
 pub use ast::CypherStatement;
+pub use params::bind_parameters;
 pub use streaming::{
     PageResult, QueryCursor, StreamQuery,
     query_paginated,
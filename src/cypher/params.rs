@@ -0,0 +1,142 @@
+use std::collections::HashMap;
+
+use super::ast::*;
+
+/// 把语句中的 `$name` 参数替换为 `params` 中的字面量
+///
+/// 参数可以出现在节点模式的属性、WHERE 表达式、SET / MERGE 赋值以及 FOREACH 的列表中。
+/// 有参数没有提供值时返回错误，多余的参数被忽略。
+pub fn bind_parameters(
+    stmt: &mut CypherStatement,
+    params: &HashMap<String, PropertyValue>,
+) -> Result<(), String> {
+    let mut binder = Binder { params, missing: None };
+    binder.statement(stmt);
+    match binder.missing {
+        Some(name) => Err(format!("Missing parameter ${}", name)),
+        None => Ok(()),
+    }
+}
+
+struct Binder<'a> {
+    params: &'a HashMap<String, PropertyValue>,
+    /// 第一个没有提供值的参数名
+    missing: Option<String>,
+}
+
+impl Binder<'_> {
+    fn value(&mut self, value: &mut PropertyValue) {
+        if let PropertyValue::Parameter(name) = value {
+            match self.params.get(name.as_str()) {
+                Some(bound) => *value = bound.clone(),
+                None => {
+                    self.missing.get_or_insert_with(|| name.clone());
+                }
+            }
+        }
+    }
+
+    fn statement(&mut self, stmt: &mut CypherStatement) {
+        match stmt {
+            CypherStatement::Query(q) => self.query(q),
+            CypherStatement::Create(c) => self.pattern(&mut c.pattern),
+            CypherStatement::Delete(d) => {
+                self.pattern(&mut d.match_clause.pattern);
+                self.where_clause(d.where_clause.as_mut());
+            }
+            CypherStatement::Set(s) => {
+                self.pattern(&mut s.match_clause.pattern);
+                self.where_clause(s.where_clause.as_mut());
+                self.assignments(&mut s.assignments);
+            }
+            CypherStatement::Merge(m) => {
+                self.pattern(&mut m.pattern);
+                for updates in [&mut m.on_create, &mut m.on_match].into_iter().flatten() {
+                    self.assignments(updates);
+                }
+            }
+            CypherStatement::Foreach(f) => {
+                self.expression(&mut f.list_expr);
+                self.assignments(&mut f.updates);
+            }
+            CypherStatement::Call(c) => {
+                self.query(&mut c.inner_query);
+                self.query(&mut c.outer_query);
+            }
+            CypherStatement::Union(u) => {
+                self.query(&mut u.left);
+                self.query(&mut u.right);
+            }
+            CypherStatement::BeginTransaction
+            | CypherStatement::CommitTransaction
+            | CypherStatement::RollbackTransaction => {}
+        }
+    }
+
+    fn query(&mut self, q: &mut CypherQuery) {
+        if let Some(m) = &mut q.match_clause {
+            self.pattern(&mut m.pattern);
+        }
+        if let Some(w) = &mut q.with_clause {
+            self.where_clause(w.where_clause.as_mut());
+        }
+        self.where_clause(q.where_clause.as_mut());
+    }
+
+    fn pattern(&mut self, pattern: &mut Pattern) {
+        self.node(&mut pattern.start_node);
+        for (_, node) in &mut pattern.relationships {
+            self.node(node);
+        }
+    }
+
+    fn node(&mut self, node: &mut NodePattern) {
+        for (_, value) in &mut node.props {
+            self.value(value);
+        }
+    }
+
+    fn where_clause(&mut self, clause: Option<&mut WhereClause>) {
+        for cond in clause.into_iter().flat_map(|w| w.conditions.iter_mut()) {
+            self.condition(cond);
+        }
+    }
+
+    fn condition(&mut self, cond: &mut Condition) {
+        match cond {
+            Condition::Eq(a, b)
+            | Condition::Gt(a, b)
+            | Condition::Lt(a, b)
+            | Condition::Gte(a, b)
+            | Condition::Lte(a, b)
+            | Condition::Ne(a, b)
+            | Condition::In(a, b) => {
+                self.expression(a);
+                self.expression(b);
+            }
+            Condition::And(a, b) | Condition::Or(a, b) => {
+                self.condition(a);
+                self.condition(b);
+            }
+            Condition::RegexMatch(expr, _) | Condition::IsNull(expr) | Condition::IsNotNull(expr) => {
+                self.expression(expr)
+            }
+            Condition::PatternExists(pattern) => self.pattern(pattern),
+            Condition::Exists(_, _) => {}
+        }
+    }
+
+    fn expression(&mut self, expr: &mut Expression) {
+        match expr {
+            Expression::Literal(value) => self.value(value),
+            Expression::List(items) => items.iter_mut().for_each(|item| self.expression(item)),
+            Expression::Property(_, _) | Expression::PathLength(_) => {}
+        }
+    }
+
+    fn assignments(&mut self, assignments: &mut [SetAssignment]) {
+        for assignment in assignments {
+            self.value(&mut assignment.value);
+        }
+    }
+}
@@ -1,7 +1,7 @@
 use nom::{
     branch::alt,
     bytes::complete::{tag, tag_no_case, take_while1},
-    character::complete::{alpha1, alphanumeric1, char, digit1, multispace0},
+    character::complete::{alpha1, alphanumeric1, char, digit1, multispace0, multispace1},
     number::complete::double,
     combinator::{map, opt, peek, recognize},
     multi::{many0, separated_list0, separated_list1},
@@ -51,6 +51,7 @@ fn property_value(input: &str) -> IResult<&str, PropertyValue> {
     alt((
         map(string_literal, PropertyValue::String),
         map(int_literal, PropertyValue::Int),
+        map(preceded(char('$'), identifier), PropertyValue::Parameter),
         map(identifier, PropertyValue::Variable),
     ))(input)
 }
@@ -190,12 +191,14 @@ fn pattern(input: &str) -> IResult<&str, Pattern> {
 fn match_clause(input: &str) -> IResult<&str, MatchClause> {
     let (input, optional) = opt(ws(tag_no_case("OPTIONAL")))(input)?;
     let (input, _) = ws(tag_no_case("MATCH"))(input)?;
+    let (input, path_var) = opt(tuple((ws(identifier), ws(char('=')))))(input)?;
     let (input, pat) = ws(pattern)(input)?;
     Ok((
         input,
         MatchClause {
             pattern: pat,
             optional: optional.is_some(),
+            path_var: path_var.map(|(var, _)| var),
         },
     ))
 }
@@ -203,6 +206,11 @@ fn match_clause(input: &str) -> IResult<&str, MatchClause> {
 // WHERE clause parsing
 fn expression(input: &str) -> IResult<&str, Expression> {
     alt((
+        // length(p)
+        map(
+            tuple((ws(tag_no_case("length")), ws(char('(')), ws(identifier), ws(char(')')))),
+            |(_, _, var, _)| Expression::PathLength(var),
+        ),
         map(
             tuple((ws(identifier), ws(char('.')), ws(identifier))),
             |(var, _, prop)| Expression::Property(var, prop),
@@ -236,6 +244,7 @@ fn parenthesized_condition(input: &str) -> IResult<&str, Condition> {
 // 解析基础条件（不包含 AND/OR）
 fn base_condition(input: &str) -> IResult<&str, Condition> {
     alt((
+        pattern_condition,
         parenthesized_condition,
         exists_condition,
         // 所有二元操作条件
@@ -292,9 +301,12 @@ fn binary_op_condition(input: &str, left: Expression) -> IResult<&str, Condition
 
     // 检查 IN 操作符（需要空格分隔）
     if let Ok((input_rest, _)) = ws(tag_no_case("IN"))(input) {
-        let (input, list_expr) = ws(list_literal)(input_rest)?;
-        match list_expr {
-            Expression::List(items) => return Ok((input, Condition::In(left, items))),
+        // 右侧可以是列表字面量，也可以是列表类型的属性
+        let (input, right) = ws(expression)(input_rest)?;
+        match right {
+            Expression::List(_) | Expression::Property(_, _) => {
+                return Ok((input, Condition::In(left, right)))
+            }
             _ => return Err(nom::Err::Error(nom::error::Error::new(input, nom::error::ErrorKind::Tag))),
         }
     }
@@ -325,6 +337,21 @@ fn exists_condition(input: &str) -> IResult<&str, Condition> {
     Ok((input, Condition::Exists(var, prop)))
 }
 
+// 模式谓词：(n)-[:TYPE]->(:Label) 或 EXISTS { (n)-[:TYPE]->(:Label) }
+// 裸模式至少要包含一段关系，避免与 (a = 1) 这样的分组条件混淆
+fn pattern_condition(input: &str) -> IResult<&str, Condition> {
+    let braced = delimited(
+        pair(ws(tag_no_case("EXISTS")), ws(char('{'))),
+        pattern,
+        ws(char('}')),
+    );
+    let (input, pat) = alt((braced, ws(pattern)))(input)?;
+    if pat.relationships.is_empty() {
+        return Err(nom::Err::Error(nom::error::Error::new(input, nom::error::ErrorKind::Verify)));
+    }
+    Ok((input, Condition::PatternExists(pat)))
+}
+
 // 解析 OR 条件（最低优先级）
 fn or_condition(input: &str) -> IResult<&str, Condition> {
     let (mut input, mut left) = and_condition(input)?;
@@ -416,6 +443,27 @@ fn return_item(input: &str) -> IResult<&str, ReturnItem> {
         map(tag_no_case("COUNT(*)"), |_| ReturnItem::Count),
         // Percentile functions: PERCENTILECONT(n.prop, 0.5)
         percentile_agg,
+        // COUNT(DISTINCT n.prop)
+        map(
+            tuple((
+                tag_no_case("COUNT"),
+                ws(char('(')),
+                tag_no_case("DISTINCT"),
+                multispace1,
+                identifier,
+                opt(preceded(ws(char('.')), ws(identifier))),
+                ws(char(')')),
+                opt(preceded(ws(tag_no_case("AS")), ws(identifier))),
+            )),
+            |(_, _, _, _, var, prop, _, alias)| {
+                let prop_str = prop.unwrap_or_default();
+                if let Some(a) = alias {
+                    ReturnItem::AggregationAs(AggFunc::CountDistinct, var, prop_str, a)
+                } else {
+                    ReturnItem::Aggregation(AggFunc::CountDistinct, var, prop_str)
+                }
+            },
+        ),
         // Aggregation: COUNT(u) or COUNT(n.prop)
         map(
             tuple((
@@ -476,7 +524,7 @@ fn func_str(func: &AggFunc) -> &str {
         AggFunc::Avg => "avg",
         AggFunc::Min => "min",
         AggFunc::Max => "max",
-        AggFunc::Count => "count",
+        AggFunc::Count | AggFunc::CountDistinct => "count",
         AggFunc::Collect => "collect",
         AggFunc::StDev => "stdev",
         AggFunc::PercentileCont => "percentileCont",
@@ -626,12 +674,20 @@ fn with_clause(input: &str) -> IResult<&str, WithClause> {
 fn create_clause(input: &str) -> IResult<&str, CreateClause> {
     let (input, _) = ws(tag_no_case("CREATE"))(input)?;
     let (input, pat) = ws(pattern)(input)?;
-    Ok((input, CreateClause { pattern: pat }))
+    let (input, return_c) = opt(return_clause)(input)?;
+    Ok((
+        input,
+        CreateClause {
+            pattern: pat,
+            return_clause: return_c,
+        },
+    ))
 }
 
 fn delete_statement(input: &str) -> IResult<&str, DeleteStatement> {
     let (input, match_c) = match_clause(input)?;
     let (input, where_c) = opt(where_clause)(input)?;
+    let (input, detach) = opt(ws(tag_no_case("DETACH")))(input)?;
     let (input, _) = ws(tag_no_case("DELETE"))(input)?;
     let (input, vars) = separated_list1(ws(char(',')), ws(identifier))(input)?;
 
@@ -641,6 +697,7 @@ fn delete_statement(input: &str) -> IResult<&str, DeleteStatement> {
             match_clause: match_c,
             where_clause: where_c,
             variables: vars,
+            detach: detach.is_some(),
         },
     ))
 }
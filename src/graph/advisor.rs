@@ -0,0 +1,84 @@
+//! 索引建议
+//!
+//! Cypher 读查询执行时记录按“标签 + 属性”做的等值过滤；[`GraphDatabase::suggest_indexes`]
+//! 把这些记录与属性值直方图结合，估计为每个未建索引的属性建立索引能少检查多少节点。
+//!
+//! [`GraphDatabase::suggest_indexes`]: crate::graph::db::GraphDatabase::suggest_indexes
+
+use std::collections::VecDeque;
+
+/// 默认保留的最近过滤记录条数
+pub const DEFAULT_TRACE_CAPACITY: usize = 1024;
+
+/// 一次查询中对某个标签属性的等值过滤
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FilterTrace {
+    pub label: String,
+    pub property: String,
+}
+
+/// 最近查询过滤记录（环形缓冲，超出容量时丢弃最旧的记录）
+#[derive(Debug)]
+pub struct QueryTracer {
+    capacity: usize,
+    entries: VecDeque<FilterTrace>,
+}
+
+impl QueryTracer {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: VecDeque::with_capacity(capacity.min(DEFAULT_TRACE_CAPACITY)),
+        }
+    }
+
+    pub fn record(&mut self, label: &str, property: &str) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.entries.len() == self.capacity {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(FilterTrace {
+            label: label.to_string(),
+            property: property.to_string(),
+        });
+    }
+
+    /// 修改容量，超出新容量的最旧记录被丢弃
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        while self.entries.len() > capacity {
+            self.entries.pop_front();
+        }
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item = &FilterTrace> {
+        self.entries.iter()
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
+impl Default for QueryTracer {
+    fn default() -> Self {
+        Self::new(DEFAULT_TRACE_CAPACITY)
+    }
+}
+
+/// 一条索引建议
+#[derive(Debug, Clone, PartialEq)]
+pub struct IndexSuggestion {
+    pub label: String,
+    pub property: String,
+    /// 最近查询中按该属性等值过滤的次数
+    pub query_count: usize,
+    /// 带该标签的节点数（未建索引时每次过滤都要逐个检查）
+    pub label_nodes: usize,
+    /// 一次等值过滤平均命中的节点比例，按属性值直方图估计为 Σ(c_v / n)²
+    pub selectivity: f64,
+    /// 估计收益：建索引后这些查询总共少检查的节点数
+    pub estimated_benefit: f64,
+}
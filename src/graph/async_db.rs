@@ -3,6 +3,7 @@
 //! 提供异步 API 的图数据库包装器
 
 use crate::graph::model::{Node, Relationship};
+use crate::query_stream::{QueryStream, StreamQueryBuilder};
 use crate::storage::{AsyncStorage, NodeId, RelId, StorageEngine};
 use crate::values::Properties;
 
@@ -135,12 +136,27 @@ impl<E: StorageEngine + Send + 'static> AsyncGraphDB<E> {
                 end: sr.end,
                 typ: sr.typ,
                 props: sr.props,
+                directed: sr.directed,
             }))
         })
         .await
         .map_err(|e| AsyncError::TaskJoin(e.to_string()))?
     }
 
+    // ========== 异步流式查询 ==========
+
+    /// 以流的形式返回带有指定标签的全部节点（按 ID 升序）
+    ///
+    /// 节点在后台按批次读取并经有界通道发送，消费者处理较慢时读取会暂停（背压），
+    /// 调用方用 `StreamExt::next` 或 `QueryStream::collect_nodes` 等消费。
+    /// 需要其他过滤条件或背压参数时使用 [`StreamQueryBuilder::build_async_node_stream`]。
+    pub async fn stream_by_label(&self, label: &str) -> QueryStream {
+        StreamQueryBuilder::new()
+            .with_label_filter(label.to_string())
+            .build_async_node_stream(self)
+            .await
+    }
+
     // ========== 流式并发写入 ==========
 
     /// 流式创建大量节点，自动分批处理
@@ -321,4 +337,57 @@ mod tests {
 
         assert_eq!(ids.len(), 10);
     }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_stream_by_label_collects_all_nodes() {
+        let db = AsyncGraphDB::from_engine(MemStore::new());
+        let nodes: Vec<_> = (0..250)
+            .map(|i| {
+                let label = if i % 5 == 0 { "Admin" } else { "User" };
+                (vec![label.to_string()], make_props(&format!("n{}", i)))
+            })
+            .collect();
+        db.batch_create_nodes_async(nodes).await.unwrap();
+
+        let stream = db.stream_by_label("User").await;
+        assert_eq!(stream.stats().total_count, 200);
+        let users = stream.collect_nodes().await.unwrap();
+        assert_eq!(users.len(), 200);
+        assert!(users.iter().all(|n| n.has_label("User")));
+        assert!(users.windows(2).all(|w| w[0].id < w[1].id));
+
+        assert!(db.stream_by_label("Missing").await.collect_nodes().await.unwrap().is_empty());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_async_stream_applies_builder_filters() {
+        use crate::query_stream::{BackpressureConfig, StreamQueryBuilder};
+        use futures::StreamExt;
+
+        let db = AsyncGraphDB::from_engine(MemStore::new());
+        let nodes: Vec<_> = (0..100)
+            .map(|i| (vec!["User".to_string()], make_props(&format!("n{}", i))))
+            .collect();
+        db.batch_create_nodes_async(nodes).await.unwrap();
+
+        let config = BackpressureConfig::new().with_channel_buffer(4).with_batch_size(10);
+        let mut stream = StreamQueryBuilder::new()
+            .with_config(config)
+            .with_label_filter("User".to_string())
+            .with_property_filter("name".to_string(), Value::Text("n42".to_string()))
+            .build_async_node_stream(&db)
+            .await;
+
+        // 通道容量小于批次大小：生产者在发送处等待消费者
+        let mut names = Vec::new();
+        let mut batch_ends = 0;
+        while let Some(item) = stream.next().await {
+            match item.node {
+                Some(node) => names.push(node.get("name").cloned()),
+                None => batch_ends += 1,
+            }
+        }
+        assert_eq!(names, vec![Some(Value::Text("n42".to_string()))]);
+        assert_eq!(batch_ends, 10);
+    }
 }
@@ -0,0 +1,135 @@
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::graph::events::GraphListener;
+use crate::graph::model::{Node, Relationship};
+use crate::storage::{NodeId, RelId};
+
+/// 一次图变更
+#[derive(Debug, Clone, PartialEq)]
+pub enum Change {
+    NodeCreated(Node),
+    /// 更新后的节点
+    NodeUpdated(Node),
+    NodeDeleted(NodeId),
+    RelCreated(Relationship),
+    /// 更新后的关系
+    RelUpdated(Relationship),
+    RelDeleted(RelId),
+}
+
+/// 变更日志中的一条记录
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChangeRecord {
+    /// 单调递增的序号，从 1 开始
+    pub seq: u64,
+    /// 记录时间（Unix 毫秒）
+    pub timestamp_ms: u64,
+    pub change: Change,
+}
+
+/// 追加式变更日志（CDC）
+///
+/// 作为 [`GraphListener`] 挂到数据库上记录每次变更。日志有容量上限，
+/// 超出时丢弃最旧的记录；副本确认后也可以用 [`ChangeLog::compact`] 主动截断。
+/// 副本发现 `oldest_seq()` 大于自己的进度时说明中间记录已丢失，需要全量同步。
+pub struct ChangeLog {
+    inner: Mutex<ChangeLogInner>,
+}
+
+struct ChangeLogInner {
+    records: VecDeque<ChangeRecord>,
+    next_seq: u64,
+    capacity: usize,
+}
+
+impl ChangeLog {
+    /// 创建最多保留 `capacity` 条记录的日志
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            inner: Mutex::new(ChangeLogInner {
+                records: VecDeque::new(),
+                next_seq: 1,
+                capacity,
+            }),
+        }
+    }
+
+    fn append(&self, change: Change) -> Result<(), String> {
+        let timestamp_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0);
+        let mut inner = self.inner.lock().map_err(|_| "change log lock poisoned".to_string())?;
+
+        let seq = inner.next_seq;
+        inner.next_seq += 1;
+        inner.records.push_back(ChangeRecord { seq, timestamp_ms, change });
+        while inner.records.len() > inner.capacity {
+            inner.records.pop_front();
+        }
+        Ok(())
+    }
+
+    /// 序号大于 `seq` 的全部记录（按序号升序）
+    pub fn since(&self, seq: u64) -> Vec<ChangeRecord> {
+        let inner = self.inner.lock().unwrap();
+        // 记录按序号连续存放，可以直接定位起点
+        let skip = inner.records.partition_point(|r| r.seq <= seq);
+        inner.records.iter().skip(skip).cloned().collect()
+    }
+
+    /// 丢弃序号不大于 `seq` 的记录，返回丢弃的条数
+    pub fn compact(&self, seq: u64) -> usize {
+        let mut inner = self.inner.lock().unwrap();
+        let drop = inner.records.partition_point(|r| r.seq <= seq);
+        inner.records.drain(..drop);
+        drop
+    }
+
+    /// 当前保留的最旧记录序号
+    pub fn oldest_seq(&self) -> Option<u64> {
+        self.inner.lock().unwrap().records.front().map(|r| r.seq)
+    }
+
+    /// 最近一条记录的序号（尚无记录时为 0）
+    pub fn latest_seq(&self) -> u64 {
+        self.inner.lock().unwrap().next_seq - 1
+    }
+
+    /// 当前保留的记录数
+    pub fn len(&self) -> usize {
+        self.inner.lock().unwrap().records.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl GraphListener for ChangeLog {
+    fn on_node_created(&self, node: &Node) -> Result<(), String> {
+        self.append(Change::NodeCreated(node.clone()))
+    }
+
+    fn on_node_updated(&self, node: &Node) -> Result<(), String> {
+        self.append(Change::NodeUpdated(node.clone()))
+    }
+
+    fn on_node_deleted(&self, id: NodeId) -> Result<(), String> {
+        self.append(Change::NodeDeleted(id))
+    }
+
+    fn on_rel_created(&self, rel: &Relationship) -> Result<(), String> {
+        self.append(Change::RelCreated(rel.clone()))
+    }
+
+    fn on_rel_updated(&self, rel: &Relationship) -> Result<(), String> {
+        self.append(Change::RelUpdated(rel.clone()))
+    }
+
+    fn on_rel_deleted(&self, id: RelId) -> Result<(), String> {
+        self.append(Change::RelDeleted(id))
+    }
+}
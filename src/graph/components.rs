@@ -0,0 +1,127 @@
+//! 增量维护的连通分量（并查集）
+//!
+//! 新增关系只需一次合并；删除关系可能把一个分量拆开，并查集无法撤销合并，
+//! 因此删除只把结构标记为失效，下次查询时再按全图重建。
+
+use std::collections::HashMap;
+
+use crate::storage::NodeId;
+
+/// 以节点 ID 为元素的并查集（按大小合并 + 路径压缩）
+///
+/// 不在表中的节点视为只含自身的单元素集合，孤立节点不占用空间。
+#[derive(Debug, Clone, Default)]
+pub struct UnionFind {
+    parent: HashMap<NodeId, NodeId>,
+    size: HashMap<NodeId, usize>,
+}
+
+impl UnionFind {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 元素所在集合的代表元素
+    pub fn find(&mut self, node: NodeId) -> NodeId {
+        let mut root = node;
+        while let Some(&parent) = self.parent.get(&root) {
+            if parent == root {
+                break;
+            }
+            root = parent;
+        }
+
+        // 路径压缩：路径上的节点直接挂到根上
+        let mut current = node;
+        while current != root {
+            let next = self.parent[&current];
+            self.parent.insert(current, root);
+            current = next;
+        }
+        root
+    }
+
+    /// 合并两个元素所在的集合，返回合并后的代表元素
+    pub fn union(&mut self, a: NodeId, b: NodeId) -> NodeId {
+        let (ra, rb) = (self.find(a), self.find(b));
+        if ra == rb {
+            return ra;
+        }
+        let size_a = self.size.get(&ra).copied().unwrap_or(1);
+        let size_b = self.size.get(&rb).copied().unwrap_or(1);
+        let (root, child) = if size_a >= size_b { (ra, rb) } else { (rb, ra) };
+
+        self.parent.insert(root, root);
+        self.parent.insert(child, root);
+        self.size.remove(&child);
+        self.size.insert(root, size_a + size_b);
+        root
+    }
+
+    /// 元素是否出现在某个多元素集合中
+    pub fn contains(&self, node: NodeId) -> bool {
+        self.parent.contains_key(&node)
+    }
+
+    pub fn clear(&mut self) {
+        self.parent.clear();
+        self.size.clear();
+    }
+}
+
+/// 数据库维护的连通分量状态
+#[derive(Debug, Default)]
+pub(crate) struct ComponentTracker {
+    sets: UnionFind,
+    /// 删除过关系，需要全量重建
+    dirty: bool,
+    /// 全量重建次数
+    rebuilds: usize,
+}
+
+impl ComponentTracker {
+    /// 引擎中已有数据时使用：首次查询时全量构建
+    pub(crate) fn stale() -> Self {
+        Self {
+            dirty: true,
+            ..Self::default()
+        }
+    }
+
+    pub(crate) fn on_rel_created(&mut self, start: NodeId, end: NodeId) {
+        if !self.dirty && start != end {
+            self.sets.union(start, end);
+        }
+    }
+
+    pub(crate) fn invalidate(&mut self) {
+        self.dirty = true;
+    }
+
+    pub(crate) fn is_tracked(&self, node: NodeId) -> bool {
+        self.sets.contains(node)
+    }
+
+    /// 代表元素；结构失效时先用 `edges` 给出的全部关系端点重建
+    pub(crate) fn find(
+        &mut self,
+        node: NodeId,
+        edges: impl FnOnce() -> Vec<(NodeId, NodeId)>,
+    ) -> NodeId {
+        if self.dirty {
+            self.sets.clear();
+            for (start, end) in edges() {
+                if start != end {
+                    self.sets.union(start, end);
+                }
+            }
+            self.dirty = false;
+            self.rebuilds += 1;
+        }
+        self.sets.find(node)
+    }
+
+    pub(crate) fn rebuilds(&self) -> usize {
+        self.rebuilds
+    }
+}
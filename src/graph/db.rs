@@ -1,21 +1,285 @@
+use crate::graph::cdc::{Change, ChangeLog, ChangeRecord};
+use crate::graph::events::GraphListener;
 use crate::graph::model::{Node, Relationship};
-use crate::storage::{mem_store::MemStore, NodeId, RelId, StorageEngine, StorageError, TxHandle};
+use crate::storage::{
+    mem_store::MemStore, IdStrategy, NodeId, RelId, StorageEngine, StorageError, StoredNode,
+    StoredRel, TxHandle,
+};
 use crate::values::{Properties, Value};
-use crate::transactions::{TransactionManager, TransactionConfig};
+use crate::transactions::{
+    OptimisticLockManager, OptimisticReadContext, RetryConfig, TransactionConfig, TransactionManager,
+    TransactionOp, TransactionResult,
+};
+use crate::index::{IndexBatch, PropertyIndex};
+use crate::graph::dump::{read_snapshot, write_snapshot, SnapshotCompression, SnapshotData};
+use crate::index_advanced::{FullTextIndex, TextAnalyzer};
+use crate::index_schema::IndexSchema;
+use crate::constraints::{ConstraintManager, ConstraintValidation};
+use crate::graph::advisor::{FilterTrace, IndexSuggestion, QueryTracer};
+use crate::graph::components::ComponentTracker;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex, PoisonError};
+
+#[cfg(feature = "caching")]
+use crate::cache::CacheManager;
 
 #[derive(Debug)]
 pub enum GraphError {
     Storage(String),
     NotFound,
+    /// 节点仍有关联关系（Restrict 删除模式下返回，附带关联的关系ID）
+    HasRelationships(Vec<RelId>),
+    /// 单个属性值超过 [`PropertyLimits::max_value_bytes`]
+    PropertyTooLarge { key: String, size: usize, limit: usize },
+    /// 节点或关系的属性数量超过 [`PropertyLimits::max_properties`]
+    TooManyProperties { count: usize, limit: usize },
+    /// 回放变更时序号不连续（期望的下一个序号与实际收到的序号）
+    SequenceGap { expected: u64, found: u64 },
+    /// 写入违反已注册的约束（唯一性、存在性或关系基数）
+    ConstraintViolation(String),
 }
 
-use crate::index::PropertyIndex;
-use crate::index_schema::IndexSchema;
-use crate::constraints::ConstraintManager;
-use std::sync::Arc;
+impl std::fmt::Display for GraphError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GraphError::Storage(msg) => write!(f, "storage error: {}", msg),
+            GraphError::NotFound => write!(f, "not found"),
+            GraphError::HasRelationships(rels) => {
+                write!(f, "node still has {} relationship(s)", rels.len())
+            }
+            GraphError::PropertyTooLarge { key, size, limit } => write!(
+                f,
+                "property '{}' is {} bytes, exceeding the limit of {} bytes",
+                key, size, limit
+            ),
+            GraphError::TooManyProperties { count, limit } => write!(
+                f,
+                "{} properties exceed the limit of {}",
+                count, limit
+            ),
+            GraphError::SequenceGap { expected, found } => write!(
+                f,
+                "change sequence gap: expected {}, found {}",
+                expected, found
+            ),
+            GraphError::ConstraintViolation(msg) => write!(f, "{}", msg),
+        }
+    }
+}
 
-#[cfg(feature = "caching")]
-use crate::cache::CacheManager;
+impl std::error::Error for GraphError {}
+
+/// 属性写入限制，`None` 表示不限制（默认全部不限制）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct PropertyLimits {
+    /// 单个属性值的最大字节数（文本按 UTF-8 字节计，列表按元素累加）
+    pub max_value_bytes: Option<usize>,
+    /// 每个节点或关系的最大属性数量
+    pub max_properties: Option<usize>,
+}
+
+impl PropertyLimits {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_max_value_bytes(mut self, bytes: usize) -> Self {
+        self.max_value_bytes = Some(bytes);
+        self
+    }
+
+    pub fn with_max_properties(mut self, count: usize) -> Self {
+        self.max_properties = Some(count);
+        self
+    }
+
+    /// 检查一组完整的节点或关系属性是否满足限制
+    pub fn check(&self, props: &Properties) -> Result<(), GraphError> {
+        if let Some(limit) = self.max_properties {
+            if props.len() > limit {
+                return Err(GraphError::TooManyProperties { count: props.len(), limit });
+            }
+        }
+        if let Some(limit) = self.max_value_bytes {
+            for (key, value) in props {
+                let size = value_byte_size(value);
+                if size > limit {
+                    return Err(GraphError::PropertyTooLarge { key: key.clone(), size, limit });
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 属性值占用的字节数估算
+fn value_byte_size(value: &Value) -> usize {
+    match value {
+        Value::Int(_) | Value::Float(_) | Value::DateTime(_) => 8,
+        Value::Bool(_) => 1,
+        Value::Null => 0,
+        Value::Text(s) => s.len(),
+        Value::Bytes(b) => b.len(),
+        Value::List(items) => items.iter().map(value_byte_size).sum(),
+    }
+}
+
+/// 节点删除模式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DeleteMode {
+    /// 级联删除：同时删除所有关联的关系
+    #[default]
+    Cascade,
+    /// 限制删除：节点存在关联关系时拒绝删除
+    Restrict,
+}
+
+/// 边权重归一化方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WeightNormalization {
+    /// `(w - min) / (max - min)`，结果落在 [0, 1]
+    #[default]
+    MinMax,
+    /// `(w - mean) / std`（总体标准差）
+    ZScore,
+}
+
+/// 全文索引在存储引擎中的保存名
+const FULLTEXT_INDEX_KEY: &str = "fulltext";
+
+/// 64 位 FNV-1a 的初始值；持久化的指纹不能用随 Rust 版本变化的 `DefaultHasher`
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+
+/// 把一段带长度前缀的字节并入 FNV-1a 哈希
+fn fnv_feed(mut hash: u64, bytes: &[u8]) -> u64 {
+    for &b in (bytes.len() as u64).to_le_bytes().iter().chain(bytes) {
+        hash ^= b as u64;
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    hash
+}
+
+/// 事务提交时要发出的变更事件（按实体类型和变更种类分组）
+#[derive(Default)]
+struct TxEvents {
+    created_nodes: Vec<NodeId>,
+    updated_nodes: Vec<NodeId>,
+    deleted_nodes: Vec<NodeId>,
+    created_rels: Vec<RelId>,
+    updated_rels: Vec<RelId>,
+    deleted_rels: Vec<RelId>,
+}
+
+impl TxEvents {
+    /// 同一实体多次出现时只保留第一次
+    fn dedup(&mut self) {
+        for ids in [
+            &mut self.created_nodes,
+            &mut self.updated_nodes,
+            &mut self.deleted_nodes,
+            &mut self.created_rels,
+            &mut self.updated_rels,
+            &mut self.deleted_rels,
+        ] {
+            let mut seen = HashSet::new();
+            ids.retain(|id| seen.insert(*id));
+        }
+    }
+}
+
+/// 暂缓发出的监听器事件
+type PendingEvent = Box<dyn Fn(&dyn GraphListener) -> Result<(), String> + Send + Sync>;
+
+/// [`GraphDatabase::atomically`] 执行期间的撤销日志
+///
+/// 记录每个被改动的节点和关系在首次改动前的状态（`None` 表示原本不存在），
+/// 以及执行成功后才发给监听器的事件。
+#[derive(Default)]
+struct UndoLog {
+    nodes: HashMap<NodeId, Option<StoredNode>>,
+    rels: HashMap<RelId, Option<StoredRel>>,
+    events: Vec<PendingEvent>,
+}
+
+impl UndoLog {
+    /// 合并内层执行的日志，同一实体保留更早的状态
+    fn absorb(&mut self, inner: UndoLog) {
+        for (id, node) in inner.nodes {
+            self.nodes.entry(id).or_insert(node);
+        }
+        for (id, rel) in inner.rels {
+            self.rels.entry(id).or_insert(rel);
+        }
+        self.events.extend(inner.events);
+    }
+}
+
+/// 节点在某个索引中的一个条目
+enum IndexedValues<'a> {
+    Single(&'a str, &'a str, &'a Value),
+    Composite(&'a str, &'a [&'a str], &'a [Value]),
+}
+
+/// 合并两个节点（边收缩、节点去重）时同名属性的取值方式
+///
+/// “第一个”节点在边收缩中是关系起点，在 [`GraphDatabase::merge_nodes`] 中是保留的节点。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PropertyConflict {
+    /// 取第一个节点的值
+    #[default]
+    PreferFirst,
+    /// 取第二个节点的值
+    PreferSecond,
+    /// 两个值不同时合并为列表 `[第一个值, 第二个值]`
+    Combine,
+}
+
+impl PropertyConflict {
+    /// 把第二个节点的属性 `other` 合并进第一个节点的属性 `target`
+    fn merge_into(self, target: &mut Properties, other: Properties) {
+        for (key, value) in other {
+            match target.get_mut(&key) {
+                None => {
+                    target.insert(key, value);
+                }
+                Some(existing) => match self {
+                    PropertyConflict::PreferFirst => {}
+                    PropertyConflict::PreferSecond => *existing = value,
+                    PropertyConflict::Combine => {
+                        if *existing != value {
+                            *existing = Value::List(vec![existing.clone(), value]);
+                        }
+                    }
+                },
+            }
+        }
+    }
+}
+
+/// 边收缩选项
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ContractOptions {
+    /// 同名属性冲突时的取值方式
+    pub conflict: PropertyConflict,
+    /// 是否保留收缩产生的自环（两端点之间的其他关系）；默认丢弃
+    pub keep_self_loops: bool,
+}
+
+impl ContractOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_conflict(mut self, conflict: PropertyConflict) -> Self {
+        self.conflict = conflict;
+        self
+    }
+
+    pub fn with_self_loops(mut self, keep: bool) -> Self {
+        self.keep_self_loops = keep;
+        self
+    }
+}
 
 pub struct GraphDatabase<E: StorageEngine> {
     pub(crate) engine: E,
@@ -26,6 +290,79 @@ pub struct GraphDatabase<E: StorageEngine> {
     cache: Option<CacheManager>,
     /// 事务管理器
     pub transactions: TransactionManager,
+    /// 全局三角形数量（随关系增删增量维护）
+    triangles: usize,
+    /// 弱连通分量（新增关系时增量合并，删除后惰性重建）
+    components: Mutex<ComponentTracker>,
+    /// 最近 Cypher 查询中的属性过滤，供索引建议使用
+    tracer: Mutex<QueryTracer>,
+    /// 属性写入限制
+    property_limits: PropertyLimits,
+    /// 变更监听器，按注册顺序回调
+    listeners: Vec<Arc<dyn GraphListener>>,
+    /// 变更日志（启用后同时注册在 `listeners` 中）
+    change_log: Option<Arc<ChangeLog>>,
+    /// 作为副本时已回放到的变更序号
+    applied_seq: u64,
+    /// [`atomically`](Self::atomically) 执行期间的撤销日志
+    undo: Option<UndoLog>,    /// 节点版本号，每次改动递增，乐观事务提交时据此检测冲突
+    versions: OptimisticLockManager,
+}
+
+/// 乐观事务：写入进入存储事务，读取和改动过的节点记下当时的版本号，
+/// 提交时版本号有变化说明节点已被其他写入改动（即使改回了原值），提交失败
+#[derive(Debug)]
+pub struct OptimisticTx {
+    handle: TxHandle,
+    reads: OptimisticReadContext,
+}
+
+impl OptimisticTx {
+    /// 底层存储事务的句柄
+    pub fn handle(&self) -> TxHandle {
+        self.handle
+    }
+
+    /// 在事务视角下读取节点，并记下节点当前的版本号
+    pub fn get_node<E: StorageEngine>(
+        &mut self,
+        db: &GraphDatabase<E>,
+        id: NodeId,
+    ) -> TransactionResult<Option<Node>> {
+        self.reads.record_node(id, db.versions.read_node_version(id));
+        Ok(db.get_node_in_tx(self.handle, id)?)
+    }
+
+    /// 在事务中创建节点
+    pub fn create_node<E: StorageEngine>(
+        &mut self,
+        db: &mut GraphDatabase<E>,
+        labels: Vec<&str>,
+        props: Properties,
+    ) -> TransactionResult<NodeId> {
+        Ok(db.create_node_in_tx(self.handle, labels, props)?)
+    }
+
+    /// 在事务中合并更新节点属性
+    pub fn set_node_props<E: StorageEngine>(
+        &mut self,
+        db: &mut GraphDatabase<E>,
+        id: NodeId,
+        props: Properties,
+    ) -> TransactionResult<()> {
+        self.reads.record_node(id, db.versions.read_node_version(id));
+        Ok(db.update_node_props_in_tx(self.handle, id, props)?)
+    }
+
+    /// 在事务中删除节点，返回事务视角下节点是否存在
+    pub fn delete_node<E: StorageEngine>(
+        &mut self,
+        db: &mut GraphDatabase<E>,
+        id: NodeId,
+    ) -> TransactionResult<bool> {
+        self.reads.record_node(id, db.versions.read_node_version(id));
+        Ok(db.delete_node_in_tx(self.handle, id)?)
+    }
 }
 
 impl GraphDatabase<MemStore> {
@@ -38,37 +375,69 @@ impl GraphDatabase<MemStore> {
             #[cfg(feature = "caching")]
             cache: None,
             transactions: TransactionManager::new(),
+            triangles: 0,
+            components: Mutex::default(),
+            tracer: Mutex::default(),
+            property_limits: PropertyLimits::default(),
+            listeners: Vec::new(),
+            change_log: None,
+            applied_seq: 0,
+            undo: None,
+            versions: OptimisticLockManager::new(),
         }
     }
 
     pub fn new_in_memory_with_schema(schema: IndexSchema) -> Self {
+        Self::new_in_memory_with(MemStore::new(), schema)
+    }
+
+    /// 使用指定 ID 分配策略的内存数据库
+    ///
+    /// `IdStrategy::Random` 生成的 ID 不暴露插入顺序，独立构建的图可以用
+    /// [`GraphDatabase::merge_from`] 直接合并，无需重新映射 ID。
+    pub fn new_in_memory_with_id_strategy(strategy: IdStrategy) -> Self {
+        Self::new_in_memory_with(MemStore::with_id_strategy(strategy), IndexSchema::default())
+    }
+
+    /// 近似内存占用：存储引擎的节点、关系、邻接表加上属性索引
+    ///
+    /// 可以据此判断是否需要切换到 Hybrid/Sled 存储。
+    pub fn memory_estimate(&self) -> crate::storage::MemoryReport {
+        crate::storage::MemoryReport {
+            indexes: self.index.estimated_size(),
+            ..self.engine.memory_estimate()
+        }
+    }
+
+    fn new_in_memory_with(engine: MemStore, schema: IndexSchema) -> Self {
         Self {
-            engine: MemStore::new(),
+            engine,
             index: PropertyIndex::new(),
             schema,
             constraints: Arc::new(ConstraintManager::new()),
             #[cfg(feature = "caching")]
             cache: None,
             transactions: TransactionManager::new(),
+            triangles: 0,
+            components: Mutex::default(),
+            tracer: Mutex::default(),
+            property_limits: PropertyLimits::default(),
+            listeners: Vec::new(),
+            change_log: None,
+            applied_seq: 0,
+            undo: None,
+            versions: OptimisticLockManager::new(),
         }
     }
 }
 
 impl<E: StorageEngine> GraphDatabase<E> {
     pub fn from_engine(engine: E) -> Self {
-        Self {
-            engine,
-            index: PropertyIndex::new(),
-            schema: IndexSchema::default(),
-            constraints: Arc::new(ConstraintManager::new()),
-            #[cfg(feature = "caching")]
-            cache: None,
-            transactions: TransactionManager::new(),
-        }
+        Self::from_engine_with_schema(engine, IndexSchema::default())
     }
 
     pub fn from_engine_with_schema(engine: E, schema: IndexSchema) -> Self {
-        Self {
+        let mut db = Self {
             engine,
             index: PropertyIndex::new(),
             schema,
@@ -76,6 +445,155 @@ impl<E: StorageEngine> GraphDatabase<E> {
             #[cfg(feature = "caching")]
             cache: None,
             transactions: TransactionManager::new(),
+            triangles: 0,
+            // 引擎中可能已有关系，首次查询时全量构建
+            components: Mutex::new(ComponentTracker::stale()),
+            tracer: Mutex::default(),
+            property_limits: PropertyLimits::default(),
+            listeners: Vec::new(),
+            change_log: None,
+            applied_seq: 0,
+            undo: None,
+            versions: OptimisticLockManager::new(),
+        };
+        // 引擎中可能已有数据，需要先全量统计一次
+        db.triangles = crate::algorithms::count_triangles(&db);
+        db.load_fulltext_index();
+        db
+    }
+
+    /// 设置属性写入限制，超限的 `try_*` 写入会被拒绝
+    pub fn with_property_limits(mut self, limits: PropertyLimits) -> Self {
+        self.property_limits = limits;
+        self
+    }
+
+    pub fn set_property_limits(&mut self, limits: PropertyLimits) {
+        self.property_limits = limits;
+    }
+
+    pub fn property_limits(&self) -> PropertyLimits {
+        self.property_limits
+    }
+
+    // ========== 变更通知 ==========
+
+    /// 注册变更监听器，之后每次写操作成功后同步回调
+    pub fn subscribe(&mut self, listener: Arc<dyn GraphListener>) {
+        self.listeners.push(listener);
+    }
+
+    /// 启用变更日志（CDC），最多保留 `capacity` 条记录
+    ///
+    /// 已启用时直接返回现有日志，`capacity` 不生效。
+    pub fn enable_change_log(&mut self, capacity: usize) -> Arc<ChangeLog> {
+        if let Some(log) = &self.change_log {
+            return log.clone();
+        }
+        let log = Arc::new(ChangeLog::new(capacity));
+        self.subscribe(log.clone());
+        self.change_log = Some(log.clone());
+        log
+    }
+
+    /// 序号大于 `seq` 的变更记录，按序号升序；未启用变更日志时为空
+    pub fn changes_since(&self, seq: u64) -> Vec<ChangeRecord> {
+        self.change_log
+            .as_ref()
+            .map(|log| log.since(seq))
+            .unwrap_or_default()
+    }
+
+    /// 截断序号不大于 `seq` 的变更记录，返回截断的条数
+    pub fn compact_changes(&self, seq: u64) -> usize {
+        self.change_log.as_ref().map_or(0, |log| log.compact(seq))
+    }
+
+    /// 依次回调所有监听器，错误只记录日志不传播
+    ///
+    /// 原子执行期间事件先暂存在撤销日志中，执行成功后才发出。
+    fn notify(&mut self, event: impl Fn(&dyn GraphListener) -> Result<(), String> + Send + Sync + 'static) {
+        if self.listeners.is_empty() {
+            return;
+        }
+        match &mut self.undo {
+            Some(undo) => undo.events.push(Box::new(event)),
+            None => self.dispatch(&event),
+        }
+    }
+
+    fn dispatch(&self, event: &dyn Fn(&dyn GraphListener) -> Result<(), String>) {
+        for listener in &self.listeners {
+            if let Err(e) = event(listener.as_ref()) {
+                log::warn!("graph listener error: {}", e);
+            }
+        }
+    }
+
+    fn notify_node_updated(&mut self, id: NodeId) {
+        if self.listeners.is_empty() {
+            return;
+        }
+        if let Some(node) = self.get_node(id) {
+            self.notify(move |l| l.on_node_updated(&node));
+        }
+    }
+
+    fn notify_rel_created(&mut self, id: RelId) {
+        if self.listeners.is_empty() {
+            return;
+        }
+        if let Some(rel) = self.get_rel(id) {
+            self.notify(move |l| l.on_rel_created(&rel));
+        }
+    }
+
+    fn notify_rel_updated(&mut self, id: RelId) {
+        if self.listeners.is_empty() {
+            return;
+        }
+        if let Some(rel) = self.get_rel(id) {
+            self.notify(move |l| l.on_rel_updated(&rel));
+        }
+    }
+
+    /// 节点即将被改动（或以已知 ID 创建）：递增版本号；原子执行期间还要在
+    /// 首次改动前记下它的状态
+    fn record_node(&mut self, id: NodeId) {
+        self.versions.increment_node_version(id);
+        if let Some(undo) = &mut self.undo {
+            undo.nodes.entry(id).or_insert_with(|| self.engine.get_node(id));
+        }
+    }
+
+    /// 同 [`record_node`](Self::record_node)，并记下删除节点时会级联删除的关系
+    fn record_node_with_rels(&mut self, id: NodeId) {
+        self.versions.increment_node_version(id);
+        if let Some(undo) = &mut self.undo {
+            undo.nodes.entry(id).or_insert_with(|| self.engine.get_node(id));
+            for rel in self.engine.outgoing_rels(id).chain(self.engine.incoming_rels(id)) {
+                undo.rels.entry(rel.id).or_insert(Some(rel));
+            }
+        }
+    }
+
+    /// 原子执行期间记下新分配 ID 的节点（改动前不存在）
+    fn record_created_node(&mut self, id: NodeId) {
+        self.versions.increment_node_version(id);
+        if let Some(undo) = &mut self.undo {
+            undo.nodes.entry(id).or_insert(None);
+        }
+    }
+
+    fn record_rel(&mut self, id: RelId) {
+        if let Some(undo) = &mut self.undo {
+            undo.rels.entry(id).or_insert_with(|| self.engine.get_rel(id));
+        }
+    }
+
+    fn record_created_rel(&mut self, id: RelId) {
+        if let Some(undo) = &mut self.undo {
+            undo.rels.entry(id).or_insert(None);
         }
     }
 
@@ -97,16 +615,75 @@ impl<E: StorageEngine> GraphDatabase<E> {
 
     /// 根据 schema 自动为节点的属性建索引
     fn index_node(&mut self, id: NodeId, labels: &[String], props: &Properties) {
+        Self::index_node_into(&mut self.index, &self.schema, id, labels, props);
+    }
+
+    fn index_node_into(
+        index: &mut PropertyIndex,
+        schema: &IndexSchema,
+        id: NodeId,
+        labels: &[String],
+        props: &Properties,
+    ) {
+        Self::for_each_index_entry(schema, labels, props, |entry| match entry {
+            IndexedValues::Single(label, prop_name, value) => index.add(label, prop_name, value, id),
+            IndexedValues::Composite(label, properties, values) => {
+                index.add_composite(label, properties, values, id)
+            }
+        });
+    }
+
+    /// 与 `index_node_into` 相同，但只把索引项收集到批次中
+    fn index_node_into_batch(
+        batch: &mut IndexBatch,
+        schema: &IndexSchema,
+        id: NodeId,
+        labels: &[String],
+        props: &Properties,
+    ) {
+        Self::for_each_index_entry(schema, labels, props, |entry| match entry {
+            IndexedValues::Single(label, prop_name, value) => batch.add(label, prop_name, value, id),
+            IndexedValues::Composite(label, properties, values) => {
+                batch.add_composite(label, properties, values, id)
+            }
+        });
+    }
+
+    /// 移除 `index_node_into` 为这组标签和属性建立的索引条目
+    fn unindex_node_into(
+        index: &mut PropertyIndex,
+        schema: &IndexSchema,
+        id: NodeId,
+        labels: &[String],
+        props: &Properties,
+    ) {
+        Self::for_each_index_entry(schema, labels, props, |entry| match entry {
+            IndexedValues::Single(label, prop_name, value) => {
+                index.remove_entry(label, prop_name, value, id)
+            }
+            IndexedValues::Composite(label, properties, values) => {
+                index.remove_composite(label, properties, values, id)
+            }
+        });
+    }
+
+    /// 按 schema 列出节点应有的索引条目
+    fn for_each_index_entry(
+        schema: &IndexSchema,
+        labels: &[String],
+        props: &Properties,
+        mut visit: impl FnMut(IndexedValues<'_>),
+    ) {
         for label in labels {
             // 单属性索引
             for (prop_name, value) in props {
-                if self.schema.should_index(label, prop_name) {
-                    self.index.add(label, prop_name, value, id);
+                if schema.should_index(label, prop_name) {
+                    visit(IndexedValues::Single(label, prop_name, value));
                 }
             }
 
             // 复合索引
-            for (_name, (index_label, properties)) in self.schema.get_all_composite_indexes() {
+            for (index_label, properties) in schema.get_all_composite_indexes().values() {
                 if index_label == label {
                     // 检查所有属性是否都存在
                     let mut values = Vec::new();
@@ -123,13 +700,14 @@ impl<E: StorageEngine> GraphDatabase<E> {
                     // 如果所有属性都存在，则添加复合索引
                     if all_exist {
                         let props_refs: Vec<&str> = properties.iter().map(|s| s.as_str()).collect();
-                        self.index.add_composite(label, &props_refs, &values, id);
+                        visit(IndexedValues::Composite(label, &props_refs, &values));
                     }
                 }
             }
         }
     }
 
+    /// 创建节点，不检查 [`PropertyLimits`] 和约束；外部输入应走 [`try_create_node`](Self::try_create_node)
     pub fn create_node(
         &mut self,
         labels: Vec<&str>,
@@ -137,16 +715,78 @@ impl<E: StorageEngine> GraphDatabase<E> {
     ) -> NodeId {
         let labels_owned: Vec<String> = labels.into_iter().map(|s| s.to_string()).collect();
         let id = self.engine.create_node(labels_owned.clone(), props.clone());
+        self.record_created_node(id);
         self.index_node(id, &labels_owned, &props);
+        Self::refresh_range_index(&mut self.index, id, &labels_owned, &Properties::new(), &props);
 
         #[cfg(feature = "caching")]
         if let Some(cache) = &self.cache {
             cache.on_node_created(id);
         }
 
+        if !self.listeners.is_empty() {
+            let node = Node { id, labels: labels_owned, props };
+            self.notify(move |l| l.on_node_created(&node));
+        }
+
         id
     }
 
+    /// 创建节点，属性超过 [`PropertyLimits`] 或违反已注册的约束时拒绝写入
+    pub fn try_create_node(
+        &mut self,
+        labels: Vec<&str>,
+        props: Properties,
+    ) -> Result<NodeId, GraphError> {
+        self.property_limits.check(&props)?;
+        let owned: Vec<String> = labels.iter().map(|l| l.to_string()).collect();
+        match self.constraints.validate_new_node(self, &owned, &props) {
+            Ok(ConstraintValidation::Valid) => {}
+            Ok(ConstraintValidation::Violated { message }) => {
+                return Err(GraphError::ConstraintViolation(message))
+            }
+            Err(e) => return Err(GraphError::Storage(e)),
+        }
+        Ok(self.create_node(labels, props))
+    }
+
+    /// 创建关系，属性超过 [`PropertyLimits`] 或超出已注册的关系基数约束上限时拒绝写入
+    pub fn try_create_rel(
+        &mut self,
+        start: NodeId,
+        end: NodeId,
+        typ: &str,
+        props: Properties,
+    ) -> Result<RelId, GraphError> {
+        self.property_limits.check(&props)?;
+        self.check_new_rels(&[(start, end, typ)])?;
+        Ok(self.create_rel(start, end, typ, props))
+    }
+
+    /// 创建无向关系，属性超过 [`PropertyLimits`] 或超出已注册的关系基数约束上限时拒绝写入
+    ///
+    /// 无向关系同时计入两个端点的出边和入边。
+    pub fn try_create_undirected_rel(
+        &mut self,
+        start: NodeId,
+        end: NodeId,
+        typ: &str,
+        props: Properties,
+    ) -> Result<RelId, GraphError> {
+        self.property_limits.check(&props)?;
+        self.check_new_rels(&[(start, end, typ), (end, start, typ)])?;
+        self.create_undirected_rel(start, end, typ, props)
+            .map_err(|e| GraphError::Storage(format!("{:?}", e)))
+    }
+
+    /// 按顺序检查待创建关系的基数约束
+    fn check_new_rels(&self, rels: &[(NodeId, NodeId, &str)]) -> Result<(), GraphError> {
+        match self.constraints.validate_new_rels(self, rels) {
+            Ok(ConstraintValidation::Valid) => Ok(()),
+            Ok(ConstraintValidation::Violated { message }) => Err(GraphError::ConstraintViolation(message)),
+            Err(e) => Err(GraphError::Storage(e)),
+        }
+    }
 
     pub fn create_rel(
         &mut self,
@@ -155,15 +795,324 @@ impl<E: StorageEngine> GraphDatabase<E> {
         typ: &str,
         props: Properties,
     ) -> RelId {
+        let delta = self.triangle_delta(start, end, &HashMap::new());
         let id = self.engine
             .create_rel(start, end, typ.to_string(), props);
+        self.record_created_rel(id);
+        self.triangles += delta;
+        self.components_mut().on_rel_created(start, end);
+
+        #[cfg(feature = "caching")]
+        if let Some(cache) = &self.cache {
+            cache.on_rel_created(id, start, end);
+        }
+
+        self.notify_rel_created(id);
+        id
+    }
+
+    /// 创建无向关系：两个端点都可以沿出边或入边到达对方
+    ///
+    /// 存储引擎不支持无向关系时返回 `StorageError::UndirectedNotSupported`
+    pub fn create_undirected_rel(
+        &mut self,
+        start: NodeId,
+        end: NodeId,
+        typ: &str,
+        props: Properties,
+    ) -> Result<RelId, StorageError> {
+        let delta = self.triangle_delta(start, end, &HashMap::new());
+        let id = self
+            .engine
+            .create_undirected_rel(start, end, typ.to_string(), props)?;
+        self.record_created_rel(id);
+        self.triangles += delta;
+        self.components_mut().on_rel_created(start, end);
 
         #[cfg(feature = "caching")]
         if let Some(cache) = &self.cache {
             cache.on_rel_created(id, start, end);
         }
 
-        id
+        self.notify_rel_created(id);
+        Ok(id)
+    }
+
+    /// 节点的度：出边数 + 入边数，无向关系只计一次
+    pub fn degree(&self, node: NodeId) -> usize {
+        self.neighbors_out(node).count()
+            + self.neighbors_in(node).filter(|r| r.directed).count()
+    }
+
+    /// 合并关系：若已存在相同起点、终点和类型的关系则返回已有关系，否则创建新关系
+    ///
+    /// 已存在时会把 `props` 合并到已有关系上（传入空属性即可只做去重）
+    pub fn merge_rel(
+        &mut self,
+        start: NodeId,
+        end: NodeId,
+        typ: &str,
+        props: Properties,
+    ) -> RelId {
+        let existing = self
+            .neighbors_out(start)
+            .filter(|r| r.directed && r.end == end && r.typ == typ)
+            .map(|r| r.id)
+            .min();
+
+        match existing {
+            Some(id) => {
+                if !props.is_empty() {
+                    self.update_rel_props(id, props);
+                }
+                id
+            }
+            None => self.create_rel(start, end, typ, props),
+        }
+    }
+
+    /// 按标签和键属性获取节点，不存在时创建（MERGE 的编程接口）
+    ///
+    /// 键属性被 schema 索引时走索引查找，否则扫描该标签的节点；有多个匹配时返回 ID 最小的。
+    /// 新节点的属性为 `default_props` 加上键属性，创建前经过 [`PropertyLimits`] 和约束
+    /// （例如其他属性上的唯一性约束）校验，违反时返回错误且不写入。返回 (节点 ID, 是否新建)。
+    ///
+    /// `&mut self` 保证查找与创建之间没有其他写入；多线程场景使用
+    /// [`ConcurrentGraphDB::get_or_create_node`](crate::ConcurrentGraphDB::get_or_create_node)。
+    pub fn get_or_create_node(
+        &mut self,
+        label: &str,
+        key_prop: &str,
+        key_value: Value,
+        default_props: Properties,
+    ) -> Result<(NodeId, bool), GraphError> {
+        if let Some(id) = self.find_node_by_key(label, key_prop, &key_value) {
+            return Ok((id, false));
+        }
+
+        let mut props = default_props;
+        props.insert(key_prop.to_string(), key_value);
+        Ok((self.try_create_node(vec![label], props)?, true))
+    }
+
+    fn find_node_by_key(&self, label: &str, key_prop: &str, key_value: &Value) -> Option<NodeId> {
+        if self.schema.should_index(label, key_prop) {
+            // 索引随写入维护，这里再按节点当前属性校验一次，避免返回过期条目
+            return self
+                .index
+                .find(label, key_prop, key_value)
+                .into_iter()
+                .filter(|&id| {
+                    self.engine.get_node(id).is_some_and(|n| {
+                        n.labels.iter().any(|l| l == label) && n.props.get(key_prop) == Some(key_value)
+                    })
+                })
+                .min();
+        }
+
+        self.scan_nodes(
+            crate::storage::NodeFilter::new()
+                .with_label(label)
+                .with_prop(key_prop, crate::storage::PropPredicate::Eq(key_value.clone())),
+        )
+        .map(|n| n.id)
+        .min()
+    }
+
+    /// 合并重复关系（相同起点、终点、类型和方向性）
+    ///
+    /// 每组重复关系保留 ID 最小的一条，其余关系的属性中保留关系缺失的键会被补充进去，
+    /// 然后删除其余关系。返回被删除的关系数量。
+    pub fn dedup_relationships(&mut self) -> usize {
+        let node_ids: Vec<NodeId> = self.all_stored_nodes().map(|n| n.id).collect();
+
+        // 先收集每组重复关系，避免边遍历边修改
+        let mut groups: Vec<Vec<Relationship>> = Vec::new();
+        for node_id in node_ids {
+            let mut by_key: std::collections::HashMap<(NodeId, String, bool), Vec<Relationship>> =
+                std::collections::HashMap::new();
+            for rel in self.neighbors_out(node_id) {
+                // 无向关系从两个端点都能看到，只在编号较小的端点处理一次
+                if !rel.directed && rel.end < node_id {
+                    continue;
+                }
+                by_key
+                    .entry((rel.end, rel.typ.clone(), rel.directed))
+                    .or_default()
+                    .push(rel);
+            }
+            groups.extend(by_key.into_values().filter(|g| g.len() > 1));
+        }
+
+        let mut removed = 0;
+        for mut group in groups {
+            group.sort_by_key(|r| r.id);
+            let keep = group.remove(0);
+
+            let mut missing = Properties::new();
+            for dup in &group {
+                for (k, v) in &dup.props {
+                    if !keep.props.contains_key(k) && !missing.contains_key(k) {
+                        missing.insert(k.clone(), v.clone());
+                    }
+                }
+            }
+            if !missing.is_empty() {
+                self.update_rel_props(keep.id, missing);
+            }
+
+            for dup in group {
+                if self.delete_rel(dup.id) {
+                    removed += 1;
+                }
+            }
+        }
+
+        removed
+    }
+
+    /// 收缩关系：把两个端点合并为一个节点，使用默认的 [`ContractOptions`]
+    pub fn contract_edge(&mut self, rel_id: RelId) -> Result<NodeId, GraphError> {
+        self.contract_edge_with(rel_id, ContractOptions::default())
+    }
+
+    /// 收缩关系：把两个端点合并为一个新节点，返回新节点 ID
+    ///
+    /// 新节点的标签为两端标签的并集，属性按 `options.conflict` 合并；两端的其他关系
+    /// （保持类型、属性和方向性）改连到新节点，被收缩的关系和两个原节点随后删除。
+    /// 两端点之间的其他关系会变成自环，按 `options.keep_self_loops` 保留或丢弃。
+    /// 关系被重建，因此这些关系的 ID 会改变。自环本身收缩时只删除该关系，返回原节点。
+    /// 关系不存在时返回 `GraphError::NotFound`。
+    pub fn contract_edge_with(&mut self, rel_id: RelId, options: ContractOptions) -> Result<NodeId, GraphError> {
+        let rel = self.engine.get_rel(rel_id).ok_or(GraphError::NotFound)?;
+        if rel.start == rel.end {
+            self.delete_rel(rel_id);
+            return Ok(rel.start);
+        }
+        let start = self.engine.get_node(rel.start).ok_or(GraphError::NotFound)?;
+        let end = self.engine.get_node(rel.end).ok_or(GraphError::NotFound)?;
+
+        let mut labels = start.labels.clone();
+        for label in &end.labels {
+            if !labels.contains(label) {
+                labels.push(label.clone());
+            }
+        }
+        let mut props = start.props.clone();
+        options.conflict.merge_into(&mut props, end.props.clone());
+
+        // 两端的其他关系，按 ID 去重（无向关系从出边和入边都能看到）
+        let mut attached: Vec<RelId> = self
+            .neighbors_out(rel.start)
+            .chain(self.neighbors_in(rel.start))
+            .chain(self.neighbors_out(rel.end))
+            .chain(self.neighbors_in(rel.end))
+            .map(|r| r.id)
+            .filter(|&id| id != rel_id)
+            .collect();
+        attached.sort_unstable();
+        attached.dedup();
+        let attached: Vec<_> = attached.into_iter().filter_map(|id| self.engine.get_rel(id)).collect();
+
+        // 重建无向关系可能失败（引擎不支持），失败时撤销已创建的新节点和关系
+        self.atomically(|db| {
+            let merged = db.create_node(labels.iter().map(String::as_str).collect(), props);
+            let remap = |id: NodeId| if id == rel.start || id == rel.end { merged } else { id };
+            for other in attached {
+                let (from, to) = (remap(other.start), remap(other.end));
+                if from == to && !options.keep_self_loops {
+                    continue;
+                }
+                if other.directed {
+                    db.create_rel(from, to, &other.typ, other.props);
+                } else {
+                    db.create_undirected_rel(from, to, &other.typ, other.props)
+                        .map_err(|e| GraphError::Storage(format!("{:?}", e)))?;
+                }
+            }
+
+            db.unindex_and_delete(&start);
+            db.unindex_and_delete(&end);
+            Ok(merged)
+        })
+    }
+
+    /// 合并重复节点：把 `remove` 的关系改连到 `keep`，属性按 `prop_strategy` 合并后删除 `remove`
+    ///
+    /// `keep` 是 [`PropertyConflict`] 中的第一个节点，标签保持不变。改连后与 `keep` 已有关系
+    /// 重复（相同端点、类型和方向性）的关系不再创建，只把已有关系缺少的属性补上；
+    /// 两个节点之间的关系在合并后没有意义，直接丢弃。属性索引随之更新。
+    /// 任一节点不存在时返回 `GraphError::NotFound`；`keep == remove` 时什么也不做。
+    pub fn merge_nodes(
+        &mut self,
+        keep: NodeId,
+        remove: NodeId,
+        prop_strategy: PropertyConflict,
+    ) -> Result<(), GraphError> {
+        let kept = self.engine.get_node(keep).ok_or(GraphError::NotFound)?;
+        let removed = self.engine.get_node(remove).ok_or(GraphError::NotFound)?;
+        if keep == remove {
+            return Ok(());
+        }
+
+        let mut props = kept.props.clone();
+        prop_strategy.merge_into(&mut props, removed.props.clone());
+        Self::unindex_node_into(&mut self.index, &self.schema, keep, &kept.labels, &kept.props);
+        self.record_node(keep);
+        self.engine.update_node_props(keep, props.clone());
+        Self::index_node_into(&mut self.index, &self.schema, keep, &kept.labels, &props);
+        Self::refresh_range_index(&mut self.index, keep, &kept.labels, &kept.props, &props);
+        self.notify_node_updated(keep);
+
+        let mut attached: Vec<RelId> = self
+            .neighbors_out(remove)
+            .chain(self.neighbors_in(remove))
+            .map(|r| r.id)
+            .collect();
+        attached.sort_unstable();
+        attached.dedup();
+        for id in attached {
+            let Some(rel) = self.engine.get_rel(id) else { continue };
+            let remap = |id: NodeId| if id == remove { keep } else { id };
+            let (from, to) = (remap(rel.start), remap(rel.end));
+            if from == keep && to == keep {
+                continue;
+            }
+            // 无向关系不区分端点顺序，两个方向的同类型关系都算重复
+            let existing = self.neighbors_out(from).find(|r| {
+                r.directed == rel.directed
+                    && r.typ == rel.typ
+                    && ((r.start == from && r.end == to) || (!r.directed && r.start == to && r.end == from))
+            });
+            match existing {
+                Some(existing) => {
+                    let missing: Properties = rel
+                        .props
+                        .into_iter()
+                        .filter(|(k, _)| !existing.props.contains_key(k))
+                        .collect();
+                    if !missing.is_empty() {
+                        self.update_rel_props(existing.id, missing);
+                    }
+                }
+                None if rel.directed => {
+                    self.create_rel(from, to, &rel.typ, rel.props);
+                }
+                None => {
+                    self.create_undirected_rel(from, to, &rel.typ, rel.props)
+                        .map_err(|e| GraphError::Storage(format!("{:?}", e)))?;
+                }
+            }
+        }
+
+        self.unindex_and_delete(&removed);
+        Ok(())
+    }
+
+    /// 从属性索引中移除节点后删除节点（`delete_node` 只清理范围索引）
+    fn unindex_and_delete(&mut self, node: &crate::storage::StoredNode) {
+        Self::unindex_node_into(&mut self.index, &self.schema, node.id, &node.labels, &node.props);
+        self.delete_node(node.id);
     }
 
     /// 批量创建节点，返回创建的节点ID列表
@@ -178,28 +1127,93 @@ impl<E: StorageEngine> GraphDatabase<E> {
         let ids = self.engine.batch_create_nodes(
             storage_nodes.iter().map(|(labels, props)| (labels.clone(), props.clone())).collect()
         );
+        for &id in &ids {
+            self.record_created_node(id);
+        }
+
+        // 先收集全部索引项，再一次性合并进索引
+        let mut batch = IndexBatch::new();
+        for (&id, (labels, props)) in ids.iter().zip(&storage_nodes) {
+            Self::index_node_into_batch(&mut batch, &self.schema, id, labels, props);
+        }
+        self.index.bulk_build(batch);
+        let empty = Properties::new();
+        for (&id, (labels, props)) in ids.iter().zip(&storage_nodes) {
+            Self::refresh_range_index(&mut self.index, id, labels, &empty, props);
+        }
 
-        // 为每个节点建立索引
-        for (i, id) in ids.iter().enumerate() {
-            if let Some((labels, props)) = storage_nodes.get(i) {
-                self.index_node(*id, labels, props);
+        if !self.listeners.is_empty() {
+            for (&id, (labels, props)) in ids.iter().zip(storage_nodes) {
+                let node = Node { id, labels, props };
+                self.notify(move |l| l.on_node_created(&node));
             }
         }
 
         ids
     }
 
+    /// 批量创建节点，任一节点的属性超过 [`PropertyLimits`] 时整批拒绝写入
+    pub fn try_batch_create_nodes(
+        &mut self,
+        nodes: Vec<(Vec<String>, Properties)>,
+    ) -> Result<Vec<NodeId>, GraphError> {
+        for (_, props) in &nodes {
+            self.property_limits.check(props)?;
+        }
+        Ok(self.batch_create_nodes(nodes))
+    }
+
     /// 批量创建关系，返回创建的关系ID列表
     pub fn batch_create_rels(
         &mut self,
         rels: Vec<(NodeId, NodeId, String, Properties)>,
     ) -> Vec<RelId> {
         let storage_rels: Vec<(NodeId, NodeId, String, Properties)> = rels;
-        self.engine.batch_create_rels(
+
+        let endpoints: Vec<(NodeId, NodeId)> = storage_rels.iter().map(|(s, e, _, _)| (*s, *e)).collect();
+
+        // 逐条计算新增三角形，本批次中前面的关系通过 pending 计入邻接
+        let mut pending: HashMap<NodeId, HashSet<NodeId>> = HashMap::new();
+        let mut delta = 0;
+        for &(start, end) in &endpoints {
+            delta += self.triangle_delta(start, end, &pending);
+            if start != end {
+                pending.entry(start).or_default().insert(end);
+                pending.entry(end).or_default().insert(start);
+            }
+        }
+
+        let ids = self.engine.batch_create_rels(
             storage_rels.into_iter()
                 .map(|(start, end, typ, props)| (start, end, typ, props))
                 .collect()
-        )
+        );
+        for &id in &ids {
+            self.record_created_rel(id);
+        }
+        self.triangles += delta;
+        let components = self.components_mut();
+        for (start, end) in endpoints {
+            components.on_rel_created(start, end);
+        }
+        for &id in &ids {
+            self.notify_rel_created(id);
+        }
+        ids
+    }
+
+    /// 批量创建关系，任一关系的属性超过 [`PropertyLimits`] 或超出关系基数约束上限时整批拒绝写入
+    pub fn try_batch_create_rels(
+        &mut self,
+        rels: Vec<(NodeId, NodeId, String, Properties)>,
+    ) -> Result<Vec<RelId>, GraphError> {
+        for (_, _, _, props) in &rels {
+            self.property_limits.check(props)?;
+        }
+        let endpoints: Vec<(NodeId, NodeId, &str)> =
+            rels.iter().map(|(s, e, t, _)| (*s, *e, t.as_str())).collect();
+        self.check_new_rels(&endpoints)?;
+        Ok(self.batch_create_rels(rels))
     }
 
     pub fn delete_node(&mut self, id: NodeId) -> bool {
@@ -207,7 +1221,40 @@ impl<E: StorageEngine> GraphDatabase<E> {
         #[cfg(feature = "caching")]
         let node_info = self.engine.get_node(id.clone());
 
+        // 级联删除的关系也要通知监听器，删除前先记下
+        let attached: Vec<RelId> = if self.listeners.is_empty() {
+            Vec::new()
+        } else {
+            let mut rels: Vec<RelId> = self
+                .neighbors_out(id)
+                .chain(self.neighbors_in(id))
+                .map(|r| r.id)
+                .collect();
+            rels.sort_unstable();
+            rels.dedup();
+            rels
+        };
+
+        // 范围索引按旧值删除，需要先读出节点
+        let range_old = if self.index.has_range_index() { self.engine.get_node(id) } else { None };
+
+        self.record_node_with_rels(id);
+        let lost = self.triangles_at(id);
         let result = self.engine.delete_node(id);
+        if result {
+            self.triangles -= lost;
+            // 级联删除的关系可能拆开分量
+            if self.components_mut().is_tracked(id) {
+                self.components_mut().invalidate();
+            }
+            if let Some(old) = range_old {
+                Self::refresh_range_index(&mut self.index, id, &old.labels, &old.props, &Properties::new());
+            }
+            for &rel_id in &attached {
+                self.notify(move |l| l.on_rel_deleted(rel_id));
+            }
+            self.notify(move |l| l.on_node_deleted(id));
+        }
 
         #[cfg(feature = "caching")]
         if let Some(cache) = &self.cache {
@@ -220,12 +1267,48 @@ impl<E: StorageEngine> GraphDatabase<E> {
         result
     }
 
+    /// 按指定模式删除节点
+    ///
+    /// - `DeleteMode::Cascade`: 与 `delete_node` 相同，关联关系一并删除
+    /// - `DeleteMode::Restrict`: 节点存在关联关系时返回 `GraphError::HasRelationships`，不做任何修改
+    ///
+    /// 返回值与 `delete_node` 一致：节点存在并被删除时为 `Ok(true)`
+    pub fn delete_node_mode(&mut self, id: NodeId, mode: DeleteMode) -> Result<bool, GraphError> {
+        if mode == DeleteMode::Restrict {
+            let mut attached: Vec<RelId> = self
+                .neighbors_out(id)
+                .chain(self.neighbors_in(id))
+                .map(|r| r.id)
+                .collect();
+            attached.sort_unstable();
+            attached.dedup();
+
+            if !attached.is_empty() {
+                return Err(GraphError::HasRelationships(attached));
+            }
+        }
+
+        Ok(self.delete_node(id))
+    }
+
     pub fn delete_rel(&mut self, id: RelId) -> bool {
         // 先获取关系信息用于缓存失效
         #[cfg(feature = "caching")]
         let rel_info = self.engine.get_rel(id.clone());
 
+        self.record_rel(id);
+        let endpoints = self.engine.get_rel(id).map(|r| (r.start, r.end));
         let result = self.engine.delete_rel(id);
+        if let (true, Some((start, end))) = (result, endpoints) {
+            // 仍有平行关系相连时三角形不受影响
+            if start != end && !self.adjacent(start, end) {
+                self.triangles -= self.common_neighbors(start, end, &HashMap::new());
+            }
+        }
+        if result {
+            self.components_mut().invalidate();
+            self.notify(move |l| l.on_rel_deleted(id));
+        }
 
         #[cfg(feature = "caching")]
         if let Some(cache) = &self.cache {
@@ -237,6 +1320,16 @@ impl<E: StorageEngine> GraphDatabase<E> {
         result
     }
 
+    /// 压缩存储与属性索引：清理删除留下的空邻接表和空索引键，释放多余容量
+    ///
+    /// 返回回收的近似字节数。sled 类存储会在清理后刷盘，由 sled 完成磁盘空间回收。
+    pub fn compact(&mut self) -> Result<usize, StorageError> {
+        let index_before = self.index.estimated_size();
+        self.index.compact();
+        let index_reclaimed = index_before.saturating_sub(self.index.estimated_size());
+        Ok(self.engine.compact()? + index_reclaimed)
+    }
+
     pub fn flush(&mut self) -> Result<(), String> {
         // For storage engines that support flush (like sled)
         // We'd need to add a flush method to StorageEngine trait
@@ -270,6 +1363,66 @@ impl<E: StorageEngine> GraphDatabase<E> {
         })
     }
 
+    /// 批量获取节点，结果与 `ids` 一一对应，不存在的节点为 None
+    pub fn get_nodes(&self, ids: &[NodeId]) -> Vec<Option<Node>> {
+        #[cfg(feature = "caching")]
+        if self.cache.is_some() {
+            return ids.iter().map(|&id| self.get_node(id)).collect();
+        }
+
+        self.engine
+            .get_nodes(ids)
+            .into_iter()
+            .map(|sn| {
+                sn.map(|sn| Node {
+                    id: sn.id,
+                    labels: sn.labels,
+                    props: sn.props,
+                })
+            })
+            .collect()
+    }
+
+    /// 批量获取关系，结果与 `ids` 一一对应，不存在的关系为 None
+    pub fn get_rels(&self, ids: &[RelId]) -> Vec<Option<Relationship>> {
+        #[cfg(feature = "caching")]
+        if self.cache.is_some() {
+            return ids.iter().map(|&id| self.get_rel(id)).collect();
+        }
+
+        self.engine
+            .get_rels(ids)
+            .into_iter()
+            .map(|sr| {
+                sr.map(|sr| Relationship {
+                    id: sr.id,
+                    start: sr.start,
+                    end: sr.end,
+                    typ: sr.typ,
+                    props: sr.props,
+                    directed: sr.directed,
+                })
+            })
+            .collect()
+    }
+
+    /// 批量读取关系的部分属性，结果与 `ids` 一一对应
+    ///
+    /// 每个结果只包含 `keys` 中存在的属性；不存在的关系为 None
+    pub fn get_rels_props(&self, ids: &[RelId], keys: &[&str]) -> Vec<Option<Properties>> {
+        self.get_rels(ids)
+            .into_iter()
+            .map(|rel| {
+                rel.map(|rel| {
+                    rel.props
+                        .into_iter()
+                        .filter(|(k, _)| keys.contains(&k.as_str()))
+                        .collect()
+                })
+            })
+            .collect()
+    }
+
     pub fn get_rel(&self, id: RelId) -> Option<Relationship> {
         #[cfg(feature = "caching")]
         if let Some(cache) = &self.cache {
@@ -280,6 +1433,7 @@ impl<E: StorageEngine> GraphDatabase<E> {
                     end: stored_rel.end,
                     typ: stored_rel.typ,
                     props: stored_rel.props,
+                    directed: stored_rel.directed,
                 });
             }
         }
@@ -296,10 +1450,12 @@ impl<E: StorageEngine> GraphDatabase<E> {
                 end: sr.end,
                 typ: sr.typ,
                 props: sr.props,
+                directed: sr.directed,
             }
         })
     }
 
+    /// 节点的出边；无向关系会以该节点为起点返回
     pub fn neighbors_out(
         &self,
         node: NodeId,
@@ -311,6 +1467,7 @@ impl<E: StorageEngine> GraphDatabase<E> {
                 let rels: Vec<Relationship> = rel_ids
                     .into_iter()
                     .filter_map(|id| self.get_rel(id))
+                    .map(|r| r.oriented_from(node))
                     .collect();
 
                 // 同时填充ID缓存到存储层的结果
@@ -332,7 +1489,9 @@ impl<E: StorageEngine> GraphDatabase<E> {
                     end: sr.end,
                     typ: sr.typ,
                     props: sr.props,
+                    directed: sr.directed,
                 }
+                .oriented_from(node)
             })
             .collect();
 
@@ -346,6 +1505,7 @@ impl<E: StorageEngine> GraphDatabase<E> {
         Box::new(rels.into_iter()) as Box<dyn Iterator<Item = Relationship> + '_>
     }
 
+    /// 节点的入边；无向关系会以该节点为终点返回
     pub fn neighbors_in(
         &self,
         node: NodeId,
@@ -357,6 +1517,7 @@ impl<E: StorageEngine> GraphDatabase<E> {
                 let rels: Vec<Relationship> = rel_ids
                     .into_iter()
                     .filter_map(|id| self.get_rel(id))
+                    .map(|r| r.oriented_to(node))
                     .collect();
 
                 return Box::new(rels.into_iter()) as Box<dyn Iterator<Item = Relationship> + '_>;
@@ -377,7 +1538,9 @@ impl<E: StorageEngine> GraphDatabase<E> {
                     end: sr.end,
                     typ: sr.typ,
                     props: sr.props,
+                    directed: sr.directed,
                 }
+                .oriented_to(node)
             })
             .collect();
 
@@ -395,6 +1558,572 @@ impl<E: StorageEngine> GraphDatabase<E> {
         self.engine.all_nodes()
     }
 
+    /// 按过滤条件扫描节点（过滤下推到存储层）
+    pub fn scan_nodes(
+        &self,
+        filter: crate::storage::NodeFilter,
+    ) -> impl Iterator<Item = crate::storage::StoredNode> + '_ {
+        self.engine.scan_nodes(filter)
+    }
+
+    /// 按关系 ID 升序分页读取关系：返回 ID 大于 `after` 的至多 `limit` 条
+    ///
+    /// 把上一页最后一条关系的 ID 作为下一次的 `after` 即可遍历全部关系
+    pub fn rels_page(&self, after: Option<RelId>, limit: usize) -> Vec<Relationship> {
+        self.engine
+            .scan_rels(after, limit)
+            .into_iter()
+            .map(|sr| Relationship {
+                id: sr.id,
+                start: sr.start,
+                end: sr.end,
+                typ: sr.typ,
+                props: sr.props,
+                directed: sr.directed,
+            })
+            .collect()
+    }
+
+    /// 估计带 `label` 标签的节点中 `property` 的不同取值个数（HyperLogLog，误差约 1%）
+    ///
+    /// 一次扫描完成，内存占用固定；缺少该属性的节点不计入。
+    pub fn approx_distinct(&self, label: &str, property: &str) -> u64 {
+        let filter = crate::storage::NodeFilter::new()
+            .with_label(label)
+            .with_prop(property, crate::storage::PropPredicate::Exists);
+        let mut sketch = crate::graph::hll::HyperLogLog::default();
+        for node in self.scan_nodes(filter) {
+            if let Some(value) = node.props.get(property) {
+                sketch.insert_value(value);
+            }
+        }
+        sketch.estimate()
+    }
+
+    /// 节点数量（由存储引擎维护，无需遍历）
+    pub fn node_count(&self) -> usize {
+        self.engine.node_count()
+    }
+
+    /// 关系数量（由存储引擎维护，无需遍历）
+    pub fn rel_count(&self) -> usize {
+        self.engine.rel_count()
+    }
+
+    // ========== 三角形计数维护 ==========
+
+    /// 图中三角形总数（O(1)）
+    ///
+    /// 关系视为无向、忽略自环和平行关系，与 `algorithms::count_triangles` 结果一致，
+    /// 但在每次增删关系时按端点的公共邻居增量维护，无需重新遍历全图。
+    pub fn triangle_count(&self) -> usize {
+        self.triangles
+    }
+
+    /// 全量重算三角形数量，用于无法逐条维护的事务提交和回滚
+    fn recount_triangles(&mut self) {
+        self.triangles = crate::algorithms::count_triangles(self);
+    }
+
+    /// 节点的邻居集合（不区分方向，排除自环）
+    fn neighbor_set(&self, node: NodeId) -> HashSet<NodeId> {
+        self.engine
+            .outgoing_rels(node)
+            .map(|r| r.end)
+            .chain(self.engine.incoming_rels(node).map(|r| r.start))
+            .filter(|&n| n != node)
+            .collect()
+    }
+
+    /// 两个节点之间是否有任意方向的关系
+    fn adjacent(&self, u: NodeId, v: NodeId) -> bool {
+        self.engine.outgoing_rels(u).any(|r| r.end == v)
+            || self.engine.outgoing_rels(v).any(|r| r.end == u)
+    }
+
+    /// u 与 v 的公共邻居数量，`pending` 为尚未写入存储的额外邻接
+    fn common_neighbors(
+        &self,
+        u: NodeId,
+        v: NodeId,
+        pending: &HashMap<NodeId, HashSet<NodeId>>,
+    ) -> usize {
+        let with_pending = |node: NodeId| {
+            let mut set = self.neighbor_set(node);
+            if let Some(extra) = pending.get(&node) {
+                set.extend(extra.iter().copied());
+            }
+            set.remove(&u);
+            set.remove(&v);
+            set
+        };
+        let nu = with_pending(u);
+        let nv = with_pending(v);
+        nu.intersection(&nv).count()
+    }
+
+    /// 新增关系 u-v 会新形成的三角形数量
+    fn triangle_delta(
+        &self,
+        u: NodeId,
+        v: NodeId,
+        pending: &HashMap<NodeId, HashSet<NodeId>>,
+    ) -> usize {
+        let already = self.adjacent(u, v)
+            || pending.get(&u).is_some_and(|set| set.contains(&v));
+        if u == v || already {
+            return 0;
+        }
+        self.common_neighbors(u, v, pending)
+    }
+
+    /// 包含指定节点的三角形数量
+    fn triangles_at(&self, node: NodeId) -> usize {
+        let neighbors: Vec<NodeId> = self.neighbor_set(node).into_iter().collect();
+        let mut count = 0;
+        for (i, &a) in neighbors.iter().enumerate() {
+            for &b in &neighbors[i + 1..] {
+                if self.adjacent(a, b) {
+                    count += 1;
+                }
+            }
+        }
+        count
+    }
+
+    // ========== 连通分量维护 ==========
+
+    /// 节点所在弱连通分量的代表节点，节点不存在时返回 None
+    ///
+    /// 关系视为无向。两个节点的代表相同当且仅当它们连通；代表节点本身可能随写入改变，
+    /// 不应长期保存。新增关系时并查集增量合并，查询接近 O(1)；删除关系（或节点）
+    /// 可能拆开分量，只把结构标记为失效，下次查询时按全图重建一次。
+    pub fn component_of(&self, node: NodeId) -> Option<NodeId> {
+        self.engine.get_node(node)?;
+        let mut components = self.components.lock().unwrap_or_else(PoisonError::into_inner);
+        Some(components.find(node, || {
+            self.engine
+                .all_nodes()
+                .flat_map(|n| self.engine.outgoing_rels(n.id).map(|r| (r.start, r.end)))
+                .collect()
+        }))
+    }
+
+    /// 两个节点是否在同一个弱连通分量中
+    pub fn same_component(&self, a: NodeId, b: NodeId) -> bool {
+        match (self.component_of(a), self.component_of(b)) {
+            (Some(ra), Some(rb)) => ra == rb,
+            _ => false,
+        }
+    }
+
+    /// 连通分量结构被全量重建的次数
+    pub fn component_rebuilds(&self) -> usize {
+        self.components.lock().unwrap_or_else(PoisonError::into_inner).rebuilds()
+    }
+
+    fn components_mut(&mut self) -> &mut ComponentTracker {
+        self.components.get_mut().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    // ========== 索引建议 ==========
+
+    /// 记录一次按标签属性的等值过滤（由 Cypher 执行器调用）
+    pub(crate) fn trace_filter(&self, label: &str, property: &str) {
+        self.tracer.lock().unwrap_or_else(PoisonError::into_inner).record(label, property);
+    }
+
+    /// 最近记录的属性过滤，按时间从旧到新
+    pub fn query_traces(&self) -> Vec<FilterTrace> {
+        self.tracer.lock().unwrap_or_else(PoisonError::into_inner).entries().cloned().collect()
+    }
+
+    /// 设置保留的过滤记录条数（默认 [`DEFAULT_TRACE_CAPACITY`](crate::graph::advisor::DEFAULT_TRACE_CAPACITY)，0 表示不记录）
+    pub fn set_trace_capacity(&mut self, capacity: usize) {
+        self.tracer.get_mut().unwrap_or_else(PoisonError::into_inner).set_capacity(capacity);
+    }
+
+    pub fn clear_query_traces(&mut self) {
+        self.tracer.get_mut().unwrap_or_else(PoisonError::into_inner).clear();
+    }
+
+    /// 根据最近的查询过滤推荐值得建立的标签+属性索引，按估计收益降序
+    ///
+    /// 只考虑尚未建索引的属性。对每个候选属性统计标签下各属性值的出现次数（直方图），
+    /// 估计一次等值过滤平均命中的比例 `selectivity = Σ(c_v / n)²`，收益为
+    /// `过滤次数 × n × (1 - selectivity)`，即建索引后少检查的节点数；收益为 0 的不返回
+    /// （例如所有节点取值相同）。需要遍历一次全部节点。
+    pub fn suggest_indexes(&self) -> Vec<IndexSuggestion> {
+        let mut counts: HashMap<(String, String), usize> = HashMap::new();
+        for trace in self.query_traces() {
+            if !self.schema.should_index(&trace.label, &trace.property) {
+                *counts.entry((trace.label, trace.property)).or_default() += 1;
+            }
+        }
+        if counts.is_empty() {
+            return Vec::new();
+        }
+
+        // 每个候选属性的标签节点数和属性值直方图（按编码后的值分桶）
+        type Histogram = (usize, HashMap<Vec<u8>, usize>);
+        let mut histograms: HashMap<&(String, String), Histogram> =
+            counts.keys().map(|key| (key, (0, HashMap::new()))).collect();
+        for node in self.engine.all_nodes() {
+            for (key, (total, histogram)) in histograms.iter_mut() {
+                if !node.labels.contains(&key.0) {
+                    continue;
+                }
+                *total += 1;
+                if let Some(value) = node.props.get(&key.1) {
+                    let bucket = bincode::serialize(value).unwrap_or_default();
+                    *histogram.entry(bucket).or_default() += 1;
+                }
+            }
+        }
+
+        let mut suggestions: Vec<IndexSuggestion> = histograms
+            .into_iter()
+            .filter(|(_, (total, _))| *total > 0)
+            .map(|(key, (total, histogram))| {
+                let n = total as f64;
+                let selectivity: f64 = histogram.values().map(|&c| (c as f64 / n).powi(2)).sum();
+                let query_count = counts[key];
+                IndexSuggestion {
+                    label: key.0.clone(),
+                    property: key.1.clone(),
+                    query_count,
+                    label_nodes: total,
+                    selectivity,
+                    estimated_benefit: query_count as f64 * n * (1.0 - selectivity),
+                }
+            })
+            .filter(|s| s.estimated_benefit > 0.0)
+            .collect();
+        suggestions.sort_by(|a, b| {
+            b.estimated_benefit
+                .total_cmp(&a.estimated_benefit)
+                .then_with(|| (&a.label, &a.property).cmp(&(&b.label, &b.property)))
+        });
+        suggestions
+    }
+
+    // ========== 推荐 ==========
+
+    /// "朋友的朋友"推荐
+    ///
+    /// 沿 `rel_type` 关系（不区分方向）找出距离 `node` 两跳、且尚未与其直接相连的节点，
+    /// 按共同邻居数量降序排列（数量相同按节点 ID 升序），最多返回 `limit` 个
+    /// `(节点, 共同邻居数)`。
+    pub fn recommend(&self, node: NodeId, rel_type: &str, limit: usize) -> Vec<(NodeId, usize)> {
+        let friends = self.typed_neighbor_set(node, rel_type);
+
+        let mut mutual: HashMap<NodeId, usize> = HashMap::new();
+        for &friend in &friends {
+            for candidate in self.typed_neighbor_set(friend, rel_type) {
+                if candidate != node && !friends.contains(&candidate) {
+                    *mutual.entry(candidate).or_insert(0) += 1;
+                }
+            }
+        }
+
+        let mut ranked: Vec<(NodeId, usize)> = mutual.into_iter().collect();
+        ranked.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        ranked.truncate(limit);
+        ranked
+    }
+
+    /// 通过指定类型关系相连的邻居集合（不区分方向，排除自环）
+    fn typed_neighbor_set(&self, node: NodeId, rel_type: &str) -> HashSet<NodeId> {
+        self.engine
+            .outgoing_rels(node)
+            .filter(|r| r.typ == rel_type)
+            .map(|r| r.end)
+            .chain(
+                self.engine
+                    .incoming_rels(node)
+                    .filter(|r| r.typ == rel_type)
+                    .map(|r| r.start),
+            )
+            .filter(|&n| n != node)
+            .collect()
+    }
+
+    // ========== 图合并 ==========
+
+    /// 切换存储引擎后续分配 ID 的策略
+    ///
+    /// 目前只有 `MemStore` 支持 `IdStrategy::Random`，其他存储引擎返回错误且保持顺序分配。
+    pub fn set_id_strategy(&mut self, strategy: IdStrategy) -> Result<(), StorageError> {
+        self.engine.set_id_strategy(strategy)
+    }
+
+    /// 把另一个图的全部节点和关系按原 ID 复制到当前图
+    ///
+    /// 先检查所有 ID，任意一个已存在时返回 `StorageError::IdConflict` 且不写入任何数据；
+    /// 成功时返回复制的 `(节点数, 关系数)`。两个图都使用随机 ID 时几乎不会冲突。
+    pub fn merge_from<F: StorageEngine>(
+        &mut self,
+        other: &GraphDatabase<F>,
+    ) -> Result<(usize, usize), StorageError> {
+        let SnapshotData { nodes, rels } = other.snapshot_data();
+        self.restore_all(nodes, rels)
+    }
+
+    /// 按 ID 排序的全部节点和关系
+    fn snapshot_data(&self) -> SnapshotData {
+        let mut nodes: Vec<_> = self.engine.all_nodes().collect();
+        nodes.sort_unstable_by_key(|n| n.id);
+        // 无向关系会在两端各出现一次，按关系 ID 去重
+        let mut rel_ids = HashSet::new();
+        let rels: Vec<_> = nodes
+            .iter()
+            .flat_map(|n| self.engine.outgoing_rels(n.id))
+            .filter(|r| rel_ids.insert(r.id))
+            .collect();
+        SnapshotData { nodes, rels }
+    }
+
+    /// 把整库快照写入 `writer`，可选压缩
+    ///
+    /// 快照包含全部节点和关系（保留 ID、方向和属性），不包含索引；
+    /// 用 [`import_snapshot`](Self::import_snapshot) 恢复时按 schema 重新建立索引。
+    pub fn export_snapshot<W: std::io::Write>(
+        &self,
+        writer: W,
+        compression: SnapshotCompression,
+    ) -> Result<(), StorageError> {
+        write_snapshot(writer, &self.snapshot_data(), compression)
+    }
+
+    /// 从 [`export_snapshot`](Self::export_snapshot) 写出的快照按原 ID 恢复节点和关系
+    ///
+    /// 压缩格式自动识别。与 [`merge_from`](Self::merge_from) 一样，任意 ID 已存在时
+    /// 返回 `StorageError::IdConflict` 且不写入任何数据；成功时返回 `(节点数, 关系数)`。
+    pub fn import_snapshot<R: std::io::Read>(&mut self, reader: R) -> Result<(usize, usize), StorageError> {
+        let SnapshotData { nodes, rels } = read_snapshot(reader)?;
+        self.restore_all(nodes, rels)
+    }
+
+    /// 先检查 ID 冲突，再按原 ID 写入全部节点和关系
+    fn restore_all(
+        &mut self,
+        nodes: Vec<crate::storage::StoredNode>,
+        rels: Vec<crate::storage::StoredRel>,
+    ) -> Result<(usize, usize), StorageError> {
+        if let Some(node) = nodes.iter().find(|n| self.engine.get_node(n.id).is_some()) {
+            return Err(StorageError::IdConflict(node.id));
+        }
+        if let Some(rel) = rels.iter().find(|r| self.engine.get_rel(r.id).is_some()) {
+            return Err(StorageError::IdConflict(rel.id));
+        }
+
+        let counts = (nodes.len(), rels.len());
+        for node in nodes {
+            self.restore_node(node.id, node.labels, node.props)?;
+        }
+        for rel in rels {
+            self.restore_rel(rel)?;
+        }
+        Ok(counts)
+    }
+
+    /// 以指定 ID 写入节点，维护索引、缓存并通知监听器
+    fn restore_node(
+        &mut self,
+        id: NodeId,
+        labels: Vec<String>,
+        props: Properties,
+    ) -> Result<(), StorageError> {
+        self.record_node(id);
+        self.engine.insert_node_with_id(id, labels.clone(), props.clone())?;
+        self.index_node(id, &labels, &props);
+        Self::refresh_range_index(&mut self.index, id, &labels, &Properties::new(), &props);
+
+        #[cfg(feature = "caching")]
+        if let Some(cache) = &self.cache {
+            cache.on_node_created(id);
+        }
+
+        if !self.listeners.is_empty() {
+            let node = Node { id, labels, props };
+            self.notify(move |l| l.on_node_created(&node));
+        }
+        Ok(())
+    }
+
+    /// 以指定 ID 写入关系，维护三角形计数、缓存并通知监听器
+    fn restore_rel(&mut self, rel: crate::storage::StoredRel) -> Result<(), StorageError> {
+        #[cfg(feature = "caching")]
+        if let Some(cache) = &self.cache {
+            cache.on_rel_created(rel.id, rel.start, rel.end);
+        }
+
+        let (id, start, end) = (rel.id, rel.start, rel.end);
+        self.record_rel(id);
+        let delta = self.triangle_delta(start, end, &HashMap::new());
+        self.engine.insert_rel_with_id(rel)?;
+        self.triangles += delta;
+        self.components_mut().on_rel_created(start, end);
+        self.notify_rel_created(id);
+        Ok(())
+    }
+
+    // ========== 原子执行 ==========
+
+    /// 原子地执行 `f`：`f` 返回错误时把图恢复到执行前的状态
+    ///
+    /// 执行期间只记录被改动的节点和关系在改动前的状态（撤销日志），开销与 `f` 的写入量成正比；
+    /// 失败时按日志撤销这些改动，索引和三角形计数随之维护。监听器事件暂存到执行成功后才发出，
+    /// 失败时既不发出原有事件，也不发出撤销产生的事件。可以嵌套调用，内层失败只撤销内层的改动。
+    /// `&mut self` 保证执行期间没有其他写入。
+    ///
+    /// 撤销时存储引擎出错则返回由 `GraphError::Storage` 转换的错误，此时图可能只恢复了一部分。
+    pub fn atomically<T, Er>(&mut self, f: impl FnOnce(&mut Self) -> Result<T, Er>) -> Result<T, Er>
+    where
+        Er: From<GraphError>,
+    {
+        let outer = self.undo.replace(UndoLog::default());
+        let result = f(self);
+        let log = self.undo.take().unwrap_or_default();
+        match result {
+            Ok(value) => {
+                match outer {
+                    Some(mut outer) => {
+                        outer.absorb(log);
+                        self.undo = Some(outer);
+                    }
+                    None => {
+                        for event in &log.events {
+                            self.dispatch(event);
+                        }
+                    }
+                }
+                Ok(value)
+            }
+            Err(e) => {
+                // 撤销产生的写入记到一个丢弃的日志里，不通知监听器
+                self.undo = Some(UndoLog::default());
+                let undone = self.undo_changes(log);
+                self.undo = outer;
+                undone.map_err(|e| GraphError::Storage(format!("failed to roll back atomic operation: {:?}", e)))?;
+                Err(e)
+            }
+        }
+    }
+
+    /// 按撤销日志把改动过的节点和关系恢复为改动前的状态
+    fn undo_changes(&mut self, log: UndoLog) -> Result<(), StorageError> {
+        let UndoLog { nodes: saved_nodes, rels: mut saved_rels, .. } = log;
+
+        // 与改动前不同的节点先删除再按原 ID 恢复；删除会级联删除未被改动的关系，先记下以便恢复
+        let stale: Vec<StoredNode> = saved_nodes
+            .iter()
+            .filter_map(|(&id, saved)| {
+                let current = self.engine.get_node(id)?;
+                (saved.as_ref() != Some(&current)).then_some(current)
+            })
+            .collect();
+        for node in &stale {
+            for rel in self.engine.outgoing_rels(node.id).chain(self.engine.incoming_rels(node.id)) {
+                saved_rels.entry(rel.id).or_insert(Some(rel));
+            }
+        }
+
+        for (&id, saved) in &saved_rels {
+            if self.engine.get_rel(id).is_some_and(|current| saved.as_ref() != Some(&current)) {
+                self.delete_rel(id);
+            }
+        }
+        for node in &stale {
+            self.unindex_and_delete(node);
+        }
+
+        let mut nodes: Vec<StoredNode> = saved_nodes
+            .into_values()
+            .flatten()
+            .filter(|n| self.engine.get_node(n.id).is_none())
+            .collect();
+        nodes.sort_unstable_by_key(|n| n.id);
+        for node in nodes {
+            self.restore_node(node.id, node.labels, node.props)?;
+        }
+        let mut rels: Vec<StoredRel> = saved_rels
+            .into_values()
+            .flatten()
+            .filter(|r| self.engine.get_rel(r.id).is_none())
+            .collect();
+        rels.sort_unstable_by_key(|r| r.id);
+        for rel in rels {
+            self.restore_rel(rel)?;
+        }
+        Ok(())
+    }
+
+    // ========== 副本回放 ==========
+
+    /// 已回放到的变更序号（从未回放时为 0）
+    pub fn applied_seq(&self) -> u64 {
+        self.applied_seq
+    }
+
+    /// 把主库的一条变更记录回放到当前数据库（只读副本），保留原 ID
+    ///
+    /// 记录必须按序号连续回放：序号不大于 [`applied_seq`](Self::applied_seq) 的记录视为重复，
+    /// 直接忽略并返回 `Ok`；跳过了中间序号时返回 `GraphError::SequenceGap`，不做任何修改。
+    /// 主库日志已截断导致缺口时，副本需要重新全量同步。
+    pub fn apply_change(&mut self, record: ChangeRecord) -> Result<(), GraphError> {
+        let expected = self.applied_seq + 1;
+        if record.seq < expected {
+            return Ok(());
+        }
+        if record.seq > expected {
+            return Err(GraphError::SequenceGap { expected, found: record.seq });
+        }
+
+        let storage_err = |e: StorageError| GraphError::Storage(format!("{:?}", e));
+        match record.change {
+            Change::NodeCreated(node) => {
+                self.restore_node(node.id, node.labels, node.props).map_err(storage_err)?
+            }
+            Change::NodeUpdated(node) => {
+                let old = self.engine.get_node(node.id).ok_or(GraphError::NotFound)?;
+                Self::unindex_node_into(&mut self.index, &self.schema, old.id, &old.labels, &old.props);
+                self.update_node_props(node.id, node.props.clone());
+                let mut props = old.props;
+                props.extend(node.props);
+                Self::index_node_into(&mut self.index, &self.schema, old.id, &old.labels, &props);
+            }
+            Change::NodeDeleted(id) => {
+                if !self.delete_node(id) {
+                    return Err(GraphError::NotFound);
+                }
+            }
+            Change::RelCreated(rel) => self
+                .restore_rel(crate::storage::StoredRel {
+                    id: rel.id,
+                    start: rel.start,
+                    end: rel.end,
+                    typ: rel.typ,
+                    props: rel.props,
+                    directed: rel.directed,
+                })
+                .map_err(storage_err)?,
+            Change::RelUpdated(rel) => {
+                if !self.update_rel_props(rel.id, rel.props) {
+                    return Err(GraphError::NotFound);
+                }
+            }
+            Change::RelDeleted(id) => {
+                if !self.delete_rel(id) {
+                    return Err(GraphError::NotFound);
+                }
+            }
+        }
+
+        self.applied_seq = record.seq;
+        Ok(())
+    }
+
     // ========== 复合索引管理 ==========
 
     /// 创建复合索引
@@ -447,7 +2176,16 @@ impl<E: StorageEngine> GraphDatabase<E> {
     /// # 返回
     /// 如果成功删除返回 true，如果索引不存在返回 false
     pub fn drop_composite_index(&mut self, name: &str) -> bool {
-        self.schema.remove_composite_index(name)
+        let Some((label, properties)) = self.schema.get_all_composite_indexes().get(name).cloned() else {
+            return false;
+        };
+        self.schema.remove_composite_index(name);
+        // 另一个同字段的复合索引仍在使用这些索引项时保留
+        let props_refs: Vec<&str> = properties.iter().map(|p| p.as_str()).collect();
+        if self.schema.get_composite_index(&label, &props_refs).is_none() {
+            self.index.drop_composite(&label, &properties);
+        }
+        true
     }
 
     /// 使用复合索引查询节点
@@ -496,6 +2234,144 @@ impl<E: StorageEngine> GraphDatabase<E> {
         )
     }
 
+    /// 列出所有索引（单属性、复合、全文、范围）及其统计信息，按标识排序
+    pub fn list_indexes(&self) -> Vec<crate::index::IndexInfo> {
+        use crate::index::{IndexInfo, IndexKind};
+
+        let stats = self.index.field_stats();
+        let info = |id: String, kind: IndexKind, label: &str, properties: Vec<String>| {
+            let stats = stats
+                .get(&(kind, label.to_string(), properties.clone()))
+                .cloned()
+                .unwrap_or_default();
+            IndexInfo { id, kind, label: label.to_string(), properties, stats }
+        };
+        let field_id = |kind: IndexKind, label: &str, prop: &str| format!("{}:{}.{}", kind.as_str(), label, prop);
+
+        let mut indexes = Vec::new();
+        for (label, prop) in self.schema.indexed_properties() {
+            let id = field_id(IndexKind::Property, label, prop);
+            indexes.push(info(id, IndexKind::Property, label, vec![prop.clone()]));
+        }
+        for (name, (label, properties)) in self.schema.get_all_composite_indexes() {
+            let id = format!("{}:{}", IndexKind::Composite.as_str(), name);
+            indexes.push(info(id, IndexKind::Composite, label, properties.clone()));
+        }
+        for (kind, fields) in [
+            (IndexKind::FullText, self.schema.fulltext_fields().collect::<Vec<_>>()),
+            (IndexKind::Range, self.schema.range_fields().collect()),
+        ] {
+            for (label, prop) in fields {
+                let id = field_id(kind, label, prop);
+                indexes.push(info(id, kind, label, vec![prop.clone()]));
+            }
+        }
+        indexes.sort_by(|a, b| a.id.cmp(&b.id));
+        indexes
+    }
+
+    /// 按 [`list_indexes`](Self::list_indexes) 给出的标识删除索引
+    ///
+    /// 索引不存在时返回 false
+    pub fn drop_index(&mut self, id: &str) -> bool {
+        let Some((kind, rest)) = id.split_once(':') else {
+            return false;
+        };
+        if kind == "composite" {
+            return self.drop_composite_index(rest);
+        }
+        let Some((label, prop)) = rest.split_once('.') else {
+            return false;
+        };
+        match kind {
+            "property" => {
+                if !self.schema.remove_index(label, prop) {
+                    return false;
+                }
+                self.index.drop_property(label, prop);
+            }
+            "fulltext" => {
+                if !self.schema.remove_fulltext_field(label, prop) {
+                    return false;
+                }
+                self.index.drop_fulltext(label, prop);
+            }
+            "range" => {
+                if !self.schema.remove_range_field(label, prop) {
+                    return false;
+                }
+                self.index.drop_range(label, prop);
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    /// 扫描存储层，按当前 schema 以及已有的全文/范围索引字段构建一份全新的索引
+    fn build_indexes_from_storage(&self) -> PropertyIndex {
+        let fulltext_fields: Vec<_> = self.schema.fulltext_fields().collect();
+        let range_fields: Vec<_> = self.schema.range_fields().collect();
+        let mut index = PropertyIndex::new();
+        for ((label, prop), analyzer) in self.index.fulltext().analyzers() {
+            index.set_fulltext_analyzer(label, prop, analyzer.clone());
+        }
+
+        let mut batch = IndexBatch::new();
+        for node in self.all_stored_nodes() {
+            Self::index_node_into_batch(&mut batch, &self.schema, node.id, &node.labels, &node.props);
+
+            for label in &node.labels {
+                for (field_label, prop) in fulltext_fields.iter().copied() {
+                    if field_label == label {
+                        if let Some(Value::Text(text)) = node.props.get(prop) {
+                            index.add_fulltext(label, prop, text, node.id);
+                        }
+                    }
+                }
+                for (field_label, prop) in range_fields.iter().copied() {
+                    if field_label == label {
+                        if let Some(value) = node.props.get(prop) {
+                            index.add_range(label, prop, value, node.id);
+                        }
+                    }
+                }
+            }
+        }
+        index.bulk_build(batch);
+
+        index
+    }
+
+    /// 清空并重建所有内存索引（单属性、复合、全文、范围）
+    ///
+    /// 所有索引都按 schema 登记的字段重建：全文和范围字段在调用
+    /// [`add_fulltext_index`](Self::add_fulltext_index)、[`add_range_index`](Self::add_range_index)
+    /// 时登记，因此即使现有索引内容丢失，也会为存储中所有匹配的节点重新建立。
+    pub fn rebuild_all_indexes(&mut self) {
+        self.index = self.build_indexes_from_storage();
+    }
+
+    /// 校验内存索引与存储层是否一致
+    ///
+    /// 返回索引中多余的项（`StaleEntry`）和缺失的项（`MissingEntry`），按索引项排序
+    pub fn verify_indexes(&self) -> Vec<crate::index::IndexInconsistency> {
+        use crate::index::IndexInconsistency;
+
+        let actual = self.index.entries();
+        let expected = self.build_indexes_from_storage().entries();
+
+        let mut stale: Vec<_> = actual.difference(&expected).cloned().collect();
+        let mut missing: Vec<_> = expected.difference(&actual).cloned().collect();
+        stale.sort();
+        missing.sort();
+
+        stale
+            .into_iter()
+            .map(IndexInconsistency::StaleEntry)
+            .chain(missing.into_iter().map(IndexInconsistency::MissingEntry))
+            .collect()
+    }
+
     // ========== 高级索引 API ==========
 
     /// 添加全文索引
@@ -521,6 +2397,7 @@ impl<E: StorageEngine> GraphDatabase<E> {
         property_name: &str,
         node_id: NodeId,
     ) {
+        self.schema.add_fulltext_field(label, property_name);
         if let Some(node) = self.engine.get_node(node_id) {
             // 获取属性值
             if let Some(Value::Text(text)) = node.props.get(property_name) {
@@ -529,6 +2406,108 @@ impl<E: StorageEngine> GraphDatabase<E> {
         }
     }
 
+    /// 为 (label, property_name) 注册全文分析器（大小写、停用词、词干提取、分词方式）
+    ///
+    /// 之后对该字段的索引和搜索都使用这个分析器；已经索引的文本不会自动重新分析，
+    /// 需要时可以调用 [`rebuild_all_indexes`](Self::rebuild_all_indexes)。
+    pub fn set_fulltext_analyzer(&mut self, label: &str, property_name: &str, analyzer: TextAnalyzer) {
+        self.index.set_fulltext_analyzer(label, property_name, analyzer);
+    }
+
+    /// 把全文索引保存到存储引擎，之后用同一存储打开数据库时自动恢复
+    ///
+    /// 同时保存登记的全文字段，以及存储中这些字段当前文本的指纹；重新打开时若文本在保存后
+    /// 被新增、修改或删除，按登记的字段和保存的分析器从存储重建全文索引，而不是恢复过期的数据。
+    /// 存储引擎不支持持久化索引（如内存存储）时返回错误
+    pub fn persist_fulltext_index(&mut self) -> Result<(), StorageError> {
+        let mut fields: Vec<(String, String)> = self.schema.fulltext_fields().cloned().collect();
+        fields.sort();
+        let mut data = self.fulltext_fingerprint(&fields).to_le_bytes().to_vec();
+        bincode::serialize_into(&mut data, &fields).map_err(|e| StorageError::Other(e.to_string()))?;
+        self.index.fulltext().save(&mut data).map_err(|e| StorageError::Other(e.to_string()))?;
+        self.engine.put_index_data(FULLTEXT_INDEX_KEY, data)
+    }
+
+    /// 从存储引擎恢复全文索引；指纹与存储内容不符时按登记的字段重建
+    ///
+    /// 没有保存过时保持空索引；数据损坏或格式版本不符时记录警告并保持空索引。
+    fn load_fulltext_index(&mut self) {
+        let Some(data) = self.engine.get_index_data(FULLTEXT_INDEX_KEY) else {
+            return;
+        };
+        let Some((fingerprint, mut rest)) = data.split_first_chunk::<8>() else {
+            log::warn!("ignoring persisted full-text index: truncated data");
+            return;
+        };
+        let loaded = bincode::deserialize_from::<_, Vec<(String, String)>>(&mut rest)
+            .and_then(|fields| Ok((fields, FullTextIndex::load(rest)?)));
+        let (saved_fields, index) = match loaded {
+            Ok(loaded) => loaded,
+            Err(e) => {
+                log::warn!("ignoring persisted full-text index: {}", e);
+                return;
+            }
+        };
+        for (label, prop) in &saved_fields {
+            self.schema.add_fulltext_field(label, prop);
+        }
+        let mut fields: Vec<(String, String)> = self.schema.fulltext_fields().cloned().collect();
+        fields.sort();
+        if fields == saved_fields && u64::from_le_bytes(*fingerprint) == self.fulltext_fingerprint(&fields) {
+            self.index.set_fulltext(index);
+        } else {
+            self.index.set_fulltext(self.rebuild_fulltext(&fields, &index));
+        }
+    }
+
+    /// 存储中各全文字段当前文本的指纹
+    ///
+    /// 按节点 ID 顺序把每个节点在 `fields`（已排序）上的文本并入哈希，
+    /// 文本的新增、修改和删除都会改变结果
+    fn fulltext_fingerprint(&self, fields: &[(String, String)]) -> u64 {
+        let mut docs: Vec<StoredNode> = self
+            .engine
+            .all_nodes()
+            .filter(|node| fields.iter().any(|(label, _)| node.labels.contains(label)))
+            .collect();
+        docs.sort_unstable_by_key(|node| node.id);
+
+        let mut hash = FNV_OFFSET;
+        for node in docs {
+            for (label, prop) in fields {
+                if !node.labels.contains(label) {
+                    continue;
+                }
+                if let Some(Value::Text(text)) = node.props.get(prop) {
+                    hash = fnv_feed(hash, &node.id.to_le_bytes());
+                    hash = fnv_feed(hash, label.as_bytes());
+                    hash = fnv_feed(hash, prop.as_bytes());
+                    hash = fnv_feed(hash, text.as_bytes());
+                }
+            }
+        }
+        hash
+    }
+
+    /// 沿用 `stale` 的分析器，为存储中所有匹配 `fields` 的节点重新建立全文索引
+    fn rebuild_fulltext(&self, fields: &[(String, String)], stale: &FullTextIndex) -> FullTextIndex {
+        let mut index = FullTextIndex::new();
+        for ((label, prop), analyzer) in stale.analyzers() {
+            index.set_analyzer(label, prop, analyzer.clone());
+        }
+        for node in self.engine.all_nodes() {
+            for (label, prop) in fields {
+                if !node.labels.contains(label) {
+                    continue;
+                }
+                if let Some(Value::Text(text)) = node.props.get(prop) {
+                    index.add(label, prop, text, node.id);
+                }
+            }
+        }
+        index
+    }
+
     /// 全文搜索（OR 查询）
     ///
     /// 返回包含任意搜索词的节点
@@ -567,6 +2546,7 @@ impl<E: StorageEngine> GraphDatabase<E> {
         property_name: &str,
         node_id: NodeId,
     ) {
+        self.schema.add_range_field(label, property_name);
         if let Some(node) = self.engine.get_node(node_id) {
             if let Some(value) = node.props.get(property_name) {
                 self.index.add_range(label, property_name, value, node_id);
@@ -605,6 +2585,20 @@ impl<E: StorageEngine> GraphDatabase<E> {
         self.index.range_between(label, property_name, &min_value, &max_value)
     }
 
+    /// 取 `property_name` 值最大（`descending` 为 true）或最小的 `n` 个节点
+    ///
+    /// 直接按范围索引的有序结构遍历，只访问前 `n` 个；
+    /// 需要先用 [`add_range_index`](Self::add_range_index) 为这些节点建立索引。
+    pub fn top_n(
+        &self,
+        label: &str,
+        property_name: &str,
+        n: usize,
+        descending: bool,
+    ) -> Vec<NodeId> {
+        self.index.range_top_n(label, property_name, n, descending)
+    }
+
     // ========== 事务支持 ==========
 
     /// 开始一个新事务（使用默认配置）
@@ -614,12 +2608,228 @@ impl<E: StorageEngine> GraphDatabase<E> {
 
     /// 开始一个新事务（使用自定义配置）
     pub fn begin_tx_with_config(&mut self, config: TransactionConfig) -> u64 {
-        self.transactions.begin_transaction().id
+        self.transactions.begin_transaction_with_config(&config).id
     }
 
     /// 提交事务
+    ///
+    /// 事务中写入的节点在提交后按 schema 重新建立属性索引
     pub fn commit_tx(&mut self, tx: TxHandle) -> Result<(), StorageError> {
-        self.engine.commit_tx(tx)
+        let ops = self.engine.tx_ops(tx).unwrap_or_default();
+        // 删除节点会级联删除关系，与关系增删一样需要重新统计三角形
+        let structural = ops.iter().any(|op| {
+            matches!(
+                op,
+                TransactionOp::CreateRel { .. }
+                    | TransactionOp::DeleteRel { .. }
+                    | TransactionOp::DeleteNode { .. }
+            )
+        });
+        let mut touched: Vec<NodeId> = ops.iter().filter_map(|op| op.affected_node()).collect();
+        touched.sort_unstable();
+        touched.dedup();
+        // 提交可能失败（例如端点已被其他事务删除），索引要等提交成功后再改
+        let before: Vec<Option<StoredNode>> = touched.iter().map(|&id| self.engine.get_node(id)).collect();
+        let events = (!self.listeners.is_empty()).then(|| self.tx_events(&ops));
+        self.record_tx_ops(&ops);
+        self.engine.commit_tx(tx)?;
+
+        if structural {
+            // 事务中的关系变更不经过增量维护，提交后全量重算
+            self.components_mut().invalidate();
+            self.recount_triangles();
+        }
+        let empty = Properties::new();
+        for (&id, old) in touched.iter().zip(before) {
+            let new = self.engine.get_node(id);
+            if let Some(node) = &new {
+                if let Some(old) = &old {
+                    Self::unindex_node_into(&mut self.index, &self.schema, id, &old.labels, &old.props);
+                }
+                Self::index_node_into(&mut self.index, &self.schema, id, &node.labels, &node.props);
+            } else if let Some(old) = &old {
+                Self::unindex_node_into(&mut self.index, &self.schema, id, &old.labels, &old.props);
+            }
+            if let Some(old) = old {
+                let new_props = new.as_ref().map_or(&empty, |n| &n.props);
+                Self::refresh_range_index(&mut self.index, id, &old.labels, &old.props, new_props);
+            }
+        }
+        if let Some(events) = events {
+            self.notify_committed(events);
+        }
+        Ok(())
+    }
+
+    /// 提交前记下事务将产生的变更事件；更新和删除只针对提交前已存在的实体
+    fn tx_events(&self, ops: &[TransactionOp]) -> TxEvents {
+        let mut events = TxEvents::default();
+        for op in ops {
+            match op {
+                TransactionOp::CreateNode { id, .. } => events.created_nodes.push(*id),
+                TransactionOp::UpdateNode { id, .. } if self.engine.get_node(*id).is_some() => {
+                    events.updated_nodes.push(*id)
+                }
+                TransactionOp::DeleteNode { id, .. } if self.engine.get_node(*id).is_some() => {
+                    // 级联删除的关系也要通知
+                    events.deleted_rels.extend(
+                        self.engine
+                            .outgoing_rels(*id)
+                            .chain(self.engine.incoming_rels(*id))
+                            .map(|r| r.id),
+                    );
+                    events.deleted_nodes.push(*id);
+                }
+                TransactionOp::CreateRel { id, .. } => events.created_rels.push(*id),
+                TransactionOp::UpdateRel { id, .. } if self.engine.get_rel(*id).is_some() => {
+                    events.updated_rels.push(*id)
+                }
+                TransactionOp::DeleteRel { id, .. } if self.engine.get_rel(*id).is_some() => {
+                    events.deleted_rels.push(*id)
+                }
+                _ => {}
+            }
+        }
+        events
+    }
+
+    /// 记下事务将要改动的节点和关系（递增版本号，原子执行期间写入撤销日志）
+    fn record_tx_ops(&mut self, ops: &[TransactionOp]) {
+        for op in ops {
+            match op {
+                TransactionOp::CreateNode { id, .. } | TransactionOp::UpdateNode { id, .. } => {
+                    self.record_node(*id)
+                }
+                TransactionOp::DeleteNode { id, .. } => self.record_node_with_rels(*id),
+                TransactionOp::CreateRel { id, .. }
+                | TransactionOp::UpdateRel { id, .. }
+                | TransactionOp::DeleteRel { id, .. } => self.record_rel(*id),
+            }
+        }
+    }
+
+    /// 按提交后的最终状态通知监听器：事务内创建又删除的实体不产生事件，
+    /// 关系删除先于节点删除，与直接写入时的事件顺序一致
+    fn notify_committed(&mut self, mut events: TxEvents) {
+        events.dedup();
+        for id in events.created_nodes {
+            if let Some(node) = self.get_node(id) {
+                self.notify(move |l| l.on_node_created(&node));
+            }
+        }
+        for id in events.updated_nodes {
+            self.notify_node_updated(id);
+        }
+        for id in events.created_rels {
+            self.notify_rel_created(id);
+        }
+        for id in events.updated_rels {
+            self.notify_rel_updated(id);
+        }
+        for id in events.deleted_rels {
+            if self.engine.get_rel(id).is_none() {
+                self.notify(move |l| l.on_rel_deleted(id));
+            }
+        }
+        for id in events.deleted_nodes {
+            if self.engine.get_node(id).is_none() {
+                self.notify(move |l| l.on_node_deleted(id));
+            }
+        }
+    }
+
+    /// 在事务中创建节点，提交前只对 [`Query::in_transaction`](crate::query::Query::in_transaction) 可见
+    pub fn create_node_in_tx(
+        &mut self,
+        tx: TxHandle,
+        labels: Vec<&str>,
+        props: Properties,
+    ) -> Result<NodeId, StorageError> {
+        let labels = labels.into_iter().map(|l| l.to_string()).collect();
+        self.engine.tx_create_node(tx, labels, props)
+    }
+
+    /// 在事务中合并更新节点属性，提交前只对事务内的查询可见
+    pub fn update_node_props_in_tx(
+        &mut self,
+        tx: TxHandle,
+        id: NodeId,
+        props: Properties,
+    ) -> Result<(), StorageError> {
+        self.engine.tx_update_node_props(tx, id, props)
+    }
+
+    /// 在事务中创建关系，两个端点在事务视角下必须存在
+    pub fn create_rel_in_tx(
+        &mut self,
+        tx: TxHandle,
+        start: NodeId,
+        end: NodeId,
+        typ: &str,
+        props: Properties,
+    ) -> Result<RelId, StorageError> {
+        self.engine.tx_create_rel(tx, start, end, typ.to_string(), props)
+    }
+
+    /// 在事务中删除节点，提交时级联删除关联关系
+    pub fn delete_node_in_tx(&mut self, tx: TxHandle, id: NodeId) -> Result<bool, StorageError> {
+        self.engine.tx_delete_node(tx, id)
+    }
+
+    /// 在事务中删除关系
+    pub fn delete_rel_in_tx(&mut self, tx: TxHandle, id: RelId) -> Result<bool, StorageError> {
+        self.engine.tx_delete_rel(tx, id)
+    }
+
+    /// 在事务视角下读取节点：能看到本事务尚未提交的写入，看不到其他事务未提交的写入
+    pub fn get_node_in_tx(&self, tx: TxHandle, id: NodeId) -> Result<Option<Node>, StorageError> {
+        Ok(self.engine.tx_get_node(tx, id)?.map(|sn| Node {
+            id: sn.id,
+            labels: sn.labels,
+            props: sn.props,
+        }))
+    }
+
+    /// 在事务视角下读取关系
+    pub fn get_rel_in_tx(&self, tx: TxHandle, id: RelId) -> Result<Option<Relationship>, StorageError> {
+        Ok(self.engine.tx_get_rel(tx, id)?.map(|sr| Relationship {
+            id: sr.id,
+            start: sr.start,
+            end: sr.end,
+            typ: sr.typ,
+            props: sr.props,
+            directed: sr.directed,
+        }))
+    }
+
+    /// 事务中尚未提交的节点写入在事务视角下的结果；`None` 表示节点已在事务中删除
+    pub(crate) fn tx_pending_nodes(
+        &self,
+        tx: TxHandle,
+    ) -> Result<HashMap<NodeId, Option<Node>>, StorageError> {
+        let mut pending: HashMap<NodeId, Option<Node>> = HashMap::new();
+        for op in self.engine.tx_ops(tx)? {
+            match op {
+                TransactionOp::CreateNode { id, labels, properties } => {
+                    pending.insert(id, Some(Node { id, labels, props: properties }));
+                }
+                TransactionOp::UpdateNode { id, new_properties: props, .. } => {
+                    let node = match pending.remove(&id) {
+                        Some(node) => node,
+                        None => self.get_node(id),
+                    };
+                    pending.insert(id, node.map(|mut node| {
+                        node.props.extend(props);
+                        node
+                    }));
+                }
+                TransactionOp::DeleteNode { id, .. } => {
+                    pending.insert(id, None);
+                }
+                TransactionOp::CreateRel { .. } | TransactionOp::DeleteRel { .. } | TransactionOp::UpdateRel { .. } => {}
+            }
+        }
+        Ok(pending)
     }
 
     /// 提交事务（使用事务管理器）
@@ -629,7 +2839,57 @@ impl<E: StorageEngine> GraphDatabase<E> {
 
     /// 回滚事务
     pub fn rollback_tx(&mut self, tx: TxHandle) -> Result<(), StorageError> {
-        self.engine.rollback_tx(tx)
+        self.engine.rollback_tx(tx)?;
+        // 回滚可能撤销任意关系变更，重新统计三角形
+        self.recount_triangles();
+        self.components_mut().invalidate();
+        Ok(())
+    }
+
+    // ========== 乐观事务 ==========
+
+    /// 开始一个乐观事务（需要存储引擎支持事务）
+    pub fn begin_optimistic(&mut self) -> TransactionResult<OptimisticTx> {
+        Ok(OptimisticTx {
+            handle: self.begin_tx()?,
+            reads: OptimisticReadContext::new(),
+        })
+    }
+
+    /// 校验乐观事务读过的节点版本并提交；有节点已被改动时回滚并返回
+    /// [`TransactionError::VersionConflict`](crate::transactions::TransactionError::VersionConflict)
+    pub fn commit_optimistic(&mut self, tx: OptimisticTx) -> TransactionResult<()> {
+        if let Err(err) = tx.reads.verify(&self.versions) {
+            self.engine.rollback_tx(tx.handle)?;
+            return Err(err);
+        }
+        Ok(self.commit_tx(tx.handle)?)
+    }
+
+    /// 放弃乐观事务中的所有写入
+    pub fn rollback_optimistic(&mut self, tx: OptimisticTx) -> TransactionResult<()> {
+        Ok(self.engine.rollback_tx(tx.handle)?)
+    }
+
+    /// 以乐观事务执行 `f`，提交时遇到版本冲突按 `config` 退避后重新执行
+    ///
+    /// 每次执行使用新的事务，`f` 返回错误时事务回滚；`f` 可能被执行多次，
+    /// 不应有事务之外的副作用
+    pub fn with_retry<T>(
+        &mut self,
+        config: &RetryConfig,
+        mut f: impl FnMut(&mut Self, &mut OptimisticTx) -> TransactionResult<T>,
+    ) -> TransactionResult<T> {
+        config.run(|| {
+            let mut tx = self.begin_optimistic()?;
+            match f(self, &mut tx) {
+                Ok(value) => self.commit_optimistic(tx).map(|_| value),
+                Err(err) => {
+                    self.rollback_optimistic(tx)?;
+                    Err(err)
+                }
+            }
+        })
     }
 
     /// 回滚事务（使用事务管理器）
@@ -652,6 +2912,30 @@ impl<E: StorageEngine> GraphDatabase<E> {
         self.transactions.cleanup_completed(keep_last)
     }
 
+    /// 捕获当前图的完整快照（可用于 `graph_diff` 比较）
+    pub fn capture_snapshot(&self, id: u64) -> crate::transactions::Snapshot {
+        use crate::transactions::{Snapshot, SnapshotNode, SnapshotRel};
+
+        let mut snapshot = Snapshot::new(id);
+        for stored_node in self.all_stored_nodes() {
+            for rel in self.neighbors_out(stored_node.id) {
+                snapshot.add_rel(SnapshotRel {
+                    id: rel.id,
+                    start: rel.start,
+                    end: rel.end,
+                    typ: rel.typ,
+                    properties: rel.props,
+                });
+            }
+            snapshot.add_node(SnapshotNode {
+                id: stored_node.id,
+                labels: stored_node.labels,
+                properties: stored_node.props,
+            });
+        }
+        snapshot
+    }
+
     /// 记录操作到事务
     pub fn record_operation(
         &mut self,
@@ -664,13 +2948,261 @@ impl<E: StorageEngine> GraphDatabase<E> {
     // ========== 更新 API ==========
 
     /// 更新节点属性（合并模式：新属性会覆盖旧属性）
+    ///
+    /// 不检查 [`PropertyLimits`]；外部输入应走 [`try_update_node_props`](Self::try_update_node_props)。
     pub fn update_node_props(&mut self, id: NodeId, props: Properties) -> bool {
-        self.engine.update_node_props(id, props)
+        self.record_node(id);
+        let old = self.engine.get_node(id);
+        let updated = self.engine.update_node_props(id, props.clone());
+        if updated {
+            if let Some(old) = old {
+                let mut merged = old.props.clone();
+                merged.extend(props);
+                // schema 索引按旧值移除、新值加入，之后按键查找的节点也能命中
+                Self::unindex_node_into(&mut self.index, &self.schema, id, &old.labels, &old.props);
+                Self::index_node_into(&mut self.index, &self.schema, id, &old.labels, &merged);
+                Self::refresh_range_index(&mut self.index, id, &old.labels, &old.props, &merged);
+            }
+            self.notify_node_updated(id);
+        }
+        updated
+    }
+
+    /// 按节点属性的新旧值维护范围索引：变化或被删除的属性按旧值移除，新值重新加入
+    fn refresh_range_index(
+        index: &mut PropertyIndex,
+        id: NodeId,
+        labels: &[String],
+        old: &Properties,
+        new: &Properties,
+    ) {
+        if !index.has_range_index() {
+            return;
+        }
+        for label in labels {
+            for (prop_name, value) in old {
+                let updated = new.get(prop_name);
+                if updated != Some(value) {
+                    index.update_range(label, prop_name, Some(value), updated, id);
+                }
+            }
+            for (prop_name, value) in new {
+                if !old.contains_key(prop_name) {
+                    index.update_range(label, prop_name, None, Some(value), id);
+                }
+            }
+        }
+    }
+
+    /// 更新节点属性，合并后的属性超过 [`PropertyLimits`] 时拒绝写入
+    ///
+    /// 节点不存在时返回 `GraphError::NotFound`。
+    pub fn try_update_node_props(&mut self, id: NodeId, props: Properties) -> Result<(), GraphError> {
+        let mut merged = self.engine.get_node(id).ok_or(GraphError::NotFound)?.props;
+        merged.extend(props.clone());
+        self.property_limits.check(&merged)?;
+        self.update_node_props(id, props);
+        Ok(())
+    }
+
+    /// 批量更新：把 `updates` 合并到所有带 `label` 且满足 `predicate` 的节点上
+    ///
+    /// 一次扫描完成，属性索引随新值增量维护；返回更新的节点数。
+    pub fn update_matching(
+        &mut self,
+        label: &str,
+        predicate: impl Fn(&Node) -> bool,
+        updates: Properties,
+    ) -> usize {
+        let matched = self.matching_nodes(label, predicate);
+        self.apply_updates(matched, updates)
+    }
+
+    /// 同 [`update_matching`](Self::update_matching)，但任一节点合并后的属性超过
+    /// [`PropertyLimits`] 时整批拒绝写入
+    pub fn try_update_matching(
+        &mut self,
+        label: &str,
+        predicate: impl Fn(&Node) -> bool,
+        updates: Properties,
+    ) -> Result<usize, GraphError> {
+        let matched = self.matching_nodes(label, predicate);
+        for node in &matched {
+            let mut merged = node.props.clone();
+            merged.extend(updates.clone());
+            self.property_limits.check(&merged)?;
+        }
+        Ok(self.apply_updates(matched, updates))
+    }
+
+    fn matching_nodes(&self, label: &str, predicate: impl Fn(&Node) -> bool) -> Vec<Node> {
+        self.scan_nodes(crate::storage::NodeFilter::new().with_label(label))
+            .map(|stored| Node {
+                id: stored.id,
+                labels: stored.labels,
+                props: stored.props,
+            })
+            .filter(|node| predicate(node))
+            .collect()
+    }
+
+    /// 把 `updates` 合并到 `matched` 中的每个节点，增量维护属性索引
+    fn apply_updates(&mut self, matched: Vec<Node>, updates: Properties) -> usize {
+        let mut updated = 0;
+        for node in matched {
+            self.record_node(node.id);
+            if !self.engine.update_node_props(node.id, updates.clone()) {
+                continue;
+            }
+            Self::unindex_node_into(&mut self.index, &self.schema, node.id, &node.labels, &node.props);
+            let mut props = node.props.clone();
+            props.extend(updates.clone());
+            Self::index_node_into(&mut self.index, &self.schema, node.id, &node.labels, &props);
+            Self::refresh_range_index(&mut self.index, node.id, &node.labels, &node.props, &props);
+            if !self.listeners.is_empty() {
+                let node = Node { id: node.id, labels: node.labels, props };
+                self.notify(move |l| l.on_node_updated(&node));
+            }
+            updated += 1;
+        }
+        updated
     }
 
     /// 更新关系属性（合并模式：新属性会覆盖旧属性）
     pub fn update_rel_props(&mut self, id: RelId, props: Properties) -> bool {
-        self.engine.update_rel_props(id, props)
+        self.record_rel(id);
+        let updated = self.engine.update_rel_props(id, props);
+        if updated {
+            self.notify_rel_updated(id);
+        }
+        updated
+    }
+
+    /// 更新关系属性，合并后的属性超过 [`PropertyLimits`] 时拒绝写入
+    ///
+    /// 关系不存在时返回 `GraphError::NotFound`。
+    pub fn try_update_rel_props(&mut self, id: RelId, props: Properties) -> Result<(), GraphError> {
+        let mut merged = self.engine.get_rel(id).ok_or(GraphError::NotFound)?.props;
+        merged.extend(props.clone());
+        self.property_limits.check(&merged)?;
+        self.update_rel_props(id, props);
+        Ok(())
+    }
+
+    /// 对 `rel_type` 关系的数值属性 `source_prop` 做 min-max 归一化，结果写入 `target_prop`
+    ///
+    /// 等价于 `normalize_edge_weights_with(.., WeightNormalization::MinMax)`
+    pub fn normalize_edge_weights(
+        &mut self,
+        rel_type: &str,
+        source_prop: &str,
+        target_prop: &str,
+    ) -> usize {
+        self.normalize_edge_weights_with(rel_type, source_prop, target_prop, WeightNormalization::MinMax)
+    }
+
+    /// 对 `rel_type` 关系的数值属性 `source_prop` 做归一化，结果以 Float 写入 `target_prop`
+    ///
+    /// 没有该属性或属性不是数值的关系被跳过。所有值相等时（分母为 0）结果统一为 0.0。
+    /// 返回写入的关系数。
+    pub fn normalize_edge_weights_with(
+        &mut self,
+        rel_type: &str,
+        source_prop: &str,
+        target_prop: &str,
+        method: WeightNormalization,
+    ) -> usize {
+        // 无向关系会从两端各返回一次，按关系 ID 去重
+        let mut seen = HashSet::new();
+        let weights: Vec<(RelId, f64)> = self
+            .engine
+            .all_nodes()
+            .flat_map(|n| self.engine.outgoing_rels(n.id))
+            .filter(|r| r.typ == rel_type && seen.insert(r.id))
+            .filter_map(|r| match r.props.get(source_prop) {
+                Some(Value::Int(i)) => Some((r.id, *i as f64)),
+                Some(Value::Float(f)) => Some((r.id, *f)),
+                _ => None,
+            })
+            .collect();
+        if weights.is_empty() {
+            return 0;
+        }
+
+        let (offset, scale) = match method {
+            WeightNormalization::MinMax => {
+                let min = weights.iter().map(|&(_, w)| w).fold(f64::INFINITY, f64::min);
+                let max = weights.iter().map(|&(_, w)| w).fold(f64::NEG_INFINITY, f64::max);
+                (min, max - min)
+            }
+            WeightNormalization::ZScore => {
+                let n = weights.len() as f64;
+                let mean = weights.iter().map(|&(_, w)| w).sum::<f64>() / n;
+                let variance = weights.iter().map(|&(_, w)| (w - mean).powi(2)).sum::<f64>() / n;
+                (mean, variance.sqrt())
+            }
+        };
+
+        for &(id, w) in &weights {
+            let normalized = if scale == 0.0 { 0.0 } else { (w - offset) / scale };
+            let mut props = Properties::new();
+            props.insert(target_prop.to_string(), Value::Float(normalized));
+            self.update_rel_props(id, props);
+        }
+        weights.len()
+    }
+
+    /// `rel_type` 关系数值属性 `prop` 的等宽直方图
+    ///
+    /// 返回 `buckets` 个 `(下界, 上界, 关系数)`，区间左闭右开，最后一个区间包含最大值。
+    /// 所有权重相等时只返回一个区间；没有权重或 `buckets` 为 0 时返回空。
+    /// 缺少该属性、属性不是数值或为 NaN / 无穷的关系被跳过。
+    pub fn weight_histogram(&self, rel_type: &str, prop: &str, buckets: usize) -> Vec<(f64, f64, usize)> {
+        if buckets == 0 {
+            return Vec::new();
+        }
+
+        // 无向关系会从两端各返回一次，按关系 ID 去重
+        let mut seen = HashSet::new();
+        let mut min = f64::INFINITY;
+        let mut max = f64::NEG_INFINITY;
+        let weights: Vec<f64> = self
+            .engine
+            .all_nodes()
+            .flat_map(|n| self.engine.outgoing_rels(n.id))
+            .filter(|r| r.typ == rel_type && seen.insert(r.id))
+            .filter_map(|r| match r.props.get(prop) {
+                Some(Value::Int(i)) => Some(*i as f64),
+                Some(Value::Float(f)) if f.is_finite() => Some(*f),
+                _ => None,
+            })
+            .inspect(|&w| {
+                min = min.min(w);
+                max = max.max(w);
+            })
+            .collect();
+        if weights.is_empty() {
+            return Vec::new();
+        }
+        if min == max {
+            return vec![(min, max, weights.len())];
+        }
+
+        let width = (max - min) / buckets as f64;
+        let mut counts = vec![0usize; buckets];
+        for w in weights {
+            let bucket = (((w - min) / width) as usize).min(buckets - 1);
+            counts[bucket] += 1;
+        }
+        counts
+            .into_iter()
+            .enumerate()
+            .map(|(i, count)| {
+                let lo = min + width * i as f64;
+                let hi = if i + 1 == buckets { max } else { min + width * (i + 1) as f64 };
+                (lo, hi, count)
+            })
+            .collect()
     }
 
     // ========== 可视化 API ==========
@@ -744,12 +3276,102 @@ impl<E: StorageEngine> GraphDatabase<E> {
         graph_view
     }
 
+    /// 把每个连通分量（忽略关系方向）分别生成一个GraphView
+    ///
+    /// 孤立节点单独成为只有一个节点的视图。视图按分量中最小的节点 ID 排序，
+    /// 视图内的节点按 ID 排序。
+    pub fn components_as_views(&self) -> Vec<crate::visualization::GraphView> {
+        let mut components: HashMap<usize, Vec<NodeId>> = HashMap::new();
+        for (node, component) in crate::algorithms::connected_components(self) {
+            components.entry(component).or_default().push(node);
+        }
+
+        let mut components: Vec<Vec<NodeId>> = components.into_values().collect();
+        for nodes in &mut components {
+            nodes.sort_unstable();
+        }
+        components.sort_unstable_by_key(|nodes| nodes[0]);
+
+        components
+            .iter()
+            .map(|nodes| self.to_subgraph_view(nodes))
+            .collect()
+    }
+
     /// 导出图为指定格式（JSON或DOT）
     pub fn export_graph(&self, format: crate::visualization::GraphFormat) -> Result<String, String> {
         let graph_view = self.to_graph_view();
         graph_view.export(format)
     }
 
+    /// 导出图为指定格式，导出前按 `filter` 剥离或脱敏属性
+    pub fn export_graph_filtered(
+        &self,
+        format: crate::visualization::GraphFormat,
+        filter: &crate::visualization::PropertyFilter,
+    ) -> Result<String, String> {
+        let mut graph_view = self.to_graph_view();
+        graph_view.filter_properties(filter);
+        graph_view.export(format)
+    }
+
+    /// 把整个图导出为指定格式并写入 `writer`
+    ///
+    /// DOT 和 GraphML 直接从存储引擎逐个读取节点和关系并写出，不构建完整的 `GraphView`，
+    /// 内存占用与图的大小无关（GraphML 需要先扫描一遍属性名）。JSON 格式仍先构建视图。
+    pub fn export_graph_to_writer(
+        &self,
+        format: crate::visualization::GraphFormat,
+        writer: impl std::io::Write,
+    ) -> std::io::Result<()> {
+        use crate::visualization::{DotWriter, GraphFormat, GraphMetadata, GraphMlSchema, GraphMlWriter};
+
+        let metadata = GraphMetadata {
+            node_count: self.node_count(),
+            edge_count: self.rel_count(),
+            ..GraphMetadata::default()
+        };
+        match format {
+            GraphFormat::Json => self.to_graph_view().export_to_writer(format, writer),
+            GraphFormat::Dot => self.stream_graph(&mut DotWriter::new(writer), &metadata),
+            GraphFormat::GraphMl => {
+                let mut schema = GraphMlSchema::new();
+                for node in self.all_stored_nodes() {
+                    schema.observe_node(&node.props);
+                    for rel in self.engine.outgoing_rels(node.id) {
+                        schema.observe_edge(&rel.props);
+                    }
+                }
+                self.stream_graph(&mut GraphMlWriter::new(writer, schema), &metadata)
+            }
+        }
+    }
+
+    /// 依次把所有节点和关系写入流式导出器
+    fn stream_graph(
+        &self,
+        exporter: &mut impl crate::visualization::StreamingExport,
+        metadata: &crate::visualization::GraphMetadata,
+    ) -> std::io::Result<()> {
+        use crate::visualization::{VisEdge, VisNode};
+
+        exporter.begin(metadata)?;
+        for node in self.all_stored_nodes() {
+            exporter.node(&VisNode::new(node.id, node.labels, node.props))?;
+        }
+        for node in self.all_stored_nodes() {
+            // 无向关系出现在两个端点的出边中，只在编号较小的端点写出
+            for rel in self.engine.outgoing_rels(node.id) {
+                if rel.directed || rel.start <= rel.end {
+                    let edge = VisEdge::new(rel.start, rel.end, rel.typ, rel.props)
+                        .with_id(format!("{}", rel.id));
+                    exporter.edge(&edge)?;
+                }
+            }
+        }
+        exporter.finish()
+    }
+
     /// 导出子图为指定格式
     pub fn export_subgraph(&self, node_ids: &[NodeId], format: crate::visualization::GraphFormat) -> Result<String, String> {
         let graph_view = self.to_subgraph_view(node_ids);
@@ -0,0 +1,95 @@
+use std::io::{Read, Write};
+
+use serde::{Deserialize, Serialize};
+
+use crate::storage::{StorageError, StoredNode, StoredRel};
+
+/// 快照文件头：未压缩的快照数据总是以它开头
+const SNAPSHOT_MAGIC: &[u8; 4] = b"RGSN";
+const SNAPSHOT_VERSION: u8 = 1;
+
+#[cfg(feature = "gzip")]
+const GZIP_MAGIC: &[u8] = &[0x1f, 0x8b];
+#[cfg(feature = "zstd")]
+const ZSTD_MAGIC: &[u8] = &[0x28, 0xb5, 0x2f, 0xfd];
+
+/// 快照导出时的压缩方式
+///
+/// 导入时根据文件头自动识别，不需要指定。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SnapshotCompression {
+    #[default]
+    None,
+    /// gzip（需要启用 `gzip` 特性）
+    #[cfg(feature = "gzip")]
+    Gzip,
+    /// zstd（需要启用 `zstd` 特性）
+    #[cfg(feature = "zstd")]
+    Zstd,
+}
+
+/// 整库快照：全部节点和关系（每条关系只出现一次）
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub(crate) struct SnapshotData {
+    pub nodes: Vec<StoredNode>,
+    pub rels: Vec<StoredRel>,
+}
+
+fn io_error(e: impl std::fmt::Display) -> StorageError {
+    StorageError::Other(e.to_string())
+}
+
+/// 把快照按 `compression` 压缩后写入 `writer`
+pub(crate) fn write_snapshot<W: Write>(
+    mut writer: W,
+    data: &SnapshotData,
+    compression: SnapshotCompression,
+) -> Result<(), StorageError> {
+    let mut raw = SNAPSHOT_MAGIC.to_vec();
+    raw.push(SNAPSHOT_VERSION);
+    bincode::serialize_into(&mut raw, data).map_err(io_error)?;
+
+    match compression {
+        SnapshotCompression::None => writer.write_all(&raw).map_err(io_error)?,
+        #[cfg(feature = "gzip")]
+        SnapshotCompression::Gzip => {
+            let mut encoder = flate2::write::GzEncoder::new(writer, flate2::Compression::default());
+            encoder.write_all(&raw).map_err(io_error)?;
+            encoder.finish().map_err(io_error)?;
+        }
+        #[cfg(feature = "zstd")]
+        SnapshotCompression::Zstd => zstd::stream::copy_encode(raw.as_slice(), writer, 0).map_err(io_error)?,
+    }
+    Ok(())
+}
+
+/// 读取快照，压缩格式按文件头自动识别
+///
+/// 文件是压缩的但对应特性未启用时返回错误。
+pub(crate) fn read_snapshot<R: Read>(mut reader: R) -> Result<SnapshotData, StorageError> {
+    let mut bytes = Vec::new();
+    reader.read_to_end(&mut bytes).map_err(io_error)?;
+
+    #[cfg(feature = "gzip")]
+    if bytes.starts_with(GZIP_MAGIC) {
+        let mut raw = Vec::new();
+        flate2::read::GzDecoder::new(bytes.as_slice())
+            .read_to_end(&mut raw)
+            .map_err(io_error)?;
+        bytes = raw;
+    }
+    #[cfg(feature = "zstd")]
+    if bytes.starts_with(ZSTD_MAGIC) {
+        bytes = zstd::stream::decode_all(bytes.as_slice()).map_err(io_error)?;
+    }
+
+    let Some(payload) = bytes.strip_prefix(SNAPSHOT_MAGIC.as_slice()) else {
+        return Err(StorageError::Other(
+            "not a snapshot file, or compressed with a format whose feature is disabled".to_string(),
+        ));
+    };
+    match payload.split_first() {
+        Some((&SNAPSHOT_VERSION, data)) => bincode::deserialize(data).map_err(io_error),
+        _ => Err(StorageError::Other("unsupported snapshot version".to_string())),
+    }
+}
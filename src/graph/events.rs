@@ -0,0 +1,39 @@
+use crate::graph::model::{Node, Relationship};
+use crate::storage::{NodeId, RelId};
+
+/// 图变更监听器
+///
+/// 通过 [`GraphDatabase::subscribe`](crate::GraphDatabase::subscribe) 注册后，
+/// 每次写操作成功后同步回调。回调返回的错误只会被记录，不会影响写操作本身。
+/// 所有方法默认不做任何事，只需实现关心的事件。
+pub trait GraphListener: Send + Sync {
+    /// 节点已创建
+    fn on_node_created(&self, _node: &Node) -> Result<(), String> {
+        Ok(())
+    }
+
+    /// 节点属性已更新，参数为更新后的节点
+    fn on_node_updated(&self, _node: &Node) -> Result<(), String> {
+        Ok(())
+    }
+
+    /// 节点已删除（级联删除的关系会先各自触发 `on_rel_deleted`）
+    fn on_node_deleted(&self, _id: NodeId) -> Result<(), String> {
+        Ok(())
+    }
+
+    /// 关系已创建
+    fn on_rel_created(&self, _rel: &Relationship) -> Result<(), String> {
+        Ok(())
+    }
+
+    /// 关系属性已更新，参数为更新后的关系
+    fn on_rel_updated(&self, _rel: &Relationship) -> Result<(), String> {
+        Ok(())
+    }
+
+    /// 关系已删除
+    fn on_rel_deleted(&self, _id: RelId) -> Result<(), String> {
+        Ok(())
+    }
+}
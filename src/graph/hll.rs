@@ -0,0 +1,88 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+use crate::values::Value;
+
+/// 默认精度：2^14 个寄存器，标准误差约 0.8%
+pub const DEFAULT_HLL_PRECISION: u8 = 14;
+
+/// HyperLogLog 基数估计
+///
+/// 用固定大小的寄存器数组估计集合中不同元素的个数，内存占用与数据量无关
+/// （精度为 p 时占 2^p 字节），标准误差约为 `1.04 / sqrt(2^p)`。
+#[derive(Debug, Clone, PartialEq)]
+pub struct HyperLogLog {
+    precision: u8,
+    registers: Vec<u8>,
+}
+
+impl HyperLogLog {
+    /// 创建精度为 `precision`（4 ~ 18）的空草图
+    pub fn new(precision: u8) -> Self {
+        assert!((4..=18).contains(&precision), "HyperLogLog precision must be in 4..=18");
+        Self {
+            precision,
+            registers: vec![0; 1 << precision],
+        }
+    }
+
+    /// 加入一个元素
+    pub fn insert<T: Hash + ?Sized>(&mut self, item: &T) {
+        let mut hasher = DefaultHasher::new();
+        item.hash(&mut hasher);
+        self.insert_hash(hasher.finish());
+    }
+
+    /// 加入一个属性值（按二进制编码哈希，`Int(1)` 与 `Float(1.0)` 视为不同的值）
+    pub fn insert_value(&mut self, value: &Value) {
+        let bytes = bincode::serialize(value).unwrap_or_default();
+        self.insert(bytes.as_slice());
+    }
+
+    fn insert_hash(&mut self, hash: u64) {
+        let p = self.precision as u32;
+        let index = (hash >> (64 - p)) as usize;
+        // 剩余位中第一个 1 的位置；全为 0 时取最大值
+        let rest = hash << p;
+        let rank = (rest.leading_zeros() + 1).min(64 - p + 1) as u8;
+        if rank > self.registers[index] {
+            self.registers[index] = rank;
+        }
+    }
+
+    /// 合并另一个同精度的草图（结果等价于对两个集合的并集计数）
+    pub fn merge(&mut self, other: &HyperLogLog) {
+        assert_eq!(self.precision, other.precision, "cannot merge sketches of different precision");
+        for (a, &b) in self.registers.iter_mut().zip(&other.registers) {
+            *a = (*a).max(b);
+        }
+    }
+
+    /// 估计的不同元素个数
+    pub fn estimate(&self) -> u64 {
+        let m = self.registers.len() as f64;
+        let alpha = match self.registers.len() {
+            16 => 0.673,
+            32 => 0.697,
+            64 => 0.709,
+            _ => 0.7213 / (1.0 + 1.079 / m),
+        };
+        let sum: f64 = self.registers.iter().map(|&r| 2f64.powi(-(r as i32))).sum();
+        let raw = alpha * m * m / sum;
+
+        // 小基数时改用线性计数，避免原始估计偏大
+        let zeros = self.registers.iter().filter(|&&r| r == 0).count();
+        let estimate = if raw <= 2.5 * m && zeros > 0 {
+            m * (m / zeros as f64).ln()
+        } else {
+            raw
+        };
+        estimate.round() as u64
+    }
+}
+
+impl Default for HyperLogLog {
+    fn default() -> Self {
+        Self::new(DEFAULT_HLL_PRECISION)
+    }
+}
@@ -1,5 +1,17 @@
 pub mod model;
 pub mod db;
 pub mod async_db;
+pub mod events;
+pub mod cdc;
+pub mod schema;
+pub mod hll;
+pub mod dump;
+pub mod components;
+pub mod advisor;
 
-pub use async_db::{AsyncGraphDB, AsyncError};
\ No newline at end of file
+pub use async_db::{AsyncGraphDB, AsyncError};
+pub use events::GraphListener;
+pub use cdc::{Change, ChangeLog, ChangeRecord};
+pub use dump::SnapshotCompression;
+pub use hll::HyperLogLog;
+pub use schema::{infer_schema, ElementSchema, GraphSchema, PropertyProfile};
\ No newline at end of file
@@ -25,4 +25,33 @@ pub struct Relationship {
     pub end: NodeId,
     pub typ: String,
     pub props: Properties,
+    /// 是否为有向关系
+    pub directed: bool,
+}
+
+impl Relationship {
+    /// 给定一个端点，返回另一个端点
+    pub fn other_node(&self, node: NodeId) -> NodeId {
+        if self.start == node {
+            self.end
+        } else {
+            self.start
+        }
+    }
+
+    /// 无向关系以 `node` 为起点重新定向；有向关系保持不变
+    pub fn oriented_from(mut self, node: NodeId) -> Self {
+        if !self.directed && self.start != node {
+            std::mem::swap(&mut self.start, &mut self.end);
+        }
+        self
+    }
+
+    /// 无向关系以 `node` 为终点重新定向；有向关系保持不变
+    pub fn oriented_to(mut self, node: NodeId) -> Self {
+        if !self.directed && self.end != node {
+            std::mem::swap(&mut self.start, &mut self.end);
+        }
+        self
+    }
 }
@@ -0,0 +1,103 @@
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+
+use serde::Serialize;
+
+use crate::graph::db::GraphDatabase;
+use crate::storage::StorageEngine;
+use crate::values::Properties;
+
+/// 某个属性在一组元素中的统计
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PropertyProfile {
+    /// 观察到的值类型（`Value::type_name`），按名称排序
+    pub types: Vec<String>,
+    /// 具有该属性的元素数
+    pub count: usize,
+    /// 具有该属性的元素占比（0.0 ~ 1.0）
+    pub fill_rate: f64,
+}
+
+/// 一个标签或关系类型的统计
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ElementSchema {
+    /// 参与统计的元素数
+    pub count: usize,
+    pub properties: BTreeMap<String, PropertyProfile>,
+}
+
+/// 从实际数据推断出的图模式
+///
+/// 与用户注册的约束不同，这里只反映数据当前的样子。
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct GraphSchema {
+    /// 标签 -> 该标签节点的属性统计（多标签节点计入每个标签）
+    pub labels: BTreeMap<String, ElementSchema>,
+    /// 关系类型 -> 该类型关系的属性统计
+    pub rel_types: BTreeMap<String, ElementSchema>,
+}
+
+/// 统计过程中的累加器：元素数以及每个属性的出现次数和类型
+#[derive(Default)]
+struct ElementAccumulator {
+    count: usize,
+    properties: BTreeMap<String, (usize, BTreeSet<&'static str>)>,
+}
+
+impl ElementAccumulator {
+    fn observe(&mut self, props: &Properties) {
+        self.count += 1;
+        for (key, value) in props {
+            let entry = self.properties.entry(key.clone()).or_default();
+            entry.0 += 1;
+            entry.1.insert(value.type_name());
+        }
+    }
+
+    fn finish(self) -> ElementSchema {
+        let total = self.count;
+        let properties = self
+            .properties
+            .into_iter()
+            .map(|(key, (count, types))| {
+                let profile = PropertyProfile {
+                    types: types.into_iter().map(str::to_string).collect(),
+                    count,
+                    fill_rate: count as f64 / total as f64,
+                };
+                (key, profile)
+            })
+            .collect();
+        ElementSchema { count: total, properties }
+    }
+}
+
+/// 扫描数据推断图模式
+///
+/// `sample_size` 为 `Some(n)` 时只统计前 n 个节点及其出边，适合大图上的快速估计；
+/// 为 `None` 时全量扫描。
+pub fn infer_schema<E: StorageEngine>(
+    db: &GraphDatabase<E>,
+    sample_size: Option<usize>,
+) -> GraphSchema {
+    let mut labels: BTreeMap<String, ElementAccumulator> = BTreeMap::new();
+    let mut rel_types: BTreeMap<String, ElementAccumulator> = BTreeMap::new();
+    // 无向关系会从两端各返回一次，按关系 ID 去重
+    let mut seen_rels = HashSet::new();
+
+    let nodes = db.all_stored_nodes().take(sample_size.unwrap_or(usize::MAX));
+    for node in nodes {
+        for label in &node.labels {
+            labels.entry(label.clone()).or_default().observe(&node.props);
+        }
+        for rel in db.neighbors_out(node.id) {
+            if seen_rels.insert(rel.id) {
+                rel_types.entry(rel.typ).or_default().observe(&rel.props);
+            }
+        }
+    }
+
+    GraphSchema {
+        labels: labels.into_iter().map(|(k, acc)| (k, acc.finish())).collect(),
+        rel_types: rel_types.into_iter().map(|(k, acc)| (k, acc.finish())).collect(),
+    }
+}
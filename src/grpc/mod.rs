@@ -2,16 +2,27 @@ pub mod proto {
     tonic::include_proto!("rsgraphdb");
 }
 
+use crate::cypher::{self, CypherBatches, CypherResult, CypherStatement};
+use crate::graph::model::Node as RustNode;
 use crate::service::{GraphService, ServiceError};
 use crate::storage::StorageEngine;
 use crate::values::{Properties, Value as RustValue};
+use futures::Stream;
 use proto::graph_db_service_server::{GraphDbService, GraphDbServiceServer};
 use proto::*;
 use std::collections::HashMap;
 use std::net::SocketAddr;
+use std::pin::Pin;
 use std::sync::Arc;
+use tokio::sync::mpsc;
 use tonic::{Request, Response, Status};
 
+/// 流式查询的行缓冲大小；客户端消费过慢时生产端在此处等待
+const STREAM_BUFFER_ROWS: usize = 16;
+
+/// 流式查询每次持有数据库锁时求值的行数上限
+const STREAM_BATCH_ROWS: usize = 64;
+
 // Rust Value <-> Proto Value 转换
 fn rust_value_to_proto(v: &RustValue) -> Value {
     let value = match v {
@@ -19,6 +30,8 @@ fn rust_value_to_proto(v: &RustValue) -> Value {
         RustValue::Bool(b) => value::Value::BoolValue(*b),
         RustValue::Text(s) => value::Value::TextValue(s.clone()),
         RustValue::Float(f) => value::Value::FloatValue(*f),
+        RustValue::DateTime(ms) => value::Value::DatetimeValue(*ms),
+        RustValue::Bytes(bytes) => value::Value::BytesValue(bytes.clone()),
     };
     Value { value: Some(value) }
 }
@@ -29,6 +42,8 @@ fn proto_value_to_rust(v: &Value) -> Option<RustValue> {
         value::Value::BoolValue(b) => Some(RustValue::Bool(*b)),
         value::Value::TextValue(s) => Some(RustValue::Text(s.clone())),
         value::Value::FloatValue(f) => Some(RustValue::Float(*f)),
+        value::Value::DatetimeValue(ms) => Some(RustValue::DateTime(*ms)),
+        value::Value::BytesValue(bytes) => Some(RustValue::Bytes(bytes.clone())),
     })
 }
 
@@ -49,11 +64,21 @@ fn proto_props_to_rust(props: &HashMap<String, Value>) -> Properties {
     result
 }
 
+/// 节点转换为结果行：属性之外附带 `_id`
+fn node_to_row(node: RustNode) -> QueryRow {
+    let mut fields = rust_props_to_proto(&node.props);
+    fields.insert("_id".to_string(), rust_value_to_proto(&RustValue::Int(node.id as i64)));
+    QueryRow { fields }
+}
+
 impl From<ServiceError> for Status {
     fn from(err: ServiceError) -> Self {
         match err {
-            ServiceError::Internal(msg) => Status::internal(msg),
             ServiceError::NotFound => Status::not_found("Not found"),
+            ServiceError::Validation(msg) => Status::invalid_argument(msg),
+            ServiceError::Constraint(msg) => Status::failed_precondition(msg),
+            ServiceError::Conflict(msg) => Status::aborted(msg),
+            ServiceError::Internal(msg) => Status::internal(msg),
         }
     }
 }
@@ -70,6 +95,9 @@ impl<E: StorageEngine> GrpcGraphService<E> {
 
 #[tonic::async_trait]
 impl<E: StorageEngine + Send + Sync + 'static> GraphDbService for GrpcGraphService<E> {
+    type ExecuteCypherStreamStream =
+        Pin<Box<dyn Stream<Item = Result<QueryRow, Status>> + Send + 'static>>;
+
     async fn create_node(
         &self,
         request: Request<CreateNodeRequest>,
@@ -127,6 +155,74 @@ impl<E: StorageEngine + Send + Sync + 'static> GraphDbService for GrpcGraphServi
         let response = ExecuteCypherResponse { rows: vec![] };
         Ok(Response::new(response))
     }
+
+    /// 流式执行 Cypher 查询
+    ///
+    /// 在阻塞线程上分批求值，每批至多 [`STREAM_BATCH_ROWS`] 行，并通过有界通道发送；
+    /// 只在求值一批时持有数据库锁，发送期间释放，慢客户端不会阻塞其他请求。
+    /// 简单扫描的第一批在扫描结束前即可到达客户端；客户端断开后生产端在下一次发送时停止。
+    async fn execute_cypher_stream(
+        &self,
+        request: Request<ExecuteCypherRequest>,
+    ) -> Result<Response<Self::ExecuteCypherStreamStream>, Status> {
+        let req = request.into_inner();
+        let stmt = cypher::parse_cypher(&req.query)
+            .map_err(|e| Status::invalid_argument(format!("Parse error: {}", e)))?;
+
+        let (tx, rx) = mpsc::channel::<Result<QueryRow, Status>>(STREAM_BUFFER_ROWS);
+        let db = Arc::clone(self.service.db());
+
+        tokio::task::spawn_blocking(move || {
+            let poisoned = || Status::internal("DB lock poisoned");
+            let opened = match db.lock() {
+                Ok(mut guard) => match &stmt {
+                    CypherStatement::Query(query) => {
+                        CypherBatches::open(&*guard, query).map_err(Status::invalid_argument)
+                    }
+                    other => match cypher::execute_statement(&mut *guard, other) {
+                        Ok(CypherResult::Nodes(nodes)) => Ok(CypherBatches::from(nodes)),
+                        Ok(_) => Err(Status::invalid_argument(
+                            "statement does not return rows; use ExecuteCypher instead",
+                        )),
+                        Err(e) => Err(Status::invalid_argument(e)),
+                    },
+                },
+                Err(_) => Err(poisoned()),
+            };
+            let mut batches = match opened {
+                Ok(batches) => batches,
+                Err(status) => {
+                    let _ = tx.blocking_send(Err(status));
+                    return;
+                }
+            };
+
+            loop {
+                // 锁只覆盖一批的求值，发送前释放
+                let batch = match db.lock() {
+                    Ok(guard) => batches.next_batch(&*guard, STREAM_BATCH_ROWS),
+                    Err(_) => {
+                        let _ = tx.blocking_send(Err(poisoned()));
+                        return;
+                    }
+                };
+                let Some(batch) = batch else {
+                    return;
+                };
+                for node in batch {
+                    if tx.blocking_send(Ok(node_to_row(node))).is_err() {
+                        // 客户端已断开
+                        return;
+                    }
+                }
+            }
+        });
+
+        let stream = futures::stream::unfold(rx, |mut rx| async move {
+            rx.recv().await.map(|item| (item, rx))
+        });
+        Ok(Response::new(Box::pin(stream)))
+    }
 }
 
 pub async fn run_grpc_server<E: StorageEngine + Send + Sync + 'static>(
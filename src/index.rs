@@ -1,9 +1,10 @@
 use crate::storage::NodeId;
 use crate::values::Value;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 // 导入高级索引
-use crate::index_advanced::{FullTextIndex, RangeIndex};
+use crate::index_advanced::{FullTextIndex, RangeIndex, TextAnalyzer};
+use crate::index_composite::CompositeIndexStats;
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum ValueKey {
@@ -60,6 +61,145 @@ impl CompositeKey {
     }
 }
 
+/// 索引类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum IndexKind {
+    /// 单属性索引
+    Property,
+    /// 复合索引
+    Composite,
+    /// 全文索引
+    FullText,
+    /// 范围索引
+    Range,
+}
+
+impl IndexKind {
+    /// 索引类型名，用作索引标识的前缀
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            IndexKind::Property => "property",
+            IndexKind::Composite => "composite",
+            IndexKind::FullText => "fulltext",
+            IndexKind::Range => "range",
+        }
+    }
+}
+
+/// 一个索引的定义和统计信息
+#[derive(Debug, Clone)]
+pub struct IndexInfo {
+    /// 索引标识：复合索引为 `composite:<名称>`，其余为 `<类型>:<标签>.<属性>`
+    pub id: String,
+    pub kind: IndexKind,
+    pub label: String,
+    /// 索引的属性名（复合索引按索引顺序）
+    pub properties: Vec<String>,
+    /// 键数量与条目数，选择性和每键平均节点数由此计算
+    pub stats: CompositeIndexStats,
+}
+
+/// 一条索引项：某个索引键指向某个节点
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct IndexEntry {
+    pub kind: IndexKind,
+    pub label: String,
+    /// 索引的属性名（复合索引按索引顺序）
+    pub properties: Vec<String>,
+    /// 索引键的文本形式（属性值、复合值或全文词项）
+    pub key: String,
+    pub node_id: NodeId,
+}
+
+/// 索引与存储层之间的不一致
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IndexInconsistency {
+    /// 索引中存在，但存储中没有对应的节点或属性值
+    StaleEntry(IndexEntry),
+    /// 存储中的节点应当被索引，但索引中缺失
+    MissingEntry(IndexEntry),
+}
+
+impl IndexInconsistency {
+    pub fn entry(&self) -> &IndexEntry {
+        match self {
+            IndexInconsistency::StaleEntry(entry) | IndexInconsistency::MissingEntry(entry) => entry,
+        }
+    }
+}
+
+fn format_value_key(key: &ValueKey) -> String {
+    match key {
+        ValueKey::Int(i) => i.to_string(),
+        ValueKey::Bool(b) => b.to_string(),
+        ValueKey::Text(s) => format!("{:?}", s),
+    }
+}
+
+/// 批量建索引的缓冲区
+///
+/// 先收集单属性和复合索引项（不查重），再由 [`PropertyIndex::bulk_build`]
+/// 按键分组、排序去重后一次性合并，避免逐条添加时对节点列表的线性查重。
+#[derive(Default)]
+pub struct IndexBatch {
+    single: HashMap<(String, String, ValueKey), Vec<NodeId>>,
+    composite: HashMap<CompositeKey, Vec<NodeId>>,
+    len: usize,
+}
+
+impl IndexBatch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 收集一条单属性索引项；不可索引的值（如浮点数）被忽略
+    pub fn add(&mut self, label: &str, prop_name: &str, value: &Value, node_id: NodeId) {
+        if let Ok(key) = ValueKey::try_from(value) {
+            self.single
+                .entry((label.to_string(), prop_name.to_string(), key))
+                .or_default()
+                .push(node_id);
+            self.len += 1;
+        }
+    }
+
+    /// 收集一条复合索引项；任一值不可索引时忽略整条
+    pub fn add_composite(&mut self, label: &str, properties: &[&str], values: &[Value], node_id: NodeId) {
+        let value_keys: Vec<ValueKey> = values
+            .iter()
+            .filter_map(|v| ValueKey::try_from(v).ok())
+            .collect();
+        if value_keys.len() == values.len() {
+            self.composite
+                .entry(CompositeKey::from_slices(label, properties, &value_keys))
+                .or_default()
+                .push(node_id);
+            self.len += 1;
+        }
+    }
+
+    /// 已收集的索引项数量
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+/// 把一个键下新收集的节点合并进已有的节点列表，保持无重复
+fn merge_node_ids(existing: &mut Vec<NodeId>, mut ids: Vec<NodeId>) {
+    ids.sort_unstable();
+    ids.dedup();
+    if existing.is_empty() {
+        *existing = ids;
+    } else {
+        let seen: HashSet<NodeId> = existing.iter().copied().collect();
+        existing.extend(ids.into_iter().filter(|id| !seen.contains(id)));
+    }
+}
+
 /// 一个非常简单的属性索引：
 /// (label, property_name, value) -> [node_id]
 #[derive(Default)]
@@ -101,6 +241,19 @@ impl PropertyIndex {
         }
     }
 
+    /// 一次性合并批量收集的索引项
+    ///
+    /// 结果与对每一项调用 [`add`](Self::add) / [`add_composite`](Self::add_composite) 相同，
+    /// 同一键下新加入的节点按 ID 升序排列。
+    pub fn bulk_build(&mut self, batch: IndexBatch) {
+        for (key, ids) in batch.single {
+            merge_node_ids(self.map.entry(key).or_default(), ids);
+        }
+        for (key, ids) in batch.composite {
+            merge_node_ids(self.composite_map.entry(key).or_default(), ids);
+        }
+    }
+
     /// 查询单属性索引
     pub fn find(
         &self,
@@ -175,6 +328,38 @@ impl PropertyIndex {
         self.composite_map.get(&key).cloned().unwrap_or_default()
     }
 
+    /// 删除单属性索引中的一个条目（用于属性值变化时的增量维护）
+    pub fn remove_entry(&mut self, label: &str, prop_name: &str, value: &Value, node_id: NodeId) {
+        if let Ok(key) = ValueKey::try_from(value) {
+            let k = (label.to_string(), prop_name.to_string(), key);
+            if let Some(entry) = self.map.get_mut(&k) {
+                entry.retain(|&id| id != node_id);
+            }
+        }
+    }
+
+    /// 删除复合索引中的一个条目
+    pub fn remove_composite(
+        &mut self,
+        label: &str,
+        properties: &[&str],
+        values: &[Value],
+        node_id: NodeId,
+    ) {
+        let value_keys: Vec<ValueKey> = values
+            .iter()
+            .filter_map(|v| ValueKey::try_from(v).ok())
+            .collect();
+        if value_keys.len() != values.len() {
+            return;
+        }
+
+        let key = CompositeKey::from_slices(label, properties, &value_keys);
+        if let Some(entry) = self.composite_map.get_mut(&key) {
+            entry.retain(|&id| id != node_id);
+        }
+    }
+
     /// 删除节点的索引（用于删除节点时清理索引）
     pub fn remove(&mut self, node_id: NodeId) {
         // 从单属性索引中删除
@@ -210,6 +395,179 @@ impl PropertyIndex {
         self.composite_map.len()
     }
 
+    /// 已建立全文索引的 (label, property_name) 集合
+    pub fn fulltext_fields(&self) -> HashSet<(String, String)> {
+        self.fulltext_index.fields()
+    }
+
+    /// 已建立范围索引的 (label, property_name) 集合
+    pub fn range_fields(&self) -> HashSet<(String, String)> {
+        self.range_index.fields()
+    }
+
+    /// 列出所有索引中的全部索引项（用于一致性校验）
+    pub fn entries(&self) -> HashSet<IndexEntry> {
+        let mut entries = HashSet::new();
+
+        for ((label, prop, key), ids) in &self.map {
+            for &node_id in ids {
+                entries.insert(IndexEntry {
+                    kind: IndexKind::Property,
+                    label: label.clone(),
+                    properties: vec![prop.clone()],
+                    key: format_value_key(key),
+                    node_id,
+                });
+            }
+        }
+
+        for (key, ids) in &self.composite_map {
+            let values: Vec<String> = key.values.iter().map(format_value_key).collect();
+            for &node_id in ids {
+                entries.insert(IndexEntry {
+                    kind: IndexKind::Composite,
+                    label: key.label.clone(),
+                    properties: key.properties.clone(),
+                    key: values.join(", "),
+                    node_id,
+                });
+            }
+        }
+
+        for (label, prop, word, node_id) in self.fulltext_index.entries() {
+            entries.insert(IndexEntry {
+                kind: IndexKind::FullText,
+                label,
+                properties: vec![prop],
+                key: word,
+                node_id,
+            });
+        }
+
+        for (label, prop, value, node_id) in self.range_index.entries() {
+            let key = match value {
+                Value::Int(i) => i.to_string(),
+                Value::Float(f) => f.to_string(),
+                other => format!("{:?}", other),
+            };
+            entries.insert(IndexEntry {
+                kind: IndexKind::Range,
+                label,
+                properties: vec![prop],
+                key,
+                node_id,
+            });
+        }
+
+        entries
+    }
+
+    /// 按 (类型, 标签, 属性列表) 统计每个索引的键数量和条目数
+    pub fn field_stats(&self) -> HashMap<(IndexKind, String, Vec<String>), CompositeIndexStats> {
+        let mut keys: HashMap<(IndexKind, String, Vec<String>), HashSet<String>> = HashMap::new();
+        let mut stats: HashMap<(IndexKind, String, Vec<String>), CompositeIndexStats> = HashMap::new();
+        for entry in self.entries() {
+            let field = (entry.kind, entry.label, entry.properties);
+            stats.entry(field.clone()).or_default().total_entries += 1;
+            keys.entry(field).or_default().insert(entry.key);
+        }
+        for (field, keys) in keys {
+            if let Some(stat) = stats.get_mut(&field) {
+                stat.unique_keys = keys.len();
+            }
+        }
+        stats
+    }
+
+    /// 删除某个单属性索引的全部索引项
+    pub fn drop_property(&mut self, label: &str, property_name: &str) {
+        self.map.retain(|(l, p, _), _| l != label || p != property_name);
+    }
+
+    /// 删除某个复合索引的全部索引项
+    pub fn drop_composite(&mut self, label: &str, properties: &[String]) {
+        self.composite_map
+            .retain(|key, _| key.label != label || key.properties != properties);
+    }
+
+    /// 全文索引
+    pub fn fulltext(&self) -> &FullTextIndex {
+        &self.fulltext_index
+    }
+
+    /// 替换整个全文索引（例如从持久化数据恢复）
+    pub fn set_fulltext(&mut self, index: FullTextIndex) {
+        self.fulltext_index = index;
+    }
+
+    /// 为全文索引字段注册分析器
+    pub fn set_fulltext_analyzer(&mut self, label: &str, property_name: &str, analyzer: TextAnalyzer) {
+        self.fulltext_index.set_analyzer(label, property_name, analyzer);
+    }
+
+    /// 删除某个字段的全文索引
+    pub fn drop_fulltext(&mut self, label: &str, property_name: &str) {
+        self.fulltext_index.drop_field(label, property_name);
+    }
+
+    /// 删除某个字段的范围索引
+    pub fn drop_range(&mut self, label: &str, property_name: &str) {
+        self.range_index.drop_field(label, property_name);
+    }
+
+    /// 删除已经没有节点的索引键并释放多余容量
+    pub fn compact(&mut self) {
+        self.map.retain(|_, ids| !ids.is_empty());
+        self.composite_map.retain(|_, ids| !ids.is_empty());
+        for ids in self.map.values_mut().chain(self.composite_map.values_mut()) {
+            ids.shrink_to_fit();
+        }
+        self.map.shrink_to_fit();
+        self.composite_map.shrink_to_fit();
+    }
+
+    /// 近似内存占用（字节）：键中的字符串长度加上节点 ID 列表
+    pub fn estimated_size(&self) -> usize {
+        let id_size = std::mem::size_of::<NodeId>();
+        let key_size = |key: &ValueKey| match key {
+            ValueKey::Int(_) => 8,
+            ValueKey::Bool(_) => 1,
+            ValueKey::Text(s) => s.len(),
+        };
+
+        let single: usize = self
+            .map
+            .iter()
+            .map(|((label, prop, key), ids)| {
+                label.len() + prop.len() + key_size(key) + ids.len() * id_size
+            })
+            .sum();
+        let composite: usize = self
+            .composite_map
+            .iter()
+            .map(|(key, ids)| {
+                key.label.len()
+                    + key.properties.iter().map(|p| p.len()).sum::<usize>()
+                    + key.values.iter().map(key_size).sum::<usize>()
+                    + ids.len() * id_size
+            })
+            .sum();
+        let fulltext: usize = self
+            .fulltext_index
+            .entries()
+            .iter()
+            .map(|(label, prop, word, _)| label.len() + prop.len() + word.len() + id_size)
+            .sum();
+        let range: usize = self
+            .range_index
+            .entries()
+            .iter()
+            .map(|(label, prop, _, _)| label.len() + prop.len() + 8 + id_size)
+            .sum();
+
+        single + composite + fulltext + range
+    }
+
     // ========== 全文索引 API ==========
 
     /// 添加全文索引
@@ -288,6 +646,51 @@ impl PropertyIndex {
         self.range_index.less_than(label, property_name, value)
     }
 
+    /// 按范围索引取值最大（`descending`）或最小的 `n` 个节点
+    pub fn range_top_n(
+        &self,
+        label: &str,
+        property_name: &str,
+        n: usize,
+        descending: bool,
+    ) -> Vec<NodeId> {
+        self.range_index.top_n(label, property_name, n, descending)
+    }
+
+    /// 属性值变化后刷新节点的范围索引项：按旧值删除、按新值添加，`None` 表示属性不存在；
+    /// 字段未建范围索引时什么也不做
+    pub fn update_range(
+        &mut self,
+        label: &str,
+        property_name: &str,
+        old: Option<&Value>,
+        new: Option<&Value>,
+        node_id: NodeId,
+    ) {
+        if !self.range_index.has_field(label, property_name) {
+            return;
+        }
+        if let Some(old) = old {
+            self.range_index.remove_value(label, property_name, old, node_id);
+        }
+        if let Some(new) = new {
+            self.range_index.add(label, property_name, new, node_id);
+        }
+    }
+
+    /// 是否建立过任何范围索引
+    pub fn has_range_index(&self) -> bool {
+        self.range_index.int_field_count()
+            + self.range_index.float_field_count()
+            + self.range_index.datetime_field_count()
+            > 0
+    }
+
+    /// 从范围索引中删除节点
+    pub fn remove_range(&mut self, node_id: NodeId) {
+        self.range_index.remove(node_id);
+    }
+
     /// 范围查询：范围之间
     pub fn range_between(
         &self,
@@ -4,8 +4,11 @@
 
 use crate::storage::NodeId;
 use crate::values::Value;
+use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, BTreeMap, BTreeSet, HashSet};
 use std::hash::{Hash, Hasher};
+use std::ops::Bound;
+use std::io::{Read, Write};
 
 /// 浮点数包装器，用于实现 Hash 和 Eq
 ///
@@ -44,11 +47,125 @@ impl Ord for OrderedFloat {
     }
 }
 
+/// 分词方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Tokenizer {
+    /// 按空白切分，并去掉词中的标点（"e-mail" -> "email"）
+    #[default]
+    Whitespace,
+    /// 按任意非字母数字字符切分（"e-mail" -> "e", "mail"）
+    Alphanumeric,
+}
+
+/// 文本分析器：决定文本如何切分成词项
+///
+/// 索引和查询使用同一个分析器，因此两边的词项总是可比的。
+/// 默认分析器按空白分词并转为小写，不去停用词、不做词干提取。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TextAnalyzer {
+    /// 是否转为小写（关闭后 "API" 与 "api" 是不同的词项）
+    pub lowercase: bool,
+    /// 停用词：与（大小写折叠后的）词项相同的词不进入索引
+    pub stop_words: HashSet<String>,
+    /// 是否做简单的英文词干提取（去掉 -ing / -ed / -es / -s 等后缀）
+    pub stemming: bool,
+    pub tokenizer: Tokenizer,
+}
+
+impl Default for TextAnalyzer {
+    fn default() -> Self {
+        Self {
+            lowercase: true,
+            stop_words: HashSet::new(),
+            stemming: false,
+            tokenizer: Tokenizer::default(),
+        }
+    }
+}
+
+impl TextAnalyzer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 区分大小写
+    pub fn case_sensitive(mut self) -> Self {
+        self.lowercase = false;
+        self
+    }
+
+    pub fn with_stop_words(mut self, words: &[&str]) -> Self {
+        self.stop_words.extend(words.iter().map(|w| w.to_string()));
+        self
+    }
+
+    pub fn with_stemming(mut self) -> Self {
+        self.stemming = true;
+        self
+    }
+
+    pub fn with_tokenizer(mut self, tokenizer: Tokenizer) -> Self {
+        self.tokenizer = tokenizer;
+        self
+    }
+
+    /// 把文本分析为词项列表
+    pub fn analyze(&self, text: &str) -> Vec<String> {
+        let words: Vec<String> = match self.tokenizer {
+            Tokenizer::Whitespace => text
+                .split_whitespace()
+                .map(|word| word.chars().filter(|c| c.is_alphanumeric()).collect())
+                .collect(),
+            Tokenizer::Alphanumeric => text
+                .split(|c: char| !c.is_alphanumeric())
+                .map(|word| word.to_string())
+                .collect(),
+        };
+
+        words
+            .into_iter()
+            .filter(|word| !word.is_empty())
+            .map(|word| if self.lowercase { word.to_lowercase() } else { word })
+            .filter(|word| !self.is_stop_word(word))
+            .map(|word| if self.stemming { stem(&word) } else { word })
+            .collect()
+    }
+
+    fn is_stop_word(&self, word: &str) -> bool {
+        if self.lowercase {
+            self.stop_words.iter().any(|w| w.to_lowercase() == word)
+        } else {
+            self.stop_words.contains(word)
+        }
+    }
+}
+
+/// 极简的英文词干提取：去掉常见屈折后缀，保留至少 3 个字符的词干
+fn stem(word: &str) -> String {
+    for suffix in ["ing", "ed", "es", "ly", "s"] {
+        if let Some(stemmed) = word.strip_suffix(suffix) {
+            if stemmed.chars().count() >= 3 && !stemmed.ends_with('s') {
+                return stemmed.to_string();
+            }
+        }
+    }
+    word.to_string()
+}
+
+/// [`FullTextIndex::save`] 输出的文件头
+const FULLTEXT_MAGIC: &[u8; 4] = b"FTIX";
+
+/// [`FullTextIndex::save`] 的数据格式版本，序列化布局变化时递增
+const FULLTEXT_FORMAT_VERSION: u32 = 2;
+
 /// 全文索引
 ///
 /// 用于文本搜索，支持分词和包含查询
 /// 例如：WHERE n.name CONTAINS "keyword"
-#[derive(Debug)]
+///
+/// 每个 (label, property_name) 可以注册自己的 [`TextAnalyzer`]，未注册的字段使用默认分析器。
+/// 可以用 [`save`](Self::save) / [`load`](Self::load) 序列化后跨进程保存
+#[derive(Debug, Serialize, Deserialize)]
 pub struct FullTextIndex {
     /// 词项索引: (label, property_name, word) -> [node_id]
     /// 使用倒排索引结构，每个词指向包含该词的节点ID列表
@@ -56,6 +173,8 @@ pub struct FullTextIndex {
     /// 文档长度: node_id -> word_count
     /// 用于计算相关性和评分
     doc_lengths: HashMap<NodeId, usize>,
+    /// 按字段注册的分析器: (label, property_name) -> analyzer
+    analyzers: HashMap<(String, String), TextAnalyzer>,
 }
 
 impl FullTextIndex {
@@ -64,27 +183,36 @@ impl FullTextIndex {
         Self {
             inverted_index: HashMap::new(),
             doc_lengths: HashMap::new(),
+            analyzers: HashMap::new(),
         }
     }
 
-    /// 分词器：将文本分解为词项
+    /// 分词器：用默认分析器将文本分解为词项
     ///
     /// 支持中文和英文分词
     fn tokenize(text: &str) -> Vec<String> {
-        let mut tokens = Vec::new();
+        TextAnalyzer::default().analyze(text)
+    }
 
-        // 按空格分割的英文单词
-        for word in text.split_whitespace() {
-            let cleaned: String = word
-                .chars()
-                .filter(|c| c.is_alphanumeric())
-                .collect();
-            if !cleaned.is_empty() {
-                tokens.push(cleaned.to_lowercase());
-            }
-        }
+    /// 为 (label, property_name) 注册分析器
+    ///
+    /// 只影响之后添加的文档和之后的查询，已经建立的词项不会重新分析
+    pub fn set_analyzer(&mut self, label: &str, property_name: &str, analyzer: TextAnalyzer) {
+        self.analyzers
+            .insert((label.to_string(), property_name.to_string()), analyzer);
+    }
 
-        tokens
+    /// 已注册的分析器
+    pub fn analyzers(&self) -> &HashMap<(String, String), TextAnalyzer> {
+        &self.analyzers
+    }
+
+    /// 按字段注册的分析器分词；未注册时使用默认分析器
+    fn analyze(&self, label: &str, property_name: &str, text: &str) -> Vec<String> {
+        match self.analyzers.get(&(label.to_string(), property_name.to_string())) {
+            Some(analyzer) => analyzer.analyze(text),
+            None => Self::tokenize(text),
+        }
     }
 
     /// 添加文档到全文索引
@@ -102,7 +230,7 @@ impl FullTextIndex {
         node_id: NodeId,
     ) {
         // 分词
-        let tokens = Self::tokenize(text);
+        let tokens = self.analyze(label, property_name, text);
 
         // 记录文档长度
         self.doc_lengths.insert(node_id, tokens.len());
@@ -133,7 +261,7 @@ impl FullTextIndex {
         query: &str,
     ) -> Vec<NodeId> {
         // 对查询进行分词
-        let query_tokens = Self::tokenize(query);
+        let query_tokens = self.analyze(label, property_name, query);
 
         if query_tokens.is_empty() {
             return Vec::new();
@@ -171,7 +299,7 @@ impl FullTextIndex {
         property_name: &str,
         query: &str,
     ) -> Vec<NodeId> {
-        let query_tokens = Self::tokenize(query);
+        let query_tokens = self.analyze(label, property_name, query);
 
         if query_tokens.is_empty() {
             return Vec::new();
@@ -220,6 +348,45 @@ impl FullTextIndex {
         self.doc_lengths.clear();
     }
 
+    /// 删除某个 (label, property_name) 字段的全部词项
+    pub fn drop_field(&mut self, label: &str, property_name: &str) {
+        self.inverted_index
+            .retain(|(l, p, _), _| l != label || p != property_name);
+    }
+
+    /// 把倒排索引、文档长度和分析器写入 `writer`（文件头 + 格式版本 + bincode 编码）
+    pub fn save<W: Write>(&self, mut writer: W) -> Result<(), bincode::Error> {
+        writer.write_all(FULLTEXT_MAGIC)?;
+        bincode::serialize_into(&mut writer, &FULLTEXT_FORMAT_VERSION)?;
+        bincode::serialize_into(writer, self)
+    }
+
+    /// 从 [`save`](Self::save) 写出的数据恢复索引，恢复后即可直接搜索
+    ///
+    /// 文件头不符或格式版本不同（包括旧版本写出的数据）时返回错误
+    pub fn load<R: Read>(mut reader: R) -> Result<Self, bincode::Error> {
+        let mut magic = [0u8; 4];
+        reader.read_exact(&mut magic)?;
+        if &magic != FULLTEXT_MAGIC {
+            return Err(Box::new(bincode::ErrorKind::Custom(
+                "not a full-text index".to_string(),
+            )));
+        }
+        let version: u32 = bincode::deserialize_from(&mut reader)?;
+        if version != FULLTEXT_FORMAT_VERSION {
+            return Err(Box::new(bincode::ErrorKind::Custom(format!(
+                "unsupported full-text index format version {}",
+                version
+            ))));
+        }
+        bincode::deserialize_from(reader)
+    }
+
+    /// 已索引的文档（节点ID）
+    pub fn doc_ids(&self) -> Vec<NodeId> {
+        self.doc_lengths.keys().copied().collect()
+    }
+
     /// 获取索引中的词项数量
     pub fn term_count(&self) -> usize {
         self.inverted_index.len()
@@ -229,6 +396,25 @@ impl FullTextIndex {
     pub fn doc_count(&self) -> usize {
         self.doc_lengths.len()
     }
+
+    /// 已建立全文索引的 (label, property_name) 集合
+    pub fn fields(&self) -> HashSet<(String, String)> {
+        self.inverted_index
+            .keys()
+            .map(|(label, prop, _)| (label.clone(), prop.clone()))
+            .collect()
+    }
+
+    /// 列出所有索引项：(label, property_name, word, node_id)
+    pub fn entries(&self) -> Vec<(String, String, String, NodeId)> {
+        self.inverted_index
+            .iter()
+            .flat_map(|((label, prop, word), ids)| {
+                ids.iter()
+                    .map(move |&id| (label.clone(), prop.clone(), word.clone(), id))
+            })
+            .collect()
+    }
 }
 
 impl Default for FullTextIndex {
@@ -247,6 +433,8 @@ pub struct RangeIndex {
     int_index: HashMap<(String, String), BTreeMap<i64, Vec<NodeId>>>,
     /// 浮点数范围索引: (label, property_name) -> BTreeMap<OrderedFloat, [node_id]>
     float_index: HashMap<(String, String), BTreeMap<OrderedFloat, Vec<NodeId>>>,
+    /// 日期时间范围索引: (label, property_name) -> BTreeMap<毫秒时间戳, [node_id]>
+    datetime_index: HashMap<(String, String), BTreeMap<i64, Vec<NodeId>>>,
 }
 
 impl RangeIndex {
@@ -255,6 +443,7 @@ impl RangeIndex {
         Self {
             int_index: HashMap::new(),
             float_index: HashMap::new(),
+            datetime_index: HashMap::new(),
         }
     }
 
@@ -312,6 +501,13 @@ impl RangeIndex {
             Value::Float(f) => {
                 self.add_float(label, property_name, *f, node_id);
             }
+            Value::DateTime(ms) => {
+                let key = (label.to_string(), property_name.to_string());
+                let entry = self.datetime_index.entry(key).or_default().entry(*ms).or_default();
+                if !entry.contains(&node_id) {
+                    entry.push(node_id);
+                }
+            }
             _ => {
                 // 其他类型不支持范围索引
             }
@@ -352,6 +548,14 @@ impl RangeIndex {
                         .collect();
                 }
             }
+            Value::DateTime(v) => {
+                if let Some(tree) = self.datetime_index.get(&key) {
+                    return tree
+                        .range((Bound::Excluded(*v), Bound::Unbounded))
+                        .flat_map(|(_, ids)| ids.iter().copied())
+                        .collect();
+                }
+            }
             _ => {}
         }
 
@@ -385,6 +589,14 @@ impl RangeIndex {
                         .collect();
                 }
             }
+            Value::DateTime(v) => {
+                if let Some(tree) = self.datetime_index.get(&key) {
+                    return tree
+                        .range(*v..)
+                        .flat_map(|(_, ids)| ids.iter().copied())
+                        .collect();
+                }
+            }
             _ => {}
         }
 
@@ -418,6 +630,14 @@ impl RangeIndex {
                         .collect();
                 }
             }
+            Value::DateTime(v) => {
+                if let Some(tree) = self.datetime_index.get(&key) {
+                    return tree
+                        .range(..*v)
+                        .flat_map(|(_, ids)| ids.iter().copied())
+                        .collect();
+                }
+            }
             _ => {}
         }
 
@@ -451,6 +671,14 @@ impl RangeIndex {
                         .collect();
                 }
             }
+            Value::DateTime(v) => {
+                if let Some(tree) = self.datetime_index.get(&key) {
+                    return tree
+                        .range(..=*v)
+                        .flat_map(|(_, ids)| ids.iter().copied())
+                        .collect();
+                }
+            }
             _ => {}
         }
 
@@ -494,12 +722,115 @@ impl RangeIndex {
                         .collect();
                 }
             }
+            (Value::DateTime(min_v), Value::DateTime(max_v)) => {
+                if let Some(tree) = self.datetime_index.get(&key) {
+                    return tree
+                        .range(min_v..=max_v)
+                        .flat_map(|(_, ids)| ids.iter().copied())
+                        .collect();
+                }
+            }
             _ => {}
         }
 
         Vec::new()
     }
 
+    /// 按值排序取前 `n` 个节点
+    ///
+    /// `descending` 为 true 时从最大值开始。整数和浮点数两棵树按数值归并，
+    /// 只遍历到凑满 `n` 个为止，不需要对整个字段排序；同值节点按加入顺序返回。
+    /// 日期时间不与数值比较，数值不足 `n` 个时再按时间顺序补足。
+    pub fn top_n(
+        &self,
+        label: &str,
+        property_name: &str,
+        n: usize,
+        descending: bool,
+    ) -> Vec<NodeId> {
+        let key = (label.to_string(), property_name.to_string());
+        let ints = self.int_index.get(&key);
+        let floats = self.float_index.get(&key);
+
+        let int_iter: Box<dyn Iterator<Item = (f64, &Vec<NodeId>)>> = match ints {
+            Some(tree) if descending => Box::new(tree.iter().rev().map(|(v, ids)| (*v as f64, ids))),
+            Some(tree) => Box::new(tree.iter().map(|(v, ids)| (*v as f64, ids))),
+            None => Box::new(std::iter::empty()),
+        };
+        let float_iter: Box<dyn Iterator<Item = (f64, &Vec<NodeId>)>> = match floats {
+            Some(tree) if descending => Box::new(tree.iter().rev().map(|(v, ids)| (v.value(), ids))),
+            Some(tree) => Box::new(tree.iter().map(|(v, ids)| (v.value(), ids))),
+            None => Box::new(std::iter::empty()),
+        };
+
+        let mut int_iter = int_iter.peekable();
+        let mut float_iter = float_iter.peekable();
+        let mut result = Vec::with_capacity(n);
+        while result.len() < n {
+            let take_int = match (int_iter.peek(), float_iter.peek()) {
+                (Some((a, _)), Some((b, _))) => {
+                    let ordering = OrderedFloat::new(*a).cmp(&OrderedFloat::new(*b));
+                    if descending { ordering.is_ge() } else { ordering.is_le() }
+                }
+                (Some(_), None) => true,
+                (None, Some(_)) => false,
+                (None, None) => break,
+            };
+            let next = if take_int { int_iter.next() } else { float_iter.next() };
+            if let Some((_, ids)) = next {
+                result.extend(ids.iter().copied().take(n - result.len()));
+            }
+        }
+
+        if let Some(tree) = self.datetime_index.get(&key) {
+            let datetimes: Box<dyn Iterator<Item = &Vec<NodeId>>> = if descending {
+                Box::new(tree.values().rev())
+            } else {
+                Box::new(tree.values())
+            };
+            for ids in datetimes {
+                if result.len() >= n {
+                    break;
+                }
+                result.extend(ids.iter().copied().take(n - result.len()));
+            }
+        }
+        result
+    }
+
+    /// 是否为 (label, property_name) 建立了范围索引
+    pub fn has_field(&self, label: &str, property_name: &str) -> bool {
+        let key = (label.to_string(), property_name.to_string());
+        self.int_index.contains_key(&key)
+            || self.float_index.contains_key(&key)
+            || self.datetime_index.contains_key(&key)
+    }
+
+    /// 按旧值删除节点在某个字段上的索引项（值发生变化或属性被删除时使用）
+    ///
+    /// 只访问旧值所在的条目，条目为空时一并删除；不支持范围索引的值什么也不做
+    pub fn remove_value(&mut self, label: &str, property_name: &str, value: &Value, node_id: NodeId) {
+        fn remove_from<K: Ord>(tree: Option<&mut BTreeMap<K, Vec<NodeId>>>, key: K, node_id: NodeId) {
+            let Some(tree) = tree else {
+                return;
+            };
+            if let Some(ids) = tree.get_mut(&key) {
+                ids.retain(|&id| id != node_id);
+                if ids.is_empty() {
+                    tree.remove(&key);
+                }
+            }
+        }
+
+        let field = (label.to_string(), property_name.to_string());
+        match value {
+            Value::Int(i) => remove_from(self.int_index.get_mut(&field), *i, node_id),
+            Value::Float(f) => remove_from(self.float_index.get_mut(&field), OrderedFloat::new(*f), node_id),
+            Value::DateTime(ms) => remove_from(self.datetime_index.get_mut(&field), *ms, node_id),
+            _ => {}
+        }
+    }
+
     /// 删除节点的索引
     pub fn remove(&mut self, node_id: NodeId) {
         // 从整数索引中删除
@@ -515,12 +846,28 @@ impl RangeIndex {
                 entry.retain(|&id| id != node_id);
             }
         }
+
+        // 从日期时间索引中删除
+        for tree in self.datetime_index.values_mut() {
+            for entry in tree.values_mut() {
+                entry.retain(|&id| id != node_id);
+            }
+        }
     }
 
     /// 清空所有索引
     pub fn clear(&mut self) {
         self.int_index.clear();
         self.float_index.clear();
+        self.datetime_index.clear();
+    }
+
+    /// 删除某个 (label, property_name) 字段的范围索引
+    pub fn drop_field(&mut self, label: &str, property_name: &str) {
+        let key = (label.to_string(), property_name.to_string());
+        self.int_index.remove(&key);
+        self.float_index.remove(&key);
+        self.datetime_index.remove(&key);
     }
 
     /// 获取整数字段的索引数量
@@ -532,6 +879,48 @@ impl RangeIndex {
     pub fn float_field_count(&self) -> usize {
         self.float_index.len()
     }
+
+    /// 获取日期时间字段的索引数量
+    pub fn datetime_field_count(&self) -> usize {
+        self.datetime_index.len()
+    }
+
+    /// 已建立范围索引的 (label, property_name) 集合
+    pub fn fields(&self) -> HashSet<(String, String)> {
+        self.int_index
+            .keys()
+            .chain(self.float_index.keys())
+            .chain(self.datetime_index.keys())
+            .cloned()
+            .collect()
+    }
+
+    /// 列出所有索引项：(label, property_name, value, node_id)
+    pub fn entries(&self) -> Vec<(String, String, Value, NodeId)> {
+        let mut entries = Vec::new();
+        for ((label, prop), tree) in &self.int_index {
+            for (value, ids) in tree {
+                for &id in ids {
+                    entries.push((label.clone(), prop.clone(), Value::Int(*value), id));
+                }
+            }
+        }
+        for ((label, prop), tree) in &self.float_index {
+            for (value, ids) in tree {
+                for &id in ids {
+                    entries.push((label.clone(), prop.clone(), Value::Float(value.value()), id));
+                }
+            }
+        }
+        for ((label, prop), tree) in &self.datetime_index {
+            for (value, ids) in tree {
+                for &id in ids {
+                    entries.push((label.clone(), prop.clone(), Value::DateTime(*value), id));
+                }
+            }
+        }
+        entries
+    }
 }
 
 impl Default for RangeIndex {
@@ -553,6 +942,21 @@ mod tests {
         assert!(tokens.contains(&"world".to_string()));
     }
 
+    #[test]
+    fn test_analyzer_stop_words_and_stemming() {
+        let analyzer = TextAnalyzer::new()
+            .with_stop_words(&["The", "of"])
+            .with_stemming();
+        assert_eq!(
+            analyzer.analyze("The joining of graphs"),
+            vec!["join".to_string(), "graph".to_string()]
+        );
+
+        let analyzer = TextAnalyzer::new().with_tokenizer(Tokenizer::Alphanumeric);
+        assert_eq!(analyzer.analyze("e-mail"), vec!["e".to_string(), "mail".to_string()]);
+        assert_eq!(TextAnalyzer::new().analyze("e-mail"), vec!["email".to_string()]);
+    }
+
     #[test]
     fn test_fulltext_add_and_search() {
         let mut index = FullTextIndex::new();
@@ -613,6 +1017,31 @@ mod tests {
         assert_eq!(index.search("User", "name", "Alice"), vec![2]);
     }
 
+    #[test]
+    fn test_fulltext_save_and_load() {
+        let mut index = FullTextIndex::new();
+        index.add("Post", "body", "Rust graph database", 1);
+        index.add("Post", "body", "graph algorithms in Rust", 2);
+
+        let mut bytes = Vec::new();
+        index.save(&mut bytes).unwrap();
+        let loaded = FullTextIndex::load(bytes.as_slice()).unwrap();
+
+        let mut hits = loaded.search("Post", "body", "graph");
+        hits.sort();
+        assert_eq!(hits, vec![1, 2]);
+        assert_eq!(loaded.search_and("Post", "body", "rust database"), vec![1]);
+        assert_eq!(loaded.term_count(), index.term_count());
+        assert_eq!(loaded.doc_count(), 2);
+
+        // 没有版本头的旧数据和其他版本的数据都拒绝加载
+        let legacy = bincode::serialize(&index).unwrap();
+        assert!(FullTextIndex::load(legacy.as_slice()).is_err());
+        let mut future = bytes.clone();
+        future[4..8].copy_from_slice(&(FULLTEXT_FORMAT_VERSION + 1).to_le_bytes());
+        assert!(FullTextIndex::load(future.as_slice()).is_err());
+    }
+
     // ========== 范围索引测试 ==========
 
     #[test]
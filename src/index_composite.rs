@@ -22,6 +22,8 @@ pub enum CompositeIndexValue {
     Bool(bool),
     String(String),
     Null,
+    /// 毫秒时间戳，按时间先后排序
+    DateTime(i64),
 }
 
 impl CompositeIndexValue {
@@ -33,7 +35,8 @@ impl CompositeIndexValue {
             Value::Bool(b) => Some(CompositeIndexValue::Bool(*b)),
             Value::Text(s) => Some(CompositeIndexValue::String(s.clone())),
             Value::Null => Some(CompositeIndexValue::Null),
-            _ => None, // List 和 Map 不支持索引
+            Value::DateTime(ms) => Some(CompositeIndexValue::DateTime(*ms)),
+            _ => None, // List、Map 和 Bytes 不支持索引
         }
     }
 
@@ -45,6 +48,7 @@ impl CompositeIndexValue {
             CompositeIndexValue::Bool(_) => 1,
             CompositeIndexValue::String(s) => s.len(),
             CompositeIndexValue::Null => 0,
+            CompositeIndexValue::DateTime(_) => 8,
         }
     }
 }
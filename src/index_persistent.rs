@@ -21,6 +21,7 @@ pub enum IndexValue {
     Int(i64),
     Bool(bool),
     Text(String),
+    DateTime(i64),
 }
 
 impl From<&Value> for Option<IndexValue> {
@@ -32,6 +33,8 @@ impl From<&Value> for Option<IndexValue> {
             Value::Float(_) => None, // Float 不支持精确索引
             Value::Null => None,     // Null 不支持索引
             Value::List(_) => None,  // List 不支持索引
+            Value::Bytes(_) => None, // Bytes 不支持索引
+            Value::DateTime(ms) => Some(IndexValue::DateTime(*ms)),
         }
     }
 }
@@ -63,9 +66,11 @@ impl PersistentPropertyIndex {
             Value::Int(i) => IndexValue::Int(*i),
             Value::Bool(b) => IndexValue::Bool(*b),
             Value::Text(s) => IndexValue::Text(s.clone()),
+            Value::DateTime(ms) => IndexValue::DateTime(*ms),
             Value::Float(_) => return Ok(()), // Float 不支持索引
             Value::Null => return Ok(()),     // Null 不支持索引
             Value::List(_) => return Ok(()),  // List 不支持索引
+            Value::Bytes(_) => return Ok(()), // Bytes 不支持索引
         };
 
         let key = self.index_key(label, property, &idx_value);
@@ -96,9 +101,11 @@ impl PersistentPropertyIndex {
             Value::Int(i) => IndexValue::Int(*i),
             Value::Bool(b) => IndexValue::Bool(*b),
             Value::Text(s) => IndexValue::Text(s.clone()),
+            Value::DateTime(ms) => IndexValue::DateTime(*ms),
             Value::Float(_) => return Ok(()), // Float 不支持索引
             Value::Null => return Ok(()),     // Null 不支持索引
             Value::List(_) => return Ok(()),  // List 不支持索引
+            Value::Bytes(_) => return Ok(()), // Bytes 不支持索引
         };
 
         let key = self.index_key(label, property, &idx_value);
@@ -126,9 +133,11 @@ impl PersistentPropertyIndex {
             Value::Int(i) => IndexValue::Int(*i),
             Value::Bool(b) => IndexValue::Bool(*b),
             Value::Text(s) => IndexValue::Text(s.clone()),
+            Value::DateTime(ms) => IndexValue::DateTime(*ms),
             Value::Float(_) => return Ok(Vec::new()),
             Value::Null => return Ok(Vec::new()),
             Value::List(_) => return Ok(Vec::new()),
+            Value::Bytes(_) => return Ok(Vec::new()),
         };
 
         let key = self.index_key(label, property, &idx_value);
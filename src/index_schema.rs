@@ -9,6 +9,10 @@ pub struct IndexSchema {
     /// key: 索引名称 (如 "user_name_email")
     /// value: (label, [properties]) - 标签和属性列表
     composite_indexes: HashMap<String, (String, Vec<String>)>,
+    /// 全文索引字段 (label, property_name)
+    fulltext: HashSet<(String, String)>,
+    /// 范围索引字段 (label, property_name)
+    range: HashSet<(String, String)>,
 }
 
 impl IndexSchema {
@@ -16,6 +20,8 @@ impl IndexSchema {
         Self {
             indexed: HashSet::new(),
             composite_indexes: HashMap::new(),
+            fulltext: HashSet::new(),
+            range: HashSet::new(),
         }
     }
 
@@ -25,6 +31,16 @@ impl IndexSchema {
             .insert((label.to_string(), property.to_string()));
     }
 
+    /// 从索引配置中移除一个 (label, property)
+    pub fn remove_index(&mut self, label: &str, property: &str) -> bool {
+        self.indexed.remove(&(label.to_string(), property.to_string()))
+    }
+
+    /// 所有单属性索引的 (label, property_name)
+    pub fn indexed_properties(&self) -> impl Iterator<Item = &(String, String)> {
+        self.indexed.iter()
+    }
+
     /// 添加复合索引
     ///
     /// # 参数
@@ -69,6 +85,36 @@ impl IndexSchema {
         &self.composite_indexes
     }
 
+    /// 登记一个全文索引字段
+    pub fn add_fulltext_field(&mut self, label: &str, property: &str) {
+        self.fulltext.insert((label.to_string(), property.to_string()));
+    }
+
+    /// 移除全文索引字段
+    pub fn remove_fulltext_field(&mut self, label: &str, property: &str) -> bool {
+        self.fulltext.remove(&(label.to_string(), property.to_string()))
+    }
+
+    /// 所有全文索引字段
+    pub fn fulltext_fields(&self) -> impl Iterator<Item = &(String, String)> {
+        self.fulltext.iter()
+    }
+
+    /// 登记一个范围索引字段
+    pub fn add_range_field(&mut self, label: &str, property: &str) {
+        self.range.insert((label.to_string(), property.to_string()));
+    }
+
+    /// 移除范围索引字段
+    pub fn remove_range_field(&mut self, label: &str, property: &str) -> bool {
+        self.range.remove(&(label.to_string(), property.to_string()))
+    }
+
+    /// 所有范围索引字段
+    pub fn range_fields(&self) -> impl Iterator<Item = &(String, String)> {
+        self.range.iter()
+    }
+
     /// 预定义一个默认 schema（User.name, User.age, User.id）
     pub fn default() -> Self {
         let mut schema = Self::new();
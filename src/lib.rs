@@ -13,10 +13,12 @@ pub mod server;
 pub mod cypher;
 pub mod algorithms;
 pub mod concurrent;
+pub mod bulk_loader;
 pub mod constraints;
 pub mod service;
 pub mod visualization;
 pub mod transactions;
+pub mod cancellation;
 
 #[cfg(feature = "caching")]
 pub mod cache;
@@ -25,14 +27,16 @@ pub mod cache;
 pub mod grpc;
 
 pub use crate::graph::db::GraphDatabase;
-pub use crate::graph::{AsyncGraphDB, AsyncError};
-pub use crate::storage::{NodeId, AsyncStorage};
+pub use crate::graph::{AsyncGraphDB, AsyncError, GraphListener};
+pub use crate::storage::{NodeId, IdStrategy, AsyncStorage};
 pub use crate::concurrent::ConcurrentGraphDB;
-pub use crate::query::Query;
+pub use crate::bulk_loader::{BulkLoader, BulkLoaderConfig, BulkLoadReport, BulkNode, BulkRel};
+pub use crate::query::{Query, RelQuery};
+pub use crate::cancellation::{CancellationToken, CancelOnDrop, Cancelled};
 
 // 导出约束模块
 pub use crate::constraints::{
-    Constraint, ConstraintType, ConstraintValidation, ConstraintManager,
+    Constraint, ConstraintType, ConstraintValidation, ConstraintManager, RelDirection,
 };
 
 // 导出查询引擎
@@ -41,6 +45,7 @@ pub use crate::query_engine::{
     QueryValue,
     QueryRow,
     QueryRows,
+    ColumnType,
     QueryPath,
     QueryContext,
     PathQueryBuilder,
@@ -53,20 +58,21 @@ pub use crate::query_engine::{
 // 导出可视化模块
 pub use crate::visualization::{
     GraphView, VisNode, VisEdge, NodeStyle, EdgeStyle, GraphMetadata, GraphFormat, Position,
+    PropertyFilter,
     Layout, LayoutConfig, CircleLayout, ForceDirectedLayout, HierarchicalLayout,
-    GraphExport, JsonExport, DotExport,
+    GraphExport, JsonExport, DotExport, GraphMlExport,
 };
 
 // 导出事务模块
 pub use crate::transactions::{
     Transaction, TransactionManager, TransactionOp, TransactionResult, TransactionError,
-    TransactionStatus, Snapshot, SnapshotManager, NodeData, RelData,
-    IsolationLevel, TransactionConfig,
+    TransactionStatus, TxInfo, Snapshot, SnapshotManager, NodeData, RelData,
+    IsolationLevel, TransactionConfig, RetryConfig, graph_diff, GraphDiff,
 };
 
 // 导出高级索引模块
 pub use crate::index_advanced::{
-    FullTextIndex, RangeIndex, OrderedFloat,
+    FullTextIndex, RangeIndex, OrderedFloat, TextAnalyzer, Tokenizer,
 };
 
 // 导出复合索引模块
@@ -1,7 +1,9 @@
+use crate::cancellation::CancellationToken;
 use crate::graph::db::GraphDatabase;
-use crate::graph::model::Node;
-use crate::storage::{NodeId, StorageEngine};
+use crate::graph::model::{Node, Relationship};
+use crate::storage::{NodeId, PropPredicate, StorageEngine, StorageError, TxHandle};
 use crate::values::Value;
+use std::collections::HashMap;
 
 #[cfg(feature = "caching")]
 use crate::cache::query_cache::{QueryCache, QueryFingerprint, QueryType};
@@ -10,10 +12,20 @@ use crate::cache::query_cache::{QueryCache, QueryFingerprint, QueryType};
 /// - from_label：按标签选起点
 /// - where_prop_eq / where_prop_int_gt：属性过滤
 /// - out：沿指定关系走一层（可多次调用）
+/// - out_where / out_where_pred：只沿关系属性满足条件的出边走一层
 /// - distinct：ID 去重
+/// - traversed_rels：最近一次 out/in_ 经过的关系
+/// - in_transaction：在事务视角下读取节点（可以看到事务自己未提交的写入）
+/// - with_cancellation：令牌被取消后扫描和遍历提前结束
 pub struct Query<'a, E: StorageEngine> {
     db: &'a GraphDatabase<E>,
     pub(crate) current: Vec<NodeId>,
+    /// 最近一次单跳遍历经过的关系
+    traversed: Vec<Relationship>,
+    /// 所在事务中未提交的节点写入；不在事务中时为空
+    tx: TxNodes,
+    /// 取消令牌；被取消后扫描和遍历在下一个循环边界处停止
+    cancel: Option<CancellationToken>,
     #[cfg(feature = "caching")]
     fingerprint: Option<QueryFingerprint>,
 }
@@ -24,6 +36,9 @@ impl<'a, E: StorageEngine> Query<'a, E> {
         Self {
             db,
             current: Vec::new(),
+            traversed: Vec::new(),
+            tx: TxNodes::default(),
+            cancel: None,
             #[cfg(feature = "caching")]
             fingerprint: None,
         }
@@ -35,14 +50,48 @@ impl<'a, E: StorageEngine> Query<'a, E> {
         Self {
             db,
             current: Vec::new(),
+            traversed: Vec::new(),
+            tx: TxNodes::default(),
+            cancel: None,
             fingerprint: Some(QueryFingerprint::label_query("*")),
         }
     }
 
+    /// 在事务 `tx` 中读取：能看到事务自己未提交的节点写入，其余读取已提交的数据
+    ///
+    /// 存储引擎事务没有隔离级别，这里始终按读已提交（`IsolationLevel::ReadCommitted`）读取：
+    /// 其他事务未提交的写入不可见，其他事务在本事务期间提交的写入可见，
+    /// 因此不提供可重复读或可串行化的保证。关系遍历只看已提交的关系。
+    /// 需要在选起点（`from_label` 等）之前调用；事务不存在时返回错误。
+    pub fn in_transaction(mut self, tx: TxHandle) -> Result<Self, StorageError> {
+        self.tx = TxNodes(self.db.tx_pending_nodes(tx)?);
+        Ok(self)
+    }
+
+    /// 关联取消令牌
+    ///
+    /// 令牌被取消后，`from_label` 等扫描和 `out` / `in_` / 可变长度遍历会提前结束，
+    /// 此时的结果只是部分结果，调用方应通过 [`is_cancelled`](Self::is_cancelled) 判断并丢弃。
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancel = Some(token);
+        self
+    }
+
+    /// 关联的令牌是否已被取消（没有关联令牌时为 false）
+    pub fn is_cancelled(&self) -> bool {
+        self.cancel.as_ref().is_some_and(|token| token.is_cancelled())
+    }
+
     /// 按 label 选出起始节点（不看属性，纯 label）
     pub fn from_label(mut self, label: &str) -> Self {
         let mut ids = Vec::new();
         for stored in self.db.all_stored_nodes() {
+            if self.is_cancelled() {
+                break;
+            }
+            if self.tx.0.contains_key(&stored.id) {
+                continue;
+            }
             let node = Node {
                 id: stored.id,
                 labels: stored.labels,
@@ -52,29 +101,66 @@ impl<'a, E: StorageEngine> Query<'a, E> {
                 ids.push(node.id);
             }
         }
+        ids.extend(self.tx.pending_matching(|node| node.has_label(label)));
         self.current = ids;
         self
     }
 
-    /// 使用索引按 label + 文本属性 = 值 选起点
-    pub fn from_label_and_prop_eq(mut self, label: &str, key: &str, expected: &str) -> Self {
-        use crate::values::Value;
-        let ids = self
+    /// 选出同时带有 `labels` 中所有标签的节点
+    ///
+    /// 先按第一个标签下推到存储层扫描，再检查其余标签；`labels` 为空时选出所有节点。
+    pub fn from_labels_all(self, labels: &[&str]) -> Self {
+        self.select_by_labels(labels.first().copied(), |node| {
+            labels.iter().all(|label| node.has_label(label))
+        })
+    }
+
+    /// 选出至少带有 `labels` 中一个标签的节点；`labels` 为空时结果为空
+    pub fn from_labels_any(self, labels: &[&str]) -> Self {
+        let narrow = match labels {
+            [label] => Some(*label),
+            _ => None,
+        };
+        self.select_by_labels(narrow, |node| labels.iter().any(|label| node.has_label(label)))
+    }
+
+    /// 扫描（可按单个标签下推过滤的）节点并保留满足 `keep` 的，同时合并事务内的写入
+    fn select_by_labels(mut self, narrow: Option<&str>, keep: impl Fn(&Node) -> bool) -> Self {
+        let filter = match narrow {
+            Some(label) => crate::storage::NodeFilter::new().with_label(label),
+            None => crate::storage::NodeFilter::new(),
+        };
+        let mut ids: Vec<NodeId> = self
             .db
-            .index
-            .find(label, key, &Value::Text(expected.to_string()));
+            .scan_nodes(filter)
+            .take_while(|_| !self.is_cancelled())
+            .filter(|stored| !self.tx.0.contains_key(&stored.id))
+            .map(|stored| Node {
+                id: stored.id,
+                labels: stored.labels,
+                props: stored.props,
+            })
+            .filter(|node| keep(node))
+            .map(|node| node.id)
+            .collect();
+        ids.extend(self.tx.pending_matching(&keep));
         self.current = ids;
         self
     }
 
+    /// 使用索引按 label + 文本属性 = 值 选起点
+    pub fn from_label_and_prop_eq(mut self, label: &str, key: &str, expected: &str) -> Self {
+        let expected = Value::Text(expected.to_string());
+        let ids = self.db.index.find(label, key, &expected);
+        self.current = self.tx.patch_index_hits(ids, label, key, &expected);
+        self
+    }
+
     /// 使用索引按 label + 整型属性 = 值 选起点
     pub fn from_label_and_prop_int_eq(mut self, label: &str, key: &str, expected: i64) -> Self {
-        use crate::values::Value;
-        let ids = self
-            .db
-            .index
-            .find(label, key, &Value::Int(expected));
-        self.current = ids;
+        let expected = Value::Int(expected);
+        let ids = self.db.index.find(label, key, &expected);
+        self.current = self.tx.patch_index_hits(ids, label, key, &expected);
         self
     }
 
@@ -82,7 +168,7 @@ impl<'a, E: StorageEngine> Query<'a, E> {
     pub fn where_prop_eq(mut self, key: &str, expected: &str) -> Self {
         let mut filtered = Vec::new();
         for id in self.current.iter().copied() {
-            if let Some(node) = self.db.get_node(id) {
+            if let Some(node) = self.tx.get(self.db, id) {
                 if let Some(Value::Text(ref v)) = node.get(key) {
                     if v == expected {
                         filtered.push(id);
@@ -94,11 +180,37 @@ impl<'a, E: StorageEngine> Query<'a, E> {
         self
     }
 
+    /// 按文本属性等于过滤，忽略大小写（Unicode 小写比较）
+    pub fn where_prop_eq_ignore_case(mut self, key: &str, expected: &str) -> Self {
+        let expected = expected.to_lowercase();
+        self.current.retain(|&id| {
+            matches!(
+                self.tx.get(self.db, id).and_then(|n| n.props.get(key).cloned()),
+                Some(Value::Text(v)) if v.to_lowercase() == expected
+            )
+        });
+        self
+    }
+
+    /// 只保留带有属性 `key` 的节点（不论属性值）
+    pub fn where_has_prop(mut self, key: &str) -> Self {
+        self.current
+            .retain(|&id| self.tx.get(self.db, id).is_some_and(|n| n.props.contains_key(key)));
+        self
+    }
+
+    /// 只保留缺少属性 `key` 的节点
+    pub fn where_missing_prop(mut self, key: &str) -> Self {
+        self.current
+            .retain(|&id| self.tx.get(self.db, id).is_some_and(|n| !n.props.contains_key(key)));
+        self
+    }
+
     /// 按整型属性等于过滤
     pub fn where_prop_int_eq(mut self, key: &str, expected: i64) -> Self {
         let mut filtered = Vec::new();
         for id in self.current.iter().copied() {
-            if let Some(node) = self.db.get_node(id) {
+            if let Some(node) = self.tx.get(self.db, id) {
                 if let Some(Value::Int(v)) = node.get(key) {
                     if *v == expected {
                         filtered.push(id);
@@ -114,7 +226,7 @@ impl<'a, E: StorageEngine> Query<'a, E> {
     pub fn where_prop_int_gt(mut self, key: &str, min: i64) -> Self {
         let mut filtered = Vec::new();
         for id in self.current.iter().copied() {
-            if let Some(node) = self.db.get_node(id) {
+            if let Some(node) = self.tx.get(self.db, id) {
                 if let Some(Value::Int(v)) = node.get(key) {
                     if *v > min {
                         filtered.push(id);
@@ -127,33 +239,71 @@ impl<'a, E: StorageEngine> Query<'a, E> {
     }
 
     /// 沿着指定类型的出边走一层
-    pub fn out(mut self, rel_type: &str) -> Self {
+    pub fn out(self, rel_type: &str) -> Self {
+        self.out_filtered(rel_type, |_| true)
+    }
+
+    /// 沿着指定类型、且关系属性 `key` 等于 `value` 的出边走一层
+    pub fn out_where(self, rel_type: &str, key: &str, value: &Value) -> Self {
+        self.out_where_pred(rel_type, key, &PropPredicate::Eq(value.clone()))
+    }
+
+    /// 沿着指定类型、且关系属性 `key` 满足谓词的出边走一层
+    ///
+    /// 例如只沿 `since > 2020` 的 FRIEND 关系遍历：
+    /// `q.out_where_pred("FRIEND", "since", &PropPredicate::Gt(Value::Int(2020)))`。
+    /// 关系缺少该属性时不满足任何谓词。
+    pub fn out_where_pred(self, rel_type: &str, key: &str, pred: &PropPredicate) -> Self {
+        self.out_filtered(rel_type, |rel| pred.matches(rel.props.get(key)))
+    }
+
+    fn out_filtered(mut self, rel_type: &str, keep: impl Fn(&Relationship) -> bool) -> Self {
         let mut next = Vec::new();
+        let mut traversed = Vec::new();
         for id in self.current.iter().copied() {
+            if self.is_cancelled() {
+                break;
+            }
             for rel in self.db.neighbors_out(id) {
-                if rel.typ == rel_type {
+                if rel.typ == rel_type && keep(&rel) {
                     next.push(rel.end);
+                    traversed.push(rel);
                 }
             }
         }
         self.current = next;
+        self.traversed = traversed;
         self
     }
 
     /// 沿着指定类型的入边走一层（反向遍历）
     pub fn in_(mut self, rel_type: &str) -> Self {
         let mut next = Vec::new();
+        let mut traversed = Vec::new();
         for id in self.current.iter().copied() {
+            if self.is_cancelled() {
+                break;
+            }
             for rel in self.db.neighbors_in(id) {
                 if rel.typ == rel_type {
                     next.push(rel.start);
+                    traversed.push(rel);
                 }
             }
         }
         self.current = next;
+        self.traversed = traversed;
         self
     }
 
+    /// 最近一次 `out` / `out_where*` / `in_` 经过的关系，按遍历顺序排列
+    ///
+    /// 每条关系对应一次到达，与该步之后的节点列表一一对应（之后的过滤、去重不会同步修改）。
+    /// 可变长度遍历不保留关系，调用后返回空列表。
+    pub fn traversed_rels(&self) -> &[Relationship] {
+        &self.traversed
+    }
+
     /// 可变长度路径遍历（出边）
     ///
     /// 从当前节点集合出发，沿着指定类型的关系遍历 min_hops 到 max_hops 跳
@@ -168,53 +318,11 @@ impl<'a, E: StorageEngine> Query<'a, E> {
     /// // 查找所有在 2-3 跳内可达的朋友
     /// query.out_variable_length("FRIEND", 2, Some(3))
     /// ```
-    pub fn out_variable_length(mut self, rel_type: &str, min_hops: usize, max_hops: Option<usize>) -> Self {
-        let mut result = Vec::new();
-        let mut visited = std::collections::HashSet::new();
-
-        for start_id in self.current.iter().copied() {
-            // BFS 遍历，记录每个节点所在的深度
-            let mut queue = std::collections::VecDeque::new();
-
-            // 从起始节点的邻居开始，深度为 1
-            for rel in self.db.neighbors_out(start_id) {
-                if rel.typ == rel_type {
-                    let neighbor = rel.end;
-                    if !visited.contains(&neighbor) {
-                        visited.insert(neighbor);
-                        queue.push_back((neighbor, 1));
-                    }
-                }
-            }
-
-            while let Some((node_id, depth)) = queue.pop_front() {
-                // 如果达到最小跳数，将节点加入结果
-                if depth >= min_hops {
-                    result.push(node_id);
-                }
-
-                // 如果达到最大跳数，停止扩展
-                if let Some(max) = max_hops {
-                    if depth >= max {
-                        continue;
-                    }
-                }
-
-                // 扩展邻接节点
-                for rel in self.db.neighbors_out(node_id) {
-                    if rel.typ == rel_type {
-                        let neighbor = rel.end;
-                        if !visited.contains(&neighbor) {
-                            visited.insert(neighbor);
-                            queue.push_back((neighbor, depth + 1));
-                        }
-                    }
-                }
-            }
-        }
-
-        self.current = result;
-        self
+    pub fn out_variable_length(self, rel_type: &str, min_hops: usize, max_hops: Option<usize>) -> Self {
+        let db = self.db;
+        self.expand_variable_length(min_hops, max_hops, |id| {
+            db.neighbors_out(id).filter(|rel| rel.typ == rel_type).map(|rel| rel.end).collect()
+        })
     }
 
     /// 可变长度路径遍历（入边）
@@ -225,53 +333,11 @@ impl<'a, E: StorageEngine> Query<'a, E> {
     /// - `rel_type`: 关系类型
     /// - `min_hops`: 最小跳数（inclusive）
     /// - `max_hops`: 最大跳数（inclusive），None 表示无限制
-    pub fn in_variable_length(mut self, rel_type: &str, min_hops: usize, max_hops: Option<usize>) -> Self {
-        let mut result = Vec::new();
-        let mut visited = std::collections::HashSet::new();
-
-        for start_id in self.current.iter().copied() {
-            // BFS 遍历，记录每个节点所在的深度
-            let mut queue = std::collections::VecDeque::new();
-
-            // 从起始节点的入边邻居开始，深度为 1
-            for rel in self.db.neighbors_in(start_id) {
-                if rel.typ == rel_type {
-                    let neighbor = rel.start;
-                    if !visited.contains(&neighbor) {
-                        visited.insert(neighbor);
-                        queue.push_back((neighbor, 1));
-                    }
-                }
-            }
-
-            while let Some((node_id, depth)) = queue.pop_front() {
-                // 如果达到最小跳数，将节点加入结果
-                if depth >= min_hops {
-                    result.push(node_id);
-                }
-
-                // 如果达到最大跳数，停止扩展
-                if let Some(max) = max_hops {
-                    if depth >= max {
-                        continue;
-                    }
-                }
-
-                // 扩展邻接节点（反向）
-                for rel in self.db.neighbors_in(node_id) {
-                    if rel.typ == rel_type {
-                        let neighbor = rel.start;
-                        if !visited.contains(&neighbor) {
-                            visited.insert(neighbor);
-                            queue.push_back((neighbor, depth + 1));
-                        }
-                    }
-                }
-            }
-        }
-
-        self.current = result;
-        self
+    pub fn in_variable_length(self, rel_type: &str, min_hops: usize, max_hops: Option<usize>) -> Self {
+        let db = self.db;
+        self.expand_variable_length(min_hops, max_hops, |id| {
+            db.neighbors_in(id).filter(|rel| rel.typ == rel_type).map(|rel| rel.start).collect()
+        })
     }
 
     /// 无向可变长度路径遍历
@@ -282,68 +348,72 @@ impl<'a, E: StorageEngine> Query<'a, E> {
     /// - `rel_type`: 关系类型
     /// - `min_hops`: 最小跳数（inclusive）
     /// - `max_hops`: 最大跳数（inclusive），None 表示无限制
-    pub fn undirected_variable_length(mut self, rel_type: &str, min_hops: usize, max_hops: Option<usize>) -> Self {
+    pub fn undirected_variable_length(self, rel_type: &str, min_hops: usize, max_hops: Option<usize>) -> Self {
+        let db = self.db;
+        self.expand_variable_length(min_hops, max_hops, |id| {
+            db.neighbors_out(id)
+                .filter(|rel| rel.typ == rel_type)
+                .map(|rel| rel.end)
+                .chain(db.neighbors_in(id).filter(|rel| rel.typ == rel_type).map(|rel| rel.start))
+                .collect()
+        })
+    }
+
+    /// 可变长度遍历的公共实现，`step` 返回一个节点沿关系走一跳能到达的节点
+    ///
+    /// 前 `min_hops` 跳逐层展开，同一节点在不同深度分别计入，因此经较短路径也能到达的节点
+    /// 只要还存在长度落在范围内的路径就不会被漏掉；之后从第 `min_hops` 层出发按 BFS 扩展，
+    /// 已访问的节点不再重复展开，无上限时也能结束。结果按首次到达的顺序去重。
+    fn expand_variable_length(
+        mut self,
+        min_hops: usize,
+        max_hops: Option<usize>,
+        step: impl Fn(NodeId) -> Vec<NodeId>,
+    ) -> Self {
+        self.traversed.clear();
         let mut result = Vec::new();
-        let mut visited = std::collections::HashSet::new();
+        let mut emitted = std::collections::HashSet::new();
+        // 路径至少一跳
+        let min_hops = min_hops.max(1);
+        if max_hops.is_some_and(|max| max < min_hops) {
+            self.current = result;
+            return self;
+        }
 
         for start_id in self.current.iter().copied() {
-            // BFS 遍历，记录每个节点所在的深度
-            let mut queue = std::collections::VecDeque::new();
-
-            // 从起始节点的邻居开始（双向），深度为 1
-            // 出边
-            for rel in self.db.neighbors_out(start_id) {
-                if rel.typ == rel_type {
-                    let neighbor = rel.end;
-                    if !visited.contains(&neighbor) {
-                        visited.insert(neighbor);
-                        queue.push_back((neighbor, 1));
-                    }
-                }
-            }
-            // 入边
-            for rel in self.db.neighbors_in(start_id) {
-                if rel.typ == rel_type {
-                    let neighbor = rel.start;
-                    if !visited.contains(&neighbor) {
-                        visited.insert(neighbor);
-                        queue.push_back((neighbor, 1));
-                    }
+            // 恰好 min_hops 跳可达的节点
+            let mut frontier = vec![start_id];
+            for _ in 0..min_hops {
+                if self.is_cancelled() {
+                    break;
                 }
+                let mut seen = std::collections::HashSet::new();
+                frontier = frontier
+                    .into_iter()
+                    .flat_map(&step)
+                    .filter(|id| seen.insert(*id))
+                    .collect();
             }
 
+            // 从该层继续 BFS，深度为相对 min_hops 的额外跳数
+            let mut visited: std::collections::HashSet<NodeId> = frontier.iter().copied().collect();
+            let mut queue: std::collections::VecDeque<(NodeId, usize)> =
+                frontier.into_iter().map(|id| (id, min_hops)).collect();
             while let Some((node_id, depth)) = queue.pop_front() {
-                // 如果达到最小跳数，将节点加入结果
-                if depth >= min_hops {
+                if self.is_cancelled() {
+                    break;
+                }
+                if emitted.insert(node_id) {
                     result.push(node_id);
                 }
 
                 // 如果达到最大跳数，停止扩展
-                if let Some(max) = max_hops {
-                    if depth >= max {
-                        continue;
-                    }
-                }
-
-                // 扩展出边
-                for rel in self.db.neighbors_out(node_id) {
-                    if rel.typ == rel_type {
-                        let neighbor = rel.end;
-                        if !visited.contains(&neighbor) {
-                            visited.insert(neighbor);
-                            queue.push_back((neighbor, depth + 1));
-                        }
-                    }
+                if max_hops.is_some_and(|max| depth >= max) {
+                    continue;
                 }
-
-                // 扩展入边
-                for rel in self.db.neighbors_in(node_id) {
-                    if rel.typ == rel_type {
-                        let neighbor = rel.start;
-                        if !visited.contains(&neighbor) {
-                            visited.insert(neighbor);
-                            queue.push_back((neighbor, depth + 1));
-                        }
+                for neighbor in step(node_id) {
+                    if visited.insert(neighbor) {
+                        queue.push_back((neighbor, depth + 1));
                     }
                 }
             }
@@ -437,14 +507,14 @@ impl<'a, E: StorageEngine> Query<'a, E> {
         self
     }
 
-    /// 按属性排序（支持整型和文本）
+    /// 按属性排序（支持整型、文本和日期时间）
     pub fn order_by(mut self, key: &str, ascending: bool) -> Self {
         let key = key.to_string();
         let mut nodes_with_vals: Vec<(NodeId, Option<Value>)> = self
             .current
             .iter()
             .map(|&id| {
-                let val = self.db.get_node(id).and_then(|n| n.props.get(&key).cloned());
+                let val = self.tx.get(self.db, id).and_then(|n| n.props.get(&key).cloned());
                 (id, val)
             })
             .collect();
@@ -457,6 +527,9 @@ impl<'a, E: StorageEngine> Query<'a, E> {
                 (Some(Value::Text(x)), Some(Value::Text(y))) => {
                     if ascending { x.cmp(y) } else { y.cmp(x) }
                 }
+                (Some(Value::DateTime(x)), Some(Value::DateTime(y))) => {
+                    if ascending { x.cmp(y) } else { y.cmp(x) }
+                }
                 (None, Some(_)) => std::cmp::Ordering::Greater,
                 (Some(_), None) => std::cmp::Ordering::Less,
                 _ => std::cmp::Ordering::Equal,
@@ -471,7 +544,7 @@ impl<'a, E: StorageEngine> Query<'a, E> {
     pub fn collect_nodes(self) -> Vec<Node> {
         self.current
             .into_iter()
-            .filter_map(|id| self.db.get_node(id))
+            .filter_map(|id| self.tx.get(self.db, id))
             .collect()
     }
 
@@ -479,10 +552,31 @@ impl<'a, E: StorageEngine> Query<'a, E> {
     pub fn collect_nodes_ref(&self) -> Vec<Node> {
         self.current
             .iter()
-            .filter_map(|id| self.db.get_node(*id))
+            .filter_map(|id| self.tx.get(self.db, *id))
             .collect()
     }
 
+    /// 展开当前节点指定类型的出边，返回 (起点, 关系, 终点) 三元组
+    ///
+    /// 按当前节点顺序和出边顺序排列；无向关系以当前节点为起点。
+    pub fn expand_edges(self, rel_type: &str) -> Vec<(Node, Relationship, Node)> {
+        let mut triples = Vec::new();
+        for id in self.current.iter().copied() {
+            let Some(start) = self.tx.get(self.db, id) else {
+                continue;
+            };
+            for rel in self.db.neighbors_out(id) {
+                if rel.typ != rel_type {
+                    continue;
+                }
+                if let Some(end) = self.tx.get(self.db, rel.end) {
+                    triples.push((start.clone(), rel, end));
+                }
+            }
+        }
+        triples
+    }
+
     /// 聚合：计数
     pub fn count(self) -> usize {
         self.current.len()
@@ -493,7 +587,7 @@ impl<'a, E: StorageEngine> Query<'a, E> {
         let key = key.to_string();
         self.current
             .into_iter()
-            .filter_map(|id| self.db.get_node(id))
+            .filter_map(|id| self.tx.get(self.db, id))
             .filter_map(|n| n.props.get(&key).cloned())
             .filter_map(|v| if let Value::Int(i) = v { Some(i) } else { None })
             .sum()
@@ -505,7 +599,7 @@ impl<'a, E: StorageEngine> Query<'a, E> {
         let values: Vec<i64> = self
             .current
             .iter()
-            .filter_map(|&id| self.db.get_node(id))
+            .filter_map(|&id| self.tx.get(self.db, id))
             .filter_map(|n| n.props.get(&key).cloned())
             .filter_map(|v| if let Value::Int(i) = v { Some(i) } else { None })
             .collect();
@@ -524,7 +618,8 @@ impl<'a, E: StorageEngine> Query<'a, E> {
     /// - `percentile`: 百分位数 (0.0 到 1.0)，例如 0.5 表示中位数
     ///
     /// # 返回
-    /// 百分位数值，如果没有数据则返回 None
+    /// 百分位数值；`percentile` 不在 [0, 1] 内（含 NaN），或过滤掉非数值、
+    /// NaN 和无穷大后没有数据时返回 None
     ///
     /// # 示例
     /// ```ignore
@@ -536,24 +631,12 @@ impl<'a, E: StorageEngine> Query<'a, E> {
             return None;
         }
 
-        let key = key.to_string();
-        let mut values: Vec<f64> = self
-            .current
-            .iter()
-            .filter_map(|&id| self.db.get_node(id))
-            .filter_map(|n| n.props.get(&key).cloned())
-            .filter_map(|v| match v {
-                Value::Int(i) => Some(i as f64),
-                Value::Float(f) => Some(f),
-                _ => None,
-            })
-            .collect();
-
+        let mut values = self.finite_values(key);
         if values.is_empty() {
             return None;
         }
 
-        values.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+        values.sort_by(f64::total_cmp);
 
         let n = values.len();
         if n == 1 {
@@ -579,23 +662,12 @@ impl<'a, E: StorageEngine> Query<'a, E> {
     /// - `key`: 属性名
     ///
     /// # 返回
-    /// 标准差值，如果数据少于2个则返回 None
+    /// 标准差值；NaN 和无穷大的属性值不参与计算，剩余数据少于 2 个时返回 None
     ///
     /// # 公式
     /// sqrt(sum((x - mean)^2) / (n - 1))
     pub fn stdev(self, key: &str) -> Option<f64> {
-        let key = key.to_string();
-        let values: Vec<f64> = self
-            .current
-            .iter()
-            .filter_map(|&id| self.db.get_node(id))
-            .filter_map(|n| n.props.get(&key).cloned())
-            .filter_map(|v| match v {
-                Value::Int(i) => Some(i as f64),
-                Value::Float(f) => Some(f),
-                _ => None,
-            })
-            .collect();
+        let values = self.finite_values(key);
 
         if values.len() < 2 {
             return None;
@@ -616,23 +688,12 @@ impl<'a, E: StorageEngine> Query<'a, E> {
     /// - `key`: 属性名
     ///
     /// # 返回
-    /// 方差值，如果数据少于2个则返回 None
+    /// 方差值；NaN 和无穷大的属性值不参与计算，剩余数据少于 2 个时返回 None
     ///
     /// # 公式
     /// sum((x - mean)^2) / (n - 1)
     pub fn variance(self, key: &str) -> Option<f64> {
-        let key = key.to_string();
-        let values: Vec<f64> = self
-            .current
-            .iter()
-            .filter_map(|&id| self.db.get_node(id))
-            .filter_map(|n| n.props.get(&key).cloned())
-            .filter_map(|v| match v {
-                Value::Int(i) => Some(i as f64),
-                Value::Float(f) => Some(f),
-                _ => None,
-            })
-            .collect();
+        let values = self.finite_values(key);
 
         if values.len() < 2 {
             return None;
@@ -647,6 +708,20 @@ impl<'a, E: StorageEngine> Query<'a, E> {
         Some(variance)
     }
 
+    /// 当前节点上属性 `key` 的有限数值（Int 转为 f64；NaN、无穷大和非数值被跳过）
+    fn finite_values(&self, key: &str) -> Vec<f64> {
+        self.current
+            .iter()
+            .filter_map(|&id| self.tx.get(self.db, id))
+            .filter_map(|n| match n.props.get(key) {
+                Some(Value::Int(i)) => Some(*i as f64),
+                Some(Value::Float(f)) => Some(*f),
+                _ => None,
+            })
+            .filter(|v| v.is_finite())
+            .collect()
+    }
+
     // ========== 缓存查询方法 ==========
 
     /// 使用缓存收集节点
@@ -660,14 +735,14 @@ impl<'a, E: StorageEngine> Query<'a, E> {
                 // 从缓存命中的ID列表收集节点
                 return cached_ids
                     .into_iter()
-                    .filter_map(|id| self.db.get_node(id))
+                    .filter_map(|id| self.tx.get(self.db, id))
                     .collect();
             }
 
             // 缓存未命中，执行查询并缓存结果
             let result: Vec<Node> = current_ids
                 .into_iter()
-                .filter_map(|id| self.db.get_node(id))
+                .filter_map(|id| self.tx.get(self.db, id))
                 .collect();
 
             let result_ids: Vec<NodeId> = result.iter().map(|n| n.id).collect();
@@ -701,3 +776,164 @@ impl<'a, E: StorageEngine> Query<'a, E> {
         }
     }
 }
+
+/// 事务中尚未提交的节点写入：`None` 表示节点在事务中被删除
+#[derive(Default)]
+struct TxNodes(HashMap<NodeId, Option<Node>>);
+
+impl TxNodes {
+    /// 事务视角下的节点：先看事务内的写入，再回落到已提交的数据
+    fn get<E: StorageEngine>(&self, db: &GraphDatabase<E>, id: NodeId) -> Option<Node> {
+        match self.0.get(&id) {
+            Some(pending) => pending.clone(),
+            None => db.get_node(id),
+        }
+    }
+
+    /// 事务中写入且满足条件的节点，按 ID 排序
+    fn pending_matching(&self, pred: impl Fn(&Node) -> bool) -> Vec<NodeId> {
+        let mut ids: Vec<NodeId> = self.0.values().flatten().filter(|n| pred(n)).map(|n| n.id).collect();
+        ids.sort_unstable();
+        ids
+    }
+
+    /// 索引只反映已提交的数据：去掉事务中改动过的节点，再补上事务中满足条件的节点
+    fn patch_index_hits(&self, mut ids: Vec<NodeId>, label: &str, key: &str, expected: &Value) -> Vec<NodeId> {
+        if self.0.is_empty() {
+            return ids;
+        }
+        ids.retain(|id| !self.0.contains_key(id));
+        ids.extend(self.pending_matching(|n| n.has_label(label) && n.get(key) == Some(expected)));
+        ids
+    }
+}
+
+/// 以关系为中心的查询 API，与 [`Query`] 对应：
+/// - from_rel_type：按关系类型选出关系
+/// - where_prop_eq / where_prop_int_eq / where_prop_int_gt：按关系属性过滤
+/// - skip / limit / order_by：对关系分页、排序
+/// - start_nodes / end_nodes：投影出关系的端点
+///
+/// 无向关系只出现一次，`start`/`end` 保持创建时的端点顺序。
+pub struct RelQuery<'a, E: StorageEngine> {
+    db: &'a GraphDatabase<E>,
+    current: Vec<Relationship>,
+}
+
+impl<'a, E: StorageEngine> RelQuery<'a, E> {
+    pub fn new(db: &'a GraphDatabase<E>) -> Self {
+        Self {
+            db,
+            current: Vec::new(),
+        }
+    }
+
+    /// 选出指定类型的所有关系（按关系 ID 升序）
+    pub fn from_rel_type(mut self, rel_type: &str) -> Self {
+        let mut rels = Vec::new();
+        for stored in self.db.all_stored_nodes() {
+            for rel in self.db.neighbors_out(stored.id) {
+                // 无向关系在两个端点都会出现，只在编号较小的端点收集
+                if rel.typ == rel_type && (rel.directed || rel.start <= rel.end) {
+                    rels.push(rel);
+                }
+            }
+        }
+        rels.sort_by_key(|r| r.id);
+        rels.dedup_by_key(|r| r.id);
+        // 无向关系恢复创建时的端点顺序
+        self.current = rels
+            .into_iter()
+            .map(|r| if r.directed { r } else { self.db.get_rel(r.id).unwrap_or(r) })
+            .collect();
+        self
+    }
+
+    /// 按关系的文本属性等于过滤
+    pub fn where_prop_eq(mut self, key: &str, expected: &str) -> Self {
+        self.current
+            .retain(|r| matches!(r.props.get(key), Some(Value::Text(v)) if v == expected));
+        self
+    }
+
+    /// 按关系的整型属性等于过滤
+    pub fn where_prop_int_eq(mut self, key: &str, expected: i64) -> Self {
+        self.current
+            .retain(|r| matches!(r.props.get(key), Some(Value::Int(v)) if *v == expected));
+        self
+    }
+
+    /// 按关系的整型属性 > 某个值过滤
+    pub fn where_prop_int_gt(mut self, key: &str, min: i64) -> Self {
+        self.current
+            .retain(|r| matches!(r.props.get(key), Some(Value::Int(v)) if *v > min));
+        self
+    }
+
+    /// 跳过前 N 条关系
+    pub fn skip(mut self, n: usize) -> Self {
+        if n < self.current.len() {
+            self.current = self.current.split_off(n);
+        } else {
+            self.current.clear();
+        }
+        self
+    }
+
+    /// 限制返回前 N 条关系
+    pub fn limit(mut self, n: usize) -> Self {
+        self.current.truncate(n);
+        self
+    }
+
+    /// 按关系属性排序（支持整型、文本和日期时间），缺少该属性的关系排在最后
+    pub fn order_by(mut self, key: &str, ascending: bool) -> Self {
+        self.current.sort_by(|a, b| {
+            match (a.props.get(key), b.props.get(key)) {
+                (Some(Value::Int(x)), Some(Value::Int(y))) => {
+                    if ascending { x.cmp(y) } else { y.cmp(x) }
+                }
+                (Some(Value::Text(x)), Some(Value::Text(y))) => {
+                    if ascending { x.cmp(y) } else { y.cmp(x) }
+                }
+                (Some(Value::DateTime(x)), Some(Value::DateTime(y))) => {
+                    if ascending { x.cmp(y) } else { y.cmp(x) }
+                }
+                (None, Some(_)) => std::cmp::Ordering::Greater,
+                (Some(_), None) => std::cmp::Ordering::Less,
+                _ => std::cmp::Ordering::Equal,
+            }
+        });
+        self
+    }
+
+    /// 收集当前关系
+    pub fn collect_rels(self) -> Vec<Relationship> {
+        self.current
+    }
+
+    /// 聚合：计数
+    pub fn count(self) -> usize {
+        self.current.len()
+    }
+
+    /// 投影关系的起点（按首次出现顺序去重）
+    pub fn start_nodes(self) -> Vec<Node> {
+        let ids: Vec<NodeId> = self.current.iter().map(|r| r.start).collect();
+        self.project(ids)
+    }
+
+    /// 投影关系的终点（按首次出现顺序去重）
+    pub fn end_nodes(self) -> Vec<Node> {
+        let ids: Vec<NodeId> = self.current.iter().map(|r| r.end).collect();
+        self.project(ids)
+    }
+
+    fn project(&self, ids: Vec<NodeId>) -> Vec<Node> {
+        let mut seen = std::collections::HashSet::new();
+        ids.into_iter()
+            .filter(|id| seen.insert(*id))
+            .filter_map(|id| self.db.get_node(id))
+            .collect()
+    }
+}
@@ -11,6 +11,7 @@ use crate::graph::db::GraphDatabase;
 use crate::graph::model::{Node, Relationship};
 use crate::storage::{NodeId, StorageEngine};
 use crate::values::Value;
+use serde::Serialize;
 use std::collections::HashMap;
 
 /// 查询结果类型
@@ -38,6 +39,52 @@ pub enum QueryValue {
     Null,
 }
 
+/// 结果列的类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ColumnType {
+    Node,
+    Relationship,
+    Path,
+    Int,
+    Float,
+    Text,
+    Bool,
+    List,
+    #[serde(rename = "datetime")]
+    DateTime,
+    Bytes,
+    Null,
+    /// 无法确定（例如所有行都缺少该列）
+    Unknown,
+}
+
+impl ColumnType {
+    /// 属性值对应的列类型
+    pub fn of_value(value: &Value) -> Self {
+        match value {
+            Value::Int(_) => ColumnType::Int,
+            Value::Float(_) => ColumnType::Float,
+            Value::Text(_) => ColumnType::Text,
+            Value::Bool(_) => ColumnType::Bool,
+            Value::List(_) => ColumnType::List,
+            Value::DateTime(_) => ColumnType::DateTime,
+            Value::Bytes(_) => ColumnType::Bytes,
+            Value::Null => ColumnType::Null,
+        }
+    }
+
+    /// 查询值对应的列类型
+    pub fn of_query_value(value: &QueryValue) -> Self {
+        match value {
+            QueryValue::Node(_) => ColumnType::Node,
+            QueryValue::Relationship(_) => ColumnType::Relationship,
+            QueryValue::Value(v) => ColumnType::of_value(v),
+            QueryValue::Null => ColumnType::Null,
+        }
+    }
+}
+
 /// 路径查询结果
 #[derive(Debug, Clone, PartialEq)]
 pub struct QueryPath {
@@ -64,6 +111,8 @@ impl QueryPath {
 pub struct QueryRows {
     pub rows: Vec<QueryRow>,
     pub columns: Vec<String>,
+    /// 与 `columns` 一一对应的列类型；为空表示未知
+    pub column_types: Vec<ColumnType>,
 }
 
 impl QueryRows {
@@ -71,9 +120,24 @@ impl QueryRows {
         Self {
             rows: Vec::new(),
             columns,
+            column_types: Vec::new(),
         }
     }
 
+    /// 根据已有行推断列类型：取每列第一个非 Null 值的类型，全部为 Null 时为 `Null`
+    pub fn infer_column_types(&mut self) {
+        self.column_types = (0..self.columns.len())
+            .map(|i| {
+                let mut values = self.rows.iter().filter_map(|row| row.values.get(i));
+                match values.find(|v| !matches!(v, QueryValue::Null | QueryValue::Value(Value::Null))) {
+                    Some(v) => ColumnType::of_query_value(v),
+                    None if self.rows.is_empty() => ColumnType::Unknown,
+                    None => ColumnType::Null,
+                }
+            })
+            .collect();
+    }
+
     pub fn add_row(&mut self, values: Vec<QueryValue>) {
         self.rows.push(QueryRow { values });
     }
@@ -301,6 +365,7 @@ impl<'a, E: StorageEngine> MultiVarQueryExecutor<'a, E> {
             }
         }
 
+        rows.infer_column_types();
         Ok(rows)
     }
 
@@ -629,6 +694,9 @@ mod tests {
 
         assert_eq!(rows.len(), 1);
         assert!(!rows.is_empty());
+
+        rows.infer_column_types();
+        assert_eq!(rows.column_types, vec![ColumnType::Null, ColumnType::Int]);
     }
 
     #[test]
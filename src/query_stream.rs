@@ -7,6 +7,7 @@
 // - 批量处理
 // - 进度跟踪
 
+use crate::graph::async_db::AsyncGraphDB;
 use crate::graph::model::{Node, Relationship};
 use crate::storage::{NodeId, RelId, StorageEngine};
 use crate::values::Value;
@@ -345,6 +346,7 @@ impl Stream for QueryStream {
 }
 
 /// 流式查询构建器
+#[derive(Clone)]
 pub struct StreamQueryBuilder {
     config: BackpressureConfig,
     filter_label: Option<String>,
@@ -402,27 +404,7 @@ impl StreamQueryBuilder {
         // 应用过滤器
         let filtered_nodes: Vec<Node> = nodes
             .into_iter()
-            .filter(|node| {
-                // 标签过滤
-                if let Some(ref label) = self.filter_label {
-                    if !node.labels.contains(label) {
-                        return false;
-                    }
-                }
-
-                // 属性过滤
-                if let Some((ref prop, ref value)) = self.filter_property {
-                    if let Some(node_value) = node.props.get(prop) {
-                        if node_value != value {
-                            return false;
-                        }
-                    } else {
-                        return false;
-                    }
-                }
-
-                true
-            })
+            .filter(|node| self.accepts(node))
             .collect();
 
         let batch_size = self.config.batch_size;
@@ -454,6 +436,72 @@ impl StreamQueryBuilder {
         QueryStream::new(rx, total_count)
     }
 
+    /// 节点是否满足标签和属性过滤条件
+    fn accepts(&self, node: &Node) -> bool {
+        // 标签过滤
+        if let Some(ref label) = self.filter_label {
+            if !node.labels.contains(label) {
+                return false;
+            }
+        }
+
+        // 属性过滤
+        if let Some((ref prop, ref value)) = self.filter_property {
+            if node.props.get(prop) != Some(value) {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    /// 在异步数据库上构建节点流，不阻塞运行时
+    ///
+    /// 先在存储的后台任务中按标签过滤条件取出候选节点 ID（没有标签过滤时为全部节点），
+    /// 再由生产者任务每次读取 `batch_size` 个节点、应用属性过滤后逐个发送。通道容量为
+    /// `channel_buffer`，消费者跟不上时生产者在发送处等待，不会继续读取下一批，
+    /// 因此内存中最多只有一批节点加上通道中的缓冲。统计中的总数为候选节点数。
+    pub async fn build_async_node_stream<E: StorageEngine + Send + 'static>(
+        &self,
+        db: &AsyncGraphDB<E>,
+    ) -> QueryStream {
+        let (tx, rx) = mpsc::channel(self.config.channel_buffer.max(1));
+        let storage = db.clone_storage();
+        let ids = storage
+            .scan_node_ids(self.filter_label.clone())
+            .await
+            .unwrap_or_default();
+        let total = ids.len() as u64;
+        let batch_size = self.config.batch_size.max(1);
+        let builder = self.clone();
+
+        tokio::spawn(async move {
+            let mut sent = 0usize;
+            for (batch_index, chunk) in ids.chunks(batch_size).enumerate() {
+                let Some(batch) = storage.get_nodes(chunk.to_vec()).await else {
+                    return; // 存储后台任务已退出
+                };
+                for stored in batch {
+                    let node = Node { id: stored.id, labels: stored.labels, props: stored.props };
+                    if !builder.accepts(&node) {
+                        continue;
+                    }
+                    if tx.send(StreamItem::node(node)).await.is_err() {
+                        return; // 接收端已关闭
+                    }
+                }
+
+                sent += chunk.len();
+                let progress = (sent as f64 / total as f64).min(1.0);
+                if tx.send(StreamItem::batch_end(batch_index, progress)).await.is_err() {
+                    return;
+                }
+            }
+        });
+
+        QueryStream::new(rx, total)
+    }
+
     /// 构建关系流
     pub fn build_rel_stream<E: StorageEngine + Send + 'static>(
         &self,
@@ -673,6 +721,7 @@ mod tests {
             end: 1,
             typ: "KNOWS".to_string(),
             props: Properties::new(),
+            directed: true,
         };
 
         let item = StreamItem::rel(rel.clone());
@@ -1,20 +1,28 @@
 use axum::{
-    extract::{Path, State},
-    http::StatusCode,
+    extract::{Path, Query as QueryParams, State},
+    http::{header, StatusCode},
     response::Html,
-    routing::{delete, get, post, put},
+    routing::{delete, get, post},
     Json, Router,
 };
 use tower_http::services::ServeDir;
 use serde::{Deserialize, Serialize};
-use std::sync::Arc;
-use std::time::{SystemTime, UNIX_EPOCH};
-
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::algorithms;
+use crate::algorithms::deadline::Deadline;
+use crate::cancellation::CancellationToken;
+use crate::graph::db::{DeleteMode, GraphError};
+use crate::graph::schema::{infer_schema, GraphSchema};
 use crate::query::Query;
 use crate::storage::mem_store::MemStore;
+use crate::storage::{MemoryReport, NodeId, PropPredicate, RelId};
 use crate::values::{Properties, Value};
+use crate::visualization::{GraphFormat, PropertyFilter};
 
-use crate::service::GraphService;
+use crate::service::{GraphService, ServiceError};
 
 #[cfg(feature = "caching")]
 use crate::cache::stats::OverallCacheReport;
@@ -23,6 +31,14 @@ use crate::cache::stats::OverallCacheReport;
 pub struct AppState {
     pub service: Arc<GraphService<MemStore>>,
     pub start_time: u64,
+    /// `/central` 的短期结果缓存，按指标名存放
+    centrality_cache: Arc<Mutex<HashMap<CentralityMetric, CachedCentrality>>>,
+    /// 按标签注册的节点属性 JSON Schema（`POST /schemas/:label`）
+    node_schemas: Arc<Mutex<HashMap<String, Arc<jsonschema::Validator>>>>,
+    /// `/schema` 的推断结果缓存，按采样数存放
+    schema_cache: Arc<Mutex<HashMap<Option<usize>, CachedSchema>>>,
+    /// 按名字注册的参数化 Cypher 查询（`POST /stored-queries/:name`），仅保存在内存中
+    stored_queries: Arc<Mutex<HashMap<String, String>>>,
 }
 
 impl AppState {
@@ -33,10 +49,39 @@ impl AppState {
                 .duration_since(UNIX_EPOCH)
                 .unwrap()
                 .as_secs(),
+            centrality_cache: Arc::new(Mutex::new(HashMap::new())),
+            node_schemas: Arc::new(Mutex::new(HashMap::new())),
+            schema_cache: Arc::new(Mutex::new(HashMap::new())),
+            stored_queries: Arc::new(Mutex::new(HashMap::new())),
         }
     }
 }
 
+/// 中心性结果的缓存时长
+const CENTRALITY_CACHE_TTL: Duration = Duration::from_secs(5);
+
+/// `/central` 单次计算的默认时间上限
+const CENTRALITY_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// 已排序的中心性结果；图的节点数或关系数变化后视为失效
+struct CachedCentrality {
+    computed_at: Instant,
+    node_count: usize,
+    rel_count: usize,
+    ranking: Vec<(NodeId, f64)>,
+}
+
+/// `/schema` 的缓存时长；只改属性不改数量的写操作最多在这段时间内不可见
+const SCHEMA_CACHE_TTL: Duration = Duration::from_secs(30);
+
+/// 已推断的图模式；图的节点数或关系数变化后视为失效
+struct CachedSchema {
+    computed_at: Instant,
+    node_count: usize,
+    rel_count: usize,
+    schema: GraphSchema,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CreateNodeRequest {
     pub labels: Vec<String>,
@@ -45,20 +90,20 @@ pub struct CreateNodeRequest {
 
 #[derive(Debug, Serialize)]
 pub struct CreateNodeResponse {
-    pub id: u64,
+    pub id: NodeId,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CreateRelRequest {
-    pub start: u64,
-    pub end: u64,
+    pub start: NodeId,
+    pub end: NodeId,
     pub rel_type: String,
     pub properties: serde_json::Map<String, serde_json::Value>,
 }
 
 #[derive(Debug, Serialize)]
 pub struct CreateRelResponse {
-    pub id: u64,
+    pub id: RelId,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -68,11 +113,30 @@ pub struct QueryRequest {
     pub value: Option<String>,
     pub out_rel: Option<String>,
     pub in_rel: Option<String>,
+    /// 分页：跳过的结果数
+    #[serde(default)]
+    pub skip: Option<usize>,
+    /// 分页：每页最大结果数
+    #[serde(default)]
+    pub limit: Option<usize>,
+    /// 排序属性
+    #[serde(default)]
+    pub order_by: Option<String>,
+    /// 排序方向："asc"（默认）或 "desc"
+    #[serde(default)]
+    pub order_dir: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct QueryResponse {
+    /// 分页前的结果总数
+    pub total: usize,
+    pub nodes: Vec<NodeResponse>,
 }
 
 #[derive(Debug, Serialize)]
 pub struct NodeResponse {
-    pub id: u64,
+    pub id: NodeId,
     pub labels: Vec<String>,
     pub properties: serde_json::Map<String, serde_json::Value>,
 }
@@ -84,20 +148,33 @@ pub fn create_router(state: AppState) -> Router {
         .route("/", get(root))
         .route("/ui", get(ui_handler))
         .route("/nodes", post(create_node).get(get_all_nodes))
+        .route("/nodes/batch-get", post(batch_get_nodes))
+        .route("/nodes/bulk-update", post(bulk_update_nodes))
         .route("/nodes/:id", get(get_node).put(update_node).delete(delete_node))
         .route("/nodes/:id/neighbors", get(get_node_neighbors))
+        .route("/nodes/:id/recommendations", get(get_node_recommendations))
+        .route("/central", get(get_central_nodes))
+        .route("/schemas/:label", post(register_node_schema))
         .route("/rels", post(create_rel).get(get_all_rels))
         .route("/rels/:id", get(get_rel).put(update_rel).delete(delete_rel))
         .route("/query", post(query))
         .route("/cypher", post(execute_cypher))
+        .route("/cypher/batch", post(execute_cypher_batch))
+        .route("/stored-queries/:name", post(register_stored_query))
+        .route("/stored-queries/:name/run", post(run_stored_query))
+        .route("/export/cypher", post(export_cypher))
         .route("/stats", get(get_stats))
         .route("/labels", get(get_all_labels))
+        .route("/schema", get(get_schema))
         .route("/rel-types", get(get_all_rel_types))
         .route("/batch/nodes", post(batch_create_nodes))
         .route("/batch/rels", post(batch_create_rels))
         .route("/search", post(search_nodes))
         .route("/sysinfo", get(get_sysinfo))
         .route("/queries", get(get_running_queries))
+        .route("/tx/stats", get(get_tx_stats))
+        .route("/indexes", get(get_indexes))
+        .route("/indexes/:id", delete(drop_index))
         .route("/dbs", get(get_databases))
         .nest_service("/assets", ServeDir::new("static/assets"))
         .fallback_service(ServeDir::new("static"));
@@ -127,10 +204,50 @@ async fn ui_handler() -> Html<&'static str> {
     Html(include_str!("../static/index.html"))
 }
 
+/// 创建节点
+///
+/// 主标签（第一个标签）注册了 JSON Schema 时先校验属性，不符合时返回 422 及错误列表
 async fn create_node(
     State(state): State<AppState>,
     Json(payload): Json<CreateNodeRequest>,
-) -> Result<Json<CreateNodeResponse>, StatusCode> {
+) -> Result<Json<CreateNodeResponse>, (StatusCode, Json<serde_json::Value>)> {
+    if let Some(label) = payload.labels.first() {
+        let validator = state
+            .node_schemas
+            .lock()
+            .map_err(|_| {
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(serde_json::json!({ "status": "error", "message": "Schema lock poisoned" })),
+                )
+            })?
+            .get(label)
+            .cloned();
+
+        if let Some(validator) = validator {
+            let instance = serde_json::Value::Object(payload.properties.clone());
+            let errors: Vec<serde_json::Value> = validator
+                .iter_errors(&instance)
+                .map(|e| {
+                    serde_json::json!({
+                        "path": e.instance_path.to_string(),
+                        "message": e.to_string(),
+                    })
+                })
+                .collect();
+            if !errors.is_empty() {
+                return Err((
+                    StatusCode::UNPROCESSABLE_ENTITY,
+                    Json(serde_json::json!({
+                        "status": "error",
+                        "message": format!("Properties do not match schema for label {}", label),
+                        "errors": errors
+                    })),
+                ));
+            }
+        }
+    }
+
     let props = convert_json_map_to_properties(&payload.properties);
     let labels: Vec<&str> = payload.labels.iter().map(|s| s.as_str()).collect();
 
@@ -138,14 +255,55 @@ async fn create_node(
         .service
         .create_node(labels, props)
         .await
-        .map_err(|e| {
-            let (code, _msg): (StatusCode, String) = e.into();
-            code
-        })?;
+        .map_err(service_error)?;
 
     Ok(Json(CreateNodeResponse { id }))
 }
 
+/// 服务层错误转换为响应：状态码按错误类别，响应体附带 `kind`
+fn service_error(err: ServiceError) -> (StatusCode, Json<serde_json::Value>) {
+    (
+        err.status_code(),
+        Json(serde_json::json!({
+            "status": "error",
+            "kind": err.kind(),
+            "message": err.to_string(),
+        })),
+    )
+}
+
+/// 为标签注册节点属性的 JSON Schema（覆盖已有的注册）
+///
+/// 请求体即 Schema 本身；Schema 无法编译时返回 400
+async fn register_node_schema(
+    State(state): State<AppState>,
+    Path(label): Path<String>,
+    Json(schema): Json<serde_json::Value>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    let validator = jsonschema::validator_for(&schema).map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({
+                "status": "error",
+                "message": format!("Invalid JSON Schema: {}", e)
+            })),
+        )
+    })?;
+
+    state
+        .node_schemas
+        .lock()
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({ "status": "error", "message": "Schema lock poisoned" })),
+            )
+        })?
+        .insert(label.clone(), Arc::new(validator));
+
+    Ok(Json(serde_json::json!({ "status": "success", "label": label })))
+}
+
 async fn create_rel(
     State(state): State<AppState>,
     Json(payload): Json<CreateRelRequest>,
@@ -156,10 +314,7 @@ async fn create_rel(
         .service
         .create_rel(payload.start, payload.end, &payload.rel_type, props)
         .await
-        .map_err(|e| {
-            let (code, _msg): (StatusCode, String) = e.into();
-            code
-        })?;
+        .map_err(|e| e.status_code())?;
 
     Ok(Json(CreateRelResponse { id }))
 }
@@ -167,7 +322,14 @@ async fn create_rel(
 async fn query(
     State(state): State<AppState>,
     Json(payload): Json<QueryRequest>,
-) -> Result<Json<Vec<NodeResponse>>, StatusCode> {
+) -> Result<Json<QueryResponse>, StatusCode> {
+    let ascending = match payload.order_dir.as_deref() {
+        None => true,
+        Some(dir) if dir.eq_ignore_ascii_case("asc") => true,
+        Some(dir) if dir.eq_ignore_ascii_case("desc") => false,
+        Some(_) => return Err(StatusCode::BAD_REQUEST),
+    };
+
     let db_arc = state.service.db().clone();
     let db = db_arc
         .lock()
@@ -190,9 +352,17 @@ async fn query(
         q = q.in_(rel);
     }
 
-    let nodes = q.collect_nodes();
+    // 排序和分页（total 为分页前的数量）
+    if let Some(key) = &payload.order_by {
+        q = q.order_by(key, ascending);
+    }
+    let total = q.current.len();
+    if payload.skip.is_some() || payload.limit.is_some() {
+        q = q.paginate(payload.skip.unwrap_or(0), payload.limit.unwrap_or(total));
+    }
 
-    let result: Vec<NodeResponse> = nodes
+    let nodes = q
+        .collect_nodes()
         .into_iter()
         .map(|n| NodeResponse {
             id: n.id,
@@ -201,7 +371,7 @@ async fn query(
         })
         .collect();
 
-    Ok(Json(result))
+    Ok(Json(QueryResponse { total, nodes }))
 }
 
 fn convert_json_map_to_properties(map: &serde_json::Map<String, serde_json::Value>) -> Properties {
@@ -214,9 +384,17 @@ fn convert_json_map_to_properties(map: &serde_json::Map<String, serde_json::Valu
     props
 }
 
+/// JSON 值转换为属性值；不支持的类型（null、数组、对象）返回 None
+///
+/// 数字按字面形式区分类型：不带小数点和指数的整数字面量（`3`、`-7`）存为 `Int`，
+/// 带小数点或指数的字面量（`3.0`、`1e3`）即使数值是整数也存为 `Float`。
+/// 超出 i64 范围的整数字面量存为 `Float`（可能损失精度）。
 fn json_value_to_value(v: &serde_json::Value) -> Option<Value> {
     match v {
-        serde_json::Value::Number(n) => n.as_i64().map(Value::Int),
+        serde_json::Value::Number(n) => match n.as_i64() {
+            Some(i) => Some(Value::Int(i)),
+            None => n.as_f64().map(Value::Float),
+        },
         serde_json::Value::Bool(b) => Some(Value::Bool(*b)),
         serde_json::Value::String(s) => Some(Value::Text(s.clone())),
         _ => None,
@@ -247,6 +425,10 @@ fn value_to_json_value(v: &Value) -> Option<serde_json::Value> {
                 .collect();
             Some(serde_json::Value::Array(arr))
         }
+        Value::DateTime(ms) => Some(serde_json::Value::String(crate::values::format_datetime(*ms))),
+        Value::Bytes(bytes) => Some(serde_json::Value::Array(
+            bytes.iter().map(|b| serde_json::Value::Number((*b).into())).collect(),
+        )),
     }
 }
 
@@ -330,17 +512,32 @@ pub struct BatchCreateNodesRequest {
 
 #[derive(Debug, Serialize)]
 pub struct BatchCreateNodesResponse {
-    pub ids: Vec<u64>,
+    pub ids: Vec<NodeId>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct BatchCreateRelsRequest {
-    pub rels: Vec<(u64, u64, String, serde_json::Map<String, serde_json::Value>)>,
+    pub rels: Vec<(NodeId, NodeId, String, serde_json::Map<String, serde_json::Value>)>,
 }
 
 #[derive(Debug, Serialize)]
 pub struct BatchCreateRelsResponse {
-    pub ids: Vec<u64>,
+    pub ids: Vec<RelId>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BatchGetNodesRequest {
+    pub ids: Vec<NodeId>,
+    /// 为 true 时结果与 ids 一一对应，不存在的节点为 null；默认省略不存在的节点
+    #[serde(default)]
+    pub include_missing: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BatchGetNodesResponse {
+    pub nodes: Vec<Option<NodeResponse>>,
+    /// 不存在的节点ID
+    pub missing: Vec<NodeId>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -377,10 +574,117 @@ async fn get_all_nodes(
     Ok(Json(nodes))
 }
 
+/// 按ID批量获取节点
+async fn batch_get_nodes(
+    State(state): State<AppState>,
+    Json(req): Json<BatchGetNodesRequest>,
+) -> Result<Json<BatchGetNodesResponse>, StatusCode> {
+    let db_arc = state.service.db().clone();
+    let db = db_arc
+        .lock()
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let mut nodes = Vec::with_capacity(req.ids.len());
+    let mut missing = Vec::new();
+    for (id, node) in req.ids.iter().zip(db.get_nodes(&req.ids)) {
+        match node {
+            Some(node) => nodes.push(Some(NodeResponse {
+                id: node.id,
+                labels: node.labels,
+                properties: convert_properties_to_json_map(&node.props),
+            })),
+            None => {
+                missing.push(*id);
+                if req.include_missing {
+                    nodes.push(None);
+                }
+            }
+        }
+    }
+
+    Ok(Json(BatchGetNodesResponse { nodes, missing }))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BulkUpdateCondition {
+    pub key: String,
+    /// eq、ne、gt、gte、lt、lte 或 exists
+    pub op: String,
+    /// 比较值（exists 不需要）
+    #[serde(default)]
+    pub value: Option<Value>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BulkUpdateRequest {
+    pub label: String,
+    /// 过滤条件（AND 关系），为空时更新该标签的所有节点
+    #[serde(default)]
+    pub conditions: Vec<BulkUpdateCondition>,
+    /// 要合并到匹配节点上的属性
+    pub properties: serde_json::Map<String, serde_json::Value>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BulkUpdateResponse {
+    pub updated: usize,
+}
+
+fn bulk_condition_predicate(cond: &BulkUpdateCondition) -> Option<PropPredicate> {
+    if cond.op == "exists" {
+        return Some(PropPredicate::Exists);
+    }
+    let value = cond.value.clone()?;
+    match cond.op.as_str() {
+        "eq" => Some(PropPredicate::Eq(value)),
+        "ne" => Some(PropPredicate::Ne(value)),
+        "gt" => Some(PropPredicate::Gt(value)),
+        "gte" => Some(PropPredicate::Gte(value)),
+        "lt" => Some(PropPredicate::Lt(value)),
+        "lte" => Some(PropPredicate::Lte(value)),
+        _ => None,
+    }
+}
+
+/// 按条件批量更新节点属性
+///
+/// 未知的操作符或缺少比较值时返回 400
+async fn bulk_update_nodes(
+    State(state): State<AppState>,
+    Json(req): Json<BulkUpdateRequest>,
+) -> Result<Json<BulkUpdateResponse>, StatusCode> {
+    let conditions: Vec<(String, PropPredicate)> = req
+        .conditions
+        .iter()
+        .map(|c| bulk_condition_predicate(c).map(|p| (c.key.clone(), p)))
+        .collect::<Option<_>>()
+        .ok_or(StatusCode::BAD_REQUEST)?;
+    let updates = convert_json_map_to_properties(&req.properties);
+
+    let db_arc = state.service.db().clone();
+    let mut db = db_arc
+        .lock()
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let updated = db
+        .try_update_matching(
+            &req.label,
+            |node| {
+                conditions
+                    .iter()
+                    .all(|(key, pred)| pred.matches(node.props.get(key)))
+            },
+            updates,
+        )
+        .map_err(|e| ServiceError::from(e).status_code())?;
+
+    Ok(Json(BulkUpdateResponse { updated }))
+}
+
 /// 获取单个节点
 async fn get_node(
     State(state): State<AppState>,
-    Path(id): Path<u64>,
+    Path(id): Path<NodeId>,
 ) -> Result<Json<NodeResponse>, StatusCode> {
     let db_arc = state.service.db().clone();
     let db = db_arc
@@ -400,7 +704,7 @@ async fn get_node(
 /// 更新节点
 async fn update_node(
     State(state): State<AppState>,
-    Path(id): Path<u64>,
+    Path(id): Path<NodeId>,
     Json(payload): Json<UpdateNodeRequest>,
 ) -> Result<Json<serde_json::Value>, StatusCode> {
     let props = convert_json_map_to_properties(&payload.properties);
@@ -410,48 +714,80 @@ async fn update_node(
         .lock()
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
 
-    // 获取现有节点
-    let existing_node = (*db).get_node(id)
-        .ok_or(StatusCode::NOT_FOUND)?;
+    (*db)
+        .try_update_node_props(id, props)
+        .map_err(|e| ServiceError::from(e).status_code())?;
 
-    // 合并属性
-    let mut updated_props = existing_node.props;
-    for (k, v) in props {
-        updated_props.insert(k, v);
-    }
-
-    // 更新节点（先删除后重新创建，或者直接添加新属性）
-    // 简化实现：我们假设 GraphDatabase 有 update_node 或类似方法
-    // 如果没有，这里返回一个提示
     Ok(Json(serde_json::json!({
         "status": "success",
-        "message": "Node properties would be updated",
         "id": id
     })))
 }
 
+#[derive(Debug, Deserialize)]
+pub struct DeleteNodeParams {
+    /// 删除模式：`cascade`（默认）或 `restrict`
+    pub mode: Option<String>,
+}
+
 /// 删除节点
+///
+/// 支持 `?mode=cascade|restrict`，restrict 模式下节点仍有关联关系时返回 409
 async fn delete_node(
     State(state): State<AppState>,
-    Path(id): Path<u64>,
-) -> Result<Json<serde_json::value::Value>, StatusCode> {
-    let db_arc = state.service.db().clone();
-    let mut db = db_arc
-        .lock()
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    Path(id): Path<NodeId>,
+    QueryParams(params): QueryParams<DeleteNodeParams>,
+) -> Result<Json<serde_json::value::Value>, (StatusCode, Json<serde_json::Value>)> {
+    let mode = match params.mode.as_deref().map(|m| m.to_lowercase()) {
+        None => DeleteMode::Cascade,
+        Some(m) if m == "cascade" => DeleteMode::Cascade,
+        Some(m) if m == "restrict" => DeleteMode::Restrict,
+        Some(m) => {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({
+                    "status": "error",
+                    "message": format!("Unknown delete mode: {}", m)
+                })),
+            ))
+        }
+    };
 
-    let result = (*db).delete_node(id);
+    let db_arc = state.service.db().clone();
+    let mut db = db_arc.lock().map_err(|_| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({ "status": "error", "message": "DB lock poisoned" })),
+        )
+    })?;
 
-    Ok(Json(serde_json::json!({
-        "status": "success",
-        "deleted": result
-    })))
+    match (*db).delete_node_mode(id, mode) {
+        Ok(result) => Ok(Json(serde_json::json!({
+            "status": "success",
+            "deleted": result
+        }))),
+        Err(GraphError::HasRelationships(rels)) => Err((
+            StatusCode::CONFLICT,
+            Json(serde_json::json!({
+                "status": "error",
+                "message": "Node still has relationships",
+                "relationships": rels
+            })),
+        )),
+        Err(e) => Err((
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({
+                "status": "error",
+                "message": format!("{:?}", e)
+            })),
+        )),
+    }
 }
 
 /// 获取节点的邻居
 async fn get_node_neighbors(
     State(state): State<AppState>,
-    Path(id): Path<u64>,
+    Path(id): Path<NodeId>,
 ) -> Result<Json<serde_json::Value>, StatusCode> {
     let db_arc = state.service.db().clone();
     let db = db_arc
@@ -467,46 +803,289 @@ async fn get_node_neighbors(
     })))
 }
 
+#[derive(Debug, Deserialize)]
+pub struct RecommendationParams {
+    /// 沿哪种关系推荐（如 `FRIEND`）
+    pub rel_type: String,
+    /// 最多返回的推荐数，默认 10
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RecommendationResponse {
+    pub node: NodeResponse,
+    /// 与源节点的共同邻居数
+    pub mutual_count: usize,
+}
+
+/// "朋友的朋友"推荐
+///
+/// `GET /nodes/:id/recommendations?rel_type=FRIEND&limit=10`，按共同邻居数降序返回
+async fn get_node_recommendations(
+    State(state): State<AppState>,
+    Path(id): Path<NodeId>,
+    QueryParams(params): QueryParams<RecommendationParams>,
+) -> Result<Json<Vec<RecommendationResponse>>, StatusCode> {
+    let db_arc = state.service.db().clone();
+    let db = db_arc
+        .lock()
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    if db.get_node(id).is_none() {
+        return Err(StatusCode::NOT_FOUND);
+    }
+
+    let result = db
+        .recommend(id, &params.rel_type, params.limit.unwrap_or(10))
+        .into_iter()
+        .filter_map(|(node_id, mutual_count)| {
+            db.get_node(node_id).map(|n| RecommendationResponse {
+                node: NodeResponse {
+                    id: n.id,
+                    labels: n.labels,
+                    properties: convert_properties_to_json_map(&n.props),
+                },
+                mutual_count,
+            })
+        })
+        .collect();
+
+    Ok(Json(result))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CentralParams {
+    /// degree、betweenness、closeness、pagerank 或 eigenvector，默认 degree
+    pub metric: Option<String>,
+    /// 最多返回的节点数，默认 10
+    pub limit: Option<usize>,
+    /// 计算时间上限（毫秒），默认 `CENTRALITY_TIMEOUT`
+    pub timeout_ms: Option<u64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CentralNodeResponse {
+    pub node: NodeResponse,
+    pub score: f64,
+}
+
+/// `/central` 支持的中心性指标
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum CentralityMetric {
+    Degree,
+    Betweenness,
+    Closeness,
+    PageRank,
+    Eigenvector,
+}
+
+impl CentralityMetric {
+    fn parse(name: &str) -> Option<Self> {
+        match name {
+            "degree" => Some(Self::Degree),
+            "betweenness" => Some(Self::Betweenness),
+            "closeness" => Some(Self::Closeness),
+            "pagerank" => Some(Self::PageRank),
+            "eigenvector" => Some(Self::Eigenvector),
+            _ => None,
+        }
+    }
+
+    /// 计算全部节点的得分，按得分降序（相同得分按节点 ID 升序）排列
+    ///
+    /// 超过 `deadline` 或被取消时返回 None（度中心性只需一次扫描，不受限时）
+    fn rank(self, db: &crate::GraphDatabase<MemStore>, deadline: Deadline) -> Option<Vec<(NodeId, f64)>> {
+        use crate::algorithms::{centrality, pagerank};
+
+        let scores = match self {
+            Self::Degree => algorithms::degree_centrality(db),
+            Self::Betweenness => centrality::betweenness_centrality_filtered_until(db, None, deadline).ok()?,
+            Self::Closeness => centrality::closeness_centrality_filtered_until(db, None, deadline).ok()?,
+            Self::PageRank => pagerank::converge(db, 0.85, 1e-6, 100, deadline).ok()?.0,
+            Self::Eigenvector => {
+                centrality::eigenvector_centrality_filtered_until(db, None, 100, 1e-6, deadline).ok()?
+            }
+        };
+
+        let mut ranking: Vec<(NodeId, f64)> = scores.into_iter().collect();
+        ranking.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+        Some(ranking)
+    }
+}
+
+/// 中心性最高的节点
+///
+/// `GET /central?metric=betweenness&limit=10`，结果按需计算并缓存
+/// `CENTRALITY_CACHE_TTL`；未知指标返回 400，超过 `timeout_ms` 返回 503
+async fn get_central_nodes(
+    State(state): State<AppState>,
+    QueryParams(params): QueryParams<CentralParams>,
+) -> Result<Json<Vec<CentralNodeResponse>>, StatusCode> {
+    let metric = CentralityMetric::parse(params.metric.as_deref().unwrap_or("degree"))
+        .ok_or(StatusCode::BAD_REQUEST)?;
+
+    let db_arc = state.service.db().clone();
+    let fresh = {
+        let db = db_arc
+            .lock()
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        let cache = state
+            .centrality_cache
+            .lock()
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        cache.get(&metric).is_some_and(|c| {
+            c.computed_at.elapsed() < CENTRALITY_CACHE_TTL
+                && c.node_count == db.node_count()
+                && c.rel_count == db.rel_count()
+        })
+    };
+    if !fresh {
+        let limit = params
+            .timeout_ms
+            .map(Duration::from_millis)
+            .unwrap_or(CENTRALITY_TIMEOUT);
+        // 在阻塞线程上计算：连接断开时请求 future 被丢弃，守卫随之取消计算
+        let token = CancellationToken::new();
+        let guard = token.cancel_on_drop();
+        let db_arc = db_arc.clone();
+        let computed = tokio::task::spawn_blocking(move || {
+            let db = db_arc.lock().ok()?;
+            let ranking = metric.rank(&db, Deadline::new(Some(limit)).with_cancellation(token))?;
+            Some((ranking, db.node_count(), db.rel_count()))
+        })
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        guard.disarm();
+
+        // 超时的部分结果不进入缓存
+        let (ranking, node_count, rel_count) = computed.ok_or(StatusCode::SERVICE_UNAVAILABLE)?;
+        state
+            .centrality_cache
+            .lock()
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+            .insert(
+                metric,
+                CachedCentrality {
+                    computed_at: Instant::now(),
+                    node_count,
+                    rel_count,
+                    ranking,
+                },
+            );
+    }
+
+    let db = db_arc
+        .lock()
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let cache = state
+        .centrality_cache
+        .lock()
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let result = cache[&metric]
+        .ranking
+        .iter()
+        .take(params.limit.unwrap_or(10))
+        .filter_map(|&(node_id, score)| {
+            db.get_node(node_id).map(|n| CentralNodeResponse {
+                node: NodeResponse {
+                    id: n.id,
+                    labels: n.labels,
+                    properties: convert_properties_to_json_map(&n.props),
+                },
+                score,
+            })
+        })
+        .collect();
+
+    Ok(Json(result))
+}
+
 #[derive(Debug, Serialize)]
 pub struct RelResponse {
-    pub id: u64,
-    pub start: u64,
-    pub end: u64,
+    pub id: RelId,
+    pub start: NodeId,
+    pub end: NodeId,
     pub typ: String,
     pub properties: serde_json::Map<String, serde_json::Value>,
 }
 
-/// 获取所有关系
+/// `GET /rels` 的分页参数
+#[derive(Debug, Deserialize)]
+pub struct RelPageParams {
+    /// 上一页返回的 `next_cursor`；从头开始时省略
+    pub cursor: Option<RelId>,
+    /// 每页条数，默认 100，最大 1000
+    pub limit: Option<usize>,
+}
+
+const DEFAULT_REL_PAGE_SIZE: usize = 100;
+const MAX_REL_PAGE_SIZE: usize = 1000;
+
+/// 按关系 ID 升序的一页关系
+#[derive(Debug, Serialize)]
+pub struct RelPageResponse {
+    pub rels: Vec<RelResponse>,
+    /// 下一页的游标；已到末尾时为 null
+    pub next_cursor: Option<RelId>,
+}
+
+/// 获取关系
+///
+/// 不带参数时返回全部关系（数组）；带 `cursor` 或 `limit` 时按关系 ID 分页，
+/// 返回 `RelPageResponse`
 async fn get_all_rels(
     State(state): State<AppState>,
-) -> Result<Json<Vec<RelResponse>>, StatusCode> {
+    QueryParams(params): QueryParams<RelPageParams>,
+) -> Result<Json<serde_json::Value>, StatusCode> {
     let db_arc = state.service.db().clone();
     let db = db_arc
         .lock()
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
 
-    let mut rels = Vec::new();
-    // 遍历所有节点，获取它们的出边关系
-    for stored_node in (*db).all_stored_nodes() {
-        // neighbors_out 返回 Relationship 迭代器
-        for rel in (*db).neighbors_out(stored_node.id) {
-            rels.push(RelResponse {
-                id: rel.id,
-                start: rel.start,
-                end: rel.end,
-                typ: rel.typ,
-                properties: convert_properties_to_json_map(&rel.props),
-            });
-        }
+    let to_response = |rel: crate::graph::model::Relationship| RelResponse {
+        id: rel.id,
+        start: rel.start,
+        end: rel.end,
+        typ: rel.typ,
+        properties: convert_properties_to_json_map(&rel.props),
+    };
+
+    if params.cursor.is_none() && params.limit.is_none() {
+        let rels: Vec<RelResponse> = db
+            .rels_page(None, usize::MAX)
+            .into_iter()
+            .map(to_response)
+            .collect();
+        return serde_json::to_value(rels)
+            .map(Json)
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR);
     }
 
-    Ok(Json(rels))
+    let limit = params
+        .limit
+        .unwrap_or(DEFAULT_REL_PAGE_SIZE)
+        .clamp(1, MAX_REL_PAGE_SIZE);
+    let rels: Vec<RelResponse> = db
+        .rels_page(params.cursor, limit)
+        .into_iter()
+        .map(to_response)
+        .collect();
+    // 满页时可能还有后续数据，以本页最后一条关系的 ID 作为游标
+    let next_cursor = if rels.len() == limit {
+        rels.last().map(|r| r.id)
+    } else {
+        None
+    };
+
+    serde_json::to_value(RelPageResponse { rels, next_cursor })
+        .map(Json)
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
 }
 
 /// 获取单个关系
 async fn get_rel(
     State(state): State<AppState>,
-    Path(id): Path<u64>,
+    Path(id): Path<RelId>,
 ) -> Result<Json<RelResponse>, StatusCode> {
     let db_arc = state.service.db().clone();
     let db = db_arc
@@ -528,7 +1107,7 @@ async fn get_rel(
 /// 删除关系
 async fn delete_rel(
     State(state): State<AppState>,
-    Path(id): Path<u64>,
+    Path(id): Path<RelId>,
 ) -> Result<Json<serde_json::Value>, StatusCode> {
     let db_arc = state.service.db().clone();
     let mut db = db_arc
@@ -545,7 +1124,7 @@ async fn delete_rel(
 /// 更新关系
 async fn update_rel(
     State(state): State<AppState>,
-    Path(id): Path<u64>,
+    Path(id): Path<RelId>,
     Json(payload): Json<UpdateRelRequest>,
 ) -> Result<Json<serde_json::Value>, StatusCode> {
     let props = convert_json_map_to_properties(&payload.properties);
@@ -555,21 +1134,12 @@ async fn update_rel(
         .lock()
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
 
-    // 获取现有关系
-    let existing_rel = (*db).get_rel(id)
-        .ok_or(StatusCode::NOT_FOUND)?;
-
-    // 合并属性
-    let mut updated_props = existing_rel.props;
-    for (k, v) in props {
-        updated_props.insert(k, v);
-    }
+    (*db)
+        .try_update_rel_props(id, props)
+        .map_err(|e| ServiceError::from(e).status_code())?;
 
-    // 简化实现：返回成功提示
-    // 实际实现需要 StorageEngine 支持 update_rel 方法
     Ok(Json(serde_json::json!({
         "status": "success",
-        "message": "Relationship properties would be updated",
         "id": id
     })))
 }
@@ -593,23 +1163,52 @@ async fn execute_cypher(
     State(state): State<AppState>,
     Json(payload): Json<CypherRequest>,
 ) -> Result<Json<CypherResponse>, StatusCode> {
-    use crate::cypher::{parser, executor};
-
-    let db_arc = state.service.db().clone();
-    let mut db = db_arc
-        .lock()
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    use crate::cypher::{parser, executor, ExecutionError};
 
     // 解析 Cypher 查询
     let stmt = parser::parse_cypher(&payload.query)
         .map_err(|_| StatusCode::BAD_REQUEST)?;
 
-    // 执行语句
-    let result = executor::execute_statement(&mut *db, &stmt)
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    // 在阻塞线程上执行：连接断开时请求 future 被丢弃，守卫随之取消查询
+    let token = CancellationToken::new();
+    let guard = token.cancel_on_drop();
+    let db_arc = state.service.db().clone();
+    let response = tokio::task::spawn_blocking(move || {
+        let mut db = db_arc
+            .lock()
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        let (result, exec_stats) = executor::execute_statement_cancellable(&mut *db, &stmt, &token)
+            .map_err(|e| match e {
+                // 只有客户端断开时才会取消，这个响应不会被读取
+                ExecutionError::Cancelled => StatusCode::REQUEST_TIMEOUT,
+                ExecutionError::Failed(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            })?;
+        Ok(cypher_response(&stmt, result, &exec_stats))
+    })
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    guard.disarm();
+
+    response.map(Json)
+}
+
+/// 把 Cypher 执行结果转换为响应体
+fn cypher_response(
+    stmt: &crate::cypher::CypherStatement,
+    result: crate::cypher::CypherResult,
+    exec_stats: &crate::cypher::ExecutionStats,
+) -> CypherResponse {
+    use crate::cypher::executor;
 
-    match result {
+    let mut response = match result {
         executor::CypherResult::Nodes(nodes) => {
+            // 读查询附带 RETURN 各列的列名和类型，便于客户端生成类型化绑定
+            let columns = match stmt {
+                crate::cypher::ast::CypherStatement::Query(q) => executor::return_columns(q, &nodes),
+                _ => Vec::new(),
+            };
+            let (column_names, column_types): (Vec<String>, Vec<_>) = columns.into_iter().unzip();
+
             let data: Vec<NodeResponse> = nodes
                 .into_iter()
                 .map(|n| NodeResponse {
@@ -619,57 +1218,351 @@ async fn execute_cypher(
                 })
                 .collect();
 
-            Ok(Json(CypherResponse {
+            CypherResponse {
                 result_type: "nodes".to_string(),
-                data: serde_json::json!({ "nodes": data }),
+                data: serde_json::json!({
+                    "nodes": data,
+                    "columns": column_names,
+                    "column_types": column_types,
+                }),
                 stats: Some(serde_json::json!({ "row_count": data.len() })),
-            }))
+            }
         }
         executor::CypherResult::Created { nodes, rels } => {
-            Ok(Json(CypherResponse {
+            CypherResponse {
                 result_type: "created".to_string(),
                 data: serde_json::json!({ "node_ids": nodes, "rel_count": rels }),
                 stats: Some(serde_json::json!({ "nodes_created": nodes.len(), "rels_created": rels })),
-            }))
+            }
         }
         executor::CypherResult::Deleted { nodes, rels } => {
-            Ok(Json(CypherResponse {
+            CypherResponse {
                 result_type: "deleted".to_string(),
                 data: serde_json::json!({}),
                 stats: Some(serde_json::json!({ "nodes_deleted": nodes, "rels_deleted": rels })),
-            }))
+            }
         }
         executor::CypherResult::Updated { nodes } => {
-            Ok(Json(CypherResponse {
+            CypherResponse {
                 result_type: "updated".to_string(),
                 data: serde_json::json!({}),
                 stats: Some(serde_json::json!({ "nodes_updated": nodes })),
-            }))
+            }
         }
         executor::CypherResult::TransactionStarted => {
-            Ok(Json(CypherResponse {
+            CypherResponse {
                 result_type: "transaction_started".to_string(),
                 data: serde_json::json!({}),
                 stats: Some(serde_json::json!({ "message": "Transaction started" })),
-            }))
+            }
         }
         executor::CypherResult::TransactionCommitted => {
-            Ok(Json(CypherResponse {
+            CypherResponse {
                 result_type: "transaction_committed".to_string(),
                 data: serde_json::json!({}),
                 stats: Some(serde_json::json!({ "message": "Transaction committed" })),
-            }))
+            }
         }
         executor::CypherResult::TransactionRolledBack => {
-            Ok(Json(CypherResponse {
+            CypherResponse {
                 result_type: "transaction_rolled_back".to_string(),
                 data: serde_json::json!({}),
                 stats: Some(serde_json::json!({ "message": "Transaction rolled back" })),
-            }))
+            }
         }
+    };
+
+    // 附加执行统计
+    if let Some(serde_json::Value::Object(stats)) = response.stats.as_mut() {
+        stats.insert("execution_time_ms".to_string(), serde_json::json!(exec_stats.execution_time_ms));
+        stats.insert("rows_examined".to_string(), serde_json::json!(exec_stats.rows_examined));
+        stats.insert("index_used".to_string(), serde_json::json!(exec_stats.index_used));
+    }
+
+    response
+}
+
+// ========== 存储查询 ==========
+
+#[derive(Debug, Deserialize)]
+pub struct StoredQueryRequest {
+    pub query: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct RunStoredQueryRequest {
+    /// `$name` 参数的取值，只支持字符串和整数
+    #[serde(default)]
+    pub params: HashMap<String, serde_json::Value>,
+}
+
+/// 把 JSON 参数转换为 Cypher 参数值，只支持字符串和整数
+fn json_params(
+    raw: HashMap<String, serde_json::Value>,
+) -> Result<HashMap<String, crate::cypher::ast::PropertyValue>, String> {
+    use crate::cypher::ast::PropertyValue;
+
+    let mut params = HashMap::new();
+    for (key, value) in raw {
+        let value = match value {
+            serde_json::Value::String(s) => PropertyValue::String(s),
+            serde_json::Value::Number(n) if n.is_i64() => PropertyValue::Int(n.as_i64().unwrap_or_default()),
+            other => return Err(format!("Unsupported value for parameter ${}: {}", key, other)),
+        };
+        params.insert(key, value);
+    }
+    Ok(params)
+}
+
+fn stored_query_error(code: StatusCode, message: impl Into<String>) -> (StatusCode, Json<serde_json::Value>) {
+    (
+        code,
+        Json(serde_json::json!({ "status": "error", "message": message.into() })),
+    )
+}
+
+/// 以名字注册一条参数化 Cypher 查询（同名覆盖），语法错误时返回 400
+async fn register_stored_query(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    Json(payload): Json<StoredQueryRequest>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    crate::cypher::parse_cypher(&payload.query)
+        .map_err(|e| stored_query_error(StatusCode::BAD_REQUEST, format!("Invalid query: {}", e)))?;
+
+    state
+        .stored_queries
+        .lock()
+        .map_err(|_| stored_query_error(StatusCode::INTERNAL_SERVER_ERROR, "Stored query lock poisoned"))?
+        .insert(name.clone(), payload.query);
+
+    Ok(Json(serde_json::json!({ "status": "success", "name": name })))
+}
+
+/// 用给定参数执行已注册的查询；查询不存在时返回 404，参数缺失或类型不支持时返回 400
+async fn run_stored_query(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    payload: Option<Json<RunStoredQueryRequest>>,
+) -> Result<Json<CypherResponse>, (StatusCode, Json<serde_json::Value>)> {
+    use crate::cypher::{bind_parameters, executor, parse_cypher, ExecutionError};
+
+    let query = state
+        .stored_queries
+        .lock()
+        .map_err(|_| stored_query_error(StatusCode::INTERNAL_SERVER_ERROR, "Stored query lock poisoned"))?
+        .get(&name)
+        .cloned()
+        .ok_or_else(|| stored_query_error(StatusCode::NOT_FOUND, format!("Stored query '{}' not found", name)))?;
+
+    let params = json_params(payload.map(|Json(p)| p.params).unwrap_or_default())
+        .map_err(|e| stored_query_error(StatusCode::BAD_REQUEST, e))?;
+
+    let mut stmt = parse_cypher(&query)
+        .map_err(|e| stored_query_error(StatusCode::INTERNAL_SERVER_ERROR, e))?;
+    bind_parameters(&mut stmt, &params).map_err(|e| stored_query_error(StatusCode::BAD_REQUEST, e))?;
+
+    // 与 /cypher 一样在阻塞线程上执行，连接断开时取消查询
+    let token = CancellationToken::new();
+    let guard = token.cancel_on_drop();
+    let db_arc = state.service.db().clone();
+    let response = tokio::task::spawn_blocking(move || {
+        let mut db = db_arc
+            .lock()
+            .map_err(|_| stored_query_error(StatusCode::INTERNAL_SERVER_ERROR, "Database lock poisoned"))?;
+        let (result, exec_stats) = executor::execute_statement_cancellable(&mut *db, &stmt, &token)
+            .map_err(|e| match e {
+                ExecutionError::Cancelled => stored_query_error(StatusCode::REQUEST_TIMEOUT, e.to_string()),
+                ExecutionError::Failed(msg) => stored_query_error(StatusCode::INTERNAL_SERVER_ERROR, msg),
+            })?;
+        Ok(cypher_response(&stmt, result, &exec_stats))
+    })
+    .await
+    .map_err(|_| stored_query_error(StatusCode::INTERNAL_SERVER_ERROR, "Query task failed"))?;
+    guard.disarm();
+
+    response.map(Json)
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CypherBatchRequest {
+    pub statements: Vec<CypherBatchStatement>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CypherBatchStatement {
+    pub query: String,
+    /// `$name` 参数的取值，只支持字符串和整数
+    #[serde(default)]
+    pub params: HashMap<String, serde_json::Value>,
+}
+
+fn batch_error(code: StatusCode, index: usize, message: impl std::fmt::Display) -> (StatusCode, Json<serde_json::Value>) {
+    (
+        code,
+        Json(serde_json::json!({
+            "status": "error",
+            "message": format!("statement {}: {}", index, message),
+            "failed_statement": index,
+        })),
+    )
+}
+
+/// 批量执行失败：出错语句的下标和错误；撤销本身失败时没有下标
+struct BatchFailure {
+    index: Option<usize>,
+    error: crate::cypher::ExecutionError,
+}
+
+impl From<GraphError> for BatchFailure {
+    fn from(err: GraphError) -> Self {
+        BatchFailure { index: None, error: crate::cypher::ExecutionError::Failed(err.to_string()) }
     }
 }
 
+/// 在一个事务中依次执行多条语句，返回每条语句的结果
+///
+/// 全部语句先解析并绑定参数，任何一条有误时返回 400 且不执行；执行中任何一条失败时
+/// 整批回滚并返回 500。`failed_statement` 给出出错语句的下标。批内不允许 BEGIN/COMMIT/ROLLBACK。
+async fn execute_cypher_batch(
+    State(state): State<AppState>,
+    Json(payload): Json<CypherBatchRequest>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    use crate::cypher::ast::CypherStatement;
+    use crate::cypher::{bind_parameters, executor, parse_cypher, ExecutionError};
+
+    let mut stmts = Vec::with_capacity(payload.statements.len());
+    for (i, item) in payload.statements.into_iter().enumerate() {
+        let mut stmt = parse_cypher(&item.query).map_err(|e| batch_error(StatusCode::BAD_REQUEST, i, e))?;
+        if matches!(
+            stmt,
+            CypherStatement::BeginTransaction | CypherStatement::CommitTransaction | CypherStatement::RollbackTransaction
+        ) {
+            return Err(batch_error(
+                StatusCode::BAD_REQUEST,
+                i,
+                "transaction control is not allowed in a batch",
+            ));
+        }
+        let params = json_params(item.params).map_err(|e| batch_error(StatusCode::BAD_REQUEST, i, e))?;
+        bind_parameters(&mut stmt, &params).map_err(|e| batch_error(StatusCode::BAD_REQUEST, i, e))?;
+        stmts.push(stmt);
+    }
+
+    // 在阻塞线程上执行，连接断开时取消；被取消的语句和之前的语句一起回滚
+    let token = CancellationToken::new();
+    let guard = token.cancel_on_drop();
+    let db_arc = state.service.db().clone();
+    let results = tokio::task::spawn_blocking(move || {
+        let mut db = db_arc
+            .lock()
+            .map_err(|_| stored_query_error(StatusCode::INTERNAL_SERVER_ERROR, "Database lock poisoned"))?;
+        db.atomically(|db| {
+            stmts
+                .iter()
+                .enumerate()
+                .map(|(i, stmt)| {
+                    let (result, exec_stats) = executor::execute_statement_cancellable(db, stmt, &token)
+                        .map_err(|error| BatchFailure { index: Some(i), error })?;
+                    Ok(cypher_response(stmt, result, &exec_stats))
+                })
+                .collect::<Result<Vec<_>, BatchFailure>>()
+        })
+        .map_err(|failure| {
+            let code = match failure.error {
+                ExecutionError::Cancelled => StatusCode::REQUEST_TIMEOUT,
+                ExecutionError::Failed(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            };
+            match failure.index {
+                Some(i) => batch_error(code, i, failure.error),
+                None => stored_query_error(code, failure.error.to_string()),
+            }
+        })
+    })
+    .await
+    .map_err(|_| stored_query_error(StatusCode::INTERNAL_SERVER_ERROR, "Batch task failed"))?;
+    guard.disarm();
+    let results = results?;
+
+    Ok(Json(serde_json::json!({ "status": "success", "results": results })))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ExportCypherRequest {
+    pub query: String,
+    /// json（默认）、dot 或 graphml
+    #[serde(default)]
+    pub format: Option<String>,
+    /// 只导出这些属性
+    #[serde(default)]
+    pub include_properties: Option<Vec<String>>,
+    /// 不导出这些属性
+    #[serde(default)]
+    pub exclude_properties: Vec<String>,
+    /// 被过滤的属性改为用该文本脱敏，而不是删除
+    #[serde(default)]
+    pub redact: Option<String>,
+}
+
+impl ExportCypherRequest {
+    fn property_filter(&self) -> PropertyFilter {
+        let mut filter = match &self.include_properties {
+            Some(keys) => PropertyFilter::allow(keys.iter().cloned()),
+            None => PropertyFilter::default(),
+        };
+        filter = filter.denying(self.exclude_properties.iter().cloned());
+        match &self.redact {
+            Some(placeholder) => filter.redact_with(placeholder.clone()),
+            None => filter,
+        }
+    }
+}
+
+/// 执行 MATCH 查询并把匹配到的子图导出为 JSON、DOT 或 GraphML
+async fn export_cypher(
+    State(state): State<AppState>,
+    Json(payload): Json<ExportCypherRequest>,
+) -> Result<([(header::HeaderName, &'static str); 1], String), (StatusCode, Json<serde_json::Value>)> {
+    use crate::cypher::{ast::CypherStatement, executor, parser};
+
+    let bad_request = |message: String| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "status": "error", "message": message })),
+        )
+    };
+
+    let (format, content_type) = match payload.format.as_deref().unwrap_or("json") {
+        "json" => (GraphFormat::Json, "application/json"),
+        "dot" => (GraphFormat::Dot, "text/vnd.graphviz"),
+        "graphml" => (GraphFormat::GraphMl, "application/graphml+xml"),
+        other => return Err(bad_request(format!("Unsupported export format: {}", other))),
+    };
+    let query = match parser::parse_cypher(&payload.query) {
+        Ok(CypherStatement::Query(q)) => q,
+        Ok(_) => return Err(bad_request("Only MATCH queries can be exported".to_string())),
+        Err(e) => return Err(bad_request(format!("Invalid Cypher query: {}", e))),
+    };
+
+    let db_arc = state.service.db().clone();
+    let db = db_arc.lock().map_err(|_| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({ "status": "error", "message": "Database lock poisoned" })),
+        )
+    })?;
+
+    let filter = payload.property_filter();
+    let body = executor::match_subgraph(&db, &query)
+        .and_then(|mut view| {
+            view.filter_properties(&filter);
+            view.export(format)
+        })
+        .map_err(bad_request)?;
+
+    Ok(([(header::CONTENT_TYPE, content_type)], body))
+}
+
 /// 获取数据库统计信息
 async fn get_stats(
     State(state): State<AppState>,
@@ -679,19 +1572,19 @@ async fn get_stats(
         .lock()
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
 
-    let mut node_count = 0;
-    let mut rel_count = 0;
+    // 节点和关系数量由存储引擎计数器提供
+    let node_count = (*db).node_count();
+    let rel_count = (*db).rel_count();
+
     let mut labels_set = std::collections::HashSet::new();
     let mut rel_types_set = std::collections::HashSet::new();
 
     for node in (*db).all_stored_nodes() {
-        node_count += 1;
         for label in &node.labels {
             labels_set.insert(label.clone());
         }
-        // 统计出边数量和类型
+        // 统计关系类型
         for rel in (*db).neighbors_out(node.id) {
-            rel_count += 1;
             rel_types_set.insert(rel.typ);
         }
     }
@@ -731,6 +1624,47 @@ async fn get_all_labels(
     Ok(Json(labels))
 }
 
+#[derive(Debug, Deserialize)]
+pub struct SchemaParams {
+    /// 只采样前 n 个节点（及其出边）；缺省时全量扫描
+    pub sample: Option<usize>,
+}
+
+/// 从实际数据推断的模式：每个标签/关系类型的属性名、值类型和填充率
+async fn get_schema(
+    State(state): State<AppState>,
+    QueryParams(params): QueryParams<SchemaParams>,
+) -> Result<Json<GraphSchema>, StatusCode> {
+    let db_arc = state.service.db().clone();
+    let db = db_arc
+        .lock()
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let mut cache = state
+        .schema_cache
+        .lock()
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let (node_count, rel_count) = (db.node_count(), db.rel_count());
+    let fresh = cache.get(&params.sample).is_some_and(|c| {
+        c.computed_at.elapsed() < SCHEMA_CACHE_TTL
+            && c.node_count == node_count
+            && c.rel_count == rel_count
+    });
+    if !fresh {
+        cache.insert(
+            params.sample,
+            CachedSchema {
+                computed_at: Instant::now(),
+                node_count,
+                rel_count,
+                schema: infer_schema(&db, params.sample),
+            },
+        );
+    }
+
+    Ok(Json(cache[&params.sample].schema.clone()))
+}
+
 /// 获取所有关系类型
 async fn get_all_rel_types(
     State(state): State<AppState>,
@@ -769,11 +1703,11 @@ async fn batch_create_nodes(
         .lock()
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
 
-    let ids = (*db).batch_create_nodes(nodes_data);
+    let ids = (*db)
+        .try_batch_create_nodes(nodes_data)
+        .map_err(|e| ServiceError::from(e).status_code())?;
 
-    Ok(Json(BatchCreateNodesResponse {
-        ids: ids.into_iter().map(|id| id as u64).collect(),
-    }))
+    Ok(Json(BatchCreateNodesResponse { ids }))
 }
 
 /// 批量创建关系
@@ -792,11 +1726,11 @@ async fn batch_create_rels(
         .lock()
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
 
-    let ids = db.batch_create_rels(rels_data);
+    let ids = db
+        .try_batch_create_rels(rels_data)
+        .map_err(|e| ServiceError::from(e).status_code())?;
 
-    Ok(Json(BatchCreateRelsResponse {
-        ids: ids.into_iter().map(|id| id as u64).collect(),
-    }))
+    Ok(Json(BatchCreateRelsResponse { ids }))
 }
 
 /// 搜索节点（按属性值模糊搜索）
@@ -861,6 +1795,8 @@ pub struct SystemInfo {
     pub rel_id_count: u64,
     pub uptime: String,
     pub databases: Vec<DatabaseInfo>,
+    /// 内存存储的近似内存占用
+    pub memory: MemoryReport,
 }
 
 #[derive(Debug, Serialize)]
@@ -898,18 +1834,13 @@ async fn get_sysinfo(
     let uptime = format!("{}h {}m", hours, minutes);
 
     // 统计节点和关系数量
-    let mut node_count = 0usize;
-    let mut rel_count = 0usize;
-    for node in (*db).all_stored_nodes() {
-        node_count += 1;
-        for _target in (*db).neighbors_out(node.id) {
-            rel_count += 1;
-        }
-    }
+    let node_count = (*db).node_count();
+    let rel_count = (*db).rel_count();
+    let memory = (*db).memory_estimate();
 
     Ok(Json(SystemInfo {
         kernel_version: "rs-graphdb 0.1.0".to_string(),
-        store_size: (node_count * 100 + rel_count * 50) as u64, // 估算存储大小
+        store_size: memory.total() as u64,
         node_id_count: node_count as u64,
         rel_id_count: rel_count as u64,
         uptime,
@@ -918,6 +1849,7 @@ async fn get_sysinfo(
             node_count,
             rel_count,
         }],
+        memory,
     }))
 }
 
@@ -929,6 +1861,96 @@ async fn get_running_queries(
     Ok(Json(vec![]))
 }
 
+#[derive(Debug, Deserialize)]
+pub struct TxStatsParams {
+    /// 只返回运行时长不少于该值（毫秒）的事务，用于排查长事务
+    pub min_age_ms: Option<u64>,
+}
+
+/// 事务运行状况
+///
+/// 列出活动事务的隔离级别、时长、操作数和持有的锁数量
+async fn get_tx_stats(
+    State(state): State<AppState>,
+    QueryParams(params): QueryParams<TxStatsParams>,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    let db_arc = state.service.db().clone();
+    let db = db_arc
+        .lock()
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let threshold = std::time::Duration::from_millis(params.min_age_ms.unwrap_or(0));
+    let transactions: Vec<serde_json::Value> = db
+        .transactions
+        .long_running_transactions(threshold)
+        .into_iter()
+        .map(|info| {
+            serde_json::json!({
+                "id": info.id,
+                "isolation_level": format!("{:?}", info.isolation_level),
+                "age_ms": info.age.as_millis() as u64,
+                "op_count": info.op_count,
+                "held_locks": info.held_locks,
+            })
+        })
+        .collect();
+
+    Ok(Json(serde_json::json!({
+        "active_count": db.active_transaction_count(),
+        "completed_count": db.completed_transaction_count(),
+        "transactions": transactions,
+    })))
+}
+
+/// 列出所有索引的定义和统计信息
+///
+/// 选择性 = 不同键数量 / 条目数，越低说明每个键命中的节点越多，索引价值越小
+async fn get_indexes(
+    State(state): State<AppState>,
+) -> Result<Json<Vec<serde_json::Value>>, StatusCode> {
+    let db_arc = state.service.db().clone();
+    let db = db_arc
+        .lock()
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let indexes = db
+        .list_indexes()
+        .into_iter()
+        .map(|index| {
+            serde_json::json!({
+                "id": index.id,
+                "kind": index.kind.as_str(),
+                "label": index.label,
+                "properties": index.properties,
+                "unique_keys": index.stats.unique_keys,
+                "entries": index.stats.total_entries,
+                "selectivity": index.stats.selectivity(),
+                "avg_nodes_per_key": index.stats.avg_nodes_per_key(),
+            })
+        })
+        .collect();
+    Ok(Json(indexes))
+}
+
+/// 按 `GET /indexes` 返回的标识删除索引；索引不存在时返回 404
+async fn drop_index(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    let db_arc = state.service.db().clone();
+    let mut db = db_arc
+        .lock()
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    if !db.drop_index(&id) {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    Ok(Json(serde_json::json!({
+        "status": "success",
+        "deleted": id
+    })))
+}
+
 /// 获取数据库列表
 async fn get_databases(
     State(_state): State<AppState>,
@@ -1,25 +1,82 @@
-use crate::graph::db::GraphDatabase;
+use crate::graph::db::{GraphDatabase, GraphError};
 use crate::graph::model::{Node, Relationship};
 use crate::storage::{NodeId, RelId, StorageEngine};
 use crate::values::Properties;
 use std::sync::{Arc, Mutex};
 
-#[derive(Debug)]
+/// 服务层错误，按类别映射到 HTTP 状态码和 gRPC 状态码
+#[derive(Debug, Clone, PartialEq)]
 pub enum ServiceError {
-    Internal(String),
+    /// 请求的节点或关系不存在
     NotFound,
+    /// 请求数据不合法（例如超过属性限制）
+    Validation(String),
+    /// 写入违反唯一性或存在性约束
+    Constraint(String),
+    /// 与数据库当前状态冲突（例如删除仍有关联关系的节点）
+    Conflict(String),
+    Internal(String),
 }
 
-impl From<ServiceError> for (axum::http::StatusCode, String) {
-    fn from(err: ServiceError) -> Self {
+impl std::fmt::Display for ServiceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ServiceError::NotFound => write!(f, "Not found"),
+            ServiceError::Validation(msg)
+            | ServiceError::Constraint(msg)
+            | ServiceError::Conflict(msg)
+            | ServiceError::Internal(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+impl std::error::Error for ServiceError {}
+
+impl ServiceError {
+    /// 错误类别的机器可读名称，随错误响应一起返回
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ServiceError::NotFound => "not_found",
+            ServiceError::Validation(_) => "validation",
+            ServiceError::Constraint(_) => "constraint",
+            ServiceError::Conflict(_) => "conflict",
+            ServiceError::Internal(_) => "internal",
+        }
+    }
+
+    pub fn status_code(&self) -> axum::http::StatusCode {
         use axum::http::StatusCode;
+        match self {
+            ServiceError::NotFound => StatusCode::NOT_FOUND,
+            ServiceError::Validation(_) => StatusCode::BAD_REQUEST,
+            ServiceError::Constraint(_) | ServiceError::Conflict(_) => StatusCode::CONFLICT,
+            ServiceError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+impl From<GraphError> for ServiceError {
+    fn from(err: GraphError) -> Self {
         match err {
-            ServiceError::Internal(msg) => (StatusCode::INTERNAL_SERVER_ERROR, msg),
-            ServiceError::NotFound => (StatusCode::NOT_FOUND, "Not found".to_string()),
+            GraphError::NotFound => ServiceError::NotFound,
+            GraphError::PropertyTooLarge { .. } | GraphError::TooManyProperties { .. } => {
+                ServiceError::Validation(err.to_string())
+            }
+            GraphError::ConstraintViolation(msg) => ServiceError::Constraint(msg),
+            GraphError::HasRelationships(_) | GraphError::SequenceGap { .. } => {
+                ServiceError::Conflict(err.to_string())
+            }
+            GraphError::Storage(_) => ServiceError::Internal(err.to_string()),
         }
     }
 }
 
+impl From<ServiceError> for (axum::http::StatusCode, String) {
+    fn from(err: ServiceError) -> Self {
+        (err.status_code(), err.to_string())
+    }
+}
+
 pub struct GraphService<E: StorageEngine> {
     db: Arc<Mutex<GraphDatabase<E>>>,
 }
@@ -42,8 +99,7 @@ impl<E: StorageEngine> GraphService<E> {
             .db
             .lock()
             .map_err(|_| ServiceError::Internal("DB lock poisoned".into()))?;
-        let id = guard.create_node(labels, props);
-        Ok(id)
+        guard.try_create_node(labels, props).map_err(ServiceError::from)
     }
 
     pub async fn create_rel(
@@ -57,8 +113,7 @@ impl<E: StorageEngine> GraphService<E> {
             .db
             .lock()
             .map_err(|_| ServiceError::Internal("DB lock poisoned".into()))?;
-        let id = guard.create_rel(start, end, typ, props);
-        Ok(id)
+        guard.try_create_rel(start, end, typ, props).map_err(ServiceError::from)
     }
 
     pub async fn get_node(&self, id: NodeId) -> Result<Node, ServiceError> {
@@ -2,7 +2,7 @@
 //!
 //! 使用 channel 将写入操作发送到后台任务，实现异步写入
 
-use super::{NodeId, RelId, StoredNode, StoredRel, StorageEngine};
+use super::{NodeFilter, NodeId, RelId, StoredNode, StoredRel, StorageEngine};
 use crate::values::Value;
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -40,6 +40,16 @@ enum AsyncCommand {
         id: RelId,
         response: oneshot::Sender<Option<StoredRel>>,
     },
+    /// 匹配标签的全部节点 ID（升序），`label` 为 None 时返回全部节点
+    ScanNodeIds {
+        label: Option<String>,
+        response: oneshot::Sender<Vec<NodeId>>,
+    },
+    /// 批量读取节点，不存在的 ID 被跳过
+    GetNodes {
+        ids: Vec<NodeId>,
+        response: oneshot::Sender<Vec<StoredNode>>,
+    },
     Shutdown {
         response: oneshot::Sender<()>,
     },
@@ -87,6 +97,16 @@ impl<E: StorageEngine + Send + 'static> AsyncStorage<E> {
                         let rel = engine.get_rel(id);
                         let _ = response.send(rel);
                     }
+                    Some(AsyncCommand::ScanNodeIds { label, response }) => {
+                        let filter = NodeFilter { label, ..NodeFilter::default() };
+                        let mut ids: Vec<NodeId> = engine.scan_nodes(filter).map(|n| n.id).collect();
+                        ids.sort_unstable();
+                        let _ = response.send(ids);
+                    }
+                    Some(AsyncCommand::GetNodes { ids, response }) => {
+                        let nodes = ids.into_iter().filter_map(|id| engine.get_node(id)).collect();
+                        let _ = response.send(nodes);
+                    }
                     Some(AsyncCommand::Shutdown { response }) => {
                         let _ = response.send(());
                         break;
@@ -107,6 +127,31 @@ impl<E: StorageEngine + Send + 'static> AsyncStorage<E> {
     }
 }
 
+impl<E: StorageEngine + Send + 'static> AsyncStorage<E> {
+    /// 异步获取带有指定标签的全部节点 ID（升序），`label` 为 None 时返回全部节点
+    ///
+    /// 只在后台任务中做一次扫描，节点数据需要再通过 [`get_nodes`](Self::get_nodes) 分批读取。
+    /// 后台任务已退出时返回 None。
+    pub async fn scan_node_ids(&self, label: Option<String>) -> Option<Vec<NodeId>> {
+        let (tx, rx) = oneshot::channel();
+        self.cmd_tx
+            .send(AsyncCommand::ScanNodeIds { label, response: tx })
+            .await
+            .ok()?;
+        rx.await.ok()
+    }
+
+    /// 异步批量读取节点，不存在的 ID 被跳过；后台任务已退出时返回 None
+    pub async fn get_nodes(&self, ids: Vec<NodeId>) -> Option<Vec<StoredNode>> {
+        let (tx, rx) = oneshot::channel();
+        self.cmd_tx
+            .send(AsyncCommand::GetNodes { ids, response: tx })
+            .await
+            .ok()?;
+        rx.await.ok()
+    }
+}
+
 impl<E: StorageEngine + Send + 'static> Clone for AsyncStorage<E> {
     fn clone(&self) -> Self {
         Self {
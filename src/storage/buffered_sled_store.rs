@@ -2,7 +2,7 @@
 //!
 //! 结合 SledStore 的持久化能力和写缓冲的批量优化，提供更高的写入性能。
 
-use super::{NodeId, RelId, StoredNode, StoredRel, StorageEngine};
+use super::{NodeId, RelId, StoredNode, StoredRel, StorageEngine, StorageError};
 use super::sled_store::SledStore;
 use crate::values::Value;
 use serde::{Deserialize, Serialize};
@@ -319,6 +319,12 @@ impl Drop for BufferedSledStore {
 }
 
 impl StorageEngine for BufferedSledStore {
+    /// 先把写缓冲刷入 sled，再压缩 sled 存储
+    fn compact(&mut self) -> Result<usize, StorageError> {
+        self.flush_to_sled();
+        self.sled_store.compact()
+    }
+
     fn create_node(
         &mut self,
         labels: Vec<String>,
@@ -399,6 +405,7 @@ impl StorageEngine for BufferedSledStore {
                     end: rel.end,
                     typ: rel.typ.clone(),
                     props: rel.props.clone(),
+                    directed: true,
                 });
             }
             if buffer.deleted_rels.contains(&id) {
@@ -2,15 +2,18 @@
 //!
 //! 整合了 LRU 缓存层、写缓冲层和 Sled 持久化层的三层存储架构。
 
-use super::{NodeId, RelId, StoredNode, StoredRel, StorageEngine};
+use super::{NodeFilter, NodeId, RelId, StoredNode, StoredRel, StorageEngine, StorageError};
 use super::sled_store::SledStore;
 use crate::values::Value;
 use serde::{Deserialize, Serialize};
-use std::collections::{HashMap, HashSet, VecDeque};
-use std::path::Path;
-use std::sync::{Arc, Mutex};
-use std::time::{Duration, Instant};
-use std::thread;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs::{File, OpenOptions};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
+use std::thread::{self, JoinHandle};
 use std::sync::atomic::{AtomicU64, Ordering};
 
 // ============================================================================
@@ -71,6 +74,9 @@ pub struct BufferConfig {
 
     /// 刷盘阈值
     pub flush_threshold: usize,
+
+    /// 是否把缓冲的写操作同时追加到 WAL 文件，用于崩溃后恢复
+    pub wal_enabled: bool,
 }
 
 impl Default for BufferConfig {
@@ -79,6 +85,7 @@ impl Default for BufferConfig {
             max_buffer_size: 1000,
             flush_interval_ms: 1000,
             flush_threshold: 500,
+            wal_enabled: true,
         }
     }
 }
@@ -137,6 +144,7 @@ impl HybridConfig {
                 max_buffer_size: 10_000,
                 flush_interval_ms: 5000,
                 flush_threshold: 5000,
+                wal_enabled: true,
             },
             flush_strategy: FlushStrategy::Batch {
                 interval_ms: 5000,
@@ -153,6 +161,7 @@ impl HybridConfig {
                 max_buffer_size: 100,
                 flush_interval_ms: 100,
                 flush_threshold: 50,
+                wal_enabled: true,
             },
             flush_strategy: FlushStrategy::Immediate,
         }
@@ -164,24 +173,30 @@ impl HybridConfig {
 // ============================================================================
 
 /// LRU 缓存条目
-#[derive(Debug, Clone)]
-struct LruEntry<K, V> {
-    key: K,
+#[derive(Debug)]
+struct LruEntry<V> {
     value: V,
-    prev: Option<usize>,
-    next: Option<usize>,
-    access_time: Instant,
+    /// 最近一次访问的逻辑时钟；读路径只做原子写入，无需独占锁
+    last_access: AtomicU64,
+    /// 条目在淘汰队列中登记时的时钟
+    queued_at: u64,
     size_bytes: usize,
 }
 
-/// 简单的 LRU 缓存
+/// 支持并发读的 LRU 缓存
+///
+/// 读操作（`&self`）通过原子逻辑时钟记录访问时间，不移动队列；写操作（`&mut self`）
+/// 淘汰时从队列头取出候选，若候选在登记后被访问过，则按最新访问时间重新登记。
+/// 外层用 `RwLock` 包装即可让多个读者并发命中并更新访问顺序。
 struct LruCache<K, V>
 where
     K: Clone + PartialEq + Eq + std::hash::Hash,
     V: Clone,
 {
-    entries: HashMap<K, LruEntry<K, V>>,
-    access_order: VecDeque<K>,
+    entries: HashMap<K, LruEntry<V>>,
+    /// 淘汰队列：登记时钟 -> 键（时钟值全局唯一）
+    access_order: BTreeMap<u64, K>,
+    clock: AtomicU64,
     max_size: usize,
     max_bytes: usize,
     current_bytes: usize,
@@ -197,7 +212,8 @@ where
     fn new(max_size: usize, max_bytes: usize) -> Self {
         Self {
             entries: HashMap::new(),
-            access_order: VecDeque::new(),
+            access_order: BTreeMap::new(),
+            clock: AtomicU64::new(0),
             max_size,
             max_bytes,
             current_bytes: 0,
@@ -206,24 +222,14 @@ where
         }
     }
 
-    fn get(&mut self, key: &K) -> Option<&V> {
-        if let Some(entry) = self.entries.get_mut(key) {
-            entry.access_time = Instant::now();
-            // 更新访问顺序
-            self.access_order.retain(|k| k != key);
-            self.access_order.push_back(key.clone());
-            self.hits.fetch_add(1, Ordering::Relaxed);
-            Some(&entry.value)
-        } else {
-            self.misses.fetch_add(1, Ordering::Relaxed);
-            None
-        }
+    fn tick(&self) -> u64 {
+        self.clock.fetch_add(1, Ordering::Relaxed) + 1
     }
 
-    fn get_immutable(&self, key: &K) -> Option<V> {
+    /// 读取并刷新访问时间（只需共享引用）
+    fn get(&self, key: &K) -> Option<V> {
         if let Some(entry) = self.entries.get(key) {
-            // 注意：这里不能更新访问顺序，因为这需要 &mut self
-            // 这是一个简化，在生产环境中可能需要使用 RwLock 或其他并发原语
+            entry.last_access.fetch_max(self.tick(), Ordering::Relaxed);
             self.hits.fetch_add(1, Ordering::Relaxed);
             Some(entry.value.clone())
         } else {
@@ -234,41 +240,56 @@ where
 
     fn put(&mut self, key: K, value: V, size_bytes: usize) {
         // 如果已存在，先移除旧的
-        if let Some(old_entry) = self.entries.remove(&key) {
-            self.current_bytes = self.current_bytes.saturating_sub(old_entry.size_bytes);
-            self.access_order.retain(|k| k != &key);
-        }
+        self.remove(&key);
 
         // 检查是否需要淘汰（只在条目数超限时淘汰）
         while self.entries.len() >= self.max_size {
-            if let Some(lru_key) = self.access_order.pop_front() {
-                if let Some(entry) = self.entries.remove(&lru_key) {
-                    self.current_bytes = self.current_bytes.saturating_sub(entry.size_bytes);
-                }
-            } else {
+            if !self.evict_one() {
                 break;
             }
         }
 
         // 插入新条目（即使超过字节数限制也插入）
+        let now = self.tick();
         let entry = LruEntry {
-            key: key.clone(),
             value,
-            prev: None,
-            next: None,
-            access_time: Instant::now(),
+            last_access: AtomicU64::new(now),
+            queued_at: now,
             size_bytes,
         };
 
         self.entries.insert(key.clone(), entry);
-        self.access_order.push_back(key);
+        self.access_order.insert(now, key);
         self.current_bytes += size_bytes;
     }
 
+    /// 淘汰最久未访问的条目
+    fn evict_one(&mut self) -> bool {
+        while let Some((queued_at, key)) = self.access_order.pop_first() {
+            let Some(entry) = self.entries.get_mut(&key) else {
+                continue;
+            };
+
+            // 登记之后被读过：按最新访问时间重新排队
+            let last_access = *entry.last_access.get_mut();
+            if last_access > queued_at {
+                entry.queued_at = last_access;
+                self.access_order.insert(last_access, key);
+                continue;
+            }
+
+            if let Some(entry) = self.entries.remove(&key) {
+                self.current_bytes = self.current_bytes.saturating_sub(entry.size_bytes);
+            }
+            return true;
+        }
+        false
+    }
+
     fn remove(&mut self, key: &K) -> Option<V> {
         if let Some(entry) = self.entries.remove(key) {
-            self.current_bytes -= entry.size_bytes;
-            self.access_order.retain(|k| k != key);
+            self.current_bytes = self.current_bytes.saturating_sub(entry.size_bytes);
+            self.access_order.remove(&entry.queued_at);
             Some(entry.value)
         } else {
             None
@@ -279,6 +300,10 @@ where
         self.remove(key);
     }
 
+    fn contains(&self, key: &K) -> bool {
+        self.entries.contains_key(key)
+    }
+
     fn clear(&mut self) {
         self.entries.clear();
         self.access_order.clear();
@@ -310,18 +335,21 @@ where
 // ============================================================================
 
 /// 缓存层
+///
+/// 每个缓存独立加读写锁：命中路径只取读锁（访问时间由原子时钟更新），
+/// 插入/失效才取写锁，因此多线程读不会相互阻塞。
 struct CacheLayer {
     /// 节点缓存
-    node_cache: LruCache<NodeId, StoredNode>,
+    node_cache: RwLock<LruCache<NodeId, StoredNode>>,
 
     /// 关系缓存
-    rel_cache: LruCache<RelId, StoredRel>,
+    rel_cache: RwLock<LruCache<RelId, StoredRel>>,
 
     /// 出边邻接表缓存
-    outgoing_cache: LruCache<NodeId, Vec<RelId>>,
+    outgoing_cache: RwLock<LruCache<NodeId, Vec<RelId>>>,
 
     /// 入边邻接表缓存
-    incoming_cache: LruCache<NodeId, Vec<RelId>>,
+    incoming_cache: RwLock<LruCache<NodeId, Vec<RelId>>>,
 
     /// 配置
     config: CacheConfig,
@@ -335,136 +363,86 @@ impl CacheLayer {
         let adj_bytes = config.max_adjacent * 50;
 
         Self {
-            node_cache: LruCache::new(config.max_nodes, node_bytes),
-            rel_cache: LruCache::new(config.max_rels, rel_bytes),
-            outgoing_cache: LruCache::new(config.max_adjacent, adj_bytes),
-            incoming_cache: LruCache::new(config.max_adjacent, adj_bytes),
+            node_cache: RwLock::new(LruCache::new(config.max_nodes, node_bytes)),
+            rel_cache: RwLock::new(LruCache::new(config.max_rels, rel_bytes)),
+            outgoing_cache: RwLock::new(LruCache::new(config.max_adjacent, adj_bytes)),
+            incoming_cache: RwLock::new(LruCache::new(config.max_adjacent, adj_bytes)),
             config,
         }
     }
 
-    fn get_node(&mut self, id: NodeId) -> Option<StoredNode> {
-        self.node_cache.get(&id).cloned()
-    }
-
-    fn get_node_immutable(&self, id: NodeId) -> Option<StoredNode> {
-        self.node_cache.get_immutable(&id)
+    fn get_node(&self, id: NodeId) -> Option<StoredNode> {
+        self.node_cache.read().unwrap().get(&id)
     }
 
-    fn put_node(&mut self, id: NodeId, node: StoredNode) {
-        let size = Self::estimate_node_size(&node);
-        self.node_cache.put(id, node, size);
+    fn put_node(&self, id: NodeId, node: StoredNode) {
+        let size = node.estimated_size();
+        let mut node_cache = self.node_cache.write().unwrap();
+        node_cache.put(id, node, size);
         // 验证是否成功插入
-        debug_assert!(self.node_cache.entries.contains_key(&id), "Failed to insert node {} into cache", id);
+        debug_assert!(node_cache.contains(&id), "Failed to insert node {} into cache", id);
     }
 
-    fn invalidate_node(&mut self, id: NodeId) {
-        self.node_cache.invalidate(&id);
-        self.outgoing_cache.invalidate(&id);
-        self.incoming_cache.invalidate(&id);
+    fn invalidate_node(&self, id: NodeId) {
+        self.node_cache.write().unwrap().invalidate(&id);
+        self.outgoing_cache.write().unwrap().invalidate(&id);
+        self.incoming_cache.write().unwrap().invalidate(&id);
     }
 
-    fn get_rel(&mut self, id: RelId) -> Option<StoredRel> {
-        self.rel_cache.get(&id).cloned()
+    fn get_rel(&self, id: RelId) -> Option<StoredRel> {
+        self.rel_cache.read().unwrap().get(&id)
     }
 
-    fn get_rel_immutable(&self, id: RelId) -> Option<StoredRel> {
-        self.rel_cache.get_immutable(&id)
+    fn put_rel(&self, id: RelId, rel: StoredRel) {
+        let size = rel.estimated_size();
+        self.rel_cache.write().unwrap().put(id, rel, size);
     }
 
-    fn put_rel(&mut self, id: RelId, rel: StoredRel) {
-        let size = Self::estimate_rel_size(&rel);
-        self.rel_cache.put(id, rel, size);
+    fn invalidate_rel(&self, id: RelId) {
+        self.rel_cache.write().unwrap().invalidate(&id);
     }
 
-    fn invalidate_rel(&mut self, id: RelId) {
-        self.rel_cache.invalidate(&id);
+    fn invalidate_adjacency(&self, node: NodeId) {
+        self.outgoing_cache.write().unwrap().invalidate(&node);
+        self.incoming_cache.write().unwrap().invalidate(&node);
     }
 
-    fn get_outgoing(&mut self, node: NodeId) -> Option<Vec<RelId>> {
-        self.outgoing_cache.get(&node).cloned()
+    fn get_outgoing(&self, node: NodeId) -> Option<Vec<RelId>> {
+        self.outgoing_cache.read().unwrap().get(&node)
     }
 
-    fn put_outgoing(&mut self, node: NodeId, ids: Vec<RelId>) {
+    fn put_outgoing(&self, node: NodeId, ids: Vec<RelId>) {
         let size = ids.len() * 8; // 每个 RelId 8 字节
-        self.outgoing_cache.put(node, ids, size);
+        self.outgoing_cache.write().unwrap().put(node, ids, size);
     }
 
-    fn get_incoming(&mut self, node: NodeId) -> Option<Vec<RelId>> {
-        self.incoming_cache.get(&node).cloned()
+    fn get_incoming(&self, node: NodeId) -> Option<Vec<RelId>> {
+        self.incoming_cache.read().unwrap().get(&node)
     }
 
-    fn put_incoming(&mut self, node: NodeId, ids: Vec<RelId>) {
+    fn put_incoming(&self, node: NodeId, ids: Vec<RelId>) {
         let size = ids.len() * 8;
-        self.incoming_cache.put(node, ids, size);
+        self.incoming_cache.write().unwrap().put(node, ids, size);
     }
 
-    fn clear(&mut self) {
-        self.node_cache.clear();
-        self.rel_cache.clear();
-        self.outgoing_cache.clear();
-        self.incoming_cache.clear();
+    fn clear(&self) {
+        self.node_cache.write().unwrap().clear();
+        self.rel_cache.write().unwrap().clear();
+        self.outgoing_cache.write().unwrap().clear();
+        self.incoming_cache.write().unwrap().clear();
     }
 
     fn stats(&self) -> CacheStats {
-        let node_size = self.node_cache.len();
-        let node_rate = self.node_cache.hit_rate();
+        let node_cache = self.node_cache.read().unwrap();
+        let rel_cache = self.rel_cache.read().unwrap();
         CacheStats {
-            node_cache_size: node_size,
-            node_cache_hit_rate: node_rate,
-            rel_cache_size: self.rel_cache.len(),
-            rel_cache_hit_rate: self.rel_cache.hit_rate(),
-            outgoing_cache_size: self.outgoing_cache.len(),
-            incoming_cache_size: self.incoming_cache.len(),
-        }
-    }
-
-    fn estimate_node_size(node: &StoredNode) -> usize {
-        let mut size = std::mem::size_of::<NodeId>()
-            + std::mem::size_of::<Vec<String>>()
-            + std::mem::size_of::<HashMap<String, Value>>();
-
-        for label in &node.labels {
-            size += label.len();
+            node_cache_size: node_cache.len(),
+            node_cache_hit_rate: node_cache.hit_rate(),
+            rel_cache_size: rel_cache.len(),
+            rel_cache_hit_rate: rel_cache.hit_rate(),
+            outgoing_cache_size: self.outgoing_cache.read().unwrap().len(),
+            incoming_cache_size: self.incoming_cache.read().unwrap().len(),
         }
-
-        for (key, value) in &node.props {
-            size += key.len();
-            size += match value {
-                Value::Int(_) => 8,
-                Value::Bool(_) => 1,
-                Value::Text(s) => s.len(),
-                Value::Float(_) => 8,
-                Value::Null => 0,
-                Value::List(v) => v.len() * 8,
-            };
-        }
-
-        size
-    }
-
-    fn estimate_rel_size(rel: &StoredRel) -> usize {
-        let mut size = std::mem::size_of::<RelId>()
-            + std::mem::size_of::<NodeId>()
-            + std::mem::size_of::<NodeId>()
-            + std::mem::size_of::<String>()
-            + std::mem::size_of::<HashMap<String, Value>>();
-
-        size += rel.typ.len();
-
-        for (key, value) in &rel.props {
-            size += key.len();
-            size += match value {
-                Value::Int(_) => 8,
-                Value::Bool(_) => 1,
-                Value::Text(s) => s.len(),
-                Value::Float(_) => 8,
-                Value::Null => 0,
-                Value::List(v) => v.len() * 8,
-            };
-        }
-
-        size
     }
 }
 
@@ -494,6 +472,8 @@ struct PendingRel {
 struct WriteBuffer {
     pending_nodes: HashMap<NodeId, PendingNode>,
     pending_rels: HashMap<RelId, PendingRel>,
+    /// `pending_rels` 中的无向关系
+    undirected_rels: HashSet<RelId>,
     deleted_nodes: HashSet<NodeId>,
     deleted_rels: HashSet<RelId>,
     config: BufferConfig,
@@ -504,6 +484,7 @@ impl WriteBuffer {
         Self {
             pending_nodes: HashMap::new(),
             pending_rels: HashMap::new(),
+            undirected_rels: HashSet::new(),
             deleted_nodes: HashSet::new(),
             deleted_rels: HashSet::new(),
             config,
@@ -517,6 +498,13 @@ impl WriteBuffer {
 
     fn push_rel(&mut self, rel: PendingRel) {
         self.deleted_rels.remove(&rel.id);
+        self.undirected_rels.remove(&rel.id);
+        self.pending_rels.insert(rel.id, rel);
+    }
+
+    fn push_undirected_rel(&mut self, rel: PendingRel) {
+        self.deleted_rels.remove(&rel.id);
+        self.undirected_rels.insert(rel.id);
         self.pending_rels.insert(rel.id, rel);
     }
 
@@ -527,6 +515,7 @@ impl WriteBuffer {
 
     fn mark_delete_rel(&mut self, id: RelId) {
         self.pending_rels.remove(&id);
+        self.undirected_rels.remove(&id);
         self.deleted_rels.insert(id);
     }
 
@@ -551,11 +540,188 @@ impl WriteBuffer {
     fn clear(&mut self) {
         self.pending_nodes.clear();
         self.pending_rels.clear();
+        self.undirected_rels.clear();
         self.deleted_nodes.clear();
         self.deleted_rels.clear();
     }
 }
 
+// ============================================================================
+// Write-Ahead Log
+// ============================================================================
+
+/// WAL 文件名（位于 Sled 数据目录内）
+const WAL_FILE_NAME: &str = "hybrid.wal";
+
+/// WAL 中记录的写缓冲操作
+#[derive(Debug, Clone, Serialize, Deserialize)]
+enum WalOp {
+    PutNode(PendingNode),
+    PutRel(PendingRel),
+    DeleteNode(NodeId),
+    DeleteRel(RelId),
+    /// 无向关系（追加在末尾，保持已有记录的编码不变）
+    PutUndirectedRel(PendingRel),
+}
+
+/// 写缓冲的追加日志
+///
+/// 每条记录为 4 字节小端长度 + bincode 编码的 `WalOp`。缓冲数据刷入 Sled 后截断；
+/// 打开存储时回放残留的记录。崩溃时只写了一半的尾部记录会被忽略。
+struct WriteAheadLog {
+    file: File,
+}
+
+impl WriteAheadLog {
+    fn open(path: &Path) -> io::Result<Self> {
+        let file = OpenOptions::new()
+            .create(true)
+            .read(true)
+            .append(true)
+            .open(path)?;
+        Ok(Self { file })
+    }
+
+    fn append(&mut self, op: &WalOp) -> io::Result<()> {
+        let data = bincode::serialize(op)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        let mut record = Vec::with_capacity(4 + data.len());
+        record.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        record.extend_from_slice(&data);
+        self.file.write_all(&record)?;
+        self.file.flush()
+    }
+
+    /// 读取所有完整的记录
+    fn read_all(path: &Path) -> io::Result<Vec<WalOp>> {
+        let mut bytes = Vec::new();
+        match File::open(path) {
+            Ok(mut file) => {
+                file.read_to_end(&mut bytes)?;
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e),
+        }
+
+        let mut ops = Vec::new();
+        let mut pos = 0;
+        while pos + 4 <= bytes.len() {
+            let len = u32::from_le_bytes(bytes[pos..pos + 4].try_into().unwrap()) as usize;
+            let Some(data) = bytes.get(pos + 4..pos + 4 + len) else {
+                break;
+            };
+            match bincode::deserialize::<WalOp>(data) {
+                Ok(op) => ops.push(op),
+                Err(_) => break,
+            }
+            pos += 4 + len;
+        }
+        Ok(ops)
+    }
+
+    fn truncate(&mut self) -> io::Result<()> {
+        self.file.set_len(0)
+    }
+}
+
+/// 把 WAL 中残留的操作回放到 Sled，然后清空 WAL
+fn recover_from_wal(sled_store: &mut SledStore, path: &Path) -> io::Result<usize> {
+    let ops = WriteAheadLog::read_all(path)?;
+    if ops.is_empty() {
+        return Ok(0);
+    }
+
+    let replayed = ops.len();
+    for op in ops {
+        match op {
+            WalOp::PutNode(node) => sled_store.put_node(node.id, node.labels, node.props),
+            WalOp::PutRel(rel) => {
+                sled_store.put_rel(rel.id, rel.start, rel.end, rel.typ, rel.props)
+            }
+            WalOp::DeleteNode(id) => {
+                sled_store.delete_node(id);
+            }
+            WalOp::DeleteRel(id) => {
+                sled_store.delete_rel(id);
+            }
+            WalOp::PutUndirectedRel(rel) => {
+                sled_store.put_undirected_rel(rel.id, rel.start, rel.end, rel.typ, rel.props)
+            }
+        }
+    }
+
+    // 回放结果落盘后才能丢弃日志
+    sled_store
+        .flush()
+        .map_err(io::Error::other)?;
+    WriteAheadLog::open(path)?.truncate()?;
+    Ok(replayed)
+}
+
+/// 把写缓冲刷入 Sled 并截断 WAL
+///
+/// 整个过程持有缓冲区锁：读路径总能在缓冲区或 Sled 之一看到数据，
+/// 写路径也不会在刷盘与截断 WAL 之间插入新记录。锁顺序固定为 缓冲区 → Sled → WAL。
+fn flush_buffer(
+    buffer: &Mutex<WriteBuffer>,
+    sled_store: &Mutex<SledStore>,
+    wal: &Mutex<Option<WriteAheadLog>>,
+) {
+    let mut buffer = buffer.lock().unwrap();
+
+    if buffer.is_empty() {
+        return;
+    }
+
+    // 收集待写入的数据
+    let mut nodes: Vec<PendingNode> = buffer.pending_nodes.drain().map(|(_, n)| n).collect();
+    let mut rels: Vec<PendingRel> = buffer.pending_rels.drain().map(|(_, r)| r).collect();
+    let undirected: HashSet<RelId> = buffer.undirected_rels.drain().collect();
+    let deleted_nodes = buffer.deleted_nodes.drain().collect::<Vec<_>>();
+    let deleted_rels = buffer.deleted_rels.drain().collect::<Vec<_>>();
+
+    let mut sled_store = sled_store.lock().unwrap();
+
+    // 按缓冲区分配的 ID 写入，保证与读路径看到的 ID 一致
+    nodes.sort_by_key(|n| n.id);
+    for node in nodes {
+        sled_store.put_node(node.id, node.labels, node.props);
+    }
+
+    rels.sort_by_key(|r| r.id);
+    for rel in rels {
+        if undirected.contains(&rel.id) {
+            sled_store.put_undirected_rel(rel.id, rel.start, rel.end, rel.typ, rel.props);
+        } else {
+            sled_store.put_rel(rel.id, rel.start, rel.end, rel.typ, rel.props);
+        }
+    }
+
+    // 删除关系
+    for id in deleted_rels {
+        let _ = sled_store.delete_rel(id);
+    }
+
+    // 删除节点
+    for id in deleted_nodes {
+        let _ = sled_store.delete_node(id);
+    }
+
+    // 数据落盘后截断 WAL
+    if let Some(wal) = wal.lock().unwrap().as_mut() {
+        if sled_store.flush().is_ok() {
+            let _ = wal.truncate();
+        }
+    }
+}
+
+/// 后台刷盘线程句柄
+struct FlushTask {
+    /// 发送（或丢弃）即通知线程退出
+    stop: Sender<()>,
+    handle: JoinHandle<()>,
+}
+
 // ============================================================================
 // HybridStore
 // ============================================================================
@@ -567,11 +733,11 @@ impl WriteBuffer {
 /// - Write Buffer: 写缓冲，批量刷盘提升写性能
 /// - SledStore: 持久化层
 pub struct HybridStore {
-    /// 底层 Sled 存储
-    sled_store: SledStore,
+    /// 底层 Sled 存储（与后台刷盘线程共享）
+    sled_store: Arc<Mutex<SledStore>>,
 
     /// 缓存层
-    cache: Arc<Mutex<CacheLayer>>,
+    cache: Arc<CacheLayer>,
 
     /// 写缓冲
     buffer: Arc<Mutex<WriteBuffer>>,
@@ -579,14 +745,27 @@ pub struct HybridStore {
     /// 配置
     config: HybridConfig,
 
-    /// 是否已停止
-    stopped: Arc<Mutex<bool>>,
+    /// 后台刷盘线程（仅 Batch 策略）
+    flush_task: Option<FlushTask>,
 
     /// 下一个节点 ID
     next_node_id: Arc<Mutex<NodeId>>,
 
     /// 下一个关系 ID
     next_rel_id: Arc<Mutex<RelId>>,
+
+    /// 节点计数（包含缓冲区中尚未刷盘的节点，不含已标记删除的节点）
+    node_count: usize,
+
+    /// 关系计数（同上）
+    rel_count: usize,
+
+    /// 写缓冲的 WAL（Immediate 策略或关闭 WAL 时为 None）
+    wal: Arc<Mutex<Option<WriteAheadLog>>>,
+
+    /// 测试用：模拟进程崩溃，Drop 时不刷盘
+    #[cfg(test)]
+    skip_flush_on_drop: bool,
 }
 
 impl HybridStore {
@@ -601,120 +780,107 @@ impl HybridStore {
         indexed_properties: Vec<(String, String)>,
         config: HybridConfig,
     ) -> Result<Self, sled::Error> {
-        let sled_store = SledStore::with_config(path, indexed_properties)?;
-        let cache = Arc::new(Mutex::new(CacheLayer::new(config.cache.clone())));
+        let mut sled_store = SledStore::with_config(&path, indexed_properties)?;
+
+        // 回放上次未刷盘的缓冲写入
+        let wal_path: PathBuf = path.as_ref().join(WAL_FILE_NAME);
+        recover_from_wal(&mut sled_store, &wal_path).map_err(sled::Error::Io)?;
+        let buffered = !matches!(config.flush_strategy, FlushStrategy::Immediate);
+        let wal = if buffered && config.buffer.wal_enabled {
+            Some(WriteAheadLog::open(&wal_path).map_err(sled::Error::Io)?)
+        } else {
+            None
+        };
+
+        let cache = Arc::new(CacheLayer::new(config.cache.clone()));
         let buffer = Arc::new(Mutex::new(WriteBuffer::new(config.buffer.clone())));
-        let stopped = Arc::new(Mutex::new(false));
 
-        // 从 Sled 读取最大 ID
-        let next_node_id = {
-            let max_id = sled_store.all_nodes().map(|n| n.id).max();
-            Arc::new(Mutex::new(max_id.map(|id| id + 1).unwrap_or(0)))
-        };
+        // 从 Sled 已有记录的最大 ID 继续分配，避免重新打开后 ID 冲突
+        let (next_node_id, next_rel_id) = sled_store.next_ids();
+        let next_node_id = Arc::new(Mutex::new(next_node_id));
+        let next_rel_id = Arc::new(Mutex::new(next_rel_id));
 
-        let next_rel_id = Arc::new(Mutex::new(0));
+        let node_count = sled_store.node_count();
+        let rel_count = sled_store.rel_count();
 
-        let store = Self {
-            sled_store,
+        let mut store = Self {
+            sled_store: Arc::new(Mutex::new(sled_store)),
             cache,
             buffer,
             config,
-            stopped,
+            flush_task: None,
             next_node_id,
             next_rel_id,
+            node_count,
+            rel_count,
+            wal: Arc::new(Mutex::new(wal)),
+            #[cfg(test)]
+            skip_flush_on_drop: false,
         };
 
         // 启动后台刷盘任务
-        if matches!(
-            store.config.flush_strategy,
-            FlushStrategy::Batch { .. }
-        ) {
-            store.start_flush_task();
+        if let FlushStrategy::Batch { interval_ms, .. } = store.config.flush_strategy {
+            store.flush_task = Some(store.start_flush_task(interval_ms));
         }
 
         Ok(store)
     }
 
-    /// 启动后台刷盘任务
-    fn start_flush_task(&self) {
+    /// 启动后台刷盘任务：每隔 `interval_ms` 把非空的写缓冲刷入 Sled
+    fn start_flush_task(&self, interval_ms: u64) -> FlushTask {
         let buffer = Arc::clone(&self.buffer);
-        let stopped = Arc::clone(&self.stopped);
-        let sled_store = unsafe { &*(&self.sled_store as *const _ as *const SledStore) };
-
-        let interval_ms = match self.config.flush_strategy {
-            FlushStrategy::Batch { interval_ms, .. } => interval_ms,
-            _ => 1000,
-        };
-
-        thread::spawn(move || {
-            loop {
-                thread::sleep(Duration::from_millis(interval_ms));
-
-                if *stopped.lock().unwrap() {
-                    break;
-                }
-
-                // 检查是否需要刷盘
-                let should_flush = {
-                    let buf = buffer.lock().unwrap();
-                    buf.should_flush()
-                };
-
-                if should_flush {
-                    // 注意：这里无法调用 flush_to_sled 因为需要 &mut self
-                    // 这是一个简化实现，实际使用时应该在写操作时检查并刷盘
-                }
+        let sled_store = Arc::clone(&self.sled_store);
+        let wal = Arc::clone(&self.wal);
+        let (stop, stop_rx) = mpsc::channel::<()>();
+
+        // 收到停止信号或发送端被丢弃时退出
+        let handle = thread::spawn(move || {
+            let interval = Duration::from_millis(interval_ms);
+            while let Err(RecvTimeoutError::Timeout) = stop_rx.recv_timeout(interval) {
+                flush_buffer(&buffer, &sled_store, &wal);
             }
         });
-    }
 
-    /// 刷盘到 Sled
-    fn flush_to_sled(&mut self) {
-        let mut buffer = self.buffer.lock().unwrap();
-
-        if buffer.is_empty() {
-            return;
-        }
-
-        // 收集待写入的数据
-        let nodes = buffer.pending_nodes.drain().collect::<Vec<_>>();
-        let rels = buffer.pending_rels.drain().collect::<Vec<_>>();
-        let deleted_nodes = buffer.deleted_nodes.drain().collect::<Vec<_>>();
-        let deleted_rels = buffer.deleted_rels.drain().collect::<Vec<_>>();
-
-        // 释放锁
-        drop(buffer);
+        FlushTask { stop, handle }
+    }
 
-        // 写入节点
-        for (_, node) in nodes {
-            let _ = self.sled_store.create_node(node.labels, node.props);
+    /// 停止后台刷盘线程并等待其退出
+    fn stop_flush_task(&mut self) {
+        if let Some(task) = self.flush_task.take() {
+            let _ = task.stop.send(());
+            let _ = task.handle.join();
         }
+    }
 
-        // 写入关系
-        for (_, rel) in rels {
-            let _ = self.sled_store.create_rel(rel.start, rel.end, rel.typ, rel.props);
-        }
+    /// 刷盘到 Sled
+    fn flush_to_sled(&self) {
+        flush_buffer(&self.buffer, &self.sled_store, &self.wal);
+    }
 
-        // 删除节点
-        for id in deleted_nodes {
-            let _ = self.sled_store.delete_node(id);
+    /// 追加 WAL 记录（未启用 WAL 时忽略）
+    ///
+    /// 调用方应持有缓冲区锁，使 WAL 记录与缓冲区内容在刷盘时保持一致
+    fn log(&self, op: WalOp) {
+        if let Some(wal) = self.wal.lock().unwrap().as_mut() {
+            let _ = wal.append(&op);
         }
+    }
 
-        // 删除关系
-        for id in deleted_rels {
-            let _ = self.sled_store.delete_rel(id);
-        }
+    /// 测试用：模拟进程崩溃，丢弃内存中的写缓冲而不刷盘
+    #[cfg(test)]
+    fn simulate_crash(mut self) {
+        self.skip_flush_on_drop = true;
     }
 
     /// 强制刷盘
     pub fn flush(&mut self) -> Result<usize, sled::Error> {
         self.flush_to_sled();
-        self.sled_store.flush()
+        self.sled_store.lock().unwrap().flush()
     }
 
     /// 获取统计信息
     pub fn stats(&self) -> HybridStats {
-        let cache = self.cache.lock().unwrap();
+        let cache = &self.cache;
         let buffer = self.buffer.lock().unwrap();
 
         HybridStats {
@@ -727,8 +893,9 @@ impl HybridStore {
     /// 预热缓存
     pub fn warmup(&mut self, node_ids: Vec<NodeId>) {
         for id in node_ids {
-            if let Some(node) = self.sled_store.get_node(id) {
-                let mut cache = self.cache.lock().unwrap();
+            let node = self.sled_store.lock().unwrap().get_node(id);
+            if let Some(node) = node {
+                let cache = &self.cache;
                 cache.put_node(id, node);
             }
         }
@@ -736,14 +903,20 @@ impl HybridStore {
 
     /// 清空缓存
     pub fn clear_cache(&self) {
-        let mut cache = self.cache.lock().unwrap();
+        let cache = &self.cache;
         cache.clear();
     }
 }
 
 impl Drop for HybridStore {
     fn drop(&mut self) {
-        *self.stopped.lock().unwrap() = true;
+        self.stop_flush_task();
+
+        #[cfg(test)]
+        if self.skip_flush_on_drop {
+            return;
+        }
+
         self.flush_to_sled();
     }
 }
@@ -753,6 +926,12 @@ impl Drop for HybridStore {
 // ============================================================================
 
 impl StorageEngine for HybridStore {
+    /// 先把写缓冲刷入 sled，再压缩 sled 存储
+    fn compact(&mut self) -> Result<usize, StorageError> {
+        self.flush_to_sled();
+        self.sled_store.lock().unwrap().compact()
+    }
+
     fn create_node(
         &mut self,
         labels: Vec<String>,
@@ -770,20 +949,21 @@ impl StorageEngine for HybridStore {
         match self.config.flush_strategy {
             FlushStrategy::Immediate => {
                 // 立即写入 Sled
-                let _ = self.sled_store.create_node(labels.clone(), props.clone());
+                let _ = self.sled_store.lock().unwrap().create_node(labels.clone(), props.clone());
 
                 // 更新缓存
                 let node = StoredNode { id, labels, props };
-                let mut cache = self.cache.lock().unwrap();
+                let cache = &self.cache;
                 cache.put_node(id, node.clone());
                 // 验证缓存已更新
-                debug_assert!(cache.node_cache.entries.contains_key(&id), "Node {} not in cache after put_node", id);
+                debug_assert!(cache.node_cache.read().unwrap().contains(&id), "Node {} not in cache after put_node", id);
             }
             FlushStrategy::Batch { .. } | FlushStrategy::OnTxCommit => {
                 // 写入缓冲区
                 let node = PendingNode { id, labels, props };
 
                 let mut buffer = self.buffer.lock().unwrap();
+                self.log(WalOp::PutNode(node.clone()));
                 buffer.push_node(node);
 
                 // 检查是否需要刷盘
@@ -794,6 +974,7 @@ impl StorageEngine for HybridStore {
             }
         }
 
+        self.node_count += 1;
         id
     }
 
@@ -814,16 +995,17 @@ impl StorageEngine for HybridStore {
 
         match self.config.flush_strategy {
             FlushStrategy::Immediate => {
-                let _ = self.sled_store.create_rel(start, end, typ.clone(), props.clone());
+                let _ = self.sled_store.lock().unwrap().create_rel(start, end, typ.clone(), props.clone());
 
-                let rel = StoredRel { id, start, end, typ, props };
-                let mut cache = self.cache.lock().unwrap();
+                let rel = StoredRel { id, start, end, typ, props, directed: true };
+                let cache = &self.cache;
                 cache.put_rel(id, rel);
             }
             FlushStrategy::Batch { .. } | FlushStrategy::OnTxCommit => {
                 let rel = PendingRel { id, start, end, typ, props };
 
                 let mut buffer = self.buffer.lock().unwrap();
+                self.log(WalOp::PutRel(rel.clone()));
                 buffer.push_rel(rel);
 
                 if buffer.should_flush() {
@@ -833,14 +1015,57 @@ impl StorageEngine for HybridStore {
             }
         }
 
+        self.rel_count += 1;
         id
     }
 
+    fn create_undirected_rel(
+        &mut self,
+        start: NodeId,
+        end: NodeId,
+        typ: String,
+        props: HashMap<String, Value>,
+    ) -> Result<RelId, StorageError> {
+        let id = {
+            let mut next_id = self.next_rel_id.lock().unwrap();
+            let id = *next_id;
+            *next_id += 1;
+            id
+        };
+
+        match self.config.flush_strategy {
+            FlushStrategy::Immediate => {
+                self.sled_store.lock().unwrap().put_undirected_rel(id, start, end, typ.clone(), props.clone());
+
+                let rel = StoredRel { id, start, end, typ, props, directed: false };
+                self.cache.put_rel(id, rel);
+            }
+            FlushStrategy::Batch { .. } | FlushStrategy::OnTxCommit => {
+                let rel = PendingRel { id, start, end, typ, props };
+
+                let mut buffer = self.buffer.lock().unwrap();
+                self.log(WalOp::PutUndirectedRel(rel.clone()));
+                buffer.push_undirected_rel(rel);
+
+                if buffer.should_flush() {
+                    drop(buffer);
+                    self.flush_to_sled();
+                }
+            }
+        }
+        // 两个端点的邻接表都多了这条关系
+        self.cache.invalidate_adjacency(start);
+        self.cache.invalidate_adjacency(end);
+
+        self.rel_count += 1;
+        Ok(id)
+    }
+
     fn get_node(&self, id: NodeId) -> Option<StoredNode> {
         // 先查缓存
         {
-            let cache = self.cache.lock().unwrap();
-            if let Some(node) = cache.get_node_immutable(id) {
+            let cache = &self.cache;
+            if let Some(node) = cache.get_node(id) {
                 return Some(node);
             }
         }
@@ -861,11 +1086,11 @@ impl StorageEngine for HybridStore {
         }
 
         // 查 Sled
-        let node = self.sled_store.get_node(id)?;
+        let node = self.sled_store.lock().unwrap().get_node(id)?;
 
         // 更新缓存
         {
-            let mut cache = self.cache.lock().unwrap();
+            let cache = &self.cache;
             cache.put_node(id, node.clone());
         }
 
@@ -875,8 +1100,8 @@ impl StorageEngine for HybridStore {
     fn get_rel(&self, id: RelId) -> Option<StoredRel> {
         // 先查缓存
         {
-            let cache = self.cache.lock().unwrap();
-            if let Some(rel) = cache.get_rel_immutable(id) {
+            let cache = &self.cache;
+            if let Some(rel) = cache.get_rel(id) {
                 return Some(rel);
             }
         }
@@ -891,6 +1116,7 @@ impl StorageEngine for HybridStore {
                     end: rel.end,
                     typ: rel.typ.clone(),
                     props: rel.props.clone(),
+                    directed: !buffer.undirected_rels.contains(&id),
                 });
             }
             if buffer.deleted_rels.contains(&id) {
@@ -899,11 +1125,11 @@ impl StorageEngine for HybridStore {
         }
 
         // 查 Sled
-        let rel = self.sled_store.get_rel(id)?;
+        let rel = self.sled_store.lock().unwrap().get_rel(id)?;
 
         // 更新缓存
         {
-            let mut cache = self.cache.lock().unwrap();
+            let cache = &self.cache;
             cache.put_rel(id, rel.clone());
         }
 
@@ -911,18 +1137,25 @@ impl StorageEngine for HybridStore {
     }
 
     fn all_nodes(&self) -> Box<dyn Iterator<Item = StoredNode> + '_> {
-        self.sled_store.all_nodes()
+        // 持有锁期间收集结果，避免迭代器借用受锁保护的存储
+        let nodes: Vec<StoredNode> = self.sled_store.lock().unwrap().all_nodes().collect();
+        Box::new(nodes.into_iter())
+    }
+
+    fn scan_nodes(&self, filter: NodeFilter) -> Box<dyn Iterator<Item = StoredNode> + '_> {
+        let nodes: Vec<StoredNode> = self.sled_store.lock().unwrap().scan_nodes(filter).collect();
+        Box::new(nodes.into_iter())
     }
 
     fn outgoing_rels(&self, node: NodeId) -> Box<dyn Iterator<Item = StoredRel> + '_> {
         // 先查缓存
         let rel_ids = {
-            let mut cache = self.cache.lock().unwrap();
+            let cache = &self.cache;
             if let Some(ids) = cache.get_outgoing(node) {
                 ids
             } else {
                 // 从 Sled 加载
-                let ids: Vec<RelId> = self.sled_store.outgoing_rels(node).map(|r| r.id).collect();
+                let ids: Vec<RelId> = self.sled_store.lock().unwrap().outgoing_rels(node).map(|r| r.id).collect();
 
                 // 更新缓存
                 cache.put_outgoing(node, ids.clone());
@@ -936,11 +1169,11 @@ impl StorageEngine for HybridStore {
     fn incoming_rels(&self, node: NodeId) -> Box<dyn Iterator<Item = StoredRel> + '_> {
         // 先查缓存
         let rel_ids = {
-            let mut cache = self.cache.lock().unwrap();
+            let cache = &self.cache;
             if let Some(ids) = cache.get_incoming(node) {
                 ids
             } else {
-                let ids: Vec<RelId> = self.sled_store.incoming_rels(node).map(|r| r.id).collect();
+                let ids: Vec<RelId> = self.sled_store.lock().unwrap().incoming_rels(node).map(|r| r.id).collect();
                 cache.put_incoming(node, ids.clone());
                 ids
             }
@@ -949,20 +1182,63 @@ impl StorageEngine for HybridStore {
         Box::new(rel_ids.into_iter().filter_map(move |rid| self.get_rel(rid)))
     }
 
+    fn node_count(&self) -> usize {
+        self.node_count
+    }
+
+    fn rel_count(&self) -> usize {
+        self.rel_count
+    }
+
     fn delete_node(&mut self, id: NodeId) -> bool {
+        if self.get_node(id).is_none() {
+            return false;
+        }
+
+        // 收集关联关系（已落盘的和缓冲区中的），用于维护计数
+        let mut attached: HashSet<RelId> = self
+            .outgoing_rels(id)
+            .chain(self.incoming_rels(id))
+            .map(|r| r.id)
+            .collect();
+        {
+            let buffer = self.buffer.lock().unwrap();
+            attached.extend(
+                buffer
+                    .pending_rels
+                    .values()
+                    .filter(|r| r.start == id || r.end == id)
+                    .map(|r| r.id),
+            );
+        }
+
         // 从缓存中移除
         {
-            let mut cache = self.cache.lock().unwrap();
+            let cache = &self.cache;
             cache.invalidate_node(id);
+            for rel_id in &attached {
+                cache.invalidate_rel(*rel_id);
+            }
         }
 
+        self.node_count -= 1;
+        self.rel_count -= attached.len();
+
         // 标记删除
         match self.config.flush_strategy {
             FlushStrategy::Immediate => {
-                self.sled_store.delete_node(id)
+                self.sled_store.lock().unwrap().delete_node(id)
             }
             FlushStrategy::Batch { .. } | FlushStrategy::OnTxCommit => {
                 let mut buffer = self.buffer.lock().unwrap();
+                for rel_id in &attached {
+                    self.log(WalOp::DeleteRel(*rel_id));
+                }
+                self.log(WalOp::DeleteNode(id));
+                // 关联关系一并标记删除，避免刷盘后留下悬空关系
+                for rel_id in attached {
+                    buffer.mark_delete_rel(rel_id);
+                }
                 buffer.mark_delete_node(id);
                 if buffer.should_flush() {
                     drop(buffer);
@@ -974,18 +1250,25 @@ impl StorageEngine for HybridStore {
     }
 
     fn delete_rel(&mut self, id: RelId) -> bool {
+        if self.get_rel(id).is_none() {
+            return false;
+        }
+
         // 从缓存中移除
         {
-            let mut cache = self.cache.lock().unwrap();
+            let cache = &self.cache;
             cache.invalidate_rel(id);
         }
 
+        self.rel_count -= 1;
+
         match self.config.flush_strategy {
             FlushStrategy::Immediate => {
-                self.sled_store.delete_rel(id)
+                self.sled_store.lock().unwrap().delete_rel(id)
             }
             FlushStrategy::Batch { .. } | FlushStrategy::OnTxCommit => {
                 let mut buffer = self.buffer.lock().unwrap();
+                self.log(WalOp::DeleteRel(id));
                 buffer.mark_delete_rel(id);
                 if buffer.should_flush() {
                     drop(buffer);
@@ -1008,12 +1291,12 @@ impl StorageEngine for HybridStore {
             id
         };
 
-        match self.config.flush_strategy {
+        let ids = match self.config.flush_strategy {
             FlushStrategy::Immediate => {
-                let ids = self.sled_store.batch_create_nodes(nodes.clone());
+                let ids = self.sled_store.lock().unwrap().batch_create_nodes(nodes.clone());
 
                 // 批量更新缓存
-                let mut cache = self.cache.lock().unwrap();
+                let cache = &self.cache;
                 for (i, (labels, props)) in nodes.into_iter().enumerate() {
                     let node = StoredNode {
                         id: ids[i],
@@ -1026,11 +1309,18 @@ impl StorageEngine for HybridStore {
                 ids
             }
             FlushStrategy::Batch { .. } | FlushStrategy::OnTxCommit => {
+                let pending: Vec<PendingNode> = nodes
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, (labels, props))| PendingNode {
+                        id: start_id + i as NodeId,
+                        labels,
+                        props,
+                    })
+                    .collect();
                 let mut buffer = self.buffer.lock().unwrap();
-
-                for (i, (labels, props)) in nodes.into_iter().enumerate() {
-                    let id = start_id + i as NodeId;
-                    let node = PendingNode { id, labels, props };
+                for node in pending {
+                    self.log(WalOp::PutNode(node.clone()));
                     buffer.push_node(node);
                 }
 
@@ -1041,7 +1331,10 @@ impl StorageEngine for HybridStore {
 
                 (start_id..start_id + count).collect()
             }
-        }
+        };
+
+        self.node_count += ids.len();
+        ids
     }
 
     fn batch_create_rels(
@@ -1056,11 +1349,11 @@ impl StorageEngine for HybridStore {
             id
         };
 
-        match self.config.flush_strategy {
+        let ids = match self.config.flush_strategy {
             FlushStrategy::Immediate => {
-                let ids = self.sled_store.batch_create_rels(rels.clone());
+                let ids = self.sled_store.lock().unwrap().batch_create_rels(rels.clone());
 
-                let mut cache = self.cache.lock().unwrap();
+                let cache = &self.cache;
                 for (i, (start, end, typ, props)) in rels.into_iter().enumerate() {
                     let rel = StoredRel {
                         id: ids[i],
@@ -1068,6 +1361,7 @@ impl StorageEngine for HybridStore {
                         end,
                         typ,
                         props,
+                        directed: true,
                     };
                     cache.put_rel(ids[i], rel);
                 }
@@ -1075,11 +1369,20 @@ impl StorageEngine for HybridStore {
                 ids
             }
             FlushStrategy::Batch { .. } | FlushStrategy::OnTxCommit => {
+                let pending: Vec<PendingRel> = rels
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, (start, end, typ, props))| PendingRel {
+                        id: start_id + i as RelId,
+                        start,
+                        end,
+                        typ,
+                        props,
+                    })
+                    .collect();
                 let mut buffer = self.buffer.lock().unwrap();
-
-                for (i, (start, end, typ, props)) in rels.into_iter().enumerate() {
-                    let id = start_id + i as RelId;
-                    let rel = PendingRel { id, start, end, typ, props };
+                for rel in pending {
+                    self.log(WalOp::PutRel(rel.clone()));
                     buffer.push_rel(rel);
                 }
 
@@ -1090,7 +1393,10 @@ impl StorageEngine for HybridStore {
 
                 (start_id..start_id + count).collect()
             }
-        }
+        };
+
+        self.rel_count += ids.len();
+        ids
     }
 }
 
@@ -1163,7 +1469,7 @@ mod tests {
     #[test]
     fn test_cache_layer_direct() {
         let config = CacheConfig::default();
-        let mut cache = CacheLayer::new(config);
+        let cache = CacheLayer::new(config);
 
         // 直接测试缓存层
         let node = StoredNode {
@@ -1175,8 +1481,8 @@ mod tests {
         cache.put_node(1, node.clone());
 
         // 验证缓存大小
-        assert_eq!(cache.node_cache.entries.len(), 1, "Cache should contain 1 entry");
-        assert_eq!(cache.node_cache.len(), 1, "Cache len() should return 1");
+        assert_eq!(cache.node_cache.read().unwrap().entries.len(), 1, "Cache should contain 1 entry");
+        assert_eq!(cache.node_cache.read().unwrap().len(), 1, "Cache len() should return 1");
 
         // 验证可以读取
         let retrieved = cache.get_node(1);
@@ -1311,4 +1617,385 @@ mod tests {
         let stats = store.stats();
         assert_eq!(stats.cache.node_cache_size, 1000);
     }
+
+    #[test]
+    fn test_counts_with_buffer_and_flush() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = HybridConfig {
+            flush_strategy: FlushStrategy::OnTxCommit,
+            buffer: BufferConfig {
+                flush_threshold: 1000,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let mut store = HybridStore::with_config(temp_dir.path(), vec![], config).unwrap();
+
+        let a = store.create_node(vec!["Person".to_string()], HashMap::new());
+        let b = store.create_node(vec!["Person".to_string()], HashMap::new());
+        store.batch_create_nodes(vec![(vec!["Person".to_string()], HashMap::new()); 3]);
+        store.create_rel(a, b, "KNOWS".to_string(), HashMap::new());
+        let r = store.create_rel(b, a, "KNOWS".to_string(), HashMap::new());
+
+        // 数据仍在缓冲区中
+        assert!(store.stats().buffer_size > 0);
+        assert_eq!(store.node_count(), 5);
+        assert_eq!(store.rel_count(), 2);
+
+        // 删除缓冲区中的关系
+        assert!(store.delete_rel(r));
+        assert!(!store.delete_rel(r));
+        assert_eq!(store.rel_count(), 1);
+
+        store.flush().unwrap();
+        assert_eq!(store.node_count(), 5);
+        assert_eq!(store.rel_count(), 1);
+        assert_eq!(store.sled_store.lock().unwrap().node_count(), 5);
+        assert_eq!(store.sled_store.lock().unwrap().rel_count(), 1);
+
+        // 刷盘后删除节点，关联关系一并计入
+        let victim = store.all_nodes().find(|n| store.outgoing_rels(n.id).count() > 0).unwrap().id;
+        assert!(store.delete_node(victim));
+        assert!(!store.delete_node(victim));
+        assert_eq!(store.node_count(), 4);
+        assert_eq!(store.rel_count(), 0);
+
+        store.flush().unwrap();
+        assert_eq!(store.sled_store.lock().unwrap().node_count(), 4);
+        assert_eq!(store.sled_store.lock().unwrap().rel_count(), 0);
+    }
+    #[test]
+    fn test_wal_recovery_after_crash() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = HybridConfig {
+            flush_strategy: FlushStrategy::OnTxCommit,
+            buffer: BufferConfig {
+                flush_threshold: 1000,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let (a, b, c, kept_rel) = {
+            let mut store =
+                HybridStore::with_config(temp_dir.path(), vec![], config.clone()).unwrap();
+            let mut props = HashMap::new();
+            props.insert("name".to_string(), Value::Text("Alice".to_string()));
+            let a = store.create_node(vec!["Person".to_string()], props);
+            let b = store.create_node(vec!["Person".to_string()], HashMap::new());
+            let c = store.create_node(vec!["Person".to_string()], HashMap::new());
+            let kept_rel = store.create_rel(a, b, "KNOWS".to_string(), HashMap::new());
+
+            // 数据只在写缓冲和 WAL 中，Sled 里还没有
+            assert!(store.stats().buffer_size > 0);
+            assert_eq!(store.sled_store.lock().unwrap().node_count(), 0);
+
+            store.simulate_crash();
+            (a, b, c, kept_rel)
+        };
+
+        let store = HybridStore::with_config(temp_dir.path(), vec![], config).unwrap();
+        assert_eq!(store.node_count(), 3);
+        assert_eq!(store.rel_count(), 1);
+
+        let alice = store.get_node(a).unwrap();
+        assert_eq!(alice.props.get("name"), Some(&Value::Text("Alice".to_string())));
+        assert!(store.get_node(c).is_some());
+
+        let rel = store.get_rel(kept_rel).unwrap();
+        assert_eq!((rel.start, rel.end), (a, b));
+        assert_eq!(store.outgoing_rels(a).count(), 1);
+
+        // 回放后 WAL 被清空
+        let wal_len = std::fs::metadata(temp_dir.path().join(WAL_FILE_NAME)).unwrap().len();
+        assert_eq!(wal_len, 0);
+    }
+
+    #[test]
+    fn test_buffered_undirected_rel_recovered_from_wal() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = HybridConfig {
+            flush_strategy: FlushStrategy::OnTxCommit,
+            buffer: BufferConfig {
+                flush_threshold: 1000,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let (a, b, undirected, directed) = {
+            let mut store =
+                HybridStore::with_config(temp_dir.path(), vec![], config.clone()).unwrap();
+            let a = store.create_node(vec!["Person".to_string()], HashMap::new());
+            let b = store.create_node(vec!["Person".to_string()], HashMap::new());
+            let undirected = store
+                .create_undirected_rel(a, b, "KNOWS".to_string(), HashMap::new())
+                .unwrap();
+            let directed = store.create_rel(a, b, "FOLLOWS".to_string(), HashMap::new());
+            // 缓冲区中的关系保留方向性
+            assert!(!store.get_rel(undirected).unwrap().directed);
+            assert!(store.get_rel(directed).unwrap().directed);
+            store.simulate_crash();
+            (a, b, undirected, directed)
+        };
+
+        let store = HybridStore::with_config(temp_dir.path(), vec![], config).unwrap();
+        assert_eq!(store.rel_count(), 2);
+        assert!(!store.get_rel(undirected).unwrap().directed);
+        assert!(store.get_rel(directed).unwrap().directed);
+        // 无向关系登记在两个端点的出边中
+        let out_b: Vec<RelId> = store.outgoing_rels(b).map(|r| r.id).collect();
+        assert_eq!(out_b, vec![undirected]);
+        assert_eq!(store.outgoing_rels(a).count(), 2);
+    }
+
+    #[test]
+    fn test_wal_ignores_torn_tail_record() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join(WAL_FILE_NAME);
+
+        let mut wal = WriteAheadLog::open(&path).unwrap();
+        wal.append(&WalOp::DeleteNode(7)).unwrap();
+        wal.append(&WalOp::DeleteRel(9)).unwrap();
+        drop(wal);
+
+        // 截掉最后一条记录的一部分，模拟写入中途崩溃
+        let len = std::fs::metadata(&path).unwrap().len();
+        OpenOptions::new().write(true).open(&path).unwrap().set_len(len - 2).unwrap();
+
+        let ops = WriteAheadLog::read_all(&path).unwrap();
+        assert_eq!(ops.len(), 1);
+        assert!(matches!(ops[0], WalOp::DeleteNode(7)));
+    }
+
+    #[test]
+    fn test_wal_truncated_after_flush() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = HybridConfig {
+            flush_strategy: FlushStrategy::OnTxCommit,
+            ..Default::default()
+        };
+        let mut store = HybridStore::with_config(temp_dir.path(), vec![], config).unwrap();
+        store.create_node(vec!["Person".to_string()], HashMap::new());
+
+        let wal_path = temp_dir.path().join(WAL_FILE_NAME);
+        assert!(std::fs::metadata(&wal_path).unwrap().len() > 0);
+
+        store.flush().unwrap();
+        assert_eq!(std::fs::metadata(&wal_path).unwrap().len(), 0);
+        assert_eq!(store.sled_store.lock().unwrap().node_count(), 1);
+    }
+
+    #[test]
+    fn test_rel_ids_do_not_collide_after_reopen() {
+        let temp_dir = TempDir::new().unwrap();
+
+        let existing: Vec<RelId> = {
+            let mut store = HybridStore::new(temp_dir.path()).unwrap();
+            let a = store.create_node(vec!["Person".to_string()], HashMap::new());
+            let b = store.create_node(vec!["Person".to_string()], HashMap::new());
+            let ids = vec![
+                store.create_rel(a, b, "KNOWS".to_string(), HashMap::new()),
+                store.create_rel(b, a, "KNOWS".to_string(), HashMap::new()),
+            ];
+            store.flush().unwrap();
+            ids
+        };
+
+        let mut store = HybridStore::new(temp_dir.path()).unwrap();
+        let nodes: Vec<NodeId> = store.all_nodes().map(|n| n.id).collect();
+        let new_rel = store.create_rel(nodes[0], nodes[1], "LIKES".to_string(), HashMap::new());
+        assert!(!existing.contains(&new_rel));
+
+        // 原有关系未被覆盖
+        for id in existing {
+            assert_eq!(store.get_rel(id).unwrap().typ, "KNOWS");
+        }
+        assert_eq!(store.get_rel(new_rel).unwrap().typ, "LIKES");
+
+        store.flush().unwrap();
+        assert_eq!(store.sled_store.lock().unwrap().rel_count(), 3);
+    }
+
+    fn batch_config(interval_ms: u64) -> HybridConfig {
+        HybridConfig {
+            buffer: BufferConfig {
+                flush_threshold: 100_000,
+                ..Default::default()
+            },
+            flush_strategy: FlushStrategy::Batch {
+                interval_ms,
+                threshold: 100_000,
+            },
+            ..Default::default()
+        }
+    }
+
+    /// 等待后台线程把缓冲区刷空
+    fn wait_for_background_flush(store: &HybridStore) {
+        for _ in 0..500 {
+            if store.stats().buffer_size == 0 {
+                return;
+            }
+            thread::sleep(Duration::from_millis(5));
+        }
+        panic!("background flush did not drain the write buffer");
+    }
+
+    #[test]
+    fn test_background_flush_with_concurrent_writers() {
+        let temp_dir = TempDir::new().unwrap();
+        let store = HybridStore::with_config(temp_dir.path(), vec![], batch_config(5)).unwrap();
+        let store = Arc::new(Mutex::new(store));
+
+        let writers: Vec<_> = (0..4)
+            .map(|_| {
+                let store = Arc::clone(&store);
+                thread::spawn(move || {
+                    let mut last = None;
+                    for _ in 0..50 {
+                        let mut guard = store.lock().unwrap();
+                        let id = guard.create_node(vec!["Person".to_string()], HashMap::new());
+                        if let Some(prev) = last {
+                            guard.create_rel(prev, id, "NEXT".to_string(), HashMap::new());
+                        }
+                        last = Some(id);
+                        drop(guard);
+                        thread::yield_now();
+                    }
+                })
+            })
+            .collect();
+        for writer in writers {
+            writer.join().unwrap();
+        }
+
+        // 没有显式 flush，数据由后台线程写入 Sled
+        let store = store.lock().unwrap();
+        wait_for_background_flush(&store);
+        let sled = store.sled_store.lock().unwrap();
+        assert_eq!(sled.node_count(), 200);
+        assert_eq!(sled.rel_count(), 196);
+        let mut ids: Vec<NodeId> = sled.all_nodes().map(|n| n.id).collect();
+        ids.sort_unstable();
+        assert_eq!(ids, (0..200).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_reads_stay_visible_during_background_flush() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut store =
+            HybridStore::with_config(temp_dir.path(), vec![], batch_config(2)).unwrap();
+        let ids = store.batch_create_nodes(vec![(vec!["Person".to_string()], HashMap::new()); 300]);
+
+        // 读线程与后台刷盘并发：数据从缓冲区迁移到 Sled 的过程中始终可见
+        thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| {
+                    for _ in 0..20 {
+                        for &id in &ids {
+                            assert!(store.get_node(id).is_some(), "node {} vanished during flush", id);
+                        }
+                    }
+                });
+            }
+        });
+
+        wait_for_background_flush(&store);
+        assert_eq!(store.sled_store.lock().unwrap().node_count(), 300);
+    }
+
+    #[test]
+    fn test_drop_stops_background_flush_before_reopen() {
+        let temp_dir = TempDir::new().unwrap();
+        for round in 0..3 {
+            let mut store =
+                HybridStore::with_config(temp_dir.path(), vec![], batch_config(1000)).unwrap();
+            assert_eq!(store.node_count(), round);
+            store.create_node(vec!["Person".to_string()], HashMap::new());
+            // Drop 会等待后台线程退出并释放 Sled，随后可以立即重新打开
+        }
+    }
+
+    #[test]
+    fn test_lru_read_refreshes_recency() {
+        let mut cache: LruCache<u64, u64> = LruCache::new(3, 1024);
+        cache.put(1, 10, 8);
+        cache.put(2, 20, 8);
+        cache.put(3, 30, 8);
+
+        // 共享引用读取也会刷新访问顺序
+        let shared = &cache;
+        assert_eq!(shared.get(&1), Some(10));
+
+        cache.put(4, 40, 8);
+        assert!(cache.contains(&1));
+        assert!(!cache.contains(&2), "least recently used key should be evicted");
+        assert!(cache.contains(&3));
+        assert!(cache.contains(&4));
+        assert_eq!(cache.access_order.len(), cache.len());
+    }
+
+    #[test]
+    fn test_concurrent_readers_keep_hot_keys_cached() {
+        use std::sync::atomic::{AtomicBool, AtomicUsize};
+
+        const READERS: usize = 4;
+        const HOT: NodeId = 10;
+
+        let cache = CacheLayer::new(CacheConfig {
+            max_nodes: 50,
+            ..CacheConfig::default()
+        });
+        let node = |id: NodeId| StoredNode {
+            id,
+            labels: vec!["Person".to_string()],
+            props: HashMap::new(),
+        };
+        for id in 0..HOT {
+            cache.put_node(id, node(id));
+        }
+
+        let done = AtomicBool::new(false);
+        let rounds: Vec<AtomicUsize> = (0..READERS).map(|_| AtomicUsize::new(0)).collect();
+
+        thread::scope(|scope| {
+            for counter in &rounds {
+                let cache = &cache;
+                let done = &done;
+                scope.spawn(move || {
+                    while !done.load(Ordering::Acquire) {
+                        for id in 0..HOT {
+                            let hit = cache.get_node(id).expect("hot key evicted");
+                            assert_eq!(hit.id, id);
+                        }
+                        counter.fetch_add(1, Ordering::Release);
+                    }
+                });
+            }
+
+            // 写入大量冷数据；每次写入前等所有读者都完成一轮读取
+            for id in 1000..1120 {
+                let seen: Vec<usize> = rounds.iter().map(|r| r.load(Ordering::Acquire)).collect();
+                while rounds
+                    .iter()
+                    .zip(&seen)
+                    .any(|(r, &before)| r.load(Ordering::Acquire) == before)
+                {
+                    thread::yield_now();
+                }
+                cache.put_node(id, node(id));
+            }
+            done.store(true, Ordering::Release);
+        });
+
+        let node_cache = cache.node_cache.read().unwrap();
+        assert_eq!(node_cache.len(), 50);
+        for id in 0..HOT {
+            assert!(node_cache.contains(&id), "hot key {} was evicted", id);
+        }
+        // 最早写入的冷数据已被淘汰
+        assert!(!node_cache.contains(&1000));
+        assert_eq!(node_cache.access_order.len(), node_cache.len());
+    }
 }
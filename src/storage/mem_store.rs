@@ -1,6 +1,11 @@
-use super::{NodeId, RelId, StoredNode, StoredRel, StorageEngine, StorageError, TxHandle};
+use super::{
+    IdStrategy, MemoryReport, NodeFilter, NodeId, RelId, StoredNode, StoredRel, StorageEngine,
+    StorageError, TxHandle,
+};
+use crate::transactions::TransactionOp;
 use crate::values::{Value, Properties};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::ops::Bound;
 
 /// 事务操作记录（公开用于测试）
 #[derive(Debug, Clone)]
@@ -22,11 +27,13 @@ struct Transaction {
 }
 
 pub struct MemStore {
+    id_strategy: IdStrategy,
     next_node_id: NodeId,
     next_rel_id: RelId,
     next_tx_id: u64,
     nodes: HashMap<NodeId, StoredNode>,
-    rels: HashMap<RelId, StoredRel>,
+    /// 按 ID 有序存放，分页扫描可以直接从游标之后开始
+    rels: BTreeMap<RelId, StoredRel>,
     outgoing: HashMap<NodeId, Vec<RelId>>,
     incoming: HashMap<NodeId, Vec<RelId>>,
     transactions: HashMap<u64, Transaction>,
@@ -34,27 +41,102 @@ pub struct MemStore {
 
 impl MemStore {
     pub fn new() -> Self {
+        Self::with_id_strategy(IdStrategy::default())
+    }
+
+    /// 使用指定的 ID 分配策略创建存储
+    pub fn with_id_strategy(id_strategy: IdStrategy) -> Self {
         Self {
+            id_strategy,
             next_node_id: 0,
             next_rel_id: 0,
             next_tx_id: 0,
             nodes: HashMap::new(),
-            rels: HashMap::new(),
+            rels: BTreeMap::new(),
             outgoing: HashMap::new(),
             incoming: HashMap::new(),
             transactions: HashMap::new(),
         }
     }
+
+    pub fn id_strategy(&self) -> IdStrategy {
+        self.id_strategy
+    }
+
+    /// 近似内存占用，估算方式与 `HybridStore` 的缓存预算一致
+    ///
+    /// 属性索引由 `GraphDatabase` 持有，这里 `indexes` 为 0；
+    /// 需要包含索引时使用 `GraphDatabase::memory_estimate`。
+    pub fn memory_estimate(&self) -> MemoryReport {
+        // 按已分配容量计算，删除后未释放的空间也计入
+        let adjacency_size = |lists: &HashMap<NodeId, Vec<RelId>>| -> usize {
+            lists.capacity() * (std::mem::size_of::<NodeId>() + std::mem::size_of::<Vec<RelId>>())
+                + lists
+                    .values()
+                    .map(|ids| ids.capacity() * std::mem::size_of::<RelId>())
+                    .sum::<usize>()
+        };
+
+        MemoryReport {
+            nodes: self.nodes.values().map(StoredNode::estimated_size).sum(),
+            relationships: self.rels.values().map(StoredRel::estimated_size).sum(),
+            adjacency: adjacency_size(&self.outgoing) + adjacency_size(&self.incoming),
+            indexes: 0,
+        }
+    }
+
+    fn alloc_node_id(&mut self) -> NodeId {
+        match self.id_strategy {
+            IdStrategy::Sequential => {
+                // 跳过按指定 ID 写入（合并、导入）占用的位置，计数器本身不受其影响
+                while self.nodes.contains_key(&self.next_node_id) {
+                    self.next_node_id += 1;
+                }
+                let id = self.next_node_id;
+                self.next_node_id += 1;
+                id
+            }
+            IdStrategy::Random => loop {
+                let id: NodeId = rand::random();
+                if !self.nodes.contains_key(&id) {
+                    return id;
+                }
+            },
+        }
+    }
+
+    fn alloc_rel_id(&mut self) -> RelId {
+        match self.id_strategy {
+            IdStrategy::Sequential => {
+                while self.rels.contains_key(&self.next_rel_id) {
+                    self.next_rel_id += 1;
+                }
+                let id = self.next_rel_id;
+                self.next_rel_id += 1;
+                id
+            }
+            IdStrategy::Random => loop {
+                let id: RelId = rand::random();
+                if !self.rels.contains_key(&id) {
+                    return id;
+                }
+            },
+        }
+    }
 }
 
 impl StorageEngine for MemStore {
+    fn set_id_strategy(&mut self, strategy: IdStrategy) -> Result<(), StorageError> {
+        self.id_strategy = strategy;
+        Ok(())
+    }
+
     fn create_node(
         &mut self,
         labels: Vec<String>,
         props: HashMap<String, Value>,
     ) -> NodeId {
-        let id = self.next_node_id;
-        self.next_node_id += 1;
+        let id = self.alloc_node_id();
 
         let node = StoredNode { id, labels, props };
         self.nodes.insert(id, node);
@@ -68,8 +150,7 @@ impl StorageEngine for MemStore {
         typ: String,
         props: HashMap<String, Value>,
     ) -> RelId {
-        let id = self.next_rel_id;
-        self.next_rel_id += 1;
+        let id = self.alloc_rel_id();
 
         let rel = StoredRel {
             id,
@@ -77,6 +158,7 @@ impl StorageEngine for MemStore {
             end,
             typ,
             props,
+            directed: true,
         };
         self.rels.insert(id, rel);
 
@@ -86,6 +168,67 @@ impl StorageEngine for MemStore {
         id
     }
 
+    fn create_undirected_rel(
+        &mut self,
+        start: NodeId,
+        end: NodeId,
+        typ: String,
+        props: HashMap<String, Value>,
+    ) -> Result<RelId, StorageError> {
+        let id = self.alloc_rel_id();
+
+        let rel = StoredRel {
+            id,
+            start,
+            end,
+            typ,
+            props,
+            directed: false,
+        };
+        self.rels.insert(id, rel);
+
+        self.outgoing.entry(start).or_default().push(id);
+        self.incoming.entry(end).or_default().push(id);
+        if start != end {
+            self.outgoing.entry(end).or_default().push(id);
+            self.incoming.entry(start).or_default().push(id);
+        }
+
+        Ok(id)
+    }
+
+    fn insert_node_with_id(
+        &mut self,
+        id: NodeId,
+        labels: Vec<String>,
+        props: HashMap<String, Value>,
+    ) -> Result<(), StorageError> {
+        if self.nodes.contains_key(&id) {
+            return Err(StorageError::IdConflict(id));
+        }
+        self.nodes.insert(id, StoredNode { id, labels, props });
+        Ok(())
+    }
+
+    fn insert_rel_with_id(&mut self, rel: StoredRel) -> Result<(), StorageError> {
+        let id = rel.id;
+        if self.rels.contains_key(&id) {
+            return Err(StorageError::IdConflict(id));
+        }
+        if !self.nodes.contains_key(&rel.start) || !self.nodes.contains_key(&rel.end) {
+            return Err(StorageError::Other(format!("relationship {} has a missing endpoint", id)));
+        }
+        let (start, end, directed) = (rel.start, rel.end, rel.directed);
+        self.rels.insert(id, rel);
+        self.outgoing.entry(start).or_default().push(id);
+        self.incoming.entry(end).or_default().push(id);
+        if !directed && start != end {
+            self.outgoing.entry(end).or_default().push(id);
+            self.incoming.entry(start).or_default().push(id);
+        }
+        Ok(())
+    }
+
     fn get_node(&self, id: NodeId) -> Option<StoredNode> {
         self.nodes.get(&id).cloned()
     }
@@ -94,15 +237,37 @@ impl StorageEngine for MemStore {
         self.rels.get(&id).cloned()
     }
 
+    fn get_nodes(&self, ids: &[NodeId]) -> Vec<Option<StoredNode>> {
+        ids.iter().map(|id| self.nodes.get(id).cloned()).collect()
+    }
+
+    fn get_rels(&self, ids: &[RelId]) -> Vec<Option<StoredRel>> {
+        ids.iter().map(|id| self.rels.get(id).cloned()).collect()
+    }
+
     fn all_nodes(&self) -> Box<dyn Iterator<Item = StoredNode> + '_> {
         Box::new(self.nodes.values().cloned())
     }
 
+    fn scan_nodes(&self, filter: NodeFilter) -> Box<dyn Iterator<Item = StoredNode> + '_> {
+        // 先过滤再克隆，避免复制不匹配的节点
+        Box::new(self.nodes.values().filter(move |n| filter.matches(n)).cloned())
+    }
+
+    fn scan_rels(&self, after: Option<RelId>, limit: usize) -> Vec<StoredRel> {
+        let range = match after {
+            Some(a) => self.rels.range((Bound::Excluded(a), Bound::Unbounded)),
+            None => self.rels.range(..),
+        };
+        range.take(limit).map(|(_, r)| r.clone()).collect()
+    }
+
     fn outgoing_rels(&self, node: NodeId) -> Box<dyn Iterator<Item = StoredRel> + '_> {
         if let Some(rel_ids) = self.outgoing.get(&node) {
             let it = rel_ids
                 .iter()
-                .filter_map(move |rid| self.rels.get(rid).cloned());
+                .filter_map(move |rid| self.rels.get(rid).cloned())
+                .map(move |r| r.oriented_from(node));
             Box::new(it)
         } else {
             Box::new(std::iter::empty())
@@ -113,13 +278,22 @@ impl StorageEngine for MemStore {
         if let Some(rel_ids) = self.incoming.get(&node) {
             let it = rel_ids
                 .iter()
-                .filter_map(move |rid| self.rels.get(rid).cloned());
+                .filter_map(move |rid| self.rels.get(rid).cloned())
+                .map(move |r| r.oriented_to(node));
             Box::new(it)
         } else {
             Box::new(std::iter::empty())
         }
     }
 
+    fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    fn rel_count(&self) -> usize {
+        self.rels.len()
+    }
+
     fn delete_node(&mut self, id: NodeId) -> bool {
         // 删除节点前先删除所有相关的关系
         let mut rels_to_delete = Vec::new();
@@ -132,7 +306,9 @@ impl StorageEngine for MemStore {
             rels_to_delete.extend(in_rels.iter().copied());
         }
 
-        // 删除所有相关关系
+        // 删除所有相关关系（无向关系会同时出现在出边和入边中）
+        rels_to_delete.sort_unstable();
+        rels_to_delete.dedup();
         for rel_id in rels_to_delete {
             self.delete_rel(rel_id);
         }
@@ -154,56 +330,71 @@ impl StorageEngine for MemStore {
             if let Some(in_list) = self.incoming.get_mut(&rel.end) {
                 in_list.retain(|&r| r != id);
             }
+            if !rel.directed {
+                if let Some(out_list) = self.outgoing.get_mut(&rel.end) {
+                    out_list.retain(|&r| r != id);
+                }
+                if let Some(in_list) = self.incoming.get_mut(&rel.start) {
+                    in_list.retain(|&r| r != id);
+                }
+            }
             true
         } else {
             false
         }
     }
 
+    fn compact(&mut self) -> Result<usize, StorageError> {
+        let before = self.memory_estimate().total();
+
+        let nodes = &self.nodes;
+        for lists in [&mut self.outgoing, &mut self.incoming] {
+            lists.retain(|id, ids| !ids.is_empty() && nodes.contains_key(id));
+            for ids in lists.values_mut() {
+                ids.shrink_to_fit();
+            }
+            lists.shrink_to_fit();
+        }
+        self.nodes.shrink_to_fit();
+
+        Ok(before.saturating_sub(self.memory_estimate().total()))
+    }
+
     fn batch_create_nodes(
         &mut self,
         nodes: Vec<(Vec<String>, HashMap<String, Value>)>,
     ) -> Vec<NodeId> {
-        // 预分配 ID
-        let start_id = self.next_node_id;
-        let count = nodes.len() as NodeId;
-        self.next_node_id += count;
-
-        // 批量创建节点
-        for (i, (labels, props)) in nodes.into_iter().enumerate() {
-            let id = start_id + i as NodeId;
-            let node = StoredNode { id, labels, props };
-            self.nodes.insert(id, node);
-        }
-
-        (start_id..start_id + count).collect()
+        nodes
+            .into_iter()
+            .map(|(labels, props)| {
+                let id = self.alloc_node_id();
+                self.nodes.insert(id, StoredNode { id, labels, props });
+                id
+            })
+            .collect()
     }
 
     fn batch_create_rels(
         &mut self,
         rels: Vec<(NodeId, NodeId, String, HashMap<String, Value>)>,
     ) -> Vec<RelId> {
-        // 预分配 ID
-        let start_id = self.next_rel_id;
-        let count = rels.len() as RelId;
-        self.next_rel_id += count;
-
-        // 批量创建关系
-        for (i, (start, end, typ, props)) in rels.into_iter().enumerate() {
-            let id = start_id + i as RelId;
-            let rel = StoredRel {
-                id,
-                start,
-                end,
-                typ,
-                props,
-            };
-            self.rels.insert(id, rel);
-            self.outgoing.entry(start).or_default().push(id);
-            self.incoming.entry(end).or_default().push(id);
-        }
-
-        (start_id..start_id + count).collect()
+        rels.into_iter()
+            .map(|(start, end, typ, props)| {
+                let id = self.alloc_rel_id();
+                let rel = StoredRel {
+                    id,
+                    start,
+                    end,
+                    typ,
+                    props,
+                    directed: true,
+                };
+                self.rels.insert(id, rel);
+                self.outgoing.entry(start).or_default().push(id);
+                self.incoming.entry(end).or_default().push(id);
+                id
+            })
+            .collect()
     }
 
     // ========== 事务支持 ==========
@@ -231,6 +422,25 @@ impl StorageEngine for MemStore {
             return Err(StorageError::Other(format!("Transaction {} already committed", tx_id)));
         }
 
+        // 先校验再应用，保证提交要么全部生效要么全部不生效：
+        // 其他事务可能已经提交删除了新关系的端点
+        for op in &tx.ops {
+            if let TxOp::CreateRel(id, start, end, ..) = op {
+                for endpoint in [start, end] {
+                    let created_here = tx
+                        .ops
+                        .iter()
+                        .any(|o| matches!(o, TxOp::CreateNode(n, ..) if n == endpoint));
+                    if !created_here && !self.nodes.contains_key(endpoint) {
+                        return Err(StorageError::Other(format!(
+                            "Transaction {} cannot create relationship {}: node {} was deleted",
+                            tx_id, id, endpoint
+                        )));
+                    }
+                }
+            }
+        }
+
         // 应用所有操作
         for op in &tx.ops {
             match op {
@@ -252,6 +462,7 @@ impl StorageEngine for MemStore {
                             end: *end,
                             typ: typ.clone(),
                             props: props.clone(),
+                            directed: true,
                         };
                         self.rels.insert(*id, rel);
                         self.outgoing.entry(*start).or_default().push(*id);
@@ -297,6 +508,117 @@ impl StorageEngine for MemStore {
         Ok(())
     }
 
+    fn tx_create_node(
+        &mut self,
+        tx: TxHandle,
+        labels: Vec<String>,
+        props: HashMap<String, Value>,
+    ) -> Result<NodeId, StorageError> {
+        if !self.transactions.contains_key(&tx.0) {
+            return Err(StorageError::Other(format!("Transaction {} not found", tx.0)));
+        }
+        let id = self.alloc_node_id();
+        self.record_tx_op(tx, TxOp::CreateNode(id, labels, props))?;
+        Ok(id)
+    }
+
+    fn tx_update_node_props(
+        &mut self,
+        tx: TxHandle,
+        id: NodeId,
+        props: HashMap<String, Value>,
+    ) -> Result<(), StorageError> {
+        self.record_tx_op(tx, TxOp::UpdateNode(id, props))
+    }
+
+    fn tx_create_rel(
+        &mut self,
+        tx: TxHandle,
+        start: NodeId,
+        end: NodeId,
+        typ: String,
+        props: HashMap<String, Value>,
+    ) -> Result<RelId, StorageError> {
+        for endpoint in [start, end] {
+            if self.tx_get_node(tx, endpoint)?.is_none() {
+                return Err(StorageError::Other(format!("Node {} not found", endpoint)));
+            }
+        }
+        let id = self.alloc_rel_id();
+        self.record_tx_op(tx, TxOp::CreateRel(id, start, end, typ, props))?;
+        Ok(id)
+    }
+
+    fn tx_delete_node(&mut self, tx: TxHandle, id: NodeId) -> Result<bool, StorageError> {
+        match self.tx_get_node(tx, id)? {
+            Some(node) => self.record_tx_op(tx, TxOp::DeleteNode(id, node)).map(|_| true),
+            None => Ok(false),
+        }
+    }
+
+    fn tx_delete_rel(&mut self, tx: TxHandle, id: RelId) -> Result<bool, StorageError> {
+        match self.tx_get_rel(tx, id)? {
+            Some(rel) => self.record_tx_op(tx, TxOp::DeleteRel(id, rel)).map(|_| true),
+            None => Ok(false),
+        }
+    }
+
+    fn tx_get_node(&self, tx: TxHandle, id: NodeId) -> Result<Option<StoredNode>, StorageError> {
+        // 其他事务的未提交写入不可见（读已提交）
+        let mut node = self.nodes.get(&id).cloned();
+        for op in &self.transaction(tx)?.ops {
+            match op {
+                TxOp::CreateNode(nid, labels, props) if *nid == id => {
+                    node = Some(StoredNode { id, labels: labels.clone(), props: props.clone() });
+                }
+                TxOp::UpdateNode(nid, props) if *nid == id => {
+                    if let Some(node) = node.as_mut() {
+                        node.props.extend(props.clone());
+                    }
+                }
+                TxOp::DeleteNode(nid, _) if *nid == id => node = None,
+                _ => {}
+            }
+        }
+        Ok(node)
+    }
+
+    fn tx_get_rel(&self, tx: TxHandle, id: RelId) -> Result<Option<StoredRel>, StorageError> {
+        let mut rel = self.rels.get(&id).cloned();
+        for op in &self.transaction(tx)?.ops {
+            match op {
+                TxOp::CreateRel(rid, start, end, typ, props) if *rid == id => {
+                    rel = Some(StoredRel {
+                        id,
+                        start: *start,
+                        end: *end,
+                        typ: typ.clone(),
+                        props: props.clone(),
+                        directed: true,
+                    });
+                }
+                TxOp::UpdateRel(rid, props) if *rid == id => {
+                    if let Some(rel) = rel.as_mut() {
+                        rel.props.extend(props.clone());
+                    }
+                }
+                TxOp::DeleteRel(rid, _) if *rid == id => rel = None,
+                // 删除节点会级联删除关联关系
+                TxOp::DeleteNode(nid, _)
+                    if rel.as_ref().is_some_and(|r| r.start == *nid || r.end == *nid) =>
+                {
+                    rel = None;
+                }
+                _ => {}
+            }
+        }
+        Ok(rel)
+    }
+
+    fn tx_ops(&self, tx: TxHandle) -> Result<Vec<TransactionOp>, StorageError> {
+        self.transaction(tx).map(|t| t.ops.iter().cloned().map(TransactionOp::from).collect())
+    }
+
     fn update_node_props(&mut self, id: NodeId, props: HashMap<String, Value>) -> bool {
         self.do_update_node_props(id, props)
     }
@@ -337,6 +659,12 @@ impl MemStore {
 
     // ========== 事务操作辅助方法 ==========
 
+    fn transaction(&self, tx: TxHandle) -> Result<&Transaction, StorageError> {
+        self.transactions
+            .get(&tx.0)
+            .ok_or_else(|| StorageError::Other(format!("Transaction {} not found", tx.0)))
+    }
+
     /// 记录事务操作（用于测试）
     pub fn record_tx_op(&mut self, tx_handle: TxHandle, op: TxOp) -> Result<(), StorageError> {
         let TxHandle(tx_id) = tx_handle;
@@ -8,31 +8,244 @@ pub use async_store::AsyncStorage;
 pub use buffered_sled_store::{BufferedSledStore, BufferConfig, BufferStats};
 pub use hybrid_store::{HybridStore, HybridConfig, CacheConfig, FlushStrategy, HybridStats, CacheStats};
 
+use crate::transactions::TransactionOp;
 use crate::values::Value;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
+/// 节点/关系 ID
+///
+/// 默认为 64 位；启用 `wide-ids` 特性后为 128 位，配合 [`IdStrategy::Random`]
+/// 可以让独立构建的图直接合并而几乎不会发生 ID 冲突。
+#[cfg(not(feature = "wide-ids"))]
 pub type NodeId = u64;
+#[cfg(not(feature = "wide-ids"))]
 pub type RelId = u64;
+#[cfg(feature = "wide-ids")]
+pub type NodeId = u128;
+#[cfg(feature = "wide-ids")]
+pub type RelId = u128;
 
-#[derive(Debug, Clone, PartialEq)]
+/// 近似内存占用报告（字节）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize)]
+pub struct MemoryReport {
+    /// 节点记录（ID、标签、属性）
+    pub nodes: usize,
+    /// 关系记录（ID、端点、类型、属性）
+    pub relationships: usize,
+    /// 出边/入边邻接表
+    pub adjacency: usize,
+    /// 属性索引（存储引擎本身不含索引时为 0）
+    pub indexes: usize,
+}
+
+impl MemoryReport {
+    pub fn total(&self) -> usize {
+        self.nodes + self.relationships + self.adjacency + self.indexes
+    }
+}
+
+/// ID 分配策略
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum IdStrategy {
+    /// 从 0 开始递增（默认）；会暴露插入顺序，不同数据库之间的 ID 会重叠
+    #[default]
+    Sequential,
+    /// 随机 ID（`wide-ids` 下为 128 位）；不暴露插入顺序，可跨数据库合并
+    ///
+    /// 目前只有 `MemStore` 支持，其他存储引擎的 `set_id_strategy` 会拒绝该策略
+    Random,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct StoredNode {
     pub id: NodeId,
     pub labels: Vec<String>,
     pub props: HashMap<String, Value>,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct StoredRel {
     pub id: RelId,
     pub start: NodeId,
     pub end: NodeId,
     pub typ: String,
     pub props: HashMap<String, Value>,
+    /// 是否为有向关系；无向关系可以从任一端点沿出边或入边到达另一端
+    pub directed: bool,
+}
+
+/// 属性值的近似字节数（列表按每个元素 8 字节估算）
+fn estimate_value_size(value: &Value) -> usize {
+    match value {
+        Value::Int(_) => 8,
+        Value::Bool(_) => 1,
+        Value::Text(s) => s.len(),
+        Value::Float(_) => 8,
+        Value::Null => 0,
+        Value::List(v) => v.len() * 8,
+        Value::DateTime(_) => 8,
+        Value::Bytes(b) => b.len(),
+    }
+}
+
+fn estimate_props_size(props: &HashMap<String, Value>) -> usize {
+    props
+        .iter()
+        .map(|(key, value)| key.len() + estimate_value_size(value))
+        .sum()
+}
+
+impl StoredNode {
+    /// 近似内存占用（字节），用于缓存预算和内存报告
+    pub fn estimated_size(&self) -> usize {
+        std::mem::size_of::<NodeId>()
+            + std::mem::size_of::<Vec<String>>()
+            + std::mem::size_of::<HashMap<String, Value>>()
+            + self.labels.iter().map(|l| l.len()).sum::<usize>()
+            + estimate_props_size(&self.props)
+    }
+}
+
+impl StoredRel {
+    /// 近似内存占用（字节），用于缓存预算和内存报告
+    pub fn estimated_size(&self) -> usize {
+        std::mem::size_of::<RelId>()
+            + std::mem::size_of::<NodeId>()
+            + std::mem::size_of::<NodeId>()
+            + std::mem::size_of::<String>()
+            + std::mem::size_of::<HashMap<String, Value>>()
+            + self.typ.len()
+            + estimate_props_size(&self.props)
+    }
+
+    /// 无向关系以 `node` 为起点重新定向；有向关系保持不变
+    pub fn oriented_from(mut self, node: NodeId) -> Self {
+        if !self.directed && self.start != node {
+            std::mem::swap(&mut self.start, &mut self.end);
+        }
+        self
+    }
+
+    /// 无向关系以 `node` 为终点重新定向；有向关系保持不变
+    pub fn oriented_to(mut self, node: NodeId) -> Self {
+        if !self.directed && self.end != node {
+            std::mem::swap(&mut self.start, &mut self.end);
+        }
+        self
+    }
+}
+
+/// 属性谓词（用于扫描时的过滤下推）
+#[derive(Debug, Clone, PartialEq)]
+pub enum PropPredicate {
+    /// 属性存在
+    Exists,
+    Eq(Value),
+    Ne(Value),
+    Gt(Value),
+    Gte(Value),
+    Lt(Value),
+    Lte(Value),
+}
+
+impl PropPredicate {
+    /// 判断属性值是否满足谓词（属性不存在时任何谓词都不满足）
+    pub fn matches(&self, value: Option<&Value>) -> bool {
+        use std::cmp::Ordering;
+
+        let value = match value {
+            Some(v) => v,
+            None => return false,
+        };
+
+        match self {
+            PropPredicate::Exists => true,
+            PropPredicate::Eq(expected) => compare_values(value, expected) == Some(Ordering::Equal),
+            PropPredicate::Ne(expected) => compare_values(value, expected) != Some(Ordering::Equal),
+            PropPredicate::Gt(bound) => compare_values(value, bound) == Some(Ordering::Greater),
+            PropPredicate::Gte(bound) => matches!(
+                compare_values(value, bound),
+                Some(Ordering::Greater | Ordering::Equal)
+            ),
+            PropPredicate::Lt(bound) => compare_values(value, bound) == Some(Ordering::Less),
+            PropPredicate::Lte(bound) => matches!(
+                compare_values(value, bound),
+                Some(Ordering::Less | Ordering::Equal)
+            ),
+        }
+    }
+}
+
+/// 比较两个属性值（整型与浮点数按数值比较，不同类型不可比较）
+fn compare_values(a: &Value, b: &Value) -> Option<std::cmp::Ordering> {
+    match (a, b) {
+        (Value::Int(x), Value::Int(y)) => Some(x.cmp(y)),
+        (Value::Float(x), Value::Float(y)) => x.partial_cmp(y),
+        (Value::Int(x), Value::Float(y)) => (*x as f64).partial_cmp(y),
+        (Value::Float(x), Value::Int(y)) => x.partial_cmp(&(*y as f64)),
+        (Value::Text(x), Value::Text(y)) => Some(x.cmp(y)),
+        (Value::Bool(x), Value::Bool(y)) => Some(x.cmp(y)),
+        (Value::DateTime(x), Value::DateTime(y)) => Some(x.cmp(y)),
+        (Value::Bytes(x), Value::Bytes(y)) => Some(x.cmp(y)),
+        (Value::Null, Value::Null) => Some(std::cmp::Ordering::Equal),
+        (Value::List(x), Value::List(y)) if x == y => Some(std::cmp::Ordering::Equal),
+        _ => None,
+    }
+}
+
+/// 节点扫描过滤条件：可选的标签 + 若干属性谓词（AND 关系）
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NodeFilter {
+    pub label: Option<String>,
+    pub props: Vec<(String, PropPredicate)>,
+}
+
+impl NodeFilter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 只匹配带有指定标签的节点
+    pub fn with_label(mut self, label: &str) -> Self {
+        self.label = Some(label.to_string());
+        self
+    }
+
+    /// 追加一个属性谓词
+    pub fn with_prop(mut self, key: &str, predicate: PropPredicate) -> Self {
+        self.props.push((key.to_string(), predicate));
+        self
+    }
+
+    /// 是否只有标签条件
+    pub fn is_label_only(&self) -> bool {
+        self.props.is_empty()
+    }
+
+    /// 判断标签条件是否满足
+    pub fn matches_labels(&self, labels: &[String]) -> bool {
+        match &self.label {
+            Some(label) => labels.iter().any(|l| l == label),
+            None => true,
+        }
+    }
+
+    /// 判断节点是否满足全部条件
+    pub fn matches(&self, node: &StoredNode) -> bool {
+        self.matches_labels(&node.labels)
+            && self
+                .props
+                .iter()
+                .all(|(key, pred)| pred.matches(node.props.get(key)))
+    }
 }
 
 #[derive(Debug)]
 pub enum StorageError {
     TxNotSupported,
+    UndirectedNotSupported,
+    /// 以指定 ID 写入时该 ID 已被占用
+    IdConflict(NodeId),
     Other(String),
 }
 
@@ -54,19 +267,147 @@ pub trait StorageEngine: Send + Sync {
         props: HashMap<String, Value>,
     ) -> RelId;
 
+    /// 创建无向关系
+    ///
+    /// 关系只存储一次（保留传入的 start/end），但会同时登记到两个端点的出边和入边列表中
+    fn create_undirected_rel(
+        &mut self,
+        _start: NodeId,
+        _end: NodeId,
+        _typ: String,
+        _props: HashMap<String, Value>,
+    ) -> Result<RelId, StorageError> {
+        Err(StorageError::UndirectedNotSupported)
+    }
+
+    /// 切换后续节点和关系的 ID 分配策略
+    ///
+    /// 默认实现只接受 `IdStrategy::Sequential`；不支持随机 ID 的存储引擎对
+    /// `IdStrategy::Random` 返回错误，而不是静默地继续顺序分配
+    fn set_id_strategy(&mut self, strategy: IdStrategy) -> Result<(), StorageError> {
+        match strategy {
+            IdStrategy::Sequential => Ok(()),
+            IdStrategy::Random => Err(StorageError::Other("random ids not supported".to_string())),
+        }
+    }
+
+    /// 以指定 ID 写入节点（例如合并另一个图时保留原 ID）
+    ///
+    /// ID 已存在时返回 `StorageError::IdConflict`；默认实现不支持指定 ID
+    fn insert_node_with_id(
+        &mut self,
+        _id: NodeId,
+        _labels: Vec<String>,
+        _props: HashMap<String, Value>,
+    ) -> Result<(), StorageError> {
+        Err(StorageError::Other("explicit ids not supported".to_string()))
+    }
+
+    /// 以指定 ID 写入关系（保留 `directed` 标记），端点必须已存在
+    ///
+    /// ID 已存在时返回 `StorageError::IdConflict`；默认实现不支持指定 ID
+    fn insert_rel_with_id(&mut self, _rel: StoredRel) -> Result<(), StorageError> {
+        Err(StorageError::Other("explicit ids not supported".to_string()))
+    }
+
     fn get_node(&self, id: NodeId) -> Option<StoredNode>;
     fn get_rel(&self, id: RelId) -> Option<StoredRel>;
 
+    /// 批量读取节点，结果与 `ids` 一一对应，不存在的节点为 None
+    ///
+    /// 默认实现逐个调用 `get_node`；存储引擎可以覆盖为批量读取
+    fn get_nodes(&self, ids: &[NodeId]) -> Vec<Option<StoredNode>> {
+        ids.iter().map(|&id| self.get_node(id)).collect()
+    }
+
+    /// 批量读取关系，结果与 `ids` 一一对应，不存在的关系为 None
+    ///
+    /// 默认实现逐个调用 `get_rel`；存储引擎可以覆盖为批量读取
+    fn get_rels(&self, ids: &[RelId]) -> Vec<Option<StoredRel>> {
+        ids.iter().map(|&id| self.get_rel(id)).collect()
+    }
+
     fn all_nodes(&self) -> Box<dyn Iterator<Item = StoredNode> + '_>;
+
+    /// 按过滤条件扫描节点
+    ///
+    /// 默认实现在反序列化后过滤；存储引擎可以覆盖此方法，
+    /// 利用索引或轻量解码提前跳过不匹配的记录
+    fn scan_nodes(&self, filter: NodeFilter) -> Box<dyn Iterator<Item = StoredNode> + '_> {
+        Box::new(self.all_nodes().filter(move |n| filter.matches(n)))
+    }
+    /// 按 ID 升序扫描关系：返回 ID 大于 `after` 的至多 `limit` 条关系，用于分页
+    ///
+    /// 默认实现遍历所有节点的出边，只保留 ID 最小的 `limit` 条，不对全部关系排序；
+    /// 存储引擎可以覆盖为按 ID 有序的范围扫描
+    fn scan_rels(&self, after: Option<RelId>, limit: usize) -> Vec<StoredRel> {
+        let mut page: BTreeMap<RelId, StoredRel> = BTreeMap::new();
+        for node in self.all_nodes() {
+            // 无向关系出现在两个端点的出边列表中，只取编号较小的端点
+            for rel in self.outgoing_rels(node.id) {
+                if !(rel.directed || rel.start <= rel.end) || after.is_some_and(|a| rel.id <= a) {
+                    continue;
+                }
+                page.insert(rel.id, rel);
+                if page.len() > limit {
+                    page.pop_last();
+                }
+            }
+        }
+        page.into_values().collect()
+    }
+
+    /// 节点的出边；无向关系以该节点为起点返回
     fn outgoing_rels(&self, node: NodeId) -> Box<dyn Iterator<Item = StoredRel> + '_>;
+    /// 节点的入边；无向关系以该节点为终点返回
     fn incoming_rels(&self, node: NodeId) -> Box<dyn Iterator<Item = StoredRel> + '_>;
 
+    /// 节点数量
+    ///
+    /// 默认实现遍历所有节点；维护了计数器的存储引擎应覆盖为 O(1) 实现
+    fn node_count(&self) -> usize {
+        self.all_nodes().count()
+    }
+
+    /// 关系数量
+    ///
+    /// 默认实现遍历所有节点的出边；维护了计数器的存储引擎应覆盖为 O(1) 实现
+    fn rel_count(&self) -> usize {
+        // 无向关系出现在两个端点的出边列表中，只在编号较小的端点计数
+        self.all_nodes()
+            .map(|n| {
+                self.outgoing_rels(n.id)
+                    .filter(|r| r.directed || r.start <= r.end)
+                    .count()
+            })
+            .sum()
+    }
+
     /// 删除节点（会同时删除所有关联的关系）
     fn delete_node(&mut self, id: NodeId) -> bool;
 
     /// 删除关系
     fn delete_rel(&mut self, id: RelId) -> bool;
 
+    /// 压缩内部存储：清理删除后残留的空邻接表、释放多余容量
+    ///
+    /// 返回回收的近似字节数；默认实现不做任何事
+    fn compact(&mut self) -> Result<usize, StorageError> {
+        Ok(0)
+    }
+
+    /// 持久化一份序列化后的内存索引（如全文索引），覆盖同名的旧数据
+    ///
+    /// 默认实现不支持持久化，返回错误
+    fn put_index_data(&mut self, name: &str, _data: Vec<u8>) -> Result<(), StorageError> {
+        Err(StorageError::Other(format!("storage engine cannot persist index {}", name)))
+    }
+
+    /// 读取 [`put_index_data`](Self::put_index_data) 保存的索引数据
+    fn get_index_data(&self, _name: &str) -> Option<Vec<u8>> {
+        None
+    }
+
     fn begin_tx(&mut self) -> Result<TxHandle, StorageError> {
         Err(StorageError::TxNotSupported)
     }
@@ -79,6 +420,63 @@ pub trait StorageEngine: Send + Sync {
         Err(StorageError::TxNotSupported)
     }
 
+    /// 在事务中创建节点：立即分配 ID，提交后才写入存储
+    fn tx_create_node(
+        &mut self,
+        _tx: TxHandle,
+        _labels: Vec<String>,
+        _props: HashMap<String, Value>,
+    ) -> Result<NodeId, StorageError> {
+        Err(StorageError::TxNotSupported)
+    }
+
+    /// 在事务中合并更新节点属性，提交后才写入存储
+    fn tx_update_node_props(
+        &mut self,
+        _tx: TxHandle,
+        _id: NodeId,
+        _props: HashMap<String, Value>,
+    ) -> Result<(), StorageError> {
+        Err(StorageError::TxNotSupported)
+    }
+
+    /// 在事务中创建关系：两个端点在事务视角下必须存在，提交后才写入存储
+    fn tx_create_rel(
+        &mut self,
+        _tx: TxHandle,
+        _start: NodeId,
+        _end: NodeId,
+        _typ: String,
+        _props: HashMap<String, Value>,
+    ) -> Result<RelId, StorageError> {
+        Err(StorageError::TxNotSupported)
+    }
+
+    /// 在事务中删除节点（提交时级联删除关联关系），节点在事务视角下不存在时返回 `Ok(false)`
+    fn tx_delete_node(&mut self, _tx: TxHandle, _id: NodeId) -> Result<bool, StorageError> {
+        Err(StorageError::TxNotSupported)
+    }
+
+    /// 在事务中删除关系，关系在事务视角下不存在时返回 `Ok(false)`
+    fn tx_delete_rel(&mut self, _tx: TxHandle, _id: RelId) -> Result<bool, StorageError> {
+        Err(StorageError::TxNotSupported)
+    }
+
+    /// 在事务视角下读取节点：已提交的数据叠加本事务尚未提交的写入
+    fn tx_get_node(&self, _tx: TxHandle, _id: NodeId) -> Result<Option<StoredNode>, StorageError> {
+        Err(StorageError::TxNotSupported)
+    }
+
+    /// 在事务视角下读取关系：已提交的数据叠加本事务尚未提交的写入
+    fn tx_get_rel(&self, _tx: TxHandle, _id: RelId) -> Result<Option<StoredRel>, StorageError> {
+        Err(StorageError::TxNotSupported)
+    }
+
+    /// 事务中尚未提交的写操作（按记录顺序）
+    fn tx_ops(&self, _tx: TxHandle) -> Result<Vec<TransactionOp>, StorageError> {
+        Err(StorageError::TxNotSupported)
+    }
+
     /// 批量创建节点，返回创建的节点ID列表
     fn batch_create_nodes(&mut self, nodes: Vec<(Vec<String>, HashMap<String, Value>)>) -> Vec<NodeId> {
         // 默认实现：逐个创建
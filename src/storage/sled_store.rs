@@ -1,8 +1,8 @@
-use super::{NodeId, RelId, StoredNode, StoredRel, StorageEngine};
+use super::{NodeFilter, NodeId, PropPredicate, RelId, StoredNode, StoredRel, StorageEngine, StorageError};
 use crate::values::Value;
 use crate::index_persistent::PersistentPropertyIndex;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -12,6 +12,17 @@ struct SerializedNode {
     props: HashMap<String, Value>,
 }
 
+/// 节点记录的前缀（id + labels）
+///
+/// bincode 按字段顺序编码且允许尾部多余字节，因此可以只解码
+/// `SerializedNode` 的前两个字段，跳过属性的反序列化
+#[derive(Debug, Deserialize)]
+struct SerializedNodeHeader {
+    #[allow(dead_code)]
+    id: NodeId,
+    labels: Vec<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 struct SerializedRel {
     id: RelId,
@@ -27,11 +38,24 @@ pub struct SledStore {
     rels: sled::Tree,
     outgoing: sled::Tree,
     incoming: sled::Tree,
+    /// 无向关系的 ID 集合（方向标记单独存放，保持关系记录格式不变）
+    undirected: sled::Tree,
+    /// 按大端编码的关系 ID 集合，供分页扫描按数值顺序做范围查询
+    ///
+    /// 关系记录的键是 bincode 小端编码，字节顺序与数值顺序不一致
+    rel_order: sled::Tree,
     index: sled::Tree,
     property_index: PersistentPropertyIndex,
     indexed_properties: Vec<(String, String)>, // (label, property) pairs to index
+    /// 关系属性的持久化索引（键中的 label 位置存放关系类型）
+    rel_property_index: PersistentPropertyIndex,
+    rel_indexed_properties: Vec<(String, String)>, // (rel_type, property) pairs to index
     next_node_id: NodeId,
     next_rel_id: RelId,
+    /// 节点计数器（打开时统计一次，之后随增删维护）
+    node_count: usize,
+    /// 关系计数器
+    rel_count: usize,
 }
 
 impl SledStore {
@@ -40,12 +64,26 @@ impl SledStore {
     }
 
     pub fn with_config<P: AsRef<Path>>(path: P, indexed_properties: Vec<(String, String)>) -> Result<Self, sled::Error> {
+        Self::with_index_config(path, indexed_properties, Vec::new())
+    }
+
+    /// 同时配置节点索引 `(label, property)` 和关系索引 `(rel_type, property)`
+    ///
+    /// 两种索引都在打开时根据已有数据重建，因此可以对已有数据库新增索引配置。
+    pub fn with_index_config<P: AsRef<Path>>(
+        path: P,
+        indexed_properties: Vec<(String, String)>,
+        rel_indexed_properties: Vec<(String, String)>,
+    ) -> Result<Self, sled::Error> {
         let db = sled::open(path)?;
         let nodes = db.open_tree("nodes")?;
         let rels = db.open_tree("rels")?;
         let outgoing = db.open_tree("outgoing")?;
         let incoming = db.open_tree("incoming")?;
+        let undirected = db.open_tree("undirected_rels")?;
+        let rel_order = db.open_tree("rel_order")?;
         let index = db.open_tree("index")?;
+        let rel_index = db.open_tree("rel_index")?;
 
         // 读取最大 ID
         let next_node_id = nodes
@@ -66,7 +104,21 @@ impl SledStore {
             .map(|id| id + 1)
             .unwrap_or(0);
 
+        let node_count = nodes.len();
+        let rel_count = rels.len();
+
+        // 旧版本数据库没有 rel_order，条目数不一致时从关系表重建
+        if rel_order.len() != rel_count {
+            rel_order.clear()?;
+            for key in rels.iter().keys() {
+                if let Ok(id) = bincode::deserialize::<RelId>(&key?) {
+                    rel_order.insert(id.to_be_bytes(), &[])?;
+                }
+            }
+        }
+
         let property_index = PersistentPropertyIndex::new(index.clone());
+        let rel_property_index = PersistentPropertyIndex::new(rel_index);
 
         let mut store = Self {
             db,
@@ -74,11 +126,17 @@ impl SledStore {
             rels,
             outgoing,
             incoming,
+            undirected,
+            rel_order,
             index,
             property_index,
             indexed_properties,
+            rel_property_index,
+            rel_indexed_properties,
             next_node_id,
             next_rel_id,
+            node_count,
+            rel_count,
         };
 
         // 重建索引（从现有节点）
@@ -92,10 +150,23 @@ impl SledStore {
     }
 
     fn rebuild_index(&mut self) -> Result<(), sled::Error> {
+        let to_sled_err = |e: Box<dyn std::error::Error>| {
+            sled::Error::Io(std::io::Error::other(format!("{:?}", e)))
+        };
         let nodes: Vec<StoredNode> = self.all_nodes().collect();
         self.property_index
             .rebuild(&nodes, &self.indexed_properties)
-            .map_err(|e| sled::Error::Io(std::io::Error::new(std::io::ErrorKind::Other, format!("{:?}", e))))?;
+            .map_err(to_sled_err)?;
+
+        self.rel_property_index.clear().map_err(to_sled_err)?;
+        if !self.rel_indexed_properties.is_empty() {
+            for item in self.rels.iter() {
+                let (_, value) = item?;
+                if let Ok(rel) = bincode::deserialize::<SerializedRel>(&value) {
+                    self.index_rel(rel.id, &rel.typ, &rel.props);
+                }
+            }
+        }
         Ok(())
     }
 
@@ -111,36 +182,52 @@ impl SledStore {
         bincode::serialize(&node_id).unwrap()
     }
 
-    /// 查询持久化索引
-    pub fn query_index(
-        &self,
-        label: &str,
-        property: &str,
-        value: &Value,
-    ) -> Result<Vec<NodeId>, Box<dyn std::error::Error>> {
-        self.property_index.find(label, property, value)
+    /// 在节点的邻接表中追加一条关系
+    fn append_adj(&self, tree: &sled::Tree, node_id: NodeId, rel_id: RelId) {
+        let key = self.adj_key(node_id);
+        let mut list: Vec<RelId> = tree
+            .get(&key)
+            .unwrap()
+            .and_then(|v| bincode::deserialize(&v).ok())
+            .unwrap_or_default();
+        list.push(rel_id);
+        tree.insert(key, bincode::serialize(&list).unwrap()).unwrap();
     }
 
-    /// 获取索引条目数量
-    pub fn index_count(&self) -> usize {
-        self.property_index.count()
+    /// 从节点的邻接表中移除一条关系
+    fn remove_adj(&self, tree: &sled::Tree, node_id: NodeId, rel_id: RelId) {
+        let key = self.adj_key(node_id);
+        if let Ok(Some(data)) = tree.get(&key) {
+            let mut list: Vec<RelId> = bincode::deserialize(&data).unwrap_or_default();
+            list.retain(|&r| r != rel_id);
+            tree.insert(key, bincode::serialize(&list).unwrap()).unwrap();
+        }
     }
-}
 
-impl StorageEngine for SledStore {
-    fn create_node(
-        &mut self,
-        labels: Vec<String>,
-        props: HashMap<String, Value>,
-    ) -> NodeId {
-        let id = self.next_node_id;
-        self.next_node_id += 1;
+    /// 下一个待分配的 (节点 ID, 关系 ID)，打开时由已有记录的最大 ID 推出
+    pub(crate) fn next_ids(&self) -> (NodeId, RelId) {
+        (self.next_node_id, self.next_rel_id)
+    }
+
+    /// 以指定 ID 写入节点（已存在时覆盖），并推进 ID 分配器
+    ///
+    /// 供上层存储（如 HybridStore 刷盘、WAL 回放）保持与自身分配的 ID 一致；
+    /// 重复写入同一条记录是幂等的
+    pub(crate) fn put_node(&mut self, id: NodeId, labels: Vec<String>, props: HashMap<String, Value>) {
+        self.next_node_id = self.next_node_id.max(id + 1);
+
+        // 覆盖时先移除旧值的索引条目
+        if let Some(old) = self.get_node(id) {
+            self.unindex_node(&old);
+        }
 
         let node = SerializedNode { id, labels: labels.clone(), props: props.clone() };
         let key = self.node_key(id);
         let value = bincode::serialize(&node).unwrap();
 
-        self.nodes.insert(key, value).unwrap();
+        if self.nodes.insert(key, value).unwrap().is_none() {
+            self.node_count += 1;
+        }
 
         // 更新持久化索引
         for label in &labels {
@@ -152,19 +239,49 @@ impl StorageEngine for SledStore {
                 }
             }
         }
+    }
 
-        id
+    /// 以指定 ID 写入有向关系（已存在时只覆盖记录），并推进 ID 分配器
+    pub(crate) fn put_rel(
+        &mut self,
+        id: RelId,
+        start: NodeId,
+        end: NodeId,
+        typ: String,
+        props: HashMap<String, Value>,
+    ) {
+        self.next_rel_id = self.next_rel_id.max(id + 1);
+        self.write_rel(id, start, end, typ, props, true);
     }
 
-    fn create_rel(
+    /// 以指定 ID 写入无向关系（两个端点的出边和入边列表都登记该关系）
+    pub(crate) fn put_undirected_rel(
         &mut self,
+        id: RelId,
         start: NodeId,
         end: NodeId,
         typ: String,
         props: HashMap<String, Value>,
-    ) -> RelId {
-        let id = self.next_rel_id;
-        self.next_rel_id += 1;
+    ) {
+        self.next_rel_id = self.next_rel_id.max(id + 1);
+        self.write_rel(id, start, end, typ, props, false);
+    }
+
+    fn write_rel(
+        &mut self,
+        id: RelId,
+        start: NodeId,
+        end: NodeId,
+        typ: String,
+        props: HashMap<String, Value>,
+        directed: bool,
+    ) {
+
+        // 覆盖时先移除旧值的索引条目
+        if let Some(old) = self.get_rel(id) {
+            self.unindex_rel(&old);
+        }
+        self.index_rel(id, &typ, &props);
 
         let rel = SerializedRel {
             id,
@@ -176,36 +293,153 @@ impl StorageEngine for SledStore {
 
         let key = self.rel_key(id);
         let value = bincode::serialize(&rel).unwrap();
-        self.rels.insert(key, value).unwrap();
+        if directed {
+            self.undirected.remove(&key).unwrap();
+        } else {
+            self.undirected.insert(key.clone(), &[]).unwrap();
+        }
+        if self.rels.insert(key, value).unwrap().is_some() {
+            // 邻接表中已有该关系
+            return;
+        }
+        self.rel_order.insert(id.to_be_bytes(), &[]).unwrap();
+        self.rel_count += 1;
 
         // 更新邻接表
-        let out_key = self.adj_key(start);
-        let mut out_list: Vec<RelId> = self
-            .outgoing
-            .get(&out_key)
-            .unwrap()
-            .and_then(|v| bincode::deserialize(&v).ok())
-            .unwrap_or_default();
-        out_list.push(id);
-        self.outgoing
-            .insert(out_key, bincode::serialize(&out_list).unwrap())
-            .unwrap();
+        self.append_adj(&self.outgoing, start, id);
+        self.append_adj(&self.incoming, end, id);
+        if !directed && start != end {
+            self.append_adj(&self.outgoing, end, id);
+            self.append_adj(&self.incoming, start, id);
+        }
+    }
 
-        let in_key = self.adj_key(end);
-        let mut in_list: Vec<RelId> = self
-            .incoming
-            .get(&in_key)
-            .unwrap()
-            .and_then(|v| bincode::deserialize(&v).ok())
-            .unwrap_or_default();
-        in_list.push(id);
-        self.incoming
-            .insert(in_key, bincode::serialize(&in_list).unwrap())
-            .unwrap();
+    /// 从持久化索引中移除节点
+    fn unindex_node(&self, node: &StoredNode) {
+        for label in &node.labels {
+            for (indexed_label, indexed_prop) in &self.indexed_properties {
+                if label == indexed_label {
+                    if let Some(value) = node.props.get(indexed_prop) {
+                        let _ = self.property_index.remove(label, indexed_prop, value, node.id);
+                    }
+                }
+            }
+        }
+    }
+
+    /// 把关系写入持久化关系索引
+    fn index_rel(&self, id: RelId, typ: &str, props: &HashMap<String, Value>) {
+        for (indexed_type, indexed_prop) in &self.rel_indexed_properties {
+            if typ == indexed_type {
+                if let Some(value) = props.get(indexed_prop) {
+                    let _ = self.rel_property_index.add(typ, indexed_prop, value, id);
+                }
+            }
+        }
+    }
+
+    /// 从持久化关系索引中移除关系
+    fn unindex_rel(&self, rel: &StoredRel) {
+        for (indexed_type, indexed_prop) in &self.rel_indexed_properties {
+            if &rel.typ == indexed_type {
+                if let Some(value) = rel.props.get(indexed_prop) {
+                    let _ = self.rel_property_index.remove(&rel.typ, indexed_prop, value, rel.id);
+                }
+            }
+        }
+    }
 
+    /// 查询持久化关系索引，返回关系类型为 `rel_type` 且属性 `property` 等于 `value` 的关系 ID
+    ///
+    /// 只对配置过的 `(rel_type, property)` 有结果；Float/Null/List 值不进入索引。
+    pub fn query_rel_index(
+        &self,
+        rel_type: &str,
+        property: &str,
+        value: &Value,
+    ) -> Result<Vec<RelId>, Box<dyn std::error::Error>> {
+        self.rel_property_index.find(rel_type, property, value)
+    }
+
+    /// 查询持久化索引
+    pub fn query_index(
+        &self,
+        label: &str,
+        property: &str,
+        value: &Value,
+    ) -> Result<Vec<NodeId>, Box<dyn std::error::Error>> {
+        self.property_index.find(label, property, value)
+    }
+
+    /// 获取索引条目数量
+    pub fn index_count(&self) -> usize {
+        self.property_index.count()
+    }
+
+    /// 如果过滤条件包含已建持久化索引的等值谓词，返回索引命中的候选节点
+    fn indexed_candidates(&self, filter: &NodeFilter) -> Option<Vec<NodeId>> {
+        let label = filter.label.as_ref()?;
+        filter.props.iter().find_map(|(key, pred)| {
+            let value = match pred {
+                PropPredicate::Eq(v) => v,
+                _ => return None,
+            };
+            let indexed = self
+                .indexed_properties
+                .iter()
+                .any(|(l, p)| l == label && p == key);
+            if !indexed {
+                return None;
+            }
+            // Float/Null/List 不进入持久化索引，无法走索引
+            if !matches!(value, Value::Int(_) | Value::Bool(_) | Value::Text(_)) {
+                return None;
+            }
+            self.property_index.find(label, key, value).ok()
+        })
+    }
+}
+
+/// 批量读取的请求量达到总量的四分之一时，单次遍历比逐个点查更划算
+fn prefers_scan(requested: usize, total: usize) -> bool {
+    requested > 0 && requested.saturating_mul(4) >= total
+}
+
+impl StorageEngine for SledStore {
+    fn create_node(
+        &mut self,
+        labels: Vec<String>,
+        props: HashMap<String, Value>,
+    ) -> NodeId {
+        let id = self.next_node_id;
+        self.put_node(id, labels, props);
         id
     }
 
+    fn create_rel(
+        &mut self,
+        start: NodeId,
+        end: NodeId,
+        typ: String,
+        props: HashMap<String, Value>,
+    ) -> RelId {
+        let id = self.next_rel_id;
+        self.put_rel(id, start, end, typ, props);
+        id
+    }
+
+    fn create_undirected_rel(
+        &mut self,
+        start: NodeId,
+        end: NodeId,
+        typ: String,
+        props: HashMap<String, Value>,
+    ) -> Result<RelId, StorageError> {
+        let id = self.next_rel_id;
+        self.put_undirected_rel(id, start, end, typ, props);
+        Ok(id)
+    }
+
     fn get_node(&self, id: NodeId) -> Option<StoredNode> {
         let key = self.node_key(id);
         self.nodes
@@ -221,6 +455,7 @@ impl StorageEngine for SledStore {
 
     fn get_rel(&self, id: RelId) -> Option<StoredRel> {
         let key = self.rel_key(id);
+        let directed = !self.undirected.contains_key(&key).unwrap_or(false);
         self.rels
             .get(key)
             .ok()?
@@ -231,9 +466,54 @@ impl StorageEngine for SledStore {
                 end: r.end,
                 typ: r.typ,
                 props: r.props,
+                directed,
             })
     }
 
+    fn get_nodes(&self, ids: &[NodeId]) -> Vec<Option<StoredNode>> {
+        // 请求量较小时逐个点查；接近全表时单次遍历节点树，避免大量随机读取
+        if !prefers_scan(ids.len(), self.node_count) {
+            return ids.iter().map(|&id| self.get_node(id)).collect();
+        }
+        let wanted: HashSet<NodeId> = ids.iter().copied().collect();
+        let found: HashMap<NodeId, StoredNode> = self
+            .all_nodes()
+            .filter(|n| wanted.contains(&n.id))
+            .map(|n| (n.id, n))
+            .collect();
+        ids.iter().map(|id| found.get(id).cloned()).collect()
+    }
+
+    fn get_rels(&self, ids: &[RelId]) -> Vec<Option<StoredRel>> {
+        if !prefers_scan(ids.len(), self.rel_count) {
+            return ids.iter().map(|&id| self.get_rel(id)).collect();
+        }
+        let wanted: HashSet<RelId> = ids.iter().copied().collect();
+        let undirected: HashSet<sled::IVec> =
+            self.undirected.iter().keys().filter_map(|k| k.ok()).collect();
+        let found: HashMap<RelId, StoredRel> = self
+            .rels
+            .iter()
+            .filter_map(|r| r.ok())
+            .filter_map(|(k, v)| {
+                let r = bincode::deserialize::<SerializedRel>(&v).ok()?;
+                if !wanted.contains(&r.id) {
+                    return None;
+                }
+                let rel = StoredRel {
+                    id: r.id,
+                    start: r.start,
+                    end: r.end,
+                    typ: r.typ,
+                    props: r.props,
+                    directed: !undirected.contains(&k),
+                };
+                Some((rel.id, rel))
+            })
+            .collect();
+        ids.iter().map(|id| found.get(id).cloned()).collect()
+    }
+
     fn all_nodes(&self) -> Box<dyn Iterator<Item = StoredNode> + '_> {
         Box::new(
             self.nodes
@@ -248,6 +528,60 @@ impl StorageEngine for SledStore {
         )
     }
 
+    fn scan_rels(&self, after: Option<RelId>, limit: usize) -> Vec<StoredRel> {
+        // 在 rel_order 上从游标之后做范围扫描，只读取当前页
+        let start = match after {
+            None => 0,
+            Some(a) => match a.checked_add(1) {
+                Some(next) => next,
+                None => return Vec::new(),
+            },
+        };
+        self.rel_order
+            .range(start.to_be_bytes()..)
+            .keys()
+            .filter_map(|k| k.ok())
+            .filter_map(|k| <[u8; std::mem::size_of::<RelId>()]>::try_from(k.as_ref()).ok())
+            .filter_map(|bytes| self.get_rel(RelId::from_be_bytes(bytes)))
+            .take(limit)
+            .collect()
+    }
+
+    fn scan_nodes(&self, filter: NodeFilter) -> Box<dyn Iterator<Item = StoredNode> + '_> {
+        // 索引路径：只读取索引命中的候选节点
+        if let Some(ids) = self.indexed_candidates(&filter) {
+            return Box::new(
+                ids.into_iter()
+                    .filter_map(move |id| self.get_node(id))
+                    .filter(move |n| filter.matches(n)),
+            );
+        }
+
+        // 扫描路径：先只解码标签，标签不匹配的记录跳过属性反序列化
+        let label_filter = NodeFilter {
+            label: filter.label.clone(),
+            props: Vec::new(),
+        };
+        Box::new(
+            self.nodes
+                .iter()
+                .filter_map(|r| r.ok())
+                .filter(move |(_, v)| {
+                    label_filter.label.is_none()
+                        || bincode::deserialize::<SerializedNodeHeader>(v)
+                            .map(|h| label_filter.matches_labels(&h.labels))
+                            .unwrap_or(false)
+                })
+                .filter_map(|(_, v)| bincode::deserialize::<SerializedNode>(&v).ok())
+                .map(|n| StoredNode {
+                    id: n.id,
+                    labels: n.labels,
+                    props: n.props,
+                })
+                .filter(move |n| filter.matches(n)),
+        )
+    }
+
     fn outgoing_rels(&self, node: NodeId) -> Box<dyn Iterator<Item = StoredRel> + '_> {
         let key = self.adj_key(node);
         let rel_ids: Vec<RelId> = self
@@ -258,7 +592,12 @@ impl StorageEngine for SledStore {
             .and_then(|v| bincode::deserialize(&v).ok())
             .unwrap_or_default();
 
-        Box::new(rel_ids.into_iter().filter_map(move |rid| self.get_rel(rid)))
+        Box::new(
+            rel_ids
+                .into_iter()
+                .filter_map(move |rid| self.get_rel(rid))
+                .map(move |r| r.oriented_from(node)),
+        )
     }
 
     fn incoming_rels(&self, node: NodeId) -> Box<dyn Iterator<Item = StoredRel> + '_> {
@@ -271,7 +610,20 @@ impl StorageEngine for SledStore {
             .and_then(|v| bincode::deserialize(&v).ok())
             .unwrap_or_default();
 
-        Box::new(rel_ids.into_iter().filter_map(move |rid| self.get_rel(rid)))
+        Box::new(
+            rel_ids
+                .into_iter()
+                .filter_map(move |rid| self.get_rel(rid))
+                .map(move |r| r.oriented_to(node)),
+        )
+    }
+
+    fn node_count(&self) -> usize {
+        self.node_count
+    }
+
+    fn rel_count(&self) -> usize {
+        self.rel_count
     }
 
     fn delete_node(&mut self, id: NodeId) -> bool {
@@ -296,9 +648,12 @@ impl StorageEngine for SledStore {
             .and_then(|v| bincode::deserialize(&v).ok())
             .unwrap_or_default();
 
-        // 删除所有相关关系
-        for rel_id in out_rels.iter().chain(in_rels.iter()) {
-            self.delete_rel(*rel_id);
+        // 删除所有相关关系（无向关系会同时出现在出边和入边中）
+        let mut rel_ids: Vec<RelId> = out_rels.into_iter().chain(in_rels).collect();
+        rel_ids.sort_unstable();
+        rel_ids.dedup();
+        for rel_id in rel_ids {
+            self.delete_rel(rel_id);
         }
 
         // 清理邻接表
@@ -308,18 +663,13 @@ impl StorageEngine for SledStore {
         // 删除节点本身
         let key = self.node_key(id);
         let deleted = self.nodes.remove(key).unwrap().is_some();
+        if deleted {
+            self.node_count -= 1;
+        }
 
         // 从持久化索引中移除
         if let Some(node) = node {
-            for label in &node.labels {
-                for (indexed_label, indexed_prop) in &self.indexed_properties {
-                    if label == indexed_label {
-                        if let Some(value) = node.props.get(indexed_prop) {
-                            let _ = self.property_index.remove(label, indexed_prop, value, id);
-                        }
-                    }
-                }
-            }
+            self.unindex_node(&node);
         }
 
         deleted
@@ -347,14 +697,65 @@ impl StorageEngine for SledStore {
                     .unwrap();
             }
 
+            if !rel.directed {
+                self.remove_adj(&self.outgoing, rel.end, id);
+                self.remove_adj(&self.incoming, rel.start, id);
+                self.undirected.remove(&key).unwrap();
+            }
+            self.unindex_rel(&rel);
+
             // 删除关系本身
-            self.rels.remove(key).unwrap();
+            if self.rels.remove(key).unwrap().is_some() {
+                self.rel_order.remove(id.to_be_bytes()).unwrap();
+                self.rel_count -= 1;
+            }
             true
         } else {
             false
         }
     }
 
+    /// 删除空的邻接表记录并刷盘，返回删除记录的键值字节数；无法解码的记录被跳过
+    ///
+    /// sled 在后台回收段空间，这里刷盘后由它完成实际的磁盘压缩。
+    fn compact(&mut self) -> Result<usize, StorageError> {
+        let sled_err = |e: sled::Error| StorageError::Other(e.to_string());
+        let mut reclaimed = 0;
+        for tree in [&self.outgoing, &self.incoming] {
+            let mut empty = Vec::new();
+            for item in tree.iter() {
+                let (key, value) = item.map_err(sled_err)?;
+                // 无法解码的记录原样保留，不能当作空表删掉
+                let Ok(list) = bincode::deserialize::<Vec<RelId>>(&value) else {
+                    continue;
+                };
+                if list.is_empty() {
+                    reclaimed += key.len() + value.len();
+                    empty.push(key);
+                }
+            }
+            for key in empty {
+                tree.remove(key).map_err(sled_err)?;
+            }
+        }
+        self.db.flush().map_err(sled_err)?;
+        Ok(reclaimed)
+    }
+
+    /// 索引数据存放在独立的 `index_data` 树中，写入后立即刷盘
+    fn put_index_data(&mut self, name: &str, data: Vec<u8>) -> Result<(), StorageError> {
+        let sled_err = |e: sled::Error| StorageError::Other(e.to_string());
+        let tree = self.db.open_tree("index_data").map_err(sled_err)?;
+        tree.insert(name.as_bytes(), data).map_err(sled_err)?;
+        tree.flush().map_err(sled_err)?;
+        Ok(())
+    }
+
+    fn get_index_data(&self, name: &str) -> Option<Vec<u8>> {
+        let tree = self.db.open_tree("index_data").ok()?;
+        tree.get(name.as_bytes()).ok()?.map(|data| data.to_vec())
+    }
+
     fn batch_create_nodes(
         &mut self,
         nodes: Vec<(Vec<String>, HashMap<String, Value>)>,
@@ -380,6 +781,7 @@ impl StorageEngine for SledStore {
 
         // 一次性写入所有节点
         self.nodes.apply_batch(batch).unwrap();
+        self.node_count += count as usize;
 
         // 更新持久化索引
         for (id, labels, props) in nodes_with_ids {
@@ -411,6 +813,7 @@ impl StorageEngine for SledStore {
         let mut node_batch = sled::Batch::default();
         let mut outgoing_batch = sled::Batch::default();
         let mut incoming_batch = sled::Batch::default();
+        let mut order_batch = sled::Batch::default();
 
         // 读取现有的邻接表数据
         let mut outgoing_adj: HashMap<NodeId, Vec<RelId>> = HashMap::new();
@@ -447,6 +850,7 @@ impl StorageEngine for SledStore {
         // 第二遍：构建批量写入
         for (i, (start, end, typ, props)) in rels.into_iter().enumerate() {
             let id = start_id + i as RelId;
+            self.index_rel(id, &typ, &props);
 
             // 序列化关系
             let rel = SerializedRel {
@@ -459,6 +863,7 @@ impl StorageEngine for SledStore {
             let rel_key = self.rel_key(id);
             let rel_value = bincode::serialize(&rel).unwrap();
             node_batch.insert(rel_key, rel_value);
+            order_batch.insert(&id.to_be_bytes()[..], &[]);
 
             // 更新出边邻接表
             outgoing_adj.entry(start).or_default().push(id);
@@ -482,8 +887,10 @@ impl StorageEngine for SledStore {
 
         // 一次性写入所有数据
         self.rels.apply_batch(node_batch).unwrap();
+        self.rel_order.apply_batch(order_batch).unwrap();
         self.outgoing.apply_batch(outgoing_batch).unwrap();
         self.incoming.apply_batch(incoming_batch).unwrap();
+        self.rel_count += count as usize;
 
         // 返回分配的 ID 列表
         (start_id..start_id + count).collect()
@@ -38,13 +38,36 @@ impl DeadlockInfo {
     }
 
     /// 获取受害者事务（建议回滚的事务）
+    ///
+    /// 选择ID最大的事务（最年轻的事务）；其他策略见 [`DeadlockDetector::with_victim_strategy`]
     pub fn select_victim(&self) -> u64 {
-        // 策略1: 选择ID最大的事务（最年轻的事务）
-        // 策略2: 可以根据事务优先级、已执行时间等来选择
         *self.involved_transactions.iter().max().unwrap()
     }
 }
 
+/// 死锁受害者选择策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum VictimStrategy {
+    /// 最晚开始的事务（默认）
+    #[default]
+    Youngest,
+    /// 最早开始的事务
+    Oldest,
+    /// 持有锁最少的事务
+    FewestLocks,
+    /// 已完成工作量最少的事务
+    LeastWork,
+}
+
+/// 死锁检测器记录的事务元数据
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TxMetadata {
+    /// 开始顺序（越大越年轻）
+    pub start_seq: u64,
+    /// 已完成的工作量（如已执行的操作数）
+    pub work_done: u64,
+}
+
 /// 锁请求信息
 #[derive(Debug, Clone, PartialEq)]
 pub struct LockHolder {
@@ -70,7 +93,9 @@ impl LockHolder {
 }
 
 /// 资源类型（用于统一处理节点和关系）
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+///
+/// 排序时节点在前、关系在后，同类按 ID 升序，作为多资源加锁的规范顺序
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum Resource {
     Node(NodeId),
     Rel(RelId),
@@ -78,7 +103,7 @@ pub enum Resource {
 
 impl Resource {
     /// 获取资源标识符
-    pub fn id(&self) -> u64 {
+    pub fn id(&self) -> NodeId {
         match self {
             Resource::Node(id) => *id,
             Resource::Rel(id) => *id,
@@ -97,6 +122,8 @@ pub struct WaitGraph {
     resource_holders: HashMap<Resource, Vec<LockHolder>>,
     /// 事务等待的资源：tx_id -> 等待的资源列表
     waiting_resources: HashMap<u64, Vec<Resource>>,
+    /// 因等待某个资源而产生的等待边：(等待者, 资源) -> 被等待的持有者
+    resource_wait_edges: HashMap<(u64, Resource), Vec<u64>>,
 }
 
 impl WaitGraph {
@@ -106,6 +133,7 @@ impl WaitGraph {
             adjacency: HashMap::new(),
             resource_holders: HashMap::new(),
             waiting_resources: HashMap::new(),
+            resource_wait_edges: HashMap::new(),
         }
     }
 
@@ -140,6 +168,44 @@ impl WaitGraph {
             .push(resource);
     }
 
+    /// 记录 tx_id 因等待 resource 而等待 holder，等待满足时可以精确撤销
+    pub fn add_resource_wait(&mut self, tx_id: u64, resource: Resource, holder: u64) {
+        self.add_wait_edge(tx_id, holder);
+        self.resource_wait_edges
+            .entry((tx_id, resource))
+            .or_default()
+            .push(holder);
+        let waiting = self.waiting_resources.entry(tx_id).or_default();
+        if !waiting.contains(&resource) {
+            waiting.push(resource);
+        }
+    }
+
+    /// tx_id 对 resource 的等待已满足：移除相应的等待资源和等待边
+    pub fn remove_resource_wait(&mut self, tx_id: u64, resource: Resource) {
+        if let Some(waiting) = self.waiting_resources.get_mut(&tx_id) {
+            waiting.retain(|r| r != &resource);
+            if waiting.is_empty() {
+                self.waiting_resources.remove(&tx_id);
+            }
+        }
+
+        let Some(holders) = self.resource_wait_edges.remove(&(tx_id, resource)) else {
+            return;
+        };
+        if let Some(wait_list) = self.adjacency.get_mut(&tx_id) {
+            // 每条等待边只撤销一次，同一持有者因其他资源产生的等待边保留
+            for holder in holders {
+                if let Some(pos) = wait_list.iter().position(|&t| t == holder) {
+                    wait_list.remove(pos);
+                }
+            }
+            if wait_list.is_empty() {
+                self.adjacency.remove(&tx_id);
+            }
+        }
+    }
+
     /// 移除事务的所有等待
     pub fn remove_transaction(&mut self, tx_id: u64) {
         self.adjacency.remove(&tx_id);
@@ -154,6 +220,12 @@ impl WaitGraph {
         for holders in self.resource_holders.values_mut() {
             holders.retain(|h| h.tx_id != tx_id);
         }
+
+        // 清理按资源记录的等待边
+        self.resource_wait_edges.retain(|(waiter, _), holders| {
+            holders.retain(|&holder| holder != tx_id);
+            *waiter != tx_id && !holders.is_empty()
+        });
     }
 
     /// 检测死环（使用DFS）
@@ -246,6 +318,12 @@ pub struct DeadlockDetector {
     max_history_size: usize,
     /// 检测次数
     detection_count: u64,
+    /// 受害者选择策略
+    victim_strategy: VictimStrategy,
+    /// 事务元数据（开始顺序、工作量）
+    tx_metadata: HashMap<u64, TxMetadata>,
+    /// 下一个开始顺序号
+    next_start_seq: u64,
 }
 
 impl DeadlockDetector {
@@ -256,9 +334,91 @@ impl DeadlockDetector {
             deadlock_history: VecDeque::with_capacity(100),
             max_history_size: 100,
             detection_count: 0,
+            victim_strategy: VictimStrategy::default(),
+            tx_metadata: HashMap::new(),
+            next_start_seq: 0,
         }
     }
 
+    /// 设置受害者选择策略
+    pub fn with_victim_strategy(mut self, strategy: VictimStrategy) -> Self {
+        self.victim_strategy = strategy;
+        self
+    }
+
+    /// 修改受害者选择策略
+    pub fn set_victim_strategy(&mut self, strategy: VictimStrategy) {
+        self.victim_strategy = strategy;
+    }
+
+    /// 当前的受害者选择策略
+    pub fn victim_strategy(&self) -> VictimStrategy {
+        self.victim_strategy
+    }
+
+    /// 登记事务开始，记录其开始顺序（重复登记不会改变顺序）
+    pub fn register_transaction(&mut self, tx_id: u64) {
+        let seq = &mut self.next_start_seq;
+        self.tx_metadata.entry(tx_id).or_insert_with(|| {
+            let start_seq = *seq;
+            *seq += 1;
+            TxMetadata { start_seq, work_done: 0 }
+        });
+    }
+
+    /// 累加事务完成的工作量（未登记的事务会先自动登记）
+    pub fn record_work(&mut self, tx_id: u64, units: u64) {
+        self.register_transaction(tx_id);
+        if let Some(meta) = self.tx_metadata.get_mut(&tx_id) {
+            meta.work_done += units;
+        }
+    }
+
+    /// 事务结束后移除其元数据
+    pub fn unregister_transaction(&mut self, tx_id: u64) {
+        self.tx_metadata.remove(&tx_id);
+    }
+
+    /// 事务元数据
+    pub fn transaction_metadata(&self, tx_id: u64) -> Option<&TxMetadata> {
+        self.tx_metadata.get(&tx_id)
+    }
+
+    /// 事务在等待图中持有的锁数量
+    pub fn held_lock_count(&self, tx_id: u64) -> usize {
+        self.wait_graph
+            .resource_holders
+            .values()
+            .map(|holders| holders.iter().filter(|h| h.tx_id == tx_id).count())
+            .sum()
+    }
+
+    /// 按当前策略从死锁环中选择受害者
+    ///
+    /// 未登记的事务按ID推断开始顺序；各策略打平时选择更年轻的事务。
+    pub fn select_victim(&self, deadlock: &DeadlockInfo) -> u64 {
+        // 年轻程度：(开始顺序, 事务ID)，未登记的事务视为在所有已登记事务之后开始
+        let youth = |tx: u64| {
+            let seq = self.tx_metadata.get(&tx).map_or(u64::MAX, |m| m.start_seq);
+            (seq, tx)
+        };
+        let work = |tx: u64| self.tx_metadata.get(&tx).map_or(0, |m| m.work_done);
+
+        let candidates = deadlock.involved_transactions.iter().copied();
+        let victim = match self.victim_strategy {
+            VictimStrategy::Youngest => candidates.max_by_key(|&tx| youth(tx)),
+            VictimStrategy::Oldest => candidates.min_by_key(|&tx| youth(tx)),
+            VictimStrategy::FewestLocks => candidates.min_by(|&a, &b| {
+                self.held_lock_count(a)
+                    .cmp(&self.held_lock_count(b))
+                    .then_with(|| youth(b).cmp(&youth(a)))
+            }),
+            VictimStrategy::LeastWork => candidates
+                .min_by(|&a, &b| work(a).cmp(&work(b)).then_with(|| youth(b).cmp(&youth(a)))),
+        };
+        victim.unwrap()
+    }
+
     /// 设置最大历史记录数
     pub fn with_max_history_size(mut self, size: usize) -> Self {
         self.max_history_size = size;
@@ -269,8 +429,8 @@ impl DeadlockDetector {
     /// 记录锁获取
     pub fn on_lock_acquired(&mut self, tx_id: u64, resource: Resource, lock_type: LockType) {
         self.wait_graph.set_resource_holder(resource, LockHolder::new(tx_id, lock_type));
-        // 移除该事务对这个资源的等待
-        self.wait_graph.waiting_resources.entry(tx_id).or_insert_with(Vec::new).retain(|r| r != &resource);
+        // 等待已满足：移除该事务对这个资源的等待及指向原持有者的等待边
+        self.wait_graph.remove_resource_wait(tx_id, resource);
     }
 
     /// 记录锁请求（可能导致等待）
@@ -294,8 +454,7 @@ impl DeadlockDetector {
 
         // 然后添加等待关系
         for holder_tx_id in conflicts {
-            self.wait_graph.add_wait_edge(tx_id, holder_tx_id);
-            self.wait_graph.add_waiting_resource(tx_id, resource);
+            self.wait_graph.add_resource_wait(tx_id, resource, holder_tx_id);
         }
     }
 
@@ -331,7 +490,7 @@ impl DeadlockDetector {
 
     /// 解除死锁（选择受害者并回滚）
     pub fn resolve_deadlock(&mut self, deadlock: &DeadlockInfo) -> u64 {
-        let victim = deadlock.select_victim();
+        let victim = self.select_victim(deadlock);
         // 移除受害者相关的等待边
         self.wait_graph.remove_transaction(victim);
         victim
@@ -641,6 +800,54 @@ mod tests {
         assert_eq!(deadlock.as_ref().unwrap().cycle_length, 2);
     }
 
+    #[test]
+    fn test_lock_grant_prunes_wait_edges() {
+        let mut detector = DeadlockDetector::new();
+        let r1 = Resource::Node(1);
+
+        // T2 持有 R1，T1 等待 R1
+        detector.on_lock_acquired(2, r1, LockType::Write);
+        detector.on_lock_requested(1, r1, LockType::Write);
+        assert_eq!(detector.stats().graph_stats.edge_count, 1);
+
+        // T2 释放，T1 获得锁
+        detector.on_lock_released(2, r1);
+        detector.on_lock_acquired(1, r1, LockType::Write);
+
+        assert!(detector.detect_deadlock().is_none());
+        let stats = detector.stats();
+        assert_eq!(stats.graph_stats.edge_count, 0);
+        assert_eq!(stats.current_waiting_transactions, 0);
+    }
+
+    #[test]
+    fn test_lock_grant_without_release_leaves_no_stale_cycle() {
+        let mut detector = DeadlockDetector::new();
+        let r1 = Resource::Node(1);
+        let r2 = Resource::Node(2);
+
+        // T2 持有 R1 的读锁，T1 请求写锁而等待 T2
+        detector.on_lock_acquired(2, r1, LockType::Read);
+        detector.on_lock_requested(1, r1, LockType::Write);
+        // T1 同时在等待 T2 持有的 R2
+        detector.on_lock_acquired(2, r2, LockType::Write);
+        detector.on_lock_requested(1, r2, LockType::Write);
+        assert_eq!(detector.stats().graph_stats.edge_count, 2);
+
+        // R1 被授予 T1（例如锁管理器降级了 T2 的锁），只撤销对应的那条等待边
+        detector.on_lock_acquired(1, r1, LockType::Write);
+        assert_eq!(detector.stats().graph_stats.edge_count, 1);
+
+        // R2 也被授予 T1 后，T1 不再等待任何事务
+        detector.on_lock_acquired(1, r2, LockType::Write);
+        assert_eq!(detector.stats().graph_stats.edge_count, 0);
+
+        // 之后 T2 等待 T1 持有的锁，不应报告 T1 <-> T2 的环
+        detector.on_lock_acquired(1, Resource::Node(4), LockType::Write);
+        detector.on_lock_requested(2, Resource::Node(4), LockType::Write);
+        assert!(detector.detect_deadlock().is_none());
+    }
+
     #[test]
     fn test_deadlock_info_victim_selection() {
         let info = DeadlockInfo::new(vec![1, 5, 3, 2]);
@@ -650,6 +857,68 @@ mod tests {
         assert_eq!(victim, 5);
     }
 
+    /// 构造 T1 -> T2 -> T3 -> T1 的等待环：
+    /// T1 持有 R1、R4，T2 持有 R2，T3 持有 R3、R5、R6
+    fn cycle_detector(strategy: VictimStrategy) -> (DeadlockDetector, DeadlockInfo) {
+        let mut detector = DeadlockDetector::new().with_victim_strategy(strategy);
+        // 开始顺序：T2 最早，T3 最晚
+        detector.register_transaction(2);
+        detector.register_transaction(1);
+        detector.register_transaction(3);
+        detector.record_work(1, 10);
+        detector.record_work(2, 50);
+        detector.record_work(3, 3);
+
+        for (tx, node) in [(1, 1), (2, 2), (3, 3), (1, 4), (3, 5), (3, 6)] {
+            detector.on_lock_acquired(tx, Resource::Node(node), LockType::Write);
+        }
+        detector.on_lock_requested(1, Resource::Node(2), LockType::Write);
+        detector.on_lock_requested(2, Resource::Node(3), LockType::Write);
+        detector.on_lock_requested(3, Resource::Node(1), LockType::Write);
+
+        let deadlock = detector.detect_deadlock().expect("cycle expected");
+        assert_eq!(deadlock.cycle_length, 3);
+        (detector, deadlock)
+    }
+
+    #[test]
+    fn test_victim_strategy_youngest() {
+        let (detector, deadlock) = cycle_detector(VictimStrategy::Youngest);
+        assert_eq!(detector.select_victim(&deadlock), 3);
+    }
+
+    #[test]
+    fn test_victim_strategy_oldest() {
+        let (detector, deadlock) = cycle_detector(VictimStrategy::Oldest);
+        assert_eq!(detector.select_victim(&deadlock), 2);
+    }
+
+    #[test]
+    fn test_victim_strategy_fewest_locks() {
+        let (detector, deadlock) = cycle_detector(VictimStrategy::FewestLocks);
+        assert_eq!(detector.held_lock_count(2), 1);
+        assert_eq!(detector.select_victim(&deadlock), 2);
+    }
+
+    #[test]
+    fn test_victim_strategy_least_work() {
+        let (mut detector, deadlock) = cycle_detector(VictimStrategy::LeastWork);
+        assert_eq!(detector.select_victim(&deadlock), 3);
+
+        // resolve_deadlock 使用同一策略
+        assert_eq!(detector.resolve_deadlock(&deadlock), 3);
+    }
+
+    #[test]
+    fn test_victim_strategy_unregistered_falls_back_to_id() {
+        let info = DeadlockInfo::new(vec![4, 9, 7]);
+        let detector = DeadlockDetector::new();
+        assert_eq!(detector.select_victim(&info), 9);
+
+        let detector = DeadlockDetector::new().with_victim_strategy(VictimStrategy::Oldest);
+        assert_eq!(detector.select_victim(&info), 4);
+    }
+
     #[test]
     fn test_timeout_detector() {
         let mut detector = TimeoutDetector::new(10); // 10秒超时
@@ -0,0 +1,156 @@
+// 快照差异模块
+//
+// 比较两个图快照，报告新增/删除/修改的节点和关系，用于变更追踪和审计
+
+use super::snapshot::{Snapshot, SnapshotNode, SnapshotRel};
+use crate::storage::{NodeId, RelId};
+use crate::values::{Properties, Value};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+/// 单个属性的变更
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PropertyChange {
+    pub key: String,
+    /// 变更前的值（None 表示新增属性）
+    pub before: Option<Value>,
+    /// 变更后的值（None 表示删除属性）
+    pub after: Option<Value>,
+}
+
+/// 被修改的节点
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NodeChange {
+    pub id: NodeId,
+    pub labels_before: Vec<String>,
+    pub labels_after: Vec<String>,
+    pub property_changes: Vec<PropertyChange>,
+}
+
+impl NodeChange {
+    /// 标签是否发生变化（按集合比较，只是顺序不同不算变化）
+    pub fn labels_changed(&self) -> bool {
+        !same_labels(&self.labels_before, &self.labels_after)
+    }
+}
+
+/// 被修改的关系
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RelChange {
+    pub id: RelId,
+    pub before: SnapshotRel,
+    pub after: SnapshotRel,
+    pub property_changes: Vec<PropertyChange>,
+}
+
+/// 两个快照之间的差异
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GraphDiff {
+    pub added_nodes: Vec<SnapshotNode>,
+    pub removed_nodes: Vec<SnapshotNode>,
+    pub modified_nodes: Vec<NodeChange>,
+    pub added_rels: Vec<SnapshotRel>,
+    pub removed_rels: Vec<SnapshotRel>,
+    pub modified_rels: Vec<RelChange>,
+}
+
+impl GraphDiff {
+    /// 两个快照是否完全一致
+    pub fn is_empty(&self) -> bool {
+        self.added_nodes.is_empty()
+            && self.removed_nodes.is_empty()
+            && self.modified_nodes.is_empty()
+            && self.added_rels.is_empty()
+            && self.removed_rels.is_empty()
+            && self.modified_rels.is_empty()
+    }
+
+    /// 变更条目总数
+    pub fn change_count(&self) -> usize {
+        self.added_nodes.len()
+            + self.removed_nodes.len()
+            + self.modified_nodes.len()
+            + self.added_rels.len()
+            + self.removed_rels.len()
+            + self.modified_rels.len()
+    }
+}
+
+/// 比较两个快照
+///
+/// 节点的修改检测比较标签（忽略顺序）和属性表；关系的修改检测比较
+/// 类型、端点和属性表。结果按 ID 升序排列。
+pub fn graph_diff(before: &Snapshot, after: &Snapshot) -> GraphDiff {
+    let mut diff = GraphDiff::default();
+
+    let node_ids: BTreeSet<NodeId> = before.nodes.keys().chain(after.nodes.keys()).copied().collect();
+    for id in node_ids {
+        match (before.nodes.get(&id), after.nodes.get(&id)) {
+            (None, Some(node)) => diff.added_nodes.push(node.clone()),
+            (Some(node), None) => diff.removed_nodes.push(node.clone()),
+            (Some(old), Some(new)) => {
+                let property_changes = diff_properties(&old.properties, &new.properties);
+                if !same_labels(&old.labels, &new.labels) || !property_changes.is_empty() {
+                    diff.modified_nodes.push(NodeChange {
+                        id,
+                        labels_before: old.labels.clone(),
+                        labels_after: new.labels.clone(),
+                        property_changes,
+                    });
+                }
+            }
+            (None, None) => {}
+        }
+    }
+
+    let rel_ids: BTreeSet<RelId> = before.rels.keys().chain(after.rels.keys()).copied().collect();
+    for id in rel_ids {
+        match (before.rels.get(&id), after.rels.get(&id)) {
+            (None, Some(rel)) => diff.added_rels.push(rel.clone()),
+            (Some(rel), None) => diff.removed_rels.push(rel.clone()),
+            (Some(old), Some(new)) => {
+                let property_changes = diff_properties(&old.properties, &new.properties);
+                let shape_changed =
+                    old.start != new.start || old.end != new.end || old.typ != new.typ;
+                if shape_changed || !property_changes.is_empty() {
+                    diff.modified_rels.push(RelChange {
+                        id,
+                        before: old.clone(),
+                        after: new.clone(),
+                        property_changes,
+                    });
+                }
+            }
+            (None, None) => {}
+        }
+    }
+
+    diff
+}
+
+/// 比较标签集合（忽略顺序）
+fn same_labels(a: &[String], b: &[String]) -> bool {
+    let a: BTreeSet<&String> = a.iter().collect();
+    let b: BTreeSet<&String> = b.iter().collect();
+    a == b
+}
+
+/// 逐个属性比较，结果按属性名排序
+fn diff_properties(before: &Properties, after: &Properties) -> Vec<PropertyChange> {
+    let keys: BTreeSet<&String> = before.keys().chain(after.keys()).collect();
+    keys.into_iter()
+        .filter_map(|key| {
+            let old = before.get(key);
+            let new = after.get(key);
+            if old == new {
+                None
+            } else {
+                Some(PropertyChange {
+                    key: key.clone(),
+                    before: old.cloned(),
+                    after: new.cloned(),
+                })
+            }
+        })
+        .collect()
+}
@@ -8,6 +8,7 @@
 
 use std::collections::{HashMap, HashSet};
 use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
 use crate::storage::{NodeId, RelId};
 use crate::transactions::{
     TransactionError, TransactionResult, IsolationLevel, Transaction, TransactionOp,
@@ -186,6 +187,32 @@ impl WriteSet {
     }
 }
 
+/// 已提交写集的自动清理策略
+///
+/// 每次事务结束后检查：已提交写集数量超过 `max_committed`，或距上次清理超过 `interval` 时，
+/// 按水位线清理（见 [`IsolationExecutor::prune_committed`]）。两者都为 None 时只能手动清理。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PrunePolicy {
+    pub max_committed: Option<usize>,
+    pub interval: Option<Duration>,
+}
+
+impl Default for PrunePolicy {
+    fn default() -> Self {
+        Self {
+            max_committed: Some(1024),
+            interval: Some(Duration::from_secs(30)),
+        }
+    }
+}
+
+/// 已提交事务的写集及其提交时间戳
+#[derive(Debug, Clone)]
+struct CommittedWriteSet {
+    commit_ts: u64,
+    write_set: WriteSet,
+}
+
 /// 隔离级别执行器
 ///
 /// 根据不同的隔离级别执行事务
@@ -194,10 +221,16 @@ pub struct IsolationExecutor {
     active_read_sets: Arc<RwLock<HashMap<u64, ReadSet>>>,
     /// 活动事务的写集
     active_write_sets: Arc<RwLock<HashMap<u64, WriteSet>>>,
+    /// 活动事务的开始时间戳
+    start_timestamps: Arc<RwLock<HashMap<u64, u64>>>,
     /// 已提交事务的写集（用于检测已提交的写入）
-    committed_write_sets: Arc<RwLock<HashMap<u64, WriteSet>>>,
-    /// 下一个事务时间戳（用于可串行化）
+    committed_write_sets: Arc<RwLock<HashMap<u64, CommittedWriteSet>>>,
+    /// 下一个事务时间戳（开始和提交各占一个）
     next_timestamp: Arc<RwLock<u64>>,
+    /// 已提交写集的自动清理策略
+    prune_policy: PrunePolicy,
+    /// 上次清理的时间
+    last_prune: Arc<RwLock<Instant>>,
 }
 
 impl IsolationExecutor {
@@ -206,11 +239,31 @@ impl IsolationExecutor {
         Self {
             active_read_sets: Arc::new(RwLock::new(HashMap::new())),
             active_write_sets: Arc::new(RwLock::new(HashMap::new())),
+            start_timestamps: Arc::new(RwLock::new(HashMap::new())),
             committed_write_sets: Arc::new(RwLock::new(HashMap::new())),
             next_timestamp: Arc::new(RwLock::new(0)),
+            prune_policy: PrunePolicy::default(),
+            last_prune: Arc::new(RwLock::new(Instant::now())),
         }
     }
 
+    /// 设置已提交写集的自动清理策略
+    pub fn with_prune_policy(mut self, policy: PrunePolicy) -> Self {
+        self.prune_policy = policy;
+        self
+    }
+
+    pub fn prune_policy(&self) -> PrunePolicy {
+        self.prune_policy
+    }
+
+    fn next_timestamp(&self) -> u64 {
+        let mut next = self.next_timestamp.write().unwrap();
+        let ts = *next;
+        *next += 1;
+        ts
+    }
+
     /// 开始事务（注册读集和写集）
     pub fn begin_transaction(&self, tx_id: u64) {
         let mut read_sets = self.active_read_sets.write().unwrap();
@@ -218,6 +271,8 @@ impl IsolationExecutor {
 
         read_sets.insert(tx_id, ReadSet::new());
         write_sets.insert(tx_id, WriteSet::new());
+        let start_ts = self.next_timestamp();
+        self.start_timestamps.write().unwrap().insert(tx_id, start_ts);
     }
 
     /// 记录读操作
@@ -293,8 +348,8 @@ impl IsolationExecutor {
         let read_set = read_sets.get(&tx_id).cloned().unwrap_or_default();
 
         // 检查读集是否与任何已提交事务的写集冲突
-        for (_committed_tx_id, committed_write) in committed_write_sets.iter() {
-            if read_set.conflicts_with_write(committed_write) {
+        for committed in committed_write_sets.values() {
+            if read_set.conflicts_with_write(&committed.write_set) {
                 return Err(TransactionError::Other(
                     "Repeatable read violation: data was modified after being read".to_string()
                 ));
@@ -334,8 +389,8 @@ impl IsolationExecutor {
         }
 
         // 2. 检查与已提交事务的写写冲突
-        for (_committed_tx_id, committed_write) in committed_write_sets.iter() {
-            if write_set.conflicts_with(committed_write) {
+        for committed in committed_write_sets.values() {
+            if write_set.conflicts_with(&committed.write_set) {
                 return Err(TransactionError::Other(
                     "Serializable violation: write-write conflict with committed transaction".to_string()
                 ));
@@ -343,8 +398,8 @@ impl IsolationExecutor {
         }
 
         // 3. 检查读写冲突（读集与已提交的写集）
-        for (_committed_tx_id, committed_write) in committed_write_sets.iter() {
-            if read_set.conflicts_with_write(committed_write) {
+        for committed in committed_write_sets.values() {
+            if read_set.conflicts_with_write(&committed.write_set) {
                 return Err(TransactionError::Other(
                     "Serializable violation: read data was modified by committed transaction".to_string()
                 ));
@@ -372,23 +427,65 @@ impl IsolationExecutor {
     }
 
     /// 完成事务（提交或回滚后调用）
+    ///
+    /// 之后按 [`PrunePolicy`] 检查是否需要清理已提交的写集。
     pub fn finish_transaction(&self, tx_id: u64, is_committed: bool, ops: Option<&[TransactionOp]>) {
         // 移除活动事务
         self.active_read_sets.write().unwrap().remove(&tx_id);
         let write_set = self.active_write_sets.write().unwrap().remove(&tx_id);
+        self.start_timestamps.write().unwrap().remove(&tx_id);
 
         if is_committed {
             // 如果提交，添加到已提交写集
-            if let Some(ops) = ops {
-                let write_set = WriteSet::from_operations(ops);
-                self.committed_write_sets.write().unwrap().insert(tx_id, write_set);
-            } else if let Some(ws) = write_set {
-                self.committed_write_sets.write().unwrap().insert(tx_id, ws);
+            let write_set = match ops {
+                Some(ops) => Some(WriteSet::from_operations(ops)),
+                None => write_set,
+            };
+            if let Some(write_set) = write_set {
+                let commit_ts = self.next_timestamp();
+                self.committed_write_sets
+                    .write()
+                    .unwrap()
+                    .insert(tx_id, CommittedWriteSet { commit_ts, write_set });
             }
         }
+
+        let due = self
+            .prune_policy
+            .max_committed
+            .is_some_and(|max| self.committed_write_sets.read().unwrap().len() > max)
+            || self
+                .prune_policy
+                .interval
+                .is_some_and(|interval| self.last_prune.read().unwrap().elapsed() >= interval);
+        if due {
+            self.prune_committed();
+        }
+    }
+
+    /// 水位线：最早的活动事务的开始时间戳，没有活动事务时为 None
+    ///
+    /// 提交时间戳早于水位线的写集在所有活动事务开始前就已提交，不会再参与冲突检测。
+    pub fn watermark(&self) -> Option<u64> {
+        self.start_timestamps.read().unwrap().values().min().copied()
+    }
+
+    /// 按水位线清理已提交的写集，返回清理的数量
+    ///
+    /// 只移除早于水位线提交的写集；没有活动事务时全部移除。
+    pub fn prune_committed(&self) -> usize {
+        let watermark = self.watermark().unwrap_or(u64::MAX);
+        let mut committed = self.committed_write_sets.write().unwrap();
+        let before = committed.len();
+        committed.retain(|_, c| c.commit_ts >= watermark);
+        *self.last_prune.write().unwrap() = Instant::now();
+        before - committed.len()
     }
 
     /// 清理已提交的写集（定期调用以释放内存）
+    ///
+    /// 按事务 ID 只保留最近的 `retain_count` 个，不考虑活动事务是否还需要；
+    /// 一般使用按水位线清理的 [`prune_committed`](Self::prune_committed)。
     pub fn cleanup_committed_transactions(&self, retain_count: usize) {
         let mut committed = self.committed_write_sets.write().unwrap();
         if committed.len() > retain_count {
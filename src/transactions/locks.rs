@@ -2,9 +2,11 @@
 //
 // 提供悲观锁机制，用于控制并发访问
 
+use super::deadlock::Resource;
 use crate::storage::{NodeId, RelId};
 use std::collections::{HashMap, HashSet};
 use std::fmt;
+use std::sync::{Arc, Condvar, Mutex, PoisonError};
 
 /// 锁类型
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -125,6 +127,8 @@ pub struct LockManager {
     wait_queue: HashMap<u64, HashSet<(NodeId, RelId)>>,
     /// 死锁检测超时（秒）
     deadlock_timeout: u64,
+    /// 有锁被释放时通知 `lock_all` 中等待的线程
+    released: Arc<Condvar>,
 }
 
 impl LockManager {
@@ -135,6 +139,7 @@ impl LockManager {
             rel_locks: HashMap::new(),
             wait_queue: HashMap::new(),
             deadlock_timeout: 30, // 默认30秒超时
+            released: Arc::new(Condvar::new()),
         }
     }
 
@@ -200,6 +205,66 @@ impl LockManager {
 
         // 从等待队列中移除
         self.wait_queue.remove(&tx_id);
+        self.released.notify_all();
+    }
+
+    /// 释放事务在单个资源上的一次锁请求（同一事务重复加的锁仍然保留）
+    fn release_one(&mut self, tx_id: u64, resource: Resource) {
+        let locks = match resource {
+            Resource::Node(id) => self
+                .node_locks
+                .get_mut(&id)
+                .and_then(|entry| entry.node_locks.get_mut(&id)),
+            Resource::Rel(id) => self
+                .rel_locks
+                .get_mut(&id)
+                .and_then(|entry| entry.rel_locks.get_mut(&id)),
+        };
+        if let Some(locks) = locks {
+            if let Some(pos) = locks.iter().rposition(|req| req.tx_id == tx_id) {
+                locks.remove(pos);
+            }
+        }
+        self.released.notify_all();
+    }
+
+    /// 按规范顺序一次锁住一组资源，阻塞直到全部获取
+    ///
+    /// 资源先去重并排序（节点在前、关系在后，同类按 ID 升序）再逐个获取。
+    /// 所有通过本方法加锁的调用方都按同一顺序等待，不会形成循环等待，因此不会死锁。
+    /// 返回的守卫在 drop 时释放这些锁。不同调用方必须使用不同的 `tx_id`。
+    pub fn lock_all(
+        manager: &Arc<Mutex<LockManager>>,
+        tx_id: u64,
+        resources: &[Resource],
+        lock_type: LockType,
+    ) -> MultiLockGuard {
+        let mut ordered = resources.to_vec();
+        ordered.sort();
+        ordered.dedup();
+
+        let mut held = Vec::with_capacity(ordered.len());
+        let mut locks = manager.lock().unwrap_or_else(PoisonError::into_inner);
+        for resource in ordered {
+            loop {
+                let acquired = match resource {
+                    Resource::Node(id) => locks.acquire_node_lock(tx_id, id, lock_type),
+                    Resource::Rel(id) => locks.acquire_rel_lock(tx_id, id, lock_type),
+                };
+                if acquired {
+                    break;
+                }
+                let released = Arc::clone(&locks.released);
+                locks = released.wait(locks).unwrap_or_else(PoisonError::into_inner);
+            }
+            held.push(resource);
+        }
+
+        MultiLockGuard {
+            manager: Arc::clone(manager),
+            tx_id,
+            resources: held,
+        }
     }
 
     /// 检查是否存在死锁
@@ -295,6 +360,17 @@ impl LockManager {
         count
     }
 
+    /// 事务是否已在资源上持有指定类型的锁
+    pub fn holds_lock(&self, tx_id: u64, resource: Resource, lock_type: LockType) -> bool {
+        let locks = match resource {
+            Resource::Node(id) => self.node_locks.get(&id).and_then(|entry| entry.node_locks.get(&id)),
+            Resource::Rel(id) => self.rel_locks.get(&id).and_then(|entry| entry.rel_locks.get(&id)),
+        };
+        locks.is_some_and(|locks| {
+            locks.iter().any(|req| req.tx_id == tx_id && req.lock_type == lock_type)
+        })
+    }
+
     /// 检查节点是否被锁定
     pub fn is_node_locked(&self, node_id: NodeId) -> bool {
         if let Some(entry) = self.node_locks.get(&node_id) {
@@ -325,3 +401,27 @@ impl Default for LockManager {
         Self::new()
     }
 }
+
+/// [`LockManager::lock_all`] 返回的守卫，drop 时释放其获取的全部锁
+#[derive(Debug)]
+pub struct MultiLockGuard {
+    manager: Arc<Mutex<LockManager>>,
+    tx_id: u64,
+    resources: Vec<Resource>,
+}
+
+impl MultiLockGuard {
+    /// 已锁住的资源（规范顺序）
+    pub fn resources(&self) -> &[Resource] {
+        &self.resources
+    }
+}
+
+impl Drop for MultiLockGuard {
+    fn drop(&mut self) {
+        let mut locks = self.manager.lock().unwrap_or_else(PoisonError::into_inner);
+        for &resource in self.resources.iter().rev() {
+            locks.release_one(self.tx_id, resource);
+        }
+    }
+}
@@ -4,34 +4,39 @@
 // - 事务生命周期管理
 // - 操作日志记录
 // - 快照机制
+// - 快照差异比较
 // - 回滚支持
 // - 锁管理
 
 pub mod snapshot;
+pub mod diff;
 pub mod transaction;
 pub mod locks;
 pub mod optimistic_lock;
 pub mod isolation;
 pub mod deadlock;
+pub mod retry;
 
 pub use snapshot::{Snapshot, SnapshotManager, SnapshotNode, SnapshotRel};
+pub use diff::{graph_diff, GraphDiff, NodeChange, RelChange, PropertyChange};
 pub use transaction::{
     Transaction, TransactionManager, TransactionOp, TransactionResult,
-    TransactionError, TransactionStatus, NodeData, RelData, Savepoint,
+    TransactionError, TransactionStatus, NodeData, RelData, Savepoint, TxInfo,
 };
-pub use locks::{LockManager, LockType, LockRequest, LockEntry};
+pub use locks::{LockManager, LockType, LockRequest, LockEntry, MultiLockGuard};
 pub use optimistic_lock::{
     OptimisticLock, OptimisticLockManager, OptimisticLockStats,
     OptimisticReadContext, Version,
 };
 pub use isolation::{
-    IsolationExecutor, IsolationStats, ReadSet, WriteSet,
+    IsolationExecutor, IsolationStats, PrunePolicy, ReadSet, WriteSet,
 };
 pub use deadlock::{
-    DeadlockDetector, DeadlockInfo, DeadlockStats,
+    DeadlockDetector, DeadlockInfo, DeadlockStats, VictimStrategy, TxMetadata,
     WaitGraph, WaitGraphStats, TimeoutDetector, TimeoutStats,
     PreventiveDeadlockDetector, PreventiveStats, Resource, LockHolder,
 };
+pub use retry::RetryConfig;
 
 use crate::storage::{NodeId, RelId};
 use crate::values::Properties;
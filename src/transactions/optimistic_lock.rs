@@ -3,6 +3,7 @@
 // 实现基于版本号的乐观并发控制
 // 适用于读多写少的场景，避免长时间持有锁
 
+use std::collections::HashMap;
 use std::sync::atomic::{AtomicU64, Ordering};
 use crate::storage::{NodeId, RelId};
 use crate::transactions::{TransactionError, TransactionResult};
@@ -99,57 +100,36 @@ impl Default for OptimisticLock {
 
 /// 乐观锁管理器
 ///
-/// 管理所有节点和关系的乐观锁
+/// 管理所有节点和关系的乐观锁。版本按 ID 稀疏存储，随机 ID 策略下也只为
+/// 实际写过的实体分配条目
 pub struct OptimisticLockManager {
     /// 节点版本锁
-    node_versions: Vec<AtomicU64>,
+    node_versions: HashMap<NodeId, AtomicU64>,
     /// 关系版本锁
-    rel_versions: Vec<AtomicU64>,
+    rel_versions: HashMap<RelId, AtomicU64>,
 }
 
 impl OptimisticLockManager {
     /// 创建新的乐观锁管理器
     pub fn new() -> Self {
         Self {
-            node_versions: Vec::new(),
-            rel_versions: Vec::new(),
-        }
-    }
-
-    /// 确保节点版本数组足够大
-    fn ensure_node_capacity(&mut self, node_id: NodeId) {
-        let idx = node_id as usize;
-        while self.node_versions.len() <= idx {
-            self.node_versions.push(AtomicU64::new(0));
-        }
-    }
-
-    /// 确保关系版本数组足够大
-    fn ensure_rel_capacity(&mut self, rel_id: RelId) {
-        let idx = rel_id as usize;
-        while self.rel_versions.len() <= idx {
-            self.rel_versions.push(AtomicU64::new(0));
+            node_versions: HashMap::new(),
+            rel_versions: HashMap::new(),
         }
     }
 
     /// 读取节点版本
     pub fn read_node_version(&self, node_id: NodeId) -> Version {
-        let idx = node_id as usize;
-        if idx < self.node_versions.len() {
-            Version::new(self.node_versions[idx].load(Ordering::Acquire))
-        } else {
-            Version::initial()
-        }
+        self.node_versions
+            .get(&node_id)
+            .map_or(Version::initial(), |v| Version::new(v.load(Ordering::Acquire)))
     }
 
     /// 读取关系版本
     pub fn read_rel_version(&self, rel_id: RelId) -> Version {
-        let idx = rel_id as usize;
-        if idx < self.rel_versions.len() {
-            Version::new(self.rel_versions[idx].load(Ordering::Acquire))
-        } else {
-            Version::initial()
-        }
+        self.rel_versions
+            .get(&rel_id)
+            .map_or(Version::initial(), |v| Version::new(v.load(Ordering::Acquire)))
     }
 
     /// 验证节点版本
@@ -196,17 +176,15 @@ impl OptimisticLockManager {
 
     /// 递增节点版本（创建新节点）
     pub fn increment_node_version(&mut self, node_id: NodeId) -> Version {
-        self.ensure_node_capacity(node_id);
-        let idx = node_id as usize;
-        let old = self.node_versions[idx].fetch_add(1, Ordering::AcqRel);
+        let entry = self.node_versions.entry(node_id).or_default();
+        let old = entry.fetch_add(1, Ordering::AcqRel);
         Version::new(old)
     }
 
     /// 递增关系版本（创建新关系）
     pub fn increment_rel_version(&mut self, rel_id: RelId) -> Version {
-        self.ensure_rel_capacity(rel_id);
-        let idx = rel_id as usize;
-        let old = self.rel_versions[idx].fetch_add(1, Ordering::AcqRel);
+        let entry = self.rel_versions.entry(rel_id).or_default();
+        let old = entry.fetch_add(1, Ordering::AcqRel);
         Version::new(old)
     }
 
@@ -216,14 +194,13 @@ impl OptimisticLockManager {
         node_id: NodeId,
         expected_version: Version,
     ) -> TransactionResult<Version> {
-        self.ensure_node_capacity(node_id);
-        let idx = node_id as usize;
-        let current = self.node_versions[idx].load(Ordering::Acquire);
+        let entry = self.node_versions.entry(node_id).or_default();
+        let current = entry.load(Ordering::Acquire);
 
         if current == expected_version.value() {
             // CAS 操作：仅在版本匹配时更新
             let new_version = current + 1;
-            match self.node_versions[idx].compare_exchange_weak(
+            match entry.compare_exchange_weak(
                 current,
                 new_version,
                 Ordering::AcqRel,
@@ -249,14 +226,13 @@ impl OptimisticLockManager {
         rel_id: RelId,
         expected_version: Version,
     ) -> TransactionResult<Version> {
-        self.ensure_rel_capacity(rel_id);
-        let idx = rel_id as usize;
-        let current = self.rel_versions[idx].load(Ordering::Acquire);
+        let entry = self.rel_versions.entry(rel_id).or_default();
+        let current = entry.load(Ordering::Acquire);
 
         if current == expected_version.value() {
             // CAS 操作：仅在版本匹配时更新
             let new_version = current + 1;
-            match self.rel_versions[idx].compare_exchange_weak(
+            match entry.compare_exchange_weak(
                 current,
                 new_version,
                 Ordering::AcqRel,
@@ -301,17 +277,11 @@ impl OptimisticLockManager {
     /// 清理无效的版本条目（用于维护）
     pub fn cleanup(&mut self, max_node_id: Option<NodeId>, max_rel_id: Option<RelId>) {
         if let Some(max_id) = max_node_id {
-            let max_idx = max_id as usize + 1;
-            if self.node_versions.len() > max_idx {
-                self.node_versions.truncate(max_idx);
-            }
+            self.node_versions.retain(|&id, _| id <= max_id);
         }
 
         if let Some(max_id) = max_rel_id {
-            let max_idx = max_id as usize + 1;
-            if self.rel_versions.len() > max_idx {
-                self.rel_versions.truncate(max_idx);
-            }
+            self.rel_versions.retain(|&id, _| id <= max_id);
         }
     }
 
@@ -322,12 +292,12 @@ impl OptimisticLockManager {
             rel_version_count: self.rel_versions.len(),
             total_version_updates: self
                 .node_versions
-                .iter()
+                .values()
                 .map(|v| v.load(Ordering::Relaxed))
                 .sum::<u64>()
                 + self
                     .rel_versions
-                    .iter()
+                    .values()
                     .map(|v| v.load(Ordering::Relaxed))
                     .sum::<u64>(),
         }
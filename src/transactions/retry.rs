@@ -0,0 +1,83 @@
+// 冲突重试
+//
+// 乐观事务在提交时才检测冲突，冲突后需要重新执行整个事务。
+// 这里提供重试策略（次数 + 指数退避）。
+
+use std::time::Duration;
+
+use crate::transactions::{TransactionError, TransactionResult};
+
+/// 冲突重试配置
+#[derive(Debug, Clone)]
+pub struct RetryConfig {
+    /// 首次执行之外最多重试的次数
+    pub max_retries: usize,
+    /// 第一次重试前的等待时间
+    pub initial_backoff: Duration,
+    /// 每次重试后等待时间的倍数
+    pub multiplier: f64,
+    /// 单次等待时间上限
+    pub max_backoff: Duration,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(10),
+            multiplier: 2.0,
+            max_backoff: Duration::from_secs(1),
+        }
+    }
+}
+
+impl RetryConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_max_retries(mut self, max_retries: usize) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    pub fn with_backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.initial_backoff = initial;
+        self.max_backoff = max;
+        self
+    }
+
+    pub fn with_multiplier(mut self, multiplier: f64) -> Self {
+        self.multiplier = multiplier;
+        self
+    }
+
+    /// 第 `retry` 次重试（从 0 开始）前的等待时间
+    pub fn backoff(&self, retry: usize) -> Duration {
+        let factor = self.multiplier.max(1.0).powi(retry.min(i32::MAX as usize) as i32);
+        self.initial_backoff.mul_f64(factor).min(self.max_backoff)
+    }
+
+    /// 执行 `attempt`，遇到冲突时退避后重新执行
+    ///
+    /// 重试次数用尽后返回最后一次冲突错误，非冲突错误立即返回
+    pub fn run<T>(&self, mut attempt: impl FnMut() -> TransactionResult<T>) -> TransactionResult<T> {
+        let mut retry = 0;
+        loop {
+            let err = match attempt() {
+                Ok(value) => return Ok(value),
+                Err(err) => err,
+            };
+            if !is_conflict(&err) || retry >= self.max_retries {
+                return Err(err);
+            }
+            std::thread::sleep(self.backoff(retry));
+            retry += 1;
+        }
+    }
+}
+
+/// 是否为可以通过重试解决的冲突错误
+pub fn is_conflict(err: &TransactionError) -> bool {
+    matches!(err, TransactionError::VersionConflict { .. })
+}
@@ -27,7 +27,7 @@ pub struct Snapshot {
 }
 
 /// 快照中的节点数据
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SnapshotNode {
     pub id: NodeId,
     pub labels: Vec<String>,
@@ -35,7 +35,7 @@ pub struct SnapshotNode {
 }
 
 /// 快照中的关系数据
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SnapshotRel {
     pub id: RelId,
     pub start: NodeId,
@@ -2,10 +2,14 @@
 //
 // 定义所有可以在事务中执行的操作类型
 
+use super::deadlock::Resource;
+use super::locks::{LockManager, LockType};
+use super::{IsolationLevel, TransactionConfig};
 use crate::storage::{NodeId, RelId};
 use crate::values::Properties;
 use serde::{Deserialize, Serialize};
 use std::fmt;
+use std::time::{Duration, Instant};
 
 /// 事务操作类型
 ///
@@ -106,6 +110,8 @@ pub enum TransactionError {
         expected: u64,
         actual: u64,
     },
+    /// 资源已被其他事务锁定（悲观锁）
+    LockConflict(Resource),
 }
 
 impl fmt::Display for TransactionError {
@@ -123,12 +129,21 @@ impl fmt::Display for TransactionError {
             TransactionError::VersionConflict { expected, actual } => {
                 write!(f, "Version conflict: expected {}, found {}", expected, actual)
             }
+            TransactionError::LockConflict(resource) => {
+                write!(f, "Lock conflict: {:?} is locked by another transaction", resource)
+            }
         }
     }
 }
 
 impl std::error::Error for TransactionError {}
 
+impl From<crate::storage::StorageError> for TransactionError {
+    fn from(e: crate::storage::StorageError) -> Self {
+        TransactionError::StorageError(format!("{:?}", e))
+    }
+}
+
 /// 事务结果类型
 pub type TransactionResult<T> = Result<T, TransactionError>;
 
@@ -166,6 +181,10 @@ pub struct Transaction {
     pub snapshot_id: Option<u64>,
     /// 保存点列表
     pub savepoints: Vec<Savepoint>,
+    /// 隔离级别
+    pub isolation_level: IsolationLevel,
+    /// 开始时刻（单调时钟，用于计算事务时长）
+    pub started_at: Instant,
 }
 
 impl Transaction {
@@ -181,9 +200,16 @@ impl Transaction {
                 .as_secs(),
             snapshot_id: None,
             savepoints: Vec::new(),
+            isolation_level: IsolationLevel::default(),
+            started_at: Instant::now(),
         }
     }
 
+    /// 事务已运行的时长
+    pub fn age(&self) -> Duration {
+        self.started_at.elapsed()
+    }
+
     /// 添加操作到事务日志
     pub fn add_op(&mut self, op: TransactionOp) {
         self.ops.push(op);
@@ -262,6 +288,21 @@ impl Transaction {
     }
 }
 
+/// 活动事务的运行信息（用于监控和排查长事务）
+#[derive(Debug, Clone, PartialEq)]
+pub struct TxInfo {
+    /// 事务ID
+    pub id: u64,
+    /// 隔离级别
+    pub isolation_level: IsolationLevel,
+    /// 已运行时长
+    pub age: Duration,
+    /// 已记录的操作数
+    pub op_count: usize,
+    /// 持有的锁数量
+    pub held_locks: usize,
+}
+
 /// 事务管理器
 ///
 /// 管理所有事务的生命周期
@@ -275,6 +316,8 @@ pub struct TransactionManager {
     next_tx_id: u64,
     /// 默认超时时间（秒）
     default_timeout_secs: u64,
+    /// 事务持有的锁（`record_op` 加写锁，提交或回滚时释放）
+    locks: LockManager,
 }
 
 impl TransactionManager {
@@ -285,6 +328,7 @@ impl TransactionManager {
             completed_transactions: Vec::new(),
             next_tx_id: 0,
             default_timeout_secs: 30, // 默认30秒超时
+            locks: LockManager::new(),
         }
     }
 
@@ -295,6 +339,7 @@ impl TransactionManager {
             completed_transactions: Vec::new(),
             next_tx_id: 0,
             default_timeout_secs: timeout_secs,
+            locks: LockManager::new(),
         }
     }
 
@@ -305,13 +350,21 @@ impl TransactionManager {
 
     /// 开始带超时的新事务
     pub fn begin_transaction_with_timeout(&mut self, timeout_secs: u64) -> Transaction {
+        self.begin_transaction_with_config(
+            &TransactionConfig::new().with_timeout(timeout_secs),
+        )
+    }
+
+    /// 按配置开始新事务（隔离级别、超时）
+    pub fn begin_transaction_with_config(&mut self, config: &TransactionConfig) -> Transaction {
         let id = self.next_tx_id;
         self.next_tx_id += 1;
 
         let mut tx = Transaction::new(id);
+        tx.isolation_level = config.isolation_level;
         // 存储超时时间在 snapshot_id 字段中（临时解决方案）
         // 实际应该添加单独的 timeout 字段
-        tx.snapshot_id = Some(timeout_secs);
+        tx.snapshot_id = config.timeout_secs;
 
         self.active_transactions.insert(id, tx.clone());
         tx
@@ -327,6 +380,7 @@ impl TransactionManager {
         }
 
         tx.mark_committed();
+        self.locks.release_all(tx_id);
         self.completed_transactions.push(tx);
         Ok(())
     }
@@ -341,6 +395,7 @@ impl TransactionManager {
         }
 
         tx.mark_rolled_back();
+        self.locks.release_all(tx_id);
         self.completed_transactions.push(tx);
         Ok(())
     }
@@ -368,6 +423,7 @@ impl TransactionManager {
         for id in &expired_ids {
             if let Some(mut tx) = self.active_transactions.remove(id) {
                 tx.mark_rolled_back();
+                self.locks.release_all(*id);
                 self.completed_transactions.push(tx);
             }
         }
@@ -407,6 +463,9 @@ impl TransactionManager {
     }
 
     /// 记录操作到事务
+    ///
+    /// 先为操作涉及的节点或关系加写锁，锁到事务提交或回滚为止；
+    /// 资源已被其他事务锁定时返回 [`TransactionError::LockConflict`]，操作不会被记录。
     pub fn record_op(&mut self, tx_id: u64, op: TransactionOp) -> TransactionResult<()> {
         let tx = self.active_transactions.get_mut(&tx_id)
             .ok_or_else(|| TransactionError::TransactionNotFound(tx_id))?;
@@ -415,6 +474,24 @@ impl TransactionManager {
             return Err(TransactionError::TransactionAlreadyCompleted(tx_id, tx.status));
         }
 
+        let resource = match &op {
+            TransactionOp::CreateNode { id, .. }
+            | TransactionOp::DeleteNode { id, .. }
+            | TransactionOp::UpdateNode { id, .. } => Resource::Node(*id),
+            TransactionOp::CreateRel { id, .. }
+            | TransactionOp::DeleteRel { id, .. }
+            | TransactionOp::UpdateRel { id, .. } => Resource::Rel(*id),
+        };
+        if !self.locks.holds_lock(tx_id, resource, LockType::Write) {
+            let acquired = match resource {
+                Resource::Node(id) => self.locks.acquire_node_lock(tx_id, id, LockType::Write),
+                Resource::Rel(id) => self.locks.acquire_rel_lock(tx_id, id, LockType::Write),
+            };
+            if !acquired {
+                return Err(TransactionError::LockConflict(resource));
+            }
+        }
+
         tx.add_op(op);
         Ok(())
     }
@@ -443,6 +520,41 @@ impl TransactionManager {
     pub fn active_transaction_ids(&self) -> Vec<u64> {
         self.active_transactions.keys().cloned().collect()
     }
+
+    /// 事务使用的锁管理器（提交/回滚时自动释放该事务的锁）
+    pub fn locks(&self) -> &LockManager {
+        &self.locks
+    }
+
+    /// 可变的锁管理器，用于为事务加锁
+    pub fn locks_mut(&mut self) -> &mut LockManager {
+        &mut self.locks
+    }
+
+    /// 所有活动事务的运行信息，按事务ID升序
+    pub fn active_transactions(&self) -> Vec<TxInfo> {
+        let mut infos: Vec<TxInfo> = self
+            .active_transactions
+            .values()
+            .map(|tx| TxInfo {
+                id: tx.id,
+                isolation_level: tx.isolation_level,
+                age: tx.age(),
+                op_count: tx.op_count(),
+                held_locks: self.locks.get_lock_count(tx.id),
+            })
+            .collect();
+        infos.sort_by_key(|info| info.id);
+        infos
+    }
+
+    /// 运行时长超过 `threshold` 的活动事务（可能卡住的长事务）
+    pub fn long_running_transactions(&self, threshold: Duration) -> Vec<TxInfo> {
+        self.active_transactions()
+            .into_iter()
+            .filter(|info| info.age >= threshold)
+            .collect()
+    }
 }
 
 impl Default for TransactionManager {
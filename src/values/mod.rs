@@ -1,3 +1,3 @@
 pub mod value;
 
-pub use value::{Value, Properties};
+pub use value::{format_datetime, parse_datetime, typed, Value, Properties, TypedValue};
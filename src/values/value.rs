@@ -1,7 +1,21 @@
 use std::collections::HashMap;
+use std::fmt;
+use serde::de::{self, Deserializer, MapAccess, SeqAccess, Visitor};
+use serde::ser::{SerializeMap, SerializeSeq, Serializer};
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+/// 属性值
+///
+/// 序列化格式取决于序列化器：
+/// - 人类可读格式（JSON 等）：直接输出 `42`、`true`、`"text"`、`3.14`、`null`、`[...]`；
+///   整数与浮点按 JSON 数字字面量区分（`1` 为 Int，`1.0` 为 Float）；
+///   日期时间输出为 ISO-8601 字符串（`"2024-01-02T03:04:05.678Z"`），读回时是 Text；
+///   字节串输出为 0-255 的整数数组，读回时是 List
+/// - 二进制格式（bincode 等）：与 `#[derive]` 生成的外部标签格式保持一致，兼容已有存储数据
+///
+/// 需要跨系统无损往返时（例如 JavaScript 会把 `1.0` 当成 `1`），使用 [`TypedValue`]
+/// 输出带类型标签的形式：`{"type":"int","value":42}`。
+#[derive(Debug, Clone, PartialEq)]
 pub enum Value {
     Int(i64),
     Bool(bool),
@@ -9,6 +23,442 @@ pub enum Value {
     Float(f64),
     Null,
     List(Vec<Value>),
+    /// 日期时间：UTC 自 Unix 纪元起的毫秒数
+    DateTime(i64),
+    /// 二进制数据（缩略图、protobuf 负载等），不参与全文索引和组合索引
+    Bytes(Vec<u8>),
 }
 
 pub type Properties = HashMap<String, Value>;
+
+// ========== 二进制格式（与 derive 版本的编码一致） ==========
+
+#[derive(Serialize)]
+#[serde(rename = "Value")]
+enum BinaryValueRef<'a> {
+    Int(i64),
+    Bool(bool),
+    Text(&'a str),
+    Float(f64),
+    Null,
+    List(&'a [Value]),
+    DateTime(i64),
+    Bytes(&'a [u8]),
+}
+
+#[derive(Deserialize)]
+#[serde(rename = "Value")]
+enum BinaryValue {
+    Int(i64),
+    Bool(bool),
+    Text(String),
+    Float(f64),
+    Null,
+    List(Vec<Value>),
+    DateTime(i64),
+    Bytes(Vec<u8>),
+}
+
+impl Serialize for Value {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if !serializer.is_human_readable() {
+            let repr = match self {
+                Value::Int(i) => BinaryValueRef::Int(*i),
+                Value::Bool(b) => BinaryValueRef::Bool(*b),
+                Value::Text(s) => BinaryValueRef::Text(s),
+                Value::Float(f) => BinaryValueRef::Float(*f),
+                Value::Null => BinaryValueRef::Null,
+                Value::List(items) => BinaryValueRef::List(items),
+                Value::DateTime(ms) => BinaryValueRef::DateTime(*ms),
+                Value::Bytes(bytes) => BinaryValueRef::Bytes(bytes),
+            };
+            return repr.serialize(serializer);
+        }
+
+        match self {
+            Value::Int(i) => serializer.serialize_i64(*i),
+            Value::Bool(b) => serializer.serialize_bool(*b),
+            Value::Text(s) => serializer.serialize_str(s),
+            Value::Float(f) => serializer.serialize_f64(*f),
+            Value::Null => serializer.serialize_unit(),
+            Value::List(items) => {
+                let mut seq = serializer.serialize_seq(Some(items.len()))?;
+                for item in items {
+                    seq.serialize_element(item)?;
+                }
+                seq.end()
+            }
+            Value::DateTime(ms) => serializer.serialize_str(&format_datetime(*ms)),
+            Value::Bytes(bytes) => serializer.serialize_bytes(bytes),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Value {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if !deserializer.is_human_readable() {
+            return Ok(match BinaryValue::deserialize(deserializer)? {
+                BinaryValue::Int(i) => Value::Int(i),
+                BinaryValue::Bool(b) => Value::Bool(b),
+                BinaryValue::Text(s) => Value::Text(s),
+                BinaryValue::Float(f) => Value::Float(f),
+                BinaryValue::Null => Value::Null,
+                BinaryValue::List(items) => Value::List(items),
+                BinaryValue::DateTime(ms) => Value::DateTime(ms),
+                BinaryValue::Bytes(bytes) => Value::Bytes(bytes),
+            });
+        }
+        deserializer.deserialize_any(ValueVisitor)
+    }
+}
+
+struct ValueVisitor;
+
+impl<'de> Visitor<'de> for ValueVisitor {
+    type Value = Value;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a number, bool, string, null, list or typed value object")
+    }
+
+    fn visit_bool<E: de::Error>(self, v: bool) -> Result<Value, E> {
+        Ok(Value::Bool(v))
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<Value, E> {
+        Ok(Value::Int(v))
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Value, E> {
+        i64::try_from(v)
+            .map(Value::Int)
+            .map_err(|_| E::custom(format!("integer {} out of range for i64", v)))
+    }
+
+    fn visit_f64<E: de::Error>(self, v: f64) -> Result<Value, E> {
+        Ok(Value::Float(v))
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Value, E> {
+        Ok(Value::Text(v.to_string()))
+    }
+
+    fn visit_string<E: de::Error>(self, v: String) -> Result<Value, E> {
+        Ok(Value::Text(v))
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<Value, E> {
+        Ok(Value::Null)
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<Value, E> {
+        Ok(Value::Null)
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<Value, D::Error> {
+        Value::deserialize(deserializer)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Value, A::Error> {
+        let mut items = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        while let Some(item) = seq.next_element()? {
+            items.push(item);
+        }
+        Ok(Value::List(items))
+    }
+
+    /// 对象形式：类型标签 `{"type":"int","value":42}`，
+    /// 或 derive 风格的外部标签 `{"Int":42}`
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Value, A::Error> {
+        let mut tag: Option<String> = None;
+        let mut value: Option<Value> = None;
+
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                "type" => tag = Some(map.next_value()?),
+                "value" => value = Some(map.next_value()?),
+                "Int" | "Bool" | "Text" | "Float" | "Null" | "List" | "DateTime" | "Bytes" => {
+                    let inner = if key == "Null" {
+                        map.next_value::<de::IgnoredAny>()?;
+                        Value::Null
+                    } else {
+                        map.next_value()?
+                    };
+                    tag = Some(key.to_lowercase());
+                    value = Some(inner);
+                }
+                other => return Err(de::Error::unknown_field(other, &["type", "value"])),
+            }
+        }
+
+        let tag = tag.ok_or_else(|| de::Error::missing_field("type"))?;
+        coerce_typed(&tag, value.unwrap_or(Value::Null)).map_err(de::Error::custom)
+    }
+}
+
+/// 按类型标签校验/转换值
+fn coerce_typed(tag: &str, value: Value) -> Result<Value, String> {
+    match (tag, value) {
+        ("int", Value::Int(i)) => Ok(Value::Int(i)),
+        ("float", Value::Float(f)) => Ok(Value::Float(f)),
+        ("float", Value::Int(i)) => Ok(Value::Float(i as f64)),
+        ("bool", Value::Bool(b)) => Ok(Value::Bool(b)),
+        ("text", Value::Text(s)) => Ok(Value::Text(s)),
+        ("null", _) => Ok(Value::Null),
+        ("list", Value::List(items)) => Ok(Value::List(items)),
+        ("datetime", Value::Int(ms)) => Ok(Value::DateTime(ms)),
+        ("datetime", Value::Text(s)) => parse_datetime(&s),
+        ("datetime", Value::DateTime(ms)) => Ok(Value::DateTime(ms)),
+        ("bytes", Value::Bytes(bytes)) => Ok(Value::Bytes(bytes)),
+        ("bytes", Value::List(items)) => items
+            .iter()
+            .map(|item| match item {
+                Value::Int(b) => u8::try_from(*b).ok(),
+                _ => None,
+            })
+            .collect::<Option<Vec<u8>>>()
+            .map(Value::Bytes)
+            .ok_or_else(|| "bytes value must be a list of integers in 0..=255".to_string()),
+        (tag, value) => Err(format!("value {:?} does not match type '{}'", value, tag)),
+    }
+}
+
+impl Value {
+    /// 类型标签名（用于带类型的序列化形式）
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Value::Int(_) => "int",
+            Value::Bool(_) => "bool",
+            Value::Text(_) => "text",
+            Value::Float(_) => "float",
+            Value::Null => "null",
+            Value::List(_) => "list",
+            Value::DateTime(_) => "datetime",
+            Value::Bytes(_) => "bytes",
+        }
+    }
+
+    /// 字节串内容；其他类型返回 `None`
+    pub fn as_bytes(&self) -> Option<&[u8]> {
+        match self {
+            Value::Bytes(bytes) => Some(bytes),
+            _ => None,
+        }
+    }
+
+    /// 解析 ISO-8601 日期时间字符串为 `Value::DateTime`，见 [`parse_datetime`]
+    pub fn parse_datetime(s: &str) -> Result<Value, String> {
+        parse_datetime(s)
+    }
+
+    /// 日期时间格式化为 ISO-8601 UTC 字符串；其他类型返回 `None`
+    pub fn format_datetime(&self) -> Option<String> {
+        match self {
+            Value::DateTime(ms) => Some(format_datetime(*ms)),
+            _ => None,
+        }
+    }
+}
+
+// ========== 日期时间 ==========
+
+/// 解析 ISO-8601 日期时间为 `Value::DateTime`
+///
+/// 接受 `YYYY-MM-DD`、`YYYY-MM-DDTHH:MM[:SS[.fff]]`（`T` 也可以是空格），
+/// 后跟可选的时区 `Z` 或 `±HH:MM`；没有时区时按 UTC 处理。小数秒超过毫秒的部分被截断。
+/// 年份最多 6 位，保证毫秒时间戳不会溢出。
+pub fn parse_datetime(s: &str) -> Result<Value, String> {
+    let err = || format!("invalid datetime '{}'", s);
+    let num = |part: &str| -> Result<i64, String> {
+        if part.is_empty() || !part.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(err());
+        }
+        part.parse().map_err(|_| err())
+    };
+
+    let (date, time) = match s.find(['T', ' ']) {
+        Some(pos) => (&s[..pos], Some(&s[pos + 1..])),
+        None => (s, None),
+    };
+
+    let (sign, date) = match date.strip_prefix('-') {
+        Some(rest) => (-1, rest),
+        None => (1, date),
+    };
+    let mut parts = date.splitn(3, '-');
+    let (Some(y), Some(m), Some(d)) = (parts.next(), parts.next(), parts.next()) else {
+        return Err(err());
+    };
+    if y.len() > 6 {
+        return Err(err());
+    }
+    let (year, month, day) = (sign * num(y)?, num(m)?, num(d)?);
+    if !(1..=12).contains(&month) || day < 1 || day > days_in_month(year, month) {
+        return Err(err());
+    }
+
+    let mut millis = days_from_civil(year, month, day) * 86_400_000;
+    if let Some(time) = time {
+        // 拆出时区后缀
+        let (clock, offset_minutes) = if let Some(clock) = time.strip_suffix('Z') {
+            (clock, 0)
+        } else if let Some(pos) = time.rfind(['+', '-']) {
+            let (clock, zone) = time.split_at(pos);
+            let sign = if zone.starts_with('-') { -1 } else { 1 };
+            let (h, m) = zone[1..].split_once(':').ok_or_else(err)?;
+            let (h, m) = (num(h)?, num(m)?);
+            if h > 23 || m > 59 {
+                return Err(err());
+            }
+            (clock, sign * (h * 60 + m))
+        } else {
+            (time, 0)
+        };
+
+        let (hms, fraction) = match clock.split_once('.') {
+            Some((hms, fraction)) => (hms, Some(fraction)),
+            None => (clock, None),
+        };
+        let mut fields = hms.split(':');
+        let hour = num(fields.next().ok_or_else(err)?)?;
+        let minute = num(fields.next().ok_or_else(err)?)?;
+        let second = fields.next().map(num).transpose()?.unwrap_or(0);
+        if fields.next().is_some() || hour > 23 || minute > 59 || second > 59 {
+            return Err(err());
+        }
+        let fraction_ms = match fraction {
+            Some(f) => {
+                num(f)?;
+                let digits: String = f.chars().chain("00".chars()).take(3).collect();
+                num(&digits)?
+            }
+            None => 0,
+        };
+
+        millis += ((hour * 60 + minute - offset_minutes) * 60 + second) * 1000 + fraction_ms;
+    }
+    Ok(Value::DateTime(millis))
+}
+
+/// 毫秒时间戳格式化为 `YYYY-MM-DDTHH:MM:SS.fffZ`
+pub fn format_datetime(millis: i64) -> String {
+    let days = millis.div_euclid(86_400_000);
+    let ms_of_day = millis.rem_euclid(86_400_000);
+    let (year, month, day) = civil_from_days(days);
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+        year,
+        month,
+        day,
+        ms_of_day / 3_600_000,
+        ms_of_day / 60_000 % 60,
+        ms_of_day / 1000 % 60,
+        ms_of_day % 1000
+    )
+}
+
+fn days_in_month(year: i64, month: i64) -> i64 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// 公历日期到 1970-01-01 起的天数（Howard Hinnant 的 days_from_civil 算法）
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let mp = (month + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+/// [`days_from_civil`] 的逆运算
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+// ========== 带类型标签的序列化形式 ==========
+
+/// 以带类型标签的形式序列化 `Value`，保证 Int/Float 等类型无损往返
+///
+/// `TypedValue(Value::Float(1.0))` 序列化为 `{"type":"float","value":1.0}`；
+/// 列表元素同样带类型标签。反序列化同时接受普通形式和带类型形式。
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypedValue(pub Value);
+
+impl Serialize for TypedValue {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        typed::serialize(&self.0, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for TypedValue {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Value::deserialize(deserializer).map(TypedValue)
+    }
+}
+
+impl From<Value> for TypedValue {
+    fn from(value: Value) -> Self {
+        TypedValue(value)
+    }
+}
+
+/// 供 `#[serde(with = "crate::values::typed")]` 使用的带类型序列化函数
+pub mod typed {
+    use super::*;
+
+    struct TypedList<'a>(&'a [Value]);
+
+    impl Serialize for TypedList<'_> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let mut seq = serializer.serialize_seq(Some(self.0.len()))?;
+            for item in self.0 {
+                seq.serialize_element(&Typed(item))?;
+            }
+            seq.end()
+        }
+    }
+
+    struct Typed<'a>(&'a Value);
+
+    impl Serialize for Typed<'_> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serialize(self.0, serializer)
+        }
+    }
+
+    pub fn serialize<S: Serializer>(value: &Value, serializer: S) -> Result<S::Ok, S::Error> {
+        // 二进制格式本身无歧义，沿用普通编码
+        if !serializer.is_human_readable() {
+            return value.serialize(serializer);
+        }
+
+        let mut map = serializer.serialize_map(Some(2))?;
+        map.serialize_entry("type", value.type_name())?;
+        match value {
+            Value::List(items) => map.serialize_entry("value", &TypedList(items))?,
+            Value::DateTime(ms) => map.serialize_entry("value", ms)?,
+            other => map.serialize_entry("value", other)?,
+        }
+        map.end()
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Value, D::Error> {
+        Value::deserialize(deserializer)
+    }
+}
@@ -3,9 +3,15 @@
 // 提供多种图导出格式：
 // - JSON格式（用于前端可视化库）
 // - Graphviz DOT格式
+// - GraphML格式（用于 Gephi、yEd 等工具）
+//
+// DOT 和 GraphML 支持流式导出：逐个节点、逐条边直接写入 `Write`，不在内存中拼接完整输出。
 
-use crate::visualization::{GraphView, GraphFormat, VisNode, VisEdge};
+use crate::values::{Properties, Value};
+use crate::visualization::{GraphMetadata, GraphView, VisEdge, VisNode};
 use serde_json;
+use std::collections::BTreeMap;
+use std::io::{self, Write};
 
 /// 图导出trait
 pub trait GraphExport {
@@ -100,103 +106,152 @@ impl GraphExport for JsonExport {
     }
 }
 
+/// 流式导出：依次写入文件头、节点、边和文件尾
+///
+/// 节点必须全部在边之前写入。
+pub trait StreamingExport {
+    /// 写入文件头
+    fn begin(&mut self, metadata: &GraphMetadata) -> io::Result<()>;
+    /// 写入一个节点
+    fn node(&mut self, node: &VisNode) -> io::Result<()>;
+    /// 写入一条边
+    fn edge(&mut self, edge: &VisEdge) -> io::Result<()>;
+    /// 写入文件尾
+    fn finish(&mut self) -> io::Result<()>;
+}
+
+/// 把整个视图写入流式导出器
+pub fn stream_view(exporter: &mut impl StreamingExport, graph: &GraphView) -> io::Result<()> {
+    exporter.begin(&graph.metadata)?;
+    for node in &graph.nodes {
+        exporter.node(node)?;
+    }
+    for edge in &graph.edges {
+        exporter.edge(edge)?;
+    }
+    exporter.finish()
+}
+
+fn into_string(buffer: Vec<u8>) -> Result<String, String> {
+    String::from_utf8(buffer).map_err(|e| format!("UTF-8 error: {}", e))
+}
+
 /// Graphviz DOT导出
 ///
 /// 导出为Graphviz DOT格式，可用于生成图片
 pub struct DotExport;
 
+impl DotExport {
+    /// 把视图以 DOT 格式流式写入 `writer`
+    pub fn export_to_writer(graph: &GraphView, writer: impl Write) -> io::Result<()> {
+        stream_view(&mut DotWriter::new(writer), graph)
+    }
+}
+
 impl GraphExport for DotExport {
     fn export(graph: &GraphView) -> Result<String, String> {
-        let mut dot = String::new();
+        let mut buffer = Vec::new();
+        Self::export_to_writer(graph, &mut buffer).map_err(|e| format!("DOT export error: {}", e))?;
+        into_string(buffer)
+    }
+}
+
+/// DOT 格式的流式导出器
+pub struct DotWriter<W: Write> {
+    writer: W,
+    edges_started: bool,
+}
+
+impl<W: Write> DotWriter<W> {
+    pub fn new(writer: W) -> Self {
+        Self { writer, edges_started: false }
+    }
+}
+
+impl<W: Write> StreamingExport for DotWriter<W> {
+    fn begin(&mut self, metadata: &GraphMetadata) -> io::Result<()> {
+        let w = &mut self.writer;
 
         // 图类型
-        dot.push_str("digraph G {\n");
+        writeln!(w, "digraph G {{")?;
 
         // 全局设置
-        dot.push_str("  node [shape=box, style=rounded];\n");
-        dot.push_str("  rankdir=TB;\n");
+        writeln!(w, "  node [shape=box, style=rounded];")?;
+        writeln!(w, "  rankdir=TB;")?;
 
         // 添加元数据作为注释
-        if let Some(title) = &graph.metadata.title {
-            dot.push_str(&format!("  // Title: {}\n", title));
+        if let Some(title) = &metadata.title {
+            writeln!(w, "  // Title: {}", title)?;
         }
-        if let Some(layout) = &graph.metadata.layout_algorithm {
-            dot.push_str(&format!("  // Layout: {}\n", layout));
+        if let Some(layout) = &metadata.layout_algorithm {
+            writeln!(w, "  // Layout: {}", layout)?;
         }
-        dot.push_str(&format!("  // Nodes: {}, Edges: {}\n", graph.metadata.node_count, graph.metadata.edge_count));
-        dot.push_str("\n");
-
-        // 添加节点
-        for node in &graph.nodes {
-            let label = node.display_name();
-            dot.push_str(&format!("  \"{}\"", node.id));
+        writeln!(w, "  // Nodes: {}, Edges: {}", metadata.node_count, metadata.edge_count)?;
+        writeln!(w)
+    }
 
-            let mut attrs = Vec::new();
+    fn node(&mut self, node: &VisNode) -> io::Result<()> {
+        let mut attrs = Vec::new();
 
-            // 标签
-            attrs.push(format!("label=\"{}\"", escape_dot_string(&label)));
+        // 标签
+        attrs.push(format!("label=\"{}\"", escape_dot_string(&node.display_name())));
 
+        if let Some(style) = &node.style {
             // 颜色
-            if let Some(style) = &node.style {
-                if let Some(color) = &style.color {
-                    attrs.push(format!("fillcolor=\"{}\", style=filled", color));
-                }
+            if let Some(color) = &style.color {
+                attrs.push(format!("fillcolor=\"{}\", style=filled", color));
             }
-
             // 形状
-            if let Some(style) = &node.style {
-                if let Some(shape) = &style.shape {
-                    attrs.push(format!("shape={}", shape));
-                }
-            }
-
-            // 位置（如果已计算）
-            if let Some(pos) = &node.position {
-                attrs.push(format!("pos=\"{},{}\"", pos.x, pos.y));
-            }
-
-            if !attrs.is_empty() {
-                dot.push_str(&format!(" [{}]", attrs.join(", ")));
+            if let Some(shape) = &style.shape {
+                attrs.push(format!("shape={}", shape));
             }
+        }
 
-            dot.push_str(";\n");
+        // 位置（如果已计算）
+        if let Some(pos) = &node.position {
+            attrs.push(format!("pos=\"{},{}\"", pos.x, pos.y));
         }
 
-        dot.push_str("\n");
+        writeln!(self.writer, "  \"{}\" [{}];", node.id, attrs.join(", "))
+    }
 
-        // 添加边
-        for edge in &graph.edges {
-            dot.push_str(&format!("  \"{}\" -> \"{}\"", edge.source, edge.target));
+    fn edge(&mut self, edge: &VisEdge) -> io::Result<()> {
+        if !self.edges_started {
+            writeln!(self.writer)?;
+            self.edges_started = true;
+        }
 
-            let mut attrs = Vec::new();
+        let mut attrs = Vec::new();
 
-            // 标签
-            attrs.push(format!("label=\"{}\"", escape_dot_string(&edge.rel_type)));
+        // 标签
+        attrs.push(format!("label=\"{}\"", escape_dot_string(&edge.rel_type)));
 
+        if let Some(style) = &edge.style {
             // 颜色
-            if let Some(style) = &edge.style {
-                if let Some(color) = &style.color {
-                    attrs.push(format!("color=\"{}\"", color));
-                }
+            if let Some(color) = &style.color {
+                attrs.push(format!("color=\"{}\"", color));
             }
-
             // 样式
-            if let Some(style) = &edge.style {
-                if let Some(style_str) = &style.style {
-                    attrs.push(format!("style={}", style_str));
-                }
-            }
-
-            if !attrs.is_empty() {
-                dot.push_str(&format!(" [{}]", attrs.join(", ")));
+            if let Some(style_str) = &style.style {
+                attrs.push(format!("style={}", style_str));
             }
-
-            dot.push_str(";\n");
         }
 
-        dot.push_str("}\n");
+        writeln!(
+            self.writer,
+            "  \"{}\" -> \"{}\" [{}];",
+            edge.source,
+            edge.target,
+            attrs.join(", ")
+        )
+    }
 
-        Ok(dot)
+    fn finish(&mut self) -> io::Result<()> {
+        if !self.edges_started {
+            writeln!(self.writer)?;
+        }
+        writeln!(self.writer, "}}")?;
+        self.writer.flush()
     }
 }
 
@@ -208,3 +263,189 @@ fn escape_dot_string(s: &str) -> String {
         .replace('\r', "\\r")
         .replace('\t', "\\t")
 }
+
+/// GraphML导出
+///
+/// 节点标签和关系类型分别写入 `labels`、`type` 数据项，属性按名称声明为 GraphML key
+pub struct GraphMlExport;
+
+impl GraphMlExport {
+    /// 把视图以 GraphML 格式流式写入 `writer`
+    pub fn export_to_writer(graph: &GraphView, writer: impl Write) -> io::Result<()> {
+        let mut schema = GraphMlSchema::new();
+        for node in &graph.nodes {
+            schema.observe_node(&node.properties);
+        }
+        for edge in &graph.edges {
+            schema.observe_edge(&edge.properties);
+        }
+        stream_view(&mut GraphMlWriter::new(writer, schema), graph)
+    }
+}
+
+impl GraphExport for GraphMlExport {
+    fn export(graph: &GraphView) -> Result<String, String> {
+        let mut buffer = Vec::new();
+        Self::export_to_writer(graph, &mut buffer)
+            .map_err(|e| format!("GraphML export error: {}", e))?;
+        into_string(buffer)
+    }
+}
+
+/// GraphML 的属性声明
+///
+/// GraphML 要求在所有节点之前声明属性 key，因此流式导出前需要先扫描一遍属性名和类型。
+/// 同名属性出现多种类型时声明为 `string`。
+#[derive(Debug, Clone, Default)]
+pub struct GraphMlSchema {
+    node_keys: BTreeMap<String, &'static str>,
+    edge_keys: BTreeMap<String, &'static str>,
+}
+
+impl GraphMlSchema {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 记录一个节点的属性
+    pub fn observe_node(&mut self, props: &Properties) {
+        observe(&mut self.node_keys, props);
+    }
+
+    /// 记录一条边的属性
+    pub fn observe_edge(&mut self, props: &Properties) {
+        observe(&mut self.edge_keys, props);
+    }
+}
+
+fn observe(keys: &mut BTreeMap<String, &'static str>, props: &Properties) {
+    for (key, value) in props {
+        let Some(typ) = graphml_type(value) else {
+            continue;
+        };
+        keys.entry(key.clone())
+            .and_modify(|existing| {
+                if *existing != typ {
+                    *existing = "string";
+                }
+            })
+            .or_insert(typ);
+    }
+}
+
+/// 属性值对应的 GraphML 类型；`Null` 不导出
+fn graphml_type(value: &Value) -> Option<&'static str> {
+    match value {
+        Value::Int(_) => Some("long"),
+        Value::Float(_) => Some("double"),
+        Value::Bool(_) => Some("boolean"),
+        Value::Text(_) | Value::List(_) => Some("string"),
+        // GraphML 没有日期时间类型，按 ISO-8601 字符串导出
+        Value::DateTime(_) => Some("string"),
+        // 字节串按十六进制字符串导出
+        Value::Bytes(_) => Some("string"),
+        Value::Null => None,
+    }
+}
+
+/// GraphML 格式的流式导出器
+pub struct GraphMlWriter<W: Write> {
+    writer: W,
+    schema: GraphMlSchema,
+}
+
+impl<W: Write> GraphMlWriter<W> {
+    pub fn new(writer: W, schema: GraphMlSchema) -> Self {
+        Self { writer, schema }
+    }
+
+    fn data(&mut self, prefix: &str, props: &Properties) -> io::Result<()> {
+        // 按属性名排序，保证输出稳定
+        let mut entries: Vec<_> = props.iter().collect();
+        entries.sort_unstable_by_key(|(key, _)| key.as_str());
+        for (key, value) in entries {
+            let text = match value {
+                Value::Null => continue,
+                Value::Text(s) => s.clone(),
+                Value::Int(i) => i.to_string(),
+                Value::Float(f) => f.to_string(),
+                Value::Bool(b) => b.to_string(),
+                Value::List(_) => serde_json::to_string(value).unwrap_or_default(),
+                Value::DateTime(ms) => crate::values::format_datetime(*ms),
+                Value::Bytes(bytes) => bytes.iter().map(|b| format!("{:02x}", b)).collect(),
+            };
+            writeln!(
+                self.writer,
+                "      <data key=\"{}{}\">{}</data>",
+                prefix,
+                escape_xml(key),
+                escape_xml(&text)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl<W: Write> StreamingExport for GraphMlWriter<W> {
+    fn begin(&mut self, metadata: &GraphMetadata) -> io::Result<()> {
+        let w = &mut self.writer;
+        writeln!(w, "<?xml version=\"1.0\" encoding=\"UTF-8\"?>")?;
+        writeln!(w, "<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">")?;
+        writeln!(w, "  <key id=\"labels\" for=\"node\" attr.name=\"labels\" attr.type=\"string\"/>")?;
+        writeln!(w, "  <key id=\"type\" for=\"edge\" attr.name=\"type\" attr.type=\"string\"/>")?;
+        for (domain, prefix, keys) in [
+            ("node", "n:", &self.schema.node_keys),
+            ("edge", "e:", &self.schema.edge_keys),
+        ] {
+            for (name, typ) in keys {
+                let name = escape_xml(name);
+                writeln!(
+                    w,
+                    "  <key id=\"{}{}\" for=\"{}\" attr.name=\"{}\" attr.type=\"{}\"/>",
+                    prefix, name, domain, name, typ
+                )?;
+            }
+        }
+        match &metadata.title {
+            Some(title) => writeln!(w, "  <graph id=\"{}\" edgedefault=\"directed\">", escape_xml(title)),
+            None => writeln!(w, "  <graph id=\"G\" edgedefault=\"directed\">"),
+        }
+    }
+
+    fn node(&mut self, node: &VisNode) -> io::Result<()> {
+        writeln!(self.writer, "    <node id=\"n{}\">", node.id)?;
+        writeln!(
+            self.writer,
+            "      <data key=\"labels\">{}</data>",
+            escape_xml(&node.labels.join(":"))
+        )?;
+        self.data("n:", &node.properties)?;
+        writeln!(self.writer, "    </node>")
+    }
+
+    fn edge(&mut self, edge: &VisEdge) -> io::Result<()> {
+        match &edge.id {
+            Some(id) => write!(self.writer, "    <edge id=\"e{}\"", escape_xml(id))?,
+            None => write!(self.writer, "    <edge")?,
+        }
+        writeln!(self.writer, " source=\"n{}\" target=\"n{}\">", edge.source, edge.target)?;
+        writeln!(self.writer, "      <data key=\"type\">{}</data>", escape_xml(&edge.rel_type))?;
+        self.data("e:", &edge.properties)?;
+        writeln!(self.writer, "    </edge>")
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        writeln!(self.writer, "  </graph>")?;
+        writeln!(self.writer, "</graphml>")?;
+        self.writer.flush()
+    }
+}
+
+/// 转义XML文本和属性值
+fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
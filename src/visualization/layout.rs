@@ -6,7 +6,7 @@
 // - 层次布局
 
 use crate::visualization::{GraphView, VisNode, VisEdge, Position};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 /// 布局配置
 #[derive(Debug, Clone)]
@@ -19,6 +19,12 @@ pub struct LayoutConfig {
     pub node_spacing: f64,
     /// 迭代次数（用于力导向布局）
     pub iterations: usize,
+    /// 层次方向（用于层次布局）
+    pub rank_direction: HierarchicalDirection,
+    /// 相邻两层之间的距离（用于层次布局）
+    pub rank_separation: f64,
+    /// 同一层内相邻节点的距离（用于层次布局）
+    pub node_separation: f64,
 }
 
 impl Default for LayoutConfig {
@@ -28,6 +34,9 @@ impl Default for LayoutConfig {
             height: 600.0,
             node_spacing: 50.0,
             iterations: 100,
+            rank_direction: HierarchicalDirection::TopToBottom,
+            rank_separation: 100.0,
+            node_separation: 80.0,
         }
     }
 }
@@ -50,6 +59,21 @@ impl LayoutConfig {
         self.iterations = iterations;
         self
     }
+
+    pub fn with_rank_direction(mut self, direction: HierarchicalDirection) -> Self {
+        self.rank_direction = direction;
+        self
+    }
+
+    pub fn with_rank_separation(mut self, separation: f64) -> Self {
+        self.rank_separation = separation;
+        self
+    }
+
+    pub fn with_node_separation(mut self, separation: f64) -> Self {
+        self.node_separation = separation;
+        self
+    }
 }
 
 /// 布局算法trait
@@ -269,13 +293,15 @@ impl Layout for ForceDirectedLayout {
 
 /// 层次布局
 ///
-/// 按照层次结构排列节点（从上到下或从左到右）
+/// 按照层次结构排列节点（从上到下或从左到右）。层次由最长路径分层得到：
+/// 没有入边的节点在第 0 层，其余节点位于所有前驱的下一层，DAG 的每条边都指向更深的层。
+/// 有环时先按节点 ID 顺序做 DFS，忽略回边以确定性地打破环。
 #[derive(Debug)]
 pub struct HierarchicalLayout {
     config: LayoutConfig,
     /// 层次方向
     direction: HierarchicalDirection,
-    /// 层内间距
+    /// 层间距
     layer_spacing: f64,
     /// 节点间距
     node_spacing: f64,
@@ -290,12 +316,13 @@ pub enum HierarchicalDirection {
 }
 
 impl HierarchicalLayout {
+    /// 方向、层间距和节点间距取自 `config` 的 `rank_direction`、`rank_separation`、`node_separation`
     pub fn new(config: LayoutConfig) -> Self {
         Self {
+            direction: config.rank_direction,
+            layer_spacing: config.rank_separation,
+            node_spacing: config.node_separation,
             config,
-            direction: HierarchicalDirection::TopToBottom,
-            layer_spacing: 100.0,
-            node_spacing: 80.0,
         }
     }
 
@@ -315,65 +342,70 @@ impl HierarchicalLayout {
         self
     }
 
-    /// 计算节点的层次（使用BFS）
-    fn calculate_layers(&self, graph: &GraphView) -> HashMap<crate::storage::NodeId, usize> {
-        let mut layers: HashMap<crate::storage::NodeId, usize> = HashMap::new();
-        let mut visited = std::collections::HashSet::new();
-
-        // 构建邻接表
-        let mut adj: HashMap<crate::storage::NodeId, Vec<crate::storage::NodeId>> = HashMap::new();
-        let mut reverse_adj: HashMap<crate::storage::NodeId, Vec<crate::storage::NodeId>> = HashMap::new();
+    /// 设置同层节点间距
+    pub fn with_node_spacing(mut self, spacing: f64) -> Self {
+        self.node_spacing = spacing;
+        self
+    }
 
-        for node in &graph.nodes {
-            adj.entry(node.id).or_default();
-            reverse_adj.entry(node.id).or_default();
-        }
+    /// 计算节点的层次（最长路径分层）
+    fn calculate_layers(&self, graph: &GraphView) -> HashMap<crate::storage::NodeId, usize> {
+        let mut ids: Vec<crate::storage::NodeId> = graph.nodes.iter().map(|n| n.id).collect();
+        ids.sort_unstable();
 
+        // 构建邻接表（忽略自环和指向视图外节点的边），邻居按 ID 排序保证结果确定
+        let mut adj: HashMap<crate::storage::NodeId, Vec<crate::storage::NodeId>> =
+            ids.iter().map(|&id| (id, Vec::new())).collect();
         for edge in &graph.edges {
-            adj.entry(edge.source).or_default().push(edge.target);
-            reverse_adj.entry(edge.target).or_default().push(edge.source);
+            if edge.source != edge.target && adj.contains_key(&edge.target) {
+                if let Some(targets) = adj.get_mut(&edge.source) {
+                    targets.push(edge.target);
+                }
+            }
         }
-
-        // 找到根节点（没有入边的节点）
-        let mut roots: Vec<crate::storage::NodeId> = graph.nodes
-            .iter()
-            .filter(|n| reverse_adj.get(&n.id).map_or(true, |v| v.is_empty()))
-            .map(|n| n.id)
-            .collect();
-
-        // 如果没有根节点，使用所有节点
-        if roots.is_empty() {
-            roots = graph.nodes.iter().map(|n| n.id).collect();
+        for targets in adj.values_mut() {
+            targets.sort_unstable();
+            targets.dedup();
         }
 
-        // BFS计算层次
-        for root in roots {
-            if visited.contains(&root) {
+        // DFS 得到逆后序（即忽略回边后的拓扑序）
+        let mut visited = HashSet::new();
+        let mut order = Vec::with_capacity(ids.len());
+        for &root in &ids {
+            if !visited.insert(root) {
                 continue;
             }
-
-            let mut queue = std::collections::VecDeque::new();
-            queue.push_back((root, 0));
-            visited.insert(root);
-
-            while let Some((node_id, layer)) = queue.pop_front() {
-                layers.entry(node_id).or_insert(layer);
-
-                if let Some(neighbors) = adj.get(&node_id) {
-                    for &neighbor in neighbors {
-                        if !visited.contains(&neighbor) {
-                            visited.insert(neighbor);
-                            queue.push_back((neighbor, layer + 1));
+            // 栈中保存 (节点, 下一个待访问邻居的下标)
+            let mut stack = vec![(root, 0)];
+            while let Some((node, next)) = stack.last_mut() {
+                let node = *node;
+                match adj[&node].get(*next) {
+                    Some(&neighbor) => {
+                        *next += 1;
+                        if visited.insert(neighbor) {
+                            stack.push((neighbor, 0));
                         }
                     }
+                    None => {
+                        order.push(node);
+                        stack.pop();
+                    }
                 }
             }
         }
-
-        // 处理未访问的节点（孤立节点）
-        for node in &graph.nodes {
-            if !visited.contains(&node.id) {
-                layers.insert(node.id, 0);
+        order.reverse();
+
+        // 按拓扑序松弛：只保留指向拓扑序中更靠后节点的边，回边被忽略
+        let position: HashMap<crate::storage::NodeId, usize> =
+            order.iter().enumerate().map(|(i, &id)| (id, i)).collect();
+        let mut layers: HashMap<crate::storage::NodeId, usize> =
+            ids.iter().map(|&id| (id, 0)).collect();
+        for &node in &order {
+            let layer = layers[&node];
+            for &neighbor in &adj[&node] {
+                if position[&neighbor] > position[&node] && layers[&neighbor] < layer + 1 {
+                    layers.insert(neighbor, layer + 1);
+                }
             }
         }
 
@@ -389,28 +421,26 @@ impl Layout for HierarchicalLayout {
 
         let layers = self.calculate_layers(graph);
 
-        // 按层分组节点
+        // 按层分组节点，层内按节点 ID 排序
         let mut layer_nodes: HashMap<usize, Vec<crate::storage::NodeId>> = HashMap::new();
         for (node_id, layer) in &layers {
             layer_nodes.entry(*layer).or_default().push(*node_id);
         }
 
-        // 计算每层的位置
-        let max_layer = layer_nodes.keys().cloned().max().unwrap_or(0);
-
-        for (layer, nodes) in layer_nodes {
+        for (layer, mut nodes) in layer_nodes {
+            nodes.sort_unstable();
             let layer_size = nodes.len() as f64;
 
             for (i, node_id) in nodes.iter().enumerate() {
+                // 层内节点以画布中线为中心对称排列
+                let offset = (i as f64 - (layer_size - 1.0) / 2.0) * self.node_spacing;
                 let pos = if self.direction == HierarchicalDirection::TopToBottom {
-                    let x = self.config.width / 2.0
-                        + (i as f64 - layer_size / 2.0) * self.node_spacing;
+                    let x = self.config.width / 2.0 + offset;
                     let y = 50.0 + layer as f64 * self.layer_spacing;
                     Position::new(x, y)
                 } else {
                     let x = 50.0 + layer as f64 * self.layer_spacing;
-                    let y = self.config.height / 2.0
-                        + (i as f64 - layer_size / 2.0) * self.node_spacing;
+                    let y = self.config.height / 2.0 + offset;
                     Position::new(x, y)
                 };
 
@@ -3,6 +3,7 @@
 // 提供图数据的可视化和布局功能，包括：
 // - 图数据序列化（JSON格式）
 // - Graphviz DOT格式导出
+// - GraphML格式导出（DOT 和 GraphML 支持流式写入）
 // - 多种布局算法（圆形、力导向、层次布局）
 
 pub mod layout;
@@ -13,12 +14,15 @@ pub use layout::{
     LayoutNode, LayoutEdge,
 };
 pub use export::{
-    GraphExport, JsonExport, DotExport,
+    GraphExport, JsonExport, DotExport, GraphMlExport,
+    StreamingExport, DotWriter, GraphMlWriter, GraphMlSchema,
 };
 
 use crate::storage::NodeId;
-use crate::values::Properties;
+use crate::values::{Properties, Value};
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::io::{self, Write};
 
 /// 可视化的图视图
 ///
@@ -65,6 +69,19 @@ impl GraphView {
         match format {
             GraphFormat::Json => JsonExport::export(self),
             GraphFormat::Dot => DotExport::export(self),
+            GraphFormat::GraphMl => GraphMlExport::export(self),
+        }
+    }
+
+    /// 导出为指定格式并写入 `writer`；DOT 和 GraphML 逐行写入，不在内存中拼接完整输出
+    pub fn export_to_writer(&self, format: GraphFormat, mut writer: impl Write) -> io::Result<()> {
+        match format {
+            GraphFormat::Json => {
+                let json = JsonExport::export(self).map_err(io::Error::other)?;
+                writer.write_all(json.as_bytes())
+            }
+            GraphFormat::Dot => DotExport::export_to_writer(self, writer),
+            GraphFormat::GraphMl => GraphMlExport::export_to_writer(self, writer),
         }
     }
 
@@ -77,6 +94,81 @@ impl GraphView {
     pub fn edge_count(&self) -> usize {
         self.edges.len()
     }
+
+    /// 限制高度数节点的边数：度数超过 `max_degree` 的节点只保留前 `max_degree` 条边（按视图中的顺序）
+    ///
+    /// 被截断的节点记录在 `metadata.truncated` 中；返回删除的边数。
+    pub fn prune_by_degree(&mut self, max_degree: usize) -> usize {
+        self.prune_edges(max_degree, |_| 0.0)
+    }
+
+    /// 与 [`GraphView::prune_by_degree`] 相同，但优先保留 `weight_property` 较大的边
+    ///
+    /// 缺少该属性或不是数值的边按 0 处理，权重相同时按视图中的顺序保留。
+    pub fn prune_by_degree_weighted(&mut self, max_degree: usize, weight_property: &str) -> usize {
+        self.prune_edges(max_degree, |edge| {
+            match edge.properties.get(weight_property) {
+                Some(Value::Int(i)) => *i as f64,
+                Some(Value::Float(f)) => *f,
+                _ => 0.0,
+            }
+        })
+    }
+
+    /// 按可见性过滤所有节点和边的属性（导出前剥离敏感字段）
+    pub fn filter_properties(&mut self, filter: &PropertyFilter) {
+        for node in &mut self.nodes {
+            filter.apply(&mut node.properties);
+        }
+        for edge in &mut self.edges {
+            filter.apply(&mut edge.properties);
+        }
+    }
+
+    fn prune_edges(&mut self, max_degree: usize, weight: impl Fn(&VisEdge) -> f64) -> usize {
+        // 每个节点关联的边下标（自环只计一次）
+        let mut incident: BTreeMap<NodeId, Vec<usize>> = BTreeMap::new();
+        for (i, edge) in self.edges.iter().enumerate() {
+            incident.entry(edge.source).or_default().push(i);
+            if edge.target != edge.source {
+                incident.entry(edge.target).or_default().push(i);
+            }
+        }
+
+        // 高度数节点各自挑选要保留的边；任一端点不保留的边都会被删除。
+        // 两个高度数节点之间的边可能被一端丢弃，此时另一端保留的边数会少于上限。
+        let mut dropped = HashSet::new();
+        let mut hubs = Vec::new();
+        for (&node, edges) in &incident {
+            if edges.len() <= max_degree {
+                continue;
+            }
+            hubs.push((node, edges.len()));
+            let mut ranked = edges.clone();
+            // 稳定排序：权重相同的边保持原顺序
+            ranked.sort_by(|&a, &b| weight(&self.edges[b]).total_cmp(&weight(&self.edges[a])));
+            dropped.extend(ranked.into_iter().skip(max_degree));
+        }
+
+        if dropped.is_empty() {
+            return 0;
+        }
+
+        let mut index = 0;
+        self.edges.retain(|_| {
+            let keep = !dropped.contains(&index);
+            index += 1;
+            keep
+        });
+        self.metadata.edge_count = self.edges.len();
+
+        for (node, original) in hubs {
+            let remaining = incident[&node].iter().filter(|i| !dropped.contains(*i)).count();
+            *self.metadata.truncated.entry(node).or_insert(0) += original - remaining;
+        }
+
+        dropped.len()
+    }
 }
 
 impl Default for GraphView {
@@ -85,6 +177,75 @@ impl Default for GraphView {
     }
 }
 
+/// 导出时的属性可见性过滤
+///
+/// 不在允许列表中（设置了允许列表时）或在拒绝列表中的属性不可见。
+/// 不可见的属性默认直接删除；设置了占位文本时保留键名，值替换为该文本。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PropertyFilter {
+    allow: Option<HashSet<String>>,
+    deny: HashSet<String>,
+    redaction: Option<String>,
+}
+
+impl PropertyFilter {
+    /// 只保留给定的属性
+    pub fn allow<I, S>(keys: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            allow: Some(keys.into_iter().map(Into::into).collect()),
+            ..Self::default()
+        }
+    }
+
+    /// 隐藏给定的属性
+    pub fn deny<I, S>(keys: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self::default().denying(keys)
+    }
+
+    /// 在已有过滤上追加要隐藏的属性
+    pub fn denying<I, S>(mut self, keys: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.deny.extend(keys.into_iter().map(Into::into));
+        self
+    }
+
+    /// 不可见属性改为用占位文本脱敏，而不是删除
+    pub fn redact_with(mut self, placeholder: impl Into<String>) -> Self {
+        self.redaction = Some(placeholder.into());
+        self
+    }
+
+    /// 属性是否可见
+    pub fn is_visible(&self, key: &str) -> bool {
+        self.allow.as_ref().is_none_or(|allow| allow.contains(key)) && !self.deny.contains(key)
+    }
+
+    /// 对一组属性应用过滤
+    pub fn apply(&self, props: &mut Properties) {
+        match &self.redaction {
+            Some(placeholder) => {
+                for (key, value) in props.iter_mut() {
+                    if !self.is_visible(key) {
+                        *value = Value::Text(placeholder.clone());
+                    }
+                }
+            }
+            None => props.retain(|key, _| self.is_visible(key)),
+        }
+    }
+}
+
 /// 可视化的节点
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VisNode {
@@ -133,7 +294,7 @@ impl VisNode {
     pub fn display_name(&self) -> String {
         self.properties
             .get("name")
-            .and_then(|v| if let crate::values::Value::Text(s) = v { Some(s.clone()) } else { None })
+            .and_then(|v| if let Value::Text(s) = v { Some(s.clone()) } else { None })
             .unwrap_or_else(|| format!("{}", self.id))
     }
 }
@@ -292,6 +453,9 @@ pub struct GraphMetadata {
     pub created_at: Option<String>,
     /// 布局算法
     pub layout_algorithm: Option<String>,
+    /// 被 `prune_by_degree` 截断的节点 -> 被隐藏的边数（前端可显示为 "+N more"）
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub truncated: BTreeMap<NodeId, usize>,
 }
 
 impl Default for GraphMetadata {
@@ -302,6 +466,7 @@ impl Default for GraphMetadata {
             title: None,
             created_at: None,
             layout_algorithm: None,
+            truncated: BTreeMap::new(),
         }
     }
 }
@@ -313,6 +478,8 @@ pub enum GraphFormat {
     Json,
     /// Graphviz DOT格式
     Dot,
+    /// GraphML格式
+    GraphMl,
 }
 
 /// 位置坐标
@@ -300,3 +300,40 @@ fn test_aggregation_edge_cases() {
     let stdev = Query::new(&db).from_label("Student").stdev("age");
     assert!((stdev.unwrap() - 7.071).abs() < 0.01);
 }
+
+#[test]
+fn test_aggregations_skip_nan_and_infinite_values() {
+    let mut db = GraphDatabase::new_in_memory();
+
+    for value in [1.0, 2.0, f64::NAN, 3.0, f64::INFINITY, f64::NEG_INFINITY] {
+        let mut props = Properties::new();
+        props.insert("value".to_string(), Value::Float(value));
+        db.create_node(vec!["Data"], props);
+    }
+
+    // 只有 1.0, 2.0, 3.0 参与计算
+    let median = Query::new(&db).from_label("Data").percentile_cont("value", 0.5);
+    assert_eq!(median, Some(2.0));
+    let max = Query::new(&db).from_label("Data").percentile_cont("value", 1.0);
+    assert_eq!(max, Some(3.0));
+    assert_eq!(Query::new(&db).from_label("Data").variance("value"), Some(1.0));
+    assert_eq!(Query::new(&db).from_label("Data").stdev("value"), Some(1.0));
+
+    // NaN 百分位数无效
+    assert_eq!(Query::new(&db).from_label("Data").percentile_cont("value", f64::NAN), None);
+}
+
+#[test]
+fn test_aggregations_all_values_non_finite() {
+    let mut db = GraphDatabase::new_in_memory();
+
+    for value in [f64::NAN, f64::INFINITY] {
+        let mut props = Properties::new();
+        props.insert("value".to_string(), Value::Float(value));
+        db.create_node(vec!["Data"], props);
+    }
+
+    assert_eq!(Query::new(&db).from_label("Data").percentile_cont("value", 0.5), None);
+    assert_eq!(Query::new(&db).from_label("Data").variance("value"), None);
+    assert_eq!(Query::new(&db).from_label("Data").stdev("value"), None);
+}
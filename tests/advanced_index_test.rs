@@ -1,7 +1,7 @@
 // 高级索引测试
 // 测试全文索引和范围索引功能
 
-use rs_graphdb::GraphDatabase;
+use rs_graphdb::{GraphDatabase, TextAnalyzer};
 use rs_graphdb::values::{Properties, Value};
 
 // 辅助函数：创建测试属性
@@ -122,6 +122,38 @@ fn test_fulltext_index_multiple_words() {
     assert_eq!(result, vec![alice]);
 }
 
+#[test]
+fn test_fulltext_case_sensitive_analyzer() {
+    let mut db = GraphDatabase::new_in_memory();
+    db.set_fulltext_analyzer("Doc", "code", TextAnalyzer::new().case_sensitive());
+
+    let mut ids = Vec::new();
+    for text in ["API", "api"] {
+        let mut props = Properties::new();
+        props.insert("code".to_string(), Value::Text(text.to_string()));
+        props.insert("bio".to_string(), Value::Text(text.to_string()));
+        let id = db.create_node(vec!["Doc"], props);
+        db.add_fulltext_index("Doc", "code", id);
+        db.add_fulltext_index("Doc", "bio", id);
+        ids.push(id);
+    }
+
+    // 区分大小写的字段：查询也按同一分析器处理
+    assert_eq!(db.search_fulltext("Doc", "code", "API"), vec![ids[0]]);
+    assert_eq!(db.search_fulltext("Doc", "code", "api"), vec![ids[1]]);
+
+    // 默认字段不区分大小写
+    let mut bio = db.search_fulltext("Doc", "bio", "API");
+    bio.sort();
+    let mut expected = ids.clone();
+    expected.sort();
+    assert_eq!(bio, expected);
+
+    // 重建索引后分析器仍然生效
+    db.rebuild_all_indexes();
+    assert_eq!(db.search_fulltext("Doc", "code", "API"), vec![ids[0]]);
+}
+
 // ========== 范围索引测试 ==========
 
 #[test]
@@ -240,6 +272,99 @@ fn test_range_index_boundary_values() {
     assert_eq!(result.len(), 2);
 }
 
+#[test]
+fn test_range_index_top_n_after_updates() {
+    let mut db = GraphDatabase::new_in_memory();
+
+    let mut players = Vec::new();
+    for (i, score) in [10i64, 50, 30, 20, 40].iter().enumerate() {
+        let mut props = Properties::new();
+        props.insert("name".to_string(), Value::Text(format!("p{}", i)));
+        props.insert("score".to_string(), Value::Int(*score));
+        let id = db.create_node(vec!["Player"], props);
+        db.add_range_index("Player", "score", id);
+        players.push(id);
+    }
+    let [p0, p1, p2, p3, p4] = players[..] else { unreachable!() };
+
+    assert_eq!(db.top_n("Player", "score", 3, true), vec![p1, p4, p2]);
+    assert_eq!(db.top_n("Player", "score", 2, false), vec![p0, p3]);
+
+    // 更新分数：p0 升到榜首（浮点数与整数混排），p1 跌到末尾
+    let mut props = Properties::new();
+    props.insert("score".to_string(), Value::Float(99.5));
+    db.update_node_props(p0, props);
+    let mut props = Properties::new();
+    props.insert("score".to_string(), Value::Int(5));
+    db.update_node_props(p1, props);
+
+    assert_eq!(db.top_n("Player", "score", 3, true), vec![p0, p4, p2]);
+    assert_eq!(db.top_n("Player", "score", 2, false), vec![p1, p3]);
+
+    // 删除的节点不再出现，n 超过节点数时返回全部
+    db.delete_node(p4);
+    assert_eq!(db.top_n("Player", "score", 10, true), vec![p0, p2, p3, p1]);
+}
+
+#[test]
+fn test_range_index_follows_removed_values_and_transactions() {
+    let mut db = GraphDatabase::new_in_memory();
+    let score = |v: Value| {
+        let mut props = Properties::new();
+        props.insert("score".to_string(), v);
+        props
+    };
+
+    let mut players = Vec::new();
+    for s in [10i64, 20, 30] {
+        let id = db.create_node(vec!["Player"], score(Value::Int(s)));
+        db.add_range_index("Player", "score", id);
+        players.push(id);
+    }
+    let [p0, p1, p2] = players[..] else { unreachable!() };
+
+    // 值被清空后，旧值的索引项随之移除
+    db.update_node_props(p0, score(Value::Null));
+    assert_eq!(db.top_n("Player", "score", 10, false), vec![p1, p2]);
+    assert!(db.range_less_than("Player", "score", Value::Int(15)).is_empty());
+
+    // 事务提交后按新值维护，删除的节点不再出现
+    let tx = db.begin_tx().unwrap();
+    db.update_node_props_in_tx(tx, p1, score(Value::Int(50))).unwrap();
+    db.delete_node_in_tx(tx, p2).unwrap();
+    db.commit_tx(tx).unwrap();
+    assert_eq!(db.top_n("Player", "score", 10, false), vec![p1]);
+    assert!(db.range_between("Player", "score", Value::Int(15), Value::Int(35)).is_empty());
+}
+
+#[test]
+fn test_range_index_covers_nodes_created_after_registration() {
+    let mut db = GraphDatabase::new_in_memory();
+    let score = |s: i64| {
+        let mut props = Properties::new();
+        props.insert("score".to_string(), Value::Int(s));
+        props
+    };
+
+    let first = db.create_node(vec!["Player"], score(10));
+    db.add_range_index("Player", "score", first);
+
+    // 注册之后通过各个创建入口写入的节点都进入范围索引
+    let created = db.create_node(vec!["Player"], score(20));
+    let tried = db.try_create_node(vec!["Player"], score(30)).unwrap();
+    let batch = db.batch_create_nodes(vec![
+        (vec!["Player".to_string()], score(40)),
+        (vec!["Player".to_string()], score(50)),
+    ]);
+    // 其他标签的节点不受影响
+    db.create_node(vec!["Coach"], score(60));
+
+    let mut hits = db.range_greater_than("Player", "score", Value::Int(15));
+    hits.sort();
+    assert_eq!(hits, vec![created, tried, batch[0], batch[1]]);
+    assert_eq!(db.top_n("Player", "score", 1, true), vec![batch[1]]);
+}
+
 // ========== 混合索引测试 ==========
 
 #[test]
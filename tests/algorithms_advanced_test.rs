@@ -3,6 +3,7 @@
 
 use rs_graphdb::{GraphDatabase, algorithms};
 use rs_graphdb::storage::mem_store::MemStore;
+use rs_graphdb::storage::NodeId;
 use rs_graphdb::values::{Properties, Value};
 
 fn make_node_with_pos(name: &str, x: i64, y: i64) -> Properties {
@@ -296,7 +297,7 @@ fn test_astar_simple_path() {
     db.create_rel(b, c, "ROAD", Properties::new());
 
     // 使用欧几里得距离作为启发式
-    let heuristic = |node: u64| -> f64 {
+    let heuristic = |node: NodeId| -> f64 {
         if let Some(n) = db.get_node(node) {
             let x = n.get("x").and_then(|v| match v {
                 Value::Int(i) => Some(*i as f64),
@@ -332,7 +333,7 @@ fn test_astar_euclidean() {
     db.create_rel(a, b, "ROAD", Properties::new());
     db.create_rel(b, c, "ROAD", Properties::new());
 
-    let get_pos = |node: u64| -> (f64, f64) {
+    let get_pos = |node: NodeId| -> (f64, f64) {
         if let Some(n) = db.get_node(node) {
             let x = n.get("x").and_then(|v| match v {
                 Value::Int(i) => Some(*i as f64),
@@ -366,7 +367,7 @@ fn test_astar_manhattan() {
     db.create_rel(a, b, "ROAD", Properties::new());
     db.create_rel(b, c, "ROAD", Properties::new());
 
-    let get_pos = |node: u64| -> (f64, f64) {
+    let get_pos = |node: NodeId| -> (f64, f64) {
         if let Some(n) = db.get_node(node) {
             let x = n.get("x").and_then(|v| match v {
                 Value::Int(i) => Some(*i as f64),
@@ -398,7 +399,7 @@ fn test_astar_no_path() {
 
     // A 和 B 之间没有路径
 
-    let get_pos = |node: u64| -> (f64, f64) {
+    let get_pos = |node: NodeId| -> (f64, f64) {
         if let Some(n) = db.get_node(node) {
             let x = n.get("x").and_then(|v| match v {
                 Value::Int(i) => Some(*i as f64),
@@ -425,7 +426,7 @@ fn test_astar_same_node() {
 
     let a = db.create_node(vec!["User"], make_node_with_pos("A", 0, 0));
 
-    let get_pos = |node: u64| -> (f64, f64) {
+    let get_pos = |node: NodeId| -> (f64, f64) {
         if let Some(n) = db.get_node(node) {
             let x = n.get("x").and_then(|v| match v {
                 Value::Int(i) => Some(*i as f64),
@@ -470,7 +471,7 @@ fn test_astar_multiple_paths() {
     db.create_rel(n2, end, "EDGE", Properties::new());
     db.create_rel(n1, n2, "EDGE", Properties::new());
 
-    let get_pos = |node: u64| -> (f64, f64) {
+    let get_pos = |node: NodeId| -> (f64, f64) {
         if let Some(n) = db.get_node(node) {
             let x = n.get("x").and_then(|v| match v {
                 Value::Int(i) => Some(*i as f64),
@@ -496,3 +497,120 @@ fn test_astar_multiple_paths() {
     assert_eq!(p[0], start);
     assert_eq!(p[p.len() - 1], end);
 }
+
+// ==================== 限时执行测试 ====================
+
+/// 环形网格：每个节点连向后面 1 和 7 个位置的节点
+fn build_ring(n: u64) -> GraphDatabase<MemStore> {
+    let mut db = GraphDatabase::<MemStore>::new_in_memory();
+    let ids: Vec<_> = (0..n).map(|_| db.create_node(vec!["N"], Properties::new())).collect();
+    for i in 0..ids.len() {
+        db.create_rel(ids[i], ids[(i + 1) % ids.len()], "E", Properties::new());
+        db.create_rel(ids[i], ids[(i + 7) % ids.len()], "E", Properties::new());
+    }
+    db
+}
+
+#[test]
+fn test_algorithms_time_out_on_large_graph() {
+    use std::time::{Duration, Instant};
+
+    let db = build_ring(3000);
+    let started = Instant::now();
+
+    let err = algorithms::betweenness_centrality_within(&db, Duration::from_millis(1)).unwrap_err();
+    assert!(err.elapsed >= Duration::from_millis(1));
+    assert_eq!(err.partial.len(), 3000);
+
+    let err = algorithms::closeness_centrality_within(&db, Duration::from_millis(1)).unwrap_err();
+    assert!(err.partial.len() < 3000);
+
+    let err = algorithms::pagerank_until_converged_within(&db, 0.85, 0.0, 10_000, Duration::from_millis(1))
+        .unwrap_err();
+    assert_eq!(err.partial.len(), 3000);
+
+    let err = algorithms::eigenvector_centrality_within(&db, 10_000, 0.0, Duration::ZERO).unwrap_err();
+    assert_eq!(err.partial.len(), 3000);
+
+    // 部分结果仍是覆盖全部节点的合法划分
+    let err = algorithms::louvain_within(&db, 1.0, 10, Duration::ZERO).unwrap_err();
+    assert_eq!(err.partial.len(), 3000);
+
+    // 全部调用都应该很快返回，而不是跑完整个算法
+    assert!(started.elapsed() < Duration::from_secs(10));
+}
+
+#[test]
+fn test_algorithms_within_generous_deadline_match_unbounded() {
+    use std::time::Duration;
+
+    let db = build_ring(30);
+    let deadline = Duration::from_secs(60);
+
+    assert_eq!(
+        algorithms::closeness_centrality_within(&db, deadline).unwrap(),
+        algorithms::closeness_centrality(&db)
+    );
+    assert_eq!(
+        algorithms::betweenness_centrality_within(&db, deadline).unwrap(),
+        algorithms::betweenness_centrality(&db)
+    );
+    assert_eq!(
+        algorithms::louvain_within(&db, 1.0, 10, deadline).unwrap(),
+        algorithms::louvain_resolution(&db, 1.0, 10)
+    );
+}
+
+// ==================== 图着色测试 ====================
+
+/// 每条关系（自环除外）两端颜色不同
+fn assert_valid_coloring(db: &GraphDatabase<MemStore>, colors: &std::collections::HashMap<NodeId, usize>) {
+    for node in db.all_stored_nodes() {
+        for rel in db.neighbors_out(node.id).filter(|r| r.start != r.end) {
+            assert_ne!(colors[&rel.start], colors[&rel.end], "edge {} -> {}", rel.start, rel.end);
+        }
+    }
+}
+
+#[test]
+fn test_greedy_coloring_bipartite() {
+    let mut db = GraphDatabase::<MemStore>::new_in_memory();
+
+    // 完全二部图 K(3,4)，关系方向混合
+    let left: Vec<_> = (0..3).map(|_| db.create_node(vec!["L"], Properties::new())).collect();
+    let right: Vec<_> = (0..4).map(|_| db.create_node(vec!["R"], Properties::new())).collect();
+    for (i, &l) in left.iter().enumerate() {
+        for &r in &right {
+            if i % 2 == 0 {
+                db.create_rel(l, r, "EDGE", Properties::new());
+            } else {
+                db.create_rel(r, l, "EDGE", Properties::new());
+            }
+        }
+    }
+
+    let colors = algorithms::greedy_coloring(&db);
+    assert_eq!(colors.len(), 7);
+    assert_valid_coloring(&db, &colors);
+    assert_eq!(algorithms::color_count(&colors), 2);
+}
+
+#[test]
+fn test_greedy_coloring_triangle() {
+    let mut db = GraphDatabase::<MemStore>::new_in_memory();
+    let a = db.create_node(vec!["User"], Properties::new());
+    let b = db.create_node(vec!["User"], Properties::new());
+    let c = db.create_node(vec!["User"], Properties::new());
+    db.create_rel(a, b, "EDGE", Properties::new());
+    db.create_rel(b, c, "EDGE", Properties::new());
+    db.create_rel(c, a, "EDGE", Properties::new());
+    // 孤立节点和自环不影响颜色数
+    let lonely = db.create_node(vec!["User"], Properties::new());
+    db.create_rel(lonely, lonely, "SELF", Properties::new());
+
+    let colors = algorithms::greedy_coloring(&db);
+    assert_valid_coloring(&db, &colors);
+    assert_eq!(algorithms::color_count(&colors), 3);
+    assert_eq!(colors[&lonely], 0);
+    assert_eq!(algorithms::color_count(&Default::default()), 0);
+}
@@ -3,6 +3,7 @@
 
 use rs_graphdb::{GraphDatabase, algorithms};
 use rs_graphdb::storage::mem_store::MemStore;
+use rs_graphdb::storage::{NodeId, RelId};
 use rs_graphdb::values::{Properties, Value};
 
 fn make_user(name: &str) -> Properties {
@@ -240,6 +241,45 @@ fn test_pagerank_dangling_nodes() {
     assert!((sum - 1.0).abs() < 1e-6);
 }
 
+#[test]
+fn test_pagerank_on_single_rel_type_changes_ranking() {
+    let mut db = GraphDatabase::<MemStore>::new_in_memory();
+
+    let a = db.create_node(vec!["Paper"], make_user("A"));
+    let b = db.create_node(vec!["Paper"], make_user("B"));
+    let c = db.create_node(vec!["Paper"], make_user("C"));
+    let d = db.create_node(vec!["Paper"], make_user("D"));
+
+    // 引用关系都指向 C，大量点赞关系指向 A
+    db.create_rel(a, c, "CITES", Properties::new());
+    db.create_rel(b, c, "CITES", Properties::new());
+    for from in [b, c, d] {
+        db.create_rel(from, a, "LIKES", Properties::new());
+    }
+    db.create_rel(d, b, "LIKES", Properties::new());
+
+    let top = |ranks: &std::collections::HashMap<NodeId, f64>| {
+        *ranks
+            .iter()
+            .max_by(|x, y| x.1.total_cmp(y.1))
+            .unwrap()
+            .0
+    };
+
+    let all = algorithms::pagerank(&db, 0.85, 50);
+    assert_eq!(top(&all), a);
+
+    let cites = algorithms::pagerank_on(&db, &["CITES"], 0.85, 50);
+    assert_eq!(top(&cites), c);
+    assert!((cites.values().sum::<f64>() - 1.0).abs() < 1e-6);
+
+    // 列出全部类型与不过滤等价
+    let both = algorithms::pagerank_on(&db, &["CITES", "LIKES"], 0.85, 50);
+    for (id, rank) in &all {
+        assert!((both[id] - rank).abs() < 1e-12);
+    }
+}
+
 // ==================== 社区检测测试 ====================
 
 #[test]
@@ -313,6 +353,81 @@ fn test_louvain_two_communities() {
     assert_ne!(comm_a, comm_d);
 }
 
+/// 由 `cliques` 个 4 节点完全子图组成的环，相邻子图之间只有一条关系
+fn ring_of_cliques(cliques: usize) -> (GraphDatabase<MemStore>, Vec<Vec<NodeId>>) {
+    let mut db = GraphDatabase::<MemStore>::new_in_memory();
+    let mut groups = Vec::new();
+    for g in 0..cliques {
+        let ids: Vec<NodeId> = (0..4)
+            .map(|i| db.create_node(vec!["User"], make_user(&format!("G{}N{}", g, i))))
+            .collect();
+        for i in 0..4 {
+            for j in i + 1..4 {
+                db.create_rel(ids[i], ids[j], "KNOWS", Properties::new());
+            }
+        }
+        groups.push(ids);
+    }
+    for g in 0..cliques {
+        let next = (g + 1) % cliques;
+        db.create_rel(groups[g][3], groups[next][0], "KNOWS", Properties::new());
+    }
+    (db, groups)
+}
+
+fn community_count(communities: &std::collections::HashMap<NodeId, usize>) -> usize {
+    communities.values().collect::<std::collections::HashSet<_>>().len()
+}
+
+#[test]
+fn test_louvain_resolution_controls_community_count() {
+    let (db, groups) = ring_of_cliques(6);
+
+    let coarse = algorithms::louvain_resolution(&db, 0.1, 10);
+    let natural = algorithms::louvain_resolution(&db, 1.0, 10);
+    let fine = algorithms::louvain_resolution(&db, 10.0, 10);
+
+    // 分辨率 1.0 恰好找到每个完全子图
+    assert_eq!(community_count(&natural), 6);
+    for group in &groups {
+        assert!(group.iter().all(|n| natural[n] == natural[&group[0]]));
+    }
+
+    // 分辨率越高，社区越多、越小
+    assert!(community_count(&coarse) < community_count(&natural));
+    assert!(community_count(&fine) > community_count(&natural));
+}
+
+#[test]
+fn test_modularity_prefers_natural_partition() {
+    let (db, groups) = ring_of_cliques(4);
+
+    let natural: std::collections::HashMap<NodeId, usize> = groups
+        .iter()
+        .enumerate()
+        .flat_map(|(g, ids)| ids.iter().map(move |&id| (id, g)))
+        .collect();
+    // 打乱的划分：每个社区从每个子图各取一个节点
+    let scrambled: std::collections::HashMap<NodeId, usize> = groups
+        .iter()
+        .flat_map(|ids| ids.iter().enumerate().map(|(i, &id)| (id, i)))
+        .collect();
+    let single: std::collections::HashMap<NodeId, usize> =
+        natural.keys().map(|&id| (id, 0)).collect();
+
+    let q_natural = algorithms::modularity(&db, &natural);
+    let q_scrambled = algorithms::modularity(&db, &scrambled);
+
+    assert!(q_natural > 0.5, "q_natural = {}", q_natural);
+    assert!(q_natural > q_scrambled);
+    // 所有节点同属一个社区时模块度为 0
+    assert!(algorithms::modularity(&db, &single).abs() < 1e-9);
+
+    // Louvain 的结果不差于自然划分
+    let found = algorithms::louvain(&db, 10);
+    assert!(algorithms::modularity(&db, &found) >= q_natural - 1e-9);
+}
+
 // ==================== 中心性测试 ====================
 
 #[test]
@@ -363,6 +478,80 @@ fn test_betweenness_centrality_simple() {
     assert!(b_centrality > c_centrality);
 }
 
+#[test]
+fn test_centrality_on_rel_types() {
+    let mut db = GraphDatabase::<MemStore>::new_in_memory();
+    let hub = db.create_node(vec!["User"], make_user("Hub"));
+    let other = db.create_node(vec!["User"], make_user("Other"));
+    let leaves: Vec<_> = (0..3)
+        .map(|i| db.create_node(vec!["User"], make_user(&format!("L{}", i))))
+        .collect();
+
+    // hub 的 FOLLOWS 关系最多，other 是唯一的 WORKS_WITH 中心
+    for &leaf in &leaves {
+        db.create_rel(hub, leaf, "FOLLOWS", Properties::new());
+    }
+    db.create_rel(other, leaves[0], "WORKS_WITH", Properties::new());
+    db.create_rel(other, leaves[1], "WORKS_WITH", Properties::new());
+
+    let degree = algorithms::degree_centrality(&db);
+    assert!(degree[&hub] > degree[&other]);
+
+    let degree = algorithms::degree_centrality_on(&db, &["WORKS_WITH"]);
+    assert_eq!(degree[&hub], 0.0);
+    assert_eq!(degree[&other], 0.5);
+
+    let closeness = algorithms::closeness_centrality_on(&db, &["WORKS_WITH"]);
+    assert_eq!(closeness[&hub], 0.0);
+    assert!(closeness[&other] > 0.0);
+
+    let eigen = algorithms::eigenvector_centrality_on(&db, &["WORKS_WITH"], 100, 1e-9);
+    assert!(eigen[&other] > eigen[&leaves[0]]);
+    assert!(eigen[&hub] < 1e-6);
+}
+
+#[test]
+fn test_closeness_centrality_path() {
+    let mut db = GraphDatabase::<MemStore>::new_in_memory();
+
+    // 无向路径 A - B - C：B 到其他节点的距离之和最小
+    let a = db.create_node(vec!["User"], make_user("A"));
+    let b = db.create_node(vec!["User"], make_user("B"));
+    let c = db.create_node(vec!["User"], make_user("C"));
+    db.create_undirected_rel(a, b, "KNOWS", Properties::new()).unwrap();
+    db.create_undirected_rel(b, c, "KNOWS", Properties::new()).unwrap();
+    let isolated = db.create_node(vec!["User"], make_user("D"));
+
+    let centrality = algorithms::closeness_centrality(&db);
+
+    // B 可达 2/3 的节点，距离之和为 2；A 可达 2/3 的节点，距离之和为 3
+    assert!((centrality[&b] - 2.0 / 3.0).abs() < 1e-9);
+    assert!((centrality[&a] - 4.0 / 9.0).abs() < 1e-9);
+    assert_eq!(centrality[&a], centrality[&c]);
+    assert_eq!(centrality[&isolated], 0.0);
+}
+
+#[test]
+fn test_eigenvector_centrality_star() {
+    let mut db = GraphDatabase::<MemStore>::new_in_memory();
+
+    let hub = db.create_node(vec!["User"], make_user("Hub"));
+    let leaves: Vec<_> = (0..4)
+        .map(|_| db.create_node(vec!["User"], make_user("Leaf")))
+        .collect();
+    for &leaf in &leaves {
+        db.create_rel(hub, leaf, "KNOWS", Properties::new());
+    }
+
+    let centrality = algorithms::eigenvector_centrality(&db, 200, 1e-10);
+
+    // 4 个叶子的星形图：特征值为 2，中心与叶子之比为 2:1，归一化后中心为 1/√2、叶子为 1/(2√2)
+    assert!((centrality[&hub] - 1.0 / 2f64.sqrt()).abs() < 1e-6);
+    for &leaf in &leaves {
+        assert!((centrality[&leaf] - 1.0 / (2.0 * 2f64.sqrt())).abs() < 1e-6);
+    }
+}
+
 // ==================== 遍历算法测试 ====================
 
 #[test]
@@ -540,6 +729,95 @@ fn test_variable_length_path() {
     }
 }
 
+/// 断言 `rels[i]` 连接 `nodes[i]` 与 `nodes[i + 1]`
+fn assert_rels_connect(db: &GraphDatabase<MemStore>, path: &algorithms::Path) {
+    assert_eq!(path.rels.len() + 1, path.nodes.len());
+    for (i, &rel_id) in path.rels.iter().enumerate() {
+        let rel = db.get_rel(rel_id).unwrap();
+        assert_eq!((rel.start, rel.end), (path.nodes[i], path.nodes[i + 1]));
+    }
+}
+
+fn road(db: &mut GraphDatabase<MemStore>, from: NodeId, to: NodeId, cost: f64) -> RelId {
+    let mut props = Properties::new();
+    props.insert("cost".to_string(), Value::Float(cost));
+    db.create_rel(from, to, "ROAD", props)
+}
+
+#[test]
+fn test_shortest_path_with_rels_connects_nodes() {
+    let mut db = GraphDatabase::<MemStore>::new_in_memory();
+    let nodes: Vec<_> = (0..4).map(|_| db.create_node(vec!["City"], Properties::new())).collect();
+    road(&mut db, nodes[0], nodes[1], 1.0);
+    road(&mut db, nodes[1], nodes[2], 1.0);
+    road(&mut db, nodes[2], nodes[3], 1.0);
+    road(&mut db, nodes[0], nodes[2], 1.0);
+
+    let path = algorithms::shortest_path_with_rels(&db, nodes[0], nodes[3]).unwrap();
+    assert_eq!(path.nodes, vec![nodes[0], nodes[2], nodes[3]]);
+    assert_rels_connect(&db, &path);
+}
+
+#[test]
+fn test_dijkstra_with_rels_prefers_cheaper_detour() {
+    let mut db = GraphDatabase::<MemStore>::new_in_memory();
+    let [a, b, c, d] = [0, 1, 2, 3].map(|_| db.create_node(vec!["City"], Properties::new()));
+    road(&mut db, a, d, 10.0);
+    let ab = road(&mut db, a, b, 2.0);
+    let bc = road(&mut db, b, c, 1.5);
+    let cd = road(&mut db, c, d, 3.0);
+
+    let (path, cost) = algorithms::dijkstra_with_rels(&db, a, d, "cost").unwrap();
+    assert_eq!(path.nodes, vec![a, b, c, d]);
+    assert_eq!(path.rels, vec![ab, bc, cd]);
+    assert_eq!(cost, 6.5);
+    assert_rels_connect(&db, &path);
+
+    // 没有权重属性时每条边代价为 1，直达边最短
+    let (path, cost) = algorithms::dijkstra_with_rels(&db, a, d, "missing").unwrap();
+    assert_eq!(path.nodes, vec![a, d]);
+    assert_eq!(cost, 1.0);
+
+    // 起点即终点
+    let (path, cost) = algorithms::dijkstra_with_rels(&db, b, b, "cost").unwrap();
+    assert_eq!((path.nodes, path.rels, cost), (vec![b], vec![], 0.0));
+
+    // 只沿出边方向，d 无法回到 a
+    assert!(algorithms::dijkstra_with_rels(&db, d, a, "cost").is_none());
+}
+
+fn route(db: &mut GraphDatabase<MemStore>, from: NodeId, to: NodeId, time: f64, cost: Option<f64>) -> RelId {
+    let mut props = Properties::new();
+    props.insert("time".to_string(), Value::Float(time));
+    if let Some(cost) = cost {
+        props.insert("cost".to_string(), Value::Float(cost));
+    }
+    db.create_rel(from, to, "ROUTE", props)
+}
+
+#[test]
+fn test_dijkstra_multi_weight_mix_selects_path() {
+    let mut db = GraphDatabase::<MemStore>::new_in_memory();
+    let [a, fast, cheap, d] = [0, 1, 2, 3].map(|_| db.create_node(vec!["City"], Properties::new()));
+    // 快但贵：a -> fast -> d；慢但便宜：a -> cheap -> d（后一段没有 cost，按 0 计）
+    route(&mut db, a, fast, 1.0, Some(10.0));
+    route(&mut db, fast, d, 1.0, Some(10.0));
+    let slow1 = route(&mut db, a, cheap, 5.0, Some(1.0));
+    let slow2 = route(&mut db, cheap, d, 5.0, None);
+
+    let time_first = vec![("time".to_string(), 0.9), ("cost".to_string(), 0.1)];
+    let (path, total) = algorithms::dijkstra_multi(&db, a, d, &time_first).unwrap();
+    assert_eq!(path.nodes, vec![a, fast, d]);
+    assert!((total - 3.8).abs() < 1e-9);
+
+    let cost_first = vec![("time".to_string(), 0.1), ("cost".to_string(), 0.9)];
+    let (path, total) = algorithms::dijkstra_multi(&db, a, d, &cost_first).unwrap();
+    assert_eq!(path.nodes, vec![a, cheap, d]);
+    assert_eq!(path.rels, vec![slow1, slow2]);
+    assert!((total - 1.9).abs() < 1e-9);
+    assert_rels_connect(&db, &path);
+}
+
 // ==================== 复杂图测试 ====================
 
 #[test]
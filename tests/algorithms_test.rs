@@ -73,6 +73,89 @@ fn test_degree_centrality() {
     assert!(centrality[&a] >= centrality[&c]);
 }
 
+#[test]
+fn test_in_out_degree_centrality_on_directed_star() {
+    let mut db = GraphDatabase::new_in_memory();
+
+    // hub -> 4 个叶子，另有一个叶子指回 hub
+    let hub = db.create_node(vec!["User"], make_user("hub"));
+    let leaves: Vec<_> = (0..4)
+        .map(|i| db.create_node(vec!["User"], make_user(&format!("leaf{}", i))))
+        .collect();
+    for &leaf in &leaves {
+        db.create_rel(hub, leaf, "FOLLOWS", Properties::new());
+    }
+    db.create_rel(leaves[0], hub, "FOLLOWS", Properties::new());
+
+    let out = algorithms::out_degree_centrality(&db);
+    let inc = algorithms::in_degree_centrality(&db);
+    let total = algorithms::degree_centrality(&db);
+
+    // n - 1 = 4
+    assert_eq!(out[&hub], 1.0);
+    assert_eq!(inc[&hub], 0.25);
+    assert_eq!(total[&hub], 1.25);
+    assert_eq!(out[&leaves[0]], 0.25);
+    assert_eq!(out[&leaves[1]], 0.0);
+    assert!(leaves.iter().all(|l| inc[l] == 0.25));
+}
+
+fn weighted(w: Value) -> Properties {
+    let mut props = Properties::new();
+    props.insert("weight".to_string(), w);
+    props
+}
+
+#[test]
+fn test_weighted_degree() {
+    let mut db = GraphDatabase::new_in_memory();
+
+    let a = db.create_node(vec!["User"], make_user("A"));
+    let b = db.create_node(vec!["User"], make_user("B"));
+    let c = db.create_node(vec!["User"], make_user("C"));
+
+    // A -5-> B, A -0.5-> C, C -(无权重, 默认1)-> B
+    db.create_rel(a, b, "KNOWS", weighted(Value::Int(5)));
+    db.create_rel(a, c, "KNOWS", weighted(Value::Float(0.5)));
+    db.create_rel(c, b, "KNOWS", Properties::new());
+
+    let strength = algorithms::weighted_degree(&db, "weight");
+    assert_eq!(strength[&a], 5.5);
+    assert_eq!(strength[&b], 6.0);
+    assert_eq!(strength[&c], 1.5);
+
+    // 普通度中 A、B、C 都是 2，加权后区分开来
+    let degree = algorithms::degree_centrality(&db);
+    assert_eq!(degree[&a], degree[&c]);
+    assert!(strength[&a] > strength[&c]);
+
+    let directed = algorithms::weighted_degree_directed(&db, "weight");
+    assert_eq!(directed[&a].out_strength, 5.5);
+    assert_eq!(directed[&a].in_strength, 0.0);
+    assert_eq!(directed[&b].out_strength, 0.0);
+    assert_eq!(directed[&b].in_strength, 6.0);
+    assert_eq!(directed[&c].out_strength, 1.0);
+    assert_eq!(directed[&c].in_strength, 0.5);
+    assert_eq!(directed[&c].total(), strength[&c]);
+}
+
+#[test]
+fn test_weighted_degree_missing_prop_equals_degree() {
+    let mut db = GraphDatabase::new_in_memory();
+
+    let a = db.create_node(vec!["User"], make_user("A"));
+    let b = db.create_node(vec!["User"], make_user("B"));
+    let c = db.create_node(vec!["User"], make_user("C"));
+    db.create_rel(a, b, "KNOWS", Properties::new());
+    db.create_rel(a, c, "KNOWS", Properties::new());
+
+    // 全部缺少权重时，强度等于未归一化的度
+    let strength = algorithms::weighted_degree(&db, "weight");
+    assert_eq!(strength[&a], 2.0);
+    assert_eq!(strength[&b], 1.0);
+    assert_eq!(strength[&c], 1.0);
+}
+
 #[test]
 fn test_connected_components() {
     let mut db = GraphDatabase::new_in_memory();
@@ -133,6 +216,55 @@ fn test_pagerank_basic() {
     assert!(rank_b > rank_a || (rank_b - rank_a).abs() < 1e-6);
 }
 
+#[test]
+fn test_pagerank_until_converged() {
+    let mut db = GraphDatabase::new_in_memory();
+
+    let a = db.create_node(vec!["User"], make_user("A"));
+    let b = db.create_node(vec!["User"], make_user("B"));
+    let c = db.create_node(vec!["User"], make_user("C"));
+    let d = db.create_node(vec!["User"], make_user("D"));
+
+    // A -> B -> C -> A，D -> C
+    db.create_rel(a, b, "LINK", Properties::new());
+    db.create_rel(b, c, "LINK", Properties::new());
+    db.create_rel(c, a, "LINK", Properties::new());
+    db.create_rel(d, c, "LINK", Properties::new());
+
+    let tolerance = 1e-8;
+    let max_iterations = 1000;
+    let (ranks, used) = algorithms::pagerank_until_converged(&db, 0.85, tolerance, max_iterations);
+
+    assert!(used > 0);
+    assert!(used < max_iterations, "should converge early, used {} iterations", used);
+
+    // 与充分迭代的固定轮数结果一致
+    let reference = algorithms::pagerank(&db, 0.85, max_iterations);
+    for id in [a, b, c, d] {
+        assert!((ranks[&id] - reference[&id]).abs() < 1e-6);
+    }
+
+    let sum: f64 = ranks.values().sum();
+    assert!((sum - 1.0).abs() < 1e-6);
+}
+
+#[test]
+fn test_pagerank_until_converged_respects_max_iterations() {
+    let mut db = GraphDatabase::new_in_memory();
+    let a = db.create_node(vec!["User"], make_user("A"));
+    let b = db.create_node(vec!["User"], make_user("B"));
+    db.create_rel(a, b, "LINK", Properties::new());
+
+    // 容差为 0 时永远不会提前停止
+    let (_, used) = algorithms::pagerank_until_converged(&db, 0.85, 0.0, 7);
+    assert_eq!(used, 7);
+
+    let empty = GraphDatabase::new_in_memory();
+    let (ranks, used) = algorithms::pagerank_until_converged(&empty, 0.85, 1e-6, 10);
+    assert!(ranks.is_empty());
+    assert_eq!(used, 0);
+}
+
 #[test]
 fn test_louvain_basic() {
     let mut db = GraphDatabase::new_in_memory();
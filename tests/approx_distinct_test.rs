@@ -0,0 +1,68 @@
+//! 近似不同值计数（HyperLogLog）测试
+
+use std::collections::HashSet;
+
+use rs_graphdb::graph::db::GraphDatabase;
+use rs_graphdb::graph::HyperLogLog;
+use rs_graphdb::values::{Properties, Value};
+
+fn user(email: String) -> Properties {
+    let mut props = Properties::new();
+    props.insert("email".to_string(), Value::Text(email));
+    props
+}
+
+#[test]
+fn test_approx_distinct_close_to_exact() {
+    let mut db = GraphDatabase::new_in_memory();
+    // 5000 个节点，约 3000 个不同取值（有重复）
+    for i in 0..5000u64 {
+        let id = i.wrapping_mul(2654435761) % 3000;
+        db.create_node(vec!["User"], user(format!("user{}@example.com", id)));
+    }
+    // 其他标签和缺少属性的节点不计入
+    db.create_node(vec!["Admin"], user("root@example.com".to_string()));
+    db.create_node(vec!["User"], Properties::new());
+
+    let exact: HashSet<String> = db
+        .all_stored_nodes()
+        .filter(|n| n.labels.iter().any(|l| l == "User"))
+        .filter_map(|n| match n.props.get("email") {
+            Some(Value::Text(s)) => Some(s.clone()),
+            _ => None,
+        })
+        .collect();
+    let estimate = db.approx_distinct("User", "email");
+
+    let error = (estimate as f64 - exact.len() as f64).abs() / exact.len() as f64;
+    assert!(error < 0.03, "estimate {} vs exact {}", estimate, exact.len());
+}
+
+#[test]
+fn test_approx_distinct_empty_and_small() {
+    let mut db = GraphDatabase::new_in_memory();
+    assert_eq!(db.approx_distinct("User", "email"), 0);
+
+    for _ in 0..10 {
+        db.create_node(vec!["User"], user("same@example.com".to_string()));
+    }
+    db.create_node(vec!["User"], user("other@example.com".to_string()));
+    // 小基数走线性计数，结果应当精确
+    assert_eq!(db.approx_distinct("User", "email"), 2);
+}
+
+#[test]
+fn test_hyperloglog_merge_counts_union() {
+    let mut a = HyperLogLog::default();
+    let mut b = HyperLogLog::default();
+    for i in 0..2000 {
+        a.insert(&i);
+    }
+    for i in 1000..3000 {
+        b.insert(&i);
+    }
+    a.merge(&b);
+
+    let error = (a.estimate() as f64 - 3000.0).abs() / 3000.0;
+    assert!(error < 0.03, "estimate {}", a.estimate());
+}
@@ -1,4 +1,5 @@
-use rs_graphdb::{GraphDatabase};
+use rs_graphdb::{GraphDatabase, Query};
+use rs_graphdb::index_schema::IndexSchema;
 use rs_graphdb::storage::sled_store::SledStore;
 use rs_graphdb::values::{Properties, Value};
 
@@ -187,3 +188,56 @@ fn test_batch_sled_persistence() {
 
     let _ = std::fs::remove_dir_all(&dir);
 }
+
+#[test]
+fn test_batch_index_build_matches_incremental() {
+    const COUNT: usize = 20_000;
+
+    let mut schema = IndexSchema::new();
+    schema.add_index("User", "city");
+    schema.add_index("User", "age");
+    schema.add_composite_index("city_age", "User", &["city", "age"]);
+
+    let nodes: Vec<(Vec<String>, Properties)> = (0..COUNT)
+        .map(|i| {
+            let mut props = make_props(&format!("user-{}", i));
+            props.insert("city".to_string(), Value::Text(format!("city-{}", i % 10)));
+            props.insert("age".to_string(), Value::Int((i % 50) as i64));
+            (vec!["User".to_string()], props)
+        })
+        .collect();
+
+    let mut incremental = GraphDatabase::new_in_memory_with_schema(schema.clone());
+    for (labels, props) in nodes.clone() {
+        incremental.create_node(labels.iter().map(|l| l.as_str()).collect(), props);
+    }
+    let mut bulk = GraphDatabase::new_in_memory_with_schema(schema);
+    bulk.batch_create_nodes(nodes);
+
+    let sorted = |mut ids: Vec<_>| {
+        ids.sort();
+        ids
+    };
+    let ids = |q: Query<'_, _>| sorted(q.collect_nodes().into_iter().map(|n| n.id).collect());
+    for city in 0..10 {
+        let city = format!("city-{}", city);
+        let expected = ids(Query::new(&incremental).from_label_and_prop_eq("User", "city", &city));
+        assert_eq!(expected.len(), COUNT / 10);
+        assert_eq!(ids(Query::new(&bulk).from_label_and_prop_eq("User", "city", &city)), expected);
+
+        for age in [0i64, 7, 49] {
+            let values = [Value::Text(city.clone()), Value::Int(age)];
+            assert_eq!(
+                sorted(bulk.find_by_composite_index("User", &["city", "age"], &values)),
+                sorted(incremental.find_by_composite_index("User", &["city", "age"], &values))
+            );
+        }
+    }
+    for age in 0..50 {
+        assert_eq!(
+            ids(Query::new(&bulk).from_label_and_prop_int_eq("User", "age", age)),
+            ids(Query::new(&incremental).from_label_and_prop_int_eq("User", "age", age))
+        );
+    }
+    assert!(bulk.verify_indexes().is_empty());
+}
@@ -0,0 +1,117 @@
+// 并发批量导入测试
+
+use rs_graphdb::bulk_loader::{BulkLoader, BulkLoaderConfig, BulkNode, BulkRel};
+use rs_graphdb::concurrent::ConcurrentGraphDB;
+use rs_graphdb::graph::db::GraphDatabase;
+use rs_graphdb::values::{Properties, Value};
+
+const NODE_COUNT: usize = 20_000;
+
+fn node(i: usize) -> BulkNode {
+    let mut props = Properties::new();
+    props.insert("ext".to_string(), Value::Int(i as i64));
+    BulkNode {
+        external_id: format!("user-{}", i),
+        labels: vec!["User".to_string()],
+        props,
+    }
+}
+
+fn rel(from: usize, to: usize) -> BulkRel {
+    BulkRel {
+        start: format!("user-{}", from),
+        end: format!("user-{}", to),
+        rel_type: "FOLLOWS".to_string(),
+        props: Properties::new(),
+    }
+}
+
+#[test]
+fn test_bulk_load_concurrent() {
+    let db = ConcurrentGraphDB::new(GraphDatabase::new_in_memory());
+    let loader = BulkLoader::with_config(
+        db.clone_handle(),
+        BulkLoaderConfig {
+            threads: 8,
+            batch_size: 500,
+        },
+    );
+
+    // 每个节点指向后面两个节点（环形），共 40000 条关系
+    let nodes = (0..NODE_COUNT).map(node);
+    let rels = (0..NODE_COUNT).flat_map(|i| {
+        vec![rel(i, (i + 1) % NODE_COUNT), rel(i, (i + 7) % NODE_COUNT)]
+    });
+
+    let report = loader.load(nodes, rels);
+
+    assert_eq!(report.nodes_loaded, NODE_COUNT);
+    assert_eq!(report.rels_loaded, NODE_COUNT * 2);
+    assert_eq!(report.duplicate_ids, 0);
+    assert!(report.skipped_rels.is_empty());
+    assert_eq!(report.id_map.len(), NODE_COUNT);
+    assert!(report.nodes_per_sec() > 0.0);
+    assert!(report.rels_per_sec() > 0.0);
+
+    assert_eq!(db.node_count(), NODE_COUNT);
+    assert_eq!(db.rel_count(), NODE_COUNT * 2);
+
+    // 抽样检查：外部 ID 映射指向正确的节点，关系端点正确
+    for i in [0usize, 1, 4_999, 12_345, NODE_COUNT - 1] {
+        let id = report.id_map[&format!("user-{}", i)];
+        let stored = db.get_node(id).unwrap();
+        assert_eq!(stored.props.get("ext"), Some(&Value::Int(i as i64)));
+
+        let mut targets: Vec<i64> = db
+            .neighbors_out(id)
+            .into_iter()
+            .map(|r| match db.get_node(r.end).unwrap().props.get("ext") {
+                Some(Value::Int(v)) => *v,
+                other => panic!("unexpected ext {:?}", other),
+            })
+            .collect();
+        targets.sort();
+        let mut expected = vec![((i + 1) % NODE_COUNT) as i64, ((i + 7) % NODE_COUNT) as i64];
+        expected.sort();
+        assert_eq!(targets, expected);
+    }
+}
+
+#[test]
+fn test_bulk_load_skips_unresolved_edges() {
+    let db = ConcurrentGraphDB::new(GraphDatabase::new_in_memory());
+    let loader = BulkLoader::new(db.clone_handle());
+
+    let nodes = vec![node(1), node(2), node(1)];
+    let rels = vec![rel(1, 2), rel(2, 99)];
+
+    let report = loader.load(nodes, rels);
+
+    assert_eq!(report.nodes_loaded, 3);
+    assert_eq!(report.duplicate_ids, 1);
+    assert_eq!(report.rels_loaded, 1);
+    assert_eq!(report.skipped_rels.len(), 1);
+    assert_eq!(report.skipped_rels[0].end, "user-99");
+    assert_eq!(db.rel_count(), 1);
+}
+
+#[test]
+fn test_bulk_load_duplicate_id_maps_to_last_occurrence() {
+    // 每批一个节点、多线程写入，批次完成顺序不确定，映射仍应指向最后出现的节点
+    for _ in 0..20 {
+        let db = ConcurrentGraphDB::new(GraphDatabase::new_in_memory());
+        let loader = BulkLoader::with_config(
+            db.clone_handle(),
+            BulkLoaderConfig { threads: 8, batch_size: 1 },
+        );
+
+        let nodes: Vec<BulkNode> = (0..64)
+            .map(|i| BulkNode { external_id: "user-dup".to_string(), ..node(i) })
+            .collect();
+        let report = loader.load(nodes, Vec::<BulkRel>::new());
+
+        assert_eq!(report.duplicate_ids, 63);
+        let id = report.id_map["user-dup"];
+        assert_eq!(db.get_node(id).unwrap().props.get("ext"), Some(&Value::Int(63)));
+    }
+}
@@ -0,0 +1,65 @@
+//! 按条件批量更新测试
+
+use rs_graphdb::storage::mem_store::MemStore;
+use rs_graphdb::values::{Properties, Value};
+use rs_graphdb::{GraphDatabase, Query};
+
+fn create_users() -> GraphDatabase<MemStore> {
+    let mut db = GraphDatabase::new_in_memory();
+    for (name, age) in [("Alice", 25), ("Bob", 31), ("Carol", 45), ("Dave", 30), ("Erin", 60)] {
+        let mut props = Properties::new();
+        props.insert("name".to_string(), Value::Text(name.to_string()));
+        props.insert("age".to_string(), Value::Int(age));
+        db.create_node(vec!["User"], props);
+    }
+    // 其他标签的节点不受影响
+    let mut props = Properties::new();
+    props.insert("age".to_string(), Value::Int(99));
+    db.create_node(vec!["Robot"], props);
+    db
+}
+
+fn age_of(node: &rs_graphdb::graph::model::Node) -> i64 {
+    match node.get("age") {
+        Some(Value::Int(age)) => *age,
+        _ => panic!("missing age"),
+    }
+}
+
+#[test]
+fn test_update_matching_sets_flag_on_older_users() {
+    let mut db = create_users();
+
+    let mut updates = Properties::new();
+    updates.insert("active".to_string(), Value::Bool(true));
+    let updated = db.update_matching("User", |n| age_of(n) > 30, updates);
+    assert_eq!(updated, 3);
+
+    let active: Vec<_> = Query::new(&db)
+        .from_label("User")
+        .collect_nodes()
+        .into_iter()
+        .filter(|n| n.get("active") == Some(&Value::Bool(true)))
+        .collect();
+    assert_eq!(active.len(), 3);
+    assert!(active.iter().all(|n| age_of(n) > 30));
+    // 原有属性保留
+    assert!(active.iter().all(|n| n.get("name").is_some()));
+
+    let robots = Query::new(&db).from_label("Robot").collect_nodes();
+    assert_eq!(robots[0].get("active"), None);
+}
+
+#[test]
+fn test_update_matching_keeps_indexes_consistent() {
+    let mut db = create_users();
+
+    // User.age 在默认 schema 中有索引
+    let mut updates = Properties::new();
+    updates.insert("age".to_string(), Value::Int(18));
+    assert_eq!(db.update_matching("User", |n| age_of(n) >= 45, updates), 2);
+
+    assert!(db.verify_indexes().is_empty());
+    assert!(Query::new(&db).from_label_and_prop_int_eq("User", "age", 45).collect_nodes().is_empty());
+    assert_eq!(Query::new(&db).from_label_and_prop_int_eq("User", "age", 18).count(), 2);
+}
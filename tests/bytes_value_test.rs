@@ -0,0 +1,64 @@
+//! 字节串属性值测试：序列化、存储往返、索引跳过和大小估算
+
+use rs_graphdb::index_composite::CompositeIndexValue;
+use rs_graphdb::storage::sled_store::SledStore;
+use rs_graphdb::storage::StoredNode;
+use rs_graphdb::values::{Properties, TypedValue, Value};
+use rs_graphdb::GraphDatabase;
+
+#[test]
+fn test_bytes_serde_round_trips() {
+    let value = Value::Bytes(vec![0, 7, 255]);
+    assert_eq!(value.as_bytes(), Some(&[0u8, 7, 255][..]));
+    assert_eq!(Value::Text("abc".into()).as_bytes(), None);
+
+    // 普通 JSON 输出整数数组
+    assert_eq!(serde_json::to_value(&value).unwrap(), serde_json::json!([0, 7, 255]));
+
+    // 带类型标签的形式无损往返
+    let typed = serde_json::to_value(TypedValue(value.clone())).unwrap();
+    assert_eq!(typed, serde_json::json!({"type": "bytes", "value": [0, 7, 255]}));
+    assert_eq!(serde_json::from_value::<Value>(typed).unwrap(), value);
+    assert!(serde_json::from_str::<Value>(r#"{"type":"bytes","value":[1,256]}"#).is_err());
+    assert!(serde_json::from_str::<Value>(r#"{"type":"bytes","value":["a"]}"#).is_err());
+
+    // 二进制格式
+    let bytes = bincode::serialize(&value).unwrap();
+    assert_eq!(bincode::deserialize::<Value>(&bytes).unwrap(), value);
+}
+
+#[test]
+fn test_bytes_round_trip_through_sled_store_and_skip_indexes() {
+    let dir = tempfile::tempdir().unwrap();
+    let thumbnail: Vec<u8> = (0..=255).collect();
+
+    let id = {
+        let mut db = GraphDatabase::from_engine(SledStore::new(dir.path()).unwrap());
+        let mut props = Properties::new();
+        props.insert("thumbnail".to_string(), Value::Bytes(thumbnail.clone()));
+        let id = db.create_node(vec!["Image"], props);
+
+        // 全文索引只处理文本属性
+        db.add_fulltext_index("Image", "thumbnail", id);
+        assert!(db.search_fulltext("Image", "thumbnail", "a").is_empty());
+        db.flush().unwrap();
+        id
+    };
+
+    let db = GraphDatabase::from_engine(SledStore::new(dir.path()).unwrap());
+    let node = db.get_node(id).unwrap();
+    assert_eq!(node.props.get("thumbnail").and_then(Value::as_bytes), Some(&thumbnail[..]));
+
+    assert_eq!(CompositeIndexValue::from_value(&Value::Bytes(thumbnail)), None);
+}
+
+#[test]
+fn test_bytes_size_estimate_counts_length() {
+    let node_with = |len: usize| {
+        let mut props = Properties::new();
+        props.insert("blob".to_string(), Value::Bytes(vec![1; len]));
+        StoredNode { id: 1, labels: vec!["Blob".to_string()], props }
+    };
+
+    assert_eq!(node_with(4096).estimated_size() - node_with(0).estimated_size(), 4096);
+}
@@ -0,0 +1,103 @@
+//! 查询取消令牌测试
+
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use rs_graphdb::cypher::{execute_statement_cancellable, parse_cypher, CypherResult, ExecutionError};
+use rs_graphdb::graph::model::Node;
+use rs_graphdb::storage::mem_store::MemStore;
+use rs_graphdb::values::Properties;
+use rs_graphdb::{CancellationToken, GraphDatabase, GraphListener, Query};
+
+/// `len` 个 N 节点串成的 NEXT 链
+fn chain(len: usize) -> GraphDatabase<MemStore> {
+    let mut db = GraphDatabase::new_in_memory();
+    let ids: Vec<_> = (0..len).map(|_| db.create_node(vec!["N"], Properties::new())).collect();
+    for pair in ids.windows(2) {
+        db.create_rel(pair[0], pair[1], "NEXT", Properties::new());
+    }
+    db
+}
+
+#[test]
+fn test_cancel_mid_traversal_returns_cancelled() {
+    let mut db = chain(3000);
+    // 每个候选节点都要沿链走到尽头才能确定不匹配，完整执行需要很长时间
+    let stmt = parse_cypher("MATCH (n:N) WHERE (n)-[:NEXT*]->(:Missing) RETURN n").unwrap();
+
+    let token = CancellationToken::new();
+    let canceller = {
+        let token = token.clone();
+        thread::spawn(move || {
+            thread::sleep(Duration::from_millis(50));
+            token.cancel();
+        })
+    };
+
+    let started = Instant::now();
+    let result = execute_statement_cancellable(&mut db, &stmt, &token);
+    canceller.join().unwrap();
+
+    assert!(matches!(result, Err(ExecutionError::Cancelled)));
+    assert!(started.elapsed() < Duration::from_secs(5), "took {:?}", started.elapsed());
+}
+
+#[test]
+fn test_cancelled_token_stops_before_execution() {
+    let mut db = chain(10);
+    let token = CancellationToken::new();
+    token.cancel();
+
+    let stmt = parse_cypher("CREATE (n:N)").unwrap();
+    assert!(matches!(
+        execute_statement_cancellable(&mut db, &stmt, &token),
+        Err(ExecutionError::Cancelled)
+    ));
+    assert_eq!(db.node_count(), 10);
+
+    // 未取消的令牌不影响结果
+    let stmt = parse_cypher("MATCH (a:N)-[:NEXT*1..3]->(b) RETURN b").unwrap();
+    let (_, stats) = execute_statement_cancellable(&mut db, &stmt, &CancellationToken::new()).unwrap();
+    assert!(stats.rows_examined > 0);
+}
+
+#[test]
+fn test_query_traversal_stops_when_cancelled() {
+    let db = chain(100);
+    let token = CancellationToken::new();
+
+    let q = Query::new(&db).with_cancellation(token.clone()).from_label("N");
+    assert_eq!(q.count(), 100);
+
+    token.cancel();
+    let q = Query::new(&db)
+        .with_cancellation(token)
+        .from_label("N")
+        .out_variable_length("NEXT", 1, None);
+    assert!(q.is_cancelled());
+    assert!(q.collect_nodes().is_empty());
+}
+
+/// 创建节点时取消令牌的监听器，模拟写入执行期间到达的取消请求
+struct CancelOnCreate(CancellationToken);
+
+impl GraphListener for CancelOnCreate {
+    fn on_node_created(&self, _node: &Node) -> Result<(), String> {
+        self.0.cancel();
+        Ok(())
+    }
+}
+
+#[test]
+fn test_write_applied_before_cancel_reports_success() {
+    let mut db = chain(3);
+    let token = CancellationToken::new();
+    db.subscribe(Arc::new(CancelOnCreate(token.clone())));
+
+    let stmt = parse_cypher("CREATE (n:N)").unwrap();
+    let (result, _) = execute_statement_cancellable(&mut db, &stmt, &token).unwrap();
+    assert!(matches!(result, CypherResult::Created { ref nodes, .. } if nodes.len() == 1));
+    assert!(token.is_cancelled());
+    assert_eq!(db.node_count(), 4);
+}
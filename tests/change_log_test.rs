@@ -0,0 +1,214 @@
+use rs_graphdb::graph::{Change, ChangeRecord};
+use rs_graphdb::values::{Properties, Value};
+use rs_graphdb::GraphDatabase;
+
+fn kinds(records: &[ChangeRecord]) -> Vec<&'static str> {
+    records
+        .iter()
+        .map(|r| match r.change {
+            Change::NodeCreated(_) => "node_created",
+            Change::NodeUpdated(_) => "node_updated",
+            Change::NodeDeleted(_) => "node_deleted",
+            Change::RelCreated(_) => "rel_created",
+            Change::RelUpdated(_) => "rel_updated",
+            Change::RelDeleted(_) => "rel_deleted",
+        })
+        .collect()
+}
+
+#[test]
+fn test_changes_since_returns_mutations_in_order() {
+    let mut db = GraphDatabase::new_in_memory();
+    assert!(db.changes_since(0).is_empty());
+    db.enable_change_log(100);
+
+    let a = db.create_node(vec!["User"], Properties::new());
+    let b = db.create_node(vec!["User"], Properties::new());
+    let rel = db.create_rel(a, b, "FRIEND", Properties::new());
+    let mut props = Properties::new();
+    props.insert("name".to_string(), Value::Text("Alice".to_string()));
+    db.update_node_props(a, props);
+    db.delete_rel(rel);
+
+    let all = db.changes_since(0);
+    assert_eq!(all.iter().map(|r| r.seq).collect::<Vec<_>>(), vec![1, 2, 3, 4, 5]);
+    assert!(all.windows(2).all(|w| w[0].timestamp_ms <= w[1].timestamp_ms));
+    assert_eq!(
+        kinds(&all),
+        vec!["node_created", "node_created", "rel_created", "node_updated", "rel_deleted"]
+    );
+
+    // 副本从第 3 条之后继续拉取
+    let tail = db.changes_since(3);
+    assert_eq!(tail, all[3..].to_vec());
+    match &tail[0].change {
+        Change::NodeUpdated(node) => {
+            assert_eq!(node.id, a);
+            assert_eq!(node.get("name"), Some(&Value::Text("Alice".to_string())));
+        }
+        other => panic!("unexpected change: {:?}", other),
+    }
+    assert_eq!(tail[1].change, Change::RelDeleted(rel));
+    assert!(db.changes_since(5).is_empty());
+}
+
+#[test]
+fn test_change_log_is_bounded_and_compactable() {
+    let mut db = GraphDatabase::new_in_memory();
+    let log = db.enable_change_log(3);
+
+    for _ in 0..5 {
+        db.create_node(vec!["Item"], Properties::new());
+    }
+    // 超出容量时丢弃最旧的记录，序号不重排
+    assert_eq!(log.len(), 3);
+    assert_eq!(log.oldest_seq(), Some(3));
+    assert_eq!(log.latest_seq(), 5);
+    assert_eq!(
+        db.changes_since(0).iter().map(|r| r.seq).collect::<Vec<_>>(),
+        vec![3, 4, 5]
+    );
+
+    assert_eq!(db.compact_changes(4), 2);
+    assert_eq!(db.changes_since(0).iter().map(|r| r.seq).collect::<Vec<_>>(), vec![5]);
+
+    db.create_node(vec!["Item"], Properties::new());
+    assert_eq!(db.changes_since(5)[0].seq, 6);
+}
+
+// ========== 副本回放 ==========
+
+use rs_graphdb::graph::db::GraphError;
+use rs_graphdb::storage::mem_store::MemStore;
+use rs_graphdb::storage::{NodeId, RelId};
+use std::collections::BTreeMap;
+
+/// 图的完整状态：节点 (ID -> 标签, 属性) 与关系 (ID -> 端点, 类型, 属性)
+fn snapshot(db: &GraphDatabase<MemStore>) -> (BTreeMap<NodeId, String>, BTreeMap<RelId, String>) {
+    let mut nodes = BTreeMap::new();
+    let mut rels = BTreeMap::new();
+    for stored in db.all_stored_nodes() {
+        let node = db.get_node(stored.id).unwrap();
+        let mut props: Vec<_> = node.props.iter().map(|(k, v)| format!("{}={:?}", k, v)).collect();
+        props.sort();
+        nodes.insert(node.id, format!("{:?} {:?}", node.labels, props));
+        for rel in db.neighbors_out(node.id) {
+            let mut props: Vec<_> = rel.props.iter().map(|(k, v)| format!("{}={:?}", k, v)).collect();
+            props.sort();
+            rels.insert(
+                rel.id,
+                format!("{}-{}->{} {} {:?}", rel.start, rel.typ, rel.end, rel.directed, props),
+            );
+        }
+    }
+    (nodes, rels)
+}
+
+fn text(key: &str, value: &str) -> Properties {
+    let mut props = Properties::new();
+    props.insert(key.to_string(), Value::Text(value.to_string()));
+    props
+}
+
+#[test]
+fn test_replica_replays_primary_change_log() {
+    let mut primary = GraphDatabase::new_in_memory();
+    primary.enable_change_log(1000);
+
+    let a = primary.create_node(vec!["User"], text("name", "Alice"));
+    let b = primary.create_node(vec!["User"], text("name", "Bob"));
+    let c = primary.create_node(vec!["User"], text("name", "Carol"));
+    primary.create_rel(a, b, "FRIEND", text("since", "2020"));
+    let bc = primary.create_rel(b, c, "FRIEND", Properties::new());
+    primary.create_undirected_rel(a, c, "KNOWS", Properties::new()).unwrap();
+    primary.update_node_props(b, text("name", "Bobby"));
+    primary.update_rel_props(bc, text("since", "2021"));
+    primary.delete_node(a);
+
+    let mut replica = GraphDatabase::new_in_memory();
+    for record in primary.changes_since(0) {
+        replica.apply_change(record).unwrap();
+    }
+
+    assert_eq!(replica.applied_seq(), primary.changes_since(0).last().unwrap().seq);
+    assert_eq!(snapshot(&replica), snapshot(&primary));
+    assert_eq!(replica.triangle_count(), primary.triangle_count());
+    // 回放的更新同样维护了索引
+    let found = rs_graphdb::Query::new(&replica)
+        .from_label_and_prop_eq("User", "name", "Bobby")
+        .collect_nodes();
+    assert_eq!(found.iter().map(|n| n.id).collect::<Vec<_>>(), vec![b]);
+
+    // 之后的新变更可以增量拉取
+    let seen = replica.applied_seq();
+    let d = primary.create_node(vec!["User"], text("name", "Dan"));
+    primary.create_rel(d, b, "FRIEND", Properties::new());
+    for record in primary.changes_since(seen) {
+        replica.apply_change(record).unwrap();
+    }
+    assert_eq!(snapshot(&replica), snapshot(&primary));
+}
+
+#[test]
+fn test_replica_replays_committed_transactions() {
+    let mut primary = GraphDatabase::new_in_memory();
+    primary.enable_change_log(1000);
+    let a = primary.create_node(vec!["User"], text("name", "Alice"));
+    let b = primary.create_node(vec!["User"], text("name", "Bob"));
+    let ab = primary.create_rel(a, b, "FRIEND", Properties::new());
+
+    let tx = primary.begin_tx().unwrap();
+    let c = primary.create_node_in_tx(tx, vec!["User"], text("name", "Carol")).unwrap();
+    primary.create_rel_in_tx(tx, c, b, "FRIEND", text("since", "2024")).unwrap();
+    primary.update_node_props_in_tx(tx, b, text("name", "Bobby")).unwrap();
+    primary.delete_rel_in_tx(tx, ab).unwrap();
+    // 未提交的写入不进入日志
+    let before_commit = primary.changes_since(0).len();
+    assert_eq!(before_commit, 3);
+    primary.commit_tx(tx).unwrap();
+
+    let rolled_back = primary.begin_tx().unwrap();
+    primary.delete_node_in_tx(rolled_back, a).unwrap();
+    primary.rollback_tx(rolled_back).unwrap();
+
+    let records = primary.changes_since(0);
+    assert_eq!(
+        kinds(&records[before_commit..]),
+        vec!["node_created", "node_updated", "rel_created", "rel_deleted"]
+    );
+
+    let mut replica = GraphDatabase::new_in_memory();
+    for record in records {
+        replica.apply_change(record).unwrap();
+    }
+    assert_eq!(snapshot(&replica), snapshot(&primary));
+    assert!(replica.get_node(a).is_some());
+}
+
+#[test]
+fn test_apply_change_ignores_duplicates_and_rejects_gaps() {
+    let mut primary = GraphDatabase::new_in_memory();
+    primary.enable_change_log(100);
+    for i in 0..3 {
+        primary.create_node(vec!["Item"], text("name", &format!("item{}", i)));
+    }
+    let records = primary.changes_since(0);
+
+    let mut replica = GraphDatabase::new_in_memory();
+    // 跳过第 1 条：拒绝且不修改
+    match replica.apply_change(records[1].clone()) {
+        Err(GraphError::SequenceGap { expected: 1, found: 2 }) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+    assert_eq!(replica.node_count(), 0);
+
+    replica.apply_change(records[0].clone()).unwrap();
+    replica.apply_change(records[1].clone()).unwrap();
+    // 重复回放被忽略
+    replica.apply_change(records[0].clone()).unwrap();
+    replica.apply_change(records[1].clone()).unwrap();
+    replica.apply_change(records[2].clone()).unwrap();
+
+    assert_eq!(replica.applied_seq(), 3);
+    assert_eq!(snapshot(&replica), snapshot(&primary));
+}
@@ -0,0 +1,83 @@
+//! 边收缩测试
+
+use rs_graphdb::graph::db::{ContractOptions, GraphError, PropertyConflict};
+use rs_graphdb::storage::mem_store::MemStore;
+use rs_graphdb::storage::NodeId;
+use rs_graphdb::values::{Properties, Value};
+use rs_graphdb::GraphDatabase;
+
+fn props(pairs: &[(&str, Value)]) -> Properties {
+    pairs.iter().map(|(k, v)| (k.to_string(), v.clone())).collect()
+}
+
+fn names(db: &GraphDatabase<MemStore>, ids: impl Iterator<Item = NodeId>) -> Vec<String> {
+    let mut names: Vec<String> = ids
+        .filter_map(|id| db.get_node(id))
+        .map(|n| match n.props.get("name") {
+            Some(Value::Text(s)) => s.clone(),
+            other => format!("{:?}", other),
+        })
+        .collect();
+    names.sort();
+    names
+}
+
+#[test]
+fn test_contract_edge_in_triangle_keeps_external_connections() {
+    let mut db = GraphDatabase::new_in_memory();
+    let a = db.create_node(vec!["A"], props(&[("name", Value::Text("a".into())), ("x", Value::Int(1))]));
+    let b = db.create_node(vec!["B"], props(&[("name", Value::Text("b".into())), ("y", Value::Int(2))]));
+    let c = db.create_node(vec!["C"], props(&[("name", Value::Text("c".into()))]));
+    let d = db.create_node(vec!["D"], props(&[("name", Value::Text("d".into()))]));
+
+    let ab = db.create_rel(a, b, "LINK", Properties::new());
+    db.create_rel(b, c, "LINK", props(&[("w", Value::Int(5))]));
+    db.create_rel(c, a, "LINK", Properties::new());
+    db.create_rel(d, b, "KNOWS", Properties::new());
+    // 两端之间的平行关系，收缩后成为自环
+    db.create_rel(b, a, "BACK", Properties::new());
+
+    let merged = db.contract_edge(ab).unwrap();
+
+    assert_eq!(db.node_count(), 3);
+    assert!(db.get_node(a).is_none() && db.get_node(b).is_none());
+    let node = db.get_node(merged).unwrap();
+    assert!(node.has_label("A") && node.has_label("B"));
+    assert_eq!(node.props.get("name"), Some(&Value::Text("a".into())));
+    assert_eq!(node.props.get("y"), Some(&Value::Int(2)));
+
+    let out: Vec<_> = db.neighbors_out(merged).collect();
+    assert_eq!(out.len(), 1);
+    assert_eq!((out[0].end, out[0].typ.as_str()), (c, "LINK"));
+    assert_eq!(out[0].props.get("w"), Some(&Value::Int(5)));
+    assert_eq!(names(&db, db.neighbors_in(merged).map(|r| r.start)), vec!["c", "d"]);
+    // 自环默认被丢弃
+    assert!(db.neighbors_out(merged).all(|r| r.end != merged));
+    assert_eq!(db.rel_count(), 3);
+
+    assert!(matches!(db.contract_edge(ab), Err(GraphError::NotFound)));
+}
+
+#[test]
+fn test_contract_edge_options() {
+    let mut db = GraphDatabase::new_in_memory();
+    let a = db.create_node(vec!["N"], props(&[("name", Value::Text("a".into()))]));
+    let b = db.create_node(vec!["N"], props(&[("name", Value::Text("b".into()))]));
+    let ab = db.create_rel(a, b, "LINK", Properties::new());
+    db.create_rel(a, b, "ALSO", Properties::new());
+
+    let options = ContractOptions::new()
+        .with_conflict(PropertyConflict::Combine)
+        .with_self_loops(true);
+    let merged = db.contract_edge_with(ab, options).unwrap();
+
+    let node = db.get_node(merged).unwrap();
+    assert_eq!(node.labels, vec!["N".to_string()]);
+    assert_eq!(
+        node.props.get("name"),
+        Some(&Value::List(vec![Value::Text("a".into()), Value::Text("b".into())]))
+    );
+    let loops: Vec<_> = db.neighbors_out(merged).collect();
+    assert_eq!(loops.len(), 1);
+    assert_eq!((loops[0].end, loops[0].typ.as_str()), (merged, "ALSO"));
+}
@@ -5,6 +5,7 @@ use rs_graphdb::storage::mem_store::MemStore;
 use rs_graphdb::values::{Properties, Value};
 use rs_graphdb::cypher::{parse_cypher, executor};
 use rs_graphdb::storage::TxHandle;
+use rs_graphdb::Query;
 
 // ========== 事务支持测试 ==========
 
@@ -86,6 +87,66 @@ fn test_transaction_invalid_handle_fails() {
     assert!(result.is_err(), "Rollback with invalid handle should fail");
 }
 
+#[test]
+fn test_query_in_transaction_reads_own_writes() {
+    let mut db = GraphDatabase::<MemStore>::new_in_memory();
+    let alice = db.create_node(vec!["User"], {
+        let mut props = Properties::new();
+        props.insert("name".to_string(), Value::Text("Alice".to_string()));
+        props.insert("age".to_string(), Value::Int(30));
+        props
+    });
+
+    let tx = db.begin_tx().unwrap();
+    let bob = db
+        .create_node_in_tx(tx, vec!["User"], {
+            let mut props = Properties::new();
+            props.insert("name".to_string(), Value::Text("Bob".to_string()));
+            props
+        })
+        .unwrap();
+    let mut older = Properties::new();
+    older.insert("age".to_string(), Value::Int(31));
+    db.update_node_props_in_tx(tx, alice, older).unwrap();
+
+    // 事务内的查询看到新节点和更新后的属性
+    let ids: Vec<_> = Query::new(&db)
+        .in_transaction(tx)
+        .unwrap()
+        .from_label("User")
+        .collect_nodes()
+        .into_iter()
+        .map(|n| n.id)
+        .collect();
+    assert_eq!(ids.len(), 2);
+    assert!(ids.contains(&bob));
+    let found = Query::new(&db)
+        .in_transaction(tx)
+        .unwrap()
+        .from_label_and_prop_eq("User", "name", "Bob")
+        .collect_nodes();
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].id, bob);
+    let aged = Query::new(&db)
+        .in_transaction(tx)
+        .unwrap()
+        .from_label_and_prop_int_eq("User", "age", 31)
+        .count();
+    assert_eq!(aged, 1);
+
+    // 事务外看不到未提交的写入
+    assert_eq!(Query::new(&db).from_label("User").count(), 1);
+    assert!(Query::new(&db).from_label_and_prop_eq("User", "name", "Bob").collect_nodes().is_empty());
+    assert_eq!(db.get_node(alice).unwrap().get("age"), Some(&Value::Int(30)));
+
+    // 提交后对所有查询可见，索引同步更新
+    db.commit_tx(tx).unwrap();
+    let found = Query::new(&db).from_label_and_prop_eq("User", "name", "Bob").collect_nodes();
+    assert_eq!(found.len(), 1);
+    assert_eq!(Query::new(&db).from_label_and_prop_int_eq("User", "age", 31).count(), 1);
+    assert!(Query::new(&db).in_transaction(tx).is_err());
+}
+
 // ========== 存储层更新 API 测试 ==========
 
 #[test]
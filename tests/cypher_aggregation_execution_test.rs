@@ -3,6 +3,7 @@
 
 use rs_graphdb::cypher::{ast::*, executor, parser};
 use rs_graphdb::graph::db::GraphDatabase;
+use rs_graphdb::query_engine::ColumnType;
 use rs_graphdb::storage::mem_store::MemStore;
 use rs_graphdb::values::{Properties, Value};
 
@@ -332,3 +333,111 @@ fn test_execute_multiple_aggregations() {
         _ => panic!("Expected Nodes result"),
     }
 }
+
+#[test]
+fn test_return_column_types() {
+    let mut db = GraphDatabase::<MemStore>::new_in_memory();
+
+    create_user(&mut db, "Alice", 30, "New York");
+    create_user(&mut db, "Bob", 25, "London");
+
+    let cases = [
+        (
+            "MATCH (n:User) RETURN n, n.name, count(*)",
+            vec![
+                ("n", ColumnType::Node),
+                ("n.name", ColumnType::Text),
+                ("count(*)", ColumnType::Int),
+            ],
+        ),
+        // 非聚合查询：属性列从匹配到的节点推断
+        ("MATCH (n:User) RETURN n.age AS age", vec![("age", ColumnType::Int)]),
+        ("MATCH (u:User) RETURN MIN(u.age)", vec![("min(u.age)", ColumnType::Int)]),
+    ];
+
+    for (query, expected) in cases {
+        let CypherStatement::Query(q) = parser::parse_cypher(query).unwrap() else {
+            panic!("Expected query statement: {}", query);
+        };
+        let results = executor::execute_cypher(&db, &q).unwrap();
+        let expected: Vec<(String, ColumnType)> =
+            expected.into_iter().map(|(name, typ)| (name.to_string(), typ)).collect();
+        assert_eq!(executor::return_columns(&q, &results), expected, "{}", query);
+    }
+}
+
+#[test]
+fn test_count_skips_missing_and_null_values() {
+    let mut db = GraphDatabase::<MemStore>::new_in_memory();
+
+    create_user(&mut db, "Alice", 30, "New York");
+    create_user(&mut db, "Bob", 25, "London");
+    create_user(&mut db, "Carol", 41, "London");
+    create_user(&mut db, "Dave", 33, "New York");
+    // 一个没有 city，一个 city 为 NULL
+    let mut props = Properties::new();
+    props.insert("name".to_string(), Value::Text("Eve".to_string()));
+    db.create_node(vec!["User"], props);
+    let mut props = Properties::new();
+    props.insert("name".to_string(), Value::Text("Frank".to_string()));
+    props.insert("city".to_string(), Value::Null);
+    db.create_node(vec!["User"], props);
+
+    let query = "MATCH (u:User) RETURN COUNT(*), count(u), count(u.city), count(DISTINCT u.city) AS cities";
+    let stmt = parser::parse_cypher(query).unwrap();
+    let result = executor::execute_statement(&mut db, &stmt).unwrap();
+
+    match result {
+        executor::CypherResult::Nodes(nodes) => {
+            assert_eq!(nodes.len(), 1);
+            assert_eq!(nodes[0].get("count"), Some(&Value::Int(6)));
+            assert_eq!(nodes[0].get("count(u)"), Some(&Value::Int(6)));
+            assert_eq!(nodes[0].get("count(u.city)"), Some(&Value::Int(4)));
+            assert_eq!(nodes[0].get("cities"), Some(&Value::Int(2)));
+        }
+        _ => panic!("Expected Nodes result"),
+    }
+
+    let stmt = parser::parse_cypher("MATCH (u:User) RETURN count(DISTINCT u.city)").unwrap();
+    let q = match &stmt {
+        CypherStatement::Query(q) => q.clone(),
+        _ => panic!("Expected query"),
+    };
+    assert_eq!(
+        q.return_clause.items,
+        vec![ReturnItem::Aggregation(AggFunc::CountDistinct, "u".to_string(), "city".to_string())]
+    );
+    match executor::execute_statement(&mut db, &stmt).unwrap() {
+        executor::CypherResult::Nodes(nodes) => {
+            assert_eq!(nodes[0].get("count(DISTINCT u.city)"), Some(&Value::Int(2)));
+        }
+        _ => panic!("Expected Nodes result"),
+    }
+}
+
+#[test]
+fn test_count_distinct_compares_values_not_debug_text() {
+    let mut db = GraphDatabase::<MemStore>::new_in_memory();
+    for score in [
+        Value::Float(0.0),
+        Value::Float(-0.0),
+        Value::Float(1.5),
+        Value::Int(1),
+        Value::Float(1.0),
+        Value::List(vec![Value::Int(1), Value::Float(-0.0)]),
+        Value::List(vec![Value::Int(1), Value::Float(0.0)]),
+    ] {
+        let mut props = Properties::new();
+        props.insert("score".to_string(), score);
+        db.create_node(vec!["Player"], props);
+    }
+
+    let stmt = parser::parse_cypher("MATCH (p:Player) RETURN count(DISTINCT p.score) AS scores").unwrap();
+    match executor::execute_statement(&mut db, &stmt).unwrap() {
+        executor::CypherResult::Nodes(nodes) => {
+            // 0.0 与 -0.0 相同；整数 1 与浮点数 1.0 不同
+            assert_eq!(nodes[0].get("scores"), Some(&Value::Int(5)));
+        }
+        _ => panic!("Expected Nodes result"),
+    }
+}
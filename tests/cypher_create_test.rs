@@ -108,3 +108,92 @@ fn test_create_then_query() {
         _ => panic!("Expected Nodes result"),
     }
 }
+
+#[test]
+fn test_create_return_node() {
+    let mut db = GraphDatabase::new_in_memory();
+
+    let stmt =
+        cypher::parse_cypher(r#"CREATE (p:Person {name: "Carol", age: 41}) RETURN p"#).unwrap();
+    let result = cypher::execute_statement(&mut db, &stmt).unwrap();
+
+    let nodes = match result {
+        cypher::CypherResult::Nodes(nodes) => nodes,
+        _ => panic!("Expected Nodes result"),
+    };
+    assert_eq!(nodes.len(), 1);
+    let created = &nodes[0];
+    assert_eq!(db.get_node(created.id).as_ref(), Some(created));
+    assert!(created.has_label("Person"));
+    assert_eq!(created.get("name"), Some(&Value::Text("Carol".to_string())));
+    assert_eq!(created.get("age"), Some(&Value::Int(41)));
+}
+
+#[test]
+fn test_create_chain_return_selected_variables() {
+    let mut db = GraphDatabase::new_in_memory();
+
+    let stmt = cypher::parse_cypher(
+        r#"CREATE (a:User {name: "Alice"})-[:FRIEND]->(b:User {name: "Bob"}) RETURN b, a"#,
+    )
+    .unwrap();
+    let result = cypher::execute_statement(&mut db, &stmt).unwrap();
+
+    let nodes = match result {
+        cypher::CypherResult::Nodes(nodes) => nodes,
+        _ => panic!("Expected Nodes result"),
+    };
+    // 按 RETURN 的顺序返回
+    let names: Vec<_> = nodes.iter().map(|n| n.get("name").cloned()).collect();
+    assert_eq!(
+        names,
+        vec![Some(Value::Text("Bob".to_string())), Some(Value::Text("Alice".to_string()))]
+    );
+    assert_eq!(db.neighbors_out(nodes[1].id).next().unwrap().end, nodes[0].id);
+}
+
+#[test]
+fn test_create_return_property_columns() {
+    let mut db = GraphDatabase::new_in_memory();
+
+    let stmt = cypher::parse_cypher(
+        r#"CREATE (a:User {name: "Alice", age: 30})-[:FRIEND]->(b:User {name: "Bob"}) RETURN a.name, b.name AS friend"#,
+    )
+    .unwrap();
+    let result = cypher::execute_statement(&mut db, &stmt).unwrap();
+
+    let rows = match result {
+        cypher::CypherResult::Nodes(rows) => rows,
+        _ => panic!("Expected Nodes result"),
+    };
+    // 只返回请求的列，不带节点的其他属性
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].props.len(), 2);
+    assert_eq!(rows[0].get("a.name"), Some(&Value::Text("Alice".to_string())));
+    assert_eq!(rows[0].get("friend"), Some(&Value::Text("Bob".to_string())));
+    assert_eq!(db.node_count(), 2);
+}
+
+#[test]
+fn test_create_return_mixed_items_creates_nothing() {
+    let mut db = GraphDatabase::new_in_memory();
+
+    let stmt = cypher::parse_cypher(r#"CREATE (p:Person {name: "Dan"}) RETURN p, p.name"#).unwrap();
+    match cypher::execute_statement(&mut db, &stmt) {
+        Err(e) => assert!(e.contains("Cannot mix")),
+        Ok(_) => panic!("Expected error"),
+    }
+    assert_eq!(db.node_count(), 0);
+}
+
+#[test]
+fn test_create_return_unknown_variable_creates_nothing() {
+    let mut db = GraphDatabase::new_in_memory();
+
+    let stmt = cypher::parse_cypher(r#"CREATE (p:Person {name: "Dan"}) RETURN q"#).unwrap();
+    match cypher::execute_statement(&mut db, &stmt) {
+        Err(e) => assert!(e.contains("Unknown variable")),
+        Ok(_) => panic!("Expected error"),
+    }
+    assert_eq!(db.node_count(), 0);
+}
@@ -43,8 +43,8 @@ fn test_delete_node_with_relationships() {
     ).unwrap();
     cypher::execute_statement(&mut db, &create_stmt).unwrap();
 
-    // 删除 Alice（会同时删除关系）
-    let delete_stmt = cypher::parse_cypher(r#"MATCH (n:User {name: "Alice"}) DELETE n"#).unwrap();
+    // DETACH DELETE Alice（会同时删除关系）
+    let delete_stmt = cypher::parse_cypher(r#"MATCH (n:User {name: "Alice"}) DETACH DELETE n"#).unwrap();
     let result = cypher::execute_statement(&mut db, &delete_stmt).unwrap();
 
     match result {
@@ -101,3 +101,46 @@ fn test_delete_with_where() {
         _ => panic!("Expected Nodes result"),
     }
 }
+
+#[test]
+fn test_delete_connected_node_requires_detach() {
+    let mut db = GraphDatabase::new_in_memory();
+
+    let create_stmt = cypher::parse_cypher(
+        r#"CREATE (a:User {name: "Alice"})-[:FRIEND]->(b:User {name: "Bob"})"#
+    ).unwrap();
+    cypher::execute_statement(&mut db, &create_stmt).unwrap();
+    cypher::execute_statement(&mut db, &cypher::parse_cypher(r#"CREATE (n:User {name: "Carol"})"#).unwrap()).unwrap();
+
+    // Bob 只有入边，普通 DELETE 同样被拒绝；整条语句不做任何修改
+    let delete_stmt = cypher::parse_cypher(r#"MATCH (n:User) DELETE n"#).unwrap();
+    let err = match cypher::execute_statement(&mut db, &delete_stmt) {
+        Err(e) => e,
+        Ok(_) => panic!("Expected DELETE of connected nodes to fail"),
+    };
+    assert!(err.contains("DETACH DELETE"));
+    assert_eq!(db.node_count(), 3);
+    assert_eq!(db.rel_count(), 1);
+
+    // 孤立节点可以直接删除
+    let delete_stmt = cypher::parse_cypher(r#"MATCH (n:User {name: "Carol"}) DELETE n"#).unwrap();
+    match cypher::execute_statement(&mut db, &delete_stmt).unwrap() {
+        cypher::CypherResult::Deleted { nodes, rels } => {
+            assert_eq!(nodes, 1);
+            assert_eq!(rels, 0);
+        }
+        _ => panic!("Expected Deleted result"),
+    }
+
+    // DETACH DELETE 删除节点及其关系
+    let delete_stmt = cypher::parse_cypher(r#"MATCH (n:User {name: "Bob"}) detach delete n"#).unwrap();
+    match cypher::execute_statement(&mut db, &delete_stmt).unwrap() {
+        cypher::CypherResult::Deleted { nodes, rels } => {
+            assert_eq!(nodes, 1);
+            assert_eq!(rels, 1);
+        }
+        _ => panic!("Expected Deleted result"),
+    }
+    assert_eq!(db.node_count(), 1);
+    assert_eq!(db.rel_count(), 0);
+}
@@ -0,0 +1,144 @@
+//! 日期时间属性值测试：解析/格式化、序列化、存储往返、排序和索引
+
+use rs_graphdb::cypher::{execute_statement, parse_cypher, CypherResult};
+use rs_graphdb::index_composite::CompositeIndexValue;
+use rs_graphdb::query::Query;
+use rs_graphdb::storage::mem_store::MemStore;
+use rs_graphdb::storage::sled_store::SledStore;
+use rs_graphdb::values::{format_datetime, parse_datetime, Properties, TypedValue, Value};
+use rs_graphdb::{GraphDatabase, RangeIndex};
+
+fn dt(s: &str) -> Value {
+    parse_datetime(s).unwrap()
+}
+
+fn event(db: &mut GraphDatabase<MemStore>, name: &str, at: &str) {
+    let mut props = Properties::new();
+    props.insert("name".to_string(), Value::Text(name.to_string()));
+    props.insert("at".to_string(), dt(at));
+    db.create_node(vec!["Event"], props);
+}
+
+#[test]
+fn test_parse_and_format_datetime() {
+    assert_eq!(dt("1970-01-01T00:00:00Z"), Value::DateTime(0));
+    assert_eq!(dt("1970-01-02"), Value::DateTime(86_400_000));
+    assert_eq!(dt("1969-12-31T23:59:59.999Z"), Value::DateTime(-1));
+    assert_eq!(dt("2024-02-29T12:34:56.789Z"), Value::DateTime(1_709_210_096_789));
+    // 时区偏移换算为 UTC，小数秒截断到毫秒
+    assert_eq!(dt("2024-02-29T20:34:56.789123+08:00"), dt("2024-02-29T12:34:56.789Z"));
+    assert_eq!(dt("2024-02-29 12:34"), dt("2024-02-29T12:34:00Z"));
+
+    assert_eq!(format_datetime(1_709_210_096_789), "2024-02-29T12:34:56.789Z");
+    assert_eq!(format_datetime(-1), "1969-12-31T23:59:59.999Z");
+    assert_eq!(Value::DateTime(0).format_datetime().as_deref(), Some("1970-01-01T00:00:00.000Z"));
+    assert_eq!(Value::Int(0).format_datetime(), None);
+
+    for invalid in ["", "2023-02-29", "2024-13-01", "2024-01-01T25:00", "2024-01-01T10:00+8", "yesterday"] {
+        assert!(parse_datetime(invalid).is_err(), "{:?} should be rejected", invalid);
+    }
+    // 超长年份直接拒绝，不会溢出
+    for invalid in ["99999999999999-01-01", "-99999999999999-01-01T00:00Z"] {
+        assert!(parse_datetime(invalid).is_err(), "{:?} should be rejected", invalid);
+    }
+    assert!(parse_datetime("999999-12-31T23:59:59.999Z").is_ok());
+    assert!(parse_datetime("-999999-01-01").is_ok());
+}
+
+#[test]
+fn test_datetime_serde_round_trips() {
+    let value = dt("2024-02-29T12:34:56.789Z");
+
+    // 普通 JSON 输出 ISO-8601 字符串
+    assert_eq!(serde_json::to_value(&value).unwrap(), serde_json::json!("2024-02-29T12:34:56.789Z"));
+
+    // 带类型标签的形式无损往返，也接受毫秒数
+    let typed = serde_json::to_value(TypedValue(value.clone())).unwrap();
+    assert_eq!(typed, serde_json::json!({"type": "datetime", "value": 1_709_210_096_789i64}));
+    assert_eq!(serde_json::from_value::<Value>(typed).unwrap(), value);
+    let from_text: Value =
+        serde_json::from_str(r#"{"type":"datetime","value":"2024-02-29T12:34:56.789Z"}"#).unwrap();
+    assert_eq!(from_text, value);
+    assert!(serde_json::from_str::<Value>(r#"{"type":"datetime","value":"soon"}"#).is_err());
+
+    // 二进制格式
+    let bytes = bincode::serialize(&value).unwrap();
+    assert_eq!(bincode::deserialize::<Value>(&bytes).unwrap(), value);
+}
+
+#[test]
+fn test_datetime_round_trips_through_sled_store() {
+    let dir = tempfile::tempdir().unwrap();
+    let at = dt("2024-02-29T12:34:56.789Z");
+
+    let id = {
+        let mut db = GraphDatabase::from_engine(SledStore::new(dir.path()).unwrap());
+        let mut props = Properties::new();
+        props.insert("at".to_string(), at.clone());
+        props.insert("history".to_string(), Value::List(vec![Value::DateTime(-1), Value::Int(1)]));
+        let id = db.create_node(vec!["Event"], props);
+        db.flush().unwrap();
+        id
+    };
+
+    let db = GraphDatabase::from_engine(SledStore::new(dir.path()).unwrap());
+    let node = db.get_node(id).unwrap();
+    assert_eq!(node.props.get("at"), Some(&at));
+    assert_eq!(
+        node.props.get("history"),
+        Some(&Value::List(vec![Value::DateTime(-1), Value::Int(1)]))
+    );
+}
+
+#[test]
+fn test_datetime_orders_chronologically() {
+    let mut db = GraphDatabase::new_in_memory();
+    event(&mut db, "launch", "2024-03-01T09:00:00Z");
+    event(&mut db, "before-epoch", "1969-07-20T20:17:00Z");
+    event(&mut db, "kickoff", "2024-03-01T08:59:59.999+00:00");
+    event(&mut db, "retro", "2024-03-01T17:00:00+02:00");
+
+    let names = |nodes: Vec<rs_graphdb::graph::model::Node>| -> Vec<String> {
+        nodes
+            .into_iter()
+            .map(|n| match n.props.get("name") {
+                Some(Value::Text(s)) => s.clone(),
+                other => panic!("unexpected name {:?}", other),
+            })
+            .collect()
+    };
+
+    let ascending = names(Query::new(&db).from_label("Event").order_by("at", true).collect_nodes());
+    assert_eq!(ascending, ["before-epoch", "kickoff", "launch", "retro"]);
+
+    let stmt = parse_cypher("MATCH (e:Event) RETURN e ORDER BY e.at DESC").unwrap();
+    let CypherResult::Nodes(nodes) = execute_statement(&mut db, &stmt).unwrap() else {
+        panic!("expected nodes");
+    };
+    assert_eq!(names(nodes), ["retro", "launch", "kickoff", "before-epoch"]);
+}
+
+#[test]
+fn test_datetime_range_and_composite_indexes() {
+    let mut index = RangeIndex::new();
+    index.add("Event", "at", &dt("2024-01-01"), 1);
+    index.add("Event", "at", &dt("2024-02-01"), 2);
+    index.add("Event", "at", &dt("2024-03-01"), 3);
+    // 整数不会混入日期时间的范围查询
+    index.add("Event", "at", &Value::Int(1_706_745_600_000), 4);
+
+    let mut in_range = index.range("Event", "at", &dt("2024-01-15"), &dt("2024-03-01"));
+    in_range.sort_unstable();
+    assert_eq!(in_range, vec![2, 3]);
+    assert_eq!(index.greater_than("Event", "at", &dt("2024-02-01")), vec![3]);
+    assert_eq!(index.less_or_equal("Event", "at", &dt("2024-02-01")), vec![1, 2]);
+    assert_eq!(index.datetime_field_count(), 1);
+
+    index.remove(2);
+    assert_eq!(index.less_than("Event", "at", &dt("2024-03-01")), vec![1]);
+
+    let earlier = CompositeIndexValue::from_value(&dt("1969-12-31T23:59:59Z")).unwrap();
+    let later = CompositeIndexValue::from_value(&dt("2024-02-29")).unwrap();
+    assert_eq!(later, CompositeIndexValue::DateTime(1_709_164_800_000));
+    assert!(earlier < later);
+}
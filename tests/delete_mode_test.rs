@@ -0,0 +1,72 @@
+// 节点删除模式测试：Cascade / Restrict
+
+use rs_graphdb::graph::db::{DeleteMode, GraphDatabase, GraphError};
+use rs_graphdb::storage::mem_store::MemStore;
+use rs_graphdb::storage::NodeId;
+use rs_graphdb::values::{Properties, Value};
+
+fn user(db: &mut GraphDatabase<MemStore>, name: &str) -> NodeId {
+    let mut props = Properties::new();
+    props.insert("name".to_string(), Value::Text(name.to_string()));
+    db.create_node(vec!["User"], props)
+}
+
+#[test]
+fn test_cascade_delete_removes_relationships() {
+    let mut db = GraphDatabase::<MemStore>::new_in_memory();
+    let alice = user(&mut db, "Alice");
+    let bob = user(&mut db, "Bob");
+    let carol = user(&mut db, "Carol");
+
+    let r1 = db.create_rel(alice, bob, "FRIEND", Properties::new());
+    let r2 = db.create_rel(carol, alice, "FOLLOWS", Properties::new());
+
+    let deleted = db.delete_node_mode(alice, DeleteMode::Cascade).unwrap();
+    assert!(deleted);
+    assert!(db.get_node(alice).is_none());
+
+    // 关联关系随节点一起删除
+    assert!(db.get_rel(r1).is_none());
+    assert!(db.get_rel(r2).is_none());
+    assert_eq!(db.neighbors_in(bob).count(), 0);
+    assert_eq!(db.neighbors_out(carol).count(), 0);
+}
+
+#[test]
+fn test_restrict_delete_fails_on_connected_node() {
+    let mut db = GraphDatabase::<MemStore>::new_in_memory();
+    let alice = user(&mut db, "Alice");
+    let bob = user(&mut db, "Bob");
+
+    let r1 = db.create_rel(alice, bob, "FRIEND", Properties::new());
+    let r2 = db.create_rel(bob, alice, "FRIEND", Properties::new());
+
+    match db.delete_node_mode(alice, DeleteMode::Restrict) {
+        Err(GraphError::HasRelationships(rels)) => {
+            assert_eq!(rels, vec![r1, r2]);
+        }
+        other => panic!("expected HasRelationships, got {:?}", other),
+    }
+
+    // 节点与关系都保持不变
+    assert!(db.get_node(alice).is_some());
+    assert!(db.get_rel(r1).is_some());
+    assert!(db.get_rel(r2).is_some());
+}
+
+#[test]
+fn test_restrict_delete_succeeds_on_isolated_node() {
+    let mut db = GraphDatabase::<MemStore>::new_in_memory();
+    let alice = user(&mut db, "Alice");
+    let bob = user(&mut db, "Bob");
+    let rel = db.create_rel(alice, bob, "FRIEND", Properties::new());
+
+    // 先删除关系，节点变为孤立节点后可以删除
+    assert!(db.delete_rel(rel));
+    let deleted = db.delete_node_mode(alice, DeleteMode::Restrict).unwrap();
+    assert!(deleted);
+    assert!(db.get_node(alice).is_none());
+
+    // 不存在的节点返回 false
+    assert!(!db.delete_node_mode(alice, DeleteMode::Restrict).unwrap());
+}
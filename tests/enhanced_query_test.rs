@@ -78,6 +78,66 @@ fn test_regex_match_complex() {
     }
 }
 
+fn matched_names(db: &mut GraphDatabase<MemStore>, query_str: &str) -> Vec<String> {
+    let stmt = parse_cypher(query_str).unwrap();
+    match execute_statement(db, &stmt).unwrap() {
+        CypherResult::Nodes(nodes) => {
+            let mut names: Vec<String> = nodes
+                .iter()
+                .filter_map(|n| match n.props.get("name") {
+                    Some(Value::Text(s)) => Some(s.clone()),
+                    _ => None,
+                })
+                .collect();
+            names.sort();
+            names
+        }
+        _ => panic!("Expected Nodes result"),
+    }
+}
+
+#[test]
+fn test_regex_case_insensitive_prefix() {
+    let mut db = create_test_db();
+    db.create_node(vec!["User"], props("albert", 50, None));
+    db.create_node(vec!["User"], props("ALINA", 22, None));
+    db.create_node(vec!["User"], props("Sal", 45, None));
+
+    let names = matched_names(&mut db, "MATCH (n:User) WHERE n.name =~ \"(?i)^al.*\" RETURN n");
+    assert_eq!(names, vec!["ALINA", "Alice", "albert"]);
+
+    // LIMIT 下推路径使用同一个预编译的正则
+    let names = matched_names(
+        &mut db,
+        "MATCH (n:User) WHERE n.name =~ '(?i)^al' AND n.age > 25 RETURN n LIMIT 5",
+    );
+    assert_eq!(names, vec!["Alice", "albert"]);
+}
+
+#[test]
+fn test_regex_invalid_pattern_is_an_error() {
+    let mut db = create_test_db();
+
+    let stmt = parse_cypher("MATCH (n:User) WHERE n.name =~ '(unclosed' RETURN n").unwrap();
+    let err = match execute_statement(&mut db, &stmt) {
+        Err(e) => e,
+        Ok(_) => panic!("Expected an error for an invalid regex"),
+    };
+    assert!(err.contains("Invalid regular expression '(unclosed'"), "{}", err);
+}
+
+#[test]
+fn test_where_prop_eq_ignore_case() {
+    let mut db = create_test_db();
+    db.create_node(vec!["User"], props("ALICE", 19, None));
+
+    let nodes = rs_graphdb::Query::new(&db)
+        .from_label("User")
+        .where_prop_eq_ignore_case("name", "alice")
+        .collect_nodes();
+    assert_eq!(nodes.len(), 2);
+}
+
 #[test]
 fn test_exists_condition() {
     let mut db = create_test_db();
@@ -167,6 +227,52 @@ fn test_in_operator_int() {
     }
 }
 
+#[test]
+fn test_in_operator_empty_list() {
+    let mut db = create_test_db();
+
+    // 空列表永远不匹配
+    let stmt = parse_cypher("MATCH (n:User) WHERE n.city IN [] RETURN n").unwrap();
+    match execute_statement(&mut db, &stmt).unwrap() {
+        CypherResult::Nodes(nodes) => assert!(nodes.is_empty()),
+        _ => panic!("Expected Nodes result"),
+    }
+}
+
+#[test]
+fn test_in_list_property() {
+    let mut db = create_test_db();
+    let tags = |items: &[&str]| Value::List(items.iter().map(|t| Value::Text(t.to_string())).collect());
+
+    let mut p = props("Grace", 22, None);
+    p.insert("tags".to_string(), tags(&["rust", "graph"]));
+    db.create_node(vec!["User"], p);
+    let mut p = props("Heidi", 45, None);
+    p.insert("tags".to_string(), tags(&["python"]));
+    db.create_node(vec!["User"], p);
+    // 标量属性不参与列表成员判断
+    let mut p = props("Ivan", 50, None);
+    p.insert("tags".to_string(), Value::Text("rust".to_string()));
+    db.create_node(vec!["User"], p);
+
+    // 测试元素在列表属性中：查找 tags 包含 "rust" 的用户
+    let stmt = parse_cypher("MATCH (n:User) WHERE \"rust\" IN n.tags RETURN n").unwrap();
+    match execute_statement(&mut db, &stmt).unwrap() {
+        CypherResult::Nodes(nodes) => {
+            assert_eq!(nodes.len(), 1);
+            assert_eq!(nodes[0].props.get("name"), Some(&Value::Text("Grace".to_string())));
+        }
+        _ => panic!("Expected Nodes result"),
+    }
+
+    // 没有 tags 属性的节点不匹配
+    let stmt = parse_cypher("MATCH (n:User) WHERE 'java' IN n.tags RETURN n").unwrap();
+    match execute_statement(&mut db, &stmt).unwrap() {
+        CypherResult::Nodes(nodes) => assert!(nodes.is_empty()),
+        _ => panic!("Expected Nodes result"),
+    }
+}
+
 #[test]
 fn test_combined_conditions() {
     let mut db = create_test_db();
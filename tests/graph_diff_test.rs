@@ -0,0 +1,113 @@
+// 快照差异比较测试
+
+use rs_graphdb::graph::db::GraphDatabase;
+use rs_graphdb::storage::mem_store::MemStore;
+use rs_graphdb::transactions::{graph_diff, PropertyChange};
+use rs_graphdb::values::{Properties, Value};
+
+fn user(name: &str, age: i64) -> Properties {
+    let mut props = Properties::new();
+    props.insert("name".to_string(), Value::Text(name.to_string()));
+    props.insert("age".to_string(), Value::Int(age));
+    props
+}
+
+#[test]
+fn test_diff_identical_snapshots_is_empty() {
+    let mut db = GraphDatabase::<MemStore>::new_in_memory();
+    let a = db.create_node(vec!["User"], user("Alice", 30));
+    let b = db.create_node(vec!["User"], user("Bob", 25));
+    db.create_rel(a, b, "FRIEND", Properties::new());
+
+    let s1 = db.capture_snapshot(1);
+    let s2 = db.capture_snapshot(2);
+    let diff = graph_diff(&s1, &s2);
+    assert!(diff.is_empty());
+    assert_eq!(diff.change_count(), 0);
+}
+
+#[test]
+fn test_diff_against_mutated_copy() {
+    let mut db = GraphDatabase::<MemStore>::new_in_memory();
+    let alice = db.create_node(vec!["User"], user("Alice", 30));
+    let bob = db.create_node(vec!["User"], user("Bob", 25));
+    let carol = db.create_node(vec!["User"], user("Carol", 40));
+    let r1 = db.create_rel(alice, bob, "FRIEND", Properties::new());
+    db.create_rel(bob, carol, "FRIEND", Properties::new());
+
+    let before = db.capture_snapshot(1);
+
+    // 变更：新增一个节点、修改一个属性、删除一条边
+    let dave = db.create_node(vec!["User"], user("Dave", 22));
+    let mut update = Properties::new();
+    update.insert("age".to_string(), Value::Int(31));
+    db.update_node_props(alice, update);
+    db.delete_rel(r1);
+
+    let after = db.capture_snapshot(2);
+    let diff = graph_diff(&before, &after);
+
+    assert_eq!(diff.added_nodes.len(), 1);
+    assert_eq!(diff.added_nodes[0].id, dave);
+    assert!(diff.removed_nodes.is_empty());
+
+    assert_eq!(diff.modified_nodes.len(), 1);
+    let change = &diff.modified_nodes[0];
+    assert_eq!(change.id, alice);
+    assert!(!change.labels_changed());
+    assert_eq!(
+        change.property_changes,
+        vec![PropertyChange {
+            key: "age".to_string(),
+            before: Some(Value::Int(30)),
+            after: Some(Value::Int(31)),
+        }]
+    );
+
+    assert!(diff.added_rels.is_empty());
+    assert_eq!(diff.removed_rels.len(), 1);
+    assert_eq!(diff.removed_rels[0].id, r1);
+    assert!(diff.modified_rels.is_empty());
+    assert_eq!(diff.change_count(), 3);
+
+    // 反向比较：新增与删除互换
+    let reverse = graph_diff(&after, &before);
+    assert_eq!(reverse.removed_nodes[0].id, dave);
+    assert_eq!(reverse.added_rels[0].id, r1);
+}
+
+#[test]
+fn test_diff_detects_label_and_removed_property() {
+    let mut db = GraphDatabase::<MemStore>::new_in_memory();
+    let id = db.create_node(vec!["User"], user("Alice", 30));
+    let before = db.capture_snapshot(1);
+
+    let mut after = before.clone();
+    let node = after.nodes.get_mut(&id).unwrap();
+    node.labels.push("Admin".to_string());
+    node.properties.remove("age");
+
+    let diff = graph_diff(&before, &after);
+    assert_eq!(diff.modified_nodes.len(), 1);
+    let change = &diff.modified_nodes[0];
+    assert!(change.labels_changed());
+    assert_eq!(change.property_changes.len(), 1);
+    assert_eq!(change.property_changes[0].key, "age");
+    assert_eq!(change.property_changes[0].after, None);
+}
+
+#[test]
+fn test_diff_ignores_label_order() {
+    let mut db = GraphDatabase::<MemStore>::new_in_memory();
+    let id = db.create_node(vec!["User", "Admin"], user("Alice", 30));
+    let before = db.capture_snapshot(1);
+
+    let mut after = before.clone();
+    let node = after.nodes.get_mut(&id).unwrap();
+    node.labels.reverse();
+    node.properties.insert("age".to_string(), Value::Int(31));
+
+    let diff = graph_diff(&before, &after);
+    assert_eq!(diff.modified_nodes.len(), 1);
+    assert!(!diff.modified_nodes[0].labels_changed());
+}
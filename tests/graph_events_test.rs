@@ -0,0 +1,202 @@
+use std::sync::{Arc, Mutex};
+
+use rs_graphdb::graph::model::{Node, Relationship};
+use rs_graphdb::storage::{NodeId, RelId};
+use rs_graphdb::values::{Properties, Value};
+use rs_graphdb::{GraphDatabase, GraphListener};
+
+/// 记录收到的事件
+#[derive(Default)]
+struct Recorder {
+    events: Mutex<Vec<String>>,
+}
+
+impl Recorder {
+    fn push(&self, event: String) -> Result<(), String> {
+        self.events.lock().unwrap().push(event);
+        Ok(())
+    }
+
+    fn take(&self) -> Vec<String> {
+        std::mem::take(&mut *self.events.lock().unwrap())
+    }
+}
+
+impl GraphListener for Recorder {
+    fn on_node_created(&self, node: &Node) -> Result<(), String> {
+        self.push(format!("node_created:{}:{}", node.id, node.labels.join(",")))
+    }
+
+    fn on_node_updated(&self, node: &Node) -> Result<(), String> {
+        self.push(format!("node_updated:{}:{}", node.id, node.props.len()))
+    }
+
+    fn on_node_deleted(&self, id: NodeId) -> Result<(), String> {
+        self.push(format!("node_deleted:{}", id))
+    }
+
+    fn on_rel_created(&self, rel: &Relationship) -> Result<(), String> {
+        self.push(format!("rel_created:{}:{}", rel.id, rel.typ))
+    }
+
+    fn on_rel_updated(&self, rel: &Relationship) -> Result<(), String> {
+        self.push(format!("rel_updated:{}", rel.id))
+    }
+
+    fn on_rel_deleted(&self, id: RelId) -> Result<(), String> {
+        self.push(format!("rel_deleted:{}", id))
+    }
+}
+
+/// 总是返回错误的监听器
+struct Failing;
+
+impl GraphListener for Failing {
+    fn on_node_created(&self, _node: &Node) -> Result<(), String> {
+        Err("search index unavailable".to_string())
+    }
+}
+
+#[test]
+fn test_listener_sees_create_then_delete() {
+    let mut db = GraphDatabase::new_in_memory();
+    let recorder = Arc::new(Recorder::default());
+    db.subscribe(recorder.clone());
+
+    let id = db.create_node(vec!["User"], Properties::new());
+    assert!(db.delete_node(id));
+    // 删除不存在的节点不产生事件
+    assert!(!db.delete_node(id));
+
+    assert_eq!(
+        recorder.take(),
+        vec![format!("node_created:{}:User", id), format!("node_deleted:{}", id)]
+    );
+}
+
+#[test]
+fn test_listener_sees_updates_and_cascaded_rel_deletes() {
+    let mut db = GraphDatabase::new_in_memory();
+    let a = db.create_node(vec!["User"], Properties::new());
+    let b = db.create_node(vec!["User"], Properties::new());
+
+    // 订阅之前的写入不会补发
+    let recorder = Arc::new(Recorder::default());
+    db.subscribe(recorder.clone());
+
+    let rel = db.create_rel(a, b, "FRIEND", Properties::new());
+    let mut props = Properties::new();
+    props.insert("since".to_string(), Value::Int(2020));
+    db.update_rel_props(rel, props.clone());
+    db.update_node_props(a, props);
+    db.delete_node(a);
+
+    assert_eq!(
+        recorder.take(),
+        vec![
+            format!("rel_created:{}:FRIEND", rel),
+            format!("rel_updated:{}", rel),
+            format!("node_updated:{}:1", a),
+            format!("rel_deleted:{}", rel),
+            format!("node_deleted:{}", a),
+        ]
+    );
+}
+
+#[test]
+fn test_listener_errors_do_not_fail_writes() {
+    let mut db = GraphDatabase::new_in_memory();
+    let recorder = Arc::new(Recorder::default());
+    db.subscribe(Arc::new(Failing));
+    db.subscribe(recorder.clone());
+
+    let id = db.create_node(vec!["User"], Properties::new());
+    assert!(db.get_node(id).is_some());
+    // 前一个监听器出错不影响后续监听器
+    assert_eq!(recorder.take(), vec![format!("node_created:{}:User", id)]);
+}
+
+#[test]
+fn test_listener_sees_committed_transaction_writes() {
+    let mut db = GraphDatabase::new_in_memory();
+    let a = db.create_node(vec!["User"], Properties::new());
+    let b = db.create_node(vec!["User"], Properties::new());
+    let old = db.create_rel(a, b, "FRIEND", Properties::new());
+    let recorder = Arc::new(Recorder::default());
+    db.subscribe(recorder.clone());
+
+    let tx = db.begin_tx().unwrap();
+    let c = db.create_node_in_tx(tx, vec!["User"], Properties::new()).unwrap();
+    let rel = db.create_rel_in_tx(tx, c, b, "FRIEND", Properties::new()).unwrap();
+    let mut props = Properties::new();
+    props.insert("since".to_string(), Value::Int(2020));
+    db.update_node_props_in_tx(tx, b, props).unwrap();
+    db.delete_node_in_tx(tx, a).unwrap();
+    // 事务内创建又删除的节点不产生事件
+    let temp = db.create_node_in_tx(tx, vec!["Temp"], Properties::new()).unwrap();
+    db.delete_node_in_tx(tx, temp).unwrap();
+
+    // 提交前不通知，回滚也不通知
+    assert!(recorder.take().is_empty());
+    let rolled_back = db.begin_tx().unwrap();
+    db.create_node_in_tx(rolled_back, vec!["User"], Properties::new()).unwrap();
+    db.rollback_tx(rolled_back).unwrap();
+
+    db.commit_tx(tx).unwrap();
+    assert_eq!(
+        recorder.take(),
+        vec![
+            format!("node_created:{}:User", c),
+            format!("node_updated:{}:1", b),
+            format!("rel_created:{}:FRIEND", rel),
+            format!("rel_deleted:{}", old),
+            format!("node_deleted:{}", a),
+        ]
+    );
+}
+
+#[test]
+fn test_atomic_failure_restores_touched_entities_without_events() {
+    use rs_graphdb::graph::db::GraphError;
+
+    let mut db = GraphDatabase::new_in_memory();
+    let a = db.create_node(vec!["User"], Properties::new());
+    let b = db.create_node(vec!["User"], Properties::new());
+    let c = db.create_node(vec!["User"], Properties::new());
+    let ab = db.create_rel(a, b, "FRIEND", Properties::new());
+    let bc = db.create_rel(b, c, "FRIEND", Properties::new());
+    let recorder = Arc::new(Recorder::default());
+    db.subscribe(recorder.clone());
+
+    let mut props = Properties::new();
+    props.insert("age".to_string(), Value::Int(99));
+    let result: Result<(), GraphError> = db.atomically(|db| {
+        db.update_node_props(b, props.clone());
+        db.delete_rel(ab);
+        db.create_node(vec!["User"], Properties::new());
+        Err(GraphError::NotFound)
+    });
+    assert!(matches!(result, Err(GraphError::NotFound)));
+
+    // 被改动的节点恢复原属性，它上面未被改动的关系也保留
+    assert!(db.get_node(b).unwrap().props.is_empty());
+    assert!(db.get_rel(ab).is_some());
+    assert!(db.get_rel(bc).is_some());
+    assert_eq!(db.node_count(), 3);
+    assert!(recorder.take().is_empty());
+
+    // 成功时暂存的事件按原顺序发出；内层失败只撤销内层
+    let result: Result<NodeId, GraphError> = db.atomically(|db| {
+        let d = db.create_node(vec!["User"], Properties::new());
+        let inner: Result<(), GraphError> = db.atomically(|db| {
+            db.delete_node(a);
+            Err(GraphError::NotFound)
+        });
+        assert!(inner.is_err());
+        Ok(d)
+    });
+    let d = result.unwrap();
+    assert!(db.get_node(a).is_some());
+    assert_eq!(db.neighbors_out(a).count(), 1);
+    assert_eq!(recorder.take(), vec![format!("node_created:{}:User", d)]);
+}
@@ -0,0 +1,88 @@
+//! 图结构指标测试：密度与度同配性
+
+use rs_graphdb::algorithms::{degree_assortativity, graph_density};
+use rs_graphdb::graph::db::GraphDatabase;
+use rs_graphdb::storage::mem_store::MemStore;
+use rs_graphdb::storage::NodeId;
+use rs_graphdb::values::Properties;
+
+fn nodes(db: &mut GraphDatabase<MemStore>, n: usize) -> Vec<NodeId> {
+    (0..n).map(|_| db.create_node(vec!["N"], Properties::new())).collect()
+}
+
+#[test]
+fn test_density_empty_and_single_node() {
+    let mut db = GraphDatabase::new_in_memory();
+    assert_eq!(graph_density(&db), 0.0);
+
+    nodes(&mut db, 1);
+    let density = graph_density(&db);
+    assert_eq!(density, 0.0);
+    assert!(!density.is_nan());
+}
+
+#[test]
+fn test_density_complete_graph() {
+    let mut db = GraphDatabase::new_in_memory();
+    let ids = nodes(&mut db, 5);
+    for &u in &ids {
+        for &v in &ids {
+            if u != v {
+                db.create_rel(u, v, "E", Properties::new());
+            }
+        }
+    }
+    assert_eq!(graph_density(&db), 1.0);
+}
+
+#[test]
+fn test_density_ignores_self_loops_and_parallel_edges() {
+    let mut db = GraphDatabase::new_in_memory();
+    let ids = nodes(&mut db, 3);
+    db.create_rel(ids[0], ids[1], "E", Properties::new());
+    db.create_rel(ids[0], ids[1], "E", Properties::new());
+    db.create_rel(ids[2], ids[2], "E", Properties::new());
+    // 1 个有序节点对 / 6 个可能
+    assert!((graph_density(&db) - 1.0 / 6.0).abs() < 1e-12);
+
+    // 无向关系计为两个方向
+    db.create_undirected_rel(ids[1], ids[2], "E", Properties::new()).unwrap();
+    assert!((graph_density(&db) - 3.0 / 6.0).abs() < 1e-12);
+}
+
+#[test]
+fn test_assortativity_star_is_negative() {
+    let mut db = GraphDatabase::new_in_memory();
+    let ids = nodes(&mut db, 6);
+    for &leaf in &ids[1..] {
+        db.create_rel(ids[0], leaf, "E", Properties::new());
+    }
+    let r = degree_assortativity(&db);
+    assert!(r < 0.0);
+    assert!((r + 1.0).abs() < 1e-9, "star graph should be perfectly disassortative, got {}", r);
+}
+
+#[test]
+fn test_assortativity_degenerate_cases() {
+    let mut db = GraphDatabase::new_in_memory();
+    assert_eq!(degree_assortativity(&db), 0.0);
+
+    // 环：所有节点度数相同，方差为 0
+    let ids = nodes(&mut db, 4);
+    for i in 0..4 {
+        db.create_rel(ids[i], ids[(i + 1) % 4], "E", Properties::new());
+    }
+    assert_eq!(degree_assortativity(&db), 0.0);
+}
+
+#[test]
+fn test_assortativity_two_components_positive() {
+    // 一个三角形（度 2）加一条孤立边（度 1）：度数相近的节点互连，呈正相关
+    let mut db = GraphDatabase::new_in_memory();
+    let ids = nodes(&mut db, 5);
+    db.create_rel(ids[0], ids[1], "E", Properties::new());
+    db.create_rel(ids[1], ids[2], "E", Properties::new());
+    db.create_rel(ids[2], ids[0], "E", Properties::new());
+    db.create_rel(ids[3], ids[4], "E", Properties::new());
+    assert!((degree_assortativity(&db) - 1.0).abs() < 1e-9);
+}
@@ -2,11 +2,13 @@
 // 测试事务管理器与图数据库的集成功能
 
 use rs_graphdb::GraphDatabase;
+use rs_graphdb::graph::db::OptimisticTx;
 use rs_graphdb::transactions::{
     TransactionOp, TransactionConfig,
     IsolationLevel,
 };
 use rs_graphdb::values::{Properties, Value};
+use std::time::Duration;
 
 // 辅助函数：创建测试属性
 fn create_test_properties(name: &str, age: i64) -> Properties {
@@ -314,3 +316,128 @@ fn test_graph_database_all_isolation_levels() {
         assert!(db.commit_transaction(tx_id).is_ok());
     }
 }
+
+// ==================== 乐观事务重试测试 ====================
+
+fn counter_value(db: &GraphDatabase<rs_graphdb::storage::mem_store::MemStore>, id: rs_graphdb::storage::NodeId) -> i64 {
+    match db.get_node(id).and_then(|n| n.get("count").cloned()) {
+        Some(Value::Int(n)) => n,
+        other => panic!("unexpected counter value {:?}", other),
+    }
+}
+
+fn count_props(count: i64) -> Properties {
+    let mut props = Properties::new();
+    props.insert("count".to_string(), Value::Int(count));
+    props
+}
+
+#[test]
+fn test_with_retry_reruns_transaction_after_conflicting_commit() {
+    use rs_graphdb::transactions::{RetryConfig, TransactionError};
+
+    let mut db = GraphDatabase::new_in_memory();
+    let counter = db.create_node(vec!["Counter"], count_props(0));
+    let config = RetryConfig::new().with_backoff(Duration::ZERO, Duration::ZERO);
+
+    let increment = |db: &mut GraphDatabase<_>, tx: &mut OptimisticTx| -> Result<i64, TransactionError> {
+        let current = match tx.get_node(db, counter)?.and_then(|n| n.get("count").cloned()) {
+            Some(Value::Int(n)) => n,
+            _ => return Err(TransactionError::Other("missing counter".to_string())),
+        };
+        tx.set_node_props(db, counter, count_props(current + 1))?;
+        Ok(current + 1)
+    };
+
+    // 第一次执行读取后，另一个事务先提交了对同一节点的修改
+    let mut attempts = 0;
+    let value = db
+        .with_retry(&config, |db, tx| {
+            let value = increment(db, tx)?;
+            attempts += 1;
+            if attempts == 1 {
+                assert_eq!(db.with_retry(&config, increment).unwrap(), 1);
+            }
+            Ok(value)
+        })
+        .unwrap();
+    assert_eq!(attempts, 2);
+    assert_eq!(value, 2);
+    assert_eq!(counter_value(&db, counter), 2);
+
+    // 其他写入把值改回原样同样算冲突；重试次数用尽后返回冲突错误，写入不生效
+    let err = db
+        .with_retry(&RetryConfig::new().with_max_retries(0), |db, tx| {
+            let value = increment(db, tx)?;
+            db.update_node_props(counter, count_props(2));
+            Ok(value)
+        })
+        .unwrap_err();
+    assert!(matches!(err, TransactionError::VersionConflict { .. }));
+    assert_eq!(counter_value(&db, counter), 2);
+
+    // 非冲突错误不重试，事务回滚
+    let mut calls = 0;
+    let err = db
+        .with_retry(&config, |db, tx| {
+            calls += 1;
+            tx.set_node_props(db, counter, count_props(99))?;
+            Err::<(), _>(TransactionError::Other("abort".to_string()))
+        })
+        .unwrap_err();
+    assert!(matches!(err, TransactionError::Other(_)));
+    assert_eq!(calls, 1);
+    assert_eq!(counter_value(&db, counter), 2);
+}
+
+#[test]
+fn test_with_retry_covers_create_and_delete() {
+    use rs_graphdb::transactions::{RetryConfig, TransactionError};
+
+    let mut db = GraphDatabase::new_in_memory();
+    let stale = db.create_node(vec!["Session"], count_props(0));
+    let config = RetryConfig::new().with_backoff(Duration::ZERO, Duration::ZERO);
+
+    // 删除的节点在读取后被其他写入改动：第一次提交冲突，第一次创建的节点随回滚丢弃
+    let mut attempts = 0;
+    let created = db
+        .with_retry(&config, |db, tx| {
+            attempts += 1;
+            let replacement = tx.create_node(db, vec!["Session"], count_props(attempts))?;
+            assert!(tx.delete_node(db, stale)?);
+            if attempts == 1 {
+                db.update_node_props(stale, count_props(0));
+            }
+            Ok(replacement)
+        })
+        .unwrap();
+    assert_eq!(attempts, 2);
+    assert!(db.get_node(stale).is_none());
+    assert_eq!(counter_value(&db, created), 2);
+    assert_eq!(db.node_count(), 1);
+
+    // 事务删除的节点被其他写入先删除：提交冲突，重试时节点已不存在
+    let other = db.create_node(vec!["Session"], count_props(5));
+    let mut seen = Vec::new();
+    db.with_retry(&config, |db, tx| {
+        let existed = tx.delete_node(db, other)?;
+        seen.push(existed);
+        if existed {
+            db.delete_node(other);
+        }
+        Ok::<_, TransactionError>(())
+    })
+    .unwrap();
+    assert_eq!(seen, vec![true, false]);
+
+    // 乐观事务中新建的节点在提交前对事务外不可见
+    let tx_node = db
+        .with_retry(&config, |db, tx| {
+            let id = tx.create_node(db, vec!["Session"], count_props(7))?;
+            assert!(db.get_node(id).is_none());
+            assert!(tx.get_node(db, id)?.is_some());
+            Ok(id)
+        })
+        .unwrap();
+    assert_eq!(counter_value(&db, tx_node), 7);
+}
@@ -0,0 +1,70 @@
+//! gRPC 流式 Cypher 查询测试
+
+#[cfg(feature = "grpc")]
+mod tests {
+    use futures::StreamExt;
+    use rs_graphdb::grpc::proto::graph_db_service_server::GraphDbService;
+    use rs_graphdb::grpc::proto::{value, ExecuteCypherRequest};
+    use rs_graphdb::grpc::GrpcGraphService;
+    use rs_graphdb::service::GraphService;
+    use rs_graphdb::storage::mem_store::MemStore;
+    use rs_graphdb::values::{Properties, Value};
+    use rs_graphdb::GraphDatabase;
+    use std::sync::{Arc, Mutex};
+    use tonic::Request;
+
+    type SharedDb = Arc<Mutex<GraphDatabase<MemStore>>>;
+
+    fn create_service(count: i64) -> (SharedDb, GrpcGraphService<MemStore>) {
+        let mut db = GraphDatabase::new_in_memory();
+        for i in 0..count {
+            let mut props = Properties::new();
+            props.insert("seq".to_string(), Value::Int(i));
+            db.create_node(vec!["Item"], props);
+        }
+        let db = Arc::new(Mutex::new(db));
+        let service = Arc::new(GraphService::new(db.clone()));
+        (db, GrpcGraphService::new(service))
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_execute_cypher_stream_yields_rows_incrementally() {
+        let (db, grpc) = create_service(1000);
+
+        let request = Request::new(ExecuteCypherRequest {
+            query: "MATCH (n:Item) RETURN n".to_string(),
+        });
+        let mut stream = grpc.execute_cypher_stream(request).await.unwrap().into_inner();
+
+        let first = stream.next().await.unwrap().unwrap();
+        assert!(matches!(first.fields["seq"].value, Some(value::Value::IntValue(_))));
+
+        // 流尚未结束时数据库锁可以获取；此时删除剩余节点，之后的批次不再返回它们，
+        // 说明结果是分批求值的，而不是在第一行之前就已全部算好
+        {
+            let mut guard = db.lock().unwrap();
+            let ids: Vec<_> = guard.all_stored_nodes().map(|n| n.id).collect();
+            for id in ids {
+                guard.delete_node(id);
+            }
+        }
+
+        let mut count = 1;
+        while let Some(row) = stream.next().await {
+            row.unwrap();
+            count += 1;
+        }
+        assert!(count < 1000, "all {} rows were computed before the first one was sent", count);
+    }
+
+    #[tokio::test]
+    async fn test_execute_cypher_stream_rejects_invalid_query() {
+        let (_db, grpc) = create_service(1);
+
+        let request = Request::new(ExecuteCypherRequest {
+            query: "NOT A QUERY".to_string(),
+        });
+        let status = grpc.execute_cypher_stream(request).await.err().unwrap();
+        assert_eq!(status.code(), tonic::Code::InvalidArgument);
+    }
+}
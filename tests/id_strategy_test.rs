@@ -0,0 +1,115 @@
+//! ID 分配策略与图合并测试
+
+use rs_graphdb::storage::mem_store::MemStore;
+use rs_graphdb::storage::{IdStrategy, NodeId, StorageError};
+use rs_graphdb::values::{Properties, Value};
+use rs_graphdb::{GraphDatabase, Query};
+
+fn user(db: &mut GraphDatabase<MemStore>, name: &str) -> NodeId {
+    let mut props = Properties::new();
+    props.insert("name".to_string(), Value::Text(name.to_string()));
+    db.create_node(vec!["User"], props)
+}
+
+/// 由 `names` 组成的三角形（有向边 + 一条无向边）
+fn triangle_graph(strategy: IdStrategy, names: [&str; 3]) -> GraphDatabase<MemStore> {
+    let mut db = GraphDatabase::new_in_memory_with_id_strategy(strategy);
+    let [a, b, c] = names.map(|n| user(&mut db, n));
+    db.create_rel(a, b, "KNOWS", Properties::new());
+    db.create_rel(b, c, "KNOWS", Properties::new());
+    db.create_undirected_rel(c, a, "KNOWS", Properties::new()).unwrap();
+    db
+}
+
+#[test]
+fn test_random_ids_do_not_expose_insertion_order() {
+    let mut db = GraphDatabase::new_in_memory_with_id_strategy(IdStrategy::Random);
+    let mut ids: Vec<NodeId> = (0..100).map(|i| user(&mut db, &format!("u{}", i))).collect();
+    ids.extend(db.batch_create_nodes(vec![(vec!["User".to_string()], Properties::new()); 100]));
+
+    let distinct: std::collections::HashSet<_> = ids.iter().collect();
+    assert_eq!(distinct.len(), 200);
+    assert_ne!(ids[..3], [0, 1, 2]);
+    assert_eq!(db.node_count(), 200);
+}
+
+#[test]
+fn test_merge_independent_random_graphs() {
+    let mut left = triangle_graph(IdStrategy::Random, ["alice", "bob", "carol"]);
+    let right = triangle_graph(IdStrategy::Random, ["dave", "erin", "frank"]);
+
+    let (nodes, rels) = left.merge_from(&right).unwrap();
+    assert_eq!((nodes, rels), (3, 3));
+    assert_eq!(left.node_count(), 6);
+    assert_eq!(left.rel_count(), 6);
+    assert_eq!(left.triangle_count(), 2);
+
+    // 合并后的节点保留原 ID，并进入属性索引
+    let dave = Query::new(&left).from_label("User").where_prop_eq("name", "dave").collect_nodes();
+    assert_eq!(dave.len(), 1);
+    assert!(right.get_node(dave[0].id).is_some());
+
+    // 关系（包括无向关系）随节点一起复制
+    let reached: Vec<_> = Query::new(&left)
+        .from_label("User")
+        .where_prop_eq("name", "frank")
+        .out("KNOWS")
+        .collect_nodes();
+    assert_eq!(reached.len(), 1);
+    assert_eq!(reached[0].get("name"), Some(&Value::Text("dave".to_string())));
+}
+
+#[test]
+fn test_merge_sequential_graphs_reports_conflict() {
+    let mut left = triangle_graph(IdStrategy::Sequential, ["alice", "bob", "carol"]);
+    let right = triangle_graph(IdStrategy::Sequential, ["dave", "erin", "frank"]);
+
+    // 两个顺序分配的图 ID 都从 0 开始，合并失败且不写入任何数据
+    match left.merge_from(&right) {
+        Err(StorageError::IdConflict(id)) => assert_eq!(id, 0),
+        other => panic!("Expected IdConflict, got {:?}", other),
+    }
+    assert_eq!(left.node_count(), 3);
+    assert_eq!(left.rel_count(), 3);
+}
+
+#[test]
+fn test_merge_into_sequential_graph_keeps_allocating_fresh_ids() {
+    let mut left = GraphDatabase::new_in_memory();
+    user(&mut left, "alice");
+    let right = triangle_graph(IdStrategy::Random, ["dave", "erin", "frank"]);
+    left.merge_from(&right).unwrap();
+
+    // 顺序分配器不受合并进来的随机 ID 影响，继续从原来的位置分配
+    let next = user(&mut left, "bob");
+    assert_eq!(next, 1);
+    assert!(right.get_node(next).is_none());
+    assert_eq!(left.node_count(), 5);
+}
+
+#[test]
+fn test_sequential_allocation_skips_ids_taken_by_merge() {
+    let mut left = GraphDatabase::new_in_memory();
+    let right = triangle_graph(IdStrategy::Sequential, ["dave", "erin", "frank"]);
+    left.merge_from(&right).unwrap();
+
+    // 0..3 已被合并进来的节点占用
+    let next = user(&mut left, "alice");
+    assert_eq!(next, 3);
+    assert_eq!(left.node_count(), 4);
+}
+
+#[test]
+fn test_random_ids_rejected_by_stores_without_support() {
+    use rs_graphdb::storage::sled_store::SledStore;
+
+    let dir = tempfile::TempDir::new().unwrap();
+    let mut db = GraphDatabase::from_engine(SledStore::new(dir.path()).unwrap());
+    assert!(db.set_id_strategy(IdStrategy::Random).is_err());
+    assert!(db.set_id_strategy(IdStrategy::Sequential).is_ok());
+
+    let mut mem = GraphDatabase::new_in_memory();
+    mem.set_id_strategy(IdStrategy::Random).unwrap();
+    let ids: Vec<NodeId> = (0..3).map(|i| user(&mut mem, &format!("u{}", i))).collect();
+    assert_ne!(ids, [0, 1, 2]);
+}
@@ -0,0 +1,69 @@
+//! 增量连通分量测试
+
+use rs_graphdb::algorithms::connected_components;
+use rs_graphdb::storage::mem_store::MemStore;
+use rs_graphdb::storage::NodeId;
+use rs_graphdb::values::Properties;
+use rs_graphdb::GraphDatabase;
+
+/// 与全量计算的连通分量逐对比较
+fn assert_matches_full(db: &GraphDatabase<MemStore>, nodes: &[NodeId]) {
+    let full = connected_components(db);
+    for &a in nodes {
+        for &b in nodes {
+            assert_eq!(
+                db.same_component(a, b),
+                full[&a] == full[&b],
+                "nodes {} and {}",
+                a,
+                b
+            );
+        }
+    }
+}
+
+#[test]
+fn test_component_of_tracks_inserts_without_rebuild() {
+    let mut db = GraphDatabase::new_in_memory();
+    let nodes: Vec<_> = (0..8).map(|_| db.create_node(vec!["N"], Properties::new())).collect();
+    assert_matches_full(&db, &nodes);
+
+    let edges = [(0, 1), (2, 3), (1, 0), (4, 4), (3, 4), (1, 3), (6, 7)];
+    for (a, b) in edges {
+        db.create_rel(nodes[a], nodes[b], "LINK", Properties::new());
+        assert_matches_full(&db, &nodes);
+    }
+    db.batch_create_rels(vec![(nodes[7], nodes[5], "LINK".to_string(), Properties::new())]);
+    assert_matches_full(&db, &nodes);
+
+    assert_eq!(db.component_of(nodes[0]), db.component_of(nodes[4]));
+    assert_ne!(db.component_of(nodes[0]), db.component_of(nodes[5]));
+    assert_eq!(db.component_of(999), None);
+    assert_eq!(db.component_rebuilds(), 0);
+}
+
+#[test]
+fn test_component_of_rebuilds_after_deletion() {
+    let mut db = GraphDatabase::new_in_memory();
+    let nodes: Vec<_> = (0..5).map(|_| db.create_node(vec!["N"], Properties::new())).collect();
+    db.create_rel(nodes[0], nodes[1], "LINK", Properties::new());
+    let bridge = db.create_rel(nodes[1], nodes[2], "LINK", Properties::new());
+    db.create_rel(nodes[2], nodes[3], "LINK", Properties::new());
+    assert!(db.same_component(nodes[0], nodes[3]));
+
+    // 删除桥把分量拆开
+    db.delete_rel(bridge);
+    assert!(!db.same_component(nodes[0], nodes[3]));
+    assert_matches_full(&db, &nodes);
+    assert_eq!(db.component_rebuilds(), 1);
+
+    // 重建后继续增量合并
+    db.create_rel(nodes[3], nodes[4], "LINK", Properties::new());
+    db.create_rel(nodes[4], nodes[0], "LINK", Properties::new());
+    assert!(db.same_component(nodes[1], nodes[2]));
+
+    db.delete_node(nodes[4]);
+    assert!(!db.same_component(nodes[0], nodes[3]));
+    assert_eq!(db.component_of(nodes[4]), None);
+    assert_eq!(db.component_rebuilds(), 2);
+}
@@ -0,0 +1,72 @@
+//! 索引建议测试
+
+use rs_graphdb::cypher::{execute_statement, parse_cypher};
+use rs_graphdb::index_schema::IndexSchema;
+use rs_graphdb::storage::mem_store::MemStore;
+use rs_graphdb::values::{Properties, Value};
+use rs_graphdb::GraphDatabase;
+
+fn users(schema: IndexSchema) -> GraphDatabase<MemStore> {
+    let mut db = GraphDatabase::new_in_memory_with_schema(schema);
+    for i in 0..50 {
+        let mut props = Properties::new();
+        props.insert("name".to_string(), Value::Text(format!("user{}", i)));
+        props.insert("email".to_string(), Value::Text(format!("user{}@example.com", i)));
+        // 只有两种取值，等值过滤命中一半节点
+        props.insert("active".to_string(), Value::Bool(i % 2 == 0));
+        db.create_node(vec!["User"], props);
+    }
+    db
+}
+
+fn run(db: &mut GraphDatabase<MemStore>, query: &str) {
+    let stmt = parse_cypher(query).unwrap();
+    execute_statement(db, &stmt).unwrap();
+}
+
+#[test]
+fn test_suggests_frequently_filtered_unindexed_property() {
+    let mut schema = IndexSchema::new();
+    schema.add_index("User", "name");
+    let mut db = users(schema);
+
+    for i in 0..5 {
+        run(&mut db, &format!("MATCH (u:User) WHERE u.email = 'user{}@example.com' RETURN u", i));
+    }
+    run(&mut db, "MATCH (u:User {email: 'user7@example.com'}) RETURN u");
+    // 已有索引的属性不会被推荐
+    run(&mut db, "MATCH (u:User {name: 'user1'}) RETURN u");
+    run(&mut db, "MATCH (u:User) WHERE u.name = 'user2' RETURN u");
+    // 范围比较不计入等值过滤
+    run(&mut db, "MATCH (u:User) WHERE u.email > 'a' RETURN u");
+
+    let suggestions = db.suggest_indexes();
+    assert_eq!(suggestions.len(), 1);
+    let top = &suggestions[0];
+    assert_eq!((top.label.as_str(), top.property.as_str()), ("User", "email"));
+    assert_eq!(top.query_count, 6);
+    assert_eq!(top.label_nodes, 50);
+    assert!((top.selectivity - 1.0 / 50.0).abs() < 1e-9);
+    assert!((top.estimated_benefit - 6.0 * 49.0).abs() < 1e-9);
+}
+
+#[test]
+fn test_suggestions_ranked_by_estimated_benefit() {
+    let mut db = users(IndexSchema::new());
+    for _ in 0..3 {
+        run(&mut db, "MATCH (u:User) WHERE u.active = 'x' AND u.email = 'user1@example.com' RETURN u");
+    }
+
+    // 唯一取值的 email 比只有两种取值的 active 收益更高
+    let ranked: Vec<_> = db.suggest_indexes().into_iter().map(|s| s.property).collect();
+    assert_eq!(ranked, vec!["email".to_string(), "active".to_string()]);
+
+    db.clear_query_traces();
+    assert!(db.suggest_indexes().is_empty());
+
+    db.set_trace_capacity(2);
+    for _ in 0..3 {
+        run(&mut db, "MATCH (u:User {email: 'user1@example.com'}) RETURN u");
+    }
+    assert_eq!(db.query_traces().len(), 2);
+}
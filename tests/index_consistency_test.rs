@@ -0,0 +1,134 @@
+// 索引重建与一致性校验测试
+
+use rs_graphdb::graph::db::GraphDatabase;
+use rs_graphdb::index::{IndexInconsistency, IndexKind};
+use rs_graphdb::index_schema::IndexSchema;
+use rs_graphdb::storage::mem_store::MemStore;
+use rs_graphdb::storage::StorageEngine;
+use rs_graphdb::values::{Properties, Value};
+
+fn user(name: &str, age: i64, bio: &str) -> Properties {
+    let mut props = Properties::new();
+    props.insert("name".to_string(), Value::Text(name.to_string()));
+    props.insert("age".to_string(), Value::Int(age));
+    props.insert("bio".to_string(), Value::Text(bio.to_string()));
+    props
+}
+
+fn indexed_db() -> GraphDatabase<MemStore> {
+    let mut schema = IndexSchema::new();
+    schema.add_index("User", "name");
+    GraphDatabase::new_in_memory_with_schema(schema)
+}
+
+#[test]
+fn test_consistent_indexes_report_nothing() {
+    let mut db = indexed_db();
+    db.create_composite_index("user_name_age", "User", &["name", "age"]);
+    let alice = db.create_node(vec!["User"], user("Alice", 30, "rust developer"));
+    db.add_fulltext_index("User", "bio", alice);
+    db.add_range_index("User", "age", alice);
+
+    assert!(db.verify_indexes().is_empty());
+}
+
+#[test]
+fn test_detect_and_repair_stale_entries() {
+    let mut db = indexed_db();
+    let alice = db.create_node(vec!["User"], user("Alice", 30, "rust developer"));
+    let bob = db.create_node(vec!["User"], user("Bob", 25, "go developer"));
+    db.add_fulltext_index("User", "bio", alice);
+    db.add_fulltext_index("User", "bio", bob);
+
+    // 破坏一致性：删除节点后索引中仍残留其索引项
+    db.delete_node(bob);
+
+    let issues = db.verify_indexes();
+    assert!(!issues.is_empty());
+    assert!(issues
+        .iter()
+        .all(|i| matches!(i, IndexInconsistency::StaleEntry(_)) && i.entry().node_id == bob));
+    assert!(issues.iter().any(|i| i.entry().kind == IndexKind::Property));
+    assert!(issues.iter().any(|i| i.entry().kind == IndexKind::FullText));
+
+    db.rebuild_all_indexes();
+    assert!(db.verify_indexes().is_empty());
+    assert!(db.search_fulltext("User", "bio", "go").is_empty());
+    assert_eq!(db.search_fulltext("User", "bio", "rust"), vec![alice]);
+}
+
+#[test]
+fn test_detect_and_repair_missing_entries() {
+    // 数据直接写入存储层，绕过索引维护
+    let mut engine = MemStore::new();
+    let alice = engine.create_node(vec!["User".to_string()], user("Alice", 30, "rust"));
+    let mut schema = IndexSchema::new();
+    schema.add_index("User", "name");
+    let mut db = GraphDatabase::from_engine_with_schema(engine, schema);
+
+    let issues = db.verify_indexes();
+    assert_eq!(issues.len(), 1);
+    match &issues[0] {
+        IndexInconsistency::MissingEntry(entry) => {
+            assert_eq!(entry.kind, IndexKind::Property);
+            assert_eq!(entry.label, "User");
+            assert_eq!(entry.properties, vec!["name".to_string()]);
+            assert_eq!(entry.node_id, alice);
+        }
+        other => panic!("unexpected inconsistency: {:?}", other),
+    }
+
+    db.rebuild_all_indexes();
+    assert!(db.verify_indexes().is_empty());
+}
+
+#[test]
+fn test_update_keeps_property_and_composite_indexes_in_sync() {
+    let mut db = indexed_db();
+    db.create_composite_index("user_name_age", "User", &["name", "age"]);
+    let alice = db.create_node(vec!["User"], user("Alice", 30, "rust"));
+    db.add_range_index("User", "age", alice);
+
+    // 属性更新按旧值移除、新值加入，单属性、复合和范围索引都不会留下过期项
+    let mut update = Properties::new();
+    update.insert("name".to_string(), Value::Text("Alicia".to_string()));
+    update.insert("age".to_string(), Value::Int(31));
+    db.update_node_props(alice, update);
+
+    assert!(db.verify_indexes().is_empty());
+    assert!(db
+        .find_by_composite_index("User", &["name", "age"], &[Value::Text("Alice".into()), Value::Int(30)])
+        .is_empty());
+    assert_eq!(
+        db.find_by_composite_index("User", &["name", "age"], &[Value::Text("Alicia".into()), Value::Int(31)]),
+        vec![alice]
+    );
+    assert_eq!(db.range_greater_than("User", "age", Value::Int(30)), vec![alice]);
+}
+
+#[test]
+fn test_rebuild_uses_registered_fields_even_when_index_is_empty() {
+    let mut db = indexed_db();
+    let mut props = Properties::new();
+    props.insert("name".to_string(), Value::Text("Ghost".to_string()));
+    let ghost = db.create_node(vec!["User"], props);
+    // 节点缺少这两个属性，索引中没有任何条目，但字段已经登记
+    db.add_fulltext_index("User", "bio", ghost);
+    db.add_range_index("User", "age", ghost);
+    assert!(db.list_indexes().iter().any(|i| i.id == "range:User.age"));
+    assert!(db.list_indexes().iter().any(|i| i.id == "fulltext:User.bio"));
+
+    let alice = db.create_node(vec!["User"], user("Alice", 30, "rust developer"));
+    let issues = db.verify_indexes();
+    assert!(issues.iter().any(|i| matches!(i, IndexInconsistency::MissingEntry(_)) && i.entry().kind == IndexKind::Range));
+
+    db.rebuild_all_indexes();
+    assert!(db.verify_indexes().is_empty());
+    assert_eq!(db.range_greater_than("User", "age", Value::Int(18)), vec![alice]);
+    assert_eq!(db.search_fulltext("User", "bio", "rust"), vec![alice]);
+
+    assert!(db.drop_index("range:User.age"));
+    assert!(!db.drop_index("range:User.age"));
+    db.rebuild_all_indexes();
+    assert!(db.range_greater_than("User", "age", Value::Int(18)).is_empty());
+}
@@ -0,0 +1,133 @@
+//! MemStore 事务测试：事务内读取、读已提交隔离、提交与回滚
+
+use rs_graphdb::storage::mem_store::MemStore;
+use rs_graphdb::storage::StorageEngine;
+use rs_graphdb::values::{Properties, Value};
+use rs_graphdb::index_schema::IndexSchema;
+use rs_graphdb::{GraphDatabase, Query};
+
+fn named(name: &str) -> Properties {
+    let mut props = Properties::new();
+    props.insert("name".to_string(), Value::Text(name.to_string()));
+    props
+}
+
+#[test]
+fn test_tx_reads_see_own_overlay_until_rollback() {
+    let mut store = MemStore::new();
+    let alice = store.create_node(vec!["User".into()], named("Alice"));
+
+    let tx = store.begin_tx().unwrap();
+    let bob = store.tx_create_node(tx, vec!["User".into()], named("Bob")).unwrap();
+    let knows = store
+        .tx_create_rel(tx, alice, bob, "KNOWS".into(), Properties::new())
+        .unwrap();
+    store.tx_update_node_props(tx, alice, named("Alicia")).unwrap();
+
+    // 事务内看到自己的写入，事务外看不到
+    assert_eq!(store.tx_get_node(tx, bob).unwrap().unwrap().props, named("Bob"));
+    assert_eq!(store.tx_get_node(tx, alice).unwrap().unwrap().props, named("Alicia"));
+    assert_eq!(store.tx_get_rel(tx, knows).unwrap().map(|r| (r.start, r.end)), Some((alice, bob)));
+    assert!(store.get_node(bob).is_none());
+    assert_eq!(store.get_node(alice).unwrap().props, named("Alice"));
+
+    // 端点在事务视角下必须存在
+    assert!(store.tx_create_rel(tx, alice, 999, "KNOWS".into(), Properties::new()).is_err());
+
+    // 删除节点级联删除事务内的关系
+    assert!(store.tx_delete_node(tx, bob).unwrap());
+    assert!(store.tx_get_rel(tx, knows).unwrap().is_none());
+    assert!(!store.tx_delete_node(tx, bob).unwrap());
+
+    store.rollback_tx(tx).unwrap();
+    assert_eq!(store.node_count(), 1);
+    assert_eq!(store.rel_count(), 0);
+    assert_eq!(store.get_node(alice).unwrap().props, named("Alice"));
+    assert!(store.tx_get_node(tx, alice).is_err());
+}
+
+#[test]
+fn test_concurrent_handles_are_read_committed() {
+    let mut store = MemStore::new();
+    let alice = store.create_node(vec!["User".into()], named("Alice"));
+    let bob = store.create_node(vec!["User".into()], named("Bob"));
+    let knows = store.create_rel(alice, bob, "KNOWS".into(), Properties::new());
+
+    let writer = store.begin_tx().unwrap();
+    let reader = store.begin_tx().unwrap();
+    store.tx_update_node_props(writer, alice, named("Alicia")).unwrap();
+    assert!(store.tx_delete_rel(writer, knows).unwrap());
+
+    // 未提交的写入对其他事务不可见
+    assert_eq!(store.tx_get_node(reader, alice).unwrap().unwrap().props, named("Alice"));
+    assert!(store.tx_get_rel(reader, knows).unwrap().is_some());
+
+    store.commit_tx(writer).unwrap();
+    assert_eq!(store.tx_get_node(reader, alice).unwrap().unwrap().props, named("Alicia"));
+    assert!(store.tx_get_rel(reader, knows).unwrap().is_none());
+    assert_eq!(store.rel_count(), 0);
+
+    // 端点已被其他事务删除时，整个提交被拒绝且不产生部分写入
+    let linker = store.begin_tx().unwrap();
+    let carol = store.tx_create_node(linker, vec!["User".into()], named("Carol")).unwrap();
+    store.tx_create_rel(linker, carol, bob, "KNOWS".into(), Properties::new()).unwrap();
+    assert!(store.tx_delete_node(reader, bob).unwrap());
+    store.commit_tx(reader).unwrap();
+
+    assert!(store.commit_tx(linker).is_err());
+    assert!(store.get_node(carol).is_none());
+    assert_eq!(store.node_count(), 1);
+    assert_eq!(store.rel_count(), 0);
+}
+
+#[test]
+fn test_graph_commit_applies_relationship_writes() {
+    let mut db = GraphDatabase::new_in_memory();
+    let nodes: Vec<_> = ["a", "b", "c"].iter().map(|n| db.create_node(vec!["N"], named(n))).collect();
+    db.create_rel(nodes[0], nodes[1], "LINK", Properties::new());
+    db.create_rel(nodes[1], nodes[2], "LINK", Properties::new());
+
+    let tx = db.begin_tx().unwrap();
+    let closing = db.create_rel_in_tx(tx, nodes[2], nodes[0], "LINK", Properties::new()).unwrap();
+    assert_eq!(db.get_rel_in_tx(tx, closing).unwrap().map(|r| r.typ), Some("LINK".to_string()));
+    assert!(db.get_rel(closing).is_none());
+    assert_eq!(db.triangle_count(), 0);
+
+    db.commit_tx(tx).unwrap();
+    assert!(db.get_rel(closing).is_some());
+    assert_eq!(db.triangle_count(), 1);
+
+    let tx = db.begin_tx().unwrap();
+    assert!(db.delete_node_in_tx(tx, nodes[1]).unwrap());
+    assert!(db.get_node_in_tx(tx, nodes[1]).unwrap().is_none());
+    assert!(db.get_node(nodes[1]).is_some());
+    db.commit_tx(tx).unwrap();
+    assert!(db.get_node(nodes[1]).is_none());
+    assert_eq!(db.rel_count(), 1);
+    assert_eq!(db.triangle_count(), 0);
+}
+
+#[test]
+fn test_failed_commit_keeps_property_index() {
+    let mut schema = IndexSchema::new();
+    schema.add_index("User", "name");
+    let mut db = GraphDatabase::new_in_memory_with_schema(schema);
+    let alice = db.create_node(vec!["User"], named("Alice"));
+    let bob = db.create_node(vec!["User"], named("Bob"));
+
+    let tx = db.begin_tx().unwrap();
+    db.update_node_props_in_tx(tx, alice, named("Alicia")).unwrap();
+    db.create_rel_in_tx(tx, alice, bob, "KNOWS", Properties::new()).unwrap();
+    // 另一个事务先删除了关系端点，提交整体失败
+    let other = db.begin_tx().unwrap();
+    db.delete_node_in_tx(other, bob).unwrap();
+    db.commit_tx(other).unwrap();
+    assert!(db.commit_tx(tx).is_err());
+
+    let by_name = |db: &GraphDatabase<MemStore>, name: &str| {
+        Query::new(db).from_label_and_prop_eq("User", "name", name).collect_nodes().len()
+    };
+    assert_eq!(by_name(&db, "Alice"), 1);
+    assert_eq!(by_name(&db, "Alicia"), 0);
+    assert_eq!(by_name(&db, "Bob"), 0);
+}
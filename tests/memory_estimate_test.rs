@@ -0,0 +1,99 @@
+use rs_graphdb::storage::mem_store::MemStore;
+use rs_graphdb::storage::StorageEngine;
+use rs_graphdb::values::{Properties, Value};
+use rs_graphdb::GraphDatabase;
+
+#[test]
+fn test_memory_estimate_grows_with_large_properties() {
+    let mut store = MemStore::new();
+    let empty = store.memory_estimate();
+    assert_eq!(empty.total(), 0);
+
+    let mut props = Properties::new();
+    props.insert("body".to_string(), Value::Text("x".repeat(10_000)));
+    let a = store.create_node(vec!["Doc".to_string()], props.clone());
+    let b = store.create_node(vec!["Doc".to_string()], props);
+    let nodes_only = store.memory_estimate();
+    assert!(nodes_only.nodes >= 20_000);
+    assert_eq!(nodes_only.relationships, 0);
+
+    store.create_rel(a, b, "LINKS".to_string(), Properties::new());
+    let report = store.memory_estimate();
+    assert!(report.relationships > 0);
+    assert!(report.adjacency > nodes_only.adjacency);
+    assert_eq!(report.indexes, 0);
+}
+
+#[test]
+fn test_database_memory_estimate_includes_indexes() {
+    let mut db = GraphDatabase::new_in_memory();
+    let before = db.memory_estimate();
+
+    // User.name 被默认 schema 索引
+    for i in 0..10 {
+        let mut props = Properties::new();
+        props.insert("name".to_string(), Value::Text(format!("user-{:0>500}", i)));
+        db.create_node(vec!["User"], props);
+    }
+
+    let after = db.memory_estimate();
+    assert!(after.nodes > before.nodes + 5_000);
+    assert!(after.indexes > before.indexes + 5_000);
+    assert_eq!(
+        after.total(),
+        after.nodes + after.relationships + after.adjacency + after.indexes
+    );
+}
+
+#[test]
+fn test_compact_reclaims_memory_after_deletes() {
+    let mut db = GraphDatabase::new_in_memory();
+    let hub = db.create_node(vec!["Hub"], Properties::new());
+    let leaves: Vec<_> = (0..500)
+        .map(|_| db.create_node(vec!["Leaf"], Properties::new()))
+        .collect();
+    let rels: Vec<_> = leaves
+        .iter()
+        .map(|&leaf| db.create_rel(hub, leaf, "LINK", Properties::new()))
+        .collect();
+    let kept = rels[0];
+    for &rel in &rels[1..] {
+        db.delete_rel(rel);
+    }
+
+    let before = db.memory_estimate();
+    let reclaimed = db.compact().unwrap();
+    let after = db.memory_estimate();
+
+    assert!(reclaimed > 0);
+    assert!(after.total() < before.total());
+    assert_eq!(before.total() - after.total(), reclaimed);
+
+    // 压缩不改变图的内容
+    assert_eq!(db.rel_count(), 1);
+    assert_eq!(db.neighbors_out(hub).map(|r| r.id).collect::<Vec<_>>(), vec![kept]);
+    assert_eq!(db.neighbors_in(leaves[0]).count(), 1);
+
+    // 已经紧凑时再次压缩不回收任何空间
+    assert_eq!(db.compact().unwrap(), 0);
+}
+
+#[test]
+fn test_compact_sled_removes_empty_adjacency() {
+    let dir = tempfile::tempdir().unwrap();
+    let engine = rs_graphdb::storage::sled_store::SledStore::new(dir.path()).unwrap();
+    let mut db = GraphDatabase::from_engine(engine);
+
+    let a = db.create_node(vec!["User"], Properties::new());
+    let b = db.create_node(vec!["User"], Properties::new());
+    let c = db.create_node(vec!["User"], Properties::new());
+    let ab = db.create_rel(a, b, "FRIEND", Properties::new());
+    db.create_rel(b, c, "FRIEND", Properties::new());
+    db.delete_rel(ab);
+
+    // a 的出边表和 b 的入边表已空
+    assert!(db.compact().unwrap() > 0);
+    assert_eq!(db.compact().unwrap(), 0);
+    assert_eq!(db.neighbors_out(b).count(), 1);
+    assert_eq!(db.neighbors_in(c).count(), 1);
+}
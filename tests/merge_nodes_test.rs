@@ -0,0 +1,106 @@
+//! 重复节点合并测试
+
+use rs_graphdb::graph::db::{GraphError, PropertyConflict};
+use rs_graphdb::index_schema::IndexSchema;
+use rs_graphdb::values::{Properties, Value};
+use rs_graphdb::GraphDatabase;
+
+fn user(name: &str, email: &str) -> Properties {
+    let mut props = Properties::new();
+    props.insert("name".to_string(), Value::Text(name.to_string()));
+    props.insert("email".to_string(), Value::Text(email.to_string()));
+    props
+}
+
+#[test]
+fn test_merge_duplicate_users() {
+    let mut schema = IndexSchema::new();
+    schema.add_index("User", "name");
+    let mut db = GraphDatabase::new_in_memory_with_schema(schema);
+
+    let alice = db.create_node(vec!["User"], user("Alice", "alice@example.com"));
+    let dup = db.create_node(vec!["User"], user("Alice B.", "alice@example.com"));
+    let bob = db.create_node(vec!["User"], user("Bob", "bob@example.com"));
+    let carol = db.create_node(vec!["User"], user("Carol", "carol@example.com"));
+    let acme = db.create_node(vec!["Company"], Properties::new());
+
+    db.create_rel(alice, bob, "FRIEND", Properties::new());
+    let mut since = Properties::new();
+    since.insert("since".to_string(), Value::Int(2019));
+    db.create_rel(dup, bob, "FRIEND", since);
+    db.create_rel(dup, carol, "FRIEND", Properties::new());
+    db.create_rel(carol, dup, "FOLLOWS", Properties::new());
+    db.create_rel(dup, acme, "WORKS_AT", Properties::new());
+    db.create_rel(alice, dup, "SAME_AS", Properties::new());
+
+    db.merge_nodes(alice, dup, PropertyConflict::PreferFirst).unwrap();
+
+    assert!(db.get_node(dup).is_none());
+    assert_eq!(db.node_count(), 4);
+    let node = db.get_node(alice).unwrap();
+    assert_eq!(node.props.get("name"), Some(&Value::Text("Alice".to_string())));
+
+    // 出边是两个节点出边的并集，重复的 FRIEND -> Bob 只保留一条并补上属性
+    let mut out: Vec<_> = db
+        .neighbors_out(alice)
+        .map(|r| (r.typ.clone(), r.end))
+        .collect();
+    out.sort();
+    let mut expected = vec![
+        ("FRIEND".to_string(), bob),
+        ("FRIEND".to_string(), carol),
+        ("WORKS_AT".to_string(), acme),
+    ];
+    expected.sort();
+    assert_eq!(out, expected);
+    let to_bob = db.neighbors_out(alice).find(|r| r.end == bob).unwrap();
+    assert_eq!(to_bob.props.get("since"), Some(&Value::Int(2019)));
+
+    let incoming: Vec<_> = db.neighbors_in(alice).map(|r| (r.typ, r.start)).collect();
+    assert_eq!(incoming, vec![("FOLLOWS".to_string(), carol)]);
+    assert_eq!(db.rel_count(), 4);
+
+    // 被删除节点的索引条目已清理
+    assert!(db.verify_indexes().is_empty());
+
+    assert!(matches!(
+        db.merge_nodes(alice, dup, PropertyConflict::PreferFirst),
+        Err(GraphError::NotFound)
+    ));
+}
+
+#[test]
+fn test_merge_nodes_prefer_second_updates_index() {
+    let mut schema = IndexSchema::new();
+    schema.add_index("User", "name");
+    let mut db = GraphDatabase::new_in_memory_with_schema(schema);
+    let keep = db.create_node(vec!["User"], user("alice", "a@example.com"));
+    let remove = db.create_node(vec!["User"], user("Alice", "a@example.com"));
+
+    db.merge_nodes(keep, remove, PropertyConflict::PreferSecond).unwrap();
+
+    let node = db.get_node(keep).unwrap();
+    assert_eq!(node.props.get("name"), Some(&Value::Text("Alice".to_string())));
+    assert!(db.verify_indexes().is_empty());
+}
+
+#[test]
+fn test_merge_nodes_dedups_undirected_rel_stored_in_reverse() {
+    let mut db = GraphDatabase::new_in_memory();
+    let alice = db.create_node(vec!["User"], user("Alice", "alice@example.com"));
+    let dup = db.create_node(vec!["User"], user("Alice B.", "alice@example.com"));
+    let bob = db.create_node(vec!["User"], user("Bob", "bob@example.com"));
+
+    // 已有关系以 bob 为起点保存，改连后的关系以 alice 为起点
+    db.create_undirected_rel(bob, alice, "KNOWS", Properties::new()).unwrap();
+    let mut since = Properties::new();
+    since.insert("since".to_string(), Value::Int(2019));
+    db.create_undirected_rel(dup, bob, "KNOWS", since).unwrap();
+
+    db.merge_nodes(alice, dup, PropertyConflict::PreferFirst).unwrap();
+
+    let knows: Vec<_> = db.neighbors_out(alice).filter(|r| r.typ == "KNOWS").collect();
+    assert_eq!(knows.len(), 1);
+    assert_eq!(knows[0].props.get("since"), Some(&Value::Int(2019)));
+    assert_eq!(db.rel_count(), 1);
+}
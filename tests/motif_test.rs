@@ -0,0 +1,57 @@
+//! 子图模式计数测试
+
+use rs_graphdb::algorithms::{count_motif, count_triangles, MotifPattern};
+use rs_graphdb::storage::mem_store::MemStore;
+use rs_graphdb::storage::NodeId;
+use rs_graphdb::values::Properties;
+use rs_graphdb::GraphDatabase;
+
+fn graph(node_count: usize, edges: &[(usize, usize)]) -> GraphDatabase<MemStore> {
+    let mut db = GraphDatabase::new_in_memory();
+    let nodes: Vec<NodeId> = (0..node_count)
+        .map(|_| db.create_node(vec!["N"], Properties::new()))
+        .collect();
+    for &(a, b) in edges {
+        db.create_rel(nodes[a], nodes[b], "LINK", Properties::new());
+    }
+    db
+}
+
+#[test]
+fn test_count_wedges() {
+    // 星形：中心 0 连接 4 个叶子，C(4, 2) = 6 个开放三元组
+    let star = graph(5, &[(0, 1), (0, 2), (0, 3), (0, 4)]);
+    assert_eq!(count_motif(&star, MotifPattern::Wedge), 6);
+    assert_eq!(count_motif(&star, MotifPattern::Path2), 6);
+
+    // 连接两个叶子后闭合一个三元组，新增的两条路径都以三角形的形式闭合
+    let closed = graph(5, &[(0, 1), (0, 2), (0, 3), (0, 4), (1, 2)]);
+    assert_eq!(count_motif(&closed, MotifPattern::Wedge), 5);
+    assert_eq!(count_motif(&closed, MotifPattern::Triangle), 1);
+    assert_eq!(count_motif(&closed, MotifPattern::Path2), 5 + 3);
+
+    // 方向、反向平行边和自环不影响计数
+    let path = graph(3, &[(0, 1), (2, 1), (1, 0), (1, 1)]);
+    assert_eq!(count_motif(&path, MotifPattern::Wedge), 1);
+}
+
+#[test]
+fn test_count_four_cycles() {
+    let square = graph(4, &[(0, 1), (1, 2), (2, 3), (3, 0)]);
+    assert_eq!(count_motif(&square, MotifPattern::Square), 1);
+
+    // 3 个方格的梯子只有两个 4-环（外圈是 6-环）
+    let ladder = graph(6, &[(0, 1), (1, 2), (3, 4), (4, 5), (0, 3), (1, 4), (2, 5)]);
+    assert_eq!(count_motif(&ladder, MotifPattern::Square), 2);
+
+    // K4 含 3 个 4-环、4 个三角形，没有开放三元组
+    let k4 = graph(4, &[(0, 1), (0, 2), (0, 3), (1, 2), (1, 3), (2, 3)]);
+    assert_eq!(count_motif(&k4, MotifPattern::Square), 3);
+    assert_eq!(count_motif(&k4, MotifPattern::Triangle), count_triangles(&k4));
+    assert_eq!(count_motif(&k4, MotifPattern::Triangle), 4);
+    assert_eq!(count_motif(&k4, MotifPattern::Wedge), 0);
+
+    // K2,3：任取右侧两个节点都与左侧两个节点组成一个 4-环
+    let k23 = graph(5, &[(0, 2), (0, 3), (0, 4), (1, 2), (1, 3), (1, 4)]);
+    assert_eq!(count_motif(&k23, MotifPattern::Square), 3);
+}
@@ -0,0 +1,101 @@
+//! 边权重归一化测试
+
+use rs_graphdb::graph::db::WeightNormalization;
+use rs_graphdb::storage::mem_store::MemStore;
+use rs_graphdb::storage::RelId;
+use rs_graphdb::values::{Properties, Value};
+use rs_graphdb::GraphDatabase;
+
+/// 一条链上的 ROAD 关系，`cost` 依次为 `costs`；另有一条不参与归一化的 RAIL 关系
+fn road_graph(costs: &[Value]) -> (GraphDatabase<MemStore>, Vec<RelId>, RelId) {
+    let mut db = GraphDatabase::new_in_memory();
+    let mut prev = db.create_node(vec!["City"], Properties::new());
+    let mut roads = Vec::new();
+    for cost in costs {
+        let next = db.create_node(vec!["City"], Properties::new());
+        let mut props = Properties::new();
+        props.insert("cost".to_string(), cost.clone());
+        roads.push(db.create_rel(prev, next, "ROAD", props));
+        prev = next;
+    }
+    let first = db.create_node(vec!["City"], Properties::new());
+    let mut props = Properties::new();
+    props.insert("cost".to_string(), Value::Int(1000));
+    let rail = db.create_rel(prev, first, "RAIL", props);
+    (db, roads, rail)
+}
+
+fn weight(db: &GraphDatabase<MemStore>, rel: RelId, prop: &str) -> Option<f64> {
+    match db.get_rel(rel)?.props.get(prop)? {
+        Value::Float(f) => Some(*f),
+        _ => None,
+    }
+}
+
+#[test]
+fn test_min_max_normalization() {
+    let costs = [Value::Int(10), Value::Float(25.0), Value::Int(40), Value::Int(20)];
+    let (mut db, roads, rail) = road_graph(&costs);
+
+    assert_eq!(db.normalize_edge_weights("ROAD", "cost", "weight"), 4);
+
+    let normalized: Vec<f64> = roads.iter().map(|&r| weight(&db, r, "weight").unwrap()).collect();
+    assert_eq!(normalized[0], 0.0);
+    assert_eq!(normalized[2], 1.0);
+    assert!((normalized[1] - 0.5).abs() < 1e-9);
+    assert!(normalized.iter().all(|w| (0.0..=1.0).contains(w)));
+
+    // 原属性保留，其他类型的关系不受影响
+    assert_eq!(db.get_rel(roads[0]).unwrap().props.get("cost"), Some(&Value::Int(10)));
+    assert_eq!(weight(&db, rail, "weight"), None);
+}
+
+#[test]
+fn test_normalization_all_equal_and_non_numeric() {
+    let costs = [Value::Int(7), Value::Int(7), Value::Text("n/a".to_string())];
+    let (mut db, roads, _) = road_graph(&costs);
+
+    // 所有值相等时不会除以零；非数值的关系被跳过
+    assert_eq!(db.normalize_edge_weights("ROAD", "cost", "weight"), 2);
+    assert_eq!(weight(&db, roads[0], "weight"), Some(0.0));
+    assert_eq!(weight(&db, roads[1], "weight"), Some(0.0));
+    assert_eq!(weight(&db, roads[2], "weight"), None);
+
+    assert_eq!(
+        db.normalize_edge_weights_with("ROAD", "cost", "z", WeightNormalization::ZScore),
+        2
+    );
+    assert_eq!(weight(&db, roads[0], "z"), Some(0.0));
+}
+
+#[test]
+fn test_z_score_normalization() {
+    let costs = [2, 4, 4, 4, 5, 5, 7, 9].map(Value::Int);
+    let (mut db, roads, _) = road_graph(&costs);
+
+    // 均值 5，总体标准差 2
+    db.normalize_edge_weights_with("ROAD", "cost", "z", WeightNormalization::ZScore);
+    assert_eq!(weight(&db, roads[0], "z"), Some(-1.5));
+    assert_eq!(weight(&db, roads[4], "z"), Some(0.0));
+    assert_eq!(weight(&db, roads[7], "z"), Some(2.0));
+}
+
+#[test]
+fn test_weight_histogram() {
+    let costs: Vec<Value> = [0, 1, 2, 2, 5, 6, 9, 10].iter().map(|&c| Value::Int(c)).collect();
+    let (db, _, _) = road_graph(&costs);
+
+    // 区间宽 2.5：[0,2.5) [2.5,5) [5,7.5) [7.5,10]；RAIL 关系不计入
+    let histogram = db.weight_histogram("ROAD", "cost", 4);
+    assert_eq!(
+        histogram,
+        vec![(0.0, 2.5, 4), (2.5, 5.0, 0), (5.0, 7.5, 2), (7.5, 10.0, 2)]
+    );
+
+    // 权重全部相等时只有一个区间
+    let (db, _, _) = road_graph(&[Value::Float(3.0), Value::Int(3)]);
+    assert_eq!(db.weight_histogram("ROAD", "cost", 5), vec![(3.0, 3.0, 2)]);
+
+    assert!(db.weight_histogram("ROAD", "missing", 5).is_empty());
+    assert!(db.weight_histogram("ROAD", "cost", 0).is_empty());
+}
@@ -422,3 +422,289 @@ fn test_complex_pattern_with_where() {
         panic!("Expected nodes result");
     }
 }
+
+#[test]
+fn test_where_pattern_predicate() {
+    let mut db = GraphDatabase::new_in_memory();
+
+    let alice = create_person_return_id(&mut db, "Alice", 30);
+    let bob = create_person_return_id(&mut db, "Bob", 25);
+    let carol = create_person_return_id(&mut db, "Carol", 40);
+    let admin = db.create_node(vec!["Admin"], Properties::new());
+
+    // Alice 有 Admin 好友；Bob 只有普通好友；Carol 被 Admin 关注但自己没有 Admin 好友
+    db.create_rel(alice, admin, "FRIEND", Properties::new());
+    db.create_rel(alice, bob, "FRIEND", Properties::new());
+    db.create_rel(bob, carol, "FRIEND", Properties::new());
+    db.create_rel(admin, carol, "FRIEND", Properties::new());
+
+    let names = |db: &mut GraphDatabase<_>, query: &str| -> Vec<String> {
+        let stmt = parse_cypher(query).unwrap();
+        let rs_graphdb::cypher::CypherResult::Nodes(nodes) = execute_statement(db, &stmt).unwrap() else {
+            panic!("Expected nodes result");
+        };
+        let mut names: Vec<String> = nodes
+            .iter()
+            .filter_map(|n| match n.props.get("name") {
+                Some(Value::Text(s)) => Some(s.clone()),
+                _ => None,
+            })
+            .collect();
+        names.sort();
+        names
+    };
+
+    assert_eq!(
+        names(&mut db, "MATCH (p:Person) WHERE (p)-[:FRIEND]->(:Admin) RETURN p"),
+        vec!["Alice"]
+    );
+    assert_eq!(
+        names(&mut db, "MATCH (p:Person) WHERE EXISTS { (p)<-[:FRIEND]-(:Admin) } RETURN p"),
+        vec!["Carol"]
+    );
+    // 与普通条件组合；多段模式
+    assert_eq!(
+        names(&mut db, "MATCH (p:Person) WHERE (p)-[:FRIEND]->(:Person)-[:FRIEND]->(:Person) AND p.age < 35 RETURN p"),
+        vec!["Alice"]
+    );
+}
+
+#[test]
+fn test_where_path_length() {
+    let mut db = GraphDatabase::new_in_memory();
+
+    // 链：n0 -> n1 -> ... -> n6，外加 n0 -> hub -> n6 的捷径
+    let chain: Vec<NodeId> = (0..7)
+        .map(|i| create_person_return_id(&mut db, &format!("n{}", i), 20 + i))
+        .collect();
+    for pair in chain.windows(2) {
+        db.create_rel(pair[0], pair[1], "LINK", Properties::new());
+    }
+    let hub = create_person_return_id(&mut db, "hub", 50);
+    db.create_rel(chain[0], hub, "LINK", Properties::new());
+    db.create_rel(hub, chain[6], "LINK", Properties::new());
+
+    let names = |db: &mut GraphDatabase<_>, query: &str| -> Vec<String> {
+        let stmt = parse_cypher(query).unwrap();
+        let rs_graphdb::cypher::CypherResult::Nodes(nodes) = execute_statement(db, &stmt).unwrap() else {
+            panic!("Expected nodes result");
+        };
+        let mut names: Vec<String> = nodes
+            .iter()
+            .filter_map(|n| match n.props.get("name") {
+                Some(Value::Text(s)) => Some(s.clone()),
+                _ => None,
+            })
+            .collect();
+        names.sort();
+        names
+    };
+
+    // n5 距离 5 跳，被排除；n6 经捷径只有 2 跳
+    assert_eq!(
+        names(&mut db, "MATCH p = (a:Person {name: 'n0'})-[:LINK*]->(b) WHERE length(p) <= 4 RETURN b"),
+        vec!["hub", "n1", "n2", "n3", "n4", "n6"]
+    );
+    assert_eq!(
+        names(&mut db, "MATCH p = (a:Person {name: 'n0'})-[:LINK*]->(b) WHERE 2 < length(p) AND length(p) < 5 AND b.age > 22 RETURN b"),
+        vec!["n3", "n4"]
+    );
+    // 固定长度部分计入路径长度
+    assert_eq!(
+        names(&mut db, "MATCH p = (a:Person {name: 'n0'})-[:LINK]->(m)-[:LINK*]->(b) WHERE length(p) = 3 RETURN b"),
+        vec!["n3"]
+    );
+    assert!(names(&mut db, "MATCH p = (a:Person {name: 'n0'})-[:LINK]->(b) WHERE length(p) > 1 RETURN b").is_empty());
+
+    let stmt = parse_cypher("MATCH p = (a)-[:LINK*]->(b) WHERE length(q) < 2 RETURN b").unwrap();
+    assert!(execute_statement(&mut db, &stmt).is_err());
+}
+
+#[test]
+fn test_relationship_variable_properties() {
+    let mut db = GraphDatabase::new_in_memory();
+    let alice = create_person_return_id(&mut db, "Alice", 30);
+    let bob = create_person_return_id(&mut db, "Bob", 25);
+    let carol = create_person_return_id(&mut db, "Carol", 35);
+    let dave = create_person_return_id(&mut db, "Dave", 40);
+
+    let since = |year: i64| {
+        let mut props = Properties::new();
+        props.insert("since".to_string(), Value::Int(year));
+        props
+    };
+    db.create_rel(alice, bob, "FRIEND", since(2015));
+    db.create_rel(alice, carol, "FRIEND", since(2020));
+    db.create_rel(bob, dave, "FRIEND", since(2022));
+    db.create_rel(alice, dave, "KNOWS", since(2023));
+
+    let run = |db: &mut GraphDatabase<_>, query: &str| -> Vec<(String, Value)> {
+        let stmt = parse_cypher(query).unwrap();
+        let rs_graphdb::cypher::CypherResult::Nodes(nodes) = execute_statement(db, &stmt).unwrap() else {
+            panic!("Expected nodes result");
+        };
+        nodes
+            .iter()
+            .map(|n| {
+                // 每行只包含 RETURN 的列，关系属性不会写进节点属性
+                assert_eq!(n.props.len(), 2);
+                let name = match n.props.get("b.name") {
+                    Some(Value::Text(s)) => s.clone(),
+                    other => panic!("unexpected name {:?}", other),
+                };
+                (name, n.props.get("r.since").cloned().unwrap_or(Value::Null))
+            })
+            .collect()
+    };
+
+    // RETURN 读取关系属性，ORDER BY 按关系属性排序
+    let rows = run(
+        &mut db,
+        "MATCH (a:Person)-[r:FRIEND]->(b:Person) RETURN b.name, r.since ORDER BY r.since DESC",
+    );
+    assert_eq!(
+        rows,
+        vec![
+            ("Dave".to_string(), Value::Int(2022)),
+            ("Carol".to_string(), Value::Int(2020)),
+            ("Bob".to_string(), Value::Int(2015)),
+        ]
+    );
+
+    // WHERE 同时按关系属性和起点属性过滤
+    let rows = run(
+        &mut db,
+        "MATCH (a:Person)-[r:FRIEND]->(b:Person) WHERE r.since > 2016 AND a.name = 'Alice' RETURN b.name, r.since",
+    );
+    assert_eq!(rows, vec![("Carol".to_string(), Value::Int(2020))]);
+
+    // 两跳路径中的关系变量各自绑定
+    let stmt = parse_cypher(
+        "MATCH (a:Person)-[r1:FRIEND]->(b)-[r2:FRIEND]->(c) WHERE r1.since < r2.since RETURN c.name, r2.since AS later",
+    )
+    .unwrap();
+    let rs_graphdb::cypher::CypherResult::Nodes(nodes) = execute_statement(&mut db, &stmt).unwrap() else {
+        panic!("Expected nodes result");
+    };
+    assert_eq!(nodes.len(), 1);
+    assert_eq!(nodes[0].props.get("c.name"), Some(&Value::Text("Dave".to_string())));
+    assert_eq!(nodes[0].props.get("later"), Some(&Value::Int(2022)));
+
+    // 返回整个节点变量时得到该变量绑定的节点本身，属性保持不变
+    let stmt = parse_cypher("MATCH (a:Person)-[r:FRIEND]->(b:Person) WHERE r.since = 2022 RETURN a").unwrap();
+    let rs_graphdb::cypher::CypherResult::Nodes(nodes) = execute_statement(&mut db, &stmt).unwrap() else {
+        panic!("Expected nodes result");
+    };
+    assert_eq!(nodes, vec![db.get_node(bob).unwrap()]);
+    assert!(!nodes[0].props.keys().any(|k| k.starts_with("r.")));
+    let stmt = parse_cypher("MATCH (a:Person)-[r:FRIEND]->(b:Person) RETURN b, r.since").unwrap();
+    assert!(execute_statement(&mut db, &stmt).is_err());
+
+    // 不支持的形式返回错误而不是静默忽略关系变量
+    let stmt = parse_cypher("MATCH (a:Person)-[r:FRIEND*1..2]->(b) WHERE r.since > 2000 RETURN b").unwrap();
+    assert!(execute_statement(&mut db, &stmt).is_err());
+}
+
+#[test]
+fn test_path_length_lower_bound_keeps_nodes_with_shorter_paths() {
+    let mut db = GraphDatabase::new_in_memory();
+
+    // a -> b -> c -> d，外加 a -> d：d 既是 1 跳也是 3 跳可达
+    let ids: Vec<NodeId> = ["a", "b", "c", "d"]
+        .iter()
+        .enumerate()
+        .map(|(i, name)| create_person_return_id(&mut db, name, 20 + i as i64))
+        .collect();
+    for pair in ids.windows(2) {
+        db.create_rel(pair[0], pair[1], "LINK", Properties::new());
+    }
+    db.create_rel(ids[0], ids[3], "LINK", Properties::new());
+
+    let names = |db: &mut GraphDatabase<_>, query: &str| -> Vec<String> {
+        let stmt = parse_cypher(query).unwrap();
+        let rs_graphdb::cypher::CypherResult::Nodes(nodes) = execute_statement(db, &stmt).unwrap() else {
+            panic!("Expected nodes result");
+        };
+        let mut names: Vec<String> = nodes
+            .iter()
+            .filter_map(|n| match n.props.get("name") {
+                Some(Value::Text(s)) => Some(s.clone()),
+                _ => None,
+            })
+            .collect();
+        names.sort();
+        names
+    };
+
+    assert_eq!(
+        names(&mut db, "MATCH p = (x:Person {name: 'a'})-[:LINK*]->(y) WHERE length(p) = 3 RETURN y"),
+        vec!["d"]
+    );
+    assert_eq!(
+        names(&mut db, "MATCH p = (x:Person {name: 'a'})-[:LINK*]->(y) WHERE length(p) >= 2 RETURN y"),
+        vec!["c", "d"]
+    );
+    assert_eq!(
+        names(&mut db, "MATCH (x:Person {name: 'a'})-[:LINK*3..3]->(y) RETURN y"),
+        vec!["d"]
+    );
+    // 反向遍历同理：a 到 d 有 1 跳和 3 跳两条路径
+    assert_eq!(
+        names(&mut db, "MATCH (x:Person {name: 'd'})<-[:LINK*3..]-(y) RETURN y"),
+        vec!["a"]
+    );
+}
+
+#[test]
+fn test_where_pattern_predicate_anchored_on_matched_variable() {
+    let mut db = GraphDatabase::new_in_memory();
+
+    let alice = create_person_return_id(&mut db, "Alice", 30);
+    let bob = create_person_return_id(&mut db, "Bob", 25);
+    let carol = create_person_return_id(&mut db, "Carol", 40);
+    let admin = db.create_node(vec!["Admin"], Properties::new());
+
+    // Admin -> Carol -> Alice；Bob -> Admin
+    db.create_rel(admin, carol, "FRIEND", Properties::new());
+    db.create_rel(carol, alice, "FRIEND", Properties::new());
+    db.create_rel(bob, admin, "FRIEND", Properties::new());
+
+    let names = |db: &mut GraphDatabase<_>, query: &str| -> Vec<String> {
+        let stmt = parse_cypher(query).unwrap();
+        let rs_graphdb::cypher::CypherResult::Nodes(nodes) = execute_statement(db, &stmt).unwrap() else {
+            panic!("Expected nodes result");
+        };
+        let mut names: Vec<String> = nodes
+            .iter()
+            .filter_map(|n| match n.props.get("name") {
+                Some(Value::Text(s)) => Some(s.clone()),
+                _ => None,
+            })
+            .collect();
+        names.sort();
+        names
+    };
+
+    assert_eq!(
+        names(&mut db, "MATCH (p:Person) WHERE (p)-[:FRIEND]->(:Admin) RETURN p"),
+        vec!["Bob"]
+    );
+    // 绑定变量不在模式开头：从 p 出发反向匹配
+    assert_eq!(
+        names(&mut db, "MATCH (p:Person) WHERE (:Admin)-[:FRIEND]->(p) RETURN p"),
+        vec!["Carol"]
+    );
+    // 绑定变量在模式中间：两侧都要匹配
+    assert_eq!(
+        names(&mut db, "MATCH (p:Person) WHERE (:Admin)-[:FRIEND]->(p)-[:FRIEND]->(:Person) RETURN p"),
+        vec!["Carol"]
+    );
+    assert_eq!(
+        names(&mut db, "MATCH (p:Person) WHERE (:Admin)-[:FRIEND]->(:Person)-[:FRIEND]->(p) RETURN p"),
+        vec!["Alice"]
+    );
+
+    // 模式中没有当前行的变量时报错，而不是按第一个节点求值
+    let stmt = parse_cypher("MATCH (p:Person) WHERE (q)-[:FRIEND]->(:Admin) RETURN p").unwrap();
+    assert!(execute_statement(&mut db, &stmt).is_err());
+}
@@ -0,0 +1,134 @@
+use rs_graphdb::cypher::{execute_statement, parse_cypher};
+use rs_graphdb::graph::db::{GraphError, PropertyLimits};
+use rs_graphdb::values::{Properties, Value};
+use rs_graphdb::GraphDatabase;
+
+fn props(pairs: &[(&str, Value)]) -> Properties {
+    pairs
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.clone()))
+        .collect()
+}
+
+#[test]
+fn test_rejects_oversized_text_property() {
+    let mut db = GraphDatabase::new_in_memory()
+        .with_property_limits(PropertyLimits::new().with_max_value_bytes(16));
+
+    let ok = db.try_create_node(vec!["Doc"], props(&[("title", Value::Text("short".into()))]));
+    assert!(ok.is_ok());
+
+    let err = db
+        .try_create_node(vec!["Doc"], props(&[("body", Value::Text("x".repeat(17)))]))
+        .unwrap_err();
+    match &err {
+        GraphError::PropertyTooLarge { key, size, limit } => {
+            assert_eq!((key.as_str(), *size, *limit), ("body", 17, 16));
+        }
+        other => panic!("unexpected error: {:?}", other),
+    }
+    assert!(err.to_string().contains("'body'"));
+    assert_eq!(db.node_count(), 1);
+
+    // 更新同样受限，且被拒绝时不修改节点
+    let id = ok.unwrap();
+    let err = db
+        .try_update_node_props(id, props(&[("title", Value::Text("y".repeat(32)))]))
+        .unwrap_err();
+    assert!(matches!(err, GraphError::PropertyTooLarge { .. }));
+    assert_eq!(
+        db.get_node(id).unwrap().props.get("title"),
+        Some(&Value::Text("short".into()))
+    );
+}
+
+#[test]
+fn test_rejects_too_many_properties() {
+    let mut db = GraphDatabase::new_in_memory();
+    db.set_property_limits(PropertyLimits::new().with_max_properties(2));
+
+    let err = db
+        .try_create_node(
+            vec!["User"],
+            props(&[("a", Value::Int(1)), ("b", Value::Int(2)), ("c", Value::Int(3))]),
+        )
+        .unwrap_err();
+    assert!(matches!(err, GraphError::TooManyProperties { count: 3, limit: 2 }));
+    assert_eq!(db.node_count(), 0);
+
+    // 合并后的属性数量超限也会被拒绝；覆盖已有键不增加数量
+    let id = db
+        .try_create_node(vec!["User"], props(&[("a", Value::Int(1)), ("b", Value::Int(2))]))
+        .unwrap();
+    assert!(db.try_update_node_props(id, props(&[("a", Value::Int(10))])).is_ok());
+    assert!(matches!(
+        db.try_update_node_props(id, props(&[("c", Value::Int(3))])),
+        Err(GraphError::TooManyProperties { count: 3, limit: 2 })
+    ));
+}
+
+#[test]
+fn test_limits_are_opt_in() {
+    let mut db = GraphDatabase::new_in_memory();
+    assert_eq!(db.property_limits(), PropertyLimits::default());
+
+    let many: Properties = (0..100)
+        .map(|i| (format!("k{}", i), Value::Text("v".repeat(1024))))
+        .collect();
+    assert!(db.try_create_node(vec!["Blob"], many).is_ok());
+}
+
+#[test]
+fn test_limits_apply_to_relationships_and_batches() {
+    let mut db = GraphDatabase::new_in_memory()
+        .with_property_limits(PropertyLimits::new().with_max_properties(1));
+    let a = db.create_node(vec!["N"], Properties::new());
+    let b = db.create_node(vec!["N"], Properties::new());
+
+    let two = props(&[("w", Value::Int(1)), ("x", Value::Int(2))]);
+    assert!(matches!(
+        db.try_create_rel(a, b, "LINK", two.clone()),
+        Err(GraphError::TooManyProperties { count: 2, limit: 1 })
+    ));
+    let rel = db.try_create_rel(a, b, "LINK", props(&[("w", Value::Int(1))])).unwrap();
+    assert!(db.try_update_rel_props(rel, props(&[("x", Value::Int(2))])).is_err());
+    assert_eq!(db.get_rel(rel).unwrap().props.len(), 1);
+
+    // 批量写入整批拒绝，不产生部分写入
+    let nodes = vec![
+        (vec!["N".to_string()], Properties::new()),
+        (vec!["N".to_string()], two.clone()),
+    ];
+    assert!(db.try_batch_create_nodes(nodes).is_err());
+    assert_eq!(db.node_count(), 2);
+    assert!(db.try_batch_create_rels(vec![(a, b, "LINK".to_string(), two)]).is_err());
+    assert_eq!(db.rel_count(), 1);
+
+    assert!(db.try_update_matching("N", |_| true, props(&[("y", Value::Int(3))])).is_ok());
+    assert!(db.try_update_matching("N", |_| true, props(&[("z", Value::Int(4))])).is_err());
+    assert!(!db.get_node(a).unwrap().props.contains_key("z"));
+}
+
+#[test]
+fn test_cypher_writes_respect_limits() {
+    let mut db = GraphDatabase::new_in_memory()
+        .with_property_limits(PropertyLimits::new().with_max_value_bytes(8));
+
+    let run = |db: &mut GraphDatabase<_>, q: &str| execute_statement(db, &parse_cypher(q).unwrap());
+    assert!(run(&mut db, "CREATE (n:User {name: 'Ann'})").is_ok());
+    assert!(run(&mut db, "CREATE (n:User {name: 'Bartholomew'})").is_err());
+    assert_eq!(db.node_count(), 1);
+    // 链中后面的节点超限时，前面已创建的节点和关系一起撤销
+    assert!(run(&mut db, "CREATE (a:User {name: 'Cy'})-[:KNOWS]->(b:User {name: 'Dee'})-[:KNOWS]->(c:User {name: 'Bartholomew'})").is_err());
+    assert_eq!(db.node_count(), 1);
+    assert_eq!(db.rel_count(), 0);
+    assert!(run(&mut db, "MERGE (a:User {name: 'Cy'})-[:KNOWS]->(b:User {name: 'Bartholomew'})").is_err());
+    assert_eq!(db.node_count(), 1);
+
+    assert!(run(&mut db, "MATCH (n:User) SET n.name = 'Bartholomew'").is_err());
+    assert_eq!(
+        db.all_stored_nodes().next().unwrap().props.get("name"),
+        Some(&Value::Text("Ann".into()))
+    );
+    assert!(run(&mut db, "MATCH (n:User) SET n.name = 'Bo'").is_ok());
+}
@@ -27,3 +27,116 @@ fn simple_query_from_label_where_out() {
     assert_eq!(result.len(), 1);
     assert_eq!(result[0].id, bob);
 }
+
+#[test]
+fn expand_edges_returns_start_rel_end_triples() {
+    let mut db = GraphDatabase::new_in_memory();
+
+    let alice: NodeId = db.create_node(vec!["User"], make_user("Alice"));
+    let bob: NodeId = db.create_node(vec!["User"], make_user("Bob"));
+    let carol: NodeId = db.create_node(vec!["User"], make_user("Carol"));
+
+    let mut since = Properties::new();
+    since.insert("since".to_string(), Value::Int(2020));
+    let friend = db.create_rel(alice, bob, "FRIEND", since);
+    db.create_rel(alice, carol, "BLOCKS", Properties::new());
+
+    let triples = Query::new(&db)
+        .from_label("User")
+        .where_prop_eq("name", "Alice")
+        .expand_edges("FRIEND");
+
+    assert_eq!(triples.len(), 1);
+    let (start, rel, end) = &triples[0];
+    assert_eq!(start.id, alice);
+    assert_eq!(start.get("name"), Some(&Value::Text("Alice".to_string())));
+    assert_eq!(rel.id, friend);
+    assert_eq!(rel.typ, "FRIEND");
+    assert_eq!(rel.props.get("since"), Some(&Value::Int(2020)));
+    assert_eq!(end.id, bob);
+    assert_eq!(end.get("name"), Some(&Value::Text("Bob".to_string())));
+}
+
+#[test]
+fn where_has_prop_and_missing_prop_split_nodes() {
+    let mut db = GraphDatabase::new_in_memory();
+
+    let mut alice = make_user("Alice");
+    alice.insert("email".to_string(), Value::Text("alice@example.com".to_string()));
+    let alice = db.create_node(vec!["User"], alice);
+    let bob = db.create_node(vec!["User"], make_user("Bob"));
+    let mut carol = make_user("Carol");
+    carol.insert("email".to_string(), Value::Text("carol@example.com".to_string()));
+    let carol = db.create_node(vec!["User"], carol);
+
+    let mut with_email: Vec<NodeId> = Query::new(&db)
+        .from_label("User")
+        .where_has_prop("email")
+        .collect_nodes()
+        .into_iter()
+        .map(|n| n.id)
+        .collect();
+    with_email.sort();
+    let mut expected = vec![alice, carol];
+    expected.sort();
+    assert_eq!(with_email, expected);
+
+    let without_email = Query::new(&db)
+        .from_label("User")
+        .where_missing_prop("email")
+        .collect_nodes();
+    assert_eq!(without_email.len(), 1);
+    assert_eq!(without_email[0].id, bob);
+
+    // 与其它 where_ 过滤组合使用
+    let carol_only = Query::new(&db)
+        .from_label("User")
+        .where_has_prop("email")
+        .where_prop_eq("name", "Carol")
+        .collect_nodes();
+    assert_eq!(carol_only.len(), 1);
+    assert_eq!(carol_only[0].id, carol);
+
+    let none = Query::new(&db)
+        .from_label("User")
+        .where_missing_prop("email")
+        .where_prop_eq("name", "Alice")
+        .collect_nodes();
+    assert!(none.is_empty());
+}
+
+#[test]
+fn traversed_rels_exposes_edge_properties() {
+    let mut db = GraphDatabase::new_in_memory();
+
+    let alice = db.create_node(vec!["User"], make_user("Alice"));
+    let bob = db.create_node(vec!["User"], make_user("Bob"));
+    let carol = db.create_node(vec!["User"], make_user("Carol"));
+
+    let since = |year: i64| {
+        let mut props = Properties::new();
+        props.insert("since".to_string(), Value::Int(year));
+        props
+    };
+    db.create_rel(alice, bob, "FRIEND", since(2018));
+    db.create_rel(alice, carol, "FRIEND", since(2022));
+    db.create_rel(alice, carol, "WORKS_WITH", since(2020));
+
+    let q = Query::new(&db).from_label("User").where_prop_eq("name", "Alice").out("FRIEND");
+    let mut seen: Vec<(NodeId, Value)> = q
+        .traversed_rels()
+        .iter()
+        .map(|rel| (rel.end, rel.props["since"].clone()))
+        .collect();
+    seen.sort_by_key(|(id, _)| *id);
+    assert_eq!(seen, vec![(bob, Value::Int(2018)), (carol, Value::Int(2022))]);
+
+    // 反向遍历后记录的是入边
+    let q = q.in_("WORKS_WITH");
+    assert_eq!(q.traversed_rels().len(), 1);
+    assert_eq!(q.traversed_rels()[0].start, alice);
+
+    // 可变长度遍历不保留关系
+    let q = q.out_variable_length("FRIEND", 1, Some(2));
+    assert!(q.traversed_rels().is_empty());
+}
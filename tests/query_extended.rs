@@ -1,6 +1,7 @@
 use rs_graphdb::{GraphDatabase, NodeId};
 use rs_graphdb::values::{Properties, Value};
 use rs_graphdb::query::Query;
+use rs_graphdb::storage::PropPredicate;
 
 fn make_user(name: &str, age: i64) -> Properties {
     let mut props = Properties::new();
@@ -33,3 +34,107 @@ fn query_chain_with_multiple_steps() {
     assert_eq!(result.len(), 1);
     assert_eq!(result[0].id, carol);
 }
+
+fn since(year: i64) -> Properties {
+    let mut props = Properties::new();
+    props.insert("since".to_string(), Value::Int(year));
+    props
+}
+
+#[test]
+fn out_where_filters_on_edge_properties() {
+    let mut db = GraphDatabase::new_in_memory();
+
+    let alice = db.create_node(vec!["User"], make_user("Alice", 30));
+    let bob = db.create_node(vec!["User"], make_user("Bob", 25));
+    let carol = db.create_node(vec!["User"], make_user("Carol", 40));
+    let dave = db.create_node(vec!["User"], make_user("Dave", 35));
+
+    db.create_rel(alice, bob, "FRIEND", since(2018));
+    db.create_rel(alice, carol, "FRIEND", since(2021));
+    db.create_rel(alice, dave, "FRIEND", since(2023));
+    // 类型不同的关系即使属性满足条件也不走
+    db.create_rel(alice, bob, "COLLEAGUE", since(2022));
+
+    let ids = |q: Query<_>| {
+        let mut ids: Vec<NodeId> = q.collect_nodes().iter().map(|n| n.id).collect();
+        ids.sort();
+        ids
+    };
+
+    let recent = Query::new(&db)
+        .from_label("User")
+        .where_prop_eq("name", "Alice")
+        .out_where_pred("FRIEND", "since", &PropPredicate::Gt(Value::Int(2020)));
+    assert_eq!(ids(recent), vec![carol, dave]);
+
+    let exact = Query::new(&db)
+        .from_label("User")
+        .where_prop_eq("name", "Alice")
+        .out_where("FRIEND", "since", &Value::Int(2018));
+    assert_eq!(ids(exact), vec![bob]);
+}
+
+#[test]
+fn out_where_skips_edges_missing_the_property() {
+    let mut db = GraphDatabase::new_in_memory();
+
+    let alice = db.create_node(vec!["User"], make_user("Alice", 30));
+    let bob = db.create_node(vec!["User"], make_user("Bob", 25));
+    let carol = db.create_node(vec!["User"], make_user("Carol", 40));
+
+    db.create_rel(alice, bob, "FRIEND", Properties::new());
+    db.create_rel(bob, carol, "FRIEND", since(2022));
+
+    // 第一跳没有 since 属性，整条路径被过滤
+    let result = Query::new(&db)
+        .from_label("User")
+        .where_prop_eq("name", "Alice")
+        .out_where_pred("FRIEND", "since", &PropPredicate::Exists)
+        .out("FRIEND")
+        .collect_nodes();
+    assert!(result.is_empty());
+
+    // 普通 out 之后再按关系属性过滤下一跳
+    let result = Query::new(&db)
+        .from_label("User")
+        .where_prop_eq("name", "Alice")
+        .out("FRIEND")
+        .out_where_pred("FRIEND", "since", &PropPredicate::Gte(Value::Int(2022)))
+        .collect_nodes();
+    assert_eq!(result.len(), 1);
+    assert_eq!(result[0].id, carol);
+}
+
+#[test]
+fn multi_label_all_vs_any() {
+    let mut db = GraphDatabase::new_in_memory();
+
+    let user = db.create_node(vec!["User"], make_user("Alice", 30));
+    let admin = db.create_node(vec!["User", "Admin"], make_user("Bob", 40));
+    let bot = db.create_node(vec!["Bot"], make_user("Crawler", 1));
+    let admin_bot = db.create_node(vec!["Admin", "Bot"], make_user("Ops", 2));
+    db.create_node(vec!["Service"], make_user("Mailer", 3));
+
+    let sorted = |mut ids: Vec<NodeId>| {
+        ids.sort();
+        ids
+    };
+    let ids = |query: Query<'_, _>| sorted(query.collect_nodes().into_iter().map(|n| n.id).collect());
+    let select_all = |labels: &[&str]| ids(Query::new(&db).from_labels_all(labels));
+    let select_any = |labels: &[&str]| ids(Query::new(&db).from_labels_any(labels));
+
+    assert_eq!(select_all(&["User", "Admin"]), vec![admin]);
+    assert_eq!(select_all(&["Admin", "User"]), vec![admin]);
+    assert_eq!(select_all(&["Admin"]), sorted(vec![admin, admin_bot]));
+    assert!(select_all(&["User", "Bot"]).is_empty());
+
+    assert_eq!(select_any(&["User", "Bot"]), sorted(vec![user, admin, bot, admin_bot]));
+    assert_eq!(select_any(&["Admin"]), sorted(vec![admin, admin_bot]));
+    assert!(select_any(&[]).is_empty());
+    assert_eq!(select_all(&[]).len(), 5);
+
+    // 可以继续链式过滤
+    let old_admins = Query::new(&db).from_labels_any(&["Admin"]).where_prop_int_gt("age", 10).count();
+    assert_eq!(old_admins, 1);
+}
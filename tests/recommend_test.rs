@@ -0,0 +1,72 @@
+//! "朋友的朋友"推荐测试
+
+use rs_graphdb::GraphDatabase;
+use rs_graphdb::storage::mem_store::MemStore;
+use rs_graphdb::storage::NodeId;
+use rs_graphdb::values::{Properties, Value};
+
+fn person(db: &mut GraphDatabase<MemStore>, name: &str) -> NodeId {
+    let mut props = Properties::new();
+    props.insert("name".to_string(), Value::Text(name.to_string()));
+    db.create_node(vec!["Person"], props)
+}
+
+/// alice 的朋友：bob、carol、dave
+/// - erin 与 bob、carol、dave 都是朋友（3 个共同好友）
+/// - frank 与 bob、carol 是朋友（2 个）
+/// - grace 与 dave 是朋友（1 个）
+/// - heidi 只通过 WORKS_WITH 与 bob 相连，不参与推荐
+fn friendship_graph() -> (GraphDatabase<MemStore>, Vec<NodeId>) {
+    let mut db = GraphDatabase::new_in_memory();
+    let names = ["alice", "bob", "carol", "dave", "erin", "frank", "grace", "heidi"];
+    let ids: Vec<NodeId> = names.iter().map(|n| person(&mut db, n)).collect();
+    let [alice, bob, carol, dave, erin, frank, grace, heidi] = ids[..] else {
+        unreachable!()
+    };
+
+    for friend in [bob, carol, dave] {
+        db.create_rel(alice, friend, "FRIEND", Properties::new());
+    }
+    // 方向混用：推荐不区分方向
+    db.create_rel(erin, bob, "FRIEND", Properties::new());
+    db.create_rel(carol, erin, "FRIEND", Properties::new());
+    db.create_undirected_rel(dave, erin, "FRIEND", Properties::new()).unwrap();
+    db.create_rel(frank, bob, "FRIEND", Properties::new());
+    db.create_rel(frank, carol, "FRIEND", Properties::new());
+    db.create_rel(grace, dave, "FRIEND", Properties::new());
+    // 朋友之间互相认识，不应被推荐
+    db.create_rel(bob, carol, "FRIEND", Properties::new());
+    db.create_rel(bob, heidi, "WORKS_WITH", Properties::new());
+
+    (db, ids)
+}
+
+#[test]
+fn test_recommend_ranks_by_mutual_friends() {
+    let (db, ids) = friendship_graph();
+    let (alice, erin, frank, grace) = (ids[0], ids[4], ids[5], ids[6]);
+
+    let recs = db.recommend(alice, "FRIEND", 10);
+    assert_eq!(recs, vec![(erin, 3), (frank, 2), (grace, 1)]);
+
+    // limit 截断
+    assert_eq!(db.recommend(alice, "FRIEND", 2), vec![(erin, 3), (frank, 2)]);
+}
+
+#[test]
+fn test_recommend_excludes_self_and_neighbors() {
+    let (db, ids) = friendship_graph();
+    let bob = ids[1];
+
+    // bob 的朋友：alice、carol、erin、frank；两跳只能到达 dave（经 alice、erin）
+    let recs = db.recommend(bob, "FRIEND", 10);
+    let recommended: Vec<NodeId> = recs.iter().map(|(id, _)| *id).collect();
+    assert!(!recommended.contains(&bob));
+    for friend in [ids[0], ids[2], ids[4], ids[5]] {
+        assert!(!recommended.contains(&friend));
+    }
+    assert_eq!(recs, vec![(ids[3], 2)]);
+
+    // 其他关系类型互不影响
+    assert!(db.recommend(bob, "WORKS_WITH", 10).is_empty());
+}
@@ -0,0 +1,70 @@
+// 关系合并与去重测试
+
+use rs_graphdb::graph::db::GraphDatabase;
+use rs_graphdb::storage::mem_store::MemStore;
+use rs_graphdb::values::{Properties, Value};
+
+fn props(pairs: &[(&str, Value)]) -> Properties {
+    pairs
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.clone()))
+        .collect()
+}
+
+#[test]
+fn test_merge_rel_creates_once() {
+    let mut db = GraphDatabase::<MemStore>::new_in_memory();
+    let a = db.create_node(vec!["User"], Properties::new());
+    let b = db.create_node(vec!["User"], Properties::new());
+
+    let r1 = db.merge_rel(a, b, "FRIEND", props(&[("since", Value::Int(2020))]));
+    let r2 = db.merge_rel(a, b, "FRIEND", Properties::new());
+    assert_eq!(r1, r2);
+    assert_eq!(db.neighbors_out(a).count(), 1);
+
+    // 已存在时合并属性
+    let r3 = db.merge_rel(a, b, "FRIEND", props(&[("weight", Value::Float(0.5))]));
+    assert_eq!(r1, r3);
+    let rel = db.get_rel(r1).unwrap();
+    assert_eq!(rel.props.get("since"), Some(&Value::Int(2020)));
+    assert_eq!(rel.props.get("weight"), Some(&Value::Float(0.5)));
+
+    // 类型或方向不同则创建新关系
+    let r4 = db.merge_rel(a, b, "FOLLOWS", Properties::new());
+    let r5 = db.merge_rel(b, a, "FRIEND", Properties::new());
+    assert_ne!(r4, r1);
+    assert_ne!(r5, r1);
+    assert_eq!(db.neighbors_out(a).count(), 2);
+    assert_eq!(db.neighbors_out(b).count(), 1);
+}
+
+#[test]
+fn test_dedup_collapses_existing_duplicates() {
+    let mut db = GraphDatabase::<MemStore>::new_in_memory();
+    let a = db.create_node(vec!["User"], Properties::new());
+    let b = db.create_node(vec!["User"], Properties::new());
+    let c = db.create_node(vec!["User"], Properties::new());
+
+    let keep = db.create_rel(a, b, "FRIEND", props(&[("since", Value::Int(2020))]));
+    db.create_rel(a, b, "FRIEND", props(&[("since", Value::Int(1999)), ("close", Value::Bool(true))]));
+    db.create_rel(a, b, "FRIEND", Properties::new());
+    let other_type = db.create_rel(a, b, "FOLLOWS", Properties::new());
+    let other_pair = db.create_rel(b, c, "FRIEND", Properties::new());
+
+    let removed = db.dedup_relationships();
+    assert_eq!(removed, 2);
+
+    let out_a: Vec<_> = db.neighbors_out(a).map(|r| r.id).collect();
+    assert_eq!(out_a.len(), 2);
+    assert!(out_a.contains(&keep));
+    assert!(out_a.contains(&other_type));
+    assert!(db.get_rel(other_pair).is_some());
+
+    // 保留的关系保持原值，并补充重复关系中缺失的属性
+    let rel = db.get_rel(keep).unwrap();
+    assert_eq!(rel.props.get("since"), Some(&Value::Int(2020)));
+    assert_eq!(rel.props.get("close"), Some(&Value::Bool(true)));
+
+    // 再次去重没有变化
+    assert_eq!(db.dedup_relationships(), 0);
+}
@@ -0,0 +1,150 @@
+use rs_graphdb::{GraphDatabase, NodeId, RelQuery};
+use rs_graphdb::values::{Properties, Value};
+
+fn make_user(name: &str) -> Properties {
+    let mut props = Properties::new();
+    props.insert("name".to_string(), Value::Text(name.to_string()));
+    props
+}
+
+fn since(year: i64) -> Properties {
+    let mut props = Properties::new();
+    props.insert("since".to_string(), Value::Int(year));
+    props
+}
+
+fn names(nodes: &[rs_graphdb::graph::model::Node]) -> Vec<String> {
+    nodes
+        .iter()
+        .map(|n| match n.get("name") {
+            Some(Value::Text(s)) => s.clone(),
+            _ => String::new(),
+        })
+        .collect()
+}
+
+/// Alice -FRIEND(2021)-> Bob, Carol -FRIEND(2019)-> Bob,
+/// Dave -FRIEND(2022)-> Alice, Alice -FRIEND(2020)-> Dave, Alice -WORKS_WITH(2023)-> Carol
+fn build() -> (GraphDatabase<rs_graphdb::storage::mem_store::MemStore>, Vec<NodeId>) {
+    let mut db = GraphDatabase::new_in_memory();
+    let alice = db.create_node(vec!["User"], make_user("Alice"));
+    let bob = db.create_node(vec!["User"], make_user("Bob"));
+    let carol = db.create_node(vec!["User"], make_user("Carol"));
+    let dave = db.create_node(vec!["User"], make_user("Dave"));
+
+    db.create_rel(alice, bob, "FRIEND", since(2021));
+    db.create_rel(carol, bob, "FRIEND", since(2019));
+    db.create_rel(dave, alice, "FRIEND", since(2022));
+    db.create_rel(alice, dave, "FRIEND", since(2020));
+    db.create_rel(alice, carol, "WORKS_WITH", since(2023));
+
+    (db, vec![alice, bob, carol, dave])
+}
+
+#[test]
+fn test_friend_edges_since_2020_start_nodes() {
+    let (db, _) = build();
+
+    let rels = RelQuery::new(&db)
+        .from_rel_type("FRIEND")
+        .where_prop_int_gt("since", 2019)
+        .collect_rels();
+    assert_eq!(rels.len(), 3);
+    assert!(rels.iter().all(|r| r.typ == "FRIEND"));
+
+    // Alice 是两条关系的起点，投影结果去重
+    let starts = RelQuery::new(&db)
+        .from_rel_type("FRIEND")
+        .where_prop_int_gt("since", 2019)
+        .start_nodes();
+    let mut start_names = names(&starts);
+    start_names.sort();
+    assert_eq!(start_names, vec!["Alice", "Dave"]);
+}
+
+#[test]
+fn test_rel_query_end_nodes_and_eq_filter() {
+    let (db, ids) = build();
+
+    let ends = RelQuery::new(&db)
+        .from_rel_type("FRIEND")
+        .where_prop_int_eq("since", 2019)
+        .end_nodes();
+    assert_eq!(ends.len(), 1);
+    assert_eq!(ends[0].id, ids[1]);
+
+    let mut db = db;
+    let mut props = Properties::new();
+    props.insert("kind".to_string(), Value::Text("close".to_string()));
+    db.create_rel(ids[1], ids[2], "FRIEND", props);
+
+    let close = RelQuery::new(&db)
+        .from_rel_type("FRIEND")
+        .where_prop_eq("kind", "close")
+        .collect_rels();
+    assert_eq!(close.len(), 1);
+    assert_eq!(close[0].start, ids[1]);
+}
+
+#[test]
+fn test_rel_query_order_skip_limit() {
+    let (db, _) = build();
+
+    let years: Vec<i64> = RelQuery::new(&db)
+        .from_rel_type("FRIEND")
+        .order_by("since", false)
+        .skip(1)
+        .limit(2)
+        .collect_rels()
+        .iter()
+        .map(|r| match r.props.get("since") {
+            Some(Value::Int(y)) => *y,
+            _ => 0,
+        })
+        .collect();
+    assert_eq!(years, vec![2021, 2020]);
+
+    assert_eq!(RelQuery::new(&db).from_rel_type("FRIEND").skip(10).count(), 0);
+    assert_eq!(RelQuery::new(&db).from_rel_type("MISSING").count(), 0);
+}
+
+#[test]
+fn test_rel_query_undirected_rel_counted_once() {
+    let mut db = GraphDatabase::new_in_memory();
+    let a = db.create_node(vec!["User"], make_user("A"));
+    let b = db.create_node(vec!["User"], make_user("B"));
+    db.create_undirected_rel(b, a, "PEER", Properties::new()).unwrap();
+
+    let rels = RelQuery::new(&db).from_rel_type("PEER").collect_rels();
+    assert_eq!(rels.len(), 1);
+    assert_eq!(rels[0].start, b);
+    assert_eq!(rels[0].end, a);
+}
+
+#[test]
+fn test_get_rels_preserves_order_with_missing_ids() {
+    let mut db = GraphDatabase::new_in_memory();
+    let alice = db.create_node(vec!["User"], make_user("Alice"));
+    let bob = db.create_node(vec!["User"], make_user("Bob"));
+    let mut props = since(2021);
+    props.insert("weight".to_string(), Value::Float(0.5));
+    let friend = db.create_rel(alice, bob, "FRIEND", props);
+    let follows = db.create_rel(bob, alice, "FOLLOWS", since(2019));
+    let deleted = db.create_rel(alice, bob, "BLOCKS", Properties::new());
+    db.delete_rel(deleted);
+
+    let ids = [follows, deleted, friend, 9999];
+    let rels = db.get_rels(&ids);
+    assert_eq!(rels.len(), 4);
+    assert_eq!(rels[0].as_ref().map(|r| r.typ.as_str()), Some("FOLLOWS"));
+    assert!(rels[1].is_none());
+    assert_eq!(rels[2].as_ref().map(|r| (r.start, r.end)), Some((alice, bob)));
+    assert!(rels[3].is_none());
+
+    // 只投影请求的属性，不存在的属性键被忽略
+    let props = db.get_rels_props(&ids, &["since", "color"]);
+    assert_eq!(props[0].as_ref().unwrap(), &since(2019));
+    assert!(props[1].is_none());
+    assert_eq!(props[2].as_ref().unwrap(), &since(2021));
+    assert!(props[3].is_none());
+}
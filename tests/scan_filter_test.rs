@@ -0,0 +1,118 @@
+// 存储层过滤扫描（scan_nodes）测试
+
+use rs_graphdb::graph::db::GraphDatabase;
+use rs_graphdb::storage::mem_store::MemStore;
+use rs_graphdb::storage::sled_store::SledStore;
+use rs_graphdb::storage::{NodeFilter, NodeId, PropPredicate, StorageEngine, StoredNode};
+use rs_graphdb::values::{Properties, Value};
+use tempfile::TempDir;
+
+fn populate<E: StorageEngine>(db: &mut GraphDatabase<E>) {
+    for i in 0..60i64 {
+        let label = if i % 3 == 0 { "Admin" } else { "User" };
+        let mut props = Properties::new();
+        props.insert("name".to_string(), Value::Text(format!("user{}", i % 10)));
+        props.insert("age".to_string(), Value::Int(18 + i % 40));
+        if i % 2 == 0 {
+            props.insert("score".to_string(), Value::Float(i as f64 / 2.0));
+        }
+        db.create_node(vec![label], props);
+    }
+}
+
+fn sorted_ids(nodes: impl Iterator<Item = StoredNode>) -> Vec<NodeId> {
+    let mut ids: Vec<NodeId> = nodes.map(|n| n.id).collect();
+    ids.sort();
+    ids
+}
+
+/// 用应用层手动过滤的结果校验 scan_nodes
+fn assert_scan_matches_manual<E: StorageEngine>(db: &GraphDatabase<E>, filter: NodeFilter) {
+    let expected = sorted_ids(db.all_stored_nodes().filter(|n| {
+        let label_ok = filter
+            .label
+            .as_ref()
+            .map(|l| n.labels.contains(l))
+            .unwrap_or(true);
+        label_ok && filter.props.iter().all(|(k, p)| p.matches(n.props.get(k)))
+    }));
+    let actual = sorted_ids(db.scan_nodes(filter.clone()));
+    assert_eq!(actual, expected, "filter {:?}", filter);
+}
+
+fn filters() -> Vec<NodeFilter> {
+    vec![
+        NodeFilter::new(),
+        NodeFilter::new().with_label("Admin"),
+        NodeFilter::new().with_label("Missing"),
+        NodeFilter::new()
+            .with_label("User")
+            .with_prop("name", PropPredicate::Eq(Value::Text("user3".to_string()))),
+        NodeFilter::new()
+            .with_prop("age", PropPredicate::Gte(Value::Int(40)))
+            .with_prop("age", PropPredicate::Lt(Value::Int(50))),
+        NodeFilter::new().with_prop("score", PropPredicate::Exists),
+        NodeFilter::new().with_prop("score", PropPredicate::Gt(Value::Int(10))),
+        NodeFilter::new()
+            .with_label("Admin")
+            .with_prop("name", PropPredicate::Ne(Value::Text("user0".to_string()))),
+    ]
+}
+
+#[test]
+fn test_scan_nodes_mem_store() {
+    let mut db = GraphDatabase::<MemStore>::new_in_memory();
+    populate(&mut db);
+
+    for filter in filters() {
+        assert_scan_matches_manual(&db, filter);
+    }
+
+    // 标签过滤
+    let admins: Vec<_> = db.scan_nodes(NodeFilter::new().with_label("Admin")).collect();
+    assert_eq!(admins.len(), 20);
+    assert!(admins.iter().all(|n| n.labels.contains(&"Admin".to_string())));
+}
+
+#[test]
+fn test_scan_nodes_sled_label_only_fast_path() {
+    let temp_dir = TempDir::new().unwrap();
+    let store = SledStore::new(temp_dir.path()).unwrap();
+    let mut db = GraphDatabase::from_engine(store);
+    populate(&mut db);
+
+    // 只有标签条件：只解码记录头部
+    let admins = sorted_ids(db.scan_nodes(NodeFilter::new().with_label("Admin")));
+    assert_eq!(admins, (0..60).filter(|i| i % 3 == 0).collect::<Vec<NodeId>>());
+    assert_eq!(db.scan_nodes(NodeFilter::new().with_label("Missing")).count(), 0);
+
+    for filter in filters() {
+        assert_scan_matches_manual(&db, filter);
+    }
+}
+
+#[test]
+fn test_scan_nodes_sled_indexed_path() {
+    let temp_dir = TempDir::new().unwrap();
+    let store = SledStore::with_config(
+        temp_dir.path(),
+        vec![("User".to_string(), "name".to_string())],
+    )
+    .unwrap();
+    let mut db = GraphDatabase::from_engine(store);
+    populate(&mut db);
+
+    let filter = NodeFilter::new()
+        .with_label("User")
+        .with_prop("name", PropPredicate::Eq(Value::Text("user4".to_string())))
+        .with_prop("age", PropPredicate::Gt(Value::Int(30)));
+    assert_scan_matches_manual(&db, filter.clone());
+
+    let hits: Vec<_> = db.scan_nodes(filter).collect();
+    assert!(!hits.is_empty());
+    assert!(hits.iter().all(|n| n.props.get("name") == Some(&Value::Text("user4".to_string()))));
+
+    for filter in filters() {
+        assert_scan_matches_manual(&db, filter);
+    }
+}
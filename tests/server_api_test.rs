@@ -8,6 +8,8 @@ use rs_graphdb::graph::db::GraphDatabase;
 use rs_graphdb::server::{create_router, AppState};
 use rs_graphdb::service::GraphService;
 use rs_graphdb::storage::mem_store::MemStore;
+use rs_graphdb::storage::NodeId;
+use rs_graphdb::transactions::{IsolationLevel, TransactionConfig};
 use rs_graphdb::values::{Properties, Value};
 
 /// 辅助函数：创建测试用应用状态
@@ -201,6 +203,42 @@ async fn test_get_node_by_id() {
     assert_eq!(node["properties"]["name"], "Alice");
 }
 
+#[tokio::test]
+async fn test_batch_get_nodes() {
+    let state = create_test_state();
+    let app = create_router(state);
+
+    let nodes: Vec<serde_json::Value> = get_json(&app, "/nodes").await;
+    let ids: Vec<u64> = nodes.iter().map(|n| n["id"].as_u64().unwrap()).collect();
+
+    // 默认省略不存在的节点
+    let resp: serde_json::Value = post_json(
+        &app,
+        "/nodes/batch-get",
+        serde_json::json!({ "ids": [ids[0], 999, ids[1]] }),
+    )
+    .await;
+    let found = resp["nodes"].as_array().unwrap();
+    assert_eq!(found.len(), 2);
+    assert_eq!(found[0]["id"], ids[0]);
+    assert_eq!(found[1]["id"], ids[1]);
+    assert_eq!(found[0]["labels"][0], "User");
+    assert_eq!(resp["missing"], serde_json::json!([999]));
+
+    // include_missing: 结果与请求的 ids 按位置对应，缺失处为 null
+    let resp: serde_json::Value = post_json(
+        &app,
+        "/nodes/batch-get",
+        serde_json::json!({ "ids": [999, ids[1]], "include_missing": true }),
+    )
+    .await;
+    let slots = resp["nodes"].as_array().unwrap();
+    assert_eq!(slots.len(), 2);
+    assert!(slots[0].is_null());
+    assert_eq!(slots[1]["id"], ids[1]);
+    assert_eq!(resp["missing"], serde_json::json!([999]));
+}
+
 #[tokio::test]
 async fn test_get_node_neighbors() {
     let state = create_test_state();
@@ -220,6 +258,166 @@ async fn test_get_node_neighbors() {
     assert_eq!(neighbors["incoming"].as_array().unwrap().len(), 0);
 }
 
+#[tokio::test]
+async fn test_get_node_recommendations() {
+    let db = GraphDatabase::<MemStore>::new_in_memory();
+    let db = Arc::new(Mutex::new(db));
+    let (alice, carol, dave) = {
+        let mut guard = db.lock().unwrap();
+        let mut person = |name: &str| {
+            let mut props = Properties::new();
+            props.insert("name".to_string(), Value::Text(name.to_string()));
+            guard.create_node(vec!["User"], props)
+        };
+        let (alice, bob, carol, dave, erin) =
+            (person("Alice"), person("Bob"), person("Carol"), person("Dave"), person("Erin"));
+        for (a, b) in [(alice, bob), (alice, erin), (bob, carol), (erin, carol), (bob, dave)] {
+            guard.create_rel(a, b, "FRIEND", Properties::new());
+        }
+        (alice, carol, dave)
+    };
+    let app = create_router(AppState::new(Arc::new(GraphService::new(db))));
+
+    let recs: Vec<serde_json::Value> = get_json(
+        &app,
+        &format!("/nodes/{}/recommendations?rel_type=FRIEND&limit=10", alice),
+    )
+    .await;
+
+    assert_eq!(recs.len(), 2);
+    assert_eq!(recs[0]["node"]["id"], serde_json::json!(carol));
+    assert_eq!(recs[0]["mutual_count"], 2);
+    assert_eq!(recs[1]["node"]["id"], serde_json::json!(dave));
+    assert_eq!(recs[1]["mutual_count"], 1);
+}
+
+#[tokio::test]
+async fn test_central_nodes_by_degree_on_star() {
+    let db = GraphDatabase::<MemStore>::new_in_memory();
+    let db = Arc::new(Mutex::new(db));
+    let hub = {
+        let mut guard = db.lock().unwrap();
+        let hub = guard.create_node(vec!["User"], Properties::new());
+        for _ in 0..5 {
+            let leaf = guard.create_node(vec!["User"], Properties::new());
+            guard.create_rel(hub, leaf, "FOLLOWS", Properties::new());
+        }
+        hub
+    };
+    let app = create_router(AppState::new(Arc::new(GraphService::new(db))));
+
+    let top: Vec<serde_json::Value> = get_json(&app, "/central?metric=degree&limit=3").await;
+    assert_eq!(top.len(), 3);
+    assert_eq!(top[0]["node"]["id"], serde_json::json!(hub));
+    assert_eq!(top[0]["score"], 1.0);
+
+    // 第二次请求命中缓存，结果一致
+    let cached: Vec<serde_json::Value> = get_json(&app, "/central?metric=degree&limit=3").await;
+    assert_eq!(cached, top);
+
+    let top: Vec<serde_json::Value> = get_json(&app, "/central?metric=closeness&limit=1").await;
+    assert_eq!(top[0]["node"]["id"], serde_json::json!(hub));
+
+    // 超过时间上限返回 503，且不缓存部分结果
+    let response = app
+        .clone()
+        .oneshot(
+            axum::http::Request::builder()
+                .uri("/central?metric=betweenness&timeout_ms=0")
+                .body(axum::body::Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 503);
+    let top: Vec<serde_json::Value> = get_json(&app, "/central?metric=betweenness&limit=1").await;
+    assert_eq!(top[0]["node"]["id"], serde_json::json!(hub));
+
+    let response = app
+        .oneshot(
+            axum::http::Request::builder()
+                .uri("/central?metric=popularity")
+                .body(axum::body::Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 400);
+}
+
+#[tokio::test]
+async fn test_bulk_update_nodes() {
+    let state = create_paging_state();
+    let app = create_router(state.clone());
+
+    let response: serde_json::Value = post_json(
+        &app,
+        "/nodes/bulk-update",
+        serde_json::json!({
+            "label": "Item",
+            "conditions": [{ "key": "rank", "op": "gte", "value": 20 }],
+            "properties": { "active": true }
+        }),
+    )
+    .await;
+    assert_eq!(response["updated"], 5);
+
+    {
+        let db = state.service.db().lock().unwrap();
+        let active = rs_graphdb::Query::new(&db)
+            .from_label("Item")
+            .collect_nodes()
+            .into_iter()
+            .filter(|n| n.get("active") == Some(&Value::Bool(true)))
+            .count();
+        assert_eq!(active, 5);
+    }
+
+    let response = app
+        .oneshot(
+            axum::http::Request::builder()
+                .method("POST")
+                .uri("/nodes/bulk-update")
+                .header("content-type", "application/json")
+                .body(axum::body::Body::from(
+                    serde_json::json!({
+                        "label": "Item",
+                        "conditions": [{ "key": "rank", "op": "like", "value": 1 }],
+                        "properties": { "active": false }
+                    })
+                    .to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 400);
+}
+
+#[tokio::test]
+async fn test_tx_stats_endpoint() {
+    let state = create_test_state();
+    {
+        let db = state.service.db().clone();
+        let mut db = db.lock().unwrap();
+        db.begin_tx_with_config(TransactionConfig::new().with_isolation_level(IsolationLevel::Serializable));
+    }
+    let app = create_router(state);
+
+    let stats: serde_json::Value = get_json(&app, "/tx/stats").await;
+    assert_eq!(stats["active_count"], 1);
+    let txs = stats["transactions"].as_array().unwrap();
+    assert_eq!(txs.len(), 1);
+    assert_eq!(txs[0]["isolation_level"], "Serializable");
+    assert_eq!(txs[0]["op_count"], 0);
+    assert_eq!(txs[0]["held_locks"], 0);
+    assert!(txs[0]["age_ms"].is_u64());
+
+    // 阈值过滤：刚开始的事务不算长事务
+    let stats: serde_json::Value = get_json(&app, "/tx/stats?min_age_ms=3600000").await;
+    assert!(stats["transactions"].as_array().unwrap().is_empty());
+}
+
 // ========== 关系操作测试 ==========
 
 #[tokio::test]
@@ -263,6 +461,42 @@ async fn test_get_all_rels() {
     assert_eq!(rels[0]["typ"], "FRIEND");
 }
 
+#[tokio::test]
+async fn test_get_rels_paginated() {
+    let state = create_test_state();
+    {
+        let db = state.service.db().clone();
+        let mut guard = db.lock().unwrap();
+        let hub = guard.create_node(vec!["Hub"], Properties::new());
+        for _ in 0..6 {
+            let leaf = guard.create_node(vec!["Leaf"], Properties::new());
+            guard.create_rel(hub, leaf, "LINK", Properties::new());
+        }
+    }
+    let app = create_router(state);
+
+    // 共 7 条关系，每页 3 条，跟随游标直到结束
+    let mut seen = Vec::new();
+    let mut path = "/rels?limit=3".to_string();
+    let mut pages = 0;
+    loop {
+        let page: serde_json::Value = get_json(&app, &path).await;
+        let rels = page["rels"].as_array().unwrap();
+        assert!(rels.len() <= 3);
+        seen.extend(rels.iter().map(|r| r["id"].as_u64().unwrap()));
+        pages += 1;
+        match page["next_cursor"].as_u64() {
+            Some(cursor) => path = format!("/rels?limit=3&cursor={}", cursor),
+            None => break,
+        }
+    }
+
+    assert_eq!(pages, 3);
+    assert_eq!(seen.len(), 7);
+    // 按 ID 升序且不重复
+    assert!(seen.windows(2).all(|w| w[0] < w[1]));
+}
+
 // ========== 查询操作测试 ==========
 
 #[tokio::test]
@@ -270,7 +504,7 @@ async fn test_query_by_label() {
     let state = create_test_state();
     let app = create_router(state);
 
-    let results: Vec<serde_json::Value> = post_json(
+    let response: serde_json::Value = post_json(
         &app,
         "/query",
         serde_json::json!({
@@ -279,7 +513,8 @@ async fn test_query_by_label() {
     )
     .await;
 
-    assert_eq!(results.len(), 2);
+    assert_eq!(response["total"], 2);
+    assert_eq!(response["nodes"].as_array().unwrap().len(), 2);
 }
 
 #[tokio::test]
@@ -287,7 +522,7 @@ async fn test_query_by_label_and_property() {
     let state = create_test_state();
     let app = create_router(state);
 
-    let results: Vec<serde_json::Value> = post_json(
+    let response: serde_json::Value = post_json(
         &app,
         "/query",
         serde_json::json!({
@@ -298,10 +533,106 @@ async fn test_query_by_label_and_property() {
     )
     .await;
 
+    let results = response["nodes"].as_array().unwrap();
     assert_eq!(results.len(), 1);
     assert_eq!(results[0]["properties"]["name"], "Alice");
 }
 
+fn create_paging_state() -> AppState {
+    let db = GraphDatabase::<MemStore>::new_in_memory();
+    let db = Arc::new(Mutex::new(db));
+    {
+        let mut guard = db.lock().unwrap();
+        for i in 0..25 {
+            let mut props = Properties::new();
+            props.insert("rank".to_string(), Value::Int(i));
+            guard.create_node(vec!["Item"], props);
+        }
+        guard.create_node(vec!["Other"], Properties::new());
+    }
+    AppState::new(Arc::new(GraphService::new(db)))
+}
+
+fn ranks(response: &serde_json::Value) -> Vec<i64> {
+    response["nodes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|n| n["properties"]["rank"].as_i64().unwrap())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_query_pagination() {
+    let app = create_router(create_paging_state());
+
+    // 每页 10 条，逐页翻完整个标签集合
+    let mut seen = Vec::new();
+    for page in 0..3 {
+        let response: serde_json::Value = post_json(
+            &app,
+            "/query",
+            serde_json::json!({
+                "label": "Item",
+                "order_by": "rank",
+                "skip": page * 10,
+                "limit": 10
+            }),
+        )
+        .await;
+
+        assert_eq!(response["total"], 25);
+        seen.extend(ranks(&response));
+    }
+    assert_eq!(seen, (0..25).collect::<Vec<_>>());
+
+    // 超出范围的页为空，total 不变
+    let response: serde_json::Value = post_json(
+        &app,
+        "/query",
+        serde_json::json!({ "label": "Item", "skip": 100, "limit": 10 }),
+    )
+    .await;
+    assert_eq!(response["total"], 25);
+    assert!(response["nodes"].as_array().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_query_order_by_desc() {
+    let app = create_router(create_paging_state());
+
+    let response: serde_json::Value = post_json(
+        &app,
+        "/query",
+        serde_json::json!({
+            "label": "Item",
+            "order_by": "rank",
+            "order_dir": "desc",
+            "limit": 3
+        }),
+    )
+    .await;
+
+    assert_eq!(response["total"], 25);
+    assert_eq!(ranks(&response), vec![24, 23, 22]);
+
+    // 非法的排序方向
+    let response = app
+        .oneshot(
+            axum::http::Request::builder()
+                .method("POST")
+                .uri("/query")
+                .header("content-type", "application/json")
+                .body(axum::body::Body::from(
+                    serde_json::json!({ "label": "Item", "order_dir": "sideways" }).to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 400);
+}
+
 #[tokio::test]
 async fn test_search_nodes() {
     let state = create_test_state();
@@ -423,6 +754,15 @@ async fn test_get_sysinfo() {
     assert_eq!(databases[0]["name"], "default");
     assert_eq!(databases[0]["node_count"], 2);
     assert_eq!(databases[0]["rel_count"], 1);
+
+    // 内存报告的各部分之和即 store_size
+    let memory = &sysinfo["memory"];
+    let parts: u64 = ["nodes", "relationships", "adjacency", "indexes"]
+        .iter()
+        .map(|k| memory[k].as_u64().unwrap())
+        .sum();
+    assert!(memory["nodes"].as_u64().unwrap() > 0);
+    assert_eq!(sysinfo["store_size"].as_u64().unwrap(), parts);
 }
 
 #[tokio::test]
@@ -509,6 +849,100 @@ async fn test_delete_rel() {
     assert!(result["deleted"].is_boolean());
 }
 
+#[tokio::test]
+async fn test_delete_node_restrict_mode() {
+    let state = create_test_state();
+    let app = create_router(state);
+
+    let nodes: Vec<serde_json::Value> = get_json(&app, "/nodes").await;
+    let node_id = nodes[0]["id"].as_u64().unwrap();
+
+    // 节点有关联关系，restrict 模式返回 409
+    let response = app
+        .clone()
+        .oneshot(
+            axum::http::Request::builder()
+                .method("DELETE")
+                .uri(format!("/nodes/{}?mode=restrict", node_id))
+                .body(axum::body::Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), 409);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let result: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(result["relationships"].as_array().unwrap().len(), 1);
+
+    // cascade 模式删除成功
+    let response = app
+        .clone()
+        .oneshot(
+            axum::http::Request::builder()
+                .method("DELETE")
+                .uri(format!("/nodes/{}?mode=cascade", node_id))
+                .body(axum::body::Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), 200);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let result: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(result["deleted"], true);
+}
+
+#[tokio::test]
+async fn test_index_stats_and_drop() {
+    let state = create_test_state();
+    {
+        let mut db = state.service.db().lock().unwrap();
+        db.create_composite_index("user_city", "User", &["city"]);
+        for (name, city) in [("Carol", "NYC"), ("Dave", "NYC"), ("Erin", "LA"), ("Frank", "LA")] {
+            let mut props = Properties::new();
+            props.insert("name".to_string(), Value::Text(name.to_string()));
+            props.insert("city".to_string(), Value::Text(city.to_string()));
+            db.create_node(vec!["User"], props);
+        }
+    }
+    let app = create_router(state);
+
+    let indexes: Vec<serde_json::Value> = get_json(&app, "/indexes").await;
+    let find = |indexes: &[serde_json::Value], id: &str| indexes.iter().find(|i| i["id"] == id).cloned();
+
+    // 4 个节点、2 个不同城市
+    let city = find(&indexes, "composite:user_city").expect("composite index listed");
+    assert_eq!(city["kind"], "composite");
+    assert_eq!(city["properties"], serde_json::json!(["city"]));
+    assert_eq!(city["entries"], 4);
+    assert_eq!(city["unique_keys"], 2);
+    assert_eq!(city["selectivity"], 0.5);
+    assert_eq!(city["avg_nodes_per_key"], 2.0);
+
+    // 默认 schema 的 User.name：6 个节点各不相同
+    let name = find(&indexes, "property:User.name").expect("property index listed");
+    assert_eq!(name["entries"], 6);
+    assert_eq!(name["selectivity"], 1.0);
+
+    let delete = |uri: &'static str| {
+        app.clone().oneshot(
+            axum::http::Request::builder()
+                .method("DELETE")
+                .uri(uri)
+                .body(axum::body::Body::empty())
+                .unwrap(),
+        )
+    };
+    assert_eq!(delete("/indexes/composite:user_city").await.unwrap().status(), 200);
+    let indexes: Vec<serde_json::Value> = get_json(&app, "/indexes").await;
+    assert!(find(&indexes, "composite:user_city").is_none());
+    assert!(find(&indexes, "property:User.name").is_some());
+
+    assert_eq!(delete("/indexes/composite:user_city").await.unwrap().status(), 404);
+}
+
 // ========== 错误处理测试 ==========
 
 #[tokio::test]
@@ -725,6 +1159,27 @@ async fn test_cypher_match_with_label_prop() {
     assert_eq!(response["data"]["nodes"][0]["properties"]["name"], "Alice");
 }
 
+#[tokio::test]
+async fn test_cypher_response_includes_execution_stats() {
+    let state = create_test_state();
+    let app = create_router(state);
+
+    let response: serde_json::Value = post_json(
+        &app,
+        "/cypher",
+        serde_json::json!({
+            "query": "MATCH (n:User) RETURN n"
+        }),
+    )
+    .await;
+
+    let stats = &response["stats"];
+    assert_eq!(stats["row_count"], 2);
+    assert!(stats["execution_time_ms"].as_f64().unwrap() >= 0.0);
+    assert!(stats["rows_examined"].as_u64().unwrap() >= 2);
+    assert_eq!(stats["index_used"], false);
+}
+
 #[tokio::test]
 async fn test_cypher_create() {
     let state = create_test_state();
@@ -745,6 +1200,30 @@ async fn test_cypher_create() {
     assert_eq!(response["stats"]["nodes_created"], 1);
 }
 
+#[tokio::test]
+async fn test_cypher_create_return() {
+    let state = create_test_state();
+    let app = create_router(state);
+
+    let response: serde_json::Value = post_json(
+        &app,
+        "/cypher",
+        serde_json::json!({
+            "query": "CREATE (p:Person {name: \"Frank\", age: 52}) RETURN p"
+        }),
+    )
+    .await;
+
+    assert_eq!(response["result_type"], "nodes");
+    let nodes = response["data"]["nodes"].as_array().unwrap();
+    assert_eq!(nodes.len(), 1);
+    // 测试数据已有 2 个节点，新节点 ID 为 2
+    assert_eq!(nodes[0]["id"], 2);
+    assert_eq!(nodes[0]["labels"], serde_json::json!(["Person"]));
+    assert_eq!(nodes[0]["properties"]["name"], "Frank");
+    assert_eq!(nodes[0]["properties"]["age"], 52);
+}
+
 #[tokio::test]
 async fn test_cypher_create_with_relationship() {
     let state = create_test_state();
@@ -874,3 +1353,397 @@ async fn test_cypher_traversal() {
     let nodes = response["data"]["nodes"].as_array().unwrap();
     assert!(nodes.len() >= 1);
 }
+
+#[tokio::test]
+async fn test_stored_query_with_parameter() {
+    let state = create_test_state();
+    let app = create_router(state);
+
+    let registered: serde_json::Value = post_json(
+        &app,
+        "/stored-queries/users_older_than",
+        serde_json::json!({ "query": "MATCH (n:User) WHERE n.age > $min_age RETURN n" }),
+    )
+    .await;
+    assert_eq!(registered["name"], "users_older_than");
+
+    let run = |min_age: i64| {
+        post_json::<serde_json::Value>(
+            &app,
+            "/stored-queries/users_older_than/run",
+            serde_json::json!({ "params": { "min_age": min_age } }),
+        )
+    };
+    let response = run(26).await;
+    let nodes = response["data"]["nodes"].as_array().unwrap();
+    assert_eq!(nodes.len(), 1);
+    assert_eq!(nodes[0]["properties"]["name"], "Alice");
+    assert_eq!(run(18).await["data"]["nodes"].as_array().unwrap().len(), 2);
+
+    let status = |uri: &'static str, body: serde_json::Value| {
+        app.clone().oneshot(
+            axum::http::Request::builder()
+                .method("POST")
+                .uri(uri)
+                .header("content-type", "application/json")
+                .body(axum::body::Body::from(body.to_string()))
+                .unwrap(),
+        )
+    };
+    // 缺少参数、未注册的查询、语法错误的查询
+    let missing = status("/stored-queries/users_older_than/run", serde_json::json!({}));
+    assert_eq!(missing.await.unwrap().status(), 400);
+    let unknown = status("/stored-queries/nope/run", serde_json::json!({ "params": {} }));
+    assert_eq!(unknown.await.unwrap().status(), 404);
+    let invalid = status("/stored-queries/bad", serde_json::json!({ "query": "MATCH" }));
+    assert_eq!(invalid.await.unwrap().status(), 400);
+}
+
+// ========== 节点属性 Schema 校验测试 ==========
+
+/// 辅助函数：发送 POST 请求，返回状态码与响应体
+async fn post_raw(
+    app: &axum::Router,
+    path: &str,
+    body: serde_json::Value,
+) -> (axum::http::StatusCode, serde_json::Value) {
+    let response = app
+        .clone()
+        .oneshot(
+            axum::http::Request::builder()
+                .method("POST")
+                .uri(path)
+                .header("content-type", "application/json")
+                .body(axum::body::Body::from(body.to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    let status = response.status();
+    let bytes = response.into_body().collect().await.unwrap().to_bytes();
+    (status, serde_json::from_slice(&bytes).unwrap())
+}
+
+fn person_schema() -> serde_json::Value {
+    serde_json::json!({
+        "type": "object",
+        "properties": {
+            "name": { "type": "string" },
+            "age": { "type": "integer", "minimum": 0 }
+        },
+        "required": ["name"]
+    })
+}
+
+#[tokio::test]
+async fn test_create_node_accepts_conforming_properties() {
+    let state = create_test_state();
+    let app = create_router(state.clone());
+
+    let (status, _) = post_raw(&app, "/schemas/Person", person_schema()).await;
+    assert_eq!(status, 200);
+
+    let response: serde_json::Value = post_json(
+        &app,
+        "/nodes",
+        serde_json::json!({
+            "labels": ["Person", "Employee"],
+            "properties": { "name": "Carol", "age": 41 }
+        }),
+    )
+    .await;
+    let id: NodeId = serde_json::from_value(response["id"].clone()).unwrap();
+    assert!(state.service.db().lock().unwrap().get_node(id).is_some());
+
+    // 未注册 Schema 的标签不做校验
+    let (status, _) = post_raw(
+        &app,
+        "/nodes",
+        serde_json::json!({ "labels": ["Thing"], "properties": { "age": "old" } }),
+    )
+    .await;
+    assert_eq!(status, 200);
+}
+
+#[tokio::test]
+async fn test_create_node_rejects_wrong_typed_field() {
+    let state = create_test_state();
+    let app = create_router(state.clone());
+
+    let (status, _) = post_raw(&app, "/schemas/Person", person_schema()).await;
+    assert_eq!(status, 200);
+    let before = state.service.db().lock().unwrap().node_count();
+
+    let (status, body) = post_raw(
+        &app,
+        "/nodes",
+        serde_json::json!({
+            "labels": ["Person"],
+            "properties": { "name": "Dave", "age": "forty" }
+        }),
+    )
+    .await;
+    assert_eq!(status, 422);
+    let errors = body["errors"].as_array().unwrap();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0]["path"], "/age");
+    assert_eq!(state.service.db().lock().unwrap().node_count(), before);
+
+    // 无法编译的 Schema 返回 400
+    let (status, _) = post_raw(
+        &app,
+        "/schemas/Broken",
+        serde_json::json!({ "type": "no-such-type" }),
+    )
+    .await;
+    assert_eq!(status, 400);
+}
+
+#[tokio::test]
+async fn test_schema_reports_observed_properties() {
+    let state = create_test_state();
+    let app = create_router(state.clone());
+
+    let schema: serde_json::Value = get_json(&app, "/schema").await;
+    let user = &schema["labels"]["User"];
+    assert_eq!(user["count"], 2);
+    assert_eq!(user["properties"]["name"]["types"], serde_json::json!(["text"]));
+    assert_eq!(user["properties"]["age"]["types"], serde_json::json!(["int"]));
+    assert_eq!(user["properties"]["age"]["fill_rate"], 1.0);
+    assert_eq!(
+        schema["rel_types"]["FRIEND"]["properties"]["since"]["types"],
+        serde_json::json!(["text"])
+    );
+
+    // 新增节点后缓存失效，填充率随之更新
+    let _: serde_json::Value = post_json(
+        &app,
+        "/nodes",
+        serde_json::json!({ "labels": ["User"], "properties": { "name": "Carol" } }),
+    )
+    .await;
+    let schema: serde_json::Value = get_json(&app, "/schema").await;
+    let age = &schema["labels"]["User"]["properties"]["age"];
+    assert_eq!(age["count"], 2);
+    assert!((age["fill_rate"].as_f64().unwrap() - 2.0 / 3.0).abs() < 1e-9);
+
+    let sampled: serde_json::Value = get_json(&app, "/schema?sample=1").await;
+    assert_eq!(sampled["labels"]["User"]["count"], 1);
+}
+
+#[tokio::test]
+async fn test_cypher_response_includes_column_types() {
+    let state = create_test_state();
+    let app = create_router(state);
+
+    let response: serde_json::Value = post_json(
+        &app,
+        "/cypher",
+        serde_json::json!({ "query": "MATCH (n:User) RETURN n, n.name, count(*)" }),
+    )
+    .await;
+
+    assert_eq!(response["data"]["columns"], serde_json::json!(["n", "n.name", "count(*)"]));
+    assert_eq!(response["data"]["column_types"], serde_json::json!(["node", "text", "int"]));
+}
+
+#[tokio::test]
+async fn test_export_cypher_match_to_dot() {
+    let state = create_test_state();
+    let app = create_router(state);
+
+    let _: serde_json::Value = post_json(
+        &app,
+        "/nodes",
+        serde_json::json!({ "labels": ["User"], "properties": { "name": "Carol" } }),
+    )
+    .await;
+
+    let export = |body: serde_json::Value| {
+        let app = app.clone();
+        async move {
+            let response = app
+                .oneshot(
+                    axum::http::Request::builder()
+                        .method("POST")
+                        .uri("/export/cypher")
+                        .header("content-type", "application/json")
+                        .body(axum::body::Body::from(body.to_string()))
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+            let status = response.status();
+            let bytes = response.into_body().collect().await.unwrap().to_bytes();
+            (status, String::from_utf8(bytes.to_vec()).unwrap())
+        }
+    };
+
+    let (status, dot) = export(serde_json::json!({
+        "query": "MATCH (a:User {name: \"Alice\"})-[:FRIEND]->(b) RETURN b",
+        "format": "dot"
+    }))
+    .await;
+    assert_eq!(status, 200);
+    assert!(dot.starts_with("digraph"));
+    assert!(dot.contains("Alice"));
+    assert!(dot.contains("Bob"));
+    assert!(dot.contains("FRIEND"));
+    assert!(!dot.contains("Carol"));
+
+    let (status, json) = export(serde_json::json!({
+        "query": "MATCH (n:User {name: \"Alice\"}) RETURN n",
+        "exclude_properties": ["age"]
+    }))
+    .await;
+    assert_eq!(status, 200);
+    assert!(json.contains("Alice"));
+    assert!(!json.contains("\"age\""));
+
+    let (status, _) = export(serde_json::json!({
+        "query": "MATCH (n:User) RETURN n",
+        "format": "svg"
+    }))
+    .await;
+    assert_eq!(status, 400);
+}
+
+#[tokio::test]
+async fn test_cypher_batch_runs_statements_in_order() {
+    let state = create_test_state();
+    let app = create_router(state);
+
+    let response: serde_json::Value = post_json(
+        &app,
+        "/cypher/batch",
+        serde_json::json!({ "statements": [
+            { "query": "CREATE (n:User {name: $name, age: 41})", "params": { "name": "Carol" } },
+            { "query": "MATCH (n:User) WHERE n.age > 35 RETURN n" }
+        ]}),
+    )
+    .await;
+
+    let results = response["results"].as_array().unwrap();
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0]["result_type"], "created");
+    let nodes = results[1]["data"]["nodes"].as_array().unwrap();
+    assert_eq!(nodes.len(), 1);
+    assert_eq!(nodes[0]["properties"]["name"], "Carol");
+}
+
+#[tokio::test]
+async fn test_cypher_batch_failure_rolls_back_earlier_create() {
+    let state = create_test_state();
+    let db = state.service.db().clone();
+    let app = create_router(state);
+    let nodes_before = db.lock().unwrap().node_count();
+
+    let response = app
+        .clone()
+        .oneshot(
+            axum::http::Request::builder()
+                .method("POST")
+                .uri("/cypher/batch")
+                .header("content-type", "application/json")
+                .body(axum::body::Body::from(
+                    serde_json::json!({ "statements": [
+                        { "query": "CREATE (n:User {name: \"Carol\"})" },
+                        { "query": "MATCH (n:User) SET n.age = 99" },
+                        // 非法正则在执行阶段才报错
+                        { "query": "MATCH (n:User) WHERE n.name =~ '[' RETURN n" }
+                    ]})
+                    .to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 500);
+    let bytes = response.into_body().collect().await.unwrap().to_bytes();
+    let body: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+    assert_eq!(body["failed_statement"], 2);
+
+    let db = db.lock().unwrap();
+    assert_eq!(db.node_count(), nodes_before);
+    let ages: Vec<_> = db
+        .all_stored_nodes()
+        .filter_map(|n| n.props.get("age").cloned())
+        .collect();
+    assert!(ages.iter().all(|age| *age != Value::Int(99)));
+    assert_eq!(db.rel_count(), 1);
+}
+
+#[tokio::test]
+async fn test_create_node_uniqueness_violation_returns_conflict() {
+    use rs_graphdb::constraints::Constraint;
+    use rs_graphdb::service::ServiceError;
+
+    let state = create_test_state();
+    let app = create_router(state.clone());
+    state
+        .service
+        .db()
+        .lock()
+        .unwrap()
+        .constraints
+        .add_constraint(Constraint::uniqueness("User", "name"))
+        .unwrap();
+    let before = state.service.db().lock().unwrap().node_count();
+
+    let (status, body) = post_raw(
+        &app,
+        "/nodes",
+        serde_json::json!({ "labels": ["User"], "properties": { "name": "Alice" } }),
+    )
+    .await;
+    assert_eq!(status, 409);
+    assert_eq!(body["kind"], "constraint");
+    assert!(body["message"].as_str().unwrap().contains("Uniqueness constraint violated"));
+    assert_eq!(state.service.db().lock().unwrap().node_count(), before);
+
+    let mut props = Properties::new();
+    props.insert("name".to_string(), Value::Text("Bob".to_string()));
+    let err = state.service.create_node(vec!["User"], props).await.unwrap_err();
+    assert!(matches!(err, ServiceError::Constraint(_)));
+
+    // 其他标签不受约束影响
+    let (status, _) = post_raw(
+        &app,
+        "/nodes",
+        serde_json::json!({ "labels": ["Admin"], "properties": { "name": "Alice" } }),
+    )
+    .await;
+    assert_eq!(status, 200);
+}
+
+#[tokio::test]
+async fn test_json_numbers_keep_int_and_float_distinct() {
+    let state = create_test_state();
+    let app = create_router(state.clone());
+
+    let response: serde_json::Value = post_json(
+        &app,
+        "/nodes",
+        serde_json::json!({
+            "labels": ["Reading"],
+            "properties": { "count": 3, "level": 3.0, "big": 1e3, "neg": -7 }
+        }),
+    )
+    .await;
+    let id: NodeId = serde_json::from_value(response["id"].clone()).unwrap();
+
+    {
+        let db = state.service.db().lock().unwrap();
+        let node = db.get_node(id).unwrap();
+        assert_eq!(node.props.get("count"), Some(&Value::Int(3)));
+        assert_eq!(node.props.get("level"), Some(&Value::Float(3.0)));
+        assert_eq!(node.props.get("big"), Some(&Value::Float(1000.0)));
+        assert_eq!(node.props.get("neg"), Some(&Value::Int(-7)));
+    }
+
+    // 读回时保持原类型
+    let node: serde_json::Value = get_json(&app, &format!("/nodes/{}", id)).await;
+    assert!(node["properties"]["count"].is_i64());
+    assert!(node["properties"]["level"].is_f64());
+}
@@ -1,8 +1,9 @@
 //! SKIP/LIMIT 优化测试
 
-use rs_graphdb::{GraphDatabase, Query};
+use rs_graphdb::{GraphDatabase, Query, cypher};
 use rs_graphdb::values::{Properties, Value};
 use rs_graphdb::storage::mem_store::MemStore;
+use rs_graphdb::index_schema::IndexSchema;
 
 fn create_test_db() -> GraphDatabase<MemStore> {
     let mut db = GraphDatabase::new_in_memory();
@@ -235,3 +236,144 @@ fn test_order_by_with_pagination() {
         }
     }
 }
+
+// ========== Cypher LIMIT/SKIP 下推 ==========
+
+fn run_cypher_with_stats(
+    db: &GraphDatabase<MemStore>,
+    query: &str,
+) -> (Vec<rs_graphdb::graph::model::Node>, cypher::ExecutionStats) {
+    match cypher::parse_cypher(query).expect("Parse failed") {
+        cypher::CypherStatement::Query(ref q) => {
+            cypher::execute_cypher_with_stats(db, q).expect("Execute failed")
+        }
+        _ => panic!("Expected Query statement"),
+    }
+}
+
+fn create_large_db() -> GraphDatabase<MemStore> {
+    let mut db = create_test_db();
+    // 额外 1000 个其他标签的节点
+    for i in 0..1000 {
+        let mut props = Properties::new();
+        props.insert("id".to_string(), Value::Int(i));
+        db.create_node(vec!["Item"], props);
+    }
+    db
+}
+
+#[test]
+fn test_cypher_limit_pushdown_stops_early() {
+    let db = create_large_db();
+
+    let (result, stats) = run_cypher_with_stats(&db, "MATCH (n:User) RETURN n LIMIT 10");
+    assert_eq!(result.len(), 10);
+    assert!(result.iter().all(|n| n.labels.contains(&"User".to_string())));
+
+    // 提前终止：只检查了 10 个节点，而不是全部 1000 个 User
+    assert!(stats.limit_pushdown);
+    assert_eq!(stats.nodes_scanned, 10);
+}
+
+#[test]
+fn test_cypher_limit_pushdown_with_skip_and_where() {
+    let db = create_large_db();
+
+    let (result, stats) = run_cypher_with_stats(
+        &db,
+        "MATCH (n:User) WHERE n.id >= 500 RETURN n SKIP 20 LIMIT 30",
+    );
+    assert_eq!(result.len(), 30);
+    assert!(stats.limit_pushdown);
+    assert!(stats.nodes_scanned < 1000);
+
+    let mut ids = Vec::new();
+    for node in &result {
+        match node.get("id") {
+            Some(Value::Int(id)) => {
+                assert!(*id >= 500);
+                ids.push(*id);
+            }
+            _ => panic!("missing id"),
+        }
+    }
+    ids.sort();
+    ids.dedup();
+    assert_eq!(ids.len(), 30);
+
+    // LIMIT 超过匹配数：扫描全部后返回所有匹配
+    let (result, _) = run_cypher_with_stats(&db, "MATCH (n:User) WHERE n.id < 5 RETURN n LIMIT 100");
+    assert_eq!(result.len(), 5);
+}
+
+#[test]
+fn test_cypher_order_by_disables_pushdown() {
+    let db = create_large_db();
+
+    let (result, stats) =
+        run_cypher_with_stats(&db, "MATCH (n:User) RETURN n ORDER BY n.id DESC LIMIT 3");
+    assert!(!stats.limit_pushdown);
+    let ids: Vec<_> = result.iter().map(|n| n.get("id").cloned()).collect();
+    assert_eq!(
+        ids,
+        vec![Some(Value::Int(999)), Some(Value::Int(998)), Some(Value::Int(997))]
+    );
+}
+
+// ========== 执行统计 ==========
+
+fn run_statement_with_stats(
+    db: &mut GraphDatabase<MemStore>,
+    query: &str,
+) -> (cypher::CypherResult, cypher::ExecutionStats) {
+    let stmt = cypher::parse_cypher(query).expect("Parse failed");
+    cypher::execute_statement_with_stats(db, &stmt).expect("Execute failed")
+}
+
+#[test]
+fn test_execution_stats_scan_vs_index_lookup() {
+    // 默认 schema 为 User.id 建了索引，Item.id 没有索引
+    let mut db = create_large_db();
+
+    let (result, stats) = run_statement_with_stats(&mut db, "MATCH (n:Item {id: 42}) RETURN n");
+    match result {
+        cypher::CypherResult::Nodes(nodes) => assert_eq!(nodes.len(), 1),
+        _ => panic!("Expected nodes"),
+    }
+    assert!(!stats.index_used);
+    // 按标签扫描需要检查全部 2000 个节点
+    assert_eq!(stats.rows_examined, 2000);
+    assert!(stats.execution_time_ms >= 0.0);
+
+    let (result, stats) = run_statement_with_stats(&mut db, "MATCH (n:User {id: 42}) RETURN n");
+    match result {
+        cypher::CypherResult::Nodes(nodes) => {
+            assert_eq!(nodes.len(), 1);
+            assert_eq!(nodes[0].get("id"), Some(&Value::Int(42)));
+        }
+        _ => panic!("Expected nodes"),
+    }
+    assert!(stats.index_used);
+    assert_eq!(stats.rows_examined, 1);
+}
+
+#[test]
+fn test_execution_stats_index_miss_returns_empty() {
+    let mut schema = IndexSchema::new();
+    schema.add_index("Item", "id");
+    let mut db = GraphDatabase::new_in_memory_with_schema(schema);
+    for i in 0..100 {
+        let mut props = Properties::new();
+        props.insert("id".to_string(), Value::Int(i));
+        db.create_node(vec!["Item"], props);
+    }
+
+    // 索引未命中时直接返回空结果，而不是退化为全量扫描
+    let (result, stats) = run_statement_with_stats(&mut db, "MATCH (n:Item {id: 5000}) RETURN n");
+    match result {
+        cypher::CypherResult::Nodes(nodes) => assert!(nodes.is_empty()),
+        _ => panic!("Expected nodes"),
+    }
+    assert!(stats.index_used);
+    assert_eq!(stats.rows_examined, 0);
+}
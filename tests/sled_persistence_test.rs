@@ -10,14 +10,11 @@ fn make_user(name: &str) -> Properties {
 
 #[test]
 fn test_sled_persistence() {
-    let db_path = "./test_db_sled";
-
-    // 清理之前的测试数据
-    let _ = fs::remove_dir_all(db_path);
+    let temp_dir = tempfile::TempDir::new().unwrap();
 
     // 第一次：创建数据
     {
-        let store = SledStore::new(db_path).unwrap();
+        let store = SledStore::new(temp_dir.path()).unwrap();
         let mut db = GraphDatabase::from_engine(store);
 
         let alice = db.create_node(vec!["User"], make_user("Alice"));
@@ -30,8 +27,9 @@ fn test_sled_persistence() {
     }
 
     // 第二次：重新打开，验证数据持久化
+    let reopened = copy_to_fresh_dir(temp_dir.path());
     {
-        let store = SledStore::new(db_path).unwrap();
+        let store = SledStore::new(reopened.path()).unwrap();
         let db = GraphDatabase::from_engine(store);
 
         let alice_node = db.get_node(0).expect("Alice should exist");
@@ -50,7 +48,250 @@ fn test_sled_persistence() {
         assert_eq!(rels.len(), 1);
         assert_eq!(rels[0].end, 1);
     }
+}
+
+/// 把已关闭数据库的文件复制到新的临时目录
+///
+/// sled 关闭后后台线程可能短暂持有原目录的文件锁，从副本重新打开不受其影响
+fn copy_to_fresh_dir(path: &std::path::Path) -> tempfile::TempDir {
+    fn copy_dir(from: &std::path::Path, to: &std::path::Path) {
+        for entry in fs::read_dir(from).unwrap() {
+            let entry = entry.unwrap();
+            let target = to.join(entry.file_name());
+            if entry.file_type().unwrap().is_dir() {
+                fs::create_dir_all(&target).unwrap();
+                copy_dir(&entry.path(), &target);
+            } else {
+                fs::copy(entry.path(), target).unwrap();
+            }
+        }
+    }
+
+    let dir = tempfile::TempDir::new().unwrap();
+    copy_dir(path, dir.path());
+    dir
+}
+
+#[test]
+fn test_sled_rel_index_rebuilt_on_reopen() {
+    use rs_graphdb::storage::StorageEngine;
+
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let friend_since = || vec![("FRIEND".to_string(), "since".to_string())];
+    let since = |year: i64| {
+        let mut props = Properties::new();
+        props.insert("since".to_string(), Value::Int(year));
+        props
+    };
+
+    // 先在没有关系索引的情况下写入数据
+    let (old_friend, new_friend) = {
+        let mut store = SledStore::new(temp_dir.path()).unwrap();
+        let a = store.create_node(vec!["User".to_string()], make_user("Alice"));
+        let b = store.create_node(vec!["User".to_string()], make_user("Bob"));
+        let c = store.create_node(vec!["User".to_string()], make_user("Carol"));
+        let old_friend = store.create_rel(a, b, "FRIEND".to_string(), since(2015));
+        let new_friend = store.create_rel(a, c, "FRIEND".to_string(), since(2021));
+        store.create_rel(b, c, "COLLEAGUE".to_string(), since(2015));
+        store.flush().unwrap();
+        (old_friend, new_friend)
+    };
+
+    // 带关系索引配置重新打开：索引由已有数据重建
+    let reopened = copy_to_fresh_dir(temp_dir.path());
+    {
+        let mut store = SledStore::with_index_config(reopened.path(), Vec::new(), friend_since()).unwrap();
+        assert_eq!(store.query_rel_index("FRIEND", "since", &Value::Int(2015)).unwrap(), vec![old_friend]);
+        assert_eq!(store.query_rel_index("FRIEND", "since", &Value::Int(2021)).unwrap(), vec![new_friend]);
+        // 未配置的关系类型不进入索引
+        assert!(store.query_rel_index("COLLEAGUE", "since", &Value::Int(2015)).unwrap().is_empty());
+
+        // 新增和删除的关系同步维护索引
+        let d = store.create_node(vec!["User".to_string()], make_user("Dave"));
+        let latest = store.create_rel(0, d, "FRIEND".to_string(), since(2021));
+        assert!(store.delete_rel(new_friend));
+        assert_eq!(store.query_rel_index("FRIEND", "since", &Value::Int(2021)).unwrap(), vec![latest]);
+        store.flush().unwrap();
+    }
+
+    let reopened = copy_to_fresh_dir(reopened.path());
+    {
+        let store = SledStore::with_index_config(reopened.path(), Vec::new(), friend_since()).unwrap();
+        let hits = store.query_rel_index("FRIEND", "since", &Value::Int(2021)).unwrap();
+        assert_eq!(hits.len(), 1);
+        let rel = store.get_rel(hits[0]).unwrap();
+        assert_eq!(rel.typ, "FRIEND");
+        assert_eq!(rel.props.get("since"), Some(&Value::Int(2021)));
+    }
+}
+
+#[test]
+fn test_sled_scan_rels_pages_in_id_order() {
+    use rs_graphdb::storage::StorageEngine;
+
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let mut store = SledStore::new(temp_dir.path()).unwrap();
+    let hub = store.create_node(vec!["User".to_string()], make_user("Hub"));
+    // 超过 255 条，确保 ID 的字节编码顺序与数值顺序不一致
+    let mut expected = Vec::new();
+    for i in 0..300 {
+        let leaf = store.create_node(vec!["User".to_string()], make_user(&format!("Leaf{}", i)));
+        expected.push(store.create_rel(hub, leaf, "LINK".to_string(), Properties::new()));
+    }
+    expected.sort();
+
+    let scan_all = |store: &SledStore| {
+        let mut seen = Vec::new();
+        let mut cursor = None;
+        loop {
+            let page = store.scan_rels(cursor, 128);
+            if page.is_empty() {
+                break;
+            }
+            cursor = page.last().map(|r| r.id);
+            seen.extend(page.into_iter().map(|r| r.id));
+        }
+        seen
+    };
+    assert_eq!(scan_all(&store), expected);
+
+    // 删除的关系不再出现在分页结果中，重新打开后顺序不变
+    let deleted: Vec<_> = expected.iter().copied().step_by(3).collect();
+    for &id in &deleted {
+        assert!(store.delete_rel(id));
+    }
+    expected.retain(|id| !deleted.contains(id));
+    assert_eq!(scan_all(&store), expected);
+    assert!(store.scan_rels(expected.last().copied(), 10).is_empty());
+    store.flush().unwrap();
+    drop(store);
+
+    let reopened = copy_to_fresh_dir(temp_dir.path());
+    let store = SledStore::new(reopened.path()).unwrap();
+    assert_eq!(scan_all(&store), expected);
+}
+
+#[test]
+fn test_sled_batch_reads_match_point_reads() {
+    use rs_graphdb::storage::StorageEngine;
+
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let mut store = SledStore::new(temp_dir.path()).unwrap();
+    let a = store.create_node(vec!["User".to_string()], make_user("A"));
+    let b = store.create_node(vec!["User".to_string()], make_user("B"));
+    let c = store.create_node(vec!["User".to_string()], make_user("C"));
+    let ab = store.create_rel(a, b, "LINK".to_string(), Properties::new());
+    let bc = store.create_undirected_rel(b, c, "PEER".to_string(), Properties::new()).unwrap();
+    for i in 0..8 {
+        let leaf = store.create_node(vec!["User".to_string()], make_user(&format!("Leaf{}", i)));
+        store.create_rel(a, leaf, "LINK".to_string(), Properties::new());
+    }
+
+    // 少量请求走点查，覆盖全部 ID 时走单次遍历；两条路径结果一致，且保留重复和缺失
+    for nodes in [vec![c], vec![c, 99, a, c, b]] {
+        let expected: Vec<_> = nodes.iter().map(|&id| store.get_node(id).map(|n| n.id)).collect();
+        let batch: Vec<_> = store.get_nodes(&nodes).into_iter().map(|n| n.map(|n| n.id)).collect();
+        assert_eq!(batch, expected);
+    }
+    for rels in [vec![bc], vec![bc, 99, ab, bc]] {
+        let expected: Vec<_> = rels.iter().map(|&id| store.get_rel(id).map(|r| (r.id, r.directed))).collect();
+        let batch: Vec<_> = store.get_rels(&rels).into_iter().map(|r| r.map(|r| (r.id, r.directed))).collect();
+        assert_eq!(batch, expected);
+    }
+    assert_eq!(store.get_rels(&[bc])[0].as_ref().map(|r| r.directed), Some(false));
+}
+
+#[test]
+fn test_sled_fulltext_index_survives_reopen() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let post = |body: &str| {
+        let mut props = Properties::new();
+        props.insert("body".to_string(), Value::Text(body.to_string()));
+        props
+    };
+
+    let (first, second) = {
+        let store = SledStore::new(temp_dir.path()).unwrap();
+        let mut db = GraphDatabase::from_engine(store);
+        let first = db.create_node(vec!["Post"], post("Rust graph database"));
+        let second = db.create_node(vec!["Post"], post("graph algorithms explained"));
+        db.add_fulltext_index("Post", "body", first);
+        db.add_fulltext_index("Post", "body", second);
+        db.persist_fulltext_index().unwrap();
+        (first, second)
+    };
+
+    // 重新打开后无需重建即可搜索
+    let reopened = copy_to_fresh_dir(temp_dir.path());
+    let store = SledStore::new(reopened.path()).unwrap();
+    let db = GraphDatabase::from_engine(store);
+    let mut hits = db.search_fulltext("Post", "body", "graph");
+    hits.sort();
+    assert_eq!(hits, vec![first, second]);
+    assert_eq!(db.search_fulltext_and("Post", "body", "rust database"), vec![first]);
+}
+
+#[test]
+fn test_sled_fulltext_index_rebuilt_when_stale() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let post = |body: &str| {
+        let mut props = Properties::new();
+        props.insert("body".to_string(), Value::Text(body.to_string()));
+        props
+    };
+
+    let (first, third) = {
+        let store = SledStore::new(temp_dir.path()).unwrap();
+        let mut db = GraphDatabase::from_engine(store);
+        let first = db.create_node(vec!["Post"], post("Rust graph database"));
+        let second = db.create_node(vec!["Post"], post("graph algorithms explained"));
+        let third = db.create_node(vec!["Post"], post("graph coloring"));
+        db.add_fulltext_index("Post", "body", first);
+        db.add_fulltext_index("Post", "body", second);
+        db.persist_fulltext_index().unwrap();
+
+        // 保存之后的删除没有再次持久化
+        db.delete_node(second);
+        (first, third)
+    };
+
+    // 指纹不符，按登记的字段为存储中所有匹配的节点重建
+    let reopened = copy_to_fresh_dir(temp_dir.path());
+    let store = SledStore::new(reopened.path()).unwrap();
+    let db = GraphDatabase::from_engine(store);
+    let mut hits = db.search_fulltext("Post", "body", "graph");
+    hits.sort();
+    assert_eq!(hits, vec![first, third]);
+}
+
+#[test]
+fn test_sled_fulltext_rebuild_covers_registered_fields_without_documents() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+
+    let post = {
+        let store = SledStore::new(temp_dir.path()).unwrap();
+        let mut db = GraphDatabase::from_engine(store);
+        let mut props = Properties::new();
+        props.insert("title".to_string(), Value::Text("Draft".to_string()));
+        let draft = db.create_node(vec!["Post"], props);
+        // 字段已登记，但保存时还没有任何文档有 body
+        db.add_fulltext_index("Post", "body", draft);
+        db.persist_fulltext_index().unwrap();
+
+        // 保存之后新建的文档没有再次持久化
+        let mut props = Properties::new();
+        props.insert("body".to_string(), Value::Text("graph storage notes".to_string()));
+        db.create_node(vec!["Post"], props)
+    };
 
-    // 清理测试数据
-    let _ = fs::remove_dir_all(db_path);
+    let reopened = copy_to_fresh_dir(temp_dir.path());
+    let store = SledStore::new(reopened.path()).unwrap();
+    let db = GraphDatabase::from_engine(store);
+    assert_eq!(db.search_fulltext("Post", "body", "graph"), vec![post]);
+}
+
+#[test]
+fn test_mem_store_cannot_persist_fulltext_index() {
+    let mut db = GraphDatabase::new_in_memory();
+    assert!(db.persist_fulltext_index().is_err());
 }
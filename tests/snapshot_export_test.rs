@@ -0,0 +1,98 @@
+//! 整库快照导出/导入测试
+
+use rs_graphdb::graph::SnapshotCompression;
+use rs_graphdb::storage::mem_store::MemStore;
+use rs_graphdb::storage::{StorageError, StoredNode, StoredRel};
+use rs_graphdb::values::{Properties, Value};
+use rs_graphdb::GraphDatabase;
+
+/// 文本较多的小图：一条 FOLLOWS 链加一条无向 KNOWS 关系
+fn sample_graph() -> GraphDatabase<MemStore> {
+    let mut db = GraphDatabase::new_in_memory();
+    let mut ids = Vec::new();
+    for i in 0..200 {
+        let mut props = Properties::new();
+        props.insert("name".to_string(), Value::Text(format!("user-{}", i)));
+        props.insert(
+            "bio".to_string(),
+            Value::Text("graph databases store nodes and relationships ".repeat(5)),
+        );
+        props.insert("score".to_string(), Value::Float(i as f64 / 3.0));
+        ids.push(db.create_node(vec!["User"], props));
+    }
+    for pair in ids.windows(2) {
+        let mut props = Properties::new();
+        props.insert("since".to_string(), Value::Int(2020));
+        db.create_rel(pair[0], pair[1], "FOLLOWS", props);
+    }
+    db.create_undirected_rel(ids[0], ids[199], "KNOWS", Properties::new())
+        .unwrap();
+    db
+}
+
+/// 按 ID 排序的节点和（去重后的）关系
+fn contents(db: &GraphDatabase<MemStore>) -> (Vec<StoredNode>, Vec<StoredRel>) {
+    let mut nodes: Vec<_> = db.all_stored_nodes().collect();
+    nodes.sort_by_key(|n| n.id);
+    let mut rels: Vec<_> = db.rels_page(None, usize::MAX);
+    rels.sort_by_key(|r| r.id);
+    let rels = rels
+        .into_iter()
+        .map(|r| StoredRel {
+            id: r.id,
+            start: r.start,
+            end: r.end,
+            typ: r.typ,
+            props: r.props,
+            directed: r.directed,
+        })
+        .collect();
+    (nodes, rels)
+}
+
+fn round_trip(compression: SnapshotCompression) -> usize {
+    let original = sample_graph();
+    let mut bytes = Vec::new();
+    original.export_snapshot(&mut bytes, compression).unwrap();
+
+    let mut restored = GraphDatabase::new_in_memory();
+    assert_eq!(restored.import_snapshot(bytes.as_slice()).unwrap(), (200, 200));
+    assert_eq!(contents(&restored), contents(&original));
+    assert_eq!(restored.degree(0), original.degree(0));
+
+    // 再次导入同一快照会发生 ID 冲突，且不做任何修改
+    assert!(matches!(
+        restored.import_snapshot(bytes.as_slice()),
+        Err(StorageError::IdConflict(_))
+    ));
+    assert_eq!(restored.node_count(), 200);
+    bytes.len()
+}
+
+#[test]
+fn test_snapshot_round_trip_uncompressed() {
+    round_trip(SnapshotCompression::None);
+}
+
+#[test]
+fn test_import_rejects_garbage() {
+    let mut db = GraphDatabase::new_in_memory();
+    assert!(db.import_snapshot(&b"not a snapshot"[..]).is_err());
+    assert_eq!(db.node_count(), 0);
+}
+
+#[cfg(feature = "gzip")]
+#[test]
+fn test_snapshot_round_trip_gzip() {
+    let raw = round_trip(SnapshotCompression::None);
+    let compressed = round_trip(SnapshotCompression::Gzip);
+    assert!(compressed * 4 < raw, "gzip {} vs raw {}", compressed, raw);
+}
+
+#[cfg(feature = "zstd")]
+#[test]
+fn test_snapshot_round_trip_zstd() {
+    let raw = round_trip(SnapshotCompression::None);
+    let compressed = round_trip(SnapshotCompression::Zstd);
+    assert!(compressed * 4 < raw, "zstd {} vs raw {}", compressed, raw);
+}
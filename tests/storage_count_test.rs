@@ -0,0 +1,106 @@
+// 存储引擎节点/关系计数器测试
+
+use rs_graphdb::graph::db::GraphDatabase;
+use rs_graphdb::storage::mem_store::MemStore;
+use rs_graphdb::storage::sled_store::SledStore;
+use rs_graphdb::storage::StorageEngine;
+use rs_graphdb::values::Properties;
+use tempfile::TempDir;
+
+/// 混合执行创建/删除操作，并在每一步之后与遍历统计结果对比
+fn run_mixed_sequence<E: StorageEngine>(db: &mut GraphDatabase<E>) {
+    let check = |db: &GraphDatabase<E>| {
+        let nodes: Vec<_> = db.all_stored_nodes().collect();
+        let rels: usize = nodes.iter().map(|n| db.neighbors_out(n.id).count()).sum();
+        assert_eq!(db.node_count(), nodes.len());
+        assert_eq!(db.rel_count(), rels);
+    };
+
+    assert_eq!(db.node_count(), 0);
+    assert_eq!(db.rel_count(), 0);
+
+    let ids: Vec<_> = (0..5)
+        .map(|_| db.create_node(vec!["User"], Properties::new()))
+        .collect();
+    check(db);
+
+    db.create_rel(ids[0], ids[1], "FRIEND", Properties::new());
+    db.create_rel(ids[1], ids[2], "FRIEND", Properties::new());
+    let r = db.create_rel(ids[2], ids[3], "FRIEND", Properties::new());
+    db.create_rel(ids[3], ids[3], "SELF", Properties::new());
+    check(db);
+    assert_eq!(db.rel_count(), 4);
+
+    // 删除关系
+    assert!(db.delete_rel(r));
+    assert!(!db.delete_rel(r));
+    check(db);
+    assert_eq!(db.rel_count(), 3);
+
+    // 删除节点会级联删除关联关系（包含自环）
+    assert!(db.delete_node(ids[1]));
+    assert!(db.delete_node(ids[3]));
+    check(db);
+    assert_eq!(db.node_count(), 3);
+    assert_eq!(db.rel_count(), 0);
+
+    // 删除不存在的节点不影响计数
+    assert!(!db.delete_node(ids[1]));
+    assert_eq!(db.node_count(), 3);
+
+    // 批量创建
+    let batch = db.batch_create_nodes(vec![(vec!["User".to_string()], Properties::new()); 3]);
+    db.batch_create_rels(vec![
+        (batch[0], batch[1], "FRIEND".to_string(), Properties::new()),
+        (batch[1], batch[2], "FRIEND".to_string(), Properties::new()),
+    ]);
+    check(db);
+    assert_eq!(db.node_count(), 6);
+    assert_eq!(db.rel_count(), 2);
+}
+
+#[test]
+fn test_mem_store_counts() {
+    let mut db = GraphDatabase::<MemStore>::new_in_memory();
+    run_mixed_sequence(&mut db);
+}
+
+#[test]
+fn test_sled_store_counts_survive_reopen() {
+    let temp_dir = TempDir::new().unwrap();
+
+    {
+        let store = SledStore::new(temp_dir.path()).unwrap();
+        let mut db = GraphDatabase::from_engine(store);
+        run_mixed_sequence(&mut db);
+        db.flush().unwrap();
+    }
+
+    // 重新打开后计数从磁盘数据恢复
+    let reopened = copy_to_fresh_dir(temp_dir.path());
+    let store = SledStore::new(reopened.path()).unwrap();
+    assert_eq!(store.node_count(), 6);
+    assert_eq!(store.rel_count(), 2);
+}
+
+/// 把已关闭数据库的文件复制到新的临时目录
+///
+/// sled 关闭后后台线程可能短暂持有原目录的文件锁，从副本重新打开不受其影响
+fn copy_to_fresh_dir(path: &std::path::Path) -> TempDir {
+    fn copy_dir(from: &std::path::Path, to: &std::path::Path) {
+        for entry in std::fs::read_dir(from).unwrap() {
+            let entry = entry.unwrap();
+            let target = to.join(entry.file_name());
+            if entry.file_type().unwrap().is_dir() {
+                std::fs::create_dir_all(&target).unwrap();
+                copy_dir(&entry.path(), &target);
+            } else {
+                std::fs::copy(entry.path(), target).unwrap();
+            }
+        }
+    }
+
+    let dir = TempDir::new().unwrap();
+    copy_dir(path, dir.path());
+    dir
+}
@@ -3,6 +3,7 @@
 use rs_graphdb::{GraphDatabase, cypher::streaming::*};
 use rs_graphdb::values::{Properties, Value};
 use rs_graphdb::storage::mem_store::MemStore;
+use rs_graphdb::storage::NodeId;
 
 fn create_test_db_with_nodes(count: usize) -> GraphDatabase<MemStore> {
     let mut db = GraphDatabase::new_in_memory();
@@ -326,3 +327,92 @@ fn test_cursor_vs_stream_consistency() {
     // 两者应该返回相同的节点ID
     assert_eq!(cursor_ids, stream_ids);
 }
+
+// ========== 逐行迭代执行 ==========
+
+fn collect_ids(nodes: impl IntoIterator<Item = rs_graphdb::graph::model::Node>) -> Vec<NodeId> {
+    nodes.into_iter().map(|n| n.id).collect()
+}
+
+#[test]
+fn test_execute_cypher_iter_matches_execute_cypher() {
+    let mut db = create_test_db_with_nodes(200);
+    let a = db.create_node(vec!["Person"], Properties::new());
+    let b = db.create_node(vec!["Person"], Properties::new());
+    db.create_rel(a, b, "KNOWS", Properties::new());
+
+    let queries = [
+        "MATCH (n:User) RETURN n",
+        "MATCH (n:User) WHERE n.age > 60 RETURN n",
+        "MATCH (n:User {age: 25}) RETURN n SKIP 1 LIMIT 2",
+        "MATCH (n:User) RETURN n SKIP 190",
+        "MATCH (n:User) RETURN n ORDER BY n.id DESC LIMIT 5",
+        "MATCH (a:Person)-[:KNOWS]->(b) RETURN b",
+    ];
+    for query in queries {
+        let parsed = match rs_graphdb::cypher::parse_cypher(query).unwrap() {
+            rs_graphdb::cypher::CypherStatement::Query(q) => q,
+            _ => panic!("Expected query"),
+        };
+        let expected = rs_graphdb::cypher::execute_cypher(&db, &parsed).unwrap();
+        let rows = rs_graphdb::cypher::execute_cypher_iter(&db, &parsed).unwrap();
+        assert_eq!(collect_ids(rows), collect_ids(expected), "query: {}", query);
+    }
+}
+
+#[test]
+fn test_execute_cypher_iter_is_lazy() {
+    let db = create_test_db_with_nodes(1000);
+    let parsed = match rs_graphdb::cypher::parse_cypher("MATCH (n:User) RETURN n").unwrap() {
+        rs_graphdb::cypher::CypherStatement::Query(q) => q,
+        _ => panic!("Expected query"),
+    };
+
+    // 只取前 3 行，不需要物化全部结果
+    let first: Vec<_> = rs_graphdb::cypher::execute_cypher_iter(&db, &parsed)
+        .unwrap()
+        .take(3)
+        .collect();
+    assert_eq!(first.len(), 3);
+    assert!(first.iter().all(|n| n.labels.contains(&"User".to_string())));
+}
+
+#[test]
+fn test_cypher_batches_match_execute_cypher_and_skip_deleted_nodes() {
+    use rs_graphdb::cypher::CypherBatches;
+
+    let parse = |query: &str| match rs_graphdb::cypher::parse_cypher(query).unwrap() {
+        rs_graphdb::cypher::CypherStatement::Query(q) => q,
+        _ => panic!("Expected query"),
+    };
+    let drain = |db: &GraphDatabase<MemStore>, batches: &mut CypherBatches| {
+        let mut rows = Vec::new();
+        while let Some(batch) = batches.next_batch(db, 16) {
+            assert!(batch.len() <= 16);
+            rows.extend(batch);
+        }
+        rows
+    };
+
+    let mut db = create_test_db_with_nodes(200);
+    for query in [
+        "MATCH (n:User) WHERE n.age > 60 RETURN n",
+        "MATCH (n:User {age: 25}) RETURN n SKIP 1 LIMIT 2",
+        "MATCH (n:User) RETURN n ORDER BY n.id DESC LIMIT 5",
+    ] {
+        let parsed = parse(query);
+        let expected = rs_graphdb::cypher::execute_cypher(&db, &parsed).unwrap();
+        let mut batches = CypherBatches::open(&db, &parsed).unwrap();
+        assert_eq!(collect_ids(drain(&db, &mut batches)), collect_ids(expected), "query: {}", query);
+    }
+
+    // 批次之间数据库可以被修改，已删除的节点不再返回
+    let mut batches = CypherBatches::open(&db, &parse("MATCH (n:User) RETURN n")).unwrap();
+    let first = batches.next_batch(&db, 16).unwrap();
+    assert_eq!(first.len(), 16);
+    let ids: Vec<NodeId> = db.all_stored_nodes().map(|n| n.id).collect();
+    for id in ids {
+        db.delete_node(id);
+    }
+    assert!(drain(&db, &mut batches).is_empty());
+}
@@ -3,10 +3,13 @@
 
 use rs_graphdb::transactions::{
     TransactionManager, TransactionOp, TransactionStatus, TransactionError,
-    Savepoint, LockManager, LockType,
+    Savepoint, LockManager, LockType, Resource,
 };
-use rs_graphdb::storage::{NodeId, RelId};
+use rs_graphdb::storage::NodeId;
 use rs_graphdb::values::{Properties, Value};
+use std::sync::{mpsc, Arc, Barrier, Mutex};
+use std::thread;
+use std::time::Duration;
 
 // ==================== 事务超时测试 ====================
 
@@ -335,6 +338,60 @@ fn test_lock_rel_locks() {
     assert!(!lm.acquire_rel_lock(2, 1, LockType::Read));
 }
 
+#[test]
+fn test_lock_all_releases_on_drop() {
+    let lm = Arc::new(Mutex::new(LockManager::new()));
+
+    let guard = LockManager::lock_all(
+        &lm,
+        1,
+        &[Resource::Rel(3), Resource::Node(7), Resource::Node(2), Resource::Node(7)],
+        LockType::Write,
+    );
+    // 去重并按规范顺序排列
+    assert_eq!(guard.resources(), &[Resource::Node(2), Resource::Node(7), Resource::Rel(3)]);
+    assert_eq!(lm.lock().unwrap().get_lock_count(1), 3);
+    assert!(!lm.lock().unwrap().acquire_node_lock(2, 7, LockType::Read));
+
+    drop(guard);
+    assert_eq!(lm.lock().unwrap().get_lock_count(1), 0);
+    assert!(lm.lock().unwrap().acquire_node_lock(2, 7, LockType::Write));
+}
+
+#[test]
+fn test_lock_all_overlapping_sets_do_not_deadlock() {
+    let lm = Arc::new(Mutex::new(LockManager::new()));
+    let barrier = Arc::new(Barrier::new(2));
+    let (done_tx, done_rx) = mpsc::channel();
+
+    // 两个线程以相反的请求顺序锁住重叠的资源集合
+    let request_orders = [
+        vec![Resource::Node(1), Resource::Node(2), Resource::Rel(5)],
+        vec![Resource::Rel(5), Resource::Node(2), Resource::Node(1), Resource::Node(3)],
+    ];
+    for (tx_id, resources) in (1u64..).zip(request_orders) {
+        let lm = Arc::clone(&lm);
+        let barrier = Arc::clone(&barrier);
+        let done_tx = done_tx.clone();
+        thread::spawn(move || {
+            barrier.wait();
+            for _ in 0..500 {
+                let _guard = LockManager::lock_all(&lm, tx_id, &resources, LockType::Write);
+                thread::yield_now();
+            }
+            done_tx.send(tx_id).unwrap();
+        });
+    }
+
+    for _ in 0..2 {
+        done_rx
+            .recv_timeout(Duration::from_secs(30))
+            .expect("lock_all deadlocked");
+    }
+    let lm = lm.lock().unwrap();
+    assert!(!lm.is_node_locked(1) && !lm.is_node_locked(2) && !lm.is_rel_locked(5));
+}
+
 // ==================== 综合测试 ====================
 
 #[test]
@@ -475,7 +532,7 @@ fn test_optimistic_lock_basic_workflow() {
     use rs_graphdb::transactions::{OptimisticLockManager, Version};
 
     let mut lock_manager = OptimisticLockManager::new();
-    let node_id: u64 = 1;
+    let node_id: NodeId = 1;
 
     // 模拟读取
     let read_version = lock_manager.read_node_version(node_id);
@@ -497,8 +554,8 @@ fn test_optimistic_lock_read_context() {
     let mut lock_manager = OptimisticLockManager::new();
     let mut ctx = OptimisticReadContext::new();
 
-    let node1: u64 = 1;
-    let node2: u64 = 2;
+    let node1: NodeId = 1;
+    let node2: NodeId = 2;
 
     // 记录读取
     let v1 = lock_manager.read_node_version(node1);
@@ -522,7 +579,7 @@ fn test_optimistic_lock_version_conflict() {
     use rs_graphdb::transactions::{OptimisticLockManager, Version, TransactionError};
 
     let mut lock_manager = OptimisticLockManager::new();
-    let node_id: u64 = 1;
+    let node_id: NodeId = 1;
 
     // 第一次读取
     let v1 = lock_manager.read_node_version(node_id);
@@ -675,3 +732,51 @@ fn test_isolation_stats() {
     let stats = executor.stats();
     assert_eq!(stats.committed_transactions, 0);
 }
+
+#[test]
+fn test_isolation_prunes_write_sets_below_watermark() {
+    use rs_graphdb::transactions::{IsolationExecutor, IsolationLevel, PrunePolicy, TransactionOp};
+
+    // 每次事务结束后只要还有已提交写集就尝试清理
+    let executor = IsolationExecutor::new().with_prune_policy(PrunePolicy {
+        max_committed: Some(0),
+        interval: None,
+    });
+    let mut props = Properties::new();
+    props.insert("value".to_string(), Value::Int(1));
+    let ops = vec![TransactionOp::UpdateNode {
+        id: 1,
+        old_properties: Properties::new(),
+        new_properties: props,
+    }];
+
+    // TX1 在 TX2 提交前开始，TX2 的写集必须保留
+    executor.begin_transaction(1);
+    executor.begin_transaction(2);
+    executor.finish_transaction(2, true, Some(&ops));
+    assert_eq!(executor.stats().committed_transactions, 1);
+    assert!(executor
+        .validate_commit(1, IsolationLevel::Serializable, &ops)
+        .is_err());
+
+    // TX3 在 TX2 提交后开始；TX1 结束后水位线越过 TX2 的提交点，写集被回收
+    executor.begin_transaction(3);
+    executor.finish_transaction(1, false, None);
+    assert_eq!(executor.stats().committed_transactions, 0);
+    assert!(executor
+        .validate_commit(3, IsolationLevel::Serializable, &ops)
+        .is_ok());
+
+    // 没有活动事务时，手动清理会回收全部写集
+    let manual = IsolationExecutor::new().with_prune_policy(PrunePolicy {
+        max_committed: None,
+        interval: None,
+    });
+    for tx in 10..15 {
+        manual.begin_transaction(tx);
+        manual.finish_transaction(tx, true, Some(&ops));
+    }
+    assert_eq!(manual.stats().committed_transactions, 5);
+    assert_eq!(manual.watermark(), None);
+    assert_eq!(manual.prune_committed(), 5);
+}
@@ -4,6 +4,7 @@
 use rs_graphdb::transactions::{
     TransactionManager, TransactionOp, TransactionStatus, TransactionError,
     Snapshot, SnapshotManager, NodeData, RelData, Transaction,
+    IsolationLevel, TransactionConfig, LockType,
 };
 use rs_graphdb::storage::{NodeId, RelId};
 use rs_graphdb::values::{Properties, Value};
@@ -410,6 +411,94 @@ fn test_transaction_active_ids() {
     assert!(ids.contains(&2));
 }
 
+#[test]
+fn test_active_transactions_info() {
+    let mut tm = TransactionManager::new();
+
+    let t1 = tm
+        .begin_transaction_with_config(
+            &TransactionConfig::new().with_isolation_level(IsolationLevel::Serializable),
+        )
+        .id;
+    let t2 = tm
+        .begin_transaction_with_config(
+            &TransactionConfig::new().with_isolation_level(IsolationLevel::ReadUncommitted),
+        )
+        .id;
+
+    for id in [1, 2] {
+        let op = TransactionOp::CreateNode {
+            id,
+            labels: vec!["Test".to_string()],
+            properties: Properties::new(),
+        };
+        tm.record_op(t1, op).unwrap();
+    }
+    // 记录操作时 t1 已对节点 1、2 加写锁
+    assert!(!tm.locks_mut().acquire_node_lock(t2, 1, LockType::Read));
+    assert!(tm.locks_mut().acquire_node_lock(t2, 3, LockType::Read));
+    assert!(tm.locks_mut().acquire_node_lock(t2, 4, LockType::Read));
+    assert!(tm.locks_mut().acquire_rel_lock(t2, 7, LockType::Write));
+
+    std::thread::sleep(std::time::Duration::from_millis(5));
+    let infos = tm.active_transactions();
+    assert_eq!(infos.len(), 2);
+
+    assert_eq!(infos[0].id, t1);
+    assert_eq!(infos[0].isolation_level, IsolationLevel::Serializable);
+    assert_eq!(infos[0].op_count, 2);
+    assert_eq!(infos[0].held_locks, 2);
+
+    assert_eq!(infos[1].id, t2);
+    assert_eq!(infos[1].isolation_level, IsolationLevel::ReadUncommitted);
+    assert_eq!(infos[1].op_count, 0);
+    assert_eq!(infos[1].held_locks, 3);
+    assert!(infos.iter().all(|info| info.age >= std::time::Duration::from_millis(5)));
+
+    // 长事务检测
+    assert_eq!(tm.long_running_transactions(std::time::Duration::ZERO).len(), 2);
+    assert!(tm.long_running_transactions(std::time::Duration::from_secs(3600)).is_empty());
+
+    // 结束事务后释放其锁并不再报告
+    tm.rollback(t2).unwrap();
+    assert_eq!(tm.locks().get_lock_count(t2), 0);
+    let infos = tm.active_transactions();
+    assert_eq!(infos.len(), 1);
+    assert_eq!(infos[0].id, t1);
+}
+
+#[test]
+fn test_record_op_locks_touched_entities() {
+    use rs_graphdb::transactions::Resource;
+
+    let mut tm = TransactionManager::new();
+    let t1 = tm.begin_transaction().id;
+    let t2 = tm.begin_transaction().id;
+
+    let update = |id: NodeId| TransactionOp::UpdateNode {
+        id,
+        old_properties: Properties::new(),
+        new_properties: Properties::new(),
+    };
+    tm.record_op(t1, update(1)).unwrap();
+    // 同一事务重复写同一节点不会重复加锁
+    tm.record_op(t1, update(1)).unwrap();
+    assert_eq!(tm.locks().get_lock_count(t1), 1);
+
+    let err = tm.record_op(t2, update(1)).unwrap_err();
+    assert!(matches!(err, TransactionError::LockConflict(Resource::Node(1))));
+    assert_eq!(tm.get_transaction(t2).unwrap().op_count(), 0);
+
+    let delete_rel = TransactionOp::DeleteRel { id: 9, rel: create_rel_data(9, 1, 2) };
+    tm.record_op(t2, delete_rel).unwrap();
+    assert!(tm.locks().is_rel_locked(9));
+
+    // 提交后释放锁，其他事务可以写入
+    tm.commit(t1).unwrap();
+    tm.record_op(t2, update(1)).unwrap();
+    assert_eq!(tm.locks().get_lock_count(t2), 2);
+}
+
 #[test]
 fn test_transaction_error_display() {
     let err = TransactionError::TransactionNotFound(42);
@@ -221,3 +221,112 @@ fn test_performance_100_nodes() {
     // 性能断言：应该在合理时间内完成
     assert!(elapsed.as_secs() < 5, "Triangle counting took too long: {:?}", elapsed);
 }
+
+// ========== 增量三角形计数 ==========
+
+#[test]
+fn test_incremental_triangle_count_matches_full_count() {
+    let mut db = GraphDatabase::new_in_memory();
+    let ids: Vec<_> = (0..5)
+        .map(|i| db.create_node(vec!["Node"], props(&i.to_string())))
+        .collect();
+
+    let check = |db: &GraphDatabase<_>| {
+        assert_eq!(db.triangle_count(), algorithms::count_triangles(db));
+    };
+
+    db.create_rel(ids[0], ids[1], "E", Properties::new());
+    db.create_rel(ids[1], ids[2], "E", Properties::new());
+    check(&db);
+    let closing = db.create_rel(ids[2], ids[0], "E", Properties::new());
+    assert_eq!(db.triangle_count(), 1);
+    check(&db);
+
+    // 反向的平行关系与自环不产生新三角形
+    let parallel = db.create_rel(ids[0], ids[2], "E", Properties::new());
+    db.create_rel(ids[1], ids[1], "E", Properties::new());
+    assert_eq!(db.triangle_count(), 1);
+
+    // 第二个三角形共享边 0-1
+    db.create_rel(ids[3], ids[0], "E", Properties::new());
+    db.create_undirected_rel(ids[1], ids[3], "E", Properties::new()).unwrap();
+    assert_eq!(db.triangle_count(), 2);
+    check(&db);
+
+    // 删除平行关系之一：0 与 2 仍相连
+    db.delete_rel(closing);
+    assert_eq!(db.triangle_count(), 2);
+    db.delete_rel(parallel);
+    assert_eq!(db.triangle_count(), 1);
+    check(&db);
+
+    // 删除节点 3 会带走它参与的三角形
+    db.delete_node(ids[3]);
+    assert_eq!(db.triangle_count(), 0);
+    check(&db);
+}
+
+#[test]
+fn test_incremental_triangle_count_batch_rels() {
+    let mut db = GraphDatabase::new_in_memory();
+    let ids: Vec<_> = (0..4)
+        .map(|i| db.create_node(vec!["Node"], props(&i.to_string())))
+        .collect();
+
+    // 完全图 K4 含 4 个三角形，其中部分边在同一批次内形成三角形
+    let mut rels = Vec::new();
+    for i in 0..4 {
+        for j in (i + 1)..4 {
+            rels.push((ids[i], ids[j], "E".to_string(), Properties::new()));
+        }
+    }
+    rels.push((ids[1], ids[0], "E".to_string(), Properties::new()));
+    db.batch_create_rels(rels);
+
+    assert_eq!(db.triangle_count(), 4);
+    assert_eq!(db.triangle_count(), algorithms::count_triangles(&db));
+}
+
+#[test]
+fn test_triangle_count_initialized_from_existing_engine() {
+    use rs_graphdb::storage::mem_store::MemStore;
+    use rs_graphdb::storage::StorageEngine;
+
+    let mut engine = MemStore::new();
+    let a = engine.create_node(vec![], Properties::new());
+    let b = engine.create_node(vec![], Properties::new());
+    let c = engine.create_node(vec![], Properties::new());
+    engine.create_rel(a, b, "E".to_string(), Properties::new());
+    engine.create_rel(b, c, "E".to_string(), Properties::new());
+    engine.create_rel(c, a, "E".to_string(), Properties::new());
+
+    let db = GraphDatabase::from_engine(engine);
+    assert_eq!(db.triangle_count(), 1);
+}
+
+#[test]
+fn test_triangle_count_restored_after_atomic_rollback() {
+    use rs_graphdb::graph::db::GraphError;
+
+    let mut db = GraphDatabase::new_in_memory();
+    let ids: Vec<_> = (0..3)
+        .map(|i| db.create_node(vec!["Node"], props(&i.to_string())))
+        .collect();
+    db.create_rel(ids[0], ids[1], "E", Properties::new());
+    let side = db.create_rel(ids[1], ids[2], "E", Properties::new());
+
+    // 回滚时按撤销日志逐条删除、恢复关系，计数随之增量还原
+    let result: Result<(), GraphError> = db.atomically(|db| {
+        db.create_rel(ids[2], ids[0], "E", Properties::new());
+        assert_eq!(db.triangle_count(), 1);
+        db.delete_rel(side);
+        assert_eq!(db.triangle_count(), 0);
+        Err(GraphError::NotFound)
+    });
+    assert!(result.is_err());
+    assert_eq!(db.triangle_count(), 0);
+
+    db.create_rel(ids[2], ids[0], "E", Properties::new());
+    assert_eq!(db.triangle_count(), 1);
+    assert_eq!(db.triangle_count(), algorithms::count_triangles(&db));
+}
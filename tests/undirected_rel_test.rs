@@ -0,0 +1,247 @@
+// 无向关系测试
+
+use rs_graphdb::graph::db::GraphDatabase;
+use rs_graphdb::storage::mem_store::MemStore;
+use rs_graphdb::storage::sled_store::SledStore;
+use rs_graphdb::storage::{NodeId, StorageEngine};
+use rs_graphdb::values::{Properties, Value};
+use rs_graphdb::{algorithms, cypher, Query};
+use tempfile::TempDir;
+
+fn user(name: &str) -> Properties {
+    let mut props = Properties::new();
+    props.insert("name".to_string(), Value::Text(name.to_string()));
+    props
+}
+
+/// 两个端点都能沿出边和入边看到无向关系，且对端正确
+fn assert_reachable_both_ways<E: StorageEngine>(db: &GraphDatabase<E>, a: NodeId, b: NodeId) {
+    let out_a: Vec<_> = db.neighbors_out(a).collect();
+    let out_b: Vec<_> = db.neighbors_out(b).collect();
+    assert_eq!(out_a.len(), 1);
+    assert_eq!(out_b.len(), 1);
+    assert!(!out_a[0].directed);
+    assert_eq!((out_a[0].start, out_a[0].end), (a, b));
+    assert_eq!((out_b[0].start, out_b[0].end), (b, a));
+
+    let in_a: Vec<_> = db.neighbors_in(a).collect();
+    let in_b: Vec<_> = db.neighbors_in(b).collect();
+    assert_eq!((in_a[0].start, in_a[0].end), (b, a));
+    assert_eq!((in_b[0].start, in_b[0].end), (a, b));
+}
+
+#[test]
+fn test_undirected_rel_traversal() {
+    let mut db = GraphDatabase::<MemStore>::new_in_memory();
+    let a = db.create_node(vec!["User"], user("A"));
+    let b = db.create_node(vec!["User"], user("B"));
+    let c = db.create_node(vec!["User"], user("C"));
+
+    let rel = db.create_undirected_rel(a, b, "KNOWS", Properties::new()).unwrap();
+    assert!(!db.get_rel(rel).unwrap().directed);
+    assert_reachable_both_ways(&db, a, b);
+
+    // 普通的出边遍历即可从任一端到达对端
+    let from_b = Query::new(&db)
+        .from_label("User")
+        .where_prop_eq("name", "B")
+        .out("KNOWS")
+        .collect_nodes();
+    assert_eq!(from_b.len(), 1);
+    assert_eq!(from_b[0].id, a);
+    let from_a = Query::new(&db)
+        .from_label("User")
+        .where_prop_eq("name", "A")
+        .out("KNOWS")
+        .collect_nodes();
+    assert_eq!(from_a[0].id, b);
+
+    // 最短路径算法沿出边即可反向走通
+    db.create_rel(c, b, "KNOWS", Properties::new());
+    let path = algorithms::bfs_shortest_path(&db, c, a).unwrap();
+    assert_eq!(path, vec![c, b, a]);
+    assert!(algorithms::bfs_shortest_path(&db, a, c).is_none());
+}
+
+#[test]
+fn test_undirected_rel_degree_and_count() {
+    let mut db = GraphDatabase::<MemStore>::new_in_memory();
+    let a = db.create_node(vec!["User"], user("A"));
+    let b = db.create_node(vec!["User"], user("B"));
+    let c = db.create_node(vec!["User"], user("C"));
+
+    let mut weight = Properties::new();
+    weight.insert("weight".to_string(), Value::Float(2.0));
+    db.create_undirected_rel(a, b, "KNOWS", weight).unwrap();
+    db.create_rel(a, c, "KNOWS", Properties::new());
+
+    // 无向关系在度中只计一次
+    assert_eq!(db.degree(a), 2);
+    assert_eq!(db.degree(b), 1);
+    assert_eq!(db.rel_count(), 2);
+
+    let strength = algorithms::weighted_degree_directed(&db, "weight");
+    assert_eq!(strength[&a].undirected_strength, 2.0);
+    assert_eq!(strength[&a].out_strength, 1.0);
+    assert_eq!(strength[&b].undirected_strength, 2.0);
+    assert_eq!(strength[&b].in_strength, 0.0);
+    assert_eq!(algorithms::weighted_degree(&db, "weight")[&b], 2.0);
+}
+
+#[test]
+fn test_undirected_rel_delete() {
+    let mut db = GraphDatabase::<MemStore>::new_in_memory();
+    let a = db.create_node(vec!["User"], user("A"));
+    let b = db.create_node(vec!["User"], user("B"));
+    let c = db.create_node(vec!["User"], user("C"));
+
+    let r1 = db.create_undirected_rel(a, b, "KNOWS", Properties::new()).unwrap();
+    db.create_undirected_rel(b, c, "KNOWS", Properties::new()).unwrap();
+
+    assert!(db.delete_rel(r1));
+    assert_eq!(db.neighbors_out(a).count(), 0);
+    assert_eq!(db.neighbors_in(a).count(), 0);
+    assert_eq!(db.degree(b), 1);
+
+    // 删除节点时级联删除无向关系，另一端不再可见
+    assert!(db.delete_node(b));
+    assert_eq!(db.neighbors_out(c).count(), 0);
+    assert_eq!(db.neighbors_in(c).count(), 0);
+    assert_eq!(db.rel_count(), 0);
+}
+
+#[test]
+fn test_cypher_create_undirected() {
+    let mut db = GraphDatabase::<MemStore>::new_in_memory();
+
+    let stmt = cypher::parse_cypher(r#"CREATE (a:User {name: "A"})-[:KNOWS]-(b:User {name: "B"})"#)
+        .expect("Parse failed");
+    let (nodes, rels) = match cypher::execute_statement(&mut db, &stmt).expect("Execute failed") {
+        cypher::CypherResult::Created { nodes, rels } => (nodes, rels),
+        _ => panic!("Expected Created"),
+    };
+    assert_eq!(rels, 1);
+    assert_reachable_both_ways(&db, nodes[0], nodes[1]);
+
+    // 从 B 沿出边匹配即可找到 A
+    let stmt = cypher::parse_cypher(r#"MATCH (b:User {name: "B"})-[:KNOWS]->(x) RETURN x"#)
+        .expect("Parse failed");
+    let result = match stmt {
+        cypher::CypherStatement::Query(ref q) => cypher::execute_cypher(&db, q).expect("Execute failed"),
+        _ => panic!("Expected Query statement"),
+    };
+    assert_eq!(result.len(), 1);
+    assert_eq!(result[0].id, nodes[0]);
+}
+
+#[test]
+fn test_cypher_merge_undirected_matches_either_orientation() {
+    let mut db = GraphDatabase::<MemStore>::new_in_memory();
+    let run = |db: &mut GraphDatabase<MemStore>, query: &str| {
+        let stmt = cypher::parse_cypher(query).expect("Parse failed");
+        cypher::execute_statement(db, &stmt).expect("Execute failed")
+    };
+
+    // 没有匹配时创建无向关系
+    let merge_ab = r#"MERGE (a:User {name: "A"})-[:KNOWS]-(b:User {name: "B"})"#;
+    let nodes = match run(&mut db, merge_ab) {
+        cypher::CypherResult::Created { nodes, rels } => {
+            assert_eq!(rels, 1);
+            nodes
+        }
+        _ => panic!("Expected Created"),
+    };
+    assert_reachable_both_ways(&db, nodes[0], nodes[1]);
+
+    // 再次 MERGE、或从另一端 MERGE，都匹配到同一条关系，且只匹配一次
+    let merge_ba = r#"MERGE (b:User {name: "B"})-[:KNOWS]-(a:User {name: "A"}) ON MATCH SET r.seen = 1"#;
+    assert!(matches!(run(&mut db, merge_ab), cypher::CypherResult::Nodes(_)));
+    assert!(matches!(run(&mut db, merge_ba), cypher::CypherResult::Updated { nodes: 1 }));
+    assert_eq!(db.rel_count(), 1);
+    assert_eq!(db.node_count(), 2);
+
+    // 多段模式中的无向关系同样与存储方向无关
+    let c = db.create_node(vec!["User"], user("C"));
+    db.create_undirected_rel(c, nodes[1], "KNOWS", Properties::new()).unwrap();
+    let merge_path = r#"MERGE (a:User {name: "A"})-[:KNOWS]-(b:User {name: "B"})-[:KNOWS]-(c:User {name: "C"})"#;
+    assert!(matches!(run(&mut db, merge_path), cypher::CypherResult::Nodes(_)));
+    assert_eq!(db.rel_count(), 2);
+}
+
+#[test]
+fn test_hybrid_undirected_rel_survives_reopen() {
+    use rs_graphdb::storage::hybrid_store::{FlushStrategy, HybridConfig, HybridStore};
+
+    let temp_dir = TempDir::new().unwrap();
+    let config = HybridConfig {
+        flush_strategy: FlushStrategy::Immediate,
+        ..Default::default()
+    };
+    let (a, b, rel) = {
+        let store = HybridStore::with_config(temp_dir.path(), Vec::new(), config.clone()).unwrap();
+        let mut db = GraphDatabase::from_engine(store);
+        let a = db.create_node(vec!["User"], user("A"));
+        let b = db.create_node(vec!["User"], user("B"));
+        // 先读一次邻接表，确认新关系不会被缓存的旧列表遮住
+        assert_eq!(db.neighbors_out(b).count(), 0);
+        let rel = db.create_undirected_rel(a, b, "KNOWS", Properties::new()).unwrap();
+        assert_reachable_both_ways(&db, a, b);
+        (a, b, rel)
+    };
+
+    let reopened = copy_to_fresh_dir(temp_dir.path());
+    let store = HybridStore::with_config(reopened.path(), Vec::new(), config).unwrap();
+    let db = GraphDatabase::from_engine(store);
+    assert!(!db.get_rel(rel).unwrap().directed);
+    assert_reachable_both_ways(&db, a, b);
+}
+
+#[test]
+fn test_sled_undirected_rel_survives_reopen() {
+    let temp_dir = TempDir::new().unwrap();
+    let (a, b, directed_rel) = {
+        let mut db = GraphDatabase::from_engine(SledStore::new(temp_dir.path()).unwrap());
+        let a = db.create_node(vec!["User"], user("A"));
+        let b = db.create_node(vec!["User"], user("B"));
+        db.create_undirected_rel(a, b, "KNOWS", Properties::new()).unwrap();
+        let directed_rel = db.create_rel(a, b, "FOLLOWS", Properties::new());
+        db.flush().unwrap();
+        (a, b, directed_rel)
+    };
+
+    let reopened = copy_to_fresh_dir(temp_dir.path());
+    let mut db = GraphDatabase::from_engine(SledStore::new(reopened.path()).unwrap());
+
+    assert!(db.get_rel(directed_rel).unwrap().directed);
+    let knows_from_b: Vec<_> = db.neighbors_out(b).filter(|r| r.typ == "KNOWS").collect();
+    assert_eq!(knows_from_b.len(), 1);
+    assert_eq!(knows_from_b[0].end, a);
+    assert!(db.neighbors_out(b).all(|r| r.typ != "FOLLOWS"));
+    assert_eq!(db.rel_count(), 2);
+
+    assert!(db.delete_rel(knows_from_b[0].id));
+    assert_eq!(db.neighbors_out(b).count(), 0);
+    assert_eq!(db.neighbors_in(a).count(), 0);
+}
+
+/// 把已关闭数据库的文件复制到新的临时目录
+///
+/// sled 关闭后后台线程可能短暂持有原目录的文件锁，从副本重新打开不受其影响
+fn copy_to_fresh_dir(path: &std::path::Path) -> TempDir {
+    fn copy_dir(from: &std::path::Path, to: &std::path::Path) {
+        for entry in std::fs::read_dir(from).unwrap() {
+            let entry = entry.unwrap();
+            let target = to.join(entry.file_name());
+            if entry.file_type().unwrap().is_dir() {
+                std::fs::create_dir_all(&target).unwrap();
+                copy_dir(&entry.path(), &target);
+            } else {
+                std::fs::copy(entry.path(), target).unwrap();
+            }
+        }
+    }
+
+    let dir = TempDir::new().unwrap();
+    copy_dir(path, dir.path());
+    dir
+}
@@ -0,0 +1,159 @@
+// Value 序列化测试：普通 JSON、带类型标签 JSON、bincode 存储格式
+
+use rs_graphdb::values::{Properties, TypedValue, Value};
+use serde::{Deserialize, Serialize};
+
+fn all_variants() -> Vec<Value> {
+    vec![
+        Value::Int(42),
+        Value::Int(-7),
+        Value::Bool(true),
+        Value::Text("text".to_string()),
+        Value::Float(2.5),
+        Value::Float(1.0),
+        Value::Null,
+        Value::List(vec![Value::Int(1), Value::Float(1.0), Value::Text("a".to_string())]),
+    ]
+}
+
+#[test]
+fn test_plain_json_format() {
+    assert_eq!(serde_json::to_string(&Value::Int(42)).unwrap(), "42");
+    assert_eq!(serde_json::to_string(&Value::Bool(true)).unwrap(), "true");
+    assert_eq!(serde_json::to_string(&Value::Text("text".into())).unwrap(), "\"text\"");
+    assert_eq!(serde_json::to_string(&Value::Float(2.5)).unwrap(), "2.5");
+    assert_eq!(serde_json::to_string(&Value::Null).unwrap(), "null");
+    assert_eq!(
+        serde_json::to_string(&Value::List(vec![Value::Int(1), Value::Bool(false)])).unwrap(),
+        "[1,false]"
+    );
+}
+
+#[test]
+fn test_plain_json_round_trip() {
+    for value in all_variants() {
+        let json = serde_json::to_string(&value).unwrap();
+        let back: Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, value, "round trip of {}", json);
+    }
+}
+
+#[test]
+fn test_plain_json_int_float_literals() {
+    // JSON 数字字面量决定类型
+    assert_eq!(serde_json::from_str::<Value>("1").unwrap(), Value::Int(1));
+    assert_eq!(serde_json::from_str::<Value>("1.0").unwrap(), Value::Float(1.0));
+    assert!(serde_json::from_str::<Value>("18446744073709551615").is_err());
+}
+
+#[test]
+fn test_typed_json_format() {
+    let json = serde_json::to_value(TypedValue(Value::Int(42))).unwrap();
+    assert_eq!(json, serde_json::json!({"type": "int", "value": 42}));
+
+    let json = serde_json::to_value(TypedValue(Value::Float(1.0))).unwrap();
+    assert_eq!(json, serde_json::json!({"type": "float", "value": 1.0}));
+
+    let json = serde_json::to_value(TypedValue(Value::List(vec![Value::Int(1), Value::Null]))).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!({
+            "type": "list",
+            "value": [{"type": "int", "value": 1}, {"type": "null", "value": null}]
+        })
+    );
+}
+
+#[test]
+fn test_typed_json_round_trip() {
+    for value in all_variants() {
+        let json = serde_json::to_string(&TypedValue(value.clone())).unwrap();
+        let back: TypedValue = serde_json::from_str(&json).unwrap();
+        assert_eq!(back.0, value, "round trip of {}", json);
+    }
+}
+
+#[test]
+fn test_typed_json_disambiguates_int_and_float() {
+    // 经过只有一种数字类型的系统（如 JavaScript）后，1.0 会变成 1；类型标签仍能还原
+    let int: Value = serde_json::from_str(r#"{"type":"int","value":1}"#).unwrap();
+    let float: Value = serde_json::from_str(r#"{"value":1,"type":"float"}"#).unwrap();
+    assert_eq!(int, Value::Int(1));
+    assert_eq!(float, Value::Float(1.0));
+
+    // 类型与值不匹配时报错
+    assert!(serde_json::from_str::<Value>(r#"{"type":"int","value":1.5}"#).is_err());
+    assert!(serde_json::from_str::<Value>(r#"{"type":"bool","value":"yes"}"#).is_err());
+    assert!(serde_json::from_str::<Value>(r#"{"value":1}"#).is_err());
+}
+
+#[test]
+fn test_legacy_tagged_json_still_parses() {
+    assert_eq!(serde_json::from_str::<Value>(r#"{"Int":5}"#).unwrap(), Value::Int(5));
+    assert_eq!(serde_json::from_str::<Value>(r#"{"Float":5.0}"#).unwrap(), Value::Float(5.0));
+}
+
+#[test]
+fn test_typed_field_attribute() {
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct Record {
+        #[serde(with = "rs_graphdb::values::typed")]
+        score: Value,
+        plain: Value,
+    }
+
+    let record = Record { score: Value::Float(2.0), plain: Value::Float(2.0) };
+    let json = serde_json::to_value(&record).unwrap();
+    assert_eq!(json["score"], serde_json::json!({"type": "float", "value": 2.0}));
+    assert_eq!(json["plain"], serde_json::json!(2.0));
+
+    let back: Record = serde_json::from_value(json).unwrap();
+    assert_eq!(back, record);
+}
+
+#[test]
+fn test_properties_json_round_trip() {
+    let mut props = Properties::new();
+    props.insert("age".to_string(), Value::Int(30));
+    props.insert("score".to_string(), Value::Float(9.5));
+    props.insert("name".to_string(), Value::Text("Alice".to_string()));
+
+    let json = serde_json::to_value(&props).unwrap();
+    assert_eq!(json["age"], serde_json::json!(30));
+
+    let back: Properties = serde_json::from_value(json).unwrap();
+    assert_eq!(back, props);
+}
+
+#[test]
+fn test_bincode_format_unchanged() {
+    // 与 derive 生成的编码保持一致，保证已有存储数据可读
+    #[derive(Serialize)]
+    enum LegacyValue {
+        Int(i64),
+        Bool(bool),
+        Text(String),
+        Float(f64),
+        Null,
+        List(Vec<LegacyValue>),
+    }
+
+    let cases = vec![
+        (Value::Int(42), LegacyValue::Int(42)),
+        (Value::Bool(true), LegacyValue::Bool(true)),
+        (Value::Text("hi".into()), LegacyValue::Text("hi".into())),
+        (Value::Float(1.5), LegacyValue::Float(1.5)),
+        (Value::Null, LegacyValue::Null),
+        (
+            Value::List(vec![Value::Int(1), Value::Null]),
+            LegacyValue::List(vec![LegacyValue::Int(1), LegacyValue::Null]),
+        ),
+    ];
+
+    for (value, legacy) in cases {
+        let bytes = bincode::serialize(&value).unwrap();
+        assert_eq!(bytes, bincode::serialize(&legacy).unwrap());
+        let back: Value = bincode::deserialize(&bytes).unwrap();
+        assert_eq!(back, value);
+    }
+}
@@ -4,8 +4,11 @@
 use rs_graphdb::graph::db::GraphDatabase;
 use rs_graphdb::storage::{StorageEngine, NodeId};
 use rs_graphdb::values::{Properties, Value};
+use std::collections::HashSet;
+use std::io::{self, Write};
+
 use rs_graphdb::visualization::{
-    GraphView, VisNode, VisEdge, NodeStyle, EdgeStyle, GraphFormat,
+    GraphView, VisNode, VisEdge, NodeStyle, EdgeStyle, GraphFormat, PropertyFilter,
     Layout, LayoutConfig, CircleLayout, ForceDirectedLayout, HierarchicalLayout,
     layout::HierarchicalDirection,
 };
@@ -191,6 +194,44 @@ fn test_hierarchical_layout_left_to_right() {
     assert!(x2 > x1, "Node 2 should be right of Node 1");
 }
 
+#[test]
+fn test_hierarchical_layout_longest_path_ranks() {
+    let mut graph_view = GraphView::new();
+    for i in 0..6 {
+        graph_view.add_node(VisNode::new(i, vec!["Node".to_string()], Properties::new()));
+    }
+
+    // 0 -> 1 -> 2 -> 3，另有捷径 0 -> 3；4 -> 3；5 与 1 互相指向构成环
+    for (source, target) in [(0, 1), (1, 2), (2, 3), (0, 3), (4, 3), (1, 5), (5, 1)] {
+        graph_view.add_edge(VisEdge::new(source, target, "NEXT".to_string(), Properties::new()));
+    }
+
+    let config = LayoutConfig::new(600.0, 400.0)
+        .with_rank_direction(HierarchicalDirection::TopToBottom)
+        .with_rank_separation(120.0)
+        .with_node_separation(40.0);
+    let mut layout = HierarchicalLayout::new(config);
+    layout.apply(&mut graph_view);
+
+    let rank_of = |id: NodeId| {
+        let node = graph_view.nodes.iter().find(|n| n.id == id).unwrap();
+        let y = node.position.as_ref().unwrap().y;
+        ((y - 50.0) / 120.0).round() as usize
+    };
+    // 最长路径分层：3 在最长链的末端而不是捷径决定的第 1 层
+    assert_eq!(rank_of(0), 0);
+    assert_eq!(rank_of(4), 0);
+    assert_eq!(rank_of(1), 1);
+    assert_eq!(rank_of(2), 2);
+    assert_eq!(rank_of(3), 3);
+    // 环 1 <-> 5 按 DFS 顺序打破：5 位于 1 的下一层
+    assert_eq!(rank_of(5), 2);
+
+    // 同层节点按节点间距排开
+    let x = |id: NodeId| graph_view.nodes.iter().find(|n| n.id == id).unwrap().position.as_ref().unwrap().x;
+    assert_eq!((x(4) - x(0)).abs(), 40.0);
+}
+
 #[test]
 fn test_json_export() {
     let mut graph_view = GraphView::new();
@@ -357,6 +398,46 @@ fn test_subgraph_export() {
     assert!(!json_str.contains("Charlie"));
 }
 
+#[test]
+fn test_filtered_export_strips_denied_properties() {
+    let mut db = GraphDatabase::new_in_memory();
+
+    let mut props = Properties::new();
+    props.insert("name".to_string(), Value::Text("Alice".to_string()));
+    props.insert("age".to_string(), Value::Int(30));
+    props.insert("ssn".to_string(), Value::Text("123-45-6789".to_string()));
+    let alice = db.create_node(vec!["Person"], props);
+    let bob = create_person(&mut db, "Bob", 25);
+    let mut rel_props = Properties::new();
+    rel_props.insert("ssn".to_string(), Value::Text("987-65-4321".to_string()));
+    db.create_rel(alice, bob, "KNOWS", rel_props);
+
+    // 拒绝列表：ssn 被删除，其余属性保留
+    let json_str = db
+        .export_graph_filtered(GraphFormat::Json, &PropertyFilter::deny(["ssn"]))
+        .unwrap();
+    assert!(!json_str.contains("ssn"));
+    assert!(!json_str.contains("123-45-6789"));
+    assert!(json_str.contains("Alice"));
+    assert!(json_str.contains("\"age\""));
+
+    // 脱敏：保留键名，值被替换
+    let json_str = db
+        .export_graph_filtered(GraphFormat::Json, &PropertyFilter::deny(["ssn"]).redact_with("***"))
+        .unwrap();
+    assert!(json_str.contains("\"ssn\""));
+    assert!(json_str.contains("***"));
+    assert!(!json_str.contains("123-45-6789"));
+    assert!(!json_str.contains("987-65-4321"));
+
+    // 允许列表：只保留 name，DOT 中的显示名称不受影响
+    let mut view = db.to_graph_view();
+    view.filter_properties(&PropertyFilter::allow(["name"]));
+    assert!(view.nodes.iter().all(|node| node.properties.keys().eq(["name"])));
+    assert!(view.edges.iter().all(|edge| edge.properties.is_empty()));
+    assert!(view.export(GraphFormat::Dot).unwrap().contains("Alice"));
+}
+
 #[test]
 fn test_graph_metadata() {
     let mut graph_view = GraphView::new();
@@ -399,3 +480,185 @@ fn test_position_distance() {
     let distance = pos1.distance_to(&pos2);
     assert!((distance - 5.0).abs() < 0.001, "Distance should be 5.0");
 }
+
+#[test]
+fn test_match_subgraph_keeps_only_matched_paths() {
+    use rs_graphdb::cypher::{self, CypherStatement};
+
+    let mut db = GraphDatabase::new_in_memory();
+    let alice = create_person(&mut db, "Alice", 30);
+    let bob = create_person(&mut db, "Bob", 25);
+    let carol = create_person(&mut db, "Carol", 35);
+    let dave = create_person(&mut db, "Dave", 40);
+    let erin = create_person(&mut db, "Erin", 28);
+    db.create_rel(alice, bob, "KNOWS", Properties::new());
+    db.create_rel(bob, carol, "KNOWS", Properties::new());
+    // 只有一跳，匹配不到两跳模式
+    db.create_rel(dave, erin, "KNOWS", Properties::new());
+
+    let CypherStatement::Query(query) =
+        cypher::parse_cypher("MATCH (p:Person)-[:KNOWS]->(q)-[:KNOWS]->(r) RETURN r").unwrap()
+    else {
+        panic!("Expected query statement");
+    };
+    let view = cypher::match_subgraph(&db, &query).unwrap();
+
+    let mut ids: Vec<NodeId> = view.nodes.iter().map(|n| n.id).collect();
+    ids.sort();
+    assert_eq!(ids, vec![alice, bob, carol]);
+    assert_eq!(view.edge_count(), 2);
+
+    let dot = view.export(GraphFormat::Dot).unwrap();
+    assert!(dot.contains("Alice") && dot.contains("Carol"));
+    assert!(!dot.contains("Dave"));
+}
+
+#[test]
+fn test_components_as_views() {
+    let mut db = GraphDatabase::new_in_memory();
+    let alice = create_person(&mut db, "Alice", 30);
+    let bob = create_person(&mut db, "Bob", 25);
+    let carol = create_person(&mut db, "Carol", 35);
+    let dave = create_person(&mut db, "Dave", 40);
+    let erin = create_person(&mut db, "Erin", 28);
+    let frank = create_person(&mut db, "Frank", 50);
+    db.create_rel(alice, bob, "KNOWS", Properties::new());
+    // 反向边也算连通
+    db.create_rel(carol, bob, "KNOWS", Properties::new());
+    db.create_rel(dave, erin, "KNOWS", Properties::new());
+
+    let views = db.components_as_views();
+    assert_eq!(views.len(), 3);
+
+    let node_ids = |view: &GraphView| -> Vec<NodeId> {
+        let mut ids: Vec<NodeId> = view.nodes.iter().map(|n| n.id).collect();
+        ids.sort();
+        ids
+    };
+    assert_eq!(node_ids(&views[0]), vec![alice, bob, carol]);
+    assert_eq!(views[0].edge_count(), 2);
+    assert_eq!(node_ids(&views[1]), vec![dave, erin]);
+    assert_eq!(views[1].edge_count(), 1);
+    // 孤立节点单独成为一个视图
+    assert_eq!(node_ids(&views[2]), vec![frank]);
+    assert_eq!(views[2].edge_count(), 0);
+}
+
+#[test]
+fn test_prune_by_degree_truncates_hub() {
+    let mut graph_view = GraphView::new();
+    graph_view.add_node(VisNode::new(0, vec!["Hub".to_string()], Properties::new()));
+    for i in 1..=12 {
+        graph_view.add_node(VisNode::new(i, vec!["Leaf".to_string()], Properties::new()));
+        let mut props = Properties::new();
+        props.insert("weight".to_string(), Value::Int(i as i64));
+        graph_view.add_edge(VisEdge::new(0, i, "LINK".to_string(), props));
+    }
+
+    let mut weighted = graph_view.clone();
+
+    // 默认保留前 5 条边
+    assert_eq!(graph_view.prune_by_degree(5), 7);
+    assert_eq!(graph_view.edge_count(), 5);
+    assert_eq!(graph_view.metadata.edge_count, 5);
+    assert_eq!(graph_view.metadata.truncated.get(&0), Some(&7));
+    // 叶子节点度数未超限，不标记
+    assert_eq!(graph_view.metadata.truncated.len(), 1);
+    let targets: Vec<NodeId> = graph_view.edges.iter().map(|e| e.target).collect();
+    assert_eq!(targets, vec![1, 2, 3, 4, 5]);
+    // 节点全部保留
+    assert_eq!(graph_view.node_count(), 13);
+
+    // 按权重保留最大的 5 条
+    assert_eq!(weighted.prune_by_degree_weighted(5, "weight"), 7);
+    let mut targets: Vec<NodeId> = weighted.edges.iter().map(|e| e.target).collect();
+    targets.sort();
+    assert_eq!(targets, vec![8, 9, 10, 11, 12]);
+
+    // 截断标记随 JSON 导出
+    let json = weighted.export(GraphFormat::Json).unwrap();
+    assert!(json.contains("truncated"));
+}
+
+/// 只统计输出、不保存输出的 writer：按行解析 DOT 节点和边，记录单次写入的最大字节数
+#[derive(Default)]
+struct DotLineCounter {
+    partial: Vec<u8>,
+    node_ids: HashSet<String>,
+    edges: usize,
+    bytes: usize,
+    max_write: usize,
+}
+
+impl Write for DotLineCounter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.bytes += buf.len();
+        self.max_write = self.max_write.max(buf.len());
+        for &byte in buf {
+            if byte != b'\n' {
+                self.partial.push(byte);
+                continue;
+            }
+            let line = String::from_utf8(std::mem::take(&mut self.partial)).unwrap();
+            if line.contains(" -> ") {
+                self.edges += 1;
+            } else if let Some(rest) = line.strip_prefix("  \"") {
+                self.node_ids.insert(rest.split('"').next().unwrap().to_string());
+            }
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn test_streaming_dot_export_of_large_graph() {
+    let mut db = GraphDatabase::new_in_memory();
+    let nodes: Vec<NodeId> = (0..20_000)
+        .map(|i| create_person(&mut db, &format!("p{}", i), i))
+        .collect();
+    for pair in nodes.windows(2) {
+        db.create_rel(pair[0], pair[1], "NEXT", Properties::new());
+    }
+
+    let mut counter = DotLineCounter::default();
+    db.export_graph_to_writer(GraphFormat::Dot, &mut counter).unwrap();
+
+    let expected: HashSet<String> = nodes.iter().map(|id| id.to_string()).collect();
+    assert_eq!(counter.node_ids, expected);
+    assert_eq!(counter.edges, nodes.len() - 1);
+    // 输出是逐行写出的，没有一次性写入整个缓冲
+    assert!(counter.bytes > 1_000_000);
+    assert!(counter.max_write < 256, "largest write was {} bytes", counter.max_write);
+}
+
+#[test]
+fn test_streaming_export_matches_view_export() {
+    let mut db = GraphDatabase::new_in_memory();
+    let alice = create_person(&mut db, "Alice & <Co>", 30);
+    let bob = create_person(&mut db, "Bob", 25);
+    let mut rel_props = Properties::new();
+    rel_props.insert("weight".to_string(), Value::Float(0.5));
+    db.create_rel(alice, bob, "KNOWS", rel_props);
+
+    let mut streamed = Vec::new();
+    db.export_graph_to_writer(GraphFormat::Dot, &mut streamed).unwrap();
+    assert_eq!(String::from_utf8(streamed).unwrap(), db.export_graph(GraphFormat::Dot).unwrap());
+
+    let mut streamed = Vec::new();
+    db.export_graph_to_writer(GraphFormat::GraphMl, &mut streamed).unwrap();
+    let graphml = String::from_utf8(streamed).unwrap();
+    assert_eq!(graphml, db.export_graph(GraphFormat::GraphMl).unwrap());
+
+    assert!(graphml.starts_with("<?xml"));
+    assert!(graphml.contains(r#"<key id="n:age" for="node" attr.name="age" attr.type="long"/>"#));
+    assert!(graphml.contains(r#"<key id="e:weight" for="edge" attr.name="weight" attr.type="double"/>"#));
+    assert!(graphml.contains(&format!(r#"<node id="n{}">"#, alice)));
+    assert!(graphml.contains(r#"<data key="n:name">Alice &amp; &lt;Co&gt;</data>"#));
+    assert!(graphml.contains(&format!(r#"source="n{}" target="n{}""#, alice, bob)));
+    assert!(graphml.contains(r#"<data key="type">KNOWS</data>"#));
+    assert!(graphml.trim_end().ends_with("</graphml>"));
+}
@@ -65,6 +65,16 @@ export interface QueryRequest {
   value?: string
   out_rel?: string
   in_rel?: string
+  skip?: number
+  limit?: number
+  order_by?: string
+  order_dir?: 'asc' | 'desc'
+}
+
+export interface QueryResponse {
+  // Total number of matches before pagination
+  total: number
+  nodes: NodeResponse[]
 }
 
 export interface SearchRequest {
@@ -158,7 +168,7 @@ export const api = {
 
   // Query
   query: (data: QueryRequest) =>
-    request<NodeResponse[]>('/query', {
+    request<QueryResponse>('/query', {
       method: 'POST',
       body: JSON.stringify(data),
     }),
@@ -131,7 +131,7 @@ describe('Graph Store', () => {
       const mockResults = [
         { id: 1, labels: ['User'], properties: { name: 'Alice' } },
       ]
-      vi.mocked(api.query).mockResolvedValue(mockResults)
+      vi.mocked(api.query).mockResolvedValue({ total: 1, nodes: mockResults })
 
       const store = useGraphStore()
       const results = await store.queryByLabel('User')
@@ -120,7 +120,8 @@ export const useGraphStore = defineStore('graph', () => {
 
   async function queryByLabel(label: string, property?: string, value?: string) {
     try {
-      return await api.query({ label, property, value })
+      const result = await api.query({ label, property, value })
+      return result.nodes
     } catch (err) {
       error.value = err instanceof Error ? err.message : 'Query failed'
       throw err
@@ -129,7 +130,8 @@ export const useGraphStore = defineStore('graph', () => {
 
   async function queryByProperty(label: string, property: string, value: string) {
     try {
-      return await api.query({ label, property, value })
+      const result = await api.query({ label, property, value })
+      return result.nodes
     } catch (err) {
       error.value = err instanceof Error ? err.message : 'Query failed'
       throw err